@@ -1,5 +1,6 @@
 use crate::api::get_stored_credentials;
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -50,13 +51,13 @@ struct SecureStorage {
     selected_pluely_model: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct StorageItem {
     key: String,
     value: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct StorageResult {
     license_key: Option<String>,
     instance_id: Option<String>,
@@ -64,6 +65,7 @@ pub struct StorageResult {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn secure_storage_save(app: AppHandle, items: Vec<StorageItem>) -> Result<(), String> {
     let storage_path = get_secure_storage_path(&app)?;
 
@@ -94,6 +96,7 @@ pub async fn secure_storage_save(app: AppHandle, items: Vec<StorageItem>) -> Res
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn secure_storage_get(app: AppHandle) -> Result<StorageResult, String> {
     let storage_path = get_secure_storage_path(&app)?;
 
@@ -119,6 +122,7 @@ pub async fn secure_storage_get(app: AppHandle) -> Result<StorageResult, String>
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn secure_storage_remove(app: AppHandle, keys: Vec<String>) -> Result<(), String> {
     let storage_path = get_secure_storage_path(&app)?;
 
@@ -158,7 +162,7 @@ pub struct ActivationRequest {
     app_version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct ActivationResponse {
     activated: bool,
     error: Option<String>,
@@ -166,20 +170,20 @@ pub struct ActivationResponse {
     instance: Option<InstanceInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct ValidateResponse {
     is_active: bool,
     last_validated_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct InstanceInfo {
     id: String,
     name: String,
     created_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct CheckoutResponse {
     success: Option<bool>,
     checkout_url: Option<String>,
@@ -187,6 +191,7 @@ pub struct CheckoutResponse {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn activate_license_api(
     app: AppHandle,
     license_key: String,
@@ -208,15 +213,15 @@ pub async fn activate_license_api(
     };
 
     // Make HTTP request to activation endpoint with authorization header
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/activate", payment_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
-        .json(&activation_request)
-        .send()
+        .json(&activation_request);
+    let response = crate::network::send(request, "license", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
@@ -251,6 +256,7 @@ pub async fn activate_license_api(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn deactivate_license_api(app: AppHandle) -> Result<ActivationResponse, String> {
     // Get payment endpoint and API access key from environment
     let payment_endpoint = get_payment_endpoint()?;
@@ -265,15 +271,15 @@ pub async fn deactivate_license_api(app: AppHandle) -> Result<ActivationResponse
         app_version: app_version.clone(),
     };
     // Make HTTP request to activation endpoint with authorization header
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/deactivate", payment_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
-        .json(&deactivation_request)
-        .send()
+        .json(&deactivation_request);
+    let response = crate::network::send(request, "license", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
@@ -307,6 +313,7 @@ pub async fn deactivate_license_api(app: AppHandle) -> Result<ActivationResponse
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn validate_license_api(app: AppHandle) -> Result<ValidateResponse, String> {
     // Get payment endpoint and API access key from environment
     let payment_endpoint = get_payment_endpoint()?;
@@ -329,15 +336,15 @@ pub async fn validate_license_api(app: AppHandle) -> Result<ValidateResponse, St
     }
 
     // Make HTTP request to validate endpoint with authorization header
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/validate", payment_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
-        .json(&validate_request)
-        .send()
+        .json(&validate_request);
+    let response = crate::network::send(request, "license", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
@@ -372,6 +379,7 @@ pub async fn validate_license_api(app: AppHandle) -> Result<ValidateResponse, St
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn mask_license_key_cmd(license_key: String) -> String {
     if license_key.len() <= 8 {
         return "*".repeat(license_key.len());
@@ -385,21 +393,22 @@ pub fn mask_license_key_cmd(license_key: String) -> String {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn get_checkout_url() -> Result<CheckoutResponse, String> {
     // Get payment endpoint and API access key from environment
     let payment_endpoint = get_payment_endpoint()?;
     let api_access_key = get_api_access_key()?;
 
     // Make HTTP request to checkout endpoint with authorization header
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/checkout", payment_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
-        .json(&serde_json::json!({}))
-        .send()
+        .json(&serde_json::json!({}));
+    let response = crate::network::send(request, "license", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
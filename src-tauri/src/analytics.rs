@@ -0,0 +1,304 @@
+// Pluely analytics. Only counts of feature use and performance timings are
+// collected, kept in `telemetry.json` in the app data directory. Metric names
+// are fixed in code, so no text, capture, path or id can end up in them, and
+// `preview_telemetry_payload` returns exactly what would be sent. Nothing
+// leaves the machine until the user turns on `telemetry.shareUsage`, and never
+// in local-only mode or when a managed policy turns telemetry off. Reports go
+// out once a day without a machine or license id, and counting starts over.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const TELEMETRY_FILE: &str = "telemetry.json";
+// How often counts are written out and a report is considered
+const FLUSH_INTERVAL: Duration = Duration::from_secs(600);
+const REPORT_PERIOD_MS: i64 = 24 * 60 * 60 * 1000;
+
+// Set at startup; metrics are recorded without an app handle, and not at all
+// when running without the app, as the CLI does
+static TELEMETRY_PATH: OnceLock<PathBuf> = OnceLock::new();
+static APP_VERSION: OnceLock<String> = OnceLock::new();
+static USAGE: Mutex<Option<Usage>> = Mutex::new(None);
+// Set when there are counts not written out yet
+static DIRTY: AtomicBool = AtomicBool::new(false);
+// Mirrors the setting so metrics are sent without the settings lock
+static SHARE_ALLOWED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetrySettings {
+    // Off unless the user explicitly agrees to send usage counts
+    pub share_usage: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    pub count: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Timing {
+    fn merge(&mut self, other: Timing) {
+        self.count += other.count;
+        self.total_ms += other.total_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Usage {
+    // Unix milliseconds the counts start from
+    since: i64,
+    counters: BTreeMap<String, u64>,
+    timings: BTreeMap<String, Timing>,
+}
+
+impl Usage {
+    fn starting(since: i64) -> Self {
+        Self {
+            since,
+            ..Default::default()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.counters.is_empty() && self.timings.is_empty()
+    }
+
+    fn merge(&mut self, other: Usage) {
+        self.since = self.since.min(other.since);
+        for (metric, count) in other.counters {
+            *self.counters.entry(metric).or_default() += count;
+        }
+        for (metric, timing) in other.timings {
+            self.timings.entry(metric).or_default().merge(timing);
+        }
+    }
+}
+
+// Everything a report holds
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryPayload {
+    pub app_version: String,
+    pub os: String,
+    // Unix milliseconds
+    pub period_start: i64,
+    pub period_end: i64,
+    pub counters: BTreeMap<String, u64>,
+    pub timings: BTreeMap<String, Timing>,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn load(path: &Path) -> Usage {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("Failed to parse telemetry, starting over: {}", e);
+            Usage::starting(now_ms())
+        }),
+        Err(_) => Usage::starting(now_ms()),
+    }
+}
+
+/// Loads the counts so far, counts this start and starts writing them out
+/// and, once the user opts in, reporting them
+pub fn init(app: &AppHandle) {
+    let path = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(TELEMETRY_FILE),
+        Err(e) => {
+            warn!("Failed to get app data directory: {}", e);
+            return;
+        }
+    };
+    let _ = APP_VERSION.set(app.package_info().version.to_string());
+    if let Ok(mut usage) = USAGE.lock() {
+        *usage = Some(load(&path));
+    }
+    let _ = TELEMETRY_PATH.set(path);
+    count("app_start");
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            if report_due() {
+                if let Err(e) = send_report().await {
+                    warn!("{}", e);
+                }
+            }
+            flush();
+        }
+    });
+}
+
+fn with_usage(record: impl FnOnce(&mut Usage)) {
+    if let Ok(mut usage) = USAGE.lock() {
+        if let Some(usage) = usage.as_mut() {
+            record(usage);
+            DIRTY.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+// Counts one use of a feature
+pub fn count(metric: &'static str) {
+    with_usage(|usage| *usage.counters.entry(metric.to_string()).or_default() += 1);
+}
+
+// Records how long one run of `metric` took
+pub fn timing(metric: &'static str, elapsed: Duration) {
+    let ms = elapsed.as_millis() as u64;
+    with_usage(|usage| {
+        usage
+            .timings
+            .entry(metric.to_string())
+            .or_default()
+            .merge(Timing {
+                count: 1,
+                total_ms: ms,
+                max_ms: ms,
+            })
+    });
+}
+
+fn save(usage: &Usage) -> Result<(), String> {
+    let Some(path) = TELEMETRY_PATH.get() else {
+        return Ok(());
+    };
+    let json = serde_json::to_string_pretty(usage)
+        .map_err(|e| format!("Failed to serialize telemetry: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write telemetry: {}", e))
+}
+
+/// Writes out counts recorded since the last flush
+pub fn flush() {
+    if !DIRTY.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let usage = USAGE.lock().ok().and_then(|usage| usage.clone());
+    if let Some(Err(e)) = usage.as_ref().map(save) {
+        warn!("{}", e);
+    }
+}
+
+fn sharing_allowed() -> bool {
+    SHARE_ALLOWED.load(Ordering::SeqCst) && crate::settings::telemetry_allowed()
+}
+
+fn report_due() -> bool {
+    sharing_allowed()
+        && USAGE.lock().ok().is_some_and(|usage| {
+            usage
+                .as_ref()
+                .is_some_and(|usage| now_ms() - usage.since >= REPORT_PERIOD_MS)
+        })
+}
+
+fn payload_of(usage: &Usage, period_end: i64) -> TelemetryPayload {
+    TelemetryPayload {
+        app_version: APP_VERSION.get().cloned().unwrap_or_default(),
+        os: std::env::consts::OS.to_string(),
+        period_start: usage.since,
+        period_end,
+        counters: usage.counters.clone(),
+        timings: usage.timings.clone(),
+    }
+}
+
+async fn upload(payload: &TelemetryPayload) -> Result<(), String> {
+    let endpoint = crate::api::get_app_endpoint()?;
+    let access_key = crate::api::get_api_access_key()?;
+    let request = crate::network::http_client()?
+        .post(format!("{}/api/telemetry", endpoint.trim_end_matches('/')))
+        .header("Authorization", format!("Bearer {}", access_key))
+        .json(payload);
+    let response = crate::network::send(request, "telemetry", &[])
+        .await
+        .map_err(|e| format!("Failed to send telemetry: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to send telemetry: server returned {}",
+            response.status()
+        ));
+    }
+    Ok(())
+}
+
+// Sends the counts so far and starts over; they are kept if sending fails
+async fn send_report() -> Result<(), String> {
+    if !sharing_allowed() {
+        return Ok(());
+    }
+    let now = now_ms();
+    let usage = USAGE
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .as_mut()
+        .map(|usage| std::mem::replace(usage, Usage::starting(now)));
+    let Some(usage) = usage.filter(|usage| !usage.is_empty()) else {
+        return Ok(());
+    };
+    DIRTY.store(true, Ordering::SeqCst);
+    if let Err(e) = upload(&payload_of(&usage, now)).await {
+        with_usage(|current| current.merge(usage));
+        return Err(e);
+    }
+    info!("Sent usage counts since {}", usage.since);
+    Ok(())
+}
+
+// Records consent; sending waits for the next daily report
+pub fn apply_telemetry_settings(settings: &TelemetrySettings) {
+    SHARE_ALLOWED.store(settings.share_usage, Ordering::SeqCst);
+}
+
+/// Tauri command telling the webview whether usage may leave the machine:
+/// the user agreed, local-only mode is off and no policy forbids it
+#[tauri::command]
+#[specta::specta]
+pub fn usage_sharing_allowed() -> bool {
+    sharing_allowed()
+}
+
+/// Tauri command returning exactly what the next report would send, whether
+/// or not sharing is on
+#[tauri::command]
+#[specta::specta]
+pub fn preview_telemetry_payload() -> Result<TelemetryPayload, String> {
+    let now = now_ms();
+    let usage = USAGE
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone()
+        .unwrap_or_else(|| Usage::starting(now));
+    Ok(payload_of(&usage, now))
+}
+
+/// Tauri command discarding the counts collected so far
+#[tauri::command]
+#[specta::specta]
+pub fn clear_telemetry() -> Result<(), String> {
+    let mut usage = USAGE
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let cleared = Usage::starting(now_ms());
+    save(&cleared)?;
+    if usage.is_some() {
+        *usage = Some(cleared);
+    }
+    DIRTY.store(false, Ordering::SeqCst);
+    Ok(())
+}
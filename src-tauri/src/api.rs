@@ -3,13 +3,17 @@ use futures_util::StreamExt;
 use reqwest::multipart::{Form, Part};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_machine_uid::MachineUidExt;
 
-fn get_app_endpoint() -> Result<String, String> {
+use crate::events::{ChatStreamChunk, ChatStreamComplete};
+
+pub(crate) fn get_app_endpoint() -> Result<String, String> {
+    crate::network::ensure_online("Pluely's hosted API")?;
     if let Ok(endpoint) = env::var("APP_ENDPOINT") {
         return Ok(endpoint);
     }
@@ -20,7 +24,7 @@ fn get_app_endpoint() -> Result<String, String> {
     }
 }
 
-fn get_api_access_key() -> Result<String, String> {
+pub(crate) fn get_api_access_key() -> Result<String, String> {
     if let Ok(key) = env::var("API_ACCESS_KEY") {
         return Ok(key);
     }
@@ -81,7 +85,7 @@ pub async fn get_stored_credentials(
 }
 
 // Audio API Structs
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct AudioResponse {
     success: bool,
     transcription: Option<String>,
@@ -105,7 +109,7 @@ pub struct ChatResponse {
 }
 
 // Model API Structs
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
 pub struct Model {
     provider: String,
     name: String,
@@ -122,7 +126,7 @@ pub struct ModelsResponse {
     models: Vec<Model>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Type)]
 pub struct SystemPromptResponse {
     prompt_name: String,
     system_prompt: String,
@@ -174,6 +178,7 @@ pub struct UserAudioConfig {
 
 // Audio API Command
 #[tauri::command]
+#[specta::specta]
 pub async fn transcribe_audio(
     app: AppHandle,
     audio_base64: String,
@@ -189,7 +194,7 @@ pub async fn transcribe_audio(
     })?;
 
     let audio_bytes = decode_audio_base64(&audio_base64)?;
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let error_provider = provider.clone();
     let error_model = model.clone();
     match perform_user_audio_transcription(
@@ -278,7 +283,7 @@ async fn fetch_api_response_config(
     let (license_key, instance_id, _) = get_stored_credentials(app).await?;
 
     // Make HTTP request to response endpoint
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/api/response", app_endpoint);
 
     let mut request = client
@@ -297,19 +302,21 @@ async fn fetch_api_response_config(
         request = request.header("model", m);
     }
 
-    let response = request.send().await.map_err(|e| {
-        let error_msg = format!("{}", e);
-        if error_msg.contains("url (") {
-            let parts: Vec<&str> = error_msg.split(" for url (").collect();
-            if parts.len() > 1 {
-                format!("Failed to fetch API config: {}", parts[0])
+    let response = crate::network::send(request, "hosted API", &[])
+        .await
+        .map_err(|e| {
+            let error_msg = format!("{}", e);
+            if error_msg.contains("url (") {
+                let parts: Vec<&str> = error_msg.split(" for url (").collect();
+                if parts.len() > 1 {
+                    format!("Failed to fetch API config: {}", parts[0])
+                } else {
+                    format!("Failed to fetch API config: {}", error_msg)
+                }
             } else {
                 format!("Failed to fetch API config: {}", error_msg)
             }
-        } else {
-            format!("Failed to fetch API config: {}", error_msg)
-        }
-    })?;
+        })?;
 
     // Check if the response is successful
     if !response.status().is_success() {
@@ -362,7 +369,7 @@ fn map_api_error_message(error_rules: &[ApiConfigError], sources: &[String]) ->
         })
 }
 
-fn decode_audio_base64(audio_base64: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn decode_audio_base64(audio_base64: &str) -> Result<Vec<u8>, String> {
     let trimmed = audio_base64.trim();
     let base64_str = if let Some(idx) = trimmed.find(',') {
         &trimmed[idx + 1..]
@@ -403,11 +410,8 @@ async fn perform_user_audio_transcription(
         }
     }
 
-    let response = client
-        .post(url)
-        .bearer_auth(token)
-        .multipart(form)
-        .send()
+    let request = client.post(url).bearer_auth(token).multipart(form);
+    let response = crate::network::send(request, "transcription", &[])
         .await
         .map_err(|e| format!("Transcription request failed to send: {}", e))?;
 
@@ -452,6 +456,7 @@ async fn perform_user_audio_transcription(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn chat_stream_response(
     app: AppHandle,
     user_message: String,
@@ -552,16 +557,14 @@ pub async fn chat_stream_response(
     }
 
     // Make HTTP request to the configured endpoint with streaming
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let error_rules = api_config.errors.clone().unwrap_or_default();
-    let response = match client
+    let request = client
         .post(&api_config.url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_config.user_token))
-        .json(&request_body)
-        .send()
-        .await
-    {
+        .json(&request_body);
+    let response = match crate::network::send(request, "chat", &[]).await {
         Ok(resp) => resp,
         Err(e) => {
             let mut sources = vec![e.to_string()];
@@ -664,7 +667,10 @@ pub async fn chat_stream_response(
                                             {
                                                 full_response.push_str(content);
                                                 // Emit just the content to frontend
-                                                let _ = app.emit("chat_stream_chunk", content);
+                                                let _ = app.emit(
+                                                    "chat_stream_chunk",
+                                                    ChatStreamChunk(content.to_string()),
+                                                );
                                                 stream_started = true;
                                             }
                                         }
@@ -696,7 +702,10 @@ pub async fn chat_stream_response(
     }
 
     // Emit completion event
-    let _ = app.emit("chat_stream_complete", &full_response);
+    let _ = app.emit(
+        "chat_stream_complete",
+        ChatStreamComplete(full_response.clone()),
+    );
 
     if stream_started && !full_response.is_empty() {
         tauri::async_runtime::spawn({
@@ -725,6 +734,9 @@ async fn user_activity(
     configured_model: String,
     app_version: String,
 ) -> Result<(), String> {
+    if !crate::settings::telemetry_allowed() {
+        return Ok(());
+    }
     let app_endpoint = match get_app_endpoint() {
         Ok(value) => value,
         Err(_) => return Ok(()),
@@ -772,15 +784,14 @@ async fn user_activity(
     }
 
     let activity_url = format!("{}/api/activity", app_endpoint.trim_end_matches('/'));
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
 
-    let _ = client
+    let request = client
         .post(&activity_url)
         .header("Authorization", format!("Bearer {}", api_access_key))
         .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await;
+        .json(&payload);
+    let _ = crate::network::send(request, "usage report", &[]).await;
 
     Ok(())
 }
@@ -792,6 +803,9 @@ async fn report_api_error(
     model: Option<String>,
     provider: Option<String>,
 ) {
+    if !crate::settings::telemetry_allowed() {
+        return;
+    }
     let app_endpoint = match get_app_endpoint() {
         Ok(value) => value,
         Err(_) => return,
@@ -838,24 +852,25 @@ async fn report_api_error(
     });
 
     let error_url = format!("{}/api/error", app_endpoint.trim_end_matches('/'));
-    let client = reqwest::Client::new();
+    let Ok(client) = crate::network::http_client() else {
+        return;
+    };
 
     tracing::debug!("Reporting API error: {:?}", payload);
 
-    if let Err(e) = client
+    let request = client
         .post(&error_url)
         .header("Authorization", format!("Bearer {}", api_access_key))
         .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-    {
+        .json(&payload);
+    if let Err(e) = crate::network::send(request, "error report", &[]).await {
         tracing::warn!("Failed to report API error: {}", e);
     }
 }
 
 // Models API Command
 #[tauri::command]
+#[specta::specta]
 pub async fn fetch_models() -> Result<Vec<Model>, String> {
     // Get environment variables
     let app_endpoint = get_app_endpoint().map_err(|e| {
@@ -870,14 +885,14 @@ pub async fn fetch_models() -> Result<Vec<Model>, String> {
     tracing::info!("Fetching models from: {}/api/models", app_endpoint);
 
     // Make HTTP request to models endpoint
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/api/models", app_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_access_key))
-        .send()
+        .header("Authorization", format!("Bearer {}", api_access_key));
+    let response = crate::network::send(request, "hosted API", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
@@ -924,6 +939,7 @@ pub async fn fetch_models() -> Result<Vec<Model>, String> {
 
 // Create System Prompt API Command
 #[tauri::command]
+#[specta::specta]
 pub async fn create_system_prompt(
     app: AppHandle,
     user_prompt: String,
@@ -935,10 +951,10 @@ pub async fn create_system_prompt(
     let machine_id: String = app.machine_uid().get_machine_uid().unwrap().id.unwrap();
     let app_version: String = app.package_info().version.to_string();
     // Make HTTP request to models endpoint
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let url = format!("{}/api/prompt", app_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
@@ -948,8 +964,8 @@ pub async fn create_system_prompt(
         .header("app_version", &app_version)
         .json(&serde_json::json!({
             "user_prompt": user_prompt
-        }))
-        .send()
+        }));
+    let response = crate::network::send(request, "hosted API", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
@@ -996,6 +1012,7 @@ pub async fn create_system_prompt(
 
 // Helper command to check if license is available
 #[tauri::command]
+#[specta::specta]
 pub async fn check_license_status(app: AppHandle) -> Result<bool, String> {
     match get_stored_credentials(&app).await {
         Ok(_) => Ok(true),
@@ -1005,6 +1022,7 @@ pub async fn check_license_status(app: AppHandle) -> Result<bool, String> {
 
 #[allow(dead_code)]
 #[tauri::command]
+#[specta::specta]
 pub async fn get_activity(app: AppHandle) -> Result<serde_json::Value, String> {
     let app_endpoint = get_app_endpoint()?;
     let api_access_key = get_api_access_key()?;
@@ -1022,17 +1040,17 @@ pub async fn get_activity(app: AppHandle) -> Result<serde_json::Value, String> {
 
     let app_version = app.package_info().version.to_string();
 
-    let client = reqwest::Client::new();
+    let client = crate::network::http_client()?;
     let activity_url = format!("{}/api/activity", app_endpoint.trim_end_matches('/'));
 
-    let response = client
+    let request = client
         .get(&activity_url)
         .header("Authorization", format!("Bearer {}", api_access_key))
         .header("license_key", &license_key)
         .header("instance_name", &instance_id)
         .header("machine_id", machine_id)
-        .header("app_version", app_version)
-        .send()
+        .header("app_version", app_version);
+    let response = crate::network::send(request, "hosted API", &[])
         .await
         .map_err(|e| {
             let error_msg = format!("{}", e);
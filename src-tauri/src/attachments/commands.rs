@@ -0,0 +1,248 @@
+use base64::{engine::general_purpose, Engine as _};
+use sqlx::Row;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+
+use super::{
+    attachments_dir, page_file, process_file, Attachment, AttachmentKind, IngestReport,
+    ProcessedFile, RejectedFile, THUMBNAIL_FILE,
+};
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// Writes the pages and thumbnail, sealed when history encryption is on, and
+// registers the attachment
+pub(crate) async fn register(
+    app: &AppHandle,
+    processed: ProcessedFile,
+    dir: &Path,
+) -> Result<Attachment, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let path = dir.join(&id);
+    std::fs::create_dir_all(&path)
+        .map_err(|e| format!("Failed to create attachment directory: {}", e))?;
+    let write = |name: &str, bytes: &[u8]| -> Result<(), String> {
+        let file = path.join(name);
+        std::fs::write(&file, bytes).map_err(|e| format!("Failed to save attachment: {}", e))?;
+        crate::storage::seal_file(&file)
+    };
+    let written = processed
+        .pages
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, page)| write(&page_file(index as u32 + 1), page))
+        .and_then(|()| write(THUMBNAIL_FILE, &processed.thumbnail));
+    if let Err(e) = written {
+        let _ = std::fs::remove_dir_all(&path);
+        return Err(e);
+    }
+
+    let attachment = Attachment {
+        id,
+        message_id: None,
+        name: processed.name,
+        kind: processed.kind,
+        mime_type: processed.mime_type,
+        bytes: processed.bytes,
+        page_count: processed.pages.len() as u32,
+        thumbnail: general_purpose::STANDARD.encode(&processed.thumbnail),
+        created_at: now_ms(),
+    };
+    let pool = crate::storage::pool(app).await?;
+    let inserted = sqlx::query(
+        "INSERT INTO attachments
+            (id, name, kind, mime_type, bytes, page_count, path, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&attachment.id)
+    .bind(&attachment.name)
+    .bind(attachment.kind.as_str())
+    .bind(&attachment.mime_type)
+    .bind(attachment.bytes as i64)
+    .bind(attachment.page_count)
+    .bind(path.to_string_lossy().to_string())
+    .bind(attachment.created_at)
+    .execute(&pool)
+    .await;
+    if let Err(e) = inserted {
+        let _ = std::fs::remove_dir_all(&path);
+        return Err(format!("Failed to register attachment: {}", e));
+    }
+    Ok(attachment)
+}
+
+// Ingests each file on its own, so one bad file doesn't reject the rest
+async fn ingest(app: &AppHandle, paths: Vec<PathBuf>) -> Result<IngestReport, String> {
+    let dir = attachments_dir(app)?;
+    let resource_dir = app.path().resource_dir().ok();
+    let mut report = IngestReport::default();
+    for path in paths {
+        let source = path.clone();
+        let resource_dir = resource_dir.clone();
+        let processed = tauri::async_runtime::spawn_blocking(move || {
+            process_file(&source, resource_dir.as_deref())
+        })
+        .await
+        .map_err(|e| format!("Failed to process file: {}", e))
+        .and_then(|processed| processed);
+        let registered = match processed {
+            Ok(processed) => register(app, processed, &dir).await,
+            Err(e) => Err(e),
+        };
+        match registered {
+            Ok(attachment) => report.attachments.push(attachment),
+            Err(reason) => report.rejected.push(RejectedFile {
+                path: path.to_string_lossy().to_string(),
+                reason,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+// Called for files dropped onto a window. Emits `attachments-ingesting` right
+// away so the UI can show progress, then `attachments-added` with the result.
+pub fn handle_file_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    let _ = app.emit(
+        "attachments-ingesting",
+        crate::events::AttachmentsIngesting(paths.len()),
+    );
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match ingest(&app, paths).await {
+            Ok(report) => {
+                let _ = app.emit("attachments-added", crate::events::AttachmentsAdded(report));
+            }
+            Err(e) => warn!("Failed to ingest dropped files: {}", e),
+        }
+    });
+}
+
+fn attachment_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Attachment, String> {
+    let path: String = row.get("path");
+    let kind: String = row.get("kind");
+    // A missing or unreadable thumbnail only costs the preview
+    let thumbnail = crate::storage::read_file(&Path::new(&path).join(THUMBNAIL_FILE))
+        .map(|bytes| general_purpose::STANDARD.encode(bytes))
+        .unwrap_or_default();
+    Ok(Attachment {
+        id: row.get("id"),
+        message_id: row.get("message_id"),
+        name: row.get("name"),
+        kind: AttachmentKind::parse(&kind).ok_or(format!("Unknown attachment kind: {}", kind))?,
+        mime_type: row.get("mime_type"),
+        bytes: row.get::<i64, _>("bytes") as u64,
+        page_count: row.get::<i64, _>("page_count") as u32,
+        thumbnail,
+        created_at: row.get("created_at"),
+    })
+}
+
+async fn attachment_path(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let pool = crate::storage::pool(app).await?;
+    let path: Option<String> = sqlx::query_scalar("SELECT path FROM attachments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to read attachments: {}", e))?;
+    path.map(PathBuf::from)
+        .ok_or(format!("Unknown attachment: {}", id))
+}
+
+// Same pipeline as a drop, for files picked in a dialog
+#[tauri::command]
+#[specta::specta]
+pub async fn ingest_files(app: AppHandle, paths: Vec<String>) -> Result<IngestReport, String> {
+    ingest(&app, paths.into_iter().map(PathBuf::from).collect()).await
+}
+
+// Attachments of a message, or the ones not sent yet when no message is given
+#[tauri::command]
+#[specta::specta]
+pub async fn list_attachments(
+    app: AppHandle,
+    message_id: Option<String>,
+) -> Result<Vec<Attachment>, String> {
+    let pool = crate::storage::pool(&app).await?;
+    let rows = sqlx::query(
+        "SELECT id, message_id, name, kind, mime_type, bytes, page_count, path, created_at
+         FROM attachments WHERE message_id IS ? ORDER BY created_at ASC",
+    )
+    .bind(&message_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to list attachments: {}", e))?;
+    rows.iter().map(attachment_from_row).collect()
+}
+
+// Every page as base64 PNG, ready to send as message images
+#[tauri::command]
+#[specta::specta]
+pub async fn read_attachment(app: AppHandle, id: String) -> Result<Vec<String>, String> {
+    let path = attachment_path(&app, &id).await?;
+    let mut pages: Vec<PathBuf> = std::fs::read_dir(&path)
+        .map_err(|e| format!("Failed to read attachment: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| {
+            file.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("page-"))
+        })
+        .collect();
+    pages.sort();
+    pages
+        .iter()
+        .map(|page| {
+            crate::storage::read_file(page).map(|bytes| general_purpose::STANDARD.encode(bytes))
+        })
+        .collect()
+}
+
+// Ties attachments to the message they were sent with
+#[tauri::command]
+#[specta::specta]
+pub async fn link_attachments(
+    app: AppHandle,
+    message_id: String,
+    ids: Vec<String>,
+) -> Result<(), String> {
+    let pool = crate::storage::pool(&app).await?;
+    for id in ids {
+        sqlx::query("UPDATE attachments SET message_id = ? WHERE id = ?")
+            .bind(&message_id)
+            .bind(&id)
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Failed to link attachment: {}", e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_attachment(app: AppHandle, id: String) -> Result<bool, String> {
+    let path = match attachment_path(&app, &id).await {
+        Ok(path) => path,
+        Err(_) => return Ok(false),
+    };
+    match std::fs::remove_dir_all(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to delete attachment: {}", e)),
+    }
+    let pool = crate::storage::pool(&app).await?;
+    sqlx::query("DELETE FROM attachments WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete attachment: {}", e))?;
+    Ok(true)
+}
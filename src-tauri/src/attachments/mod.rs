@@ -0,0 +1,247 @@
+// Pluely attachments: local images and PDFs dropped onto the window (or picked
+// in the UI) are validated, turned into PNG pages with a thumbnail, and
+// registered in the database so they can be sent with a message.
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use pdfium_render::prelude::*;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+const ATTACHMENTS_DIR: &str = "attachments";
+pub const THUMBNAIL_FILE: &str = "thumbnail.png";
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+const MAX_PDF_BYTES: u64 = 50 * 1024 * 1024;
+// Later pages are dropped; more than this rarely fits a model's context
+pub const MAX_PDF_PAGES: usize = 20;
+// Wide enough for small print to stay readable to vision models
+const PDF_PAGE_WIDTH: i32 = 1_600;
+const THUMBNAIL_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Image,
+    Pdf,
+}
+
+impl AttachmentKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Pdf => "pdf",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "image" => Some(AttachmentKind::Image),
+            "pdf" => Some(AttachmentKind::Pdf),
+            _ => None,
+        }
+    }
+
+    fn max_bytes(self) -> u64 {
+        match self {
+            AttachmentKind::Image => MAX_IMAGE_BYTES,
+            AttachmentKind::Pdf => MAX_PDF_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub message_id: Option<String>,
+    // File name as dropped
+    pub name: String,
+    pub kind: AttachmentKind,
+    pub mime_type: String,
+    pub bytes: u64,
+    pub page_count: u32,
+    // Base64 PNG, at most THUMBNAIL_SIZE on each side
+    pub thumbnail: String,
+    pub created_at: i64,
+}
+
+// A dropped file that was not ingested, and why
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestReport {
+    pub attachments: Vec<Attachment>,
+    pub rejected: Vec<RejectedFile>,
+}
+
+// What ingesting produced on disk, before it is registered
+pub(crate) struct ProcessedFile {
+    pub name: String,
+    pub kind: AttachmentKind,
+    pub mime_type: String,
+    pub bytes: u64,
+    pub pages: Vec<Vec<u8>>,
+    pub thumbnail: Vec<u8>,
+}
+
+pub fn attachments_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::workspaces::data_dir(app)?.join(ATTACHMENTS_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+    Ok(dir)
+}
+
+// Pages are named so a directory listing sorts them in order
+pub fn page_file(page: u32) -> String {
+    format!("page-{:03}.png", page)
+}
+
+// Accepted file types, by extension, with the MIME type recorded for them
+fn file_type(path: &Path) -> Option<(AttachmentKind, &'static str)> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let kind = match extension.as_str() {
+        "png" => (AttachmentKind::Image, "image/png"),
+        "jpg" | "jpeg" => (AttachmentKind::Image, "image/jpeg"),
+        "gif" => (AttachmentKind::Image, "image/gif"),
+        "webp" => (AttachmentKind::Image, "image/webp"),
+        "bmp" => (AttachmentKind::Image, "image/bmp"),
+        "pdf" => (AttachmentKind::Pdf, "application/pdf"),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+// Checks type and size before anything is read
+pub fn validate(path: &Path) -> Result<(AttachmentKind, &'static str, u64), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Folders cannot be attached".to_string());
+    }
+    let (kind, mime_type) = file_type(path)
+        .ok_or("Unsupported file type: only images and PDFs can be attached".to_string())?;
+    if metadata.len() == 0 {
+        return Err("File is empty".to_string());
+    }
+    if metadata.len() > kind.max_bytes() {
+        return Err(format!(
+            "File is too large: {} MB is the limit for {} files",
+            kind.max_bytes() / (1024 * 1024),
+            kind.as_str()
+        ));
+    }
+    Ok((kind, mime_type, metadata.len()))
+}
+
+pub(crate) fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+    Ok(png)
+}
+
+pub(crate) fn thumbnail(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    encode_png(&image.resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle))
+}
+
+// PDFium ships next to the executable as a resource; a system copy also works
+pub(crate) fn pdfium(resource_dir: Option<&Path>) -> Result<Pdfium, String> {
+    let bindings = match resource_dir {
+        Some(dir) => Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(dir))
+            .or_else(|_| Pdfium::bind_to_system_library()),
+        None => Pdfium::bind_to_system_library(),
+    }
+    .map_err(|e| format!("PDF support is unavailable: {}", e))?;
+    Ok(Pdfium::new(bindings))
+}
+
+pub(crate) fn load_pdf<'a>(pdfium: &'a Pdfium, bytes: &'a [u8]) -> Result<PdfDocument<'a>, String> {
+    pdfium
+        .load_pdf_from_byte_slice(bytes, None)
+        .map_err(|e| match e {
+            PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError) => {
+                "Password-protected PDFs cannot be attached".to_string()
+            }
+            e => format!("Failed to open PDF: {}", e),
+        })
+}
+
+// `number` is the page's 1-based number, for errors
+pub(crate) fn render_page(page: &PdfPage, number: usize) -> Result<DynamicImage, String> {
+    let config = PdfRenderConfig::new().set_target_width(PDF_PAGE_WIDTH);
+    page.render_with_config(&config)
+        .map(|bitmap| bitmap.as_image())
+        .map_err(|e| format!("Failed to render PDF page {}: {}", number, e))
+}
+
+// Renders up to MAX_PDF_PAGES pages
+fn rasterize_pdf(bytes: &[u8], resource_dir: Option<&Path>) -> Result<Vec<DynamicImage>, String> {
+    let pdfium = pdfium(resource_dir)?;
+    let document = load_pdf(&pdfium, bytes)?;
+    document
+        .pages()
+        .iter()
+        .take(MAX_PDF_PAGES)
+        .enumerate()
+        .map(|(index, page)| render_page(&page, index + 1))
+        .collect()
+}
+
+// Reads and converts a validated file. Blocking; PDF rendering can take a while.
+pub(crate) fn process_file(
+    path: &Path,
+    resource_dir: Option<&Path>,
+) -> Result<ProcessedFile, String> {
+    let (kind, mime_type, bytes) = validate(path)?;
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+
+    let (pages, thumbnail) = match kind {
+        AttachmentKind::Image => {
+            let image = image::load_from_memory(&data)
+                .map_err(|e| format!("Failed to read image: {}", e))?;
+            // PNGs are kept as dropped; everything else is normalized to PNG
+            let page = if mime_type == "image/png" {
+                data
+            } else {
+                encode_png(&image)?
+            };
+            (vec![page], thumbnail(&image)?)
+        }
+        AttachmentKind::Pdf => {
+            let images = rasterize_pdf(&data, resource_dir)?;
+            let first = images.first().ok_or("PDF has no pages".to_string())?;
+            let thumbnail = thumbnail(first)?;
+            let pages = images
+                .iter()
+                .map(encode_png)
+                .collect::<Result<Vec<_>, String>>()?;
+            (pages, thumbnail)
+        }
+    };
+
+    Ok(ProcessedFile {
+        name,
+        kind,
+        mime_type: mime_type.to_string(),
+        bytes,
+        pages,
+        thumbnail,
+    })
+}
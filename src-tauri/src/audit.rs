@@ -0,0 +1,160 @@
+// Pluely audit log. Every capture taken and every request that carries data to
+// another machine is appended to `audit/audit.jsonl` in the app data directory,
+// one JSON object per line, so users can check what left their machine. Pluely
+// only ever appends to it. Shared by every workspace, like the logs.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit.jsonl";
+const DEFAULT_ENTRIES: usize = 500;
+const MAX_ENTRIES: usize = 10_000;
+
+// Set at startup, since requests are recorded without an app handle
+static AUDIT_PATH: OnceLock<PathBuf> = OnceLock::new();
+// Keeps lines from concurrent writers whole
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    Capture,
+    // A payload sent off the machine
+    Egress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    // Milliseconds since the epoch
+    pub timestamp: i64,
+    pub kind: AuditKind,
+    // What took the capture or sent the payload, e.g. "selection" or "chat"
+    pub source: String,
+    // Where the payload went, without the query, which can hold keys; None
+    // for captures
+    pub destination: Option<String>,
+    // Size of the capture or payload; None when it was streamed, or only
+    // read in memory for OCR
+    pub bytes: Option<u64>,
+    // What was left out or reduced before sending; empty when nothing was
+    #[serde(default)]
+    pub redaction: Vec<String>,
+}
+
+/// Points the log at the app data directory; nothing is recorded before this
+/// or when running without the app, as the CLI does
+pub fn init(app: &AppHandle) {
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(AUDIT_DIR),
+        Err(e) => {
+            warn!("Failed to get app data directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!("Failed to create audit directory: {}", e);
+        return;
+    }
+    let _ = AUDIT_PATH.set(dir.join(AUDIT_FILE));
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn append(entry: &AuditEntry) -> Result<(), String> {
+    let Some(path) = AUDIT_PATH.get() else {
+        return Ok(());
+    };
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    line.push('\n');
+    let _guard = WRITE_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+// A failed write is logged rather than failing the capture or request
+fn record(entry: AuditEntry) {
+    if let Err(e) = append(&entry) {
+        warn!("{}", e);
+    }
+}
+
+pub fn record_capture(source: &str, bytes: Option<u64>) {
+    record(AuditEntry {
+        timestamp: now_ms(),
+        kind: AuditKind::Capture,
+        source: source.to_string(),
+        destination: None,
+        bytes,
+        redaction: Vec::new(),
+    });
+}
+
+pub fn record_egress(source: &str, url: &reqwest::Url, bytes: Option<u64>, redaction: &[String]) {
+    record(AuditEntry {
+        timestamp: now_ms(),
+        kind: AuditKind::Egress,
+        source: source.to_string(),
+        destination: Some(format!(
+            "{}{}",
+            url.origin().ascii_serialization(),
+            url.path()
+        )),
+        bytes,
+        redaction: redaction.to_vec(),
+    });
+}
+
+// Decoded size of a base64 capture
+pub fn base64_size(data: &str) -> u64 {
+    let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+    (data.len() / 4 * 3).saturating_sub(padding) as u64
+}
+
+/// The most recent `limit` entries (500 by default), newest first, optionally
+/// only those of `kind`
+#[tauri::command]
+#[specta::specta]
+pub async fn get_audit_log(
+    limit: Option<usize>,
+    kind: Option<AuditKind>,
+) -> Result<Vec<AuditEntry>, String> {
+    let Some(path) = AUDIT_PATH.get().cloned() else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(DEFAULT_ENTRIES).min(MAX_ENTRIES);
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read audit log: {}", e)),
+        };
+        // A line cut short by a crash is skipped
+        Ok(text
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .filter(|entry| kind.is_none() || kind == Some(entry.kind))
+            .take(limit)
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task panicked: {}", e))?
+}
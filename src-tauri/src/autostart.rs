@@ -0,0 +1,105 @@
+// Launch at login via the autostart plugin: a LaunchAgent on macOS, the Run
+// registry key on Windows and an XDG autostart entry on Linux.
+use serde::Serialize;
+use serde_json::json;
+use specta::Type;
+use tauri::AppHandle;
+use tracing::warn;
+
+use crate::settings::{current_settings, set_setting};
+
+// Passed by the login item so a launch at login can be told apart
+pub const AUTOSTART_FLAG: &str = "--autostart";
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    // Whether the OS will actually launch the app at login
+    pub enabled: bool,
+    pub start_hidden: bool,
+}
+
+// True when the OS started the app at login
+pub fn launched_at_login() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_FLAG)
+}
+
+#[cfg(desktop)]
+fn is_registered(app: &AppHandle) -> Result<bool, String> {
+    use tauri_plugin_autostart::ManagerExt;
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to read autostart status: {}", e))
+}
+
+#[cfg(not(desktop))]
+fn is_registered(_app: &AppHandle) -> Result<bool, String> {
+    Ok(false)
+}
+
+#[cfg(desktop)]
+fn register(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    use tauri_plugin_autostart::ManagerExt;
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| format!("Failed to update autostart: {}", e))
+}
+
+#[cfg(not(desktop))]
+fn register(_app: &AppHandle, _enabled: bool) -> Result<(), String> {
+    Err("Autostart is not supported on this platform".to_string())
+}
+
+// Brings the login item in line with the saved setting, e.g. after a
+// reinstall removed it. Nothing changes until settings were saved once.
+pub fn sync_autostart(app: &AppHandle) {
+    if !crate::settings::settings_file_exists(app) {
+        return;
+    }
+    let Ok(settings) = current_settings(app) else {
+        return;
+    };
+    let wanted = settings.window.autostart;
+    match is_registered(app) {
+        Ok(registered) if registered != wanted => {
+            if let Err(e) = register(app, wanted) {
+                warn!("{}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("{}", e),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_autostart(app: AppHandle) -> Result<AutostartStatus, String> {
+    Ok(AutostartStatus {
+        enabled: is_registered(&app)?,
+        start_hidden: current_settings(&app)?.window.autostart_hidden,
+    })
+}
+
+// Registers or removes the login item and saves the choice; `start_hidden`
+// keeps the window in the tray when launched at login
+#[tauri::command]
+#[specta::specta]
+pub fn set_autostart(
+    app: AppHandle,
+    enabled: bool,
+    start_hidden: Option<bool>,
+) -> Result<AutostartStatus, String> {
+    register(&app, enabled)?;
+
+    let mut window = current_settings(&app)?.window;
+    window.autostart = enabled;
+    if let Some(start_hidden) = start_hidden {
+        window.autostart_hidden = start_hidden;
+    }
+    set_setting(app.clone(), "window".to_string(), json!(window))?;
+    get_autostart(app)
+}
@@ -0,0 +1,98 @@
+// Pluely headless browser. Webviews can't render a page to an image or a PDF
+// on their own, so an installed Chromium-based browser does it headlessly.
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Chromium-based browsers, tried in order
+#[cfg(target_os = "windows")]
+const BROWSERS: &[&str] = &[
+    "C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe",
+    "C:\\Program Files (x86)\\Google\\Chrome\\Application\\chrome.exe",
+    "C:\\Program Files (x86)\\Microsoft\\Edge\\Application\\msedge.exe",
+    "C:\\Program Files\\Microsoft\\Edge\\Application\\msedge.exe",
+];
+#[cfg(target_os = "macos")]
+const BROWSERS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const BROWSERS: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "microsoft-edge",
+    "brave-browser",
+];
+
+pub fn find_browser() -> Option<PathBuf> {
+    BROWSERS.iter().map(PathBuf::from).find(|browser| {
+        if browser.is_absolute() {
+            browser.exists()
+        } else {
+            std::process::Command::new(browser)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success())
+        }
+    })
+}
+
+// Runs the browser, killing it if it is still going after `timeout`
+fn run(browser: &Path, args: &[String], timeout: Duration) -> Result<(), String> {
+    let mut child = std::process::Command::new(browser)
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start browser: {}", e))?;
+    let started = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("Browser exited with {}", status)),
+            Ok(None) if started.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("Browser took too long to render the page".to_string());
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("Failed to wait for browser: {}", e)),
+        }
+    }
+}
+
+/// A PNG of `url` as it renders in a `width`x`height` window, given up to
+/// `timeout`. Runs in a throwaway profile so the user's browser is untouched.
+pub fn screenshot(
+    url: &str,
+    width: u32,
+    height: u32,
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    let browser =
+        find_browser().ok_or("Page screenshots need Chrome, Edge or Chromium installed")?;
+    let work_dir = std::env::temp_dir().join(format!("pluely-browser-{}", uuid::Uuid::new_v4()));
+    let output = work_dir.join("screenshot.png");
+    let args = vec![
+        "--headless".to_string(),
+        "--disable-gpu".to_string(),
+        "--hide-scrollbars".to_string(),
+        "--mute-audio".to_string(),
+        "--no-first-run".to_string(),
+        format!("--user-data-dir={}", work_dir.join("profile").display()),
+        format!("--window-size={},{}", width, height),
+        // Lets scripts and late images settle before the shot
+        "--virtual-time-budget=5000".to_string(),
+        format!("--screenshot={}", output.display()),
+        url.to_string(),
+    ];
+    let result = run(&browser, &args, timeout).and_then(|()| {
+        std::fs::read(&output).map_err(|e| format!("Failed to read page screenshot: {}", e))
+    });
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
@@ -0,0 +1,129 @@
+// Pluely camera snapshots. A single frame from a webcam, returned like a
+// screen capture, so a printed page or a whiteboard can be shown to the model.
+use base64::Engine;
+use image::codecs::png::PngEncoder;
+use image::{ColorType, DynamicImage, ImageEncoder, RgbImage};
+use nokhwa::pixel_format::RgbFormat;
+use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
+use nokhwa::Camera;
+use serde::Serialize;
+use specta::Type;
+use tauri::Manager;
+
+// Cameras adjust exposure over their first frames, which come out dark
+const WARMUP_FRAMES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraDevice {
+    // What `capture_camera_frame` takes
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[cfg(target_os = "macos")]
+fn request_access() -> Result<(), String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    nokhwa::nokhwa_initialize(move |granted| {
+        let _ = sender.send(granted);
+    });
+    match receiver.recv() {
+        Ok(true) => Ok(()),
+        _ => Err("Camera access was denied; allow it in System Settings".to_string()),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn request_access() -> Result<(), String> {
+    Ok(())
+}
+
+fn parse_index(device_id: Option<String>) -> CameraIndex {
+    match device_id {
+        Some(id) => match id.parse::<u32>() {
+            Ok(index) => CameraIndex::Index(index),
+            Err(_) => CameraIndex::String(id),
+        },
+        None => CameraIndex::Index(0),
+    }
+}
+
+fn grab_frame(index: CameraIndex) -> Result<RgbImage, String> {
+    let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+    let mut camera =
+        Camera::new(index, format).map_err(|e| format!("Failed to open camera: {}", e))?;
+    camera
+        .open_stream()
+        .map_err(|e| format!("Failed to start camera: {}", e))?;
+    let mut frame = None;
+    for _ in 0..WARMUP_FRAMES {
+        frame = Some(
+            camera
+                .frame()
+                .map_err(|e| format!("Failed to read camera frame: {}", e))?,
+        );
+    }
+    let _ = camera.stop_stream();
+    let decoded = frame
+        .ok_or("Camera returned no frame")?
+        .decode_image::<RgbFormat>()
+        .map_err(|e| format!("Failed to decode camera frame: {}", e))?;
+    let (width, height) = (decoded.width(), decoded.height());
+    RgbImage::from_raw(width, height, decoded.into_raw())
+        .ok_or_else(|| "Failed to decode camera frame".to_string())
+}
+
+// Cameras the OS knows of, in the order it lists them
+#[tauri::command]
+#[specta::specta]
+pub async fn list_cameras() -> Result<Vec<CameraDevice>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        request_access()?;
+        let backend =
+            nokhwa::native_api_backend().ok_or("Cameras are not supported on this platform")?;
+        let cameras =
+            nokhwa::query(backend).map_err(|e| format!("Failed to list cameras: {}", e))?;
+        Ok(cameras
+            .into_iter()
+            .map(|camera| CameraDevice {
+                id: camera.index().to_string(),
+                name: camera.human_name(),
+                description: camera.description().to_string(),
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+/// A PNG frame from camera `device_id` (the first camera when None) as base64,
+/// the same format `capture_to_base64` returns
+#[tauri::command]
+#[specta::specta]
+pub async fn capture_camera_frame(
+    window: tauri::WebviewWindow,
+    device_id: Option<String>,
+) -> Result<String, String> {
+    let index = parse_index(device_id);
+    let base64_str = tauri::async_runtime::spawn_blocking(move || {
+        request_access()?;
+        let image = DynamicImage::ImageRgb8(grab_frame(index)?).to_rgba8();
+        let image = crate::power::fit_capture(image);
+        let mut png_buffer = Vec::new();
+        PngEncoder::new(&mut png_buffer)
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                ColorType::Rgba8.into(),
+            )
+            .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+        crate::audit::record_capture("camera", Some(png_buffer.len() as u64));
+        Ok::<_, String>(base64::engine::general_purpose::STANDARD.encode(png_buffer))
+    })
+    .await
+    .map_err(|e| format!("Task panicked: {}", e))??;
+    crate::feedback::capture_taken(window.app_handle(), crate::feedback::CaptureTrigger::Camera);
+    Ok(base64_str)
+}
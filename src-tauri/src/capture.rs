@@ -1,18 +1,24 @@
 use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
 use image::{ColorType, GenericImageView, ImageEncoder};
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::{thread, time::Duration};
 use tauri::Emitter;
 use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::warn;
 use xcap::Monitor;
+use zeroize::Zeroize;
+
+use crate::events::{CaptureCloseReason, CaptureClosed, CapturedSelection, SelectionChanged};
 
 /// Mendapatkan posisi mouse saat ini (Linux - mendukung X11, Xorg, dan Wayland)
 #[cfg(target_os = "linux")]
-fn get_mouse_position() -> Result<(i32, i32), String> {
+pub(crate) fn get_mouse_position() -> Result<(i32, i32), String> {
     use std::process::Command;
     use std::env;
 
@@ -167,7 +173,7 @@ fn get_mouse_position() -> Result<(i32, i32), String> {
 
 /// Mendapatkan posisi mouse saat ini (macOS)
 #[cfg(target_os = "macos")]
-fn get_mouse_position() -> Result<(i32, i32), String> {
+pub(crate) fn get_mouse_position() -> Result<(i32, i32), String> {
     use std::process::Command;
 
     // Menggunakan AppleScript untuk mendapatkan posisi mouse di macOS
@@ -190,7 +196,7 @@ fn get_mouse_position() -> Result<(i32, i32), String> {
 
 /// Mendapatkan posisi mouse saat ini (Windows)
 #[cfg(target_os = "windows")]
-fn get_mouse_position() -> Result<(i32, i32), String> {
+pub(crate) fn get_mouse_position() -> Result<(i32, i32), String> {
     use windows::Win32::Foundation::POINT;
     use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
     unsafe {
@@ -204,7 +210,7 @@ fn get_mouse_position() -> Result<(i32, i32), String> {
 }
 
 /// Mencari index monitor yang mengandung posisi tertentu
-fn find_monitor_at_position(monitors: &[Monitor], x: i32, y: i32) -> Option<usize> {
+pub(crate) fn find_monitor_at_position(monitors: &[Monitor], x: i32, y: i32) -> Option<usize> {
     for (idx, monitor) in monitors.iter().enumerate() {
         let mon_x = monitor.x();
         let mon_y = monitor.y();
@@ -219,7 +225,7 @@ fn find_monitor_at_position(monitors: &[Monitor], x: i32, y: i32) -> Option<usiz
 }
 
 /// Informasi dukungan multi-monitor
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct MultiMonitorSupport {
     pub supported: bool,
     pub session_type: String,
@@ -231,6 +237,7 @@ pub struct MultiMonitorSupport {
 /// Cek apakah tools untuk mendapatkan posisi mouse tersedia
 #[cfg(target_os = "linux")]
 #[tauri::command]
+#[specta::specta]
 pub fn check_multi_monitor_support() -> MultiMonitorSupport {
     use std::process::Command;
     use std::env;
@@ -297,6 +304,7 @@ pub fn check_multi_monitor_support() -> MultiMonitorSupport {
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
+#[specta::specta]
 pub fn check_multi_monitor_support() -> MultiMonitorSupport {
     MultiMonitorSupport {
         supported: true,
@@ -309,6 +317,7 @@ pub fn check_multi_monitor_support() -> MultiMonitorSupport {
 
 #[cfg(target_os = "windows")]
 #[tauri::command]
+#[specta::specta]
 pub fn check_multi_monitor_support() -> MultiMonitorSupport {
     // Coba akses Win32 API untuk cek dukungan
     let mut available_tools = Vec::new();
@@ -332,7 +341,55 @@ pub fn check_multi_monitor_support() -> MultiMonitorSupport {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// How the selection overlay looks, from the `overlay` settings section
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlaySettings {
+    // Hex color laid over the screen outside the selection
+    pub dim_color: String,
+    // 0.0 to 1.0
+    pub dim_opacity: f64,
+    // Hex color; None follows the theme
+    pub border_color: Option<String>,
+    // Shows the selection's size in pixels next to it
+    pub show_dimensions: bool,
+    // Shows an enlarged view around the cursor for precise edges
+    pub show_magnifier: bool,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            dim_color: "#0f172a".to_string(),
+            dim_opacity: 0.35,
+            border_color: None,
+            show_dimensions: false,
+            show_magnifier: false,
+        }
+    }
+}
+
+impl OverlaySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let is_hex = |color: &str| {
+            color.strip_prefix('#').is_some_and(|hex| {
+                matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+            })
+        };
+        if !is_hex(&self.dim_color) {
+            return Err(format!("Invalid overlay dim color: {}", self.dim_color));
+        }
+        if let Some(border_color) = self.border_color.as_deref().filter(|c| !is_hex(c)) {
+            return Err(format!("Invalid overlay border color: {}", border_color));
+        }
+        if !(0.0..=1.0).contains(&self.dim_opacity) {
+            return Err("Invalid overlay dim opacity: must be 0.0-1.0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
 pub struct SelectionCoords {
     pub x: u32,
     pub y: u32,
@@ -340,15 +397,161 @@ pub struct SelectionCoords {
     pub height: u32,
 }
 
+// Optional shape a capture session's selection must have, written "16:9" for
+// an aspect ratio or "1280x720" for an exact size in image pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionConstraint {
+    Ratio(u32, u32),
+    Fixed(u32, u32),
+}
+
+impl TryFrom<String> for SelectionConstraint {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let parse = |separator: char| {
+            let (a, b) = value.trim().split_once(separator)?;
+            let (a, b) = (a.trim().parse::<u32>().ok()?, b.trim().parse::<u32>().ok()?);
+            (a > 0 && b > 0).then_some((a, b))
+        };
+        if let Some((w, h)) = parse(':') {
+            Ok(SelectionConstraint::Ratio(w, h))
+        } else if let Some((w, h)) = parse('x') {
+            Ok(SelectionConstraint::Fixed(w, h))
+        } else {
+            Err(format!(
+                "Invalid selection constraint {}: expected a ratio like 16:9 or a size like 1280x720",
+                value
+            ))
+        }
+    }
+}
+
+impl SelectionConstraint {
+    // Fits `coords` to the constraint on an image of the given size. A ratio
+    // trims the selection's long side; a fixed size is centered on the
+    // selection and moved back onto the image if it hangs off an edge.
+    fn apply(
+        self,
+        coords: SelectionCoords,
+        image_width: u32,
+        image_height: u32,
+    ) -> Result<SelectionCoords, String> {
+        match self {
+            SelectionConstraint::Ratio(rw, rh) => {
+                let (w, h) = (coords.width as u64, coords.height as u64);
+                let (width, height) = if w * rh as u64 > h * rw as u64 {
+                    (h * rw as u64 / rh as u64, h)
+                } else {
+                    (w, w * rh as u64 / rw as u64)
+                };
+                Ok(SelectionCoords {
+                    width: (width as u32).max(1),
+                    height: (height as u32).max(1),
+                    ..coords
+                })
+            }
+            SelectionConstraint::Fixed(width, height) => {
+                if width > image_width || height > image_height {
+                    return Err(format!(
+                        "Selection size {}x{} is larger than the screen ({}x{})",
+                        width, height, image_width, image_height
+                    ));
+                }
+                let center_x = coords.x + coords.width / 2;
+                let center_y = coords.y + coords.height / 2;
+                Ok(SelectionCoords {
+                    x: center_x.saturating_sub(width / 2).min(image_width - width),
+                    y: center_y
+                        .saturating_sub(height / 2)
+                        .min(image_height - height),
+                    width,
+                    height,
+                })
+            }
+        }
+    }
+}
+
+// Smallest keyboard selection, in image pixels
+const MIN_SELECTION: u32 = 10;
+
+// The keyboard selection, in image pixels of the monitor it is on. Emitted as
+// `selection-changed` so the overlays can draw it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Selection {
+    pub monitor_index: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Selection {
+    // A third of the monitor, centered, to start nudging from
+    fn initial(monitor_index: usize, image_width: u32, image_height: u32) -> Self {
+        let width = (image_width / 3).max(MIN_SELECTION).min(image_width);
+        let height = (image_height / 3).max(MIN_SELECTION).min(image_height);
+        Self {
+            monitor_index,
+            x: (image_width - width) / 2,
+            y: (image_height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    fn coords(self) -> SelectionCoords {
+        SelectionCoords {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    // Keeps the selection on its monitor's image
+    fn clamped(self, image_width: u32, image_height: u32) -> Self {
+        let width = self
+            .width
+            .clamp(MIN_SELECTION.min(image_width), image_width);
+        let height = self
+            .height
+            .clamp(MIN_SELECTION.min(image_height), image_height);
+        Self {
+            x: self.x.min(image_width - width),
+            y: self.y.min(image_height - height),
+            width,
+            height,
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MonitorInfo {
     pub image: image::RgbaImage,
 }
 
+// The screen is zeroed once the session is done with it, not just freed
+impl Drop for MonitorInfo {
+    fn drop(&mut self) {
+        let pixels: &mut [u8] = &mut self.image;
+        pixels.zeroize();
+    }
+}
+
 // Store captured images from all monitors temporarily for cropping
 pub struct CaptureState {
     pub captured_monitors: Arc<Mutex<HashMap<usize, MonitorInfo>>>,
     pub overlay_active: Arc<AtomicBool>,
+    // Where focus was before the overlays opened, handed back when they close
+    pub previous_focus: Arc<Mutex<Option<crate::focus::PreviousWindow>>>,
+    // Set once the keyboard is used to select; the rectangle that is captured
+    pub selection: Arc<Mutex<Option<Selection>>>,
+    // Shape every selection of the current session must have
+    pub constraint: Arc<Mutex<Option<SelectionConstraint>>>,
 }
 
 impl Default for CaptureState {
@@ -356,12 +559,20 @@ impl Default for CaptureState {
         Self {
             captured_monitors: Arc::default(),
             overlay_active: Arc::new(AtomicBool::new(false)),
+            previous_focus: Arc::default(),
+            selection: Arc::default(),
+            constraint: Arc::default(),
         }
     }
 }
 
 #[tauri::command]
-pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
+#[specta::specta]
+pub async fn start_screen_capture(
+    app: tauri::AppHandle,
+    constraint: Option<String>,
+) -> Result<(), String> {
+    let constraint = constraint.map(SelectionConstraint::try_from).transpose()?;
     // Get all monitors
     let capture_monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
 
@@ -375,7 +586,7 @@ pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
         .map_err(|e| format!("Failed to get monitor layout: {}", e))?;
 
     if tauri_monitors.len() != capture_monitors.len() {
-        eprintln!(
+        warn!(
             "Monitor count mismatch between capture ({}) and layout ({}); falling back to capture dimensions",
             capture_monitors.len(),
             tauri_monitors.len()
@@ -385,17 +596,23 @@ pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
     let state = app.state::<CaptureState>();
     if state.overlay_active.load(Ordering::SeqCst) {
         // Attempt to clean up any stale overlays before proceeding
-        let _ = close_overlay_window(app.clone());
+        close_overlays(&app, CaptureCloseReason::Restarted);
     }
     state.overlay_active.store(true, Ordering::SeqCst);
+    *state.previous_focus.lock().unwrap() = crate::focus::foreground_window();
+    *state.constraint.lock().unwrap() = constraint;
     let mut captured_monitors = HashMap::new();
 
     // Capture all monitors and store their info
     for (idx, monitor) in capture_monitors.iter().enumerate() {
-        let captured_image = monitor.capture_image().map_err(|e| {
-            state.overlay_active.store(false, Ordering::SeqCst);
-            format!("Failed to capture monitor {}: {}", idx, e)
-        })?;
+        let captured_image = monitor
+            .capture_image()
+            .map_err(|e| format!("Failed to capture monitor {}: {}", idx, e))
+            .and_then(|mut image| {
+                crate::sensitive::enforce(monitor, &mut image)?;
+                Ok(image)
+            })
+            .inspect_err(|_| state.overlay_active.store(false, Ordering::SeqCst))?;
 
         let monitor_info = MonitorInfo {
             image: captured_image,
@@ -442,7 +659,7 @@ pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
         let window_label = format!("capture-overlay-{}", idx);
 
         let overlay =
-            WebviewWindowBuilder::new(&app, &window_label, WebviewUrl::App("index.html".into()))
+            WebviewWindowBuilder::new(&app, &window_label, WebviewUrl::App("overlay.html".into()))
                 .title("Screen Capture")
                 .inner_size(logical_width, logical_height)
                 .position(logical_x, logical_y)
@@ -450,6 +667,7 @@ pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
                 .always_on_top(true)
                 .decorations(false)
                 .skip_taskbar(true)
+                .content_protected(crate::window::content_protected())
                 .resizable(false)
                 .closable(false)
                 .minimizable(false)
@@ -493,9 +711,67 @@ pub async fn start_screen_capture(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+// Gives focus back to the app that was in front before the capture started
+fn restore_previous_focus(app: &tauri::AppHandle) {
+    let state = app.state::<CaptureState>();
+    let previous = state.previous_focus.lock().unwrap().take();
+    if let Some(previous) = previous {
+        crate::focus::restore_focus(&previous);
+    }
+}
+
+// Appearance settings for the overlay windows to style themselves with
+#[tauri::command]
+#[specta::specta]
+pub fn get_overlay_config(app: tauri::AppHandle) -> Result<OverlaySettings, String> {
+    Ok(crate::settings::current_settings(&app)?.overlay)
+}
+
+// The frozen screen behind an overlay as a base64 JPEG, for the magnifier
+#[tauri::command]
+#[specta::specta]
+pub async fn get_overlay_image(
+    app: tauri::AppHandle,
+    monitor_index: usize,
+) -> Result<String, String> {
+    let image = app
+        .state::<CaptureState>()
+        .captured_monitors
+        .lock()
+        .unwrap()
+        .get(&monitor_index)
+        .map(|monitor_info| monitor_info.image.clone())
+        .ok_or(format!(
+            "No captured image found for monitor {}",
+            monitor_index
+        ))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let rgb = image::DynamicImage::ImageRgba8(image).to_rgb8();
+        let mut jpeg_buffer = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg_buffer, 80)
+            .write_image(
+                rgb.as_raw(),
+                rgb.width(),
+                rgb.height(),
+                ColorType::Rgb8.into(),
+            )
+            .map_err(|e| format!("Failed to encode to JPEG: {}", e))?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(jpeg_buffer))
+    })
+    .await
+    .map_err(|e| format!("Task panicked: {}", e))?
+}
+
 // close overlay window
 #[tauri::command]
+#[specta::specta]
 pub fn close_overlay_window(app: tauri::AppHandle) -> Result<(), String> {
+    close_overlays(&app, CaptureCloseReason::Cancelled);
+    Ok(())
+}
+
+fn close_overlays(app: &tauri::AppHandle, reason: CaptureCloseReason) {
     // Get all webview windows and close those that are capture overlays
     let webview_windows = app.webview_windows();
 
@@ -508,17 +784,34 @@ pub fn close_overlay_window(app: tauri::AppHandle) -> Result<(), String> {
     // Clear captured monitors from state
     let state = app.state::<CaptureState>();
     state.captured_monitors.lock().unwrap().clear();
+    state.selection.lock().unwrap().take();
     state.overlay_active.store(false, Ordering::SeqCst);
+    restore_previous_focus(app);
 
     // Emit an event to the main window to signal that the overlay has been closed
     if let Some(main_window) = app.get_webview_window("main") {
-        main_window.emit("capture-closed", ()).unwrap();
+        if let Err(e) = main_window.emit("capture-closed", CaptureClosed::new(reason)) {
+            warn!("Failed to emit capture-closed event: {}", e);
+        }
     }
+}
 
-    Ok(())
+// Drops the screens held for a selection, closing the overlays if they are
+// open; returns how many were held
+pub fn purge_captures(app: &tauri::AppHandle) -> usize {
+    let state = app.state::<CaptureState>();
+    let held = state.captured_monitors.lock().unwrap().len();
+    if state.overlay_active.load(Ordering::SeqCst) {
+        close_overlays(app, CaptureCloseReason::Cancelled);
+    } else {
+        state.captured_monitors.lock().unwrap().clear();
+        state.selection.lock().unwrap().take();
+    }
+    held
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn capture_selected_area(
     app: tauri::AppHandle,
     coords: SelectionCoords,
@@ -547,8 +840,32 @@ pub async fn capture_selected_area(
     let width = coords.width.min(img_width - x);
     let height = coords.height.min(img_height - y);
 
+    // Held to the session's ratio or size, if it has one
+    let bounded = SelectionCoords {
+        x,
+        y,
+        width,
+        height,
+    };
+    let constraint = *state.constraint.lock().unwrap();
+    let SelectionCoords {
+        x,
+        y,
+        width,
+        height,
+    } = match constraint.map(|c| c.apply(bounded, img_width, img_height)) {
+        Some(Ok(constrained)) => constrained,
+        Some(Err(e)) => {
+            // Kept so another selection can be made
+            captured_monitors.insert(monitor_index, monitor_info);
+            return Err(e);
+        }
+        None => bounded,
+    };
+
     // Crop the image to the selected area
     let cropped = monitor_info.image.view(x, y, width, height).to_image();
+    let cropped = crate::power::fit_capture(cropped);
 
     // Encode to PNG and base64
     let mut png_buffer = Vec::new();
@@ -560,11 +877,14 @@ pub async fn capture_selected_area(
             ColorType::Rgba8.into(),
         )
         .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+    crate::audit::record_capture("selection", Some(png_buffer.len() as u64));
+    crate::wipe::track_image(&cropped);
 
     let base64_str = base64::engine::general_purpose::STANDARD.encode(png_buffer);
 
     captured_monitors.clear();
     drop(captured_monitors);
+    state.selection.lock().unwrap().take();
 
     // Close all overlay windows
     let webview_windows = app.webview_windows();
@@ -575,16 +895,187 @@ pub async fn capture_selected_area(
     }
 
     // Emit event with base64 data
-    app.emit("captured-selection", &base64_str)
+    let captured = CapturedSelection::png(
+        Some(monitor_index),
+        cropped.width(),
+        cropped.height(),
+        base64_str.clone(),
+    )
+    .with_thumbnail(crate::captures::thumbnail_of(&app, &cropped));
+    app.emit("captured-selection", captured)
         .map_err(|e| format!("Failed to emit captured-selection event: {}", e))?;
 
     state.overlay_active.store(false, Ordering::SeqCst);
+    restore_previous_focus(&app);
+    crate::feedback::capture_taken(&app, crate::feedback::CaptureTrigger::Selection);
 
     Ok(base64_str)
 }
 
+// Applies `change` to the keyboard selection, starting one on the calling
+// overlay's monitor if there is none yet, and tells the overlays
+fn update_selection(
+    app: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    change: impl FnOnce(&mut Selection),
+) -> Result<Selection, String> {
+    let state = app.state::<CaptureState>();
+    let captured_monitors = state
+        .captured_monitors
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut selection = state
+        .selection
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+    let monitor_index = match *selection {
+        Some(current) => current.monitor_index,
+        None => window
+            .label()
+            .strip_prefix("capture-overlay-")
+            .and_then(|idx| idx.parse().ok())
+            .ok_or("Selection can only start from a capture overlay")?,
+    };
+    let image = &captured_monitors
+        .get(&monitor_index)
+        .ok_or(format!(
+            "No captured image found for monitor {}",
+            monitor_index
+        ))?
+        .image;
+    let mut updated = selection
+        .unwrap_or_else(|| Selection::initial(monitor_index, image.width(), image.height()));
+    change(&mut updated);
+    let mut updated = updated.clamped(image.width(), image.height());
+    let constraint = *state
+        .constraint
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(constraint) = constraint {
+        let coords = constraint.apply(updated.coords(), image.width(), image.height())?;
+        updated = Selection {
+            monitor_index,
+            x: coords.x,
+            y: coords.y,
+            width: coords.width,
+            height: coords.height,
+        };
+    }
+    *selection = Some(updated);
+
+    app.emit("selection-changed", SelectionChanged::from(updated))
+        .map_err(|e| format!("Failed to emit selection-changed event: {}", e))?;
+    Ok(updated)
+}
+
+// Moves the keyboard selection by whole image pixels
+#[tauri::command]
+#[specta::specta]
+pub fn nudge_selection(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    dx: i32,
+    dy: i32,
+) -> Result<Selection, String> {
+    update_selection(&app, &window, |selection| {
+        selection.x = selection.x.saturating_add_signed(dx);
+        selection.y = selection.y.saturating_add_signed(dy);
+    })
+}
+
+// Grows or shrinks the keyboard selection from its bottom-right corner
+#[tauri::command]
+#[specta::specta]
+pub fn resize_selection(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    dw: i32,
+    dh: i32,
+) -> Result<Selection, String> {
+    update_selection(&app, &window, |selection| {
+        selection.width = selection.width.saturating_add_signed(dw);
+        selection.height = selection.height.saturating_add_signed(dh);
+    })
+}
+
+// Captures the keyboard selection, exactly as `capture_selected_area` would
 #[tauri::command]
-pub async fn capture_to_base64(_window: tauri::WebviewWindow) -> Result<String, String> {
+#[specta::specta]
+pub async fn confirm_selection(app: tauri::AppHandle) -> Result<String, String> {
+    let selection = app
+        .state::<CaptureState>()
+        .selection
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .ok_or("Nothing is selected")?;
+    capture_selected_area(app, selection.coords(), selection.monitor_index).await
+}
+
+// Dots per logical inch the OS assumes at a scale factor of 1. Monitors don't
+// report their physical size, so this is the screen's nominal density.
+#[cfg(target_os = "macos")]
+const BASE_DPI: f64 = 72.0;
+#[cfg(not(target_os = "macos"))]
+const BASE_DPI: f64 = 96.0;
+const MM_PER_INCH: f64 = 25.4;
+
+// A selection measured as a ruler would: its size in image pixels and at the
+// monitor's DPI, and its center
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionMeasurement {
+    pub width_px: u32,
+    pub height_px: u32,
+    pub dpi: f64,
+    pub width_mm: f64,
+    pub height_mm: f64,
+    pub width_in: f64,
+    pub height_in: f64,
+    // In image pixels of the monitor, like the selection
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+/// Measures a selection on a monitor, for using the overlay as a screen ruler
+#[tauri::command]
+#[specta::specta]
+pub fn measure_selection(
+    app: tauri::AppHandle,
+    coords: SelectionCoords,
+    monitor: usize,
+) -> Result<SelectionMeasurement, String> {
+    let scale_factor = app
+        .available_monitors()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?
+        .get(monitor)
+        .ok_or_else(|| format!("Monitor not found: {}", monitor))?
+        .scale_factor();
+    let dpi = BASE_DPI * scale_factor;
+    let inches = |px: u32| px as f64 / dpi;
+    Ok(SelectionMeasurement {
+        width_px: coords.width,
+        height_px: coords.height,
+        dpi,
+        width_mm: inches(coords.width) * MM_PER_INCH,
+        height_mm: inches(coords.height) * MM_PER_INCH,
+        width_in: inches(coords.width),
+        height_in: inches(coords.height),
+        center_x: coords.x as f64 + coords.width as f64 / 2.0,
+        center_y: coords.y as f64 + coords.height as f64 / 2.0,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn capture_to_base64(window: tauri::WebviewWindow) -> Result<String, String> {
+    let base64_str = capture_screen_base64().await?;
+    crate::feedback::capture_taken(window.app_handle(), crate::feedback::CaptureTrigger::Screen);
+    Ok(base64_str)
+}
+
+// PNG of the monitor under the mouse, as base64
+pub async fn capture_screen_base64() -> Result<String, String> {
     // Coba dapatkan posisi mouse terlebih dahulu
     let mouse_pos = get_mouse_position().ok();
 
@@ -618,9 +1109,11 @@ pub async fn capture_to_base64(_window: tauri::WebviewWindow) -> Result<String,
             })
             .ok_or_else(|| "Failed to determine target monitor".to_string())?;
 
-        let image = monitor
+        let mut image = monitor
             .capture_image()
             .map_err(|e| format!("Failed to capture image: {}", e))?;
+        crate::sensitive::enforce(&monitor, &mut image)?;
+        let image = crate::power::fit_capture(image);
         let mut png_buffer = Vec::new();
         PngEncoder::new(&mut png_buffer)
             .write_image(
@@ -630,6 +1123,8 @@ pub async fn capture_to_base64(_window: tauri::WebviewWindow) -> Result<String,
                 ColorType::Rgba8.into(),
             )
             .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+        crate::audit::record_capture("screen", Some(png_buffer.len() as u64));
+        crate::wipe::track_image(&image);
         let base64_str = base64::engine::general_purpose::STANDARD.encode(png_buffer);
 
         Ok(base64_str)
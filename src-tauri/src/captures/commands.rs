@@ -0,0 +1,345 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use tracing::warn;
+
+use super::diff::diff_images;
+use super::metadata::{embed_metadata, read_metadata, CaptureMetadata};
+use super::thumbnails::{create_thumbnail, load_thumbnail};
+use super::watermark::watermark_bytes;
+use super::{
+    capture_path, captures_dir, disk_usage, expired, extension_for, list_files, remove_file,
+    CaptureFile, CleanupReport, RetentionPolicy,
+};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Default)]
+pub struct CapturesState {
+    retention: Mutex<RetentionPolicy>,
+    // Runs cleanup now instead of at the next interval
+    cleanup_wake: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub captures_bytes: u64,
+    pub captures_count: usize,
+    pub oldest_capture_at: Option<i64>,
+    // pluely.db with its WAL files
+    pub database_bytes: u64,
+    // Settings, models and anything else the app keeps
+    pub other_bytes: u64,
+    pub total_bytes: u64,
+    pub retention: RetentionPolicy,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn retention(app: &AppHandle) -> Result<RetentionPolicy, String> {
+    let state = app.state::<CapturesState>();
+    let policy = state
+        .retention
+        .lock()
+        .map_err(|e| format!("Failed to get retention policy: {}", e))?
+        .clone();
+    Ok(policy)
+}
+
+// Only files inside the library may be deleted through it
+fn library_path(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let dir = captures_dir(app)?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve captures directory: {}", e))?;
+    let path = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Capture not found: {}", e))?;
+    if !path.starts_with(&dir) {
+        return Err("Path is not in the capture library".to_string());
+    }
+    Ok(path)
+}
+
+// Applies the retention policy once
+async fn cleanup(app: &AppHandle) -> Result<CleanupReport, String> {
+    let policy = retention(app)?;
+    let mut report = CleanupReport::default();
+    for file in expired(&list_files(app)?, &policy, now_ms()) {
+        remove_file(app, Path::new(&file.path)).await?;
+        report.removed += 1;
+        report.freed_bytes += file.bytes;
+    }
+    if report.removed > 0 {
+        let _ = app.emit(
+            "captures-cleaned",
+            crate::events::CapturesCleaned(report.clone()),
+        );
+    }
+    Ok(report)
+}
+
+// Enforces retention for as long as the app runs
+pub fn start_capture_cleanup(app: AppHandle) {
+    let wake = app.state::<CapturesState>().cleanup_wake.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = cleanup(&app).await {
+                warn!("Capture cleanup failed: {}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(CLEANUP_INTERVAL) => {}
+                _ = wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_capture_retention(app: AppHandle) -> Result<RetentionPolicy, String> {
+    retention(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_capture_retention(app: AppHandle, policy: RetentionPolicy) -> Result<(), String> {
+    policy.validate()?;
+    let state = app.state::<CapturesState>();
+    *state
+        .retention
+        .lock()
+        .map_err(|e| format!("Failed to update retention policy: {}", e))? = policy;
+    // A tighter policy takes effect right away
+    state.cleanup_wake.notify_one();
+    Ok(())
+}
+
+// Saves a base64 screenshot or recording into the library and returns its
+// path, ready to attach to a message. Screenshots carry when and where they
+// were taken, plus `note`, in the file itself.
+#[tauri::command]
+#[specta::specta]
+pub fn save_capture(
+    app: AppHandle,
+    data_base64: String,
+    kind: Option<String>,
+    monitor: Option<String>,
+    note: Option<String>,
+) -> Result<CaptureFile, String> {
+    let kind = kind.unwrap_or_else(|| "screenshot".to_string());
+    if !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') || kind.is_empty() {
+        return Err(format!("Invalid capture kind: {}", kind));
+    }
+    let data = data_base64
+        .split_once("base64,")
+        .map_or(data_base64.as_str(), |(_, data)| data);
+    let bytes = general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Failed to decode capture: {}", e))?;
+
+    // Stamped before the metadata, since stamping re-encodes the image
+    let bytes = watermark_bytes(&app, bytes)?;
+    let created_at = now_ms();
+    let metadata = CaptureMetadata {
+        created_at: Some(created_at),
+        monitor,
+        app: crate::focus::last_focused(&app).map(|focused| focused.app_name),
+        note,
+        software: Some(format!("Pluely {}", env!("CARGO_PKG_VERSION"))),
+    };
+    let bytes = embed_metadata(&bytes, &metadata);
+    let name = format!(
+        "{}-{}-{}.{}",
+        kind,
+        created_at,
+        &uuid::Uuid::new_v4().simple().to_string()[..8],
+        extension_for(&bytes)
+    );
+    let path = captures_dir(&app)?.join(name);
+    std::fs::write(&path, &bytes).map_err(|e| format!("Failed to save capture: {}", e))?;
+    crate::storage::seal_file(&path)?;
+    crate::audit::record_capture(&format!("library {}", kind), Some(bytes.len() as u64));
+    // The capture is kept even if its preview can't be made
+    if let Err(e) = create_thumbnail(&app, &path, &bytes) {
+        warn!("{}", e);
+    }
+
+    app.state::<CapturesState>().cleanup_wake.notify_one();
+    crate::notify::notify(
+        &app,
+        crate::notify::NotificationCategory::CaptureSaved,
+        "Capture saved",
+        &format!("{} added to the capture library", kind),
+    );
+    let file = CaptureFile {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        bytes: bytes.len() as u64,
+        created_at,
+        thumbnail: load_thumbnail(&app, &path),
+    };
+    crate::control::publish(crate::control::BridgeEvent::CaptureCompleted(file.clone()));
+    Ok(file)
+}
+
+// Library contents, newest first, with thumbnails of the screenshots
+#[tauri::command]
+#[specta::specta]
+pub async fn list_captures(app: AppHandle) -> Result<Vec<CaptureFile>, String> {
+    // Captures from before thumbnails existed get theirs made here
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut files = list_files(&app)?;
+        files.reverse();
+        for file in &mut files {
+            file.thumbnail = load_thumbnail(&app, Path::new(&file.path));
+        }
+        Ok(files)
+    })
+    .await
+    .map_err(|e| format!("Failed to list captures: {}", e))?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_capture(app: AppHandle, path: String) -> Result<(), String> {
+    let path = library_path(&app, &path)?;
+    remove_file(&app, &path).await
+}
+
+// Applies the retention policy now instead of waiting for the next run
+#[tauri::command]
+#[specta::specta]
+pub async fn run_capture_cleanup(app: AppHandle) -> Result<CleanupReport, String> {
+    cleanup(&app).await
+}
+
+// Disk space used by captures, the database and everything else
+#[tauri::command]
+#[specta::specta]
+pub fn get_storage_usage(app: AppHandle) -> Result<StorageUsage, String> {
+    let files = list_files(&app)?;
+    let captures_bytes = files.iter().map(|file| file.bytes).sum();
+
+    let config_dir = crate::workspaces::config_dir(&app)?;
+    let data_dir = crate::workspaces::data_dir(&app)?;
+    let database_bytes = ["pluely.db", "pluely.db-wal", "pluely.db-shm"]
+        .iter()
+        .map(|name| disk_usage(&config_dir.join(name)))
+        .sum();
+
+    // The default profile's directories also hold the other profiles
+    let nested = crate::workspaces::active_workspace() == crate::workspaces::DEFAULT_WORKSPACE;
+    let own_usage = |dir: &Path| {
+        let others = if nested {
+            disk_usage(&dir.join(crate::workspaces::WORKSPACES_DIR))
+        } else {
+            0
+        };
+        disk_usage(dir).saturating_sub(others)
+    };
+    // Both are the same directory on macOS
+    let mut total_bytes = own_usage(&data_dir);
+    if config_dir != data_dir {
+        total_bytes += own_usage(&config_dir);
+    }
+    let other_bytes = total_bytes.saturating_sub(captures_bytes + database_bytes);
+
+    Ok(StorageUsage {
+        captures_bytes,
+        captures_count: files.len(),
+        oldest_capture_at: files.first().map(|file| file.created_at),
+        database_bytes,
+        other_bytes,
+        total_bytes,
+        retention: retention(&app)?,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureDiff {
+    // Base64 PNG with the changes highlighted
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    // Share of pixels that changed, 0.0 to 100.0
+    pub changed_percent: f64,
+}
+
+fn load_image(app: &AppHandle, capture_id: &str) -> Result<image::RgbaImage, String> {
+    let bytes = crate::storage::read_file(&capture_path(app, capture_id)?)?;
+    image::load_from_memory(&bytes)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| format!("Failed to decode capture {}: {}", capture_id, e))
+}
+
+// What changed between two screenshots in the library (by file name), as an
+// image of the second with the differences highlighted
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_captures(
+    app: AppHandle,
+    id_a: String,
+    id_b: String,
+) -> Result<CaptureDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let before = load_image(&app, &id_a)?;
+        let after = load_image(&app, &id_b)?;
+        let diff = diff_images(&before, &after);
+        let mut png = Vec::new();
+        diff.image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode diff: {}", e))?;
+        Ok(CaptureDiff {
+            image: general_purpose::STANDARD.encode(png),
+            width: diff.image.width(),
+            height: diff.image.height(),
+            changed_percent: diff.changed_percent,
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to diff captures: {}", e))?
+}
+
+// What a screenshot saved by Pluely says about itself; works on copies
+// exported out of the library too
+#[tauri::command]
+#[specta::specta]
+pub fn read_capture_metadata(path: String) -> Result<CaptureMetadata, String> {
+    let bytes = crate::storage::read_file(Path::new(&path))?;
+    read_metadata(&bytes)
+}
+
+// The watermark applied to an image leaving the app some other way, such as
+// the clipboard or a share sheet; returned as base64 PNG
+#[tauri::command]
+#[specta::specta]
+pub async fn watermark_image(app: AppHandle, data_base64: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = data_base64
+            .split_once("base64,")
+            .map_or(data_base64.as_str(), |(_, data)| data);
+        let bytes = general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        let marked = watermark_bytes(&app, bytes)?;
+        // It may well end up on the clipboard, where a purge should find it
+        if let Ok(image) = image::load_from_memory(&marked) {
+            crate::wipe::track_image(&image.to_rgba8());
+        }
+        Ok(general_purpose::STANDARD.encode(marked))
+    })
+    .await
+    .map_err(|e| format!("Failed to watermark image: {}", e))?
+}
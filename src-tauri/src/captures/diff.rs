@@ -0,0 +1,54 @@
+// Pixel diff of two captures: the second image with everything that changed
+// since the first tinted red and the rest faded, so the changes stand out.
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+
+// Channel difference under which a pixel counts as unchanged, for noise and
+// compression artefacts
+const TOLERANCE: u8 = 32;
+// How much of the original shows through the red on changed pixels
+const HIGHLIGHT_ALPHA: f32 = 0.45;
+// Brightness unchanged pixels are kept at
+const FADE: f32 = 0.35;
+
+pub struct ImageDiff {
+    pub image: RgbaImage,
+    // 0.0 to 100.0
+    pub changed_percent: f64,
+}
+
+fn changed(a: &Rgba<u8>, b: &Rgba<u8>) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .any(|(x, y)| x.abs_diff(*y) > TOLERANCE)
+}
+
+// Compares `after` against `before`, which is stretched to `after`'s size when
+// the two differ
+pub fn diff_images(before: &RgbaImage, after: &RgbaImage) -> ImageDiff {
+    let before = if before.dimensions() == after.dimensions() {
+        before.clone()
+    } else {
+        image::imageops::resize(before, after.width(), after.height(), FilterType::Triangle)
+    };
+    let mut image = RgbaImage::new(after.width(), after.height());
+    let mut count = 0u64;
+    for ((x, y, new), old) in after.enumerate_pixels().zip(before.pixels()) {
+        let pixel = if changed(old, new) {
+            count += 1;
+            let mix = |channel: u8, target: f32| {
+                (channel as f32 * HIGHLIGHT_ALPHA + target * (1.0 - HIGHLIGHT_ALPHA)) as u8
+            };
+            Rgba([mix(new[0], 255.0), mix(new[1], 0.0), mix(new[2], 0.0), 255])
+        } else {
+            let fade = |channel: u8| (channel as f32 * FADE) as u8;
+            Rgba([fade(new[0]), fade(new[1]), fade(new[2]), 255])
+        };
+        image.put_pixel(x, y, pixel);
+    }
+    let total = (after.width() as u64 * after.height() as u64).max(1);
+    ImageDiff {
+        image,
+        changed_percent: count as f64 * 100.0 / total as f64,
+    }
+}
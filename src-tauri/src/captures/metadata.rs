@@ -0,0 +1,206 @@
+// Describes a screenshot inside the file itself: when it was taken, on which
+// monitor, in which app and the user's note travel with it as PNG text chunks,
+// so a capture copied out of the library still says what it is.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+// Keywords of the text chunks; the first two are registered PNG keywords
+const KEY_CREATED: &str = "Creation Time";
+const KEY_SOFTWARE: &str = "Software";
+const KEY_NOTE: &str = "Comment";
+const KEY_MONITOR: &str = "Pluely Monitor";
+const KEY_APP: &str = "Pluely App";
+const KEY_TIMESTAMP: &str = "Pluely Timestamp";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureMetadata {
+    // Unix milliseconds
+    pub created_at: Option<i64>,
+    pub monitor: Option<String>,
+    // The app the user was in, when focus tracking is on
+    pub app: Option<String>,
+    pub note: Option<String>,
+    // Whatever wrote the file
+    pub software: Option<String>,
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+    chunk
+}
+
+// tEXt for plain ASCII, iTXt (UTF-8) for anything else
+fn text_chunk(keyword: &str, text: &str) -> Vec<u8> {
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+    if text.is_ascii() {
+        data.extend_from_slice(text.as_bytes());
+        chunk(b"tEXt", &data)
+    } else {
+        // Uncompressed, no language tag or translated keyword
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(text.as_bytes());
+        chunk(b"iTXt", &data)
+    }
+}
+
+// (year, month, day, seconds into the day, days since 1970-01-01) in UTC
+fn civil_time(unix_ms: i64) -> (i64, i64, i64, i64, i64) {
+    let secs = unix_ms.div_euclid(1000);
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, rem, days)
+}
+
+// RFC 1123 date, the format the PNG spec suggests for "Creation Time"
+pub fn http_date(unix_ms: i64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (year, month, day, rem, days) = civil_time(unix_ms);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        DAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// ISO 8601 date and time in UTC, such as 2024-05-01T09:30:00Z
+pub fn iso_date(unix_ms: i64) -> String {
+    let (year, month, day, rem, _) = civil_time(unix_ms);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Adds `metadata` to a PNG as text chunks right after its header; anything
+/// that isn't a PNG is returned as it was
+pub fn embed_metadata(bytes: &[u8], metadata: &CaptureMetadata) -> Vec<u8> {
+    // Signature plus the IHDR chunk, which has to stay first
+    let header_end = PNG_SIGNATURE.len() + 25;
+    if !bytes.starts_with(PNG_SIGNATURE) || bytes.len() < header_end {
+        return bytes.to_vec();
+    }
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    if let Some(created_at) = metadata.created_at {
+        fields.push((KEY_CREATED, http_date(created_at)));
+        fields.push((KEY_TIMESTAMP, created_at.to_string()));
+    }
+    for (key, value) in [
+        (KEY_SOFTWARE, metadata.software.clone()),
+        (KEY_MONITOR, metadata.monitor.clone()),
+        (KEY_APP, metadata.app.clone()),
+        (KEY_NOTE, metadata.note.clone()),
+    ] {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            fields.push((key, value));
+        }
+    }
+
+    let mut out = bytes[..header_end].to_vec();
+    for (key, value) in fields {
+        out.extend(text_chunk(key, &value));
+    }
+    out.extend_from_slice(&bytes[header_end..]);
+    out
+}
+
+/// Reads back what `embed_metadata` wrote
+pub fn read_metadata(bytes: &[u8]) -> Result<CaptureMetadata, String> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err("Capture metadata is only stored in PNG files".to_string());
+    }
+    let mut metadata = CaptureMetadata::default();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 8 <= bytes.len() {
+        let length =
+            u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+                as usize;
+        let kind = &bytes[pos + 4..pos + 8];
+        let Some(data) = bytes.get(pos + 8..pos + 8 + length) else {
+            break;
+        };
+        // Text chunks all come before the image data
+        if kind == b"IDAT" || kind == b"IEND" {
+            break;
+        }
+        let text = match kind {
+            b"tEXt" => data
+                .iter()
+                .position(|b| *b == 0)
+                // Latin-1, which maps byte for byte onto the first code points
+                .map(|at| {
+                    let text = data[at + 1..]
+                        .iter()
+                        .map(|b| *b as char)
+                        .collect::<String>();
+                    (&data[..at], text)
+                }),
+            b"iTXt" => data.iter().position(|b| *b == 0).and_then(|at| {
+                // Compressed text isn't something Pluely writes
+                if data.get(at + 1) != Some(&0) {
+                    return None;
+                }
+                let rest = data.get(at + 3..)?;
+                let language_end = rest.iter().position(|b| *b == 0)?;
+                let rest = &rest[language_end + 1..];
+                let translated_end = rest.iter().position(|b| *b == 0)?;
+                let text = String::from_utf8_lossy(&rest[translated_end + 1..]).to_string();
+                Some((&data[..at], text))
+            }),
+            _ => None,
+        };
+        if let Some((keyword, text)) = text {
+            match String::from_utf8_lossy(keyword).as_ref() {
+                KEY_TIMESTAMP => metadata.created_at = text.parse().ok(),
+                KEY_SOFTWARE => metadata.software = Some(text),
+                KEY_MONITOR => metadata.monitor = Some(text),
+                KEY_APP => metadata.app = Some(text),
+                KEY_NOTE => metadata.note = Some(text),
+                _ => {}
+            }
+        }
+        pos += 12 + length;
+    }
+    Ok(metadata)
+}
@@ -0,0 +1,199 @@
+// Pluely capture library: screenshots and recordings saved under the app data
+// directory, trimmed by a retention policy so they don't fill the disk.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+mod commands;
+mod diff;
+mod metadata;
+mod thumbnails;
+mod watermark;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+pub use metadata::{http_date, iso_date};
+pub use thumbnails::{thumbnail_of, thumbnail_of_base64, ThumbnailSettings};
+pub use watermark::WatermarkSettings;
+
+const CAPTURES_DIR: &str = "captures";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    // Each limit is off when None
+    pub max_age_days: Option<u32>,
+    pub max_total_mb: Option<u64>,
+    pub max_count: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(30),
+            max_total_mb: Some(1_024),
+            max_count: None,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_age_days == Some(0) || self.max_total_mb == Some(0) || self.max_count == Some(0)
+        {
+            return Err("Invalid retention limit: must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureFile {
+    pub path: String,
+    // "screenshot" or "recording", from the file name
+    pub kind: String,
+    pub bytes: u64,
+    // Unix milliseconds
+    pub created_at: i64,
+    // Base64 JPEG preview of screenshots; only filled in for listings
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+pub fn captures_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::workspaces::data_dir(app)?.join(CAPTURES_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create captures directory: {}", e))?;
+    Ok(dir)
+}
+
+// Resolves a capture id (its file name) to its file, refusing anything outside
+// the library
+pub fn capture_path(app: &AppHandle, capture_id: &str) -> Result<PathBuf, String> {
+    if capture_id.is_empty() || capture_id.starts_with('.') || capture_id.contains(['/', '\\']) {
+        return Err(format!("Invalid capture id: {}", capture_id));
+    }
+    let path = captures_dir(app)?.join(capture_id);
+    if !path.is_file() {
+        return Err(format!("Capture not found: {}", capture_id));
+    }
+    Ok(path)
+}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// Extension for the saved bytes; sniffed so callers only pass base64
+pub fn extension_for(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "png"
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "jpg"
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WAVE") {
+        "wav"
+    } else {
+        "bin"
+    }
+}
+
+// Every file in the library, oldest first
+pub fn list_files(app: &AppHandle) -> Result<Vec<CaptureFile>, String> {
+    let entries = std::fs::read_dir(captures_dir(app)?)
+        .map_err(|e| format!("Failed to read captures directory: {}", e))?;
+    let mut files: Vec<CaptureFile> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Half-written files from an interrupted save or conversion
+            if name.ends_with(".tmp") || name.ends_with(".pluely-tmp") {
+                return None;
+            }
+            Some(CaptureFile {
+                path: entry.path().to_string_lossy().to_string(),
+                kind: name.split('-').next().unwrap_or("screenshot").to_string(),
+                bytes: metadata.len(),
+                created_at: metadata
+                    .created()
+                    .or_else(|_| metadata.modified())
+                    .map(millis)
+                    .unwrap_or_default(),
+                thumbnail: None,
+            })
+        })
+        .collect();
+    files.sort_by_key(|file| file.created_at);
+    Ok(files)
+}
+
+// Files `policy` says should go, oldest first
+pub fn expired(files: &[CaptureFile], policy: &RetentionPolicy, now: i64) -> Vec<CaptureFile> {
+    let max_age_ms = policy
+        .max_age_days
+        .map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60).as_millis() as i64);
+    let max_bytes = policy.max_total_mb.map(|mb| mb * 1024 * 1024);
+
+    let mut count = files.len();
+    let mut total: u64 = files.iter().map(|file| file.bytes).sum();
+    let mut removed = Vec::new();
+    for file in files {
+        let too_old = max_age_ms.is_some_and(|max| now - file.created_at > max);
+        let too_many = policy.max_count.is_some_and(|max| count > max as usize);
+        let too_big = max_bytes.is_some_and(|max| total > max);
+        if !(too_old || too_many || too_big) {
+            // Sorted oldest first, so the rest are within every limit
+            break;
+        }
+        count -= 1;
+        total -= file.bytes;
+        removed.push(file.clone());
+    }
+    removed
+}
+
+// Deletes a library file and any message references to it
+pub async fn remove_file(app: &AppHandle, path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to delete capture: {}", e)),
+    }
+    thumbnails::remove_thumbnail(path);
+    let pool = crate::storage::pool(app).await?;
+    sqlx::query("DELETE FROM message_captures WHERE path = ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete capture references: {}", e))?;
+    Ok(())
+}
+
+// Size of a file or of everything under a directory
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return 0;
+    };
+    if metadata.is_file() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,134 @@
+// Small JPEG previews of library images, kept next to them in a hidden
+// directory so history grids don't have to decode full-size PNGs.
+use base64::{engine::general_purpose, Engine as _};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::warn;
+
+// Hidden, so the library listing and capture ids never reach it
+const THUMBNAILS_DIR: &str = ".thumbnails";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThumbnailSettings {
+    // Longest edge in pixels
+    pub size: u32,
+    // JPEG quality, 1-100
+    pub quality: u8,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            size: 320,
+            quality: 75,
+        }
+    }
+}
+
+impl ThumbnailSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(64..=1024).contains(&self.size) {
+            return Err("Invalid thumbnail size: must be 64-1024 px".to_string());
+        }
+        if !(1..=100).contains(&self.quality) {
+            return Err("Invalid thumbnail quality: must be 1-100".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn settings(app: &AppHandle) -> ThumbnailSettings {
+    crate::settings::current_settings(app)
+        .map(|settings| settings.thumbnails)
+        .unwrap_or_default()
+}
+
+fn thumbnail_path(capture: &Path) -> Option<PathBuf> {
+    let name = capture.file_name()?.to_string_lossy();
+    Some(
+        capture
+            .parent()?
+            .join(THUMBNAILS_DIR)
+            .join(format!("{}.jpg", name)),
+    )
+}
+
+fn encode(image: &RgbaImage, settings: &ThumbnailSettings) -> Result<Vec<u8>, String> {
+    let thumbnail = DynamicImage::ImageRgba8(image.clone())
+        .resize(settings.size, settings.size, FilterType::Triangle)
+        .to_rgb8();
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(thumbnail)
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, settings.quality))
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(bytes)
+}
+
+/// Base64 JPEG thumbnail of an image that isn't in the library, such as a
+/// capture about to be emitted
+pub fn thumbnail_of(app: &AppHandle, image: &RgbaImage) -> Option<String> {
+    match encode(image, &settings(app)) {
+        Ok(bytes) => Some(general_purpose::STANDARD.encode(bytes)),
+        Err(e) => {
+            warn!("{}", e);
+            None
+        }
+    }
+}
+
+/// Same for a base64 PNG or JPEG
+pub fn thumbnail_of_base64(app: &AppHandle, image_base64: &str) -> Option<String> {
+    let bytes = general_purpose::STANDARD.decode(image_base64).ok()?;
+    let image = image::load_from_memory(&bytes).ok()?.to_rgba8();
+    thumbnail_of(app, &image)
+}
+
+/// Writes the thumbnail of a library image; other captures have none
+pub fn create_thumbnail(app: &AppHandle, capture: &Path, bytes: &[u8]) -> Result<(), String> {
+    if !matches!(super::extension_for(bytes), "png" | "jpg") {
+        return Ok(());
+    }
+    let path = thumbnail_path(capture).ok_or("Invalid capture path")?;
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("Failed to decode capture: {}", e))?
+        .to_rgba8();
+    let encoded = encode(&image, &settings(app))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
+    }
+    std::fs::write(&path, encoded).map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+    crate::storage::seal_file(&path)
+}
+
+/// The stored thumbnail of a library capture as base64, made now for captures
+/// saved before thumbnails existed
+pub fn load_thumbnail(app: &AppHandle, capture: &Path) -> Option<String> {
+    let extension = capture.extension()?.to_string_lossy();
+    if !matches!(extension.as_ref(), "png" | "jpg") {
+        return None;
+    }
+    let path = thumbnail_path(capture)?;
+    if !path.exists() {
+        let bytes = crate::storage::read_file(capture).ok()?;
+        if let Err(e) = create_thumbnail(app, capture, &bytes) {
+            warn!("{}", e);
+            return None;
+        }
+    }
+    let bytes = crate::storage::read_file(&path).ok()?;
+    Some(general_purpose::STANDARD.encode(bytes))
+}
+
+/// Deletes a capture's thumbnail, if it has one
+pub fn remove_thumbnail(capture: &Path) {
+    if let Some(path) = thumbnail_path(capture) {
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,254 @@
+// Optional stamp on screenshots before they are saved: a line of text (a
+// ticket ID, a team name) and/or a logo image in a corner, at a set opacity.
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use image::imageops::FilterType;
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+// Tried in order when no font is configured
+const SYSTEM_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WatermarkSettings {
+    pub enabled: bool,
+    pub text: Option<String>,
+    // PNG or JPEG drawn next to the text, such as a logo
+    pub image_path: Option<String>,
+    pub position: WatermarkPosition,
+    // 0.0 to 1.0
+    pub opacity: f64,
+    // Text height as a share of the screenshot's height
+    pub text_scale: f64,
+    // Image width as a share of the screenshot's width
+    pub image_scale: f64,
+    // TrueType or OpenType font; None picks a system sans-serif
+    pub font_path: Option<String>,
+}
+
+impl Default for WatermarkSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            text: None,
+            image_path: None,
+            position: WatermarkPosition::default(),
+            opacity: 0.6,
+            text_scale: 0.03,
+            image_scale: 0.12,
+            font_path: None,
+        }
+    }
+}
+
+impl WatermarkSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err("Invalid watermark opacity: must be 0.0-1.0".to_string());
+        }
+        if !(0.005..=0.5).contains(&self.text_scale) || !(0.01..=1.0).contains(&self.image_scale) {
+            return Err("Invalid watermark size".to_string());
+        }
+        let has_text = self.text.as_deref().is_some_and(|t| !t.trim().is_empty());
+        if self.enabled && !has_text && self.image_path.is_none() {
+            return Err("Watermark needs a text or an image".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn load_font(settings: &WatermarkSettings) -> Result<FontVec, String> {
+    let candidates: Vec<PathBuf> = match &settings.font_path {
+        Some(path) => vec![PathBuf::from(path)],
+        None => SYSTEM_FONTS.iter().map(PathBuf::from).collect(),
+    };
+    let bytes = candidates
+        .iter()
+        .find_map(|path| std::fs::read(path).ok())
+        .ok_or("No font found for the watermark text; set one in the watermark settings")?;
+    FontVec::try_from_vec(bytes).map_err(|e| format!("Failed to load watermark font: {}", e))
+}
+
+// Blends `color` over the pixel at `coverage` (0.0 to 1.0)
+fn blend(image: &mut RgbaImage, x: i64, y: i64, color: [u8; 3], coverage: f32) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    for (channel, value) in pixel.0.iter_mut().zip(color) {
+        *channel = (*channel as f32 * (1.0 - coverage) + value as f32 * coverage) as u8;
+    }
+}
+
+// The text rendered on a transparent canvas as white on a soft shadow
+fn render_text(text: &str, font: &FontVec, height: f32) -> RgbaImage {
+    let scaled = font.as_scaled(PxScale::from(height));
+    let mut width = 0.0f32;
+    let mut previous = None;
+    let mut glyphs = Vec::new();
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            width += scaled.kern(previous, id);
+        }
+        glyphs.push(id.with_scale_and_position(height, ab_glyph::point(width, scaled.ascent())));
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    let shadow = (height / 16.0).ceil().max(1.0) as i64;
+    let mut canvas = RgbaImage::new(
+        width.ceil() as u32 + shadow as u32,
+        scaled.height().ceil() as u32 + shadow as u32,
+    );
+    // Shadow first, the text over it
+    for (offset, color) in [(shadow, 0.0), (0, 255.0)] {
+        for glyph in &glyphs {
+            let Some(outline) = font.outline_glyph(glyph.clone()) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|x, y, coverage| {
+                let px = bounds.min.x as i64 + x as i64 + offset;
+                let py = bounds.min.y as i64 + y as i64 + offset;
+                if px < 0 || py < 0 || px >= canvas.width() as i64 || py >= canvas.height() as i64 {
+                    return;
+                }
+                let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+                // Source-over onto what is already drawn
+                let below = pixel[3] as f32 / 255.0;
+                let alpha = coverage + below * (1.0 - coverage);
+                if alpha <= 0.0 {
+                    return;
+                }
+                let mix = |channel: u8| {
+                    ((color * coverage + channel as f32 * below * (1.0 - coverage)) / alpha) as u8
+                };
+                *pixel = Rgba([
+                    mix(pixel[0]),
+                    mix(pixel[1]),
+                    mix(pixel[2]),
+                    (alpha * 255.0) as u8,
+                ]);
+            });
+        }
+    }
+    canvas
+}
+
+fn load_image(path: &str, width: u32) -> Result<RgbaImage, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read watermark image: {}", e))?;
+    let image = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read watermark image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode watermark image: {}", e))?;
+    let height = (image.height() as u64 * width as u64 / image.width().max(1) as u64).max(1);
+    Ok(image
+        .resize_exact(width.max(1), height as u32, FilterType::Triangle)
+        .to_rgba8())
+}
+
+// Draws `stamp` onto `image` at (x, y), scaled by the watermark opacity
+fn overlay(image: &mut RgbaImage, stamp: &RgbaImage, x: i64, y: i64, opacity: f32) {
+    for (sx, sy, pixel) in stamp.enumerate_pixels() {
+        let coverage = pixel[3] as f32 / 255.0 * opacity;
+        if coverage > 0.0 {
+            let color = [pixel[0], pixel[1], pixel[2]];
+            blend(image, x + sx as i64, y + sy as i64, color, coverage);
+        }
+    }
+}
+
+/// Stamps `image` as `settings` describe; unchanged when the watermark is off
+pub fn apply_watermark(image: &mut RgbaImage, settings: &WatermarkSettings) -> Result<(), String> {
+    if !settings.enabled {
+        return Ok(());
+    }
+    let (width, height) = image.dimensions();
+    let margin = (width.min(height) as f32 * 0.02).round() as i64;
+    let gap = margin;
+
+    // The image first, then the text beside it
+    let mut stamps = Vec::new();
+    if let Some(path) = &settings.image_path {
+        stamps.push(load_image(
+            path,
+            (width as f64 * settings.image_scale) as u32,
+        )?);
+    }
+    if let Some(text) = settings.text.as_deref().filter(|t| !t.trim().is_empty()) {
+        let font = load_font(settings)?;
+        let text_height = (height as f64 * settings.text_scale).max(8.0) as f32;
+        stamps.push(render_text(text.trim(), &font, text_height));
+    }
+    let total_width: i64 = stamps.iter().map(|s| s.width() as i64).sum::<i64>()
+        + gap * (stamps.len() as i64 - 1).max(0);
+    let total_height = stamps.iter().map(|s| s.height() as i64).max().unwrap_or(0);
+
+    let (left, top) = match settings.position {
+        WatermarkPosition::TopLeft => (margin, margin),
+        WatermarkPosition::TopRight => (width as i64 - total_width - margin, margin),
+        WatermarkPosition::BottomLeft => (margin, height as i64 - total_height - margin),
+        WatermarkPosition::BottomRight => (
+            width as i64 - total_width - margin,
+            height as i64 - total_height - margin,
+        ),
+        WatermarkPosition::Center => (
+            (width as i64 - total_width) / 2,
+            (height as i64 - total_height) / 2,
+        ),
+    };
+    let mut x = left;
+    for stamp in &stamps {
+        // Vertically centred on the tallest stamp
+        let y = top + (total_height - stamp.height() as i64) / 2;
+        overlay(image, stamp, x, y, settings.opacity as f32);
+        x += stamp.width() as i64 + gap;
+    }
+    Ok(())
+}
+
+/// Watermarks an encoded PNG or JPEG with the current settings, returning a
+/// PNG; other files and everything while the watermark is off pass through
+pub fn watermark_bytes(app: &AppHandle, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let settings = crate::settings::current_settings(app)
+        .map(|settings| settings.watermark)
+        .unwrap_or_default();
+    if !settings.enabled || !matches!(super::extension_for(&bytes), "png" | "jpg") {
+        return Ok(bytes);
+    }
+    let mut image = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to decode capture: {}", e))?
+        .to_rgba8();
+    apply_watermark(&mut image, &settings)?;
+    let mut out = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut out), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode capture: {}", e))?;
+    Ok(out)
+}
@@ -0,0 +1,278 @@
+// Pluely headless CLI. `pluely capture` and `pluely ocr` run the capture and
+// OCR pipeline without opening any windows and print the result to stdout, so
+// they can be used from scripts and cron jobs.
+use base64::{engine::general_purpose, Engine as _};
+use image::{DynamicImage, ImageFormat};
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use xcap::Monitor;
+
+const USAGE: &str = "Usage:
+  pluely capture [--area X,Y,WIDTH,HEIGHT] [--monitor N] [--out FILE]
+  pluely ocr [--file FILE | --area X,Y,WIDTH,HEIGHT] [--monitor N]
+  pluely monitors
+
+Monitors are numbered from 1 as listed by `pluely monitors`; the primary
+monitor is used when --monitor is omitted. Without --out, `capture` prints
+the PNG as base64.";
+
+// Exit codes
+const EXIT_OK: i32 = 0;
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Capture {
+        area: Option<Area>,
+        monitor: Option<usize>,
+        out: Option<PathBuf>,
+    },
+    Ocr {
+        file: Option<PathBuf>,
+        area: Option<Area>,
+        monitor: Option<usize>,
+    },
+    Monitors,
+    Help,
+}
+
+fn parse_area(value: &str) -> Result<Area, String> {
+    let parts: Vec<u32> = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid area '{}': expected X,Y,WIDTH,HEIGHT", value))?;
+    match parts[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Area {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => Err(format!(
+            "Invalid area '{}': expected X,Y,WIDTH,HEIGHT with a non-zero size",
+            value
+        )),
+    }
+}
+
+fn parse_monitor(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n),
+        _ => Err(format!("Invalid monitor '{}': expected 1 or more", value)),
+    }
+}
+
+/// The CLI command in `args` (without the program name), or None when the
+/// app should start normally. Errors are usage errors.
+pub fn parse_args(args: &[String]) -> Option<Result<CliCommand, String>> {
+    let (command, rest) = args.split_first()?;
+    let command = command.as_str();
+    if !matches!(
+        command,
+        "capture" | "ocr" | "monitors" | "help" | "--help" | "-h"
+    ) {
+        return None;
+    }
+
+    let parse = || -> Result<CliCommand, String> {
+        let mut area = None;
+        let mut monitor = None;
+        let mut out = None;
+        let mut file = None;
+        let mut rest = rest.iter();
+        while let Some(flag) = rest.next() {
+            let mut value = || {
+                rest.next()
+                    .cloned()
+                    .ok_or(format!("{} needs a value", flag))
+            };
+            match flag.as_str() {
+                "--area" => area = Some(parse_area(&value()?)?),
+                "--monitor" => monitor = Some(parse_monitor(&value()?)?),
+                "--out" if command == "capture" => out = Some(PathBuf::from(value()?)),
+                "--file" if command == "ocr" => file = Some(PathBuf::from(value()?)),
+                "--help" | "-h" => return Ok(CliCommand::Help),
+                other => return Err(format!("Unknown option for {}: {}", command, other)),
+            }
+        }
+
+        match command {
+            "capture" => Ok(CliCommand::Capture { area, monitor, out }),
+            "ocr" if file.is_some() && (area.is_some() || monitor.is_some()) => {
+                Err("--file cannot be combined with --area or --monitor".to_string())
+            }
+            "ocr" => Ok(CliCommand::Ocr {
+                file,
+                area,
+                monitor,
+            }),
+            "monitors" if area.is_none() && monitor.is_none() => Ok(CliCommand::Monitors),
+            "monitors" => Err("monitors takes no options".to_string()),
+            _ => Ok(CliCommand::Help),
+        }
+    };
+    Some(parse())
+}
+
+// Monitor `number` (from 1), or the primary one
+fn capture_monitor(number: Option<usize>) -> Result<DynamicImage, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    if monitors.is_empty() {
+        return Err("No monitors found".to_string());
+    }
+    let index = match number {
+        Some(number) if number > monitors.len() => {
+            return Err(format!(
+                "Monitor {} not found: {} connected",
+                number,
+                monitors.len()
+            ))
+        }
+        Some(number) => number - 1,
+        None => monitors.iter().position(|m| m.is_primary()).unwrap_or(0),
+    };
+    let mut image = monitors[index]
+        .capture_image()
+        .map_err(|e| format!("Failed to capture image: {}", e))?;
+    // The user's rules, loaded in `run`
+    crate::sensitive::enforce(&monitors[index], &mut image)?;
+    Ok(DynamicImage::ImageRgba8(image))
+}
+
+// Regions running off the edge are clipped to the monitor
+fn crop(image: DynamicImage, area: Option<Area>) -> Result<DynamicImage, String> {
+    let Some(area) = area else {
+        return Ok(image);
+    };
+    if area.x >= image.width() || area.y >= image.height() {
+        return Err(format!(
+            "Area starts outside the {}x{} monitor",
+            image.width(),
+            image.height()
+        ));
+    }
+    Ok(image.crop_imm(
+        area.x,
+        area.y,
+        area.width.min(image.width() - area.x),
+        area.height.min(image.height() - area.y),
+    ))
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+    Ok(png)
+}
+
+fn capture(area: Option<Area>, monitor: Option<usize>) -> Result<Vec<u8>, String> {
+    encode_png(&crop(capture_monitor(monitor)?, area)?)
+}
+
+fn list_monitors() -> Result<String, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            format!(
+                "{}\t{}x{}\t{}{}",
+                index + 1,
+                monitor.width(),
+                monitor.height(),
+                monitor.name(),
+                if monitor.is_primary() {
+                    " (primary)"
+                } else {
+                    ""
+                }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+async fn execute(command: CliCommand) -> Result<String, String> {
+    match command {
+        CliCommand::Capture { area, monitor, out } => {
+            let png = capture(area, monitor)?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, png)
+                        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                    Ok(path.display().to_string())
+                }
+                None => Ok(general_purpose::STANDARD.encode(png)),
+            }
+        }
+        CliCommand::Ocr {
+            file,
+            area,
+            monitor,
+        } => {
+            let png = match file {
+                // Decoded and re-encoded so any image format works
+                Some(path) => {
+                    let image = image::open(&path)
+                        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                    encode_png(&image)?
+                }
+                None => capture(area, monitor)?,
+            };
+            crate::llm::ocr_image(general_purpose::STANDARD.encode(png)).await
+        }
+        CliCommand::Monitors => list_monitors(),
+        CliCommand::Help => Ok(USAGE.to_string()),
+    }
+}
+
+// Release builds on Windows have no console of their own; print to the one
+// the command was started from
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Runs a CLI command and returns the process exit code
+pub fn run(command: Result<CliCommand, String>) -> i32 {
+    #[cfg(target_os = "windows")]
+    attach_console();
+
+    let command = match command {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    if let Err(e) = crate::settings::load_headless() {
+        eprintln!("Using the default sensitive app rules: {}", e);
+    }
+    match tauri::async_runtime::block_on(execute(command)) {
+        Ok(output) => {
+            let mut stdout = std::io::stdout();
+            let _ = writeln!(stdout, "{}", output);
+            let _ = stdout.flush();
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            EXIT_FAILED
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// Pluely clipboard ingestion: images copied from other tools become
+// attachments just like screen captures, and an opt-in watcher tells the UI
+// when something new was copied.
+use base64::Engine;
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tracing::warn;
+
+// RGBA pixels from the clipboard, encoded the way captures are
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<String, String> {
+    let mut png_buffer = Vec::new();
+    PngEncoder::new(&mut png_buffer)
+        .write_image(rgba, width, height, ColorType::Rgba8.into())
+        .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png_buffer))
+}
+
+fn clipboard_image_png() -> Result<String, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => {
+            return Err("The clipboard has no image".to_string())
+        }
+        Err(e) => return Err(format!("Failed to read clipboard image: {}", e)),
+    };
+    if image.width == 0 || image.height == 0 {
+        return Err("The clipboard image is empty".to_string());
+    }
+    encode_png(image.width as u32, image.height as u32, &image.bytes)
+}
+
+/// Tauri command returning the clipboard image as a base64 PNG, the same
+/// shape as `capture_to_base64`
+#[tauri::command]
+#[specta::specta]
+pub async fn read_clipboard_image() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(clipboard_image_png)
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+#[derive(Default)]
+pub struct ClipboardState {
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardSettings {
+    // Off unless the user opts in
+    pub watch: bool,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            poll_interval_ms: 500,
+        }
+    }
+}
+
+// What changed, never the content itself; the UI reads that on request
+#[derive(Debug, Clone, Serialize, PartialEq, Type, Event)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ClipboardChanged {
+    Text {
+        chars: usize,
+        bytes: usize,
+        lines: usize,
+    },
+    Image {
+        width: u32,
+        height: u32,
+        bytes: usize,
+    },
+}
+
+// Every 97th byte is enough to tell images apart without hashing megabytes
+pub(crate) fn image_fingerprint(width: usize, height: usize, rgba: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(width.to_le_bytes());
+    hasher.update(height.to_le_bytes());
+    hasher.update(rgba.iter().step_by(97).copied().collect::<Vec<u8>>());
+    hasher.finalize().to_vec()
+}
+
+// Cheap fingerprint of the clipboard, so unchanged content isn't reported
+fn snapshot(clipboard: &mut arboard::Clipboard) -> Option<(Vec<u8>, ClipboardChanged)> {
+    if let Ok(text) = clipboard.get_text() {
+        if !text.is_empty() {
+            let digest = Sha256::digest(text.as_bytes()).to_vec();
+            return Some((
+                digest,
+                ClipboardChanged::Text {
+                    chars: text.chars().count(),
+                    bytes: text.len(),
+                    lines: text.lines().count(),
+                },
+            ));
+        }
+    }
+    let image = clipboard.get_image().ok()?;
+    Some((
+        image_fingerprint(image.width, image.height, &image.bytes),
+        ClipboardChanged::Image {
+            width: image.width as u32,
+            height: image.height as u32,
+            bytes: image.bytes.len(),
+        },
+    ))
+}
+
+fn watch(app: AppHandle, interval: Duration, stop: Arc<AtomicBool>) {
+    let mut clipboard = match arboard::Clipboard::new() {
+        Ok(clipboard) => clipboard,
+        Err(e) => {
+            warn!("Failed to open clipboard: {}", e);
+            return;
+        }
+    };
+    // Whatever was copied before watching started isn't news
+    let mut last = snapshot(&mut clipboard).map(|(digest, _)| digest);
+    while !stop.load(Ordering::SeqCst) {
+        std::thread::sleep(interval);
+        // Copies made while paused are picked up afterwards if still there
+        if crate::power::background_paused() {
+            continue;
+        }
+        let Some((digest, change)) = snapshot(&mut clipboard) else {
+            continue;
+        };
+        if last.as_ref() == Some(&digest) {
+            continue;
+        }
+        last = Some(digest);
+        if let Err(e) = app.emit("clipboard-changed", change) {
+            warn!("Failed to emit clipboard change: {}", e);
+        }
+    }
+}
+
+// Starts or stops the watcher to match `settings`
+pub fn apply_clipboard_settings(
+    app: &AppHandle,
+    settings: &ClipboardSettings,
+) -> Result<(), String> {
+    if !(100..=10_000).contains(&settings.poll_interval_ms) {
+        return Err("Invalid clipboard poll interval: must be 100-10000 ms".to_string());
+    }
+    let state = app.state::<ClipboardState>();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Restarted so a new interval takes effect
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if settings.watch {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let interval = Duration::from_millis(settings.poll_interval_ms);
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, interval, flag));
+        *watcher = Some(stop);
+    }
+    Ok(())
+}
+
+/// Tauri command to check whether the clipboard watcher is running
+#[tauri::command]
+#[specta::specta]
+pub fn is_clipboard_watcher_running(app: AppHandle) -> Result<bool, String> {
+    let state = app.state::<ClipboardState>();
+    let watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(watcher.is_some())
+}
+
+/// Tauri command returning the clipboard text, for "ask about what you
+/// just copied" after a `clipboard-changed` event
+#[tauri::command]
+#[specta::specta]
+pub fn read_clipboard_text() -> Result<String, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    match clipboard.get_text() {
+        Ok(text) => Ok(text),
+        Err(arboard::Error::ContentNotAvailable) => Err("The clipboard has no text".to_string()),
+        Err(e) => Err(format!("Failed to read clipboard: {}", e)),
+    }
+}
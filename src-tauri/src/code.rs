@@ -0,0 +1,273 @@
+// Pluely code extraction. Code on the screen is read as text rather than sent
+// as an image: lines that look like code are grouped into blocks, OCR'd with
+// settings suited to monospace text, re-indented from the words' positions and
+// tagged with a guessed language, so the model gets the code itself.
+use serde::Serialize;
+use specta::Type;
+
+use crate::capture::SelectionCoords;
+use crate::regions::{ocr_boxes, OcrBox};
+
+// A single uniform block of text, keeping the spaces between words
+const OCR_OPTIONS: &[&str] = &["--psm", "6", "-c", "preserve_interword_spaces=1"];
+// A vertical gap of more than this many line heights starts a new block
+const BLOCK_GAP: f64 = 1.8;
+// Share of a block's lines that have to look like code
+const MIN_CODE_SHARE: f64 = 0.4;
+// Characters that are rare in prose and common in code
+const CODE_SYMBOLS: &[char] = &['{', '}', '(', ')', ';', '=', '<', '>', '[', ']', '#', '_'];
+
+// Telltale tokens of each language, tried as substrings of the text
+const LANGUAGES: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "fn ", "let mut ", "impl ", "pub fn", "::", "-> ", "&self", "match ",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "def ", "import ", "self.", "elif ", "None", "print(", "__init__",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            "interface ",
+            ": string",
+            ": number",
+            "=> ",
+            "export ",
+            "import {",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            "const ",
+            "function ",
+            "=> ",
+            "===",
+            "console.log",
+            "require(",
+        ],
+    ),
+    ("go", &["func ", "package ", ":= ", "fmt.", "chan "]),
+    (
+        "java",
+        &[
+            "public class",
+            "System.out",
+            "private ",
+            "void ",
+            "@Override",
+        ],
+    ),
+    ("cpp", &["#include", "std::", "int main", "->", "nullptr"]),
+    (
+        "sql",
+        &[
+            "SELECT ",
+            "FROM ",
+            "WHERE ",
+            "JOIN ",
+            "INSERT INTO",
+            "GROUP BY",
+        ],
+    ),
+    ("shell", &["#!/bin", "echo ", "sudo ", "$(", "&& "]),
+    ("html", &["</", "<div", "class=", "<html", "href="]),
+];
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock {
+    // None when nothing gave the language away
+    pub language: Option<String>,
+    pub text: String,
+    // Where the block is, in image pixels of the region
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// The words of one OCR line, left to right
+struct Line {
+    words: Vec<OcrBox>,
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+}
+
+impl Line {
+    fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn looks_like_code(&self) -> bool {
+        let text = self.text();
+        let symbols = text.chars().filter(|c| CODE_SYMBOLS.contains(c)).count();
+        symbols >= 2
+            || text.ends_with([';', '{', '}', ':'])
+            || LANGUAGES
+                .iter()
+                .any(|(_, tokens)| tokens.iter().any(|token| text.starts_with(token)))
+    }
+}
+
+fn group_lines(boxes: Vec<OcrBox>) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    for word in boxes
+        .into_iter()
+        .filter(|ocr_box| ocr_box.level == 5 && !ocr_box.text.is_empty())
+    {
+        match lines.last_mut() {
+            Some(line) if line.words[0].line == word.line => {
+                line.left = line.left.min(word.x);
+                line.top = line.top.min(word.y);
+                line.right = line.right.max(word.x + word.width);
+                line.bottom = line.bottom.max(word.y + word.height);
+                line.words.push(word);
+            }
+            _ => lines.push(Line {
+                left: word.x,
+                top: word.y,
+                right: word.x + word.width,
+                bottom: word.y + word.height,
+                words: vec![word],
+            }),
+        }
+    }
+    for line in &mut lines {
+        line.words.sort_by_key(|word| word.x);
+    }
+    lines
+}
+
+// Lines separated by small gaps, top to bottom
+fn group_blocks(lines: Vec<Line>) -> Vec<Vec<Line>> {
+    let mut heights: Vec<u32> = lines.iter().map(|line| line.bottom - line.top).collect();
+    heights.sort_unstable();
+    let line_height = heights.get(heights.len() / 2).copied().unwrap_or(0) as f64;
+
+    let mut blocks: Vec<Vec<Line>> = Vec::new();
+    for line in lines {
+        let near = |previous: &Line| {
+            line.top.saturating_sub(previous.bottom) as f64 <= line_height * BLOCK_GAP
+        };
+        match blocks.last_mut() {
+            Some(block) if block.last().is_some_and(near) => block.push(line),
+            _ => blocks.push(vec![line]),
+        }
+    }
+    blocks
+}
+
+// Rebuilds the block's text on a grid of the average character width, which
+// keeps indentation and aligned columns as they were on screen
+fn layout(block: &[Line]) -> String {
+    let (chars, pixels) =
+        block
+            .iter()
+            .flat_map(|line| &line.words)
+            .fold((0u64, 0u64), |(chars, pixels), word| {
+                (
+                    chars + word.text.chars().count() as u64,
+                    pixels + word.width as u64,
+                )
+            });
+    let char_width = (pixels as f64 / chars.max(1) as f64).max(1.0);
+    let left = block.iter().map(|line| line.left).min().unwrap_or(0);
+
+    block
+        .iter()
+        .map(|line| {
+            let mut text = String::new();
+            for word in &line.words {
+                let column = ((word.x - left) as f64 / char_width).round() as usize;
+                let length = text.chars().count();
+                // At least one space between words, however they were measured
+                let padding = if length == 0 {
+                    column
+                } else {
+                    column.saturating_sub(length).max(1)
+                };
+                text.push_str(&" ".repeat(padding));
+                text.push_str(&word.text);
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Best guess at the language of `code`: the one with the most tokens in it
+fn guess_language(code: &str) -> Option<String> {
+    let mut best: Option<(&str, usize)> = None;
+    for (language, tokens) in LANGUAGES {
+        let hits: usize = tokens.iter().map(|token| code.matches(token).count()).sum();
+        // The first listed wins a tie, e.g. TypeScript over JavaScript
+        if hits > best.map_or(0, |(_, best_hits)| best_hits) {
+            best = Some((*language, hits));
+        }
+    }
+    best.map(|(language, _)| language.to_string())
+}
+
+fn extract_blocks(boxes: Vec<OcrBox>) -> Vec<CodeBlock> {
+    group_blocks(group_lines(boxes))
+        .into_iter()
+        .filter(|block| {
+            let code = block.iter().filter(|line| line.looks_like_code()).count();
+            code as f64 >= block.len() as f64 * MIN_CODE_SHARE
+        })
+        .map(|block| {
+            let text = layout(&block);
+            let left = block.iter().map(|line| line.left).min().unwrap_or(0);
+            let top = block.iter().map(|line| line.top).min().unwrap_or(0);
+            let right = block.iter().map(|line| line.right).max().unwrap_or(0);
+            let bottom = block.iter().map(|line| line.bottom).max().unwrap_or(0);
+            CodeBlock {
+                language: guess_language(&text),
+                text,
+                x: left,
+                y: top,
+                width: right - left,
+                height: bottom - top,
+            }
+        })
+        .collect()
+}
+
+/// The code blocks in `coords` on `monitor` (the whole monitor when None) as
+/// text, top to bottom. `language` is the OCR language of comments and
+/// strings when they aren't English. Needs `tesseract` installed.
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_code(
+    app: tauri::AppHandle,
+    coords: Option<SelectionCoords>,
+    monitor: usize,
+    language: Option<String>,
+) -> Result<Vec<CodeBlock>, String> {
+    let mut options: Vec<String> = OCR_OPTIONS.iter().map(|o| o.to_string()).collect();
+    options.extend(crate::ocr::language_options(&app, language.as_deref())?);
+    tauri::async_runtime::spawn_blocking(move || {
+        let image = crate::watch::capture_region(monitor, coords)?;
+        crate::audit::record_capture("code", None);
+        let blocks = extract_blocks(ocr_boxes(&image, &options)?);
+        if blocks.is_empty() {
+            return Err("No code found in the selected area".to_string());
+        }
+        Ok(blocks)
+    })
+    .await
+    .map_err(|e| format!("Failed to extract code: {}", e))?
+}
@@ -0,0 +1,146 @@
+// WebSocket event bridge at `/v1/events` on the control API, for OBS overlays,
+// note-taking plugins and other subscribers. Browsers can't set headers on a
+// WebSocket, so the token may also be given as `?token=`.
+//
+// Every message is one JSON object:
+//   {"v": 1, "ts": <unix ms>, "type": "<type>", "data": {...}}
+//
+// Types and their data:
+//   capture-completed  {path, kind, bytes, createdAt}
+//                      a capture was saved to the library
+//   transcription      {source, role, text, isFinal}
+//                      source is "stt" (live system audio), "recording" (a
+//                      finished voice note) or "realtime"; role is "user" or
+//                      "assistant" for realtime and null otherwise
+//   llm-token          {requestId, text}   next piece of a streamed answer
+//   llm-done           {requestId, text}   the full answer
+//   lagged             {missed}            this subscriber fell behind and
+//                                          missed that many messages
+//
+// Subscribers only receive; anything they send is ignored.
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::ApiContext;
+use crate::captures::CaptureFile;
+use crate::realtime::SpeakerRole;
+
+const PROTOCOL_VERSION: u8 = 1;
+// Messages a slow subscriber can fall behind before it misses some
+const BUFFER: usize = 512;
+
+static EVENTS: Lazy<broadcast::Sender<BridgeMessage>> = Lazy::new(|| broadcast::channel(BUFFER).0);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptSource {
+    Stt,
+    Recording,
+    Realtime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "type",
+    content = "data",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
+pub enum BridgeEvent {
+    CaptureCompleted(CaptureFile),
+    Transcription {
+        source: TranscriptSource,
+        role: Option<SpeakerRole>,
+        text: String,
+        is_final: bool,
+    },
+    LlmToken {
+        request_id: String,
+        text: String,
+    },
+    LlmDone {
+        request_id: String,
+        text: String,
+    },
+    Lagged {
+        missed: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeMessage {
+    v: u8,
+    // Unix milliseconds
+    pub ts: i64,
+    #[serde(flatten)]
+    pub event: BridgeEvent,
+}
+
+impl BridgeMessage {
+    fn new(event: BridgeEvent) -> Self {
+        Self {
+            v: PROTOCOL_VERSION,
+            ts: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or_default(),
+            event,
+        }
+    }
+}
+
+/// Whether anyone is listening, so hot paths can skip building events
+pub fn has_subscribers() -> bool {
+    EVENTS.receiver_count() > 0
+}
+
+/// Sends an event to every connected subscriber
+pub fn publish(event: BridgeEvent) {
+    if has_subscribers() {
+        // Only fails when the last subscriber just left
+        let _ = EVENTS.send(BridgeMessage::new(event));
+    }
+}
+
+/// Receives every event published from now on, like a WebSocket subscriber
+pub fn subscribe() -> broadcast::Receiver<BridgeMessage> {
+    EVENTS.subscribe()
+}
+
+pub(super) async fn events(State(ctx): State<ApiContext>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| stream_events(socket, ctx))
+}
+
+async fn stream_events(mut socket: WebSocket, ctx: ApiContext) {
+    let mut events = subscribe();
+    let mut shutdown = ctx.shutdown.clone();
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let message = match event {
+                    Ok(message) => message,
+                    Err(RecvError::Lagged(missed)) => BridgeMessage::new(BridgeEvent::Lagged { missed }),
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                // Pings are answered by axum; other messages are ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            // Upgraded connections outlive the server's graceful shutdown
+            _ = shutdown.changed() => break,
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
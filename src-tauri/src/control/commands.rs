@@ -0,0 +1,39 @@
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use super::{load_token, regenerate_token, running_port};
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    // Base URL for scripts, e.g. http://127.0.0.1:47821/v1
+    pub url: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_control_api_status(app: AppHandle) -> Result<ControlApiStatus, String> {
+    let port = running_port(&app)?;
+    Ok(ControlApiStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|port| format!("http://127.0.0.1:{}/v1", port)),
+    })
+}
+
+// The bearer token scripts send, created on first use
+#[tauri::command]
+#[specta::specta]
+pub fn get_control_api_token(app: AppHandle) -> Result<String, String> {
+    load_token(&app)
+}
+
+// Replaces the token; scripts using the old one stop working right away
+#[tauri::command]
+#[specta::specta]
+pub fn regenerate_control_api_token(app: AppHandle) -> Result<String, String> {
+    regenerate_token(&app)
+}
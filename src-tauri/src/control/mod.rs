@@ -0,0 +1,478 @@
+// Pluely local control API. An opt-in REST server on 127.0.0.1 lets Stream
+// Deck, AutoHotkey or shell scripts trigger captures and ask questions. Every
+// request needs the bearer token kept in the keychain, except signed capture
+// links handed out with `signed_capture_url`.
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tokio::sync::watch;
+use tracing::warn;
+
+mod bridge;
+mod commands;
+
+pub use bridge::{has_subscribers, publish, subscribe, BridgeEvent, TranscriptSource};
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain entry holding the token
+pub const TOKEN_KEY_ID: &str = "control_api";
+const MAX_ASK_CHARS: usize = 10_000;
+const SIGNED_CAPTURE_PATH: &str = "/v1/captures/signed";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ControlApiSettings {
+    // Off unless the user opts in
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ControlApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47_821,
+        }
+    }
+}
+
+impl ControlApiSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err("Invalid control API port: must be 1024-65535".to_string());
+        }
+        Ok(())
+    }
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+pub struct ControlState {
+    server: Mutex<Option<RunningServer>>,
+    // Cached so requests don't each hit the keychain
+    token: Mutex<Option<String>>,
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    port: u16,
+    // Becomes true when the server stops
+    shutdown: watch::Receiver<bool>,
+}
+
+fn new_token() -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(crate::crypto::random_key())
+}
+
+// The active workspace's token, created on first use
+pub fn load_token(app: &AppHandle) -> Result<String, String> {
+    let token = match crate::secrets::get_api_key(TOKEN_KEY_ID)? {
+        Some(token) if !token.trim().is_empty() => token,
+        _ => {
+            let token = new_token();
+            crate::secrets::store_api_key(TOKEN_KEY_ID, &token)?;
+            token
+        }
+    };
+    *app.state::<ControlState>()
+        .token
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(token.clone());
+    Ok(token)
+}
+
+pub fn regenerate_token(app: &AppHandle) -> Result<String, String> {
+    crate::secrets::store_api_key(TOKEN_KEY_ID, &new_token())?;
+    load_token(app)
+}
+
+fn cached_token(app: &AppHandle) -> Option<String> {
+    app.state::<ControlState>()
+        .token
+        .lock()
+        .ok()
+        .and_then(|token| token.clone())
+}
+
+// Compares without stopping at the first difference
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+// Rejects requests without the token, and ones addressed to another host name
+// so a web page can't reach the server through DNS rebinding
+async fn authorize(State(ctx): State<ApiContext>, request: Request, next: Next) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or_default();
+    let allowed_hosts = [
+        format!("127.0.0.1:{}", ctx.port),
+        format!("localhost:{}", ctx.port),
+    ];
+    if !allowed_hosts.iter().any(|allowed| allowed == host) {
+        return error(StatusCode::FORBIDDEN, "Unexpected host");
+    }
+
+    // Signed links carry their own proof
+    if request.uri().path() == SIGNED_CAPTURE_PATH {
+        return next.run(request).await;
+    }
+
+    let expected = cached_token(&ctx.app);
+    let header_token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    // Browser WebSockets can't send headers, so the event stream also takes it
+    // from the query. Tokens are URL-safe base64 and never need decoding.
+    let query_token = || {
+        request
+            .uri()
+            .query()
+            .filter(|_| request.uri().path() == "/v1/events")
+            .and_then(|query| {
+                query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("token=").map(str::to_string))
+            })
+    };
+    let given = header_token.or_else(query_token).unwrap_or_default();
+    match expected {
+        Some(expected) if token_matches(&expected, given.trim()) => next.run(request).await,
+        _ => error(StatusCode::UNAUTHORIZED, "Missing or invalid token"),
+    }
+}
+
+async fn status(State(ctx): State<ApiContext>) -> Response {
+    Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "workspace": crate::workspaces::active_workspace(),
+        "hotkeysPaused": crate::shortcuts::get_hotkeys_paused(ctx.app.clone()),
+    }))
+    .into_response()
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct CaptureRequest {
+    // "screen" (default) or "area"
+    mode: Option<String>,
+}
+
+// A screen capture is saved to the library and shown in the chat like one
+// taken with the hotkey; an area capture opens the selection overlay
+async fn capture(State(ctx): State<ApiContext>, body: Option<Json<CaptureRequest>>) -> Response {
+    let mode = body.and_then(|Json(body)| body.mode);
+    match mode.as_deref().unwrap_or("screen") {
+        "area" => {
+            crate::tray::capture_area(&ctx.app);
+            (StatusCode::ACCEPTED, Json(json!({ "status": "selecting" }))).into_response()
+        }
+        "screen" => {
+            let image = match crate::capture::capture_screen_base64().await {
+                Ok(image) => image,
+                Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+            };
+            let file = match crate::captures::save_capture(
+                ctx.app.clone(),
+                image.clone(),
+                Some("screenshot".to_string()),
+                None,
+                None,
+            ) {
+                Ok(file) => file,
+                Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+            };
+            let thumbnail = crate::captures::thumbnail_of_base64(&ctx.app, &image);
+            let captured = crate::events::CapturedSelection::from_png_base64(None, image)
+                .with_thumbnail(thumbnail);
+            if let Err(e) = ctx.app.emit("captured-selection", captured) {
+                warn!("Failed to emit capture: {}", e);
+            }
+            Json(file).into_response()
+        }
+        other => error(
+            StatusCode::BAD_REQUEST,
+            &format!("Unknown capture mode: {}", other),
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestQuery {
+    kind: Option<String>,
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") => "image/jpeg",
+        Some("wav") => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+// The newest library file, optionally of one kind, as raw bytes
+async fn latest_capture(
+    State(ctx): State<ApiContext>,
+    Query(query): Query<LatestQuery>,
+) -> Response {
+    let files = match crate::captures::list_files(&ctx.app) {
+        Ok(files) => files,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    };
+    let Some(file) = files
+        .into_iter()
+        .rev()
+        .find(|file| query.kind.is_none() || query.kind.as_deref() == Some(file.kind.as_str()))
+    else {
+        return error(StatusCode::NOT_FOUND, "No captures yet");
+    };
+    let mut headers = HeaderMap::new();
+    if let Ok(created_at) = header::HeaderValue::from_str(&file.created_at.to_string()) {
+        headers.insert("x-capture-created-at", created_at);
+    }
+    file_response(Path::new(&file.path), headers)
+}
+
+fn file_response(path: &Path, mut headers: HeaderMap) -> Response {
+    match crate::storage::read_file(path) {
+        Ok(bytes) => {
+            headers.insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static(content_type(path)),
+            );
+            (headers, bytes).into_response()
+        }
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn signed_data(name: &str, expires: i64) -> String {
+    format!("{}:{}", name, expires)
+}
+
+/// A link to one library file that works without the bearer token until it
+/// expires, for webhooks and other tools that only get a URL. Signed with the
+/// token, so regenerating it revokes every link. None while the server is off.
+pub fn signed_capture_url(
+    app: &AppHandle,
+    path: &Path,
+    ttl_secs: i64,
+) -> Result<Option<String>, String> {
+    let (Some(port), Some(token)) = (running_port(app)?, cached_token(app)) else {
+        return Ok(None);
+    };
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid capture path: {}", path.display()))?;
+    let expires = now_secs() + ttl_secs;
+    let sig = crate::crypto::sign(token.as_bytes(), signed_data(name, expires).as_bytes());
+    Ok(Some(format!(
+        "http://127.0.0.1:{}{}?name={}&expires={}&sig={}",
+        port, SIGNED_CAPTURE_PATH, name, expires, sig
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedQuery {
+    name: String,
+    expires: i64,
+    sig: String,
+}
+
+async fn signed_capture(
+    State(ctx): State<ApiContext>,
+    Query(query): Query<SignedQuery>,
+) -> Response {
+    let valid = cached_token(&ctx.app).is_some_and(|token| {
+        crate::crypto::verify(
+            token.as_bytes(),
+            signed_data(&query.name, query.expires).as_bytes(),
+            &query.sig,
+        )
+    });
+    if !valid || query.expires < now_secs() {
+        return error(StatusCode::FORBIDDEN, "Invalid or expired link");
+    }
+    // Only plain file names, so a link can't leave the library
+    if query.name.starts_with('.') || query.name.contains(['/', '\\']) {
+        return error(StatusCode::BAD_REQUEST, "Invalid file name");
+    }
+    let path = match crate::captures::captures_dir(&ctx.app) {
+        Ok(dir) => dir.join(&query.name),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    };
+    if !path.is_file() {
+        return error(StatusCode::NOT_FOUND, "Capture not found");
+    }
+    file_response(&path, HeaderMap::new())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiAsk {
+    pub text: String,
+    // Sends right away instead of only filling in the input
+    #[serde(default)]
+    pub submit: bool,
+}
+
+// Hands the question to the chat window, which sends it like typed input
+async fn ask(State(ctx): State<ApiContext>, Json(ask): Json<ControlApiAsk>) -> Response {
+    let text = ask.text.trim();
+    if text.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Question cannot be empty");
+    }
+    if text.chars().count() > MAX_ASK_CHARS {
+        return error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!("Question is longer than {} characters", MAX_ASK_CHARS),
+        );
+    }
+    crate::shortcuts::handle_shortcut_action(&ctx.app, "focus_input");
+    let ask = ControlApiAsk {
+        text: text.to_string(),
+        ..ask
+    };
+    if let Err(e) = ctx.app.emit("control-api-ask", ask) {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
+    }
+    (StatusCode::ACCEPTED, Json(json!({ "status": "accepted" }))).into_response()
+}
+
+fn router(ctx: ApiContext) -> Router {
+    Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/capture", post(capture))
+        .route("/v1/captures/latest", get(latest_capture))
+        .route(SIGNED_CAPTURE_PATH, get(signed_capture))
+        .route("/v1/ask", post(ask))
+        .route("/v1/events", get(bridge::events))
+        .layer(middleware::from_fn_with_state(ctx.clone(), authorize))
+        .with_state(ctx)
+}
+
+fn stop_server(state: &ControlState) -> Result<(), String> {
+    if let Some(running) = state
+        .server
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .take()
+    {
+        let _ = running.shutdown.send(true);
+    }
+    Ok(())
+}
+
+// Starts, stops or moves the server to match `settings`
+pub fn apply_control_api_settings(
+    app: &AppHandle,
+    settings: &ControlApiSettings,
+) -> Result<(), String> {
+    settings.validate()?;
+    let state = app.state::<ControlState>();
+    if !settings.enabled {
+        return stop_server(&state);
+    }
+    // Also picks up the token of a workspace that was just switched to
+    load_token(app)?;
+    if running_port(app)? == Some(settings.port) {
+        return Ok(());
+    }
+    stop_server(&state)?;
+
+    // Bound here so a port in use fails the settings change
+    let listener =
+        std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port)).map_err(|e| {
+            format!(
+                "Failed to start control API on port {}: {}",
+                settings.port, e
+            )
+        })?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to start control API: {}", e))?;
+    let (shutdown, stopped) = watch::channel(false);
+    let router = router(ApiContext {
+        app: app.clone(),
+        port: settings.port,
+        shutdown: stopped.clone(),
+    });
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Failed to start control API: {}", e);
+                return;
+            }
+        };
+        let served = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let mut stopped = stopped;
+                let _ = stopped.wait_for(|stopped| *stopped).await;
+            })
+            .await;
+        if let Err(e) = served {
+            warn!("Control API stopped: {}", e);
+        }
+    });
+
+    *state
+        .server
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(RunningServer {
+        port: settings.port,
+        shutdown,
+    });
+    Ok(())
+}
+
+pub fn running_port(app: &AppHandle) -> Result<Option<u16>, String> {
+    Ok(app
+        .state::<ControlState>()
+        .server
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .as_ref()
+        .map(|running| running.port))
+}
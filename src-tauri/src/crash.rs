@@ -0,0 +1,275 @@
+// Pluely crash reports. A panic hook writes a report (message, location, thread,
+// backtrace and the capture environment) to the app data directory. Reports
+// are only uploaded when the user has opted in, and never include chat
+// content, captures or keys.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+use tracing::{error, warn};
+
+pub const CRASH_DIR: &str = "crashes";
+// Older reports are removed past this many
+const MAX_REPORTS: usize = 20;
+
+// Mirrors the setting so the startup upload doesn't need the settings lock
+static UPLOAD_ALLOWED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportSettings {
+    // Off unless the user explicitly agrees to send reports
+    pub upload: bool,
+}
+
+// Where screens and system audio come from; Wayland sessions capture through
+// the desktop portal and fail in ways X11 never does
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureEnvironment {
+    pub screen_backend: String,
+    pub audio_backend: String,
+    // "wayland", "x11", or None off Linux
+    pub session_type: Option<String>,
+    pub desktop: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    // Unix milliseconds
+    pub created_at: i64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub environment: CaptureEnvironment,
+    pub uploaded: bool,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+pub fn capture_environment() -> CaptureEnvironment {
+    let session_type = if cfg!(target_os = "linux") {
+        env_var("XDG_SESSION_TYPE").or_else(|| {
+            if env_var("WAYLAND_DISPLAY").is_some() {
+                Some("wayland".to_string())
+            } else if env_var("DISPLAY").is_some() {
+                Some("x11".to_string())
+            } else {
+                None
+            }
+        })
+    } else {
+        None
+    };
+    let screen_backend = match session_type.as_deref() {
+        Some("wayland") => "xcap (wayland portal)",
+        Some(_) => "xcap (x11)",
+        None if cfg!(target_os = "macos") => "xcap (core graphics)",
+        None if cfg!(windows) => "xcap (dxgi)",
+        None => "xcap",
+    };
+    let audio_backend = if cfg!(target_os = "macos") {
+        "core audio tap"
+    } else if cfg!(windows) {
+        "wasapi loopback"
+    } else {
+        "pulseaudio monitor"
+    };
+    CaptureEnvironment {
+        screen_backend: screen_backend.to_string(),
+        audio_backend: audio_backend.to_string(),
+        session_type,
+        desktop: env_var("XDG_CURRENT_DESKTOP"),
+    }
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// Shared by every workspace, like the logs
+pub fn crash_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(CRASH_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create crash report directory: {}", e))?;
+    Ok(dir)
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    std::fs::write(report_path(dir, &report.id), json)
+        .map_err(|e| format!("Failed to write crash report: {}", e))
+}
+
+// Every readable report, oldest first
+fn read_reports(dir: &Path) -> Result<Vec<CrashReport>, String> {
+    let mut reports: Vec<CrashReport> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read crash reports: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    reports.sort_by_key(|report| report.created_at);
+    Ok(reports)
+}
+
+fn prune(dir: &Path) {
+    let Ok(reports) = read_reports(dir) else {
+        return;
+    };
+    let excess = reports.len().saturating_sub(MAX_REPORTS);
+    for report in &reports[..excess] {
+        let _ = std::fs::remove_file(report_path(dir, &report.id));
+    }
+}
+
+/// Writes a report for every panic, then runs the default hook. Installed
+/// once at startup; the directory is resolved up front so the hook itself
+/// needs nothing from the app.
+pub fn install_panic_hook(app: &AppHandle) {
+    let dir = match crash_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!("Crash reports disabled: {}", e);
+            return;
+        }
+    };
+    let environment = capture_environment();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let report = CrashReport {
+            id: format!("crash-{}", uuid::Uuid::new_v4().simple()),
+            created_at: now_ms(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            message,
+            location: info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line())),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            environment: environment.clone(),
+            uploaded: false,
+        };
+        error!(
+            "Panic on thread {}: {} at {}",
+            report.thread,
+            report.message,
+            report.location.as_deref().unwrap_or("unknown location")
+        );
+        if let Err(e) = write_report(&dir, &report) {
+            error!("{}", e);
+        }
+        prune(&dir);
+        default_hook(info);
+    }));
+}
+
+async fn upload_report(report: &CrashReport) -> Result<(), String> {
+    let endpoint = crate::api::get_app_endpoint()?;
+    let access_key = crate::api::get_api_access_key()?;
+    let request = crate::network::http_client()?
+        .post(format!("{}/api/crash", endpoint.trim_end_matches('/')))
+        .header("Authorization", format!("Bearer {}", access_key))
+        .json(report);
+    let response = crate::network::send(request, "crash report", &[])
+        .await
+        .map_err(|e| format!("Failed to upload crash report: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to upload crash report: server returned {}",
+            response.status()
+        ));
+    }
+    Ok(())
+}
+
+// Sends reports not uploaded yet and marks them; returns how many were sent
+async fn upload_pending(app: &AppHandle) -> Result<usize, String> {
+    if !UPLOAD_ALLOWED.load(Ordering::SeqCst) {
+        return Err("Crash report uploads are turned off".to_string());
+    }
+    let dir = crash_dir(app)?;
+    let mut sent = 0;
+    for mut report in read_reports(&dir)?.into_iter().filter(|r| !r.uploaded) {
+        upload_report(&report).await?;
+        report.uploaded = true;
+        write_report(&dir, &report)?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+// Records consent; turning it on sends what earlier crashes left behind
+pub fn apply_crash_report_settings(app: &AppHandle, settings: &CrashReportSettings) {
+    let was_allowed = UPLOAD_ALLOWED.swap(settings.upload, Ordering::SeqCst);
+    if settings.upload && !was_allowed {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = upload_pending(&app).await {
+                warn!("{}", e);
+            }
+        });
+    }
+}
+
+/// Tauri command listing saved crash reports, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    let mut reports = read_reports(&crash_dir(&app)?)?;
+    reports.reverse();
+    Ok(reports)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_crash_report(app: AppHandle, id: String) -> Result<(), String> {
+    if !id.starts_with("crash-") || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid crash report id: {}", id));
+    }
+    let path = report_path(&crash_dir(&app)?, &id);
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete crash report: {}", e))
+}
+
+/// Tauri command uploading pending reports; fails unless uploads are on
+#[tauri::command]
+#[specta::specta]
+pub async fn upload_crash_reports(app: AppHandle) -> Result<usize, String> {
+    upload_pending(&app).await
+}
@@ -17,5 +17,68 @@ pub fn migrations() -> Vec<Migration> {
             sql: include_str!("migrations/chat-history.sql"),
             kind: MigrationKind::Up,
         },
+        // Migration 3: Track the model used and attach capture references to messages
+        Migration {
+            version: 3,
+            description: "add_conversation_metadata",
+            sql: include_str!("migrations/conversation-metadata.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 4: Full-text search over messages and capture OCR text
+        Migration {
+            version: 4,
+            description: "create_history_search_index",
+            sql: include_str!("migrations/history-search.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 5: Embedding vectors for semantic recall
+        Migration {
+            version: 5,
+            description: "create_embeddings_table",
+            sql: include_str!("migrations/embeddings.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 6: Prompt profile per conversation
+        Migration {
+            version: 6,
+            description: "add_conversation_profile",
+            sql: include_str!("migrations/conversation-profile.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 7: Token usage and estimated cost per LLM request
+        Migration {
+            version: 7,
+            description: "create_llm_usage_table",
+            sql: include_str!("migrations/usage.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 8: Opt-in cache of LLM responses
+        Migration {
+            version: 8,
+            description: "create_response_cache_table",
+            sql: include_str!("migrations/response-cache.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 9: Chat requests queued while offline
+        Migration {
+            version: 9,
+            description: "create_request_queue_table",
+            sql: include_str!("migrations/request-queue.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 10: Files dropped into the chat
+        Migration {
+            version: 10,
+            description: "create_attachments_table",
+            sql: include_str!("migrations/attachments.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 11: Focused app recorded with each message
+        Migration {
+            version: 11,
+            description: "add_message_app_context",
+            sql: include_str!("migrations/app-context.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
@@ -0,0 +1,3 @@
+-- App and window the user was in when a message was sent, as JSON. Only set
+-- when focus tracking is turned on.
+ALTER TABLE messages ADD COLUMN app_context TEXT;
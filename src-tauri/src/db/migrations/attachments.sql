@@ -0,0 +1,18 @@
+-- Files dropped into the chat, stored under the data directory. Images are one
+-- page; PDFs are rasterized into one PNG per page.
+CREATE TABLE IF NOT EXISTS attachments (
+    id TEXT PRIMARY KEY,
+    -- Set once the message the attachment was sent with is saved
+    message_id TEXT REFERENCES messages(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    -- Size of the dropped file
+    bytes INTEGER NOT NULL,
+    page_count INTEGER NOT NULL,
+    -- Directory holding the pages and thumbnail
+    path TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_message_id ON attachments(message_id);
@@ -0,0 +1,16 @@
+-- Model that produced each conversation and message
+ALTER TABLE conversations ADD COLUMN model TEXT;
+ALTER TABLE messages ADD COLUMN model TEXT;
+
+-- Captures (screenshots, recordings) referenced by a message, stored as paths
+-- rather than inline base64
+CREATE TABLE IF NOT EXISTS message_captures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'screenshot',
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES messages(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_captures_message_id ON message_captures(message_id);
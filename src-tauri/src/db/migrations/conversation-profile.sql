@@ -0,0 +1,2 @@
+-- Prompt profile selected for a conversation
+ALTER TABLE conversations ADD COLUMN profile_id TEXT;
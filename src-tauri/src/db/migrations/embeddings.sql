@@ -0,0 +1,30 @@
+-- Embedding vectors for messages and capture OCR text, as little-endian f32 blobs
+CREATE TABLE IF NOT EXISTS embeddings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL CHECK(source IN ('message', 'capture')),
+    ref_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    vector BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE(source, ref_id, model)
+);
+
+CREATE INDEX IF NOT EXISTS idx_embeddings_model ON embeddings(model);
+
+-- Stale vectors go away with their text so they get re-indexed
+CREATE TRIGGER IF NOT EXISTS embeddings_message_delete AFTER DELETE ON messages BEGIN
+    DELETE FROM embeddings WHERE source = 'message' AND ref_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS embeddings_message_update AFTER UPDATE OF content ON messages BEGIN
+    DELETE FROM embeddings WHERE source = 'message' AND ref_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS embeddings_capture_delete AFTER DELETE ON message_captures BEGIN
+    DELETE FROM embeddings WHERE source = 'capture' AND ref_id = CAST(OLD.id AS TEXT);
+END;
+
+CREATE TRIGGER IF NOT EXISTS embeddings_capture_update AFTER UPDATE OF ocr_text ON message_captures BEGIN
+    DELETE FROM embeddings WHERE source = 'capture' AND ref_id = CAST(OLD.id AS TEXT);
+END;
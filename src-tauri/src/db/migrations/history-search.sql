@@ -0,0 +1,54 @@
+-- Text recognized in a capture, filled in by OCR
+ALTER TABLE message_captures ADD COLUMN ocr_text TEXT;
+
+-- Full-text indexes over message content and capture text. Both are external
+-- content tables kept in sync by the triggers below.
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    content,
+    content='messages',
+    content_rowid='rowid',
+    tokenize='porter unicode61'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS captures_fts USING fts5(
+    ocr_text,
+    content='message_captures',
+    content_rowid='id',
+    tokenize='porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_fts(rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content) VALUES ('delete', OLD.rowid, OLD.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content ON messages BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, content) VALUES ('delete', OLD.rowid, OLD.content);
+    INSERT INTO messages_fts(rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS captures_fts_insert AFTER INSERT ON message_captures
+WHEN NEW.ocr_text IS NOT NULL BEGIN
+    INSERT INTO captures_fts(rowid, ocr_text) VALUES (NEW.id, NEW.ocr_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS captures_fts_delete AFTER DELETE ON message_captures
+WHEN OLD.ocr_text IS NOT NULL BEGIN
+    INSERT INTO captures_fts(captures_fts, rowid, ocr_text) VALUES ('delete', OLD.id, OLD.ocr_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS captures_fts_update_old AFTER UPDATE OF ocr_text ON message_captures
+WHEN OLD.ocr_text IS NOT NULL BEGIN
+    INSERT INTO captures_fts(captures_fts, rowid, ocr_text) VALUES ('delete', OLD.id, OLD.ocr_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS captures_fts_update_new AFTER UPDATE OF ocr_text ON message_captures
+WHEN NEW.ocr_text IS NOT NULL BEGIN
+    INSERT INTO captures_fts(rowid, ocr_text) VALUES (NEW.id, NEW.ocr_text);
+END;
+
+-- Index history saved before this migration
+INSERT INTO messages_fts(messages_fts) VALUES ('rebuild');
@@ -0,0 +1,12 @@
+-- Chat requests made while offline, replayed once the network is back
+CREATE TABLE IF NOT EXISTS request_queue (
+    id TEXT PRIMARY KEY,
+    -- JSON: messages (with attached images), profile, variables and tools
+    request TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at INTEGER NOT NULL,
+    last_attempt_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_queue_created_at ON request_queue(created_at);
@@ -0,0 +1,12 @@
+-- Answers to earlier requests, keyed by a hash of provider, model, prompt and images
+CREATE TABLE IF NOT EXISTS response_cache (
+    key TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    response TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 0,
+    last_hit_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_response_cache_created_at ON response_cache(created_at);
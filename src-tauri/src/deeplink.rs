@@ -0,0 +1,143 @@
+// Pluely deep links: `pluely://` URLs let launchers, scripts and browser
+// extensions trigger area captures or open the chat with a question filled in.
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_specta::Event;
+use tracing::warn;
+
+use crate::shortcuts;
+
+pub const SCHEME: &str = "pluely";
+// Longer questions are cut; a URL is not the way to paste a document
+const MAX_ASK_CHARS: usize = 10_000;
+
+/// A question waiting for the frontend, kept in case the link arrived before
+/// the window was ready to listen
+#[derive(Default)]
+pub struct DeepLinkState {
+    pending_ask: Mutex<Option<DeepLinkAsk>>,
+}
+
+/// `deep-link-ask`: also what take_deep_link_ask returns
+#[derive(Debug, Clone, Serialize, PartialEq, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkAsk {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    CaptureArea,
+    Ask(DeepLinkAsk),
+    ToggleWindow,
+    Dashboard,
+}
+
+/// Parses `pluely://<action>?<params>`. The action is the URL host, so
+/// `pluely://capture?mode=area` and `pluely://ask?text=...` both work.
+pub fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {} link: {}", SCHEME, url));
+    }
+    let action = url
+        .host_str()
+        .or_else(|| url.path_segments().and_then(|mut s| s.next()))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+    };
+
+    match action.as_str() {
+        "capture" => match param("mode").as_deref().unwrap_or("area") {
+            "area" => Ok(DeepLink::CaptureArea),
+            // Any web page can open a link, so it may not grab the whole
+            // screen without the user picking the area
+            "screen" | "full" => Err("Full screen capture is not available from links".to_string()),
+            mode => Err(format!("Unknown capture mode: {}", mode)),
+        },
+        "ask" => {
+            let text: String = param("text")
+                .unwrap_or_default()
+                .trim()
+                .chars()
+                .take(MAX_ASK_CHARS)
+                .collect();
+            Ok(DeepLink::Ask(DeepLinkAsk { text }))
+        }
+        "toggle" => Ok(DeepLink::ToggleWindow),
+        "dashboard" => Ok(DeepLink::Dashboard),
+        _ => Err(format!("Unknown deep link action: {}", action)),
+    }
+}
+
+/// Runs one link. Questions are only filled in, never sent: any web page can
+/// open a `pluely://` URL, so the user always confirms.
+pub fn handle_url(app: &AppHandle, url: &Url) {
+    let link = match parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            warn!("Ignoring deep link: {}", e);
+            return;
+        }
+    };
+    match link {
+        DeepLink::CaptureArea => crate::tray::capture_area(app),
+        DeepLink::ToggleWindow => shortcuts::handle_shortcut_action(app, "toggle_window"),
+        DeepLink::Dashboard => shortcuts::handle_shortcut_action(app, "toggle_dashboard"),
+        DeepLink::Ask(ask) => {
+            if let Ok(mut pending) = app.state::<DeepLinkState>().pending_ask.lock() {
+                *pending = Some(ask.clone());
+            }
+            shortcuts::handle_shortcut_action(app, "focus_input");
+            // The UI takes the question with take_deep_link_ask, so a link
+            // that arrived before it listened is still picked up once
+            if let Err(e) = app.emit("deep-link-ask", ask) {
+                warn!("Failed to emit deep link question: {}", e);
+            }
+        }
+    }
+}
+
+/// Handles links opened while running and the one the app was launched with
+pub fn setup_deep_links(app: &App) {
+    // Installed bundles register the scheme; dev builds and AppImages do it here
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        warn!("Failed to register deep link scheme: {}", e);
+    }
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, &url);
+        }
+    });
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                handle_url(app.handle(), &url);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to read launch deep link: {}", e),
+    }
+}
+
+/// Tauri command returning the question from the last `pluely://ask` link, once
+#[tauri::command]
+#[specta::specta]
+pub fn take_deep_link_ask(app: AppHandle) -> Result<Option<DeepLinkAsk>, String> {
+    let state = app.state::<DeepLinkState>();
+    let mut pending = state
+        .pending_ask
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(pending.take())
+}
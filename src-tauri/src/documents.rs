@@ -0,0 +1,177 @@
+// Pluely documents. A PDF is opened by path and the pages the user picks are
+// rendered and registered as one attachment. Text the PDF carries is returned
+// with them, so the model can read it exactly instead of from pixels.
+use pdfium_render::prelude::{PdfDocumentMetadataTagType, PdfPage, PdfPageIndex};
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::attachments::{
+    encode_png, load_pdf, pdfium, render_page, thumbnail, Attachment, AttachmentKind,
+    ProcessedFile, MAX_PDF_PAGES,
+};
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+    pub name: String,
+    // From the PDF's metadata, when it has one
+    pub title: Option<String>,
+    pub page_count: u32,
+    // Whether the first pages carry text; scans usually don't
+    pub has_text: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentPage {
+    // 1-based, as numbered in the PDF
+    pub page: u32,
+    // Empty for pages that are only images
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestedDocument {
+    // The rendered pages, in page order
+    pub attachment: Attachment,
+    pub pages: Vec<DocumentPage>,
+}
+
+fn read_pdf(path: &Path) -> Result<(String, Vec<u8>, u64), String> {
+    let (kind, _, bytes) = crate::attachments::validate(path)?;
+    if kind != AttachmentKind::Pdf {
+        return Err("Only PDFs can be opened as documents".to_string());
+    }
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "document.pdf".to_string());
+    Ok((name, data, bytes))
+}
+
+fn page_text(page: &PdfPage) -> String {
+    page.text()
+        .map(|text| text.all().trim().to_string())
+        .unwrap_or_default()
+}
+
+// Checks and orders a page selection; None picks the first pages
+fn select_pages(pages: Option<Vec<u32>>, page_count: u32) -> Result<Vec<u32>, String> {
+    let Some(mut pages) = pages else {
+        return Ok((1..=page_count.min(MAX_PDF_PAGES as u32)).collect());
+    };
+    pages.sort_unstable();
+    pages.dedup();
+    if pages.is_empty() {
+        return Err("No pages selected".to_string());
+    }
+    if let Some(page) = pages.iter().find(|page| **page == 0 || **page > page_count) {
+        return Err(format!(
+            "Invalid page {}: the PDF has {} pages",
+            page, page_count
+        ));
+    }
+    if pages.len() > MAX_PDF_PAGES {
+        return Err(format!(
+            "Too many pages: at most {} can be attached at once",
+            MAX_PDF_PAGES
+        ));
+    }
+    Ok(pages)
+}
+
+fn inspect(path: &Path, resource_dir: Option<&Path>) -> Result<DocumentInfo, String> {
+    let (name, data, _) = read_pdf(path)?;
+    let pdfium = pdfium(resource_dir)?;
+    let document = load_pdf(&pdfium, &data)?;
+    let title = document
+        .metadata()
+        .get(PdfDocumentMetadataTagType::Title)
+        .map(|tag| tag.value().trim().to_string())
+        .filter(|title| !title.is_empty());
+    let has_text = document
+        .pages()
+        .iter()
+        .take(MAX_PDF_PAGES)
+        .any(|page| !page_text(&page).is_empty());
+    Ok(DocumentInfo {
+        name,
+        title,
+        page_count: document.pages().len() as u32,
+        has_text,
+    })
+}
+
+fn rasterize(
+    path: &Path,
+    pages: Option<Vec<u32>>,
+    resource_dir: Option<&Path>,
+) -> Result<(ProcessedFile, Vec<DocumentPage>), String> {
+    let (name, data, bytes) = read_pdf(path)?;
+    let pdfium = pdfium(resource_dir)?;
+    let document = load_pdf(&pdfium, &data)?;
+    let selected = select_pages(pages, document.pages().len() as u32)?;
+
+    let mut images = Vec::with_capacity(selected.len());
+    let mut texts = Vec::with_capacity(selected.len());
+    for number in selected {
+        let page = document
+            .pages()
+            .get((number - 1) as PdfPageIndex)
+            .map_err(|e| format!("Failed to read PDF page {}: {}", number, e))?;
+        images.push(render_page(&page, number as usize)?);
+        texts.push(DocumentPage {
+            page: number,
+            text: page_text(&page),
+        });
+    }
+    let first = images.first().ok_or("PDF has no pages".to_string())?;
+    let processed = ProcessedFile {
+        name,
+        kind: AttachmentKind::Pdf,
+        mime_type: "application/pdf".to_string(),
+        bytes,
+        thumbnail: thumbnail(first)?,
+        pages: images
+            .iter()
+            .map(encode_png)
+            .collect::<Result<Vec<_>, String>>()?,
+    };
+    Ok((processed, texts))
+}
+
+/// Page count, title and whether the PDF at `path` has text, for picking
+/// which pages to attach
+#[tauri::command]
+#[specta::specta]
+pub async fn inspect_document(app: AppHandle, path: String) -> Result<DocumentInfo, String> {
+    let resource_dir = app.path().resource_dir().ok();
+    tauri::async_runtime::spawn_blocking(move || inspect(Path::new(&path), resource_dir.as_deref()))
+        .await
+        .map_err(|e| format!("Failed to read document: {}", e))?
+}
+
+/// Renders `pages` (1-based; the first pages when None) of the PDF at `path`,
+/// registers them as an attachment and returns it with the pages' text
+#[tauri::command]
+#[specta::specta]
+pub async fn ingest_document(
+    app: AppHandle,
+    path: String,
+    pages: Option<Vec<u32>>,
+) -> Result<IngestedDocument, String> {
+    let resource_dir = app.path().resource_dir().ok();
+    let source = PathBuf::from(path);
+    let (processed, pages) = tauri::async_runtime::spawn_blocking(move || {
+        rasterize(&source, pages, resource_dir.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Failed to process document: {}", e))??;
+    let dir = crate::attachments::attachments_dir(&app)?;
+    let attachment = crate::attachments::register(&app, processed, &dir).await?;
+    Ok(IngestedDocument { attachment, pages })
+}
@@ -0,0 +1,181 @@
+// Pluely embeddings commands: configuration, indexing history and semantic search.
+use serde::Serialize;
+use specta::Type;
+use sqlx::Row;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::{cosine_similarity, decode_vector, embed, encode_vector, EmbeddingsConfig};
+use crate::storage::pool;
+
+// Texts sent per embedding request while indexing
+const INDEX_BATCH_SIZE: usize = 32;
+const DEFAULT_INDEX_LIMIT: i64 = 256;
+
+#[derive(Default)]
+pub struct EmbeddingsState {
+    config: Mutex<EmbeddingsConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticHit {
+    // "message" or "capture"
+    pub source: String,
+    pub message_id: String,
+    pub conversation_id: String,
+    pub conversation_title: String,
+    pub role: String,
+    pub timestamp: i64,
+    pub text: String,
+    pub score: f32,
+}
+
+fn current_config(app: &AppHandle) -> Result<EmbeddingsConfig, String> {
+    let state = app.state::<EmbeddingsState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read embeddings config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_embeddings_config(app: AppHandle) -> Result<EmbeddingsConfig, String> {
+    current_config(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_embeddings_config(app: AppHandle, config: EmbeddingsConfig) -> Result<(), String> {
+    let state = app.state::<EmbeddingsState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update embeddings config: {}", e))? = config;
+    Ok(())
+}
+
+// Embeds messages and capture text that have no vector for the current model
+// yet. Returns how many were indexed; call again until it returns 0.
+#[tauri::command]
+#[specta::specta]
+pub async fn index_history(app: AppHandle, limit: Option<i64>) -> Result<usize, String> {
+    let config = current_config(&app)?;
+    let model = config.model();
+    let pool = pool(&app).await?;
+
+    let pending = sqlx::query(
+        "SELECT 'message' AS source, m.id AS ref_id, m.content AS text FROM messages m
+         WHERE length(trim(m.content)) > 0 AND NOT EXISTS (
+             SELECT 1 FROM embeddings e
+             WHERE e.source = 'message' AND e.ref_id = m.id AND e.model = ?1)
+         UNION ALL
+         SELECT 'capture', CAST(mc.id AS TEXT), mc.ocr_text FROM message_captures mc
+         WHERE length(trim(coalesce(mc.ocr_text, ''))) > 0 AND NOT EXISTS (
+             SELECT 1 FROM embeddings e
+             WHERE e.source = 'capture' AND e.ref_id = CAST(mc.id AS TEXT) AND e.model = ?1)
+         LIMIT ?2",
+    )
+    .bind(&model)
+    .bind(limit.unwrap_or(DEFAULT_INDEX_LIMIT))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read history: {}", e))?;
+
+    let mut indexed = 0;
+    for batch in pending.chunks(INDEX_BATCH_SIZE) {
+        let texts = batch
+            .iter()
+            .map(|row| crate::storage::open_text(row.get("text")))
+            .collect::<Result<Vec<String>, String>>()?;
+        let vectors = embed(&config, &texts).await?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        for (row, vector) in batch.iter().zip(vectors) {
+            sqlx::query(
+                "INSERT OR REPLACE INTO embeddings (source, ref_id, model, dimensions, vector, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(row.get::<String, _>("source"))
+            .bind(row.get::<String, _>("ref_id"))
+            .bind(&model)
+            .bind(vector.len() as i64)
+            .bind(encode_vector(&vector))
+            .bind(now)
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Failed to store embedding: {}", e))?;
+            indexed += 1;
+        }
+    }
+    Ok(indexed)
+}
+
+// The `k` stored messages and captures closest in meaning to `query`
+#[tauri::command]
+#[specta::specta]
+pub async fn semantic_search(
+    app: AppHandle,
+    query: String,
+    k: Option<usize>,
+) -> Result<Vec<SemanticHit>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let config = current_config(&app)?;
+    let model = config.model();
+    let query_vector = embed(&config, &[query])
+        .await?
+        .pop()
+        .ok_or("Embedding response was empty".to_string())?;
+
+    let pool = pool(&app).await?;
+    let rows = sqlx::query(
+        "SELECT e.source, e.vector, m.id AS message_id, m.conversation_id, c.title, m.role,
+                m.timestamp, m.content AS text
+         FROM embeddings e
+         JOIN messages m ON e.source = 'message' AND m.id = e.ref_id
+         JOIN conversations c ON c.id = m.conversation_id
+         WHERE e.model = ?1
+         UNION ALL
+         SELECT e.source, e.vector, m.id, m.conversation_id, c.title, m.role,
+                m.timestamp, mc.ocr_text
+         FROM embeddings e
+         JOIN message_captures mc ON e.source = 'capture' AND CAST(mc.id AS TEXT) = e.ref_id
+         JOIN messages m ON m.id = mc.message_id
+         JOIN conversations c ON c.id = m.conversation_id
+         WHERE e.model = ?1",
+    )
+    .bind(&model)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read embeddings: {}", e))?;
+
+    // History is small enough to score exhaustively
+    let mut hits: Vec<SemanticHit> = rows
+        .iter()
+        .map(|row| {
+            let vector = decode_vector(&row.get::<Vec<u8>, _>("vector"));
+            Ok(SemanticHit {
+                source: row.get("source"),
+                message_id: row.get("message_id"),
+                conversation_id: row.get("conversation_id"),
+                conversation_title: row.get("title"),
+                role: row.get("role"),
+                timestamp: row.get("timestamp"),
+                text: crate::storage::open_text(row.get("text"))?,
+                score: cosine_similarity(&query_vector, &vector),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(k.unwrap_or(5));
+    Ok(hits)
+}
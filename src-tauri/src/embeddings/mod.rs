@@ -0,0 +1,182 @@
+// Pluely embeddings: vectors for past messages and capture OCR text, used to
+// recall related history by meaning rather than exact words.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::time::Duration;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+// Keeps requests under every provider's input limit
+const MAX_INPUT_CHARS: usize = 8_000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderKind {
+    #[default]
+    OpenAi,
+    // Runs locally, nothing leaves the machine
+    Ollama,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct EmbeddingsConfig {
+    pub provider: EmbeddingProviderKind,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl EmbeddingsConfig {
+    pub fn model(&self) -> String {
+        self.model
+            .clone()
+            .filter(|model| !model.trim().is_empty())
+            .unwrap_or_else(|| match self.provider {
+                EmbeddingProviderKind::OpenAi => "text-embedding-3-small".to_string(),
+                EmbeddingProviderKind::Ollama => "nomic-embed-text".to_string(),
+            })
+    }
+
+    fn base_url(&self) -> String {
+        let default = match self.provider {
+            EmbeddingProviderKind::OpenAi => OPENAI_BASE_URL,
+            EmbeddingProviderKind::Ollama => OLLAMA_BASE_URL,
+        };
+        self.base_url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| default.to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
+
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+// Embeds a batch of texts, returning one vector per input in order
+pub async fn embed(config: &EmbeddingsConfig, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let inputs: Vec<&str> = texts.iter().map(|text| truncate(text)).collect();
+    let model = config.model();
+
+    let client = crate::network::client_builder()?
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let request = match config.provider {
+        EmbeddingProviderKind::OpenAi => {
+            let api_key = crate::secrets::get_api_key("openai")?
+                .ok_or("OpenAI API key is not configured".to_string())?;
+            client
+                .post(format!("{}/embeddings", config.base_url()))
+                .bearer_auth(api_key)
+                .json(&json!({ "model": model, "input": inputs }))
+        }
+        EmbeddingProviderKind::Ollama => client
+            .post(format!("{}/api/embed", config.base_url()))
+            .json(&json!({ "model": model, "input": inputs })),
+    };
+
+    let response = crate::network::send(request, "embeddings", &[])
+        .await
+        .map_err(|e| format!("Embedding request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Embedding request failed: {} - {}",
+            status, error_text
+        ));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse embedding response: {}", e))?;
+
+    // OpenAI: data[].embedding; Ollama: embeddings[]
+    let vectors: Vec<Vec<f32>> = match config.provider {
+        EmbeddingProviderKind::OpenAi => json
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.get("embedding"))
+                    .map(to_vector)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        EmbeddingProviderKind::Ollama => json
+            .get("embeddings")
+            .and_then(|d| d.as_array())
+            .map(|items| items.iter().map(to_vector).collect())
+            .unwrap_or_default(),
+    };
+
+    if vectors.len() != texts.len() {
+        return Err(format!(
+            "Embedding response had {} vectors for {} inputs",
+            vectors.len(),
+            texts.len()
+        ));
+    }
+    Ok(vectors)
+}
+
+fn to_vector(value: &serde_json::Value) -> Vec<f32> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_f64())
+                .map(|v| v as f32)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let mut dot = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
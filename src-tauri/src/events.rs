@@ -0,0 +1,434 @@
+// Pluely event payloads. The window and capture events carry these structs
+// instead of bare values, each stamped with the schema version so either side
+// can recognise a payload from a newer or older build. Their TypeScript types,
+// and those of every other event, are generated into src/bindings.ts along
+// with the command bindings.
+use base64::Engine;
+use serde::Serialize;
+use specta::Type;
+use tauri_specta::Event;
+
+use crate::placement::WindowMode;
+
+/// Bumped whenever a payload changes shape
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+}
+
+/// `captured-selection`: an area or whole-screen capture
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedSelection {
+    pub version: u32,
+    pub id: String,
+    // Index of the captured monitor, when known
+    pub monitor: Option<usize>,
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+    // Unix milliseconds
+    pub timestamp: i64,
+    // Base64 of the image
+    pub data: String,
+    // Base64 JPEG preview, sized by the thumbnail settings
+    pub thumbnail: Option<String>,
+}
+
+impl CapturedSelection {
+    pub fn png(monitor: Option<usize>, width: u32, height: u32, data: String) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            monitor,
+            width,
+            height,
+            format: ImageFormat::Png,
+            timestamp: now_ms(),
+            data,
+            thumbnail: None,
+        }
+    }
+
+    pub fn with_thumbnail(mut self, thumbnail: Option<String>) -> Self {
+        self.thumbnail = thumbnail;
+        self
+    }
+
+    /// A base64 PNG whose size is read from its header
+    pub fn from_png_base64(monitor: Option<usize>, data: String) -> Self {
+        // The IHDR chunk's width and height end at byte 24, which is 32 base64 chars
+        let header = data
+            .get(..32)
+            .and_then(|head| base64::engine::general_purpose::STANDARD.decode(head).ok())
+            .filter(|bytes| bytes.len() >= 24);
+        let (width, height) = header
+            .map(|bytes| {
+                (
+                    u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
+                    u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]),
+                )
+            })
+            .unwrap_or_default();
+        Self::png(monitor, width, height, data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureCloseReason {
+    // The user cancelled the selection
+    Cancelled,
+    // A new capture replaced overlays that were still open
+    Restarted,
+}
+
+/// `capture-closed`: the overlays closed without a capture
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureClosed {
+    pub version: u32,
+    pub reason: CaptureCloseReason,
+}
+
+impl CaptureClosed {
+    pub fn new(reason: CaptureCloseReason) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            reason,
+        }
+    }
+}
+
+/// `watch-frame`: a watched region changed since the last frame sent
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFrame {
+    pub version: u32,
+    pub id: String,
+    pub monitor: usize,
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+    // Hex perceptual hash the next frame is compared against
+    pub hash: String,
+    pub timestamp: i64,
+    // Base64 of the image
+    pub data: String,
+}
+
+impl WatchFrame {
+    pub fn png(monitor: usize, width: u32, height: u32, hash: String, data: String) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            monitor,
+            width,
+            height,
+            format: ImageFormat::Png,
+            hash,
+            timestamp: now_ms(),
+            data,
+        }
+    }
+}
+
+/// `screen-changed`: the change trigger saw enough of the screen change
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenChanged {
+    pub version: u32,
+    pub monitor: usize,
+    // None when the whole monitor is watched
+    pub region: Option<crate::capture::SelectionCoords>,
+    // Share of the screen that changed, 0.0 to 1.0
+    pub change: f64,
+    pub timestamp: i64,
+}
+
+impl ScreenChanged {
+    pub fn new(
+        monitor: usize,
+        region: Option<crate::capture::SelectionCoords>,
+        change: f64,
+    ) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            monitor,
+            region,
+            change,
+            timestamp: now_ms(),
+        }
+    }
+}
+
+/// `selection-changed`: the keyboard selection moved, in image pixels
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionChanged {
+    pub version: u32,
+    pub monitor_index: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<crate::capture::Selection> for SelectionChanged {
+    fn from(selection: crate::capture::Selection) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            monitor_index: selection.monitor_index,
+            x: selection.x,
+            y: selection.y,
+            width: selection.width,
+            height: selection.height,
+        }
+    }
+}
+
+/// `click-through-changed`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickThroughChanged {
+    pub version: u32,
+    pub enabled: bool,
+}
+
+impl ClickThroughChanged {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            enabled,
+        }
+    }
+}
+
+/// `window-mode-changed`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowModeChanged {
+    pub version: u32,
+    pub mode: WindowMode,
+}
+
+impl WindowModeChanged {
+    pub fn new(mode: WindowMode) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            mode,
+        }
+    }
+}
+
+/// `do-not-disturb-changed`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct DoNotDisturbChanged {
+    pub version: u32,
+    // None where the platform doesn't say
+    pub enabled: Option<bool>,
+}
+
+impl DoNotDisturbChanged {
+    pub fn new(enabled: Option<bool>) -> Self {
+        Self {
+            version: EVENT_SCHEMA_VERSION,
+            enabled,
+        }
+    }
+}
+
+// Events that carry a bare value, or a payload shared with a command, are
+// wrapped so each still has its own name in the bindings. A wrapper
+// serializes as the value it holds, so the payload is unchanged.
+
+/// `chat_stream_chunk`: text streamed by `chat_stream_response`
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ChatStreamChunk(pub String);
+
+// The chat stream events predate kebab-case names
+impl Event for ChatStreamChunk {
+    const NAME: &'static str = "chat_stream_chunk";
+}
+
+/// `chat_stream_complete`: the whole response once the stream ends
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ChatStreamComplete(pub String);
+
+impl Event for ChatStreamComplete {
+    const NAME: &'static str = "chat_stream_complete";
+}
+
+/// `toggle-window-visibility`: whether the main window is now hidden
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ToggleWindowVisibility(pub bool);
+
+/// `focus-text-input`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct FocusTextInput {}
+
+/// `start-audio-recording`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct StartAudioRecording {}
+
+/// `trigger-screenshot`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct TriggerScreenshot {}
+
+/// `toggle-system-audio`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ToggleSystemAudio {}
+
+/// `custom-shortcut-triggered`: a shortcut bound to a custom action
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct CustomShortcutTriggered {
+    pub action: String,
+}
+
+/// `shortcut-registration-error`: action, shortcut and error of each
+/// shortcut that couldn't be registered
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ShortcutRegistrationError(pub Vec<(String, String, String)>);
+
+/// `hotkeys-paused`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct HotkeysPaused(pub bool);
+
+/// `capture-started`: the sample rate of the audio that follows
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct CaptureStarted(pub u32);
+
+/// `capture-stopped`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct CaptureStopped;
+
+/// `speech-start`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SpeechStart;
+
+/// `speech-end`: metadata and audio of a finished utterance
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SpeechEnd(pub crate::speaker::SpeechSegment);
+
+/// `speech-detected`: base64 WAV of a finished utterance
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SpeechDetected(pub String);
+
+/// `speech-discarded`: why a segment was dropped
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SpeechDiscarded(pub String);
+
+/// `audio-encoding-error`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct AudioEncodingError(pub String);
+
+/// `continuous-recording-start`: the longest the recording may run, in seconds
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ContinuousRecordingStart(pub u64);
+
+/// `recording-progress`: seconds recorded so far
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RecordingProgress(pub u64);
+
+/// `continuous-recording-stopped`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ContinuousRecordingStopped;
+
+/// `manual-stop-continuous`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ManualStopContinuous;
+
+/// `recording-started`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RecordingStarted(pub crate::speaker::RecordingFormat);
+
+/// `recording-finished`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RecordingFinished(pub crate::speaker::RecordingSummary);
+
+/// `stt-partial`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SttPartial(pub crate::whisper::TranscriptUpdate);
+
+/// `stt-final`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SttFinal(pub crate::whisper::TranscriptUpdate);
+
+/// `stt-error`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SttError(pub String);
+
+/// `whisper-partial`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct WhisperPartial(pub crate::whisper::TranscriptUpdate);
+
+/// `whisper-final`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct WhisperFinal(pub crate::whisper::TranscriptUpdate);
+
+/// `realtime-error`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RealtimeError(pub String);
+
+/// `attachments-ingesting`: how many files are being read
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct AttachmentsIngesting(pub usize);
+
+/// `attachments-added`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct AttachmentsAdded(pub crate::attachments::IngestReport);
+
+/// `captures-cleaned`: the retention policy removed captures
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct CapturesCleaned(pub crate::captures::CleanupReport);
+
+/// `captures-purged`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct CapturesPurged(pub crate::wipe::PurgeReport);
+
+/// `history-encryption`: progress of encrypting or decrypting the history
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct HistoryEncryption(pub crate::storage::EncryptionProgress);
+
+/// `hook-finished`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct HookFinished(pub crate::hooks::HookRun);
+
+/// `user-idle`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct UserIdle(pub crate::idle::IdleStatus);
+
+/// `user-active`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct UserActive(pub crate::idle::IdleStatus);
+
+/// `fullscreen-entered`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct FullscreenEntered(pub crate::fullscreen::FullscreenApp);
+
+/// `fullscreen-exited`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct FullscreenExited(pub crate::fullscreen::FullscreenApp);
+
+/// `meeting-started`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct MeetingStarted(pub crate::meeting::Meeting);
+
+/// `meeting-ended`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct MeetingEnded(pub crate::meeting::Meeting);
+
+/// `power-status-changed`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct PowerStatusChanged(pub crate::power::PowerStatus);
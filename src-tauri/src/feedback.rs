@@ -0,0 +1,87 @@
+// Pluely capture feedback. Hotkey captures usually happen with the window
+// hidden, so a shutter sound confirms that a capture was taken. Each trigger
+// can be turned off in the `captureFeedback` settings section.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use tauri::AppHandle;
+use tracing::warn;
+
+const SHUTTER_SOUND: &[u8] = include_bytes!("../sounds/shutter.wav");
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureTrigger {
+    // `capture_selected_area`
+    Selection,
+    // `capture_to_base64`
+    Screen,
+    // `capture_camera_frame`
+    Camera,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureFeedbackSettings {
+    pub sound_on_selection: bool,
+    pub sound_on_screen: bool,
+    pub sound_on_camera: bool,
+    // 0.0 to 1.0
+    pub volume: f32,
+}
+
+impl Default for CaptureFeedbackSettings {
+    fn default() -> Self {
+        Self {
+            sound_on_selection: false,
+            sound_on_screen: false,
+            sound_on_camera: false,
+            volume: 0.5,
+        }
+    }
+}
+
+impl CaptureFeedbackSettings {
+    fn allows(&self, trigger: CaptureTrigger) -> bool {
+        match trigger {
+            CaptureTrigger::Selection => self.sound_on_selection,
+            CaptureTrigger::Screen => self.sound_on_screen,
+            CaptureTrigger::Camera => self.sound_on_camera,
+        }
+    }
+}
+
+fn play_shutter(volume: f32) -> Result<(), String> {
+    let (_stream, handle) = rodio::OutputStream::try_default()
+        .map_err(|e| format!("Failed to open audio output: {}", e))?;
+    let sink = rodio::Sink::try_new(&handle).map_err(|e| format!("Failed to play sound: {}", e))?;
+    let source = rodio::Decoder::new(Cursor::new(SHUTTER_SOUND))
+        .map_err(|e| format!("Failed to decode sound: {}", e))?;
+    sink.set_volume(volume.clamp(0.0, 1.0));
+    sink.append(source);
+    // The stream stops when dropped, so wait for the sound to finish
+    sink.sleep_until_end();
+    Ok(())
+}
+
+// Counts the capture and plays the shutter sound unless `trigger` is off. It
+// plays on its own thread and failures are only logged, so feedback never
+// holds up a capture.
+pub fn capture_taken(app: &AppHandle, trigger: CaptureTrigger) {
+    crate::analytics::count(match trigger {
+        CaptureTrigger::Selection => "capture.selection",
+        CaptureTrigger::Screen => "capture.screen",
+        CaptureTrigger::Camera => "capture.camera",
+    });
+    let settings = crate::settings::current_settings(app)
+        .map(|settings| settings.capture_feedback)
+        .unwrap_or_default();
+    if !settings.allows(trigger) {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Err(e) = play_shutter(settings.volume) {
+            warn!("{}", e);
+        }
+    });
+}
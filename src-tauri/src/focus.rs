@@ -0,0 +1,281 @@
+// Pluely focus tracking. An opt-in watcher remembers which application and
+// window the user was in before switching to Pluely, so questions are saved
+// with that context and the model can be told "the user is in VS Code editing
+// main.rs". Window titles can be sensitive; nothing is recorded unless enabled.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::debug;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FocusSettings {
+    // Off unless the user opts in
+    pub track: bool,
+    // Tells the model which app the user is in
+    pub add_to_prompt: bool,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            track: false,
+            add_to_prompt: true,
+            poll_interval_ms: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusedApp {
+    pub app_name: String,
+    pub title: String,
+    // Unix milliseconds of the last time it was seen focused
+    pub seen_at: i64,
+}
+
+#[derive(Default)]
+pub struct FocusState {
+    settings: Mutex<FocusSettings>,
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    // The last focused window that wasn't Pluely's own
+    last: Mutex<Option<FocusedApp>>,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// None while Pluely itself is focused or the platform can't tell (Wayland)
+pub(crate) fn focused_window() -> Option<FocusedApp> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    if window.process_id == std::process::id() as u64 {
+        return None;
+    }
+    Some(FocusedApp {
+        app_name: window.app_name.trim().to_string(),
+        title: window.title.trim().to_string(),
+        seen_at: now_ms(),
+    })
+}
+
+// A window to hand focus back to once Pluely's overlays close
+#[derive(Debug, Clone)]
+pub struct PreviousWindow {
+    #[cfg(target_os = "windows")]
+    hwnd: isize,
+    #[cfg(target_os = "macos")]
+    pid: u64,
+    #[cfg(target_os = "linux")]
+    xid: String,
+}
+
+/// The window in front right now, unless it is Pluely's own
+#[cfg(target_os = "windows")]
+pub fn foreground_window() -> Option<PreviousWindow> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0 == 0 {
+        return None;
+    }
+    let mut pid = 0;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if pid == std::process::id() {
+        return None;
+    }
+    Some(PreviousWindow { hwnd: hwnd.0 })
+}
+
+#[cfg(target_os = "macos")]
+pub fn foreground_window() -> Option<PreviousWindow> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    if window.process_id == std::process::id() as u64 {
+        return None;
+    }
+    Some(PreviousWindow {
+        pid: window.process_id,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn xdotool(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("xdotool")
+        .args(args)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !stdout.is_empty()).then_some(stdout)
+}
+
+// Only X11 (and XWayland) windows can be looked up and activated
+#[cfg(target_os = "linux")]
+pub fn foreground_window() -> Option<PreviousWindow> {
+    let xid = xdotool(&["getactivewindow"])?;
+    if xdotool(&["getwindowpid", &xid]) == Some(std::process::id().to_string()) {
+        return None;
+    }
+    Some(PreviousWindow { xid })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn foreground_window() -> Option<PreviousWindow> {
+    None
+}
+
+/// Brings `previous` back to the front. Failures are only logged: the window
+/// may have closed in the meantime.
+pub fn restore_focus(previous: &PreviousWindow) {
+    if let Err(e) = activate(previous) {
+        debug!("Failed to restore focus: {}", e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn activate(previous: &PreviousWindow) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{IsWindow, SetForegroundWindow};
+    let hwnd = HWND(previous.hwnd);
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+        return Err("window is gone".to_string());
+    }
+    // Allowed because Pluely is the foreground process while its overlay is up
+    if !unsafe { SetForegroundWindow(hwnd) }.as_bool() {
+        return Err("SetForegroundWindow was refused".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn activate(previous: &PreviousWindow) -> Result<(), String> {
+    let script = format!(
+        "tell application \"System Events\" to set frontmost of (first process whose unix id is {}) to true",
+        previous.pid
+    );
+    let status = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .status()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    status
+        .success()
+        .then_some(())
+        .ok_or_else(|| format!("osascript exited with {}", status))
+}
+
+#[cfg(target_os = "linux")]
+fn activate(previous: &PreviousWindow) -> Result<(), String> {
+    let status = std::process::Command::new("xdotool")
+        .args(["windowactivate", &previous.xid])
+        .status()
+        .map_err(|e| format!("Failed to run xdotool: {}", e))?;
+    status
+        .success()
+        .then_some(())
+        .ok_or_else(|| format!("xdotool exited with {}", status))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn activate(_previous: &PreviousWindow) -> Result<(), String> {
+    Ok(())
+}
+
+fn watch(app: AppHandle, interval: Duration, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::SeqCst) {
+        if crate::power::background_paused() {
+            std::thread::sleep(interval);
+            continue;
+        }
+        if let Some(focused) = focused_window() {
+            if let Ok(mut last) = app.state::<FocusState>().last.lock() {
+                *last = Some(focused);
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+// Starts or stops the watcher to match `settings`
+pub fn apply_focus_settings(app: &AppHandle, settings: &FocusSettings) -> Result<(), String> {
+    if !(250..=10_000).contains(&settings.poll_interval_ms) {
+        return Err("Invalid focus poll interval: must be 250-10000 ms".to_string());
+    }
+    let state = app.state::<FocusState>();
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = settings.clone();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Restarted so a new interval takes effect
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if settings.track {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let interval = Duration::from_millis(settings.poll_interval_ms);
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, interval, flag));
+        *watcher = Some(stop);
+    } else {
+        // Turning tracking off forgets what was seen
+        *state
+            .last
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))? = None;
+    }
+    Ok(())
+}
+
+/// The app the user was last in, or None when tracking is off
+pub fn last_focused(app: &AppHandle) -> Option<FocusedApp> {
+    let state = app.state::<FocusState>();
+    if !state.settings.lock().ok()?.track {
+        return None;
+    }
+    let last = state.last.lock().ok()?;
+    last.clone()
+}
+
+/// A system prompt line describing the focused app, when enabled
+pub fn context_prompt(app: &AppHandle) -> Option<String> {
+    let add_to_prompt = app
+        .state::<FocusState>()
+        .settings
+        .lock()
+        .ok()?
+        .add_to_prompt;
+    let focused = last_focused(app).filter(|_| add_to_prompt)?;
+    Some(describe(&focused))
+}
+
+pub fn describe(focused: &FocusedApp) -> String {
+    if focused.title.is_empty() || focused.title == focused.app_name {
+        format!(
+            "Context: the user was last working in {}.",
+            focused.app_name
+        )
+    } else {
+        format!(
+            "Context: the user was last working in {}, in the window \"{}\".",
+            focused.app_name, focused.title
+        )
+    }
+}
+
+/// Tauri command returning the app the user was last in, if tracked
+#[tauri::command]
+#[specta::specta]
+pub fn get_focused_app(app: AppHandle) -> Result<Option<FocusedApp>, String> {
+    Ok(last_focused(&app))
+}
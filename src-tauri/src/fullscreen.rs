@@ -0,0 +1,197 @@
+// Pluely fullscreen detection. A watcher notices when the focused app covers
+// its whole monitor (a game, a slideshow, a fullscreen video) and emits events,
+// so Pluely can keep its overlays and notifications out of the way until the
+// app leaves fullscreen.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, warn};
+
+// Window edges within this many pixels of the monitor's still count as covering it
+const EDGE_TOLERANCE: f64 = 2.0;
+
+// Mirrors the settings so notifications can check without the settings lock
+static SUPPRESS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FullscreenSettings {
+    pub detect: bool,
+    // Shows overlays and notifications over fullscreen apps anyway
+    pub allow_overlays: bool,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FullscreenSettings {
+    fn default() -> Self {
+        Self {
+            detect: true,
+            allow_overlays: false,
+            poll_interval_ms: 2_000,
+        }
+    }
+}
+
+// Payload of `fullscreen-entered` and `fullscreen-exited`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FullscreenApp {
+    pub app_name: String,
+    // Exclusive fullscreen (Direct3D games) or presentation mode on Windows
+    pub exclusive: bool,
+}
+
+#[derive(Default)]
+pub struct FullscreenState {
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    current: Mutex<Option<FullscreenApp>>,
+}
+
+// Games and slideshows that take the display exclusively; their windows can
+// report any size, so Windows is asked directly
+#[cfg(target_os = "windows")]
+fn exclusive_fullscreen() -> bool {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+    unsafe { SHQueryUserNotificationState() }
+        .is_ok_and(|state| state == QUNS_RUNNING_D3D_FULL_SCREEN || state == QUNS_PRESENTATION_MODE)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn exclusive_fullscreen() -> bool {
+    false
+}
+
+// Whether the focused window covers a whole monitor. The window and monitor
+// sizes are in the same units (points on macOS, pixels elsewhere).
+fn covers_monitor(window: &active_win_pos_rs::ActiveWindow) -> bool {
+    let Ok(monitors) = xcap::Monitor::all() else {
+        return false;
+    };
+    let position = &window.position;
+    monitors.iter().any(|monitor| {
+        position.x <= monitor.x() as f64 + EDGE_TOLERANCE
+            && position.y <= monitor.y() as f64 + EDGE_TOLERANCE
+            && position.x + position.width
+                >= (monitor.x() + monitor.width() as i32) as f64 - EDGE_TOLERANCE
+            && position.y + position.height
+                >= (monitor.y() + monitor.height() as i32) as f64 - EDGE_TOLERANCE
+    })
+}
+
+// The fullscreen app in front, if any. Pluely's own windows never count.
+fn detect() -> Option<FullscreenApp> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    if window.process_id == std::process::id() as u64 {
+        return None;
+    }
+    let exclusive = exclusive_fullscreen();
+    if !exclusive && !covers_monitor(&window) {
+        return None;
+    }
+    Some(FullscreenApp {
+        app_name: window.app_name.trim().to_string(),
+        exclusive,
+    })
+}
+
+fn set_current(app: &AppHandle, detected: Option<FullscreenApp>) {
+    let state = app.state::<FullscreenState>();
+    let Ok(mut current) = state.current.lock() else {
+        return;
+    };
+    if *current == detected {
+        return;
+    }
+    let (event, result) = match (&detected, current.as_ref()) {
+        (Some(entered), _) => {
+            debug!("fullscreen-entered: {}", entered.app_name);
+            let entered = crate::events::FullscreenEntered(entered.clone());
+            (
+                "fullscreen-entered",
+                app.emit("fullscreen-entered", entered),
+            )
+        }
+        (None, Some(exited)) => {
+            debug!("fullscreen-exited: {}", exited.app_name);
+            let exited = crate::events::FullscreenExited(exited.clone());
+            ("fullscreen-exited", app.emit("fullscreen-exited", exited))
+        }
+        (None, None) => return,
+    };
+    *current = detected;
+    if let Err(e) = result {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+fn watch(app: AppHandle, interval: Duration, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::SeqCst) {
+        if !crate::power::background_paused() {
+            set_current(&app, detect());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+// Starts or stops the watcher to match `settings`
+pub fn apply_fullscreen_settings(
+    app: &AppHandle,
+    settings: &FullscreenSettings,
+) -> Result<(), String> {
+    if !(500..=10_000).contains(&settings.poll_interval_ms) {
+        return Err("Invalid fullscreen poll interval: must be 500-10000 ms".to_string());
+    }
+    SUPPRESS.store(
+        settings.detect && !settings.allow_overlays,
+        Ordering::SeqCst,
+    );
+    let state = app.state::<FullscreenState>();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Restarted so a new interval takes effect
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if settings.detect {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let interval = Duration::from_millis(settings.poll_interval_ms);
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, interval, flag));
+        *watcher = Some(stop);
+    } else {
+        set_current(app, None);
+    }
+    Ok(())
+}
+
+/// Whether overlays and notifications should stay hidden because a
+/// fullscreen app is in front
+pub fn suppress_overlays(app: &AppHandle) -> bool {
+    SUPPRESS.load(Ordering::SeqCst)
+        && app
+            .state::<FullscreenState>()
+            .current
+            .lock()
+            .is_ok_and(|current| current.is_some())
+}
+
+/// Tauri command returning the fullscreen app in front, if any
+#[tauri::command]
+#[specta::specta]
+pub fn get_fullscreen_app(app: AppHandle) -> Result<Option<FullscreenApp>, String> {
+    let state = app.state::<FullscreenState>();
+    let current = state
+        .current
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(current.clone())
+}
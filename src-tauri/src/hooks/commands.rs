@@ -0,0 +1,78 @@
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use super::{record, run, HookEvent, HookInput, HookRun, HooksState};
+
+// Runs one saved hook now with sample values for `event` (the hook's first
+// event when None), and records the run like any other
+#[tauri::command]
+#[specta::specta]
+pub async fn test_hook(
+    app: AppHandle,
+    id: String,
+    event: Option<HookEvent>,
+) -> Result<HookRun, String> {
+    let hook = app
+        .state::<HooksState>()
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .hooks
+        .iter()
+        .find(|hook| hook.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Hook not found: {}", id))?;
+
+    let event = event
+        .or_else(|| hook.events.first().copied())
+        .unwrap_or(HookEvent::CaptureSaved);
+    let input = match event {
+        HookEvent::CaptureSaved => HookInput {
+            event,
+            id: "test.png".to_string(),
+            path: Some(String::new()),
+            text: None,
+            data: json!({ "test": true }),
+        },
+        HookEvent::AnswerCompleted => {
+            let text = "This is a test answer from Pluely.".to_string();
+            HookInput {
+                event,
+                id: "test".to_string(),
+                path: None,
+                data: json!({ "requestId": "test", "text": text, "test": true }),
+                text: Some(text),
+            }
+        }
+    };
+    let hook_run = run(&hook, &input).await;
+    record(&app, hook_run.clone());
+    Ok(hook_run)
+}
+
+// Recent runs, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn get_hook_runs(app: AppHandle) -> Result<Vec<HookRun>, String> {
+    let runs = app
+        .state::<HooksState>()
+        .runs
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+    Ok(runs)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_hook_runs(app: AppHandle) -> Result<(), String> {
+    app.state::<HooksState>()
+        .runs
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clear();
+    Ok(())
+}
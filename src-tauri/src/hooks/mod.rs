@@ -0,0 +1,396 @@
+// Pluely hooks. User commands run when a capture is saved or an answer
+// completes, for automations the built-in integrations don't cover.
+//
+// The program is started directly, not through a shell. Arguments may use
+// {event}, {id} (the capture's or the answer request's), {path} (the capture)
+// and {text} (the answer); the same values are set as PLUELY_EVENT, PLUELY_ID,
+// PLUELY_PATH and PLUELY_TEXT, and the event's JSON is written to stdin.
+// Output is kept with each run.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::control::BridgeEvent;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+const MAX_TIMEOUT_SECS: u64 = 600;
+// Output past this is dropped, per stream
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+// Runs kept for the settings page
+const MAX_RUNS: usize = 50;
+// How long to wait for output once a timed-out hook was killed; programs it
+// started may still hold the pipes open
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    CaptureSaved,
+    AnswerCompleted,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CaptureSaved => "capture-saved",
+            Self::AnswerCompleted => "answer-completed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hook {
+    pub id: String,
+    pub enabled: bool,
+    pub events: Vec<HookEvent>,
+    // Program to run, by path or from PATH
+    pub command: String,
+    pub args: Vec<String>,
+    pub working_dir: Option<String>,
+    // The hook is killed after this long
+    pub timeout_secs: u64,
+}
+
+impl Default for Hook {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            enabled: true,
+            events: vec![HookEvent::CaptureSaved, HookEvent::AnswerCompleted],
+            command: String::new(),
+            args: Vec::new(),
+            working_dir: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookSettings {
+    pub hooks: Vec<Hook>,
+}
+
+impl HookSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for hook in &self.hooks {
+            if hook.id.trim().is_empty() {
+                return Err("Hook id cannot be empty".to_string());
+            }
+            if !ids.insert(hook.id.as_str()) {
+                return Err(format!("Duplicate hook id: {}", hook.id));
+            }
+            if hook.command.trim().is_empty() {
+                return Err(format!("Hook {} has no command", hook.id));
+            }
+            if hook.timeout_secs == 0 || hook.timeout_secs > MAX_TIMEOUT_SECS {
+                return Err(format!(
+                    "Invalid hook timeout: must be 1-{} seconds",
+                    MAX_TIMEOUT_SECS
+                ));
+            }
+            if let Some(dir) = hook.working_dir.as_deref().filter(|d| !d.trim().is_empty()) {
+                if !Path::new(dir.trim()).is_dir() {
+                    return Err(format!("Hook working directory not found: {}", dir));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.hooks
+            .iter()
+            .any(|hook| hook.enabled && !hook.events.is_empty())
+    }
+}
+
+#[derive(Default)]
+pub struct HooksState {
+    settings: Mutex<HookSettings>,
+    // Runs hooks on bridge events while any is enabled
+    worker: Mutex<Option<JoinHandle<()>>>,
+    // Newest last
+    runs: Mutex<VecDeque<HookRun>>,
+}
+
+// Emitted as `hook-finished` after every run
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    pub id: String,
+    pub event: String,
+    // Unix milliseconds
+    pub started_at: i64,
+    pub duration_ms: u64,
+    // None when the hook didn't start, timed out or was killed by a signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    // Why the hook couldn't be started
+    pub error: Option<String>,
+}
+
+impl HookRun {
+    fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+// What a hook is run with
+pub struct HookInput {
+    pub event: HookEvent,
+    pub id: String,
+    pub path: Option<String>,
+    pub text: Option<String>,
+    // Written to stdin as JSON
+    pub data: Value,
+}
+
+// Validates `settings` and starts or stops running hooks
+pub fn apply_hook_settings(app: &AppHandle, settings: &HookSettings) -> Result<(), String> {
+    settings.validate()?;
+    let active = settings.is_active();
+    let state = app.state::<HooksState>();
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = settings.clone();
+
+    let mut worker = state
+        .worker
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    match (active, worker.is_some()) {
+        (true, false) => *worker = Some(tauri::async_runtime::spawn(forward_events(app.clone()))),
+        (false, true) => {
+            if let Some(worker) = worker.take() {
+                worker.abort();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn forward_events(app: AppHandle) {
+    let mut events = crate::control::subscribe();
+    loop {
+        match events.recv().await {
+            Ok(message) => {
+                let input = match message.event {
+                    BridgeEvent::CaptureCompleted(file) => HookInput {
+                        event: HookEvent::CaptureSaved,
+                        // Captures are known by their file name
+                        id: Path::new(&file.path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        path: Some(file.path.clone()),
+                        text: None,
+                        data: json!(file),
+                    },
+                    BridgeEvent::LlmDone { request_id, text } => HookInput {
+                        event: HookEvent::AnswerCompleted,
+                        data: json!({ "requestId": request_id, "text": text }),
+                        id: request_id,
+                        path: None,
+                        text: Some(text),
+                    },
+                    _ => continue,
+                };
+                dispatch(&app, input);
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!("Hooks missed {} events", missed);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// Runs every hook subscribed to the event, each on its own task so a slow
+// hook doesn't hold up the others
+fn dispatch(app: &AppHandle, input: HookInput) {
+    let state = app.state::<HooksState>();
+    let Ok(settings) = state.settings.lock().map(|settings| settings.clone()) else {
+        return;
+    };
+    let input = std::sync::Arc::new(input);
+    for hook in settings
+        .hooks
+        .into_iter()
+        .filter(|hook| hook.enabled && hook.events.contains(&input.event))
+    {
+        let app = app.clone();
+        let input = input.clone();
+        tauri::async_runtime::spawn(async move {
+            let run = run(&hook, &input).await;
+            record(&app, run);
+        });
+    }
+}
+
+// Keeps the run and tells the frontend about it
+fn record(app: &AppHandle, run: HookRun) {
+    if !run.succeeded() {
+        let reason = match (&run.error, run.timed_out, run.exit_code) {
+            (Some(error), _, _) => error.clone(),
+            (None, true, _) => "timed out".to_string(),
+            (None, false, Some(code)) => format!("exited with {}", code),
+            (None, false, None) => "killed".to_string(),
+        };
+        warn!("Hook {} failed: {}", run.id, reason);
+    }
+    if let Ok(mut runs) = app.state::<HooksState>().runs.lock() {
+        if runs.len() >= MAX_RUNS {
+            runs.pop_front();
+        }
+        runs.push_back(run.clone());
+    }
+    if let Err(e) = app.emit("hook-finished", crate::events::HookFinished(run)) {
+        warn!("Failed to emit hook run: {}", e);
+    }
+}
+
+// Replaces placeholders in one pass, so values containing braces are left as
+// they are
+fn expand(arg: &str, input: &HookInput) -> String {
+    let mut out = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let value = tail.find('}').and_then(|end| {
+            let value = match &tail[1..end] {
+                "event" => input.event.name(),
+                "id" => &input.id,
+                "path" => input.path.as_deref().unwrap_or_default(),
+                "text" => input.text.as_deref().unwrap_or_default(),
+                _ => return None,
+            };
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// Reads a pipe to the end, keeping the first MAX_OUTPUT_BYTES. The rest is
+// still read so the hook doesn't block on a full pipe.
+async fn read_output(mut pipe: impl AsyncRead + Unpin) -> String {
+    let mut kept = Vec::new();
+    let mut chunk = [0u8; 8192];
+    while let Ok(read) = pipe.read(&mut chunk).await {
+        if read == 0 {
+            break;
+        }
+        let room = MAX_OUTPUT_BYTES.saturating_sub(kept.len());
+        kept.extend_from_slice(&chunk[..read.min(room)]);
+    }
+    String::from_utf8_lossy(&kept).to_string()
+}
+
+async fn run(hook: &Hook, input: &HookInput) -> HookRun {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+    let started = Instant::now();
+    let mut run = HookRun {
+        id: hook.id.clone(),
+        event: input.event.name().to_string(),
+        started_at,
+        duration_ms: 0,
+        exit_code: None,
+        timed_out: false,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+    };
+
+    let mut command = tokio::process::Command::new(hook.command.trim());
+    command
+        .args(hook.args.iter().map(|arg| expand(arg, input)))
+        .env("PLUELY_EVENT", input.event.name())
+        .env("PLUELY_ID", &input.id)
+        .env("PLUELY_PATH", input.path.as_deref().unwrap_or_default())
+        .env("PLUELY_TEXT", input.text.as_deref().unwrap_or_default())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = hook.working_dir.as_deref().filter(|d| !d.trim().is_empty()) {
+        command.current_dir(dir.trim());
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            run.error = Some(format!("Failed to start {}: {}", hook.command, e));
+            return run;
+        }
+    };
+
+    // Hooks that don't read stdin close it early; that isn't an error
+    if let Some(mut stdin) = child.stdin.take() {
+        let data = input.data.to_string();
+        tauri::async_runtime::spawn(async move {
+            let _ = stdin.write_all(data.as_bytes()).await;
+        });
+    }
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| tauri::async_runtime::spawn(read_output(pipe)));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| tauri::async_runtime::spawn(read_output(pipe)));
+
+    match tokio::time::timeout(Duration::from_secs(hook.timeout_secs), child.wait()).await {
+        Ok(Ok(status)) => run.exit_code = status.code(),
+        Ok(Err(e)) => run.error = Some(format!("Failed to wait for hook: {}", e)),
+        Err(_) => {
+            run.timed_out = true;
+            if let Err(e) = child.kill().await {
+                warn!("Failed to kill hook {}: {}", hook.id, e);
+            }
+        }
+    }
+    for (reader, output) in [(stdout, &mut run.stdout), (stderr, &mut run.stderr)] {
+        if let Some(reader) = reader {
+            if let Ok(Ok(text)) = tokio::time::timeout(DRAIN_TIMEOUT, reader).await {
+                *output = text;
+            }
+        }
+    }
+    run.duration_ms = started.elapsed().as_millis() as u64;
+    run
+}
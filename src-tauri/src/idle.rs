@@ -0,0 +1,171 @@
+// Pluely idle detection. A watcher reads how long the user has gone without
+// keyboard or mouse input and emits `user-idle` once it passes the threshold
+// and `user-active` when input returns, so interval captures and audio capture
+// can pause. A running realtime session can be paused and resumed here.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, warn};
+
+use crate::events::{UserActive, UserIdle};
+
+// How often idle time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleSettings {
+    // Off unless the user opts in
+    pub enabled: bool,
+    // No input for this long counts as idle
+    pub idle_after_secs: u64,
+    // Stops a realtime session while idle and starts it again on activity
+    pub pause_realtime: bool,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_after_secs: 300,
+            pause_realtime: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct IdleState {
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    idle: AtomicBool,
+    // Set when going idle stopped a realtime session
+    paused_realtime: AtomicBool,
+}
+
+// Payload of `user-idle` and `user-active`
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    pub idle: bool,
+    // Seconds since the last input
+    pub idle_secs: u64,
+}
+
+fn idle_secs() -> Result<u64, String> {
+    user_idle::UserIdle::get_time()
+        .map(|idle| idle.as_seconds())
+        .map_err(|e| format!("Failed to read idle time: {}", e))
+}
+
+fn went_idle(app: &AppHandle, settings: &IdleSettings) {
+    let state = app.state::<IdleState>();
+    if settings.pause_realtime {
+        match crate::realtime::stop_realtime_session(app.clone()) {
+            Ok(stopped) => state.paused_realtime.store(stopped, Ordering::SeqCst),
+            Err(e) => warn!("Failed to pause realtime session: {}", e),
+        }
+    }
+}
+
+fn became_active(app: &AppHandle) {
+    let state = app.state::<IdleState>();
+    if state.paused_realtime.swap(false, Ordering::SeqCst) {
+        if let Err(e) = crate::realtime::start_realtime_session(app.clone()) {
+            warn!("Failed to resume realtime session: {}", e);
+        }
+    }
+}
+
+fn watch(app: AppHandle, settings: IdleSettings, stop: Arc<AtomicBool>) {
+    let mut reported_error = false;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let idle_secs = match idle_secs() {
+            Ok(secs) => secs,
+            Err(e) => {
+                // Some Wayland compositors expose no idle time; say so once
+                if !reported_error {
+                    warn!("{}", e);
+                    reported_error = true;
+                }
+                continue;
+            }
+        };
+        let idle = idle_secs >= settings.idle_after_secs;
+        let state = app.state::<IdleState>();
+        if state.idle.swap(idle, Ordering::SeqCst) == idle {
+            continue;
+        }
+        debug!(
+            "User is {} after {}s",
+            if idle { "idle" } else { "active" },
+            idle_secs
+        );
+        if idle {
+            went_idle(&app, &settings);
+        } else {
+            became_active(&app);
+        }
+        let status = IdleStatus { idle, idle_secs };
+        let (event, result) = if idle {
+            ("user-idle", app.emit("user-idle", UserIdle(status)))
+        } else {
+            ("user-active", app.emit("user-active", UserActive(status)))
+        };
+        if let Err(e) = result {
+            warn!("Failed to emit {}: {}", event, e);
+        }
+    }
+}
+
+// Starts or stops the watcher to match `settings`
+pub fn apply_idle_settings(app: &AppHandle, settings: &IdleSettings) -> Result<(), String> {
+    if !(30..=86_400).contains(&settings.idle_after_secs) {
+        return Err("Invalid idle time: must be 30-86400 seconds".to_string());
+    }
+    let state = app.state::<IdleState>();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Restarted so a new threshold takes effect
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    // Whatever idling paused comes back when detection changes
+    if state.idle.swap(false, Ordering::SeqCst) {
+        became_active(app);
+        let _ = app.emit(
+            "user-active",
+            UserActive(IdleStatus {
+                idle: false,
+                idle_secs: 0,
+            }),
+        );
+    }
+    if settings.enabled {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let settings = settings.clone();
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, settings, flag));
+        *watcher = Some(stop);
+    }
+    Ok(())
+}
+
+/// Tauri command returning whether the user is idle and for how long
+#[tauri::command]
+#[specta::specta]
+pub fn get_idle_status(app: AppHandle) -> Result<IdleStatus, String> {
+    Ok(IdleStatus {
+        idle: app.state::<IdleState>().idle.load(Ordering::SeqCst),
+        idle_secs: idle_secs()?,
+    })
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::warn;
+
+use super::{notion, notion_page_id, obsidian, Note, NoteImage, NOTION_KEY_ID};
+
+// Longest title taken from the question
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteDestination {
+    Obsidian,
+    Notion,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SentNote {
+    pub destination: NoteDestination,
+    // The Markdown file written, or the Notion page's link
+    pub location: String,
+}
+
+fn title_from(question: Option<&str>, fallback: &str) -> String {
+    question
+        .and_then(|question| question.lines().map(str::trim).find(|l| !l.is_empty()))
+        .map(|line| {
+            let mut title: String = line.chars().take(MAX_TITLE_CHARS).collect();
+            if title.len() < line.len() {
+                title.push('…');
+            }
+            title
+        })
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+// The answer `message_id` with the user's question before it and the images
+// captured for either
+async fn note_for(
+    app: &AppHandle,
+    conversation_id: &str,
+    message_id: &str,
+) -> Result<Note, String> {
+    let conversation = crate::storage::load_conversation(app.clone(), conversation_id.to_string())
+        .await?
+        .ok_or_else(|| format!("Conversation not found: {}", conversation_id))?;
+    let index = conversation
+        .messages
+        .iter()
+        .position(|message| message.id == message_id)
+        .ok_or_else(|| format!("Message not found: {}", message_id))?;
+    let answer = &conversation.messages[index];
+    let question = conversation.messages[..index]
+        .iter()
+        .rev()
+        .find(|message| message.role == "user");
+
+    let mut images = Vec::new();
+    for capture in question
+        .into_iter()
+        .chain([answer])
+        .flat_map(|m| &m.captures)
+    {
+        match crate::storage::read_file(Path::new(&capture.path)) {
+            // Recordings have no place in a note
+            Ok(bytes) if matches!(crate::captures::extension_for(&bytes), "png" | "jpg") => {
+                let name = Path::new(&capture.path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "capture".to_string());
+                images.push(NoteImage { name, bytes });
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Leaving capture {} out of note: {}", capture.path, e),
+        }
+    }
+
+    Ok(Note {
+        title: title_from(
+            question.map(|message| message.content.as_str()),
+            &conversation.title,
+        ),
+        question: question.map(|message| message.content.clone()),
+        answer: answer.content.clone(),
+        model: answer.model.clone().or_else(|| conversation.model.clone()),
+        conversation_id: conversation.id.clone(),
+        created_at: answer.timestamp,
+        images,
+    })
+}
+
+/// Sends a saved answer, the question before it and their captures to the
+/// user's notes, returning where the note went
+#[tauri::command]
+#[specta::specta]
+pub async fn send_answer_to_notes(
+    app: AppHandle,
+    destination: NoteDestination,
+    conversation_id: String,
+    message_id: String,
+) -> Result<SentNote, String> {
+    let settings = crate::settings::current_settings(&app)?.integrations;
+    let note = note_for(&app, &conversation_id, &message_id).await?;
+    let location = match destination {
+        NoteDestination::Obsidian => tauri::async_runtime::spawn_blocking(move || {
+            obsidian::write_note(&note, &settings.obsidian)
+        })
+        .await
+        .map_err(|e| format!("Failed to write Obsidian note: {}", e))??
+        .to_string_lossy()
+        .to_string(),
+        NoteDestination::Notion => {
+            crate::network::ensure_online("Sending notes to Notion")?;
+            let token = crate::secrets::get_api_key(NOTION_KEY_ID)?
+                .ok_or("Add a Notion integration token in settings first")?;
+            let parent = notion_page_id(&settings.notion.parent_page)
+                .ok_or("Choose a Notion page to add notes to in settings first")?;
+            notion::create_page(&note, &token, &parent).await?
+        }
+    };
+    Ok(SentNote {
+        destination,
+        location,
+    })
+}
@@ -0,0 +1,146 @@
+// Pluely note integrations. An answer, the question it answered and their
+// captures can be sent to the user's notes: as a Markdown file in an Obsidian
+// vault, or as a page in Notion.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Component, Path};
+
+mod commands;
+mod notion;
+mod obsidian;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain entry holding the Notion integration token
+pub const NOTION_KEY_ID: &str = "notion";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObsidianSettings {
+    // Root of the vault; None until the user picks one
+    pub vault_path: Option<String>,
+    // Where notes and their images go, relative to the vault
+    pub folder: String,
+    pub attachments_folder: String,
+    // Written to the note's front matter
+    pub tags: Vec<String>,
+}
+
+impl Default for ObsidianSettings {
+    fn default() -> Self {
+        Self {
+            vault_path: None,
+            folder: "Pluely".to_string(),
+            attachments_folder: "Pluely/attachments".to_string(),
+            tags: vec!["pluely".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotionSettings {
+    // Written to the keychain, never kept in the config; empty removes it
+    pub token: Option<String>,
+    // Page notes are created under, as an id or a link to it. The page must be
+    // shared with the integration.
+    pub parent_page: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IntegrationSettings {
+    pub obsidian: ObsidianSettings,
+    pub notion: NotionSettings,
+}
+
+// A folder inside the vault; absolute paths and `..` could write elsewhere
+fn validate_folder(folder: &str, what: &str) -> Result<(), String> {
+    let path = Path::new(folder.trim());
+    if path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!(
+            "Invalid {}: must be a folder inside the vault",
+            what
+        ));
+    }
+    Ok(())
+}
+
+/// The 32-digit id in a Notion page id or link, in the dashed form the API
+/// takes
+pub fn notion_page_id(page: &str) -> Option<String> {
+    // Links end in the id, after the page's title
+    let page = page.trim().split(['?', '#']).next()?;
+    let hex: String = page
+        .chars()
+        .rev()
+        .filter(|c| *c != '-')
+        .take_while(char::is_ascii_hexdigit)
+        .take(32)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    if hex.len() != 32 {
+        return None;
+    }
+    let hex = hex.to_lowercase();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+impl IntegrationSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_folder(&self.obsidian.folder, "Obsidian folder")?;
+        validate_folder(
+            &self.obsidian.attachments_folder,
+            "Obsidian attachments folder",
+        )?;
+        if self.obsidian.tags.iter().any(|tag| {
+            tag.trim().is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',')
+        }) {
+            return Err("Obsidian tags cannot be empty or contain spaces".to_string());
+        }
+        if !self.notion.parent_page.trim().is_empty()
+            && notion_page_id(&self.notion.parent_page).is_none()
+        {
+            return Err(format!("Invalid Notion page: {}", self.notion.parent_page));
+        }
+        Ok(())
+    }
+}
+
+// Validates the section and moves the Notion token into the keychain
+pub fn apply_integration_settings(settings: &IntegrationSettings) -> Result<(), String> {
+    settings.validate()?;
+    if let Some(token) = &settings.notion.token {
+        crate::secrets::store_api_key(NOTION_KEY_ID, token)?;
+    }
+    Ok(())
+}
+
+pub struct NoteImage {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+// What gets sent: an answer, the question before it and their captures
+pub struct Note {
+    pub title: String,
+    pub question: Option<String>,
+    pub answer: String,
+    pub model: Option<String>,
+    pub conversation_id: String,
+    pub created_at: i64,
+    pub images: Vec<NoteImage>,
+}
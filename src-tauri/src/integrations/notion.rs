@@ -0,0 +1,333 @@
+// Notes as Notion pages, created under a page shared with the user's
+// integration. Captures go up through Notion's file uploads first.
+use reqwest::multipart::{Form, Part};
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::{Note, NoteImage};
+use crate::captures::http_date;
+
+const API_URL: &str = "https://api.notion.com/v1";
+const API_VERSION: &str = "2022-06-28";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Limits of the API: characters in one text object, text objects in one
+// block, blocks in one request
+const MAX_TEXT_CHARS: usize = 2000;
+const MAX_TEXT_OBJECTS: usize = 100;
+const MAX_BLOCKS: usize = 100;
+
+// Code languages Notion highlights; others are shown as plain text
+const LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c#",
+    "c++",
+    "css",
+    "dart",
+    "diff",
+    "docker",
+    "go",
+    "graphql",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "kotlin",
+    "lua",
+    "makefile",
+    "markdown",
+    "php",
+    "powershell",
+    "python",
+    "r",
+    "ruby",
+    "rust",
+    "scala",
+    "shell",
+    "sql",
+    "swift",
+    "typescript",
+    "xml",
+    "yaml",
+];
+
+fn language(tag: &str) -> &'static str {
+    let tag = tag.trim().to_lowercase();
+    let tag = match tag.as_str() {
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rs" => "rust",
+        "sh" | "zsh" => "shell",
+        "cpp" | "cc" => "c++",
+        "cs" | "csharp" => "c#",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "ps1" => "powershell",
+        "kt" => "kotlin",
+        "rb" => "ruby",
+        "dockerfile" => "docker",
+        other => other,
+    };
+    LANGUAGES
+        .iter()
+        .find(|language| **language == tag)
+        .copied()
+        .unwrap_or("plain text")
+}
+
+fn text_object(content: &str, bold: bool, code: bool) -> Value {
+    json!({
+        "type": "text",
+        "text": { "content": content },
+        "annotations": { "bold": bold, "code": code },
+    })
+}
+
+// `text` in pieces the API accepts
+fn split_text(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT_CHARS)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+// `code` and **bold** spans become annotations; other Markdown stays as typed
+fn rich_text(text: &str) -> Vec<Value> {
+    let mut out = Vec::new();
+    for (i, segment) in text.split('`').enumerate() {
+        if i % 2 == 1 {
+            for piece in split_text(segment) {
+                out.push(text_object(&piece, false, true));
+            }
+            continue;
+        }
+        for (j, part) in segment.split("**").enumerate() {
+            for piece in split_text(part) {
+                out.push(text_object(&piece, j % 2 == 1, false));
+            }
+        }
+    }
+    out
+}
+
+// Blocks of type `kind` holding `rich_text`, split where one would be too long
+fn blocks_of(kind: &str, rich_text: Vec<Value>, extra: &Value) -> Vec<Value> {
+    rich_text
+        .chunks(MAX_TEXT_OBJECTS)
+        .map(|chunk| {
+            let mut body = extra.clone();
+            body["rich_text"] = Value::Array(chunk.to_vec());
+            let mut block = json!({ "object": "block", "type": kind });
+            block[kind] = body;
+            block
+        })
+        .collect()
+}
+
+fn text_blocks(kind: &str, text: &str) -> Vec<Value> {
+    blocks_of(kind, rich_text(text), &json!({}))
+}
+
+fn code_blocks(tag: &str, code: &str) -> Vec<Value> {
+    let rich_text = split_text(code)
+        .iter()
+        .map(|piece| text_object(piece, false, false))
+        .collect();
+    blocks_of("code", rich_text, &json!({ "language": language(tag) }))
+}
+
+// "12. item" -> "item"
+fn numbered_item(line: &str) -> Option<&str> {
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ")
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, blocks: &mut Vec<Value>) {
+    if !paragraph.is_empty() {
+        blocks.extend(text_blocks("paragraph", &paragraph.join("\n")));
+        paragraph.clear();
+    }
+}
+
+// Headings, lists, quotes, code fences and paragraphs as Notion blocks
+fn markdown_blocks(markdown: &str) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    // Language tag and lines of an open code fence
+    let mut fence: Option<(&str, Vec<&str>)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some((tag, lines)) = fence.as_mut() {
+            if trimmed.starts_with("```") {
+                blocks.extend(code_blocks(tag, &lines.join("\n")));
+                fence = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        if let Some(tag) = trimmed.strip_prefix("```") {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            fence = Some((tag, Vec::new()));
+            continue;
+        }
+
+        let item = if let Some(text) = trimmed.strip_prefix("### ") {
+            Some(("heading_3", text))
+        } else if let Some(text) = trimmed.strip_prefix("## ") {
+            Some(("heading_2", text))
+        } else if let Some(text) = trimmed.strip_prefix("# ") {
+            Some(("heading_1", text))
+        } else if let Some(text) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            Some(("bulleted_list_item", text))
+        } else if let Some(text) = numbered_item(trimmed) {
+            Some(("numbered_list_item", text))
+        } else {
+            trimmed.strip_prefix("> ").map(|text| ("quote", text))
+        };
+        match item {
+            Some((kind, text)) => {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.extend(text_blocks(kind, text));
+            }
+            None if trimmed.is_empty() => flush_paragraph(&mut paragraph, &mut blocks),
+            None => paragraph.push(line),
+        }
+    }
+    // An answer cut off inside a fence keeps its code
+    if let Some((tag, lines)) = fence {
+        blocks.extend(code_blocks(tag, &lines.join("\n")));
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn authorized(request: RequestBuilder, token: &str) -> RequestBuilder {
+    request
+        .bearer_auth(token)
+        .header("Notion-Version", API_VERSION)
+        .timeout(REQUEST_TIMEOUT)
+}
+
+async fn call(request: RequestBuilder) -> Result<Value, String> {
+    let response = crate::network::send(request, "notion", &[])
+        .await
+        .map_err(|e| format!("Failed to reach Notion: {}", e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read Notion response: {}", e))?;
+    if !status.is_success() {
+        // Errors carry a JSON message, unless a proxy answered instead
+        let message = serde_json::from_str::<Value>(&body)
+            .ok()
+            .and_then(|error| error["message"].as_str().map(str::to_string))
+            .unwrap_or(body);
+        return Err(format!("Notion returned {}: {}", status, message.trim()));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Invalid response from Notion: {}", e))
+}
+
+// Uploads a capture, returning the id an image block refers to it by
+async fn upload_image(
+    client: &reqwest::Client,
+    token: &str,
+    image: &NoteImage,
+) -> Result<String, String> {
+    let content_type = match crate::captures::extension_for(&image.bytes) {
+        "png" => "image/png",
+        "jpg" => "image/jpeg",
+        _ => return Err(format!("Unsupported capture format: {}", image.name)),
+    };
+    let upload = call(
+        authorized(client.post(format!("{}/file_uploads", API_URL)), token).json(&json!({
+            "filename": image.name,
+            "content_type": content_type,
+        })),
+    )
+    .await?;
+    let id = upload["id"]
+        .as_str()
+        .ok_or("Invalid response from Notion: missing upload id")?
+        .to_string();
+
+    let part = Part::bytes(image.bytes.clone())
+        .file_name(image.name.clone())
+        .mime_str(content_type)
+        .map_err(|e| format!("Failed to upload capture: {}", e))?;
+    call(
+        authorized(
+            client.post(format!("{}/file_uploads/{}/send", API_URL, id)),
+            token,
+        )
+        .multipart(Form::new().part("file", part)),
+    )
+    .await?;
+    Ok(id)
+}
+
+// Creates the note as a page under `parent_id`, returning the page's link
+pub async fn create_page(note: &Note, token: &str, parent_id: &str) -> Result<String, String> {
+    let client = crate::network::http_client()?;
+
+    let mut header = format!("From Pluely · {}", http_date(note.created_at));
+    if let Some(model) = &note.model {
+        header.push_str(&format!(" · {}", model));
+    }
+    let mut blocks = text_blocks("paragraph", &header);
+    if let Some(question) = &note.question {
+        blocks.extend(text_blocks("heading_2", "Question"));
+        blocks.extend(text_blocks("quote", question.trim()));
+    }
+    blocks.extend(text_blocks("heading_2", "Answer"));
+    blocks.extend(markdown_blocks(&note.answer));
+    if !note.images.is_empty() {
+        blocks.extend(text_blocks("heading_2", "Captures"));
+        for image in &note.images {
+            let id = upload_image(&client, token, image).await?;
+            blocks.push(json!({
+                "object": "block",
+                "type": "image",
+                "image": { "type": "file_upload", "file_upload": { "id": id } },
+            }));
+        }
+    }
+
+    let title: String = note.title.chars().take(MAX_TEXT_CHARS).collect();
+    let mut batches = blocks.chunks(MAX_BLOCKS);
+    let page = call(
+        authorized(client.post(format!("{}/pages", API_URL)), token).json(&json!({
+            "parent": { "page_id": parent_id },
+            "properties": {
+                "title": { "title": [text_object(&title, false, false)] },
+            },
+            "children": batches.next().unwrap_or_default(),
+        })),
+    )
+    .await?;
+    let page_id = page["id"]
+        .as_str()
+        .ok_or("Invalid response from Notion: missing page id")?;
+    // The rest is appended in order, a request's worth at a time
+    for batch in batches {
+        call(
+            authorized(
+                client.patch(format!("{}/blocks/{}/children", API_URL, page_id)),
+                token,
+            )
+            .json(&json!({ "children": batch })),
+        )
+        .await?;
+    }
+    Ok(page["url"].as_str().unwrap_or_default().to_string())
+}
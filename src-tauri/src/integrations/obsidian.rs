@@ -0,0 +1,123 @@
+// Notes as Markdown files in an Obsidian vault, with YAML front matter and the
+// captures saved beside them as embeds
+use std::path::{Path, PathBuf};
+
+use super::{Note, ObsidianSettings};
+use crate::captures::iso_date;
+
+// Longest file name taken from a note's title
+const MAX_NAME_CHARS: usize = 80;
+
+// A name every platform accepts for a file, from a note title
+fn file_stem(title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    let stem = stem.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stem = stem.trim_matches('.');
+    if stem.is_empty() {
+        "Pluely note".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+// `dir/<stem><extension>`, numbered when a file of that name exists
+fn unused_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}{}", stem, extension));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{} {}{}", stem, n, extension));
+    }
+    path
+}
+
+// JSON strings are valid double-quoted YAML scalars
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+fn front_matter(note: &Note, settings: &ObsidianSettings) -> String {
+    let mut out = format!(
+        "---\ntitle: {}\ncreated: {}\nsource: pluely\nconversation: {}\n",
+        yaml_string(&note.title),
+        iso_date(note.created_at),
+        yaml_string(&note.conversation_id)
+    );
+    if let Some(model) = &note.model {
+        out.push_str(&format!("model: {}\n", yaml_string(model)));
+    }
+    if !settings.tags.is_empty() {
+        out.push_str("tags:\n");
+        for tag in &settings.tags {
+            out.push_str(&format!("  - {}\n", yaml_string(tag.trim())));
+        }
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+// Writes the note into the vault, returning the Markdown file's path
+pub fn write_note(note: &Note, settings: &ObsidianSettings) -> Result<PathBuf, String> {
+    let vault = settings
+        .vault_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .ok_or("Choose an Obsidian vault in settings first")?;
+    let vault = Path::new(vault);
+    if !vault.is_dir() {
+        return Err(format!("Obsidian vault not found: {}", vault.display()));
+    }
+    let notes_dir = vault.join(settings.folder.trim());
+    std::fs::create_dir_all(&notes_dir)
+        .map_err(|e| format!("Failed to create Obsidian folder: {}", e))?;
+
+    let mut embeds = Vec::new();
+    if !note.images.is_empty() {
+        let attachments_dir = vault.join(settings.attachments_folder.trim());
+        std::fs::create_dir_all(&attachments_dir)
+            .map_err(|e| format!("Failed to create Obsidian attachments folder: {}", e))?;
+        for image in &note.images {
+            let name = Path::new(&image.name);
+            let stem = name
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let extension = name
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_default();
+            let path = unused_path(&attachments_dir, &file_stem(&stem), &extension);
+            std::fs::write(&path, &image.bytes)
+                .map_err(|e| format!("Failed to save capture to Obsidian: {}", e))?;
+            if let Some(file_name) = path.file_name() {
+                embeds.push(format!("![[{}]]", file_name.to_string_lossy()));
+            }
+        }
+    }
+
+    let mut out = front_matter(note, settings);
+    if let Some(question) = &note.question {
+        out.push_str("## Question\n\n");
+        for line in question.trim().lines() {
+            out.push_str(&format!("> {}\n", line));
+        }
+        out.push('\n');
+    }
+    out.push_str(&format!("## Answer\n\n{}\n", note.answer.trim()));
+    if !embeds.is_empty() {
+        out.push_str(&format!("\n## Captures\n\n{}\n", embeds.join("\n\n")));
+    }
+
+    let path = unused_path(&notes_dir, &file_stem(&note.title), ".md");
+    std::fs::write(&path, out).map_err(|e| format!("Failed to write Obsidian note: {}", e))?;
+    Ok(path)
+}
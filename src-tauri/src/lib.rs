@@ -1,14 +1,69 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod activate;
+mod analytics;
 mod api;
+mod attachments;
+mod audit;
+mod autostart;
+mod browser;
+mod camera;
 mod capture;
+mod captures;
+mod cli;
+mod clipboard;
+mod code;
+mod control;
+mod crash;
+mod crypto;
 mod db;
+mod deeplink;
+mod documents;
+mod embeddings;
+mod events;
+mod feedback;
+mod focus;
+mod fullscreen;
+mod hooks;
+mod idle;
+mod integrations;
+mod llm;
+mod logging;
+mod mcp;
+mod meeting;
+mod network;
+mod notify;
+mod ocr;
+mod pins;
+mod placement;
+mod plugins;
+mod power;
+mod prompts;
+mod realtime;
+mod regions;
+mod secrets;
+mod sensitive;
+mod settings;
+mod share;
 mod shortcuts;
+mod storage;
+mod stt;
+mod tables;
+mod translate;
+mod tray;
+mod tts;
+mod updater;
+mod watch;
+mod webhooks;
+mod webpage;
+mod whisper;
 mod window;
+mod wipe;
+mod workspaces;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager, WebviewWindow};
 use tauri_plugin_posthog::{init as posthog_init, PostHogConfig, PostHogOptions};
 use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
 mod speaker;
 use capture::CaptureState;
 use speaker::VadConfig;
@@ -22,15 +77,343 @@ pub struct AudioState {
     stream_task: Arc<Mutex<Option<JoinHandle<()>>>>,
     vad_config: Arc<Mutex<VadConfig>>,
     is_capturing: Arc<Mutex<bool>>,
+    system_device: Arc<speaker::hotswap::DeviceSelector>,
+    mic_device: Arc<speaker::hotswap::DeviceSelector>,
 }
 
 #[tauri::command]
+#[specta::specta]
 fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+// True when launched by an MCP client rather than by the user
+pub fn is_mcp_launch() -> bool {
+    std::env::args().any(|arg| arg == mcp::MCP_FLAG)
+}
+
+// The headless CLI command the app was started with, if any
+pub fn cli_command() -> Option<Result<cli::CliCommand, String>> {
+    cli::parse_args(&std::env::args().skip(1).collect::<Vec<_>>())
+}
+
+// Runs a CLI command without opening any windows; returns the exit code
+pub fn run_cli(command: Result<cli::CliCommand, String>) -> i32 {
+    cli::run(command)
+}
+
+// Serves MCP over stdio without opening any windows
+pub fn run_mcp_server() {
+    logging::init_stderr();
+    if let Err(e) = tauri::async_runtime::block_on(mcp::serve_stdio()) {
+        error!("MCP server stopped: {}", e);
+    }
+}
+
+// Every command and event the frontend shares with Rust. The commands are
+// registered through this builder, and debug builds write the typed bindings
+// to src/bindings.ts
+fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
+    tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(tauri_specta::collect_commands![
+            get_app_version,
+            window::set_window_height,
+            window::open_dashboard,
+            window::toggle_dashboard,
+            window::move_window,
+            window::set_content_protection,
+            window::set_window_opacity,
+            window::set_window_effect,
+            window::set_skip_taskbar,
+            window::set_hide_from_switcher,
+            window::open_conversation_window,
+            placement::set_window_mode,
+            placement::get_window_mode,
+            pins::pin_capture,
+            pins::list_pins,
+            pins::close_pin,
+            pins::get_pin_image,
+            capture::capture_to_base64,
+            capture::start_screen_capture,
+            capture::capture_selected_area,
+            capture::nudge_selection,
+            capture::resize_selection,
+            capture::confirm_selection,
+            capture::measure_selection,
+            regions::detect_regions,
+            tables::extract_table,
+            code::extract_code,
+            translate::translate_capture,
+            camera::list_cameras,
+            camera::capture_camera_frame,
+            capture::get_overlay_config,
+            capture::get_overlay_image,
+            capture::close_overlay_window,
+            capture::check_multi_monitor_support,
+            watch::start_watch,
+            watch::stop_watch,
+            watch::start_change_trigger,
+            watch::stop_change_trigger,
+            clipboard::read_clipboard_image,
+            clipboard::read_clipboard_text,
+            clipboard::is_clipboard_watcher_running,
+            captures::save_capture,
+            captures::list_captures,
+            captures::delete_capture,
+            captures::get_capture_retention,
+            captures::update_capture_retention,
+            captures::run_capture_cleanup,
+            captures::get_storage_usage,
+            captures::diff_captures,
+            captures::read_capture_metadata,
+            captures::watermark_image,
+            shortcuts::check_shortcuts_registered::<tauri::Wry>,
+            shortcuts::get_registered_shortcuts::<tauri::Wry>,
+            shortcuts::update_shortcuts::<tauri::Wry>,
+            shortcuts::validate_shortcut_key,
+            shortcuts::set_hotkeys_paused::<tauri::Wry>,
+            shortcuts::get_hotkeys_paused::<tauri::Wry>,
+            shortcuts::set_license_status::<tauri::Wry>,
+            shortcuts::set_app_icon_visibility::<tauri::Wry>,
+            shortcuts::set_always_on_top::<tauri::Wry>,
+            shortcuts::set_click_through::<tauri::Wry>,
+            shortcuts::get_click_through::<tauri::Wry>,
+            shortcuts::exit_app,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            activate::activate_license_api,
+            activate::deactivate_license_api,
+            activate::validate_license_api,
+            activate::mask_license_key_cmd,
+            activate::get_checkout_url,
+            activate::secure_storage_save,
+            activate::secure_storage_get,
+            activate::secure_storage_remove,
+            api::transcribe_audio,
+            api::chat_stream_response,
+            api::fetch_models,
+            api::create_system_prompt,
+            api::check_license_status,
+            api::get_activity,
+            llm::send_chat_request,
+            llm::cancel_request,
+            llm::get_llm_config,
+            llm::update_llm_config,
+            llm::list_llm_providers,
+            llm::llm_chat,
+            llm::count_tokens,
+            llm::list_local_models,
+            llm::check_local_llm_health,
+            llm::retry_queued_requests,
+            llm::preview_pii_scrub,
+            llm::approve_pii_scrub,
+            storage::get_history_database,
+            storage::list_conversations,
+            storage::load_conversation,
+            storage::create_conversation,
+            storage::save_message,
+            storage::export_conversation,
+            storage::export_history,
+            storage::import_history,
+            storage::rename_conversation,
+            storage::delete_conversation,
+            storage::read_capture,
+            storage::search_history,
+            storage::get_usage_summary,
+            storage::clear_response_cache,
+            storage::list_queued_requests,
+            storage::cancel_queued_request,
+            embeddings::get_embeddings_config,
+            embeddings::update_embeddings_config,
+            embeddings::index_history,
+            embeddings::semantic_search,
+            mcp::get_mcp_server_command,
+            network::get_proxy_config,
+            network::update_proxy_config,
+            network::test_connection,
+            notify::send_notification,
+            updater::check_for_update,
+            updater::install_update,
+            prompts::list_prompt_profiles,
+            prompts::save_prompt_profile,
+            prompts::delete_prompt_profile,
+            prompts::apply_prompt_profile,
+            prompts::set_conversation_profile,
+            realtime::get_realtime_config,
+            realtime::update_realtime_config,
+            realtime::start_realtime_session,
+            realtime::stop_realtime_session,
+            realtime::interrupt_realtime_response,
+            secrets::set_api_key,
+            secrets::has_api_key,
+            secrets::delete_api_key,
+            secrets::migrate_api_keys,
+            settings::get_settings,
+            settings::get_setting,
+            settings::set_setting,
+            settings::reset_settings,
+            settings::import_local_storage,
+            settings::export_settings,
+            settings::import_settings,
+            settings::get_managed_policy,
+            workspaces::get_active_profile,
+            workspaces::list_workspace_profiles,
+            workspaces::create_workspace_profile,
+            workspaces::delete_workspace_profile,
+            workspaces::switch_profile,
+            speaker::start_system_audio_capture,
+            speaker::stop_system_audio_capture,
+            speaker::set_audio_input,
+            speaker::manual_stop_continuous,
+            speaker::check_system_audio_access,
+            speaker::request_system_audio_access,
+            speaker::get_vad_config,
+            speaker::update_vad_config,
+            speaker::get_capture_status,
+            speaker::get_audio_sample_rate,
+            speaker::list_audio_devices,
+            speaker::start_audio_recording,
+            speaker::stop_audio_recording,
+            speaker::get_recording_status,
+            whisper::get_whisper_config,
+            whisper::update_whisper_config,
+            whisper::is_local_whisper_available,
+            whisper::transcribe_local,
+            whisper::start_whisper_stream,
+            whisper::stop_whisper_stream,
+            ocr::list_ocr_languages,
+            ocr::download_ocr_language,
+            ocr::remove_ocr_language,
+            stt::get_stt_config,
+            stt::update_stt_config,
+            stt::stt_transcribe,
+            stt::send_custom_stt_request,
+            stt::start_stt_stream,
+            stt::stop_stt_stream,
+            tts::get_tts_config,
+            tts::update_tts_config,
+            tts::speak,
+            tts::stop_speaking,
+            attachments::ingest_files,
+            attachments::list_attachments,
+            attachments::read_attachment,
+            attachments::link_attachments,
+            attachments::delete_attachment,
+            documents::inspect_document,
+            documents::ingest_document,
+            webpage::fetch_url_context,
+            deeplink::take_deep_link_ask,
+            control::get_control_api_status,
+            control::get_control_api_token,
+            control::regenerate_control_api_token,
+            webhooks::test_webhook,
+            hooks::test_hook,
+            hooks::get_hook_runs,
+            hooks::clear_hook_runs,
+            plugins::list_plugins,
+            plugins::get_plugins_dir,
+            plugins::call_plugin_command,
+            plugins::stop_plugin,
+            integrations::send_answer_to_notes,
+            share::share_capture,
+            logging::get_recent_logs,
+            logging::copy_diagnostics,
+            audit::get_audit_log,
+            wipe::purge_all_captures,
+            analytics::preview_telemetry_payload,
+            analytics::clear_telemetry,
+            analytics::usage_sharing_allowed,
+            crash::list_crash_reports,
+            crash::delete_crash_report,
+            crash::upload_crash_reports,
+            focus::get_focused_app,
+            idle::get_idle_status,
+            power::get_power_status,
+            meeting::get_meeting_status,
+            fullscreen::get_fullscreen_app,
+        ])
+        .events(tauri_specta::collect_events![
+            events::CapturedSelection,
+            events::CaptureClosed,
+            events::WatchFrame,
+            events::ScreenChanged,
+            events::SelectionChanged,
+            events::ClickThroughChanged,
+            events::WindowModeChanged,
+            events::DoNotDisturbChanged,
+            events::ChatStreamChunk,
+            events::ChatStreamComplete,
+            events::ToggleWindowVisibility,
+            events::FocusTextInput,
+            events::StartAudioRecording,
+            events::TriggerScreenshot,
+            events::ToggleSystemAudio,
+            events::CustomShortcutTriggered,
+            events::ShortcutRegistrationError,
+            events::HotkeysPaused,
+            events::CaptureStarted,
+            events::CaptureStopped,
+            events::SpeechStart,
+            events::SpeechEnd,
+            events::SpeechDetected,
+            events::SpeechDiscarded,
+            events::AudioEncodingError,
+            events::ContinuousRecordingStart,
+            events::RecordingProgress,
+            events::ContinuousRecordingStopped,
+            events::ManualStopContinuous,
+            events::RecordingStarted,
+            events::RecordingFinished,
+            events::SttPartial,
+            events::SttFinal,
+            events::SttError,
+            events::WhisperPartial,
+            events::WhisperFinal,
+            events::RealtimeError,
+            events::AttachmentsIngesting,
+            events::AttachmentsAdded,
+            events::CapturesCleaned,
+            events::CapturesPurged,
+            events::HistoryEncryption,
+            events::HookFinished,
+            events::UserIdle,
+            events::UserActive,
+            events::FullscreenEntered,
+            events::FullscreenExited,
+            events::MeetingStarted,
+            events::MeetingEnded,
+            events::PowerStatusChanged,
+            clipboard::ClipboardChanged,
+            control::ControlApiAsk,
+            deeplink::DeepLinkAsk,
+            llm::LlmAttempt,
+            llm::LlmCancelled,
+            llm::LlmScrubReview,
+            llm::LlmCacheHit,
+            llm::LlmImagesProcessed,
+            llm::LlmTextScrubbed,
+            llm::LlmContextTrimmed,
+            llm::LlmToolCall,
+            llm::QueueStatus,
+            llm::QueuedRequestFinished,
+            ocr::OcrLanguageProgress,
+            realtime::RealtimeTranscript,
+            realtime::RealtimeStatusEvent,
+            settings::SettingsChanged,
+            speaker::hotswap::AudioDeviceChanged,
+            speaker::meter::AudioLevel,
+            speaker::RecordingStatus,
+            tts::TtsProgress,
+            tts::TtsFinished,
+            webhooks::WebhookFailed,
+            workspaces::ProfileSwitched,
+        ])
+        .constant("EVENT_SCHEMA_VERSION", events::EVENT_SCHEMA_VERSION)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let specta_builder = specta_builder();
     // Get PostHog API key
     let posthog_api_key = option_env!("POSTHOG_API_KEY").unwrap_or("").to_string();
     let mut builder = tauri::Builder::default()
@@ -41,15 +424,43 @@ pub fn run() {
         )
         .manage(AudioState::default())
         .manage(CaptureState::default())
+        .manage(whisper::WhisperState::default())
+        .manage(stt::SttState::default())
+        .manage(updater::UpdaterState::default())
+        .manage(speaker::RecorderState::default())
+        .manage(llm::LlmState::default())
+        .manage(storage::StorageState::default())
+        .manage(embeddings::EmbeddingsState::default())
+        .manage(prompts::PromptsState::default())
+        .manage(realtime::RealtimeState::default())
+        .manage(tts::TtsState::default())
+        .manage(settings::SettingsState::default())
+        .manage(captures::CapturesState::default())
+        .manage(clipboard::ClipboardState::default())
+        .manage(control::ControlState::default())
+        .manage(webhooks::WebhooksState::default())
+        .manage(hooks::HooksState::default())
+        .manage(plugins::PluginsState::default())
+        .manage(focus::FocusState::default())
+        .manage(idle::IdleState::default())
+        .manage(power::PowerState::default())
+        .manage(meeting::MeetingState::default())
+        .manage(fullscreen::FullscreenState::default())
+        .manage(deeplink::DeepLinkState::default())
         .manage(shortcuts::WindowVisibility {
             is_hidden: Mutex::new(false),
         })
         .manage(shortcuts::RegisteredShortcuts::default())
         .manage(shortcuts::LicenseState::default())
         .manage(shortcuts::MoveWindowState::default())
+        .manage(shortcuts::ClickThroughState::default())
+        .manage(placement::PlacementState::default())
+        .manage(pins::PinsState::default())
+        .manage(watch::WatchState::default())
+        .manage(wipe::WipeState::default())
+        .manage(ocr::OcrState::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_keychain::init())
         .plugin(tauri_plugin_shell::init()) // Add shell plugin
         .plugin(posthog_init(PostHogConfig {
@@ -65,57 +476,54 @@ pub fn run() {
             }),
             ..Default::default()
         }))
-        .plugin(tauri_plugin_machine_uid::init());
+        .plugin(tauri_plugin_machine_uid::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init());
+    // Must come first: a second launch (e.g. from a pluely:// link) hands its
+    // arguments to this instance, and the deep-link feature forwards the URL
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}));
+    }
     #[cfg(target_os = "macos")]
     {
         builder = builder.plugin(tauri_nspanel::init());
     }
     let mut builder = builder
-        .invoke_handler(tauri::generate_handler![
-            get_app_version,
-            window::set_window_height,
-            window::open_dashboard,
-            window::toggle_dashboard,
-            window::move_window,
-            capture::capture_to_base64,
-            capture::start_screen_capture,
-            capture::capture_selected_area,
-            capture::close_overlay_window,
-            capture::check_multi_monitor_support,
-            shortcuts::check_shortcuts_registered,
-            shortcuts::get_registered_shortcuts,
-            shortcuts::update_shortcuts,
-            shortcuts::validate_shortcut_key,
-            shortcuts::set_license_status,
-            shortcuts::set_app_icon_visibility,
-            shortcuts::set_always_on_top,
-            shortcuts::exit_app,
-            activate::activate_license_api,
-            activate::deactivate_license_api,
-            activate::validate_license_api,
-            activate::mask_license_key_cmd,
-            activate::get_checkout_url,
-            activate::secure_storage_save,
-            activate::secure_storage_get,
-            activate::secure_storage_remove,
-            api::transcribe_audio,
-            api::chat_stream_response,
-            api::fetch_models,
-            api::create_system_prompt,
-            api::check_license_status,
-            api::get_activity,
-            speaker::start_system_audio_capture,
-            speaker::stop_system_audio_capture,
-            speaker::manual_stop_continuous,
-            speaker::check_system_audio_access,
-            speaker::request_system_audio_access,
-            speaker::get_vad_config,
-            speaker::update_vad_config,
-            speaker::get_capture_status,
-            speaker::get_audio_sample_rate,
-            speaker::list_audio_devices,
-        ])
-        .setup(|app| {
+        .invoke_handler(specta_builder.invoke_handler())
+        .on_window_event(|window, event| {
+            // Files dropped onto a window become chat attachments
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                attachments::handle_file_drop(window.app_handle(), paths.clone());
+            }
+            // Remember where the main window and pins are left
+            if matches!(
+                event,
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+            ) {
+                if window.label() == "main" {
+                    placement::window_changed(window.app_handle());
+                }
+                pins::window_changed(window);
+            }
+        })
+        .setup(move |app| {
+            logging::init(app.handle());
+            audit::init(app.handle());
+            analytics::init(app.handle());
+            #[cfg(debug_assertions)]
+            if let Err(e) = specta_builder.export(
+                specta_typescript::Typescript::default()
+                    .bigint(specta_typescript::BigIntExportBehavior::Number)
+                    .header("// @ts-nocheck"),
+                "../src/bindings.ts",
+            ) {
+                warn!("Failed to export TypeScript bindings: {}", e);
+            }
+            specta_builder.mount_events(app);
+            crash::install_panic_hook(app.handle());
+            // Pick the workspace profile before anything reads its files
+            workspaces::init(app.handle());
             // Setup main window positioning
             window::setup_main_window(app).expect("Failed to setup main window");
             #[cfg(target_os = "macos")]
@@ -124,7 +532,7 @@ pub fn run() {
             let app_handle = app.handle();
             if app_handle.get_webview_window("dashboard").is_none() {
                 if let Err(e) = window::create_dashboard_window(&app_handle) {
-                    eprintln!("Failed to create dashboard window on startup: {}", e);
+                    warn!("Failed to create dashboard window on startup: {}", e);
                 }
             }
 
@@ -135,9 +543,9 @@ pub fn run() {
                 #[allow(deprecated, unexpected_cfgs)]
                 if let Err(e) = app.handle().plugin(tauri_plugin_autostart::init(
                     MacosLauncher::LaunchAgent,
-                    Some(vec![]),
+                    Some(vec![autostart::AUTOSTART_FLAG]),
                 )) {
-                    eprintln!("Failed to initialize autostart plugin: {}", e);
+                    warn!("Failed to initialize autostart plugin: {}", e);
                 }
             }
 
@@ -153,7 +561,7 @@ pub fn run() {
                                 let registered = match state.shortcuts.lock() {
                                     Ok(guard) => guard,
                                     Err(poisoned) => {
-                                        eprintln!("Mutex poisoned in handler, recovering...");
+                                        warn!("Mutex poisoned in handler, recovering...");
                                         poisoned.into_inner()
                                     }
                                 };
@@ -176,7 +584,7 @@ pub fn run() {
                                         {
                                             shortcuts::start_move_window(app, direction);
                                         } else {
-                                            eprintln!("Shortcut triggered: {}", action_id);
+                                            debug!("Shortcut triggered: {}", action_id);
                                             shortcuts::handle_shortcut_action(app, &action_id);
                                         }
                                     }
@@ -194,8 +602,28 @@ pub fn run() {
                 )
                 .expect("Failed to initialize global shortcut plugin");
             if let Err(e) = shortcuts::setup_global_shortcuts(app.handle()) {
-                eprintln!("Failed to setup global shortcuts: {}", e);
+                error!("Failed to setup global shortcuts: {}", e);
+            }
+            // Configure the backend from the saved settings
+            settings::load_on_startup(app.handle());
+            if let Err(e) = placement::restore(app.handle()) {
+                warn!("Failed to restore window placement: {}", e);
             }
+            pins::restore_pins(app.handle());
+            #[cfg(desktop)]
+            {
+                if let Err(e) = tray::setup_tray(app) {
+                    warn!("Failed to create tray icon: {}", e);
+                }
+                tray::apply_start_minimized(app.handle());
+                autostart::sync_autostart(app.handle());
+                // After settings, so links run against the configured backend
+                deeplink::setup_deep_links(app);
+            }
+            // Trim the capture library to its retention policy
+            captures::start_capture_cleanup(app.handle().clone());
+            // Replay chat requests queued while offline
+            llm::start_request_queue(app.handle().clone());
             Ok(())
         });
 
@@ -206,8 +634,15 @@ pub fn run() {
     }
 
     builder
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Nothing captured outlives the app
+            if let tauri::RunEvent::Exit = event {
+                wipe::purge(app, wipe::PurgeTrigger::Exit);
+                analytics::flush();
+            }
+        });
 }
 
 #[cfg(target_os = "macos")]
@@ -229,10 +664,10 @@ fn init(app_handle: &AppHandle) {
             "window_did_become_key" => {
                 let app_name = handle.package_info().name.to_owned();
 
-                println!("[info]: {:?} panel becomes key window!", app_name);
+                debug!("{:?} panel became key window", app_name);
             }
             "window_did_resign_key" => {
-                println!("[info]: panel resigned from key window!");
+                debug!("Panel resigned from key window");
             }
             _ => (),
         }
@@ -0,0 +1,198 @@
+// Anthropic Messages API
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::{
+    ensure_vision, image_mime_type, required_key, strip_data_url, ChatMessage, ChatRole,
+    ImageLimits, Provider, ProviderRequest, ProviderSettings, TokenLimits, ToolFormat,
+};
+
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+// The API requires max_tokens on every request
+const DEFAULT_MAX_TOKENS: u32 = 4_096;
+
+pub struct AnthropicProvider {
+    settings: ProviderSettings,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(settings: ProviderSettings) -> Result<Self, String> {
+        let api_key = required_key(&settings, "Anthropic")?;
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| ANTHROPIC_BASE_URL.to_string());
+        let model = settings
+            .model
+            .clone()
+            .unwrap_or_else(|| "claude-3-5-sonnet-latest".to_string());
+
+        Ok(Self {
+            settings,
+            base_url,
+            model,
+            api_key,
+        })
+    }
+}
+
+fn image_blocks(images: &[String]) -> Vec<Value> {
+    images
+        .iter()
+        .map(|image| {
+            let data = strip_data_url(image);
+            json!({
+                "type": "image",
+                "source": {
+                    "type": "base64",
+                    "media_type": image_mime_type(data),
+                    "data": data,
+                }
+            })
+        })
+        .collect()
+}
+
+impl Provider for AnthropicProvider {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn supports_vision(&self) -> bool {
+        // Every Claude 3+ model accepts images
+        let legacy = self.model.starts_with("claude-2") || self.model.starts_with("claude-instant");
+        self.settings.supports_vision.unwrap_or(!legacy)
+    }
+
+    fn limits(&self) -> TokenLimits {
+        let model = self.model.as_str();
+        let default_output = if model.contains("sonnet-4") || model.contains("3-7-sonnet") {
+            64_000
+        } else if model.contains("opus-4") {
+            32_000
+        } else if model.contains("3-5") {
+            8_192
+        } else {
+            DEFAULT_MAX_TOKENS
+        };
+        TokenLimits {
+            context_window: self.settings.context_window.unwrap_or(200_000),
+            max_output_tokens: self.settings.max_output_tokens.unwrap_or(default_output),
+        }
+    }
+
+    fn image_limits(&self) -> ImageLimits {
+        // 5MB per image once base64 encoded; larger than 1568px is downscaled server-side
+        ImageLimits {
+            max_dimension: 1_568,
+            max_bytes: 3_750_000,
+        }
+    }
+
+    fn tool_format(&self) -> Option<ToolFormat> {
+        Some(ToolFormat::Anthropic)
+    }
+
+    fn build_request(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<(ProviderRequest, Value), String> {
+        ensure_vision(self, messages)?;
+
+        // System prompts are a top-level field rather than a message
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .map(|m| m.content.as_str())
+            .collect();
+
+        let mut conversation: Vec<Value> = Vec::new();
+        for message in messages.iter().filter(|m| m.role != ChatRole::System) {
+            let role = if message.role == ChatRole::Assistant {
+                "assistant"
+            } else {
+                "user"
+            };
+
+            let mut content = image_blocks(&message.images);
+            if message.role == ChatRole::Tool {
+                let mut result = vec![json!({ "type": "text", "text": message.content })];
+                result.extend(content);
+                content = vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": message.tool_call_id,
+                    "content": result,
+                })];
+            } else if !message.content.is_empty() || message.tool_calls.is_empty() {
+                content.push(json!({ "type": "text", "text": message.content }));
+            }
+            for call in &message.tool_calls {
+                content.push(json!({
+                    "type": "tool_use",
+                    "id": call.id,
+                    "name": call.name,
+                    "input": call.arguments,
+                }));
+            }
+
+            // Results of parallel calls belong in a single user turn
+            let previous_is_tool = message.role == ChatRole::Tool
+                && conversation
+                    .last()
+                    .is_some_and(|last| last["content"][0]["type"].as_str() == Some("tool_result"));
+            match conversation.last_mut() {
+                Some(last) if previous_is_tool => {
+                    if let Some(blocks) = last["content"].as_array_mut() {
+                        blocks.extend(content);
+                    }
+                }
+                _ => conversation.push(json!({ "role": role, "content": content })),
+            }
+        }
+
+        let mut body = json!({
+            "model": self.model,
+            "messages": conversation,
+            "max_tokens": self.settings.max_output_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "stream": stream,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        if let Some(temperature) = self.settings.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        let headers = HashMap::from([
+            ("x-api-key".to_string(), self.api_key.clone()),
+            (
+                "anthropic-version".to_string(),
+                self.settings
+                    .api_version
+                    .clone()
+                    .filter(|version| !version.trim().is_empty())
+                    .unwrap_or_else(|| ANTHROPIC_VERSION.to_string()),
+            ),
+        ]);
+
+        let request = ProviderRequest {
+            url: format!("{}/messages", self.base_url.trim_end_matches('/')),
+            method: "POST".to_string(),
+            headers,
+            stream,
+            response_content_path: Some("content[0].text".to_string()),
+            api_key_ref: None,
+            tool_format: None,
+        };
+        Ok((request, body))
+    }
+}
@@ -0,0 +1,62 @@
+// Opt-in response cache. Asking the same question about the same screenshot
+// again returns the stored answer instead of paying for another request.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+use super::{strip_data_url, ChatMessage, ChatRole};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    // Answers older than this are fetched again
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+fn role_name(role: &ChatRole) -> &'static str {
+    match role {
+        ChatRole::System => "system",
+        ChatRole::User => "user",
+        ChatRole::Assistant => "assistant",
+        ChatRole::Tool => "tool",
+    }
+}
+
+// Stray whitespace shouldn't miss the cache
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Hex SHA-256 of provider, model, normalized prompt and image hashes
+pub fn cache_key(provider: &str, model: &str, messages: &[ChatMessage]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(provider.as_bytes());
+    hasher.update([0]);
+    hasher.update(model.as_bytes());
+    for message in messages {
+        hasher.update([0]);
+        hasher.update(role_name(&message.role).as_bytes());
+        hasher.update([0]);
+        hasher.update(normalize(&message.content).as_bytes());
+        for image in &message.images {
+            let image_hash = Sha256::digest(strip_data_url(image).as_bytes());
+            hasher.update([1]);
+            hasher.update(image_hash);
+        }
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,1492 @@
+// Pluely LLM commands: proxied chat requests with streaming and cancellation.
+use futures_util::StreamExt;
+use serde::Serialize;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, WebviewWindow};
+use tauri_specta::Event;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use super::content::{extract_stream_delta, get_by_path};
+use super::local::{self, LocalModel, LocalServerStatus};
+use super::queue::{self, QueuedChat};
+use super::ratelimit::{RateLimiter, RateLimits};
+use super::sse::SseParser;
+use super::{
+    build_provider_kind, provider_info, ChatMessage, ChatRole, ChatStreamEvent, ContextTrim,
+    ImageReuse, ImageTransform, LlmConfig, LlmProviderKind, ProviderInfo, ProviderRequest,
+    ScrubPreview, TokenCount, ToolCall, ToolSpec,
+};
+use super::{cache, dedup, images, pricing, retry, scrub, tokens, tools};
+use crate::control::BridgeEvent;
+
+// Filled in from the keychain when a request names a key
+const API_KEY_PLACEHOLDER: &str = "{{API_KEY}}";
+// Hosts each chat provider's keychain key may be sent to, by the ids the UI
+// and the Rust providers save keys under. A configured base URL adds its host.
+const PROVIDER_KEY_HOSTS: &[(&str, &str)] = &[
+    ("openai", "api.openai.com"),
+    ("anthropic", "api.anthropic.com"),
+    ("claude", "api.anthropic.com"),
+    ("gemini", "generativelanguage.googleapis.com"),
+    ("openrouter", "openrouter.ai"),
+    ("grok", "api.x.ai"),
+    ("mistral", "api.mistral.ai"),
+    ("cohere", "api.cohere.ai"),
+    ("groq", "api.groq.com"),
+    ("perplexity", "api.perplexity.ai"),
+    ("ollama", "localhost"),
+    ("lm_studio", "localhost"),
+];
+// Model turns that may call tools before it must answer
+const MAX_TOOL_ROUNDS: usize = 5;
+
+#[derive(Default)]
+pub struct LlmState {
+    config: Mutex<LlmConfig>,
+    // In-flight requests by the id the frontend chose, so they can be cancelled
+    requests: Mutex<HashMap<String, Arc<CancelSignal>>>,
+    // Label of the window that started each request, so a request's events
+    // reach only that window when several chat windows are open
+    owners: Mutex<HashMap<String, String>>,
+    // Wakes the offline queue worker for an immediate retry
+    queue_wake: Arc<Notify>,
+    limiter: Mutex<RateLimiter>,
+    // Scrub previews the user approved; each lets one request through
+    approved_scrubs: Mutex<HashSet<String>>,
+}
+
+pub fn current_config(app: &AppHandle) -> Result<LlmConfig, String> {
+    let state = app.state::<LlmState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read LLM config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+// Terminal event for a request stopped by `cancel_request`
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmCancelled {
+    pub request_id: String,
+    // Whatever had streamed in before the cancel
+    pub text: String,
+}
+
+// Set by `cancel_request`. The flag outlives a consumed notification, so a
+// cancel between model rounds, while a tool runs, still stops the next one.
+#[derive(Default)]
+struct CancelSignal {
+    notify: Notify,
+    cancelled: AtomicBool,
+}
+
+impl CancelSignal {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        // notify_one stores a permit, so a cancel that lands before the
+        // select starts waiting is not lost
+        self.notify.notify_one();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    async fn cancelled(&self) {
+        if !self.is_cancelled() {
+            self.notify.notified().await;
+        }
+    }
+}
+
+// Adds a request to the registry, refusing an id that is already running
+fn register_request(
+    requests: &Mutex<HashMap<String, Arc<CancelSignal>>>,
+    request_id: &str,
+) -> Result<Arc<CancelSignal>, String> {
+    let mut requests = requests
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if requests.contains_key(request_id) {
+        return Err(format!("Request {} is already running", request_id));
+    }
+    let cancel = Arc::new(CancelSignal::default());
+    requests.insert(request_id.to_string(), cancel.clone());
+    Ok(cancel)
+}
+
+// Registers the request for cancellation until the returned guard drops
+fn start_request(
+    app: &AppHandle,
+    request_id: &str,
+) -> Result<(Arc<CancelSignal>, RequestGuard), String> {
+    let cancel = register_request(&app.state::<LlmState>().requests, request_id)?;
+    let guard = RequestGuard {
+        app: app.clone(),
+        request_id: request_id.to_string(),
+    };
+    Ok((cancel, guard))
+}
+
+// Removes the request from the registry however the command exits
+struct RequestGuard {
+    app: AppHandle,
+    request_id: String,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        let state = self.app.state::<LlmState>();
+        if let Ok(mut requests) = state.requests.lock() {
+            requests.remove(&self.request_id);
+        };
+    }
+}
+
+// Forgets which window owns the request however the command exits
+struct OwnerGuard {
+    app: AppHandle,
+    request_id: String,
+}
+
+impl Drop for OwnerGuard {
+    fn drop(&mut self) {
+        let state = self.app.state::<LlmState>();
+        if let Ok(mut owners) = state.owners.lock() {
+            owners.remove(&self.request_id);
+        };
+    }
+}
+
+// Refuses an id that is already running, so a duplicate's guard never
+// removes the running request's owner
+fn register_owner(
+    app: &AppHandle,
+    request_id: &str,
+    window: &WebviewWindow,
+) -> Result<OwnerGuard, String> {
+    let state = app.state::<LlmState>();
+    let mut owners = state
+        .owners
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if owners.contains_key(request_id) {
+        return Err(format!("Request {} is already running", request_id));
+    }
+    owners.insert(request_id.to_string(), window.label().to_string());
+    Ok(OwnerGuard {
+        app: app.clone(),
+        request_id: request_id.to_string(),
+    })
+}
+
+// Emits a request's event to the window that started it. Requests without
+// one, like those replayed from the offline queue, emit to every window.
+fn emit_for<S: Serialize + Clone>(app: &AppHandle, request_id: &str, event: &str, payload: S) {
+    let owner = app
+        .state::<LlmState>()
+        .owners
+        .lock()
+        .ok()
+        .and_then(|owners| owners.get(request_id).cloned());
+    let _ = match owner {
+        Some(label) => app.emit_to(EventTarget::webview_window(label), event, payload),
+        None => app.emit(event, payload),
+    };
+}
+
+// Fails the request with an `llm-scrub-review` event when `preview` would mask
+// something the user hasn't approved yet
+fn hold_for_review(app: &AppHandle, request_id: &str, preview: ScrubPreview) -> Result<(), String> {
+    let Some(approval_id) = &preview.approval_id else {
+        return Ok(());
+    };
+    let approved = app
+        .state::<LlmState>()
+        .approved_scrubs
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .remove(approval_id);
+    if !approved {
+        emit_for(
+            app,
+            request_id,
+            "llm-scrub-review",
+            LlmScrubReview {
+                request_id: request_id.to_string(),
+                preview,
+            },
+        );
+        return Err("Review the masked text before sending".to_string());
+    }
+    Ok(())
+}
+
+// Sends `payload` to the provider and streams text back over `on_event`.
+// Resolves with the full response text (partial if cancelled).
+#[tauri::command]
+#[specta::specta]
+pub async fn send_chat_request(
+    app: AppHandle,
+    window: WebviewWindow,
+    request_id: String,
+    provider: ProviderRequest,
+    payload: serde_json::Value,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<String, String> {
+    let config = current_config(&app)?;
+    check_api_key_ref(&config, &provider)?;
+    let _owner = register_owner(&app, &request_id, &window)?;
+    // The frontend built the body, so its text is scrubbed in place
+    if config.scrub.enabled && config.scrub.require_approval {
+        let preview = scrub::preview_payload(&config.scrub, &payload)?;
+        hold_for_review(&app, &request_id, preview)?;
+    }
+    let (payload, masked) = scrub::scrub_payload(&config.scrub, &payload)?;
+    if !masked.is_empty() {
+        emit_for(
+            &app,
+            &request_id,
+            "llm-text-scrubbed",
+            LlmTextScrubbed {
+                request_id: request_id.clone(),
+                masked: masked.clone(),
+            },
+        );
+    }
+    let name = reqwest::Url::parse(&provider.url)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+        .unwrap_or_else(|| "custom".to_string());
+    let model = payload
+        .get("model")
+        .and_then(|m| m.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let candidates = vec![ChatCandidate {
+        provider: name,
+        model,
+        input_tokens: None,
+        limits: RateLimits::default(),
+        request: provider,
+        payload,
+        redaction: masked,
+    }];
+    let (cancel, _guard) = start_request(&app, &request_id)?;
+    run_registered(app, request_id, &cancel, candidates, on_event)
+        .await
+        .map(|outcome| outcome.text)
+}
+
+// One provider in a failover chain, with its request ready to send
+struct ChatCandidate {
+    provider: String,
+    model: String,
+    // Estimated prompt size, for usage tracking
+    input_tokens: Option<u32>,
+    limits: RateLimits,
+    request: ProviderRequest,
+    payload: serde_json::Value,
+    // What was cut, shrunk or masked before sending, for the audit log
+    redaction: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptStatus {
+    // Waiting for the provider's rate limit; `retry_in_ms` says how long
+    Throttled,
+    Started,
+    Retrying,
+    Failed,
+    Succeeded,
+}
+
+// Emitted as `llm-attempt` so the UI can show retries and failovers
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmAttempt {
+    pub request_id: String,
+    pub provider: String,
+    // 1-based, counted per provider
+    pub attempt: u32,
+    pub status: AttemptStatus,
+    pub error: Option<String>,
+    pub retry_in_ms: Option<u64>,
+}
+
+// Emitted when `llm_chat` had to shorten the conversation
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmContextTrimmed {
+    pub request_id: String,
+    #[serde(flatten)]
+    pub trim: ContextTrim,
+}
+
+// Emitted when personal data or secrets were masked in the text sent
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmTextScrubbed {
+    pub request_id: String,
+    // e.g. "2 masked as [EMAIL]"
+    pub masked: Vec<String>,
+}
+
+// Emitted as `llm-scrub-review` when `llm_chat` holds a request until the
+// user approves what would be masked
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmScrubReview {
+    pub request_id: String,
+    #[serde(flatten)]
+    pub preview: ScrubPreview,
+}
+
+// Emitted when attached images had to be resized or re-encoded, or were left
+// out as repeats of an earlier screenshot
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmImagesProcessed {
+    pub request_id: String,
+    pub images: Vec<ImageTransform>,
+    pub reused: Vec<ImageReuse>,
+}
+
+// Tries each candidate in turn, retrying transient failures, until the
+// request registered under `cancel` is cancelled.
+async fn run_registered(
+    app: AppHandle,
+    request_id: String,
+    cancel: &CancelSignal,
+    candidates: Vec<ChatCandidate>,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<ChatOutcome, String> {
+    let retry = current_config(&app)?.retry;
+    let emit_attempt = |provider: &str, attempt: u32, status, error, retry_in: Option<Duration>| {
+        emit_for(
+            &app,
+            &request_id,
+            "llm-attempt",
+            LlmAttempt {
+                request_id: request_id.clone(),
+                provider: provider.to_string(),
+                attempt,
+                status,
+                error,
+                retry_in_ms: retry_in.map(|d| d.as_millis() as u64),
+            },
+        );
+    };
+
+    let mut last_error = "No LLM provider is configured".to_string();
+    'providers: for candidate in &candidates {
+        let mut attempt = 1;
+        loop {
+            // Over the limit, the request waits rather than failing
+            loop {
+                let wait = app
+                    .state::<LlmState>()
+                    .limiter
+                    .lock()
+                    .map_err(|e| format!("Failed to acquire lock: {}", e))?
+                    .try_acquire(
+                        &candidate.provider,
+                        candidate.limits,
+                        candidate.input_tokens.unwrap_or(0),
+                    );
+                let Err(wait) = wait else {
+                    break;
+                };
+                emit_attempt(
+                    &candidate.provider,
+                    attempt,
+                    AttemptStatus::Throttled,
+                    None,
+                    Some(wait),
+                );
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = cancel.cancelled() => return Ok(cancel_waiting(&app, &request_id, &on_event)),
+                }
+            }
+
+            emit_attempt(
+                &candidate.provider,
+                attempt,
+                AttemptStatus::Started,
+                None,
+                None,
+            );
+            let result = run_chat_request(
+                &request_id,
+                &candidate.request,
+                &candidate.payload,
+                &candidate.redaction,
+                &on_event,
+                cancel,
+            )
+            .await;
+
+            let (error, delay) = match result {
+                Ok(outcome) => {
+                    record_usage(&app, &request_id, candidate, &outcome);
+                    if outcome.cancelled {
+                        emit_for(
+                            &app,
+                            &request_id,
+                            "llm-cancelled",
+                            LlmCancelled {
+                                request_id: request_id.clone(),
+                                text: outcome.text.clone(),
+                            },
+                        );
+                    } else {
+                        emit_attempt(
+                            &candidate.provider,
+                            attempt,
+                            AttemptStatus::Succeeded,
+                            None,
+                            None,
+                        );
+                        // Tool calls mean the model hasn't answered yet
+                        if outcome.tool_calls.is_empty() {
+                            crate::control::publish(BridgeEvent::LlmDone {
+                                request_id: request_id.clone(),
+                                text: outcome.text.clone(),
+                            });
+                        }
+                    }
+                    return Ok(outcome);
+                }
+                Err(ChatError::Transient {
+                    message,
+                    retry_after,
+                }) => {
+                    // Other requests to this provider hold off too
+                    if let Some(retry_after) = retry_after {
+                        if let Ok(mut limiter) = app.state::<LlmState>().limiter.lock() {
+                            limiter.block(&candidate.provider, retry_after);
+                        }
+                    }
+                    // Waiting longer than the backoff allows goes to the next
+                    // provider instead
+                    let too_long = retry_after
+                        .is_some_and(|after| after > Duration::from_millis(retry.max_delay_ms));
+                    if attempt <= retry.max_retries && !too_long {
+                        (message, Some(retry.delay(attempt, retry_after)))
+                    } else {
+                        (message, None)
+                    }
+                }
+                Err(ChatError::Fatal(message)) => (message, None),
+                Err(ChatError::Interrupted(message)) => {
+                    // Text already reached the UI, so another provider can't take over
+                    emit_attempt(
+                        &candidate.provider,
+                        attempt,
+                        AttemptStatus::Failed,
+                        Some(message.clone()),
+                        None,
+                    );
+                    return Err(message);
+                }
+            };
+
+            let Some(delay) = delay else {
+                warn!("{} failed: {}", candidate.provider, error);
+                emit_attempt(
+                    &candidate.provider,
+                    attempt,
+                    AttemptStatus::Failed,
+                    Some(error.clone()),
+                    None,
+                );
+                last_error = error;
+                continue 'providers;
+            };
+
+            emit_attempt(
+                &candidate.provider,
+                attempt,
+                AttemptStatus::Retrying,
+                Some(error),
+                Some(delay),
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel.cancelled() => return Ok(cancel_waiting(&app, &request_id, &on_event)),
+            }
+            attempt += 1;
+        }
+    }
+
+    Err(last_error)
+}
+
+// Ends a request cancelled before anything was sent
+fn cancel_waiting(
+    app: &AppHandle,
+    request_id: &str,
+    on_event: &Channel<ChatStreamEvent>,
+) -> ChatOutcome {
+    let _ = on_event.send(ChatStreamEvent::Cancelled {
+        text: String::new(),
+    });
+    emit_for(
+        app,
+        request_id,
+        "llm-cancelled",
+        LlmCancelled {
+            request_id: request_id.to_string(),
+            text: String::new(),
+        },
+    );
+    ChatOutcome {
+        cancelled: true,
+        ..Default::default()
+    }
+}
+
+// Stores estimated tokens and cost in the background; failures are only logged
+fn record_usage(
+    app: &AppHandle,
+    request_id: &str,
+    candidate: &ChatCandidate,
+    outcome: &ChatOutcome,
+) {
+    let output_tokens = tokens::count_text(&candidate.model, &outcome.text);
+    if let Ok(mut limiter) = app.state::<LlmState>().limiter.lock() {
+        limiter.charge(&candidate.provider, output_tokens);
+    }
+    let cost_usd = candidate.input_tokens.and_then(|input_tokens| {
+        pricing::estimate_cost(
+            &candidate.provider,
+            &candidate.model,
+            input_tokens,
+            output_tokens,
+        )
+    });
+    let usage = crate::storage::UsageRecord {
+        request_id: request_id.to_string(),
+        provider: candidate.provider.clone(),
+        model: candidate.model.clone(),
+        input_tokens: candidate.input_tokens,
+        output_tokens,
+        cost_usd,
+        cancelled: outcome.cancelled,
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::storage::record_usage(&app, usage).await {
+            warn!("{}", e);
+        }
+    });
+}
+
+enum ChatError {
+    // Worth another try: rate limits, server errors, dropped connections
+    Transient {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    Fatal(String),
+    // Failed after text had streamed out
+    Interrupted(String),
+}
+
+impl From<String> for ChatError {
+    fn from(message: String) -> Self {
+        ChatError::Fatal(message)
+    }
+}
+
+#[derive(Default)]
+struct ChatOutcome {
+    text: String,
+    cancelled: bool,
+    // Tools the model wants run before it answers
+    tool_calls: Vec<ToolCall>,
+}
+
+fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+}
+
+// Hosts the key saved under `key_ref` may go to; empty if it isn't a chat
+// provider's key
+fn api_key_hosts(config: &LlmConfig, key_ref: &str) -> Vec<String> {
+    let mut hosts: Vec<String> = PROVIDER_KEY_HOSTS
+        .iter()
+        .filter(|(id, _)| *id == key_ref)
+        .map(|(_, host)| host.to_string())
+        .collect();
+    if let Some(kind) = LlmProviderKind::ALL
+        .into_iter()
+        .find(|kind| kind.key_id() == key_ref)
+    {
+        let base_url = if kind.is_local() {
+            Some(local::server_base_url(config, kind))
+        } else {
+            config.settings(kind).base_url.clone()
+        };
+        hosts.extend(base_url.as_deref().and_then(host_of));
+    }
+    hosts
+}
+
+// The webview names the keychain key a request carries, so the request must
+// be for that provider's host and may only put the key in a header
+fn check_api_key_ref(config: &LlmConfig, provider: &ProviderRequest) -> Result<(), String> {
+    let Some(key_ref) = provider.api_key_ref.as_deref() else {
+        return Ok(());
+    };
+    if provider.url.contains(API_KEY_PLACEHOLDER) {
+        return Err("API keys can only be sent in request headers".to_string());
+    }
+    let hosts = api_key_hosts(config, key_ref);
+    if hosts.is_empty() {
+        return Err(format!("{} is not a chat provider key", key_ref));
+    }
+    match host_of(&provider.url) {
+        Some(host) if hosts.contains(&host) => Ok(()),
+        _ => Err(format!(
+            "The {} key can only be sent to {}",
+            key_ref,
+            hosts.join(", ")
+        )),
+    }
+}
+
+async fn run_chat_request(
+    request_id: &str,
+    provider: &ProviderRequest,
+    payload: &serde_json::Value,
+    redaction: &[String],
+    on_event: &Channel<ChatStreamEvent>,
+    cancel: &CancelSignal,
+) -> Result<ChatOutcome, ChatError> {
+    let method = reqwest::Method::from_bytes(provider.method.to_uppercase().as_bytes())
+        .map_err(|e| format!("Invalid HTTP method {}: {}", provider.method, e))?;
+
+    let api_key = match &provider.api_key_ref {
+        Some(key_ref) => Some(
+            crate::secrets::get_api_key(key_ref)?
+                .ok_or(format!("No API key saved for {}", key_ref))?,
+        ),
+        None => None,
+    };
+    let with_key = |value: &str| match &api_key {
+        Some(key) => value.replace(API_KEY_PLACEHOLDER, key),
+        None => value.to_string(),
+    };
+
+    let client = crate::network::http_client()?;
+    let mut request = client
+        .request(method.clone(), &provider.url)
+        .header("Content-Type", "application/json");
+    for (name, value) in &provider.headers {
+        request = request.header(name, with_key(value));
+    }
+    if method != reqwest::Method::GET {
+        request = request.json(payload);
+    }
+
+    // Dropping the request future closes the connection
+    let response = tokio::select! {
+        response = crate::network::send(request, "chat", redaction) => {
+            response.map_err(|e| ChatError::Transient {
+                message: format!("Network error during API request: {}", e),
+                retry_after: None,
+            })?
+        }
+        _ = cancel.cancelled() => {
+            let _ = on_event.send(ChatStreamEvent::Cancelled { text: String::new() });
+            return Ok(ChatOutcome { cancelled: true, ..Default::default() });
+        }
+    };
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let retry_after = retry::retry_after(response.headers());
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown server error".to_string());
+        let message = format!("API request failed: {} - {}", status, error_text);
+        if retry::is_retryable_status(status) {
+            return Err(ChatError::Transient {
+                message,
+                retry_after,
+            });
+        }
+        return Err(ChatError::Fatal(message));
+    }
+
+    let content_path = provider.response_content_path.as_deref();
+
+    if !provider.stream {
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse non-streaming response: {}", e))?;
+        let text = content_path
+            .and_then(|path| get_by_path(&json, path))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let tool_calls = provider
+            .tool_format
+            .map(|format| tools::parse_tool_calls(format, &json))
+            .unwrap_or_default();
+        // The answer isn't done while the model is still calling tools
+        if tool_calls.is_empty() {
+            let _ = on_event.send(ChatStreamEvent::Done { text: text.clone() });
+        }
+        return Ok(ChatOutcome {
+            text,
+            cancelled: false,
+            tool_calls,
+        });
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut parser = SseParser::default();
+    let mut full_response = String::new();
+
+    let handle_event = |data: String, full_response: &mut String| -> bool {
+        if data.trim() == "[DONE]" {
+            return false;
+        }
+        // Ignore keep-alives and partial JSON
+        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&data) {
+            if let Some(text) = extract_stream_delta(&parsed, content_path) {
+                full_response.push_str(&text);
+                if crate::control::has_subscribers() {
+                    crate::control::publish(BridgeEvent::LlmToken {
+                        request_id: request_id.to_string(),
+                        text: text.clone(),
+                    });
+                }
+                let _ = on_event.send(ChatStreamEvent::Delta { text });
+            }
+        }
+        true
+    };
+
+    'read: loop {
+        let chunk = tokio::select! {
+            chunk = stream.next() => chunk,
+            _ = cancel.cancelled() => {
+                // Returning drops the body stream, which closes the connection
+                let _ = on_event.send(ChatStreamEvent::Cancelled { text: full_response.clone() });
+                return Ok(ChatOutcome { text: full_response, cancelled: true, ..Default::default() });
+            }
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        let bytes = chunk.map_err(|e| {
+            let message = format!("Error reading stream: {}", e);
+            if full_response.is_empty() {
+                ChatError::Transient {
+                    message,
+                    retry_after: None,
+                }
+            } else {
+                ChatError::Interrupted(message)
+            }
+        })?;
+        for data in parser.push(&bytes) {
+            if !handle_event(data, &mut full_response) {
+                break 'read;
+            }
+        }
+    }
+    if let Some(data) = parser.finish() {
+        handle_event(data, &mut full_response);
+    }
+
+    if full_response.is_empty() {
+        warn!("Streaming response from {} contained no text", provider.url);
+    }
+
+    let _ = on_event.send(ChatStreamEvent::Done {
+        text: full_response.clone(),
+    });
+    Ok(ChatOutcome {
+        text: full_response,
+        ..Default::default()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_llm_config(app: AppHandle) -> Result<LlmConfig, String> {
+    current_config(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_llm_config(app: AppHandle, mut config: LlmConfig) -> Result<(), String> {
+    for kind in LlmProviderKind::ALL {
+        if let Some(temperature) = config.settings(kind).temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err("Invalid temperature: must be 0.0-2.0".to_string());
+            }
+        }
+    }
+
+    for kind in LlmProviderKind::ALL {
+        let settings = config.settings(kind);
+        if settings.requests_per_minute == Some(0) || settings.tokens_per_minute == Some(0) {
+            return Err(format!(
+                "Invalid rate limit for {}: must be at least 1 per minute",
+                kind.key_id()
+            ));
+        }
+        if let Some(base_url) = settings.base_url.as_deref() {
+            if !base_url.trim().is_empty() {
+                reqwest::Url::parse(base_url.trim())
+                    .map_err(|e| format!("Invalid base URL for {}: {}", kind.key_id(), e))?;
+            }
+        }
+    }
+
+    if config.retry.max_retries > 10 {
+        return Err("Invalid retry count: must be 0-10".to_string());
+    }
+    if config.retry.initial_delay_ms > config.retry.max_delay_ms {
+        return Err("Invalid retry delays: initial delay exceeds the maximum".to_string());
+    }
+    if config.cache.enabled && config.cache.ttl_secs == 0 {
+        return Err("Invalid cache lifetime: must be at least 1 second".to_string());
+    }
+    config.scrub.validate()?;
+
+    // Move any keys into the keychain so the config never holds them
+    for kind in LlmProviderKind::ALL {
+        if let Some(api_key) = config.settings_mut(kind).api_key.take() {
+            crate::secrets::store_api_key(kind.key_id(), &api_key)?;
+        }
+    }
+
+    let state = app.state::<LlmState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update LLM config: {}", e))? = config;
+
+    Ok(())
+}
+
+// Capabilities of every provider that is configured well enough to use.
+#[tauri::command]
+#[specta::specta]
+pub fn list_llm_providers(app: AppHandle) -> Result<Vec<ProviderInfo>, String> {
+    let config = current_config(&app)?;
+
+    Ok(LlmProviderKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let provider = build_provider_kind(&config, kind).ok()?;
+            Some(provider_info(kind, provider.as_ref()))
+        })
+        .collect())
+}
+
+// Emitted when `llm_chat` answered from the response cache
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmCacheHit {
+    pub request_id: String,
+    pub provider: String,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Started,
+    Succeeded,
+    Failed,
+}
+
+// Emitted as `llm-tool-call` while the model's tool calls run
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct LlmToolCall {
+    pub request_id: String,
+    pub call: ToolCall,
+    pub status: ToolCallStatus,
+    pub error: Option<String>,
+}
+
+// Sends a conversation to the selected provider, streaming text over `on_event`.
+// A prompt profile adds its system prompt and overrides the model settings.
+// `tools` names built-in tools the model may call; their results are fed back
+// until it answers. With `queue_when_offline` set, a request that can't reach
+// the provider is queued and resolves empty after a `queued` event. When
+// scrubbing needs approval, a request that would be masked fails after an
+// `llm-scrub-review` event until `approve_pii_scrub` is called for it.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn llm_chat(
+    app: AppHandle,
+    window: WebviewWindow,
+    request_id: String,
+    messages: Vec<ChatMessage>,
+    stream: Option<bool>,
+    profile_id: Option<String>,
+    variables: Option<HashMap<String, String>>,
+    tools: Option<Vec<String>>,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<String, String> {
+    let request = QueuedChat {
+        messages,
+        stream: stream.unwrap_or(true),
+        profile_id,
+        variables,
+        tools: tools.unwrap_or_default(),
+    };
+    let _owner = register_owner(&app, &request_id, &window)?;
+
+    let scrub_config = current_config(&app)?.scrub;
+    if scrub_config.enabled && scrub_config.require_approval {
+        let preview = scrub::preview(&scrub_config, &request.messages)?;
+        hold_for_review(&app, &request_id, preview)?;
+    }
+
+    let started = Instant::now();
+    match chat(
+        app.clone(),
+        request_id.clone(),
+        request.clone(),
+        on_event.clone(),
+    )
+    .await
+    {
+        Ok(text) => {
+            crate::analytics::timing("chat", started.elapsed());
+            Ok(text)
+        }
+        Err(ChatFailure::Offline(e)) if current_config(&app)?.queue_when_offline => {
+            warn!("Queueing request {} while offline: {}", request_id, e);
+            queue::enqueue(&app, &request_id, &request).await?;
+            let _ = on_event.send(ChatStreamEvent::Queued);
+            Ok(String::new())
+        }
+        Err(ChatFailure::Offline(e)) | Err(ChatFailure::Failed(e)) => {
+            crate::notify::notify(
+                &app,
+                crate::notify::NotificationCategory::ProviderError,
+                "Request failed",
+                &crate::notify::summary(&e),
+            );
+            Err(e)
+        }
+    }
+}
+
+// One answer to `messages` from the configured provider, without streaming,
+// for backend features that need the model's text rather than a chat
+pub async fn complete(app: &AppHandle, messages: Vec<ChatMessage>) -> Result<String, String> {
+    let request = QueuedChat {
+        messages,
+        stream: false,
+        profile_id: None,
+        variables: None,
+        tools: Vec::new(),
+    };
+    let request_id = uuid::Uuid::new_v4().to_string();
+    match chat(app.clone(), request_id, request, Channel::new(|_| Ok(()))).await {
+        Ok(text) => Ok(text),
+        Err(ChatFailure::Offline(e)) | Err(ChatFailure::Failed(e)) => Err(e),
+    }
+}
+
+pub(super) enum ChatFailure {
+    // The provider couldn't be reached before anything was sent
+    Offline(String),
+    Failed(String),
+}
+
+impl From<String> for ChatFailure {
+    fn from(message: String) -> Self {
+        ChatFailure::Failed(message)
+    }
+}
+
+pub(super) async fn chat(
+    app: AppHandle,
+    request_id: String,
+    request: QueuedChat,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<String, ChatFailure> {
+    // Registered once for every round, so a cancel while a tool runs still counts
+    let (cancel, _guard) = start_request(&app, &request_id)?;
+    let mut config = current_config(&app)?;
+    let mut messages = request.messages;
+    let tools = tools::select_tools(&app, &request.tools)?;
+    // Tool calls are read from the whole response, so tool rounds don't stream
+    let stream = request.stream && tools.is_empty();
+
+    if let Some(profile_id) = request.profile_id {
+        let profile = crate::prompts::find_profile(&app, &profile_id)?;
+        let applied =
+            crate::prompts::apply_profile(&profile, &request.variables.unwrap_or_default())?;
+        if let Some(provider) = applied.provider {
+            config.provider = provider;
+        }
+        let settings = config.settings_mut(config.provider);
+        if applied.model.is_some() {
+            settings.model = applied.model;
+        }
+        if applied.temperature.is_some() {
+            settings.temperature = applied.temperature;
+        }
+        if !applied.system_prompt.trim().is_empty() {
+            messages.insert(0, ChatMessage::new(ChatRole::System, applied.system_prompt));
+        }
+    }
+    // Follows the system prompts so they still come first
+    if let Some(context) = crate::focus::context_prompt(&app) {
+        let at = messages
+            .iter()
+            .take_while(|message| message.role == ChatRole::System)
+            .count();
+        messages.insert(at, ChatMessage::new(ChatRole::System, context));
+    }
+
+    let cache_ttl_ms =
+        i64::try_from(config.cache.ttl_secs.saturating_mul(1000)).unwrap_or(i64::MAX);
+    let mut round = 0;
+    loop {
+        if cancel.is_cancelled() {
+            return Ok(cancel_waiting(&app, &request_id, &on_event).text);
+        }
+        // The last round offers no tools so the model has to answer
+        let offered = if round < MAX_TOOL_ROUNDS {
+            tools.clone()
+        } else {
+            Vec::new()
+        };
+        let config = config.clone();
+        let round_messages = messages.clone();
+        // Image re-encoding and tokenizing are CPU-bound
+        let (candidates, preparation, cache_key) =
+            tauri::async_runtime::spawn_blocking(move || {
+                let (candidates, preparation) =
+                    chat_candidates(&config, &round_messages, stream, &offered)?;
+                // Keyed on what the user asked, before images were fitted to the
+                // provider. Tool results depend on the moment, so they skip the cache.
+                let cache_key =
+                    (config.cache.enabled && offered.is_empty() && round == 0).then(|| {
+                        cache::cache_key(
+                            &candidates[0].provider,
+                            &candidates[0].model,
+                            &round_messages,
+                        )
+                    });
+                Ok::<_, String>((candidates, preparation, cache_key))
+            })
+            .await
+            .map_err(|e| format!("Task panicked: {}", e))??;
+
+        if let Some(key) = &cache_key {
+            match crate::storage::cached_response(&app, key, cache_ttl_ms).await {
+                Ok(Some(text)) => {
+                    emit_for(
+                        &app,
+                        &request_id,
+                        "llm-cache-hit",
+                        LlmCacheHit {
+                            request_id: request_id.clone(),
+                            provider: candidates[0].provider.clone(),
+                            model: candidates[0].model.clone(),
+                        },
+                    );
+                    crate::control::publish(BridgeEvent::LlmDone {
+                        request_id: request_id.clone(),
+                        text: text.clone(),
+                    });
+                    let _ = on_event.send(ChatStreamEvent::Done { text: text.clone() });
+                    return Ok(text);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        if !preparation.images.is_empty() || !preparation.reused.is_empty() {
+            emit_for(
+                &app,
+                &request_id,
+                "llm-images-processed",
+                LlmImagesProcessed {
+                    request_id: request_id.clone(),
+                    images: preparation.images,
+                    reused: preparation.reused,
+                },
+            );
+        }
+        if !preparation.masked.is_empty() {
+            emit_for(
+                &app,
+                &request_id,
+                "llm-text-scrubbed",
+                LlmTextScrubbed {
+                    request_id: request_id.clone(),
+                    masked: preparation.masked,
+                },
+            );
+        }
+        let trim = preparation.trim;
+        if trim.dropped_messages > 0 || trim.dropped_images > 0 {
+            emit_for(
+                &app,
+                &request_id,
+                "llm-context-trimmed",
+                LlmContextTrimmed {
+                    request_id: request_id.clone(),
+                    trim,
+                },
+            );
+        }
+
+        let (provider, model) = (candidates[0].provider.clone(), candidates[0].model.clone());
+        let probe_url = candidates[0].request.url.clone();
+        let outcome = match run_registered(
+            app.clone(),
+            request_id.clone(),
+            &cancel,
+            candidates,
+            on_event.clone(),
+        )
+        .await
+        {
+            Ok(outcome) => outcome,
+            // Nothing has run yet, so the whole request can wait for the network
+            Err(e) if round == 0 && !crate::network::is_reachable(&probe_url).await => {
+                return Err(ChatFailure::Offline(e));
+            }
+            Err(e) => return Err(ChatFailure::Failed(e)),
+        };
+
+        if outcome.cancelled || outcome.tool_calls.is_empty() {
+            // Only complete answers are worth repeating
+            if let Some(key) = cache_key {
+                if !outcome.cancelled && !outcome.text.is_empty() {
+                    if let Err(e) =
+                        crate::storage::store_response(&app, &key, &provider, &model, &outcome.text)
+                            .await
+                    {
+                        warn!("{}", e);
+                    }
+                }
+            }
+            return Ok(outcome.text);
+        }
+
+        let mut assistant = ChatMessage::new(ChatRole::Assistant, outcome.text);
+        assistant.tool_calls = outcome.tool_calls.clone();
+        messages.push(assistant);
+        for call in outcome.tool_calls {
+            if cancel.is_cancelled() {
+                break;
+            }
+            messages.push(run_tool_call(&app, &request_id, call).await);
+        }
+        round += 1;
+    }
+}
+
+// Runs one tool call, reporting progress as `llm-tool-call` events
+async fn run_tool_call(app: &AppHandle, request_id: &str, call: ToolCall) -> ChatMessage {
+    let emit = |status, error| {
+        emit_for(
+            app,
+            request_id,
+            "llm-tool-call",
+            LlmToolCall {
+                request_id: request_id.to_string(),
+                call: call.clone(),
+                status,
+                error,
+            },
+        );
+    };
+
+    emit(ToolCallStatus::Started, None);
+    let mut message = match tools::run_tool(app, &call).await {
+        Ok(output) => {
+            emit(ToolCallStatus::Succeeded, None);
+            let mut message = ChatMessage::new(ChatRole::Tool, output.text);
+            message.images = output.images;
+            message
+        }
+        Err(e) => {
+            warn!("Tool {} failed: {}", call.name, e);
+            emit(ToolCallStatus::Failed, Some(e.clone()));
+            ChatMessage::new(ChatRole::Tool, format!("Error: {}", e))
+        }
+    };
+    message.tool_call_id = Some(call.id);
+    message.tool_name = Some(call.name);
+    message
+}
+
+// What was done to the conversation to fit the selected provider
+#[derive(Default)]
+struct Preparation {
+    trim: ContextTrim,
+    images: Vec<ImageTransform>,
+    reused: Vec<ImageReuse>,
+    masked: Vec<String>,
+}
+
+// The selected provider must work; failover entries that aren't set up are
+// skipped. In local-only mode a cloud provider gives way to the first local
+// failover entry, or Ollama. Text is scrubbed first, whichever provider
+// it goes to.
+fn chat_candidates(
+    config: &LlmConfig,
+    messages: &[ChatMessage],
+    stream: bool,
+    tools: &[ToolSpec],
+) -> Result<(Vec<ChatCandidate>, Preparation), String> {
+    let local_only = crate::network::local_only();
+    let provider = if local_only && !config.provider.is_local() {
+        config
+            .failover
+            .iter()
+            .copied()
+            .find(|kind| kind.is_local())
+            .unwrap_or(LlmProviderKind::Ollama)
+    } else {
+        config.provider
+    };
+    let (messages, masked) = scrub::scrub_messages(&config.scrub, messages)?;
+    let messages = &messages;
+    let (primary, mut preparation) = chat_candidate(config, provider, messages, stream, tools)?;
+    let mut candidates = vec![primary];
+    for kind in &config.failover {
+        if candidates.iter().any(|c| c.provider == kind.key_id())
+            || (local_only && !kind.is_local())
+        {
+            continue;
+        }
+        match chat_candidate(config, *kind, messages, stream, tools) {
+            Ok((candidate, _)) => candidates.push(candidate),
+            Err(e) => warn!("Skipping failover provider {}: {}", kind.key_id(), e),
+        }
+    }
+    for candidate in &mut candidates {
+        candidate.redaction.extend(masked.iter().cloned());
+    }
+    preparation.masked = masked;
+    Ok((candidates, preparation))
+}
+
+// Builds the request for one provider, with images and context fitted to it
+fn chat_candidate(
+    config: &LlmConfig,
+    kind: LlmProviderKind,
+    messages: &[ChatMessage],
+    stream: bool,
+    tools: &[ToolSpec],
+) -> Result<(ChatCandidate, Preparation), String> {
+    crate::settings::ensure_provider_allowed(kind)?;
+    let provider = build_provider_kind(config, kind)?;
+    let stream = stream && provider.supports_streaming();
+    let (messages, images) = images::prepare_images(messages, provider.image_limits())?;
+    let (messages, trim) = tokens::fit_to_context(provider.model(), provider.limits(), &messages)?;
+    // After trimming, so the screenshot a repeat points back to is still sent
+    let (messages, reused) = dedup::dedup_images(&messages);
+    let (mut request, mut payload) = provider.build_request(&messages, stream)?;
+    if !tools.is_empty() {
+        let format = provider.tool_format().ok_or(format!(
+            "Provider {} ({}) does not support tools",
+            provider.name(),
+            provider.model()
+        ))?;
+        tools::add_tools(format, &mut payload, tools);
+        request.tool_format = Some(format);
+    }
+    let mut redaction = Vec::new();
+    if !images.is_empty() {
+        redaction.push(format!("{} image(s) resized or re-encoded", images.len()));
+    }
+    if !reused.is_empty() {
+        redaction.push(format!("{} repeated screenshot(s) left out", reused.len()));
+    }
+    if trim.dropped_messages > 0 || trim.dropped_images > 0 {
+        redaction.push(format!(
+            "{} older message(s) and {} image(s) trimmed to fit the context",
+            trim.dropped_messages, trim.dropped_images
+        ));
+    }
+    Ok((
+        ChatCandidate {
+            provider: kind.key_id().to_string(),
+            model: provider.model().to_string(),
+            input_tokens: Some(trim.tokens_after),
+            limits: RateLimits {
+                requests_per_minute: config.settings(kind).requests_per_minute,
+                tokens_per_minute: config.settings(kind).tokens_per_minute,
+            },
+            request,
+            payload,
+            redaction,
+        },
+        Preparation {
+            trim,
+            images,
+            reused,
+            masked: Vec::new(),
+        },
+    ))
+}
+
+// Token usage of a conversation for `model`, counting images by their size
+#[tauri::command]
+#[specta::specta]
+pub async fn count_tokens(model: String, messages: Vec<ChatMessage>) -> Result<TokenCount, String> {
+    // Loading a tokenizer takes a moment the first time
+    tauri::async_runtime::spawn_blocking(move || tokens::count_messages(&model, &messages))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))
+}
+
+// Models installed on the local Ollama and LM Studio servers. Servers that
+// aren't running are skipped rather than failing the whole call.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_local_models(app: AppHandle) -> Result<Vec<LocalModel>, String> {
+    let config = current_config(&app)?;
+    let ollama_url = local::server_base_url(&config, LlmProviderKind::Ollama);
+    let lm_studio_url = local::server_base_url(&config, LlmProviderKind::LmStudio);
+
+    let (ollama, lm_studio) = tokio::join!(
+        local::list_ollama_models(&ollama_url),
+        local::list_lm_studio_models(&lm_studio_url),
+    );
+
+    let mut models = Vec::new();
+    for result in [ollama, lm_studio] {
+        match result {
+            Ok(found) => models.extend(found),
+            Err(e) => warn!("Local model discovery failed: {}", e),
+        }
+    }
+    Ok(models)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn check_local_llm_health(app: AppHandle) -> Result<Vec<LocalServerStatus>, String> {
+    let config = current_config(&app)?;
+    let (ollama, lm_studio) = tokio::join!(
+        local::check_server(
+            LlmProviderKind::Ollama,
+            local::server_base_url(&config, LlmProviderKind::Ollama)
+        ),
+        local::check_server(
+            LlmProviderKind::LmStudio,
+            local::server_base_url(&config, LlmProviderKind::LmStudio)
+        ),
+    );
+    Ok(vec![ollama, lm_studio])
+}
+
+// Stops an in-flight request. The request itself emits `llm-cancelled` once it
+// has shut down; returns false if nothing is running under `request_id`.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_request(app: AppHandle, request_id: String) -> Result<bool, String> {
+    cancel_registered(&app.state::<LlmState>().requests, &request_id)
+}
+
+fn cancel_registered(
+    requests: &Mutex<HashMap<String, Arc<CancelSignal>>>,
+    request_id: &str,
+) -> Result<bool, String> {
+    let requests = requests
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+    match requests.get(request_id) {
+        Some(cancel) => {
+            cancel.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// Replays requests queued while offline, for as long as the app runs
+pub fn start_request_queue(app: AppHandle) {
+    let wake = app.state::<LlmState>().queue_wake.clone();
+    tauri::async_runtime::spawn(queue::run_worker(app, wake));
+}
+
+// What scrubbing would mask in `messages` with the current settings
+#[tauri::command]
+#[specta::specta]
+pub fn preview_pii_scrub(
+    app: AppHandle,
+    messages: Vec<ChatMessage>,
+) -> Result<ScrubPreview, String> {
+    scrub::preview(&current_config(&app)?.scrub, &messages)
+}
+
+// Lets the next `llm_chat` whose scrub preview has `approval_id` through
+#[tauri::command]
+#[specta::specta]
+pub fn approve_pii_scrub(app: AppHandle, approval_id: String) -> Result<(), String> {
+    app.state::<LlmState>()
+        .approved_scrubs
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .insert(approval_id);
+    Ok(())
+}
+
+// Retries queued requests now instead of at the next interval
+#[tauri::command]
+#[specta::specta]
+pub fn retry_queued_requests(app: AppHandle) {
+    app.state::<LlmState>().queue_wake.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancel_during_tool_call_stops_next_round() {
+        let requests = Mutex::new(HashMap::new());
+        let cancel = register_request(&requests, "chat").unwrap();
+
+        // The first round's wait consumed nothing; a tool is running when the
+        // cancel arrives
+        let tool = tokio::time::sleep(Duration::from_millis(20));
+        let (_, found) = tokio::join!(tool, async {
+            cancel_registered(&requests, "chat").unwrap()
+        });
+        assert!(found);
+        assert!(cancel.is_cancelled());
+
+        // Every later wait ends at once, not just the one that takes the permit
+        for _ in 0..2 {
+            tokio::time::timeout(Duration::from_secs(1), cancel.cancelled())
+                .await
+                .unwrap();
+        }
+        assert!(register_request(&requests, "chat").is_err());
+    }
+}
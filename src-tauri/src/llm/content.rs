@@ -0,0 +1,47 @@
+// Helpers for pulling generated text out of provider JSON responses.
+use serde_json::Value;
+
+// Resolves a path like `choices[0].delta.content` against a JSON value.
+pub fn get_by_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = match segment.find('[') {
+            Some(idx) => (&segment[..idx], &segment[idx..]),
+            None => (segment, ""),
+        };
+
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.trim_end_matches(']').parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+
+    Some(current)
+}
+
+// Same lookup order as the frontend's `getStreamingContent`: the configured path
+// adjusted for deltas, well-known streaming shapes, then the path itself.
+pub fn extract_stream_delta(chunk: &Value, content_path: Option<&str>) -> Option<String> {
+    let default_path = content_path.unwrap_or("");
+    let candidates = [
+        default_path.replace(".message.", ".delta."),
+        "choices[0].delta.content".to_string(), // OpenAI, Groq, Mistral, Perplexity
+        "candidates[0].content.parts[0].text".to_string(), // Gemini
+        "delta.text".to_string(), // Claude
+        "message.content".to_string(), // Ollama
+        "text".to_string(), // Cohere
+        default_path.to_string(),
+    ];
+
+    candidates
+        .iter()
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| get_by_path(chunk, path)?.as_str())
+        .find(|text| !text.is_empty())
+        .map(|text| text.to_string())
+}
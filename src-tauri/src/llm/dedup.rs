@@ -0,0 +1,129 @@
+// Drops screenshots the model has already seen. Every attached image gets a
+// perceptual hash, and one that matches an image earlier in the conversation
+// is replaced by a note pointing back to it instead of being uploaded again.
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::FilterType;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::{strip_data_url, ChatMessage};
+
+// Hashes at most this many bits apart are the same screenshot, which absorbs
+// re-encoding and scaling differences
+const MAX_DISTANCE: u32 = 2;
+// Conversations resend their images every turn, so hashes are remembered
+const MEMO_SIZE: usize = 256;
+
+static MEMO: Lazy<Mutex<HashMap<[u8; 32], Option<u64>>>> = Lazy::new(Default::default);
+
+// One image left out because an earlier one matches it
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ImageReuse {
+    pub message_index: usize,
+    pub image_index: usize,
+    pub original_message_index: usize,
+    pub original_image_index: usize,
+}
+
+// DCT hash: the signs of the lowest 8x8 frequencies of a 32x32 grayscale copy
+// against their median
+fn compute_phash(image_base64: &str) -> Option<u64> {
+    let bytes = general_purpose::STANDARD
+        .decode(strip_data_url(image_base64))
+        .ok()?;
+    let image = image::load_from_memory(&bytes).ok()?;
+    let small = image.resize_exact(32, 32, FilterType::Triangle).to_luma8();
+    let cosines: Vec<[f64; 32]> = (0..8)
+        .map(|u| {
+            let mut row = [0.0; 32];
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = (((2 * x + 1) * u) as f64 * std::f64::consts::PI / 64.0).cos();
+            }
+            row
+        })
+        .collect();
+    let mut coefficients = [0.0f64; 64];
+    for (v, cosines_v) in cosines.iter().enumerate() {
+        for (u, cosines_u) in cosines.iter().enumerate() {
+            let mut sum = 0.0;
+            for (y, cos_y) in cosines_v.iter().enumerate() {
+                for (x, cos_x) in cosines_u.iter().enumerate() {
+                    sum += small.get_pixel(x as u32, y as u32)[0] as f64 * cos_x * cos_y;
+                }
+            }
+            coefficients[v * 8 + u] = sum;
+        }
+    }
+    // The first coefficient is overall brightness, which says nothing of content
+    let mut sorted = coefficients[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    Some(
+        coefficients
+            .iter()
+            .fold(0u64, |hash, c| (hash << 1) | (*c > median) as u64),
+    )
+}
+
+pub fn phash(image_base64: &str) -> Option<u64> {
+    let key: [u8; 32] = Sha256::digest(strip_data_url(image_base64).as_bytes()).into();
+    if let Some(hash) = MEMO.lock().ok().and_then(|memo| memo.get(&key).copied()) {
+        return hash;
+    }
+    let hash = compute_phash(image_base64);
+    if let Ok(mut memo) = MEMO.lock() {
+        if memo.len() >= MEMO_SIZE {
+            memo.clear();
+        }
+        memo.insert(key, hash);
+    }
+    hash
+}
+
+pub fn dedup_images(messages: &[ChatMessage]) -> (Vec<ChatMessage>, Vec<ImageReuse>) {
+    let mut deduped = messages.to_vec();
+    let mut reused = Vec::new();
+    // Hashes of images kept so far, with where they end up
+    let mut seen: Vec<(u64, usize, usize)> = Vec::new();
+
+    for (message_index, message) in deduped.iter_mut().enumerate() {
+        let mut kept = Vec::new();
+        let mut notes = Vec::new();
+        for (image_index, image) in message.images.drain(..).enumerate() {
+            let Some(hash) = phash(&image) else {
+                kept.push(image);
+                continue;
+            };
+            let original = seen
+                .iter()
+                .find(|(seen_hash, _, _)| (seen_hash ^ hash).count_ones() <= MAX_DISTANCE);
+            match original {
+                Some(&(_, original_message_index, original_image_index)) => {
+                    notes.push(format!(
+                        "[Attached image {} is the same screenshot as one attached earlier]",
+                        image_index + 1
+                    ));
+                    reused.push(ImageReuse {
+                        message_index,
+                        image_index,
+                        original_message_index,
+                        original_image_index,
+                    });
+                }
+                None => {
+                    seen.push((hash, message_index, kept.len()));
+                    kept.push(image);
+                }
+            }
+        }
+        message.images = kept;
+        if !notes.is_empty() {
+            message.content = format!("{}\n\n{}", message.content, notes.join("\n"));
+        }
+    }
+    (deduped, reused)
+}
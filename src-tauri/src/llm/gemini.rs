@@ -0,0 +1,184 @@
+// Google Gemini generateContent API
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::{
+    ensure_vision, image_mime_type, required_key, strip_data_url, ChatMessage, ChatRole,
+    ImageLimits, Provider, ProviderRequest, ProviderSettings, TokenLimits, ToolFormat,
+};
+
+const GEMINI_HOST: &str = "https://generativelanguage.googleapis.com";
+const GEMINI_API_VERSION: &str = "v1beta";
+
+pub struct GeminiProvider {
+    settings: ProviderSettings,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl GeminiProvider {
+    pub fn new(settings: ProviderSettings) -> Result<Self, String> {
+        let api_key = required_key(&settings, "Gemini")?;
+        let base_url = settings.base_url.clone().unwrap_or_else(|| {
+            let version = settings
+                .api_version
+                .as_deref()
+                .map(str::trim)
+                .filter(|version| !version.is_empty())
+                .unwrap_or(GEMINI_API_VERSION);
+            format!("{}/{}", GEMINI_HOST, version)
+        });
+        let model = settings
+            .model
+            .clone()
+            .unwrap_or_else(|| "gemini-2.0-flash".to_string());
+
+        Ok(Self {
+            settings,
+            base_url,
+            model,
+            api_key,
+        })
+    }
+}
+
+impl Provider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.settings.supports_vision.unwrap_or(true)
+    }
+
+    fn limits(&self) -> TokenLimits {
+        let default_output = if self.model.starts_with("gemini-2.5") {
+            65_536
+        } else {
+            8_192
+        };
+        TokenLimits {
+            context_window: self.settings.context_window.unwrap_or(1_048_576),
+            max_output_tokens: self.settings.max_output_tokens.unwrap_or(default_output),
+        }
+    }
+
+    fn image_limits(&self) -> ImageLimits {
+        // Inline data shares a 20MB request limit, so leave room for several captures
+        ImageLimits {
+            max_dimension: 3_072,
+            max_bytes: 7 * 1024 * 1024,
+        }
+    }
+
+    fn tool_format(&self) -> Option<ToolFormat> {
+        Some(ToolFormat::Gemini)
+    }
+
+    fn build_request(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<(ProviderRequest, Value), String> {
+        ensure_vision(self, messages)?;
+
+        let system: Vec<Value> = messages
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .map(|m| json!({ "text": m.content }))
+            .collect();
+
+        let mut contents: Vec<Value> = Vec::new();
+        for message in messages.iter().filter(|m| m.role != ChatRole::System) {
+            let role = if message.role == ChatRole::Assistant {
+                "model"
+            } else {
+                "user"
+            };
+
+            let mut parts = Vec::new();
+            if message.role == ChatRole::Tool {
+                parts.push(json!({
+                    "functionResponse": {
+                        "name": message.tool_name,
+                        "response": { "content": message.content },
+                    }
+                }));
+            } else if !message.content.is_empty() || message.tool_calls.is_empty() {
+                parts.push(json!({ "text": message.content }));
+            }
+            for call in &message.tool_calls {
+                parts.push(json!({
+                    "functionCall": { "name": call.name, "args": call.arguments }
+                }));
+            }
+            for image in &message.images {
+                let data = strip_data_url(image);
+                parts.push(json!({
+                    "inline_data": {
+                        "mime_type": image_mime_type(data),
+                        "data": data,
+                    }
+                }));
+            }
+
+            // Responses to parallel calls go back together in one turn
+            let previous_is_tool = message.role == ChatRole::Tool
+                && contents
+                    .last()
+                    .is_some_and(|last| last["parts"][0].get("functionResponse").is_some());
+            match contents.last_mut() {
+                Some(last) if previous_is_tool => {
+                    if let Some(existing) = last["parts"].as_array_mut() {
+                        existing.extend(parts);
+                    }
+                }
+                _ => contents.push(json!({ "role": role, "parts": parts })),
+            }
+        }
+
+        let mut body = json!({ "contents": contents });
+        if !system.is_empty() {
+            body["systemInstruction"] = json!({ "parts": system });
+        }
+
+        let mut generation_config = serde_json::Map::new();
+        if let Some(max_tokens) = self.settings.max_output_tokens {
+            generation_config.insert("maxOutputTokens".to_string(), json!(max_tokens));
+        }
+        if let Some(temperature) = self.settings.temperature {
+            generation_config.insert("temperature".to_string(), json!(temperature));
+        }
+        if !generation_config.is_empty() {
+            body["generationConfig"] = Value::Object(generation_config);
+        }
+
+        // Streaming is a different method, not a body flag
+        let method = if stream {
+            "streamGenerateContent?alt=sse"
+        } else {
+            "generateContent"
+        };
+
+        let request = ProviderRequest {
+            url: format!(
+                "{}/models/{}:{}",
+                self.base_url.trim_end_matches('/'),
+                self.model,
+                method
+            ),
+            method: "POST".to_string(),
+            headers: HashMap::from([("x-goog-api-key".to_string(), self.api_key.clone())]),
+            stream,
+            response_content_path: Some("candidates[0].content.parts[0].text".to_string()),
+            api_key_ref: None,
+            tool_format: None,
+        };
+        Ok((request, body))
+    }
+}
@@ -0,0 +1,168 @@
+// Fits attached images to a provider's documented limits before sending, so
+// oversized captures get downscaled instead of failing with an opaque 400.
+use base64::{engine::general_purpose, Engine as _};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use serde::Serialize;
+use specta::Type;
+use std::io::Cursor;
+
+use super::{strip_data_url, ChatMessage, ImageLimits};
+
+// Tried in order once lossless PNG is too big
+const JPEG_QUALITIES: [u8; 3] = [85, 70, 55];
+// Each further step shrinks both sides by this factor
+const DOWNSCALE_FACTOR: f32 = 0.75;
+const MAX_DOWNSCALE_STEPS: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub bytes: usize,
+    pub format: String,
+}
+
+// One image that had to be changed, and how
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ImageTransform {
+    pub message_index: usize,
+    pub image_index: usize,
+    pub original: ImageInfo,
+    pub processed: ImageInfo,
+}
+
+fn format_name(format: ImageFormat) -> String {
+    format
+        .extensions_str()
+        .first()
+        .unwrap_or(&"unknown")
+        .to_string()
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if format == ImageFormat::Jpeg {
+        // JPEG has no alpha channel
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality))
+            .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    } else {
+        image
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+    }
+    Ok(bytes)
+}
+
+// Re-encodes `image` under `max_bytes`: PNG first, then JPEG at falling
+// quality, shrinking further if even that is too big.
+fn shrink_to_size(
+    mut image: DynamicImage,
+    max_bytes: usize,
+) -> Result<(DynamicImage, Vec<u8>, ImageFormat), String> {
+    for _ in 0..=MAX_DOWNSCALE_STEPS {
+        let png = encode(&image, ImageFormat::Png, 0)?;
+        if png.len() <= max_bytes {
+            return Ok((image, png, ImageFormat::Png));
+        }
+        for quality in JPEG_QUALITIES {
+            let jpeg = encode(&image, ImageFormat::Jpeg, quality)?;
+            if jpeg.len() <= max_bytes {
+                return Ok((image, jpeg, ImageFormat::Jpeg));
+            }
+        }
+        let (width, height) = image.dimensions();
+        image = image.resize(
+            ((width as f32 * DOWNSCALE_FACTOR) as u32).max(1),
+            ((height as f32 * DOWNSCALE_FACTOR) as u32).max(1),
+            FilterType::Triangle,
+        );
+    }
+    Err(format!(
+        "Image could not be reduced below {} bytes",
+        max_bytes
+    ))
+}
+
+// Returns the image unchanged (None) when it already fits, otherwise the
+// processed base64 and what was done to it.
+fn fit_image(
+    image_base64: &str,
+    limits: ImageLimits,
+) -> Result<Option<(String, ImageInfo, ImageInfo)>, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(strip_data_url(image_base64))
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let format =
+        image::guess_format(&bytes).map_err(|e| format!("Unrecognized image format: {}", e))?;
+    // The header is enough to tell whether the image needs any work
+    let (width, height) = image::ImageReader::with_format(Cursor::new(&bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+
+    let supported = matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Gif
+    );
+    if supported && width.max(height) <= limits.max_dimension && bytes.len() <= limits.max_bytes {
+        return Ok(None);
+    }
+
+    let original = ImageInfo {
+        width,
+        height,
+        bytes: bytes.len(),
+        format: format_name(format),
+    };
+
+    let image = image::load_from_memory_with_format(&bytes, format)
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    let image = if width.max(height) > limits.max_dimension {
+        image.resize(
+            limits.max_dimension,
+            limits.max_dimension,
+            FilterType::Lanczos3,
+        )
+    } else {
+        image
+    };
+    let (image, encoded, format) = shrink_to_size(image, limits.max_bytes)?;
+    let (width, height) = image.dimensions();
+
+    let processed = ImageInfo {
+        width,
+        height,
+        bytes: encoded.len(),
+        format: format_name(format),
+    };
+    Ok(Some((
+        general_purpose::STANDARD.encode(encoded),
+        original,
+        processed,
+    )))
+}
+
+pub fn prepare_images(
+    messages: &[ChatMessage],
+    limits: ImageLimits,
+) -> Result<(Vec<ChatMessage>, Vec<ImageTransform>), String> {
+    let mut prepared = messages.to_vec();
+    let mut transforms = Vec::new();
+
+    for (message_index, message) in prepared.iter_mut().enumerate() {
+        for (image_index, image) in message.images.iter_mut().enumerate() {
+            if let Some((processed_image, original, processed)) = fit_image(image, limits)? {
+                *image = processed_image;
+                transforms.push(ImageTransform {
+                    message_index,
+                    image_index,
+                    original,
+                    processed,
+                });
+            }
+        }
+    }
+    Ok((prepared, transforms))
+}
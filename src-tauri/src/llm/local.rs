@@ -0,0 +1,168 @@
+// Local inference servers (Ollama, LM Studio): discovery, health checks and the
+// provider, which talks to their OpenAI-compatible `/v1` endpoints.
+use serde::Serialize;
+use specta::Type;
+use std::time::Duration;
+
+use super::{LlmConfig, LlmProviderKind};
+
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+const LM_STUDIO_BASE_URL: &str = "http://localhost:1234";
+
+// Local servers answer immediately or not at all
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Model families that accept images; used when the server doesn't say
+const VISION_MODEL_HINTS: &[&str] = &[
+    "llava",
+    "bakllava",
+    "vision",
+    "moondream",
+    "minicpm-v",
+    "qwen2.5vl",
+    "qwen2-vl",
+    "gemma3",
+    "llama4",
+    "granite3.2-vision",
+    "pixtral",
+];
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocalModel {
+    pub server: LlmProviderKind,
+    pub name: String,
+    pub size_bytes: Option<u64>,
+    pub supports_vision: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocalServerStatus {
+    pub server: LlmProviderKind,
+    pub base_url: String,
+    pub online: bool,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn looks_like_vision_model(name: &str) -> bool {
+    let name = name.to_lowercase();
+    VISION_MODEL_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+// Server root for a local provider, without the `/v1` suffix
+pub fn server_base_url(config: &LlmConfig, kind: LlmProviderKind) -> String {
+    let default = match kind {
+        LlmProviderKind::LmStudio => LM_STUDIO_BASE_URL,
+        _ => OLLAMA_BASE_URL,
+    };
+    config
+        .settings(kind)
+        .base_url
+        .clone()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| default.to_string())
+        .trim_end_matches('/')
+        .trim_end_matches("/v1")
+        .to_string()
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    crate::network::client_builder()?
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn get_json(url: &str) -> Result<serde_json::Value, String> {
+    let response = client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response from {}: {}", url, e))
+}
+
+pub async fn list_ollama_models(base_url: &str) -> Result<Vec<LocalModel>, String> {
+    let json = get_json(&format!("{}/api/tags", base_url)).await?;
+    let models = json
+        .get("models")
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(models
+        .iter()
+        .filter_map(|model| {
+            let name = model.get("name")?.as_str()?.to_string();
+            // Newer Ollama versions list the families a model was built from
+            let clip = model
+                .pointer("/details/families")
+                .and_then(|f| f.as_array())
+                .is_some_and(|f| f.iter().any(|v| v.as_str() == Some("clip")));
+            Some(LocalModel {
+                server: LlmProviderKind::Ollama,
+                supports_vision: clip || looks_like_vision_model(&name),
+                size_bytes: model.get("size").and_then(|s| s.as_u64()),
+                name,
+            })
+        })
+        .collect())
+}
+
+pub async fn list_lm_studio_models(base_url: &str) -> Result<Vec<LocalModel>, String> {
+    let json = get_json(&format!("{}/v1/models", base_url)).await?;
+    let models = json
+        .get("data")
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(models
+        .iter()
+        .filter_map(|model| {
+            let name = model.get("id")?.as_str()?.to_string();
+            Some(LocalModel {
+                server: LlmProviderKind::LmStudio,
+                supports_vision: looks_like_vision_model(&name),
+                size_bytes: None,
+                name,
+            })
+        })
+        .collect())
+}
+
+pub async fn check_server(kind: LlmProviderKind, base_url: String) -> LocalServerStatus {
+    let result = match kind {
+        LlmProviderKind::Ollama => get_json(&format!("{}/api/version", base_url))
+            .await
+            .map(|json| json.get("version").and_then(|v| v.as_str()).map(String::from)),
+        _ => get_json(&format!("{}/v1/models", base_url))
+            .await
+            .map(|_| None),
+    };
+
+    match result {
+        Ok(version) => LocalServerStatus {
+            server: kind,
+            base_url,
+            online: true,
+            version,
+            error: None,
+        },
+        Err(e) => LocalServerStatus {
+            server: kind,
+            base_url,
+            online: false,
+            version: None,
+            error: Some(e),
+        },
+    }
+}
@@ -0,0 +1,390 @@
+// Pluely LLM request proxy. Provider calls run in Rust so API keys never touch
+// the webview and CORS doesn't apply; tokens stream back over a Tauri channel.
+// Vendors sit behind the `Provider` trait, so adding one doesn't touch the frontend.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+mod anthropic;
+mod cache;
+mod commands;
+mod content;
+mod dedup;
+mod gemini;
+mod images;
+mod local;
+mod openai;
+mod pricing;
+mod queue;
+mod ratelimit;
+mod retry;
+mod scrub;
+mod sse;
+mod tokens;
+mod tools;
+
+// Re-export commands for tauri handler
+pub use cache::CacheConfig;
+pub use commands::*;
+pub use dedup::ImageReuse;
+pub use images::ImageTransform;
+pub use queue::{QueueStatus, QueuedRequestFinished};
+pub use retry::{is_retryable_status, retry_after, RetryConfig};
+pub use scrub::{ScrubConfig, ScrubPreview};
+pub use tokens::{ContextTrim, TokenCount};
+pub use tools::{ocr_image, ToolCall, ToolFormat, ToolSpec};
+
+// Where and how to send a chat request
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRequest {
+    pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default = "default_stream")]
+    pub stream: bool,
+    // Path to the generated text in a non-streaming response, e.g. `choices[0].message.content`
+    #[serde(default)]
+    pub response_content_path: Option<String>,
+    // Keychain account whose key replaces `{{API_KEY}}` in the headers, so the
+    // webview never holds the key. Only chat provider keys are allowed, and only
+    // when the url is that provider's host.
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
+    // Set when the body offers tools, so the response is checked for tool calls
+    #[serde(default)]
+    pub tool_format: Option<ToolFormat>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+fn default_stream() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum ChatStreamEvent {
+    Delta { text: String },
+    Done { text: String },
+    Cancelled { text: String },
+    // Offline; the answer comes later as `queued-request-finished`
+    Queued,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProviderKind {
+    #[default]
+    OpenAi,
+    Anthropic,
+    Gemini,
+    OpenRouter,
+    OpenAiCompatible,
+    AzureOpenAi,
+    Ollama,
+    LmStudio,
+}
+
+impl LlmProviderKind {
+    pub const ALL: [LlmProviderKind; 8] = [
+        LlmProviderKind::OpenAi,
+        LlmProviderKind::Anthropic,
+        LlmProviderKind::Gemini,
+        LlmProviderKind::OpenRouter,
+        LlmProviderKind::OpenAiCompatible,
+        LlmProviderKind::AzureOpenAi,
+        LlmProviderKind::Ollama,
+        LlmProviderKind::LmStudio,
+    ];
+
+    // Account name for the provider's key in the OS keychain
+    pub fn key_id(self) -> &'static str {
+        match self {
+            LlmProviderKind::OpenAi => "openai",
+            LlmProviderKind::Anthropic => "anthropic",
+            LlmProviderKind::Gemini => "gemini",
+            LlmProviderKind::OpenRouter => "openrouter",
+            LlmProviderKind::OpenAiCompatible => "openai_compatible",
+            LlmProviderKind::AzureOpenAi => "azure_openai",
+            LlmProviderKind::Ollama => "ollama",
+            LlmProviderKind::LmStudio => "lm_studio",
+        }
+    }
+
+    // Runs on the user's machine
+    pub fn is_local(self) -> bool {
+        matches!(self, LlmProviderKind::Ollama | LlmProviderKind::LmStudio)
+    }
+}
+
+// Per-provider settings; unset fields fall back to the provider's defaults
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct ProviderSettings {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub max_output_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    // Overrides for models the built-in tables don't know about
+    pub context_window: Option<u32>,
+    pub supports_vision: Option<bool>,
+    // Sent as `api-version` (Azure, gateways) or `anthropic-version`; for
+    // Gemini it picks the API path (v1, v1beta) when no base URL is set
+    pub api_version: Option<String>,
+    // Azure deployment name; routes to `/openai/deployments/{name}`
+    pub deployment: Option<String>,
+    // Account limits; requests over them wait instead of failing
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct LlmConfig {
+    pub provider: LlmProviderKind,
+    #[serde(default)]
+    pub openai: ProviderSettings,
+    #[serde(default)]
+    pub anthropic: ProviderSettings,
+    #[serde(default)]
+    pub gemini: ProviderSettings,
+    #[serde(default)]
+    pub openrouter: ProviderSettings,
+    #[serde(default)]
+    pub openai_compatible: ProviderSettings,
+    #[serde(default)]
+    pub azure_openai: ProviderSettings,
+    #[serde(default)]
+    pub ollama: ProviderSettings,
+    #[serde(default)]
+    pub lm_studio: ProviderSettings,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    // Providers to try, in order, when the selected one keeps failing
+    #[serde(default)]
+    pub failover: Vec<LlmProviderKind>,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    // Masking of personal data and secrets in the text sent
+    #[serde(default)]
+    pub scrub: ScrubConfig,
+    // Hold requests made while offline and send them when the network is back
+    #[serde(default)]
+    pub queue_when_offline: bool,
+}
+
+impl LlmConfig {
+    pub fn settings(&self, kind: LlmProviderKind) -> &ProviderSettings {
+        match kind {
+            LlmProviderKind::OpenAi => &self.openai,
+            LlmProviderKind::Anthropic => &self.anthropic,
+            LlmProviderKind::Gemini => &self.gemini,
+            LlmProviderKind::OpenRouter => &self.openrouter,
+            LlmProviderKind::OpenAiCompatible => &self.openai_compatible,
+            LlmProviderKind::AzureOpenAi => &self.azure_openai,
+            LlmProviderKind::Ollama => &self.ollama,
+            LlmProviderKind::LmStudio => &self.lm_studio,
+        }
+    }
+
+    pub fn settings_mut(&mut self, kind: LlmProviderKind) -> &mut ProviderSettings {
+        match kind {
+            LlmProviderKind::OpenAi => &mut self.openai,
+            LlmProviderKind::Anthropic => &mut self.anthropic,
+            LlmProviderKind::Gemini => &mut self.gemini,
+            LlmProviderKind::OpenRouter => &mut self.openrouter,
+            LlmProviderKind::OpenAiCompatible => &mut self.openai_compatible,
+            LlmProviderKind::AzureOpenAi => &mut self.azure_openai,
+            LlmProviderKind::Ollama => &mut self.ollama,
+            LlmProviderKind::LmStudio => &mut self.lm_studio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+    // The result of one tool call
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+    // Base64 images (PNG or JPEG) attached to this message
+    #[serde(default)]
+    pub images: Vec<String>,
+    // Tools an assistant message asked to run
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    // Which call a tool message answers
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+    #[serde(default)]
+    pub tool_name: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            images: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+            tool_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+pub struct TokenLimits {
+    pub context_window: u32,
+    pub max_output_tokens: u32,
+}
+
+// Largest image a provider accepts, per image
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ImageLimits {
+    // Longest side in pixels
+    pub max_dimension: u32,
+    // Decoded size
+    pub max_bytes: usize,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        // OpenAI scales anything larger than 2048px down anyway
+        Self {
+            max_dimension: 2_048,
+            max_bytes: 20 * 1024 * 1024,
+        }
+    }
+}
+
+// What the settings UI needs to know about a provider
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ProviderInfo {
+    pub kind: LlmProviderKind,
+    pub name: &'static str,
+    pub model: String,
+    pub supports_vision: bool,
+    pub supports_streaming: bool,
+    pub limits: TokenLimits,
+}
+
+pub trait Provider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn model(&self) -> &str;
+
+    fn supports_vision(&self) -> bool;
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn limits(&self) -> TokenLimits;
+
+    fn image_limits(&self) -> ImageLimits {
+        ImageLimits::default()
+    }
+
+    // How the API declares tools and returns calls; None if it can't use them
+    fn tool_format(&self) -> Option<ToolFormat> {
+        None
+    }
+
+    // Builds the HTTP request and JSON body for a chat turn.
+    fn build_request(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<(ProviderRequest, serde_json::Value), String>;
+}
+
+// Builds the provider `kind` from its settings in `config`.
+pub fn build_provider_kind(
+    config: &LlmConfig,
+    kind: LlmProviderKind,
+) -> Result<Box<dyn Provider>, String> {
+    let mut settings = config.settings(kind).clone();
+    // Keys are kept in the OS keychain rather than in the config itself
+    if settings.api_key.is_none() {
+        settings.api_key = crate::secrets::get_api_key(kind.key_id())?;
+    }
+    match kind {
+        LlmProviderKind::OpenAi => Ok(Box::new(openai::OpenAiProvider::openai(settings)?)),
+        LlmProviderKind::Anthropic => Ok(Box::new(anthropic::AnthropicProvider::new(settings)?)),
+        LlmProviderKind::Gemini => Ok(Box::new(gemini::GeminiProvider::new(settings)?)),
+        LlmProviderKind::OpenRouter => Ok(Box::new(openai::OpenAiProvider::openrouter(settings)?)),
+        LlmProviderKind::OpenAiCompatible => {
+            Ok(Box::new(openai::OpenAiProvider::compatible(settings)?))
+        }
+        LlmProviderKind::AzureOpenAi => Ok(Box::new(openai::OpenAiProvider::azure(settings)?)),
+        LlmProviderKind::Ollama | LlmProviderKind::LmStudio => {
+            let server_url = local::server_base_url(config, kind);
+            Ok(Box::new(openai::OpenAiProvider::local(kind, settings, server_url)?))
+        }
+    }
+}
+
+pub fn provider_info(kind: LlmProviderKind, provider: &dyn Provider) -> ProviderInfo {
+    ProviderInfo {
+        kind,
+        name: provider.name(),
+        model: provider.model().to_string(),
+        supports_vision: provider.supports_vision(),
+        supports_streaming: provider.supports_streaming(),
+        limits: provider.limits(),
+    }
+}
+
+// Picks the API key or reports which provider is missing one
+fn required_key(settings: &ProviderSettings, provider: &str) -> Result<String, String> {
+    settings
+        .api_key
+        .clone()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or(format!("{} API key is not configured", provider))
+}
+
+// Guesses the MIME type of a base64 image from its leading bytes
+fn image_mime_type(image_base64: &str) -> &'static str {
+    if image_base64.starts_with("/9j/") {
+        "image/jpeg"
+    } else if image_base64.starts_with("R0lGOD") {
+        "image/gif"
+    } else if image_base64.starts_with("UklGR") {
+        "image/webp"
+    } else {
+        "image/png"
+    }
+}
+
+// Base64 payloads sometimes arrive as data URLs
+fn strip_data_url(image_base64: &str) -> &str {
+    match image_base64.find(',') {
+        Some(idx) if image_base64.starts_with("data:") => &image_base64[idx + 1..],
+        _ => image_base64,
+    }
+}
+
+fn ensure_vision(provider: &dyn Provider, messages: &[ChatMessage]) -> Result<(), String> {
+    if !provider.supports_vision() && messages.iter().any(|m| !m.images.is_empty()) {
+        return Err(format!(
+            "Provider {} ({}) does not support image input",
+            provider.name(),
+            provider.model()
+        ));
+    }
+    Ok(())
+}
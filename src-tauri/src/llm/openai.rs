@@ -0,0 +1,361 @@
+// OpenAI chat completions, also used for OpenRouter and any OpenAI-compatible endpoint
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::local::looks_like_vision_model;
+use super::{
+    ensure_vision, image_mime_type, required_key, strip_data_url, ChatMessage, ChatRole,
+    LlmProviderKind, Provider, ProviderRequest, ProviderSettings, TokenLimits, ToolFormat,
+};
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+// Latest GA data-plane version
+const AZURE_API_VERSION: &str = "2024-10-21";
+
+pub struct OpenAiProvider {
+    name: &'static str,
+    settings: ProviderSettings,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    extra_headers: HashMap<String, String>,
+    // Local servers run small context windows and don't follow OpenAI model names
+    is_local: bool,
+    // Azure takes the key in an `api-key` header instead of a bearer token
+    azure: bool,
+}
+
+impl OpenAiProvider {
+    pub fn openai(settings: ProviderSettings) -> Result<Self, String> {
+        let api_key = required_key(&settings, "OpenAI")?;
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| OPENAI_BASE_URL.to_string());
+        let model = settings
+            .model
+            .clone()
+            .unwrap_or_else(|| "gpt-4o-mini".to_string());
+
+        Ok(Self {
+            name: "openai",
+            settings,
+            base_url,
+            model,
+            api_key: Some(api_key),
+            extra_headers: HashMap::new(),
+            is_local: false,
+            azure: false,
+        })
+    }
+
+    pub fn openrouter(settings: ProviderSettings) -> Result<Self, String> {
+        let api_key = required_key(&settings, "OpenRouter")?;
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| OPENROUTER_BASE_URL.to_string());
+        let model = settings
+            .model
+            .clone()
+            .unwrap_or_else(|| "openai/gpt-4o-mini".to_string());
+
+        // OpenRouter uses these to attribute traffic to the app
+        let extra_headers = HashMap::from([
+            ("HTTP-Referer".to_string(), "https://pluely.com".to_string()),
+            ("X-Title".to_string(), "Pluely".to_string()),
+        ]);
+
+        Ok(Self {
+            name: "openrouter",
+            settings,
+            base_url,
+            model,
+            api_key: Some(api_key),
+            extra_headers,
+            is_local: false,
+            azure: false,
+        })
+    }
+
+    // Self-hosted and third-party servers; the key is optional
+    pub fn compatible(settings: ProviderSettings) -> Result<Self, String> {
+        let base_url = settings
+            .base_url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+            .ok_or("OpenAI-compatible base URL is not configured".to_string())?;
+        let model = settings
+            .model
+            .clone()
+            .ok_or("OpenAI-compatible model is not configured".to_string())?;
+        let api_key = settings
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty());
+
+        Ok(Self {
+            name: "openai_compatible",
+            settings,
+            base_url,
+            model,
+            api_key,
+            extra_headers: HashMap::new(),
+            is_local: false,
+            azure: false,
+        })
+    }
+
+    // Azure OpenAI: the resource endpoint plus a deployment of some model
+    pub fn azure(mut settings: ProviderSettings) -> Result<Self, String> {
+        let api_key = required_key(&settings, "Azure OpenAI")?;
+        let base_url = settings
+            .base_url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+            .ok_or("Azure OpenAI endpoint is not configured".to_string())?;
+        let deployment = settings
+            .deployment
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .ok_or("Azure OpenAI deployment is not configured".to_string())?;
+        if settings.api_version.is_none() {
+            settings.api_version = Some(AZURE_API_VERSION.to_string());
+        }
+        // The model behind the deployment drives limits and pricing; the
+        // deployment name is the best guess when it isn't given
+        let model = settings
+            .model
+            .clone()
+            .filter(|model| !model.trim().is_empty())
+            .unwrap_or(deployment);
+
+        Ok(Self {
+            name: "azure_openai",
+            settings,
+            base_url,
+            model,
+            api_key: Some(api_key),
+            extra_headers: HashMap::new(),
+            is_local: false,
+            azure: true,
+        })
+    }
+
+    // Ollama and LM Studio through their OpenAI-compatible endpoints
+    pub fn local(
+        kind: LlmProviderKind,
+        settings: ProviderSettings,
+        server_url: String,
+    ) -> Result<Self, String> {
+        let name = if kind == LlmProviderKind::LmStudio {
+            "lm_studio"
+        } else {
+            "ollama"
+        };
+        let model = settings
+            .model
+            .clone()
+            .filter(|model| !model.trim().is_empty())
+            .ok_or("No local model selected".to_string())?;
+        let api_key = settings
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty());
+
+        Ok(Self {
+            name,
+            settings,
+            base_url: format!("{}/v1", server_url),
+            model,
+            api_key,
+            extra_headers: HashMap::new(),
+            is_local: true,
+            azure: false,
+        })
+    }
+
+    // Deployments and API versions also serve gateways that mimic Azure
+    fn chat_url(&self) -> String {
+        let base_url = self.base_url.trim_end_matches('/');
+        let mut url = match non_empty(&self.settings.deployment) {
+            Some(deployment) => format!(
+                "{}/openai/deployments/{}/chat/completions",
+                base_url, deployment
+            ),
+            None => format!("{}/chat/completions", base_url),
+        };
+        if let Some(api_version) = non_empty(&self.settings.api_version) {
+            url.push_str(if url.contains('?') { "&" } else { "?" });
+            url.push_str("api-version=");
+            url.push_str(api_version);
+        }
+        url
+    }
+
+    fn default_limits(&self) -> TokenLimits {
+        let model = self.model.rsplit('/').next().unwrap_or(&self.model);
+        let (context_window, max_output_tokens) = if self.is_local {
+            (8_192, 2_048)
+        } else if model.starts_with("gpt-4.1") {
+            (1_047_576, 32_768)
+        } else if ["o1", "o3", "o4"].iter().any(|p| model.starts_with(p)) {
+            (200_000, 100_000)
+        } else if model.starts_with("gpt-4o") {
+            (128_000, 16_384)
+        } else if model.starts_with("gpt-3.5") {
+            (16_385, 4_096)
+        } else {
+            (128_000, 4_096)
+        };
+        TokenLimits {
+            context_window,
+            max_output_tokens,
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn image_parts(text: &str, images: &[String]) -> Vec<Value> {
+    let mut content = vec![json!({ "type": "text", "text": text })];
+    for image in images {
+        let data = strip_data_url(image);
+        content.push(json!({
+            "type": "image_url",
+            "image_url": {
+                "url": format!("data:{};base64,{}", image_mime_type(data), data)
+            }
+        }));
+    }
+    content
+}
+
+// Converts messages to the chat completions shape; images become data URLs
+pub(super) fn openai_messages(messages: &[ChatMessage]) -> Vec<Value> {
+    let mut converted = Vec::with_capacity(messages.len());
+    for message in messages {
+        let role = match message.role {
+            ChatRole::System => "system",
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+            ChatRole::Tool => {
+                converted.push(json!({
+                    "role": "tool",
+                    "tool_call_id": message.tool_call_id,
+                    "content": message.content,
+                }));
+                // Tool messages are text only, so images follow as a user turn
+                if !message.images.is_empty() {
+                    converted.push(json!({
+                        "role": "user",
+                        "content": image_parts("Images from the tool call above.", &message.images),
+                    }));
+                }
+                continue;
+            }
+        };
+
+        let mut value = if message.images.is_empty() {
+            json!({ "role": role, "content": message.content })
+        } else {
+            json!({ "role": role, "content": image_parts(&message.content, &message.images) })
+        };
+        if !message.tool_calls.is_empty() {
+            value["tool_calls"] = json!(message
+                .tool_calls
+                .iter()
+                .map(|call| json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": {
+                        "name": call.name,
+                        "arguments": call.arguments.to_string(),
+                    }
+                }))
+                .collect::<Vec<_>>());
+        }
+        converted.push(value);
+    }
+    converted
+}
+
+impl Provider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn supports_vision(&self) -> bool {
+        let default = if self.is_local {
+            looks_like_vision_model(&self.model)
+        } else {
+            !self.model.contains("gpt-3.5")
+        };
+        self.settings.supports_vision.unwrap_or(default)
+    }
+
+    fn tool_format(&self) -> Option<ToolFormat> {
+        Some(ToolFormat::OpenAi)
+    }
+
+    fn limits(&self) -> TokenLimits {
+        let defaults = self.default_limits();
+        TokenLimits {
+            context_window: self
+                .settings
+                .context_window
+                .unwrap_or(defaults.context_window),
+            max_output_tokens: self
+                .settings
+                .max_output_tokens
+                .unwrap_or(defaults.max_output_tokens),
+        }
+    }
+
+    fn build_request(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<(ProviderRequest, Value), String> {
+        ensure_vision(self, messages)?;
+
+        let mut body = json!({
+            "model": self.model,
+            "messages": openai_messages(messages),
+            "stream": stream,
+        });
+        if let Some(max_tokens) = self.settings.max_output_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if let Some(temperature) = self.settings.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        let mut headers = self.extra_headers.clone();
+        if let Some(api_key) = &self.api_key {
+            if self.azure {
+                headers.insert("api-key".to_string(), api_key.clone());
+            } else {
+                headers.insert("Authorization".to_string(), format!("Bearer {}", api_key));
+            }
+        }
+
+        let request = ProviderRequest {
+            url: self.chat_url(),
+            method: "POST".to_string(),
+            headers,
+            stream,
+            response_content_path: Some("choices[0].message.content".to_string()),
+            api_key_ref: None,
+            tool_format: None,
+        };
+        Ok((request, body))
+    }
+}
@@ -0,0 +1,139 @@
+// Offline request queue: chat requests that couldn't reach the provider wait
+// in storage and are replayed, oldest first, once the network is back.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter};
+use tauri_specta::Event;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use super::commands::{chat, ChatFailure};
+use super::ChatMessage;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(15);
+
+// Everything `llm_chat` was asked, kept whole so the request can be replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedChat {
+    // Attached captures travel inline as base64 images
+    pub messages: Vec<ChatMessage>,
+    pub stream: bool,
+    pub profile_id: Option<String>,
+    pub variables: Option<HashMap<String, String>>,
+    pub tools: Vec<String>,
+}
+
+// Emitted as `queue-status` whenever the queue changes or a retry ran
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct QueueStatus {
+    pub pending: usize,
+    pub online: bool,
+}
+
+// Emitted as `queued-request-finished` with the answer or why it was dropped
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct QueuedRequestFinished {
+    pub request_id: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+fn emit_status(app: &AppHandle, pending: usize, online: bool) {
+    let _ = app.emit("queue-status", QueueStatus { pending, online });
+}
+
+fn emit_finished(app: &AppHandle, request_id: &str, result: Result<String, String>) {
+    let (text, error) = match result {
+        Ok(text) => (Some(text), None),
+        Err(e) => {
+            crate::notify::notify(
+                app,
+                crate::notify::NotificationCategory::ProviderError,
+                "Queued request failed",
+                &crate::notify::summary(&e),
+            );
+            (None, Some(e))
+        }
+    };
+    let _ = app.emit(
+        "queued-request-finished",
+        QueuedRequestFinished {
+            request_id: request_id.to_string(),
+            text,
+            error,
+        },
+    );
+}
+
+pub async fn enqueue(
+    app: &AppHandle,
+    request_id: &str,
+    request: &QueuedChat,
+) -> Result<(), String> {
+    let json = serde_json::to_string(request)
+        .map_err(|e| format!("Failed to serialize request: {}", e))?;
+    crate::storage::enqueue_request(app, request_id, &json).await?;
+    let pending = crate::storage::queued_requests(app).await?.len();
+    emit_status(app, pending, false);
+    Ok(())
+}
+
+pub(super) async fn run_worker(app: AppHandle, wake: Arc<Notify>) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(RETRY_INTERVAL) => {}
+            _ = wake.notified() => {}
+        }
+
+        let queued = match crate::storage::queued_requests(&app).await {
+            Ok(queued) => queued,
+            Err(e) => {
+                warn!("{}", e);
+                continue;
+            }
+        };
+        if queued.is_empty() {
+            continue;
+        }
+
+        let mut pending = queued.len();
+        let mut online = true;
+        for item in queued {
+            let result = match serde_json::from_str::<QueuedChat>(&item.request) {
+                // Nobody is listening for deltas anymore; the answer arrives as an event
+                Ok(mut request) => {
+                    request.stream = false;
+                    let on_event = Channel::new(|_| Ok(()));
+                    chat(app.clone(), item.id.clone(), request, on_event).await
+                }
+                Err(e) => Err(ChatFailure::Failed(format!(
+                    "Failed to read queued request: {}",
+                    e
+                ))),
+            };
+
+            let result = match result {
+                Ok(text) => Ok(text),
+                Err(ChatFailure::Failed(e)) => Err(e),
+                Err(ChatFailure::Offline(e)) => {
+                    // Still offline; the rest would fail the same way
+                    if let Err(e) = crate::storage::record_queue_attempt(&app, &item.id, &e).await {
+                        warn!("{}", e);
+                    }
+                    online = false;
+                    break;
+                }
+            };
+            if let Err(e) = crate::storage::remove_queued_request(&app, &item.id).await {
+                warn!("{}", e);
+            }
+            pending -= 1;
+            emit_finished(&app, &item.id, result);
+        }
+        emit_status(&app, pending, online);
+    }
+}
@@ -0,0 +1,140 @@
+// Token buckets per provider, so bursts of requests wait their turn instead of
+// tripping the provider's own limits. Both buckets refill continuously over a
+// minute; a `Retry-After` from the provider pauses everything sent to it.
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimits {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+struct Bucket {
+    capacity: f64,
+    // May go negative when a response used more than was reserved
+    available: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: u32) -> Self {
+        Self {
+            capacity: capacity as f64,
+            available: capacity as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.updated = now;
+    }
+
+    // How long until `amount` is available; never more than a full bucket
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.capacity)
+        }
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.available -= amount.min(self.capacity);
+    }
+}
+
+#[derive(Default)]
+struct ProviderBuckets {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    blocked_until: Option<Instant>,
+}
+
+// Keeps the bucket in step with the configured limit, which can change at runtime
+fn sync_bucket(bucket: &mut Option<Bucket>, limit: Option<u32>, now: Instant) {
+    match (bucket.as_mut(), limit.filter(|l| *l > 0)) {
+        (Some(existing), Some(limit)) if existing.capacity == limit as f64 => existing.refill(now),
+        (Some(existing), Some(limit)) => {
+            existing.refill(now);
+            let available = existing.available.min(limit as f64);
+            *bucket = Some(Bucket {
+                available,
+                ..Bucket::new(limit)
+            });
+        }
+        (None, Some(limit)) => *bucket = Some(Bucket::new(limit)),
+        (_, None) => *bucket = None,
+    }
+}
+
+#[derive(Default)]
+pub struct RateLimiter {
+    providers: HashMap<String, ProviderBuckets>,
+}
+
+impl RateLimiter {
+    // Reserves one request of about `tokens` for `provider`, or says how long
+    // to wait before asking again.
+    pub fn try_acquire(
+        &mut self,
+        provider: &str,
+        limits: RateLimits,
+        tokens: u32,
+    ) -> Result<(), Duration> {
+        let now = Instant::now();
+        let buckets = self.providers.entry(provider.to_string()).or_default();
+        if let Some(until) = buckets.blocked_until {
+            if until > now {
+                return Err(until - now);
+            }
+            buckets.blocked_until = None;
+        }
+
+        sync_bucket(&mut buckets.requests, limits.requests_per_minute, now);
+        sync_bucket(&mut buckets.tokens, limits.tokens_per_minute, now);
+        let wait = [
+            buckets.requests.as_ref().map(|b| b.wait_for(1.0)),
+            buckets.tokens.as_ref().map(|b| b.wait_for(tokens as f64)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_default();
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        if let Some(bucket) = buckets.requests.as_mut() {
+            bucket.take(1.0);
+        }
+        if let Some(bucket) = buckets.tokens.as_mut() {
+            bucket.take(tokens as f64);
+        }
+        Ok(())
+    }
+
+    // Counts tokens only known after the response, such as the output
+    pub fn charge(&mut self, provider: &str, tokens: u32) {
+        if let Some(bucket) = self
+            .providers
+            .get_mut(provider)
+            .and_then(|b| b.tokens.as_mut())
+        {
+            bucket.refill(Instant::now());
+            bucket.take(tokens as f64);
+        }
+    }
+
+    // Holds every request to `provider` until `delay` has passed
+    pub fn block(&mut self, provider: &str, delay: Duration) {
+        let until = Instant::now() + delay;
+        let buckets = self.providers.entry(provider.to_string()).or_default();
+        if buckets.blocked_until.is_none_or(|current| current < until) {
+            buckets.blocked_until = Some(until);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Retry policy for provider calls: exponential backoff with jitter on rate
+// limits and server errors.
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RetryConfig {
+    // Extra attempts per provider after the first; 0 disables retries
+    pub max_retries: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_delay_ms: 500,
+            max_delay_ms: 8_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    // Delay before retry number `retry` (1-based). Half the backoff is fixed and
+    // half random, so clients that failed together don't retry together. A
+    // server's Retry-After is honoured up to `max_delay_ms`.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(Duration::from_millis(self.max_delay_ms));
+        }
+        let backoff = self
+            .initial_delay_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay_ms);
+        let half = backoff / 2;
+        Duration::from_millis(half + rand::thread_rng().gen_range(0..=half))
+    }
+}
+
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Reads `Retry-After` when given in seconds
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
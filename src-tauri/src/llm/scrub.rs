@@ -0,0 +1,391 @@
+// Text scrubbing before a request is built. Emails, tokens, IP addresses,
+// listed names and custom patterns in user messages and tool results (OCR
+// text among them) are replaced with placeholders such as [EMAIL], so they
+// never reach the provider. Images are left to the capture pipeline.
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::net::Ipv6Addr;
+
+use super::{ChatMessage, ChatRole};
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap());
+static TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}",
+        r"|\bgh[pousr]_[A-Za-z0-9]{20,}",
+        r"|\bAKIA[0-9A-Z]{16}\b",
+        r"|\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        r"|\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        r"|(?i:\bbearer\s+)[A-Za-z0-9._~+/-]{16,}=*",
+    ))
+    .unwrap()
+});
+// Tokens without a known prefix; only those mixing letters and digits count
+static GENERIC_TOKEN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Za-z0-9_-]{32,}\b").unwrap());
+static IPV4: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b").unwrap()
+});
+// Candidates only; each is checked by parsing it
+static IPV6: Lazy<Regex> = Lazy::new(|| Regex::new(r"[0-9A-Fa-f:]*:[0-9A-Fa-f:]*").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScrubRule {
+    // Shown in the placeholder, e.g. "ticket" gives [TICKET]
+    pub label: String,
+    // A regular expression
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScrubConfig {
+    pub enabled: bool,
+    pub emails: bool,
+    // API keys, access tokens and other long secrets
+    pub tokens: bool,
+    pub ip_addresses: bool,
+    // People's names, matched as whole words in any case
+    pub names: Vec<String>,
+    pub custom_rules: Vec<ScrubRule>,
+    // `llm_chat` holds requests whose text would be masked until the user
+    // approves the preview
+    pub require_approval: bool,
+}
+
+impl Default for ScrubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            emails: true,
+            tokens: true,
+            ip_addresses: true,
+            names: Vec::new(),
+            custom_rules: Vec::new(),
+            require_approval: false,
+        }
+    }
+}
+
+impl ScrubConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.custom_rules {
+            if rule.label.trim().is_empty() {
+                return Err("Invalid scrub rule: a label is required".to_string());
+            }
+            Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid scrub rule {}: {}", rule.label, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubKind {
+    Email,
+    Token,
+    IpAddress,
+    Name,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubMatch {
+    pub kind: ScrubKind,
+    // Byte range in the original text
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub replacement: String,
+}
+
+// One message that scrubbing changes
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubChange {
+    pub message_index: usize,
+    pub original: String,
+    pub scrubbed: String,
+    pub matches: Vec<ScrubMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrubPreview {
+    pub changes: Vec<ScrubChange>,
+    // Passed to `approve_pii_scrub` to send these messages as scrubbed; None
+    // when nothing would change
+    pub approval_id: Option<String>,
+}
+
+fn placeholder(label: &str) -> String {
+    format!("[{}]", label.trim().to_uppercase().replace(' ', "_"))
+}
+
+// Addresses inside identifiers, like `std::fs`, aren't addresses
+fn standalone(text: &str, start: usize, end: usize) -> bool {
+    let word = |c: char| c.is_alphanumeric() || c == '_';
+    !text[..start].chars().next_back().is_some_and(word)
+        && !text[end..].chars().next().is_some_and(word)
+}
+
+// Every match of `regex` in `text` whose byte range `keep` accepts
+fn collect(
+    found: &mut Vec<(ScrubKind, String, usize, usize)>,
+    text: &str,
+    kind: ScrubKind,
+    label: &str,
+    regex: &Regex,
+    keep: impl Fn(usize, usize) -> bool,
+) {
+    for m in regex.find_iter(text) {
+        if keep(m.start(), m.end()) {
+            found.push((kind, placeholder(label), m.start(), m.end()));
+        }
+    }
+}
+
+fn find_matches(config: &ScrubConfig, text: &str) -> Result<Vec<ScrubMatch>, String> {
+    let mut found = Vec::new();
+    let any = |_: usize, _: usize| true;
+    if config.emails {
+        collect(&mut found, text, ScrubKind::Email, "email", &EMAIL, any);
+    }
+    if config.tokens {
+        collect(&mut found, text, ScrubKind::Token, "token", &TOKEN, any);
+        collect(
+            &mut found,
+            text,
+            ScrubKind::Token,
+            "token",
+            &GENERIC_TOKEN,
+            |start, end| {
+                text[start..end].chars().any(|c| c.is_ascii_digit())
+                    && text[start..end].chars().any(|c| c.is_ascii_alphabetic())
+            },
+        );
+    }
+    if config.ip_addresses {
+        collect(&mut found, text, ScrubKind::IpAddress, "ip", &IPV4, any);
+        collect(
+            &mut found,
+            text,
+            ScrubKind::IpAddress,
+            "ip",
+            &IPV6,
+            |start, end| {
+                let candidate = &text[start..end];
+                candidate.len() > 2
+                    && candidate.matches(':').count() >= 2
+                    && candidate.parse::<Ipv6Addr>().is_ok()
+                    && standalone(text, start, end)
+            },
+        );
+    }
+    let names: Vec<String> = config
+        .names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(regex::escape)
+        .collect();
+    if !names.is_empty() {
+        let regex = Regex::new(&format!(r"(?i)\b(?:{})\b", names.join("|")))
+            .map_err(|e| format!("Invalid scrub names: {}", e))?;
+        collect(&mut found, text, ScrubKind::Name, "name", &regex, any);
+    }
+    for rule in &config.custom_rules {
+        let regex = Regex::new(&rule.pattern)
+            .map_err(|e| format!("Invalid scrub rule {}: {}", rule.label, e))?;
+        collect(
+            &mut found,
+            text,
+            ScrubKind::Custom,
+            &rule.label,
+            &regex,
+            any,
+        );
+    }
+
+    // Earlier matches win, and the longer one where two start together
+    found.sort_by(|a, b| a.2.cmp(&b.2).then(b.3.cmp(&a.3)));
+    let mut matches: Vec<ScrubMatch> = Vec::new();
+    for (kind, replacement, start, end) in found {
+        if start == end || matches.last().is_some_and(|last| start < last.end) {
+            continue;
+        }
+        matches.push(ScrubMatch {
+            kind,
+            start,
+            end,
+            text: text[start..end].to_string(),
+            replacement,
+        });
+    }
+    Ok(matches)
+}
+
+fn apply(text: &str, matches: &[ScrubMatch]) -> String {
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut at = 0;
+    for m in matches {
+        scrubbed.push_str(&text[at..m.start]);
+        scrubbed.push_str(&m.replacement);
+        at = m.end;
+    }
+    scrubbed.push_str(&text[at..]);
+    scrubbed
+}
+
+// What scrubbing would change in `messages`; nothing when it's off
+fn changes(config: &ScrubConfig, messages: &[ChatMessage]) -> Result<Vec<ScrubChange>, String> {
+    if !config.enabled {
+        return Ok(Vec::new());
+    }
+    let mut changes = Vec::new();
+    for (index, message) in messages.iter().enumerate() {
+        if !matches!(message.role, ChatRole::User | ChatRole::Tool) {
+            continue;
+        }
+        let matches = find_matches(config, &message.content)?;
+        if matches.is_empty() {
+            continue;
+        }
+        changes.push(ScrubChange {
+            message_index: index,
+            original: message.content.clone(),
+            scrubbed: apply(&message.content, &matches),
+            matches,
+        });
+    }
+    Ok(changes)
+}
+
+// A hash of each original and scrubbed text
+fn approval_id(changes: &[ScrubChange]) -> Option<String> {
+    if changes.is_empty() {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    for change in changes {
+        hasher.update(change.original.as_bytes());
+        hasher.update([0]);
+        hasher.update(change.scrubbed.as_bytes());
+        hasher.update([0]);
+    }
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
+
+// The changes, and the id that approves sending them
+pub fn preview(config: &ScrubConfig, messages: &[ChatMessage]) -> Result<ScrubPreview, String> {
+    let changes = changes(config, messages)?;
+    let approval_id = approval_id(&changes);
+    Ok(ScrubPreview {
+        changes,
+        approval_id,
+    })
+}
+
+// `messages` as they go to the provider, and a line per kind of text masked
+pub fn scrub_messages(
+    config: &ScrubConfig,
+    messages: &[ChatMessage],
+) -> Result<(Vec<ChatMessage>, Vec<String>), String> {
+    let changes = changes(config, messages)?;
+    let mut scrubbed = messages.to_vec();
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for change in changes {
+        for m in &change.matches {
+            match counts.iter_mut().find(|(label, _)| *label == m.replacement) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((m.replacement.clone(), 1)),
+            }
+        }
+        scrubbed[change.message_index].content = change.scrubbed;
+    }
+    let summary = counts
+        .into_iter()
+        .map(|(label, count)| format!("{} masked as {}", count, label))
+        .collect();
+    Ok((scrubbed, summary))
+}
+
+// Text of the other roles, which isn't scrubbed in messages either
+const UNSCRUBBED_ROLES: [&str; 4] = ["system", "developer", "assistant", "model"];
+// Where system prompts sit outside the messages
+const SYSTEM_KEYS: [&str; 3] = ["system", "system_instruction", "systemInstruction"];
+
+fn collect_payload_text(value: &Value, pointer: String, texts: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            let role = object.get("role").and_then(|role| role.as_str());
+            if role.is_some_and(|role| UNSCRUBBED_ROLES.contains(&role)) {
+                return;
+            }
+            for (key, value) in object {
+                if SYSTEM_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                match value {
+                    Value::String(text) if key == "content" || key == "text" => {
+                        texts.push((pointer, text.clone()))
+                    }
+                    _ => collect_payload_text(value, pointer, texts),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_payload_text(item, format!("{}/{}", pointer, index), texts);
+            }
+        }
+        _ => {}
+    }
+}
+
+// The text of a request body the frontend built for a provider (`content` and
+// `text` fields in the OpenAI, Anthropic and Gemini shapes), as user messages,
+// with the JSON pointer each came from
+fn payload_text(payload: &Value) -> (Vec<String>, Vec<ChatMessage>) {
+    let mut texts = Vec::new();
+    collect_payload_text(payload, String::new(), &mut texts);
+    texts
+        .into_iter()
+        .map(|(pointer, text)| (pointer, ChatMessage::new(ChatRole::User, text)))
+        .unzip()
+}
+
+// What scrubbing would change in a provider request body
+pub fn preview_payload(config: &ScrubConfig, payload: &Value) -> Result<ScrubPreview, String> {
+    preview(config, &payload_text(payload).1)
+}
+
+// `payload` as it goes to the provider, and a line per kind of text masked
+pub fn scrub_payload(
+    config: &ScrubConfig,
+    payload: &Value,
+) -> Result<(Value, Vec<String>), String> {
+    let (pointers, messages) = payload_text(payload);
+    let (scrubbed, summary) = scrub_messages(config, &messages)?;
+    let mut payload = payload.clone();
+    for (pointer, message) in pointers.iter().zip(scrubbed) {
+        if let Some(text) = payload.pointer_mut(pointer) {
+            *text = Value::String(message.content);
+        }
+    }
+    Ok((payload, summary))
+}
@@ -0,0 +1,58 @@
+// Incremental server-sent events parser for streamed LLM responses.
+
+#[derive(Default)]
+pub struct SseParser {
+    // Raw bytes of the current incomplete line; chunks can split UTF-8 characters
+    line: Vec<u8>,
+    // `data:` lines of the event being assembled
+    data: Vec<String>,
+}
+
+impl SseParser {
+    // Feeds a network chunk and returns the payload of every completed event.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        let mut events = Vec::new();
+
+        for &byte in chunk {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+
+            let raw = std::mem::take(&mut self.line);
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+
+            if line.is_empty() {
+                // A blank line dispatches the event
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+            // `event:`, `id:`, `retry:` and comments carry nothing we use
+        }
+
+        events
+    }
+
+    // Returns whatever is left once the connection closes without a trailing blank line.
+    pub fn finish(&mut self) -> Option<String> {
+        if !self.line.is_empty() {
+            let raw = std::mem::take(&mut self.line);
+            let line = String::from_utf8_lossy(&raw).trim_end().to_string();
+            if let Some(value) = line.strip_prefix("data:") {
+                self.data.push(value.trim_start().to_string());
+            }
+        }
+
+        if self.data.is_empty() {
+            return None;
+        }
+        let event = self.data.join("\n");
+        self.data.clear();
+        Some(event)
+    }
+}
@@ -0,0 +1,217 @@
+// Token counting and context budgeting. Counts use OpenAI's tokenizers, which
+// are close enough for other vendors to keep requests inside the window.
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use specta::Type;
+use std::io::Cursor;
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+
+use super::{strip_data_url, ChatMessage, ChatRole, TokenLimits};
+
+// Role markers and separators the chat format adds around each message
+const TOKENS_PER_MESSAGE: u32 = 4;
+// Every reply is primed with the assistant role
+const REPLY_PRIMING_TOKENS: u32 = 3;
+// Room left for the note that stands in for dropped turns
+const NOTE_RESERVE_TOKENS: u32 = 32;
+// Used when an image's size can't be read
+const FALLBACK_IMAGE_TOKENS: u32 = 1_105;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TokenCount {
+    pub total: u32,
+    pub per_message: Vec<u32>,
+    pub image_tokens: u32,
+}
+
+// What `fit_to_context` had to drop
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct ContextTrim {
+    pub dropped_messages: usize,
+    pub dropped_images: usize,
+    pub tokens_before: u32,
+    pub tokens_after: u32,
+}
+
+fn o200k() -> Option<&'static CoreBPE> {
+    static BPE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    BPE.get_or_init(|| tiktoken_rs::o200k_base().ok()).as_ref()
+}
+
+fn cl100k() -> Option<&'static CoreBPE> {
+    static BPE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    BPE.get_or_init(|| tiktoken_rs::cl100k_base().ok()).as_ref()
+}
+
+fn tokenizer(model: &str) -> Option<&'static CoreBPE> {
+    let model = model.rsplit('/').next().unwrap_or(model);
+    let newer = ["gpt-4o", "gpt-4.1", "gpt-5", "o1", "o3", "o4"]
+        .iter()
+        .any(|prefix| model.starts_with(prefix));
+    if newer {
+        o200k()
+    } else {
+        cl100k()
+    }
+}
+
+pub fn count_text(model: &str, text: &str) -> u32 {
+    match tokenizer(model) {
+        Some(bpe) => bpe.encode_with_special_tokens(text).len() as u32,
+        // Roughly four characters per token in English
+        None => text.chars().count().div_ceil(4) as u32,
+    }
+}
+
+fn image_dimensions(image_base64: &str) -> Option<(u32, u32)> {
+    let bytes = general_purpose::STANDARD
+        .decode(strip_data_url(image_base64))
+        .ok()?;
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+// Vendors bill images differently; each formula follows their published docs
+pub fn count_image(model: &str, image_base64: &str) -> u32 {
+    let Some((width, height)) = image_dimensions(image_base64) else {
+        return FALLBACK_IMAGE_TOKENS;
+    };
+    let model = model.to_lowercase();
+
+    if model.contains("gemini") {
+        return 258;
+    }
+    if model.contains("claude") {
+        // Images are scaled to fit 1568px on the long edge
+        let scale = (1568.0 / width.max(height) as f64).min(1.0);
+        let (w, h) = (width as f64 * scale, height as f64 * scale);
+        return ((w * h) / 750.0).ceil() as u32;
+    }
+
+    // OpenAI high detail: fit in 2048x2048, shortest side to 768, then 512px tiles
+    let mut w = width as f64;
+    let mut h = height as f64;
+    let fit = (2048.0 / w.max(h)).min(1.0);
+    w *= fit;
+    h *= fit;
+    let shrink = (768.0 / w.min(h)).min(1.0);
+    w *= shrink;
+    h *= shrink;
+    let tiles = (w / 512.0).ceil() * (h / 512.0).ceil();
+    85 + 170 * tiles as u32
+}
+
+fn count_message(model: &str, message: &ChatMessage) -> (u32, u32) {
+    let images: u32 = message
+        .images
+        .iter()
+        .map(|image| count_image(model, image))
+        .sum();
+    let tool_calls: u32 = message
+        .tool_calls
+        .iter()
+        .map(|call| count_text(model, &call.name) + count_text(model, &call.arguments.to_string()))
+        .sum();
+    (
+        TOKENS_PER_MESSAGE + count_text(model, &message.content) + tool_calls + images,
+        images,
+    )
+}
+
+pub fn count_messages(model: &str, messages: &[ChatMessage]) -> TokenCount {
+    let mut per_message = Vec::with_capacity(messages.len());
+    let mut image_tokens = 0;
+    for message in messages {
+        let (tokens, images) = count_message(model, message);
+        per_message.push(tokens);
+        image_tokens += images;
+    }
+    TokenCount {
+        total: per_message.iter().sum::<u32>() + REPLY_PRIMING_TOKENS,
+        per_message,
+        image_tokens,
+    }
+}
+
+// Trims a conversation so prompt plus reply fit the model's window. Images go
+// first, oldest first, then the oldest turns; system prompts and the latest
+// message are always kept. Dropped turns are replaced by a short note.
+pub fn fit_to_context(
+    model: &str,
+    limits: TokenLimits,
+    messages: &[ChatMessage],
+) -> Result<(Vec<ChatMessage>, ContextTrim), String> {
+    let budget = limits
+        .context_window
+        .saturating_sub(limits.max_output_tokens);
+    let tokens_before = count_messages(model, messages).total;
+    let mut trim = ContextTrim {
+        tokens_before,
+        tokens_after: tokens_before,
+        ..Default::default()
+    };
+    if tokens_before <= budget || messages.is_empty() {
+        return Ok((messages.to_vec(), trim));
+    }
+
+    let mut kept = messages.to_vec();
+    let last = kept.len() - 1;
+    let mut total = tokens_before;
+
+    for message in kept.iter_mut().take(last) {
+        if total <= budget {
+            break;
+        }
+        for image in message.images.drain(..) {
+            total = total.saturating_sub(count_image(model, &image));
+            trim.dropped_images += 1;
+        }
+    }
+
+    let mut dropped = vec![false; kept.len()];
+    for (index, message) in kept.iter().enumerate().take(last) {
+        if total + NOTE_RESERVE_TOKENS <= budget {
+            break;
+        }
+        if message.role == ChatRole::System {
+            continue;
+        }
+        total = total.saturating_sub(count_message(model, message).0);
+        dropped[index] = true;
+        trim.dropped_messages += 1;
+    }
+
+    if trim.dropped_messages > 0 {
+        let note = ChatMessage::new(
+            ChatRole::System,
+            format!(
+                "[{} earlier messages were omitted to fit the context window.]",
+                trim.dropped_messages
+            ),
+        );
+        let first_dropped = dropped.iter().position(|d| *d).unwrap_or(0);
+        let mut trimmed = Vec::with_capacity(kept.len());
+        for (index, message) in kept.into_iter().enumerate() {
+            if index == first_dropped {
+                trimmed.push(note.clone());
+            }
+            if !dropped[index] {
+                trimmed.push(message);
+            }
+        }
+        kept = trimmed;
+    }
+
+    trim.tokens_after = count_messages(model, &kept).total;
+    if trim.tokens_after > budget {
+        return Err(format!(
+            "Message is too long for {}: {} tokens with {} available",
+            model, trim.tokens_after, budget
+        ));
+    }
+    Ok((kept, trim))
+}
@@ -0,0 +1,526 @@
+// Built-in tools the model can call during `llm_chat`: look at the screen,
+// read the clipboard and fetch pages. Each provider declares and returns tool
+// calls in its own shape, converted here.
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::content::get_by_path;
+
+// Larger pages are cut off rather than read to the end
+const MAX_FETCH_BYTES: usize = 2 * 1024 * 1024;
+// Keeps a fetched page from crowding out the rest of the conversation
+const MAX_TOOL_OUTPUT_CHARS: usize = 20_000;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+// Redirects `fetch_url` follows, each checked like the first URL
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    // JSON Schema for the arguments
+    pub parameters: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+// What a tool hands back to the model
+#[derive(Debug, Clone, Default)]
+pub struct ToolOutput {
+    pub text: String,
+    pub images: Vec<String>,
+}
+
+pub fn builtin_tools() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "take_screenshot".to_string(),
+            description: "Take a new screenshot of the screen the user is working on.".to_string(),
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        ToolSpec {
+            name: "ocr_screen".to_string(),
+            description: "Read the text currently visible on the user's screen.".to_string(),
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        ToolSpec {
+            name: "read_clipboard".to_string(),
+            description: "Read the text on the user's clipboard.".to_string(),
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        ToolSpec {
+            name: "fetch_url".to_string(),
+            description: "Fetch a public web page or document and return its text.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "An http or https URL" }
+                },
+                "required": ["url"]
+            }),
+        },
+    ]
+}
+
+// The built-in and plugin tools named in `names`, in that order
+pub fn select_tools(app: &AppHandle, names: &[String]) -> Result<Vec<ToolSpec>, String> {
+    let tools = builtin_tools();
+    names
+        .iter()
+        .map(|name| {
+            if let Some(tool) = tools.iter().find(|tool| tool.name == *name) {
+                return Ok(tool.clone());
+            }
+            crate::plugins::tool_spec(app, name)
+                .unwrap_or_else(|| Err(format!("Unknown tool: {}", name)))
+        })
+        .collect()
+}
+
+fn has_parameters(tool: &ToolSpec) -> bool {
+    tool.parameters
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .is_some_and(|p| !p.is_empty())
+}
+
+// Declares `tools` in a request body
+pub fn add_tools(format: ToolFormat, body: &mut Value, tools: &[ToolSpec]) {
+    if tools.is_empty() {
+        return;
+    }
+    body["tools"] = match format {
+        ToolFormat::OpenAi => json!(tools
+            .iter()
+            .map(|tool| json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.parameters,
+                }
+            }))
+            .collect::<Vec<_>>()),
+        ToolFormat::Anthropic => json!(tools
+            .iter()
+            .map(|tool| json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.parameters,
+            }))
+            .collect::<Vec<_>>()),
+        // Gemini rejects object schemas without properties
+        ToolFormat::Gemini => json!([{
+            "functionDeclarations": tools
+                .iter()
+                .map(|tool| {
+                    let mut declaration = json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                    });
+                    if has_parameters(tool) {
+                        declaration["parameters"] = tool.parameters.clone();
+                    }
+                    declaration
+                })
+                .collect::<Vec<_>>()
+        }]),
+    };
+}
+
+// Tool calls in a non-streaming response
+pub fn parse_tool_calls(format: ToolFormat, response: &Value) -> Vec<ToolCall> {
+    match format {
+        ToolFormat::OpenAi => get_by_path(response, "choices[0].message.tool_calls")
+            .and_then(|calls| calls.as_array())
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter_map(|call| {
+                        let function = call.get("function")?;
+                        // Arguments arrive as a JSON string
+                        let arguments = function
+                            .get("arguments")
+                            .and_then(|a| a.as_str())
+                            .and_then(|a| serde_json::from_str(a).ok())
+                            .unwrap_or_else(|| json!({}));
+                        Some(ToolCall {
+                            id: call.get("id")?.as_str()?.to_string(),
+                            name: function.get("name")?.as_str()?.to_string(),
+                            arguments,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ToolFormat::Anthropic => response
+            .get("content")
+            .and_then(|content| content.as_array())
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                    .filter_map(|block| {
+                        Some(ToolCall {
+                            id: block.get("id")?.as_str()?.to_string(),
+                            name: block.get("name")?.as_str()?.to_string(),
+                            arguments: block.get("input").cloned().unwrap_or_else(|| json!({})),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ToolFormat::Gemini => get_by_path(response, "candidates[0].content.parts")
+            .and_then(|parts| parts.as_array())
+            .map(|parts| {
+                parts
+                    .iter()
+                    .filter_map(|part| part.get("functionCall"))
+                    .enumerate()
+                    .filter_map(|(index, call)| {
+                        // Older models don't number their calls
+                        let id = call
+                            .get("id")
+                            .and_then(|id| id.as_str())
+                            .map(String::from)
+                            .unwrap_or_else(|| format!("call_{}", index));
+                        Some(ToolCall {
+                            id,
+                            name: call.get("name")?.as_str()?.to_string(),
+                            arguments: call.get("args").cloned().unwrap_or_else(|| json!({})),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+// Runs a tool call. Failures are reported to the model as text so it can
+// carry on without the tool.
+pub async fn run_tool(app: &AppHandle, call: &ToolCall) -> Result<ToolOutput, String> {
+    match call.name.as_str() {
+        "take_screenshot" => {
+            let image = crate::capture::capture_screen_base64().await?;
+            Ok(ToolOutput {
+                text: "Screenshot attached.".to_string(),
+                images: vec![image],
+            })
+        }
+        "ocr_screen" => {
+            let image = crate::capture::capture_screen_base64().await?;
+            let text = ocr_image(image).await?;
+            Ok(ToolOutput {
+                text: truncate(&text),
+                ..Default::default()
+            })
+        }
+        "read_clipboard" => {
+            let text = tauri::async_runtime::spawn_blocking(read_clipboard_text)
+                .await
+                .map_err(|e| format!("Task panicked: {}", e))??;
+            Ok(ToolOutput {
+                text: truncate(&text),
+                ..Default::default()
+            })
+        }
+        "fetch_url" => {
+            let url = call
+                .arguments
+                .get("url")
+                .and_then(|u| u.as_str())
+                .ok_or("fetch_url needs a url".to_string())?;
+            let text = fetch_url(url).await?;
+            Ok(ToolOutput {
+                text: truncate(&text),
+                ..Default::default()
+            })
+        }
+        _ => {
+            let (text, images) = crate::plugins::run_tool(app, call).await?;
+            Ok(ToolOutput {
+                text: truncate(&text),
+                images,
+            })
+        }
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TOOL_OUTPUT_CHARS) {
+        Some((idx, _)) => format!("{}\n[truncated]", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+// Runs the `tesseract` CLI over a base64 PNG
+pub async fn ocr_image(image_base64: String) -> Result<String, String> {
+    let started = std::time::Instant::now();
+    let text = tauri::async_runtime::spawn_blocking(move || {
+        let bytes = general_purpose::STANDARD
+            .decode(image_base64)
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        let path = std::env::temp_dir().join(format!("pluely-ocr-{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write image: {}", e))?;
+
+        let output = std::process::Command::new("tesseract")
+            .arg(&path)
+            .arg("stdout")
+            .output();
+        let _ = std::fs::remove_file(&path);
+
+        let output =
+            output.map_err(|e| format!("Failed to run tesseract (is it installed?): {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "OCR failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task panicked: {}", e))??;
+    crate::analytics::timing("ocr", started.elapsed());
+    Ok(text)
+}
+
+fn read_clipboard_text() -> Result<String, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    clipboard
+        .get_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))
+}
+
+// Loopback, private, shared (carrier-grade NAT) and link-local addresses,
+// including IPv6 unique local addresses and IPv4 addresses mapped or
+// embedded into IPv6
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                // 0.0.0.0/8
+                || first == 0
+                // 100.64.0.0/10
+                || (first == 100 && second & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4() {
+            Some(ip) => is_local_ip(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // fc00::/7
+                    || first & 0xfe00 == 0xfc00
+                    // fe80::/10
+                    || first & 0xffc0 == 0xfe80
+            }
+        },
+    }
+}
+
+// Only public hosts: the model shouldn't be able to reach the user's network
+fn ensure_public_url(url: &reqwest::Url) -> Result<(), String> {
+    if !["http", "https"].contains(&url.scheme()) {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    let host = url.host_str().ok_or("URL has no host".to_string())?;
+    if host.eq_ignore_ascii_case("localhost") || host.ends_with(".local") {
+        return Err(format!("Refusing to fetch local address {}", host));
+    }
+    if let Ok(ip) = host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        if is_local_ip(ip) {
+            return Err(format!("Refusing to fetch local address {}", host));
+        }
+    }
+    Ok(())
+}
+
+// Resolves the URL's host and checks every address. Through a proxy the
+// resolver below is never asked, so each URL is checked here before it's sent.
+async fn ensure_public_host(url: &reqwest::Url) -> Result<(), String> {
+    ensure_public_url(url)?;
+    let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    if host.parse::<IpAddr>().is_ok() {
+        return Ok(());
+    }
+    let port = url.port_or_known_default().unwrap_or(0);
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
+    for addr in addrs {
+        if is_local_ip(addr.ip()) {
+            return Err(format!(
+                "Refusing to fetch {}: it resolves to local address {}",
+                host,
+                addr.ip()
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Checks the addresses a name resolves to when connecting directly, so a name
+// can't resolve somewhere else after `ensure_public_host` checked it.
+// Addresses in the URL skip resolution and are checked by `ensure_public_url`.
+struct PublicResolver {
+    // The proxy the user configured, which may well run on this machine
+    proxy_host: Option<String>,
+}
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        let is_proxy = self
+            .proxy_host
+            .as_ref()
+            .is_some_and(|proxy| proxy.eq_ignore_ascii_case(&host));
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let local = addrs.iter().find(|addr| is_local_ip(addr.ip()));
+            if let (Some(addr), false) = (local, is_proxy) {
+                return Err(format!(
+                    "Refusing to fetch {}: it resolves to local address {}",
+                    host,
+                    addr.ip()
+                )
+                .into());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+async fn fetch_url(url: &str) -> Result<String, String> {
+    crate::network::ensure_online("Fetching pages")?;
+    let mut url = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    let proxy = crate::network::proxy_config()?;
+    let proxy_host = proxy
+        .url
+        .filter(|_| proxy.enabled)
+        .and_then(|url| reqwest::Url::parse(url.trim()).ok())
+        .and_then(|url| url.host_str().map(String::from));
+
+    let client = crate::network::client_builder()?
+        .timeout(FETCH_TIMEOUT)
+        .dns_resolver(Arc::new(PublicResolver { proxy_host }))
+        // Followed below, so every hop is checked
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut redirects = 0;
+    let response = loop {
+        ensure_public_host(&url).await?;
+        let response = crate::network::send(client.get(url.clone()), "fetch_url tool", &[])
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .filter(|_| response.status().is_redirection())
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| url.join(location).ok());
+        match location {
+            Some(next) if redirects < MAX_REDIRECTS => {
+                url = next;
+                redirects += 1;
+            }
+            Some(_) => return Err(format!("Failed to fetch {}: too many redirects", url)),
+            None => break response,
+        }
+    };
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {}: {}", url, response.status()));
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("html"));
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to read {}: {}", url, e))?;
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_FETCH_BYTES {
+            body.truncate(MAX_FETCH_BYTES);
+            break;
+        }
+    }
+
+    let text = String::from_utf8_lossy(&body);
+    Ok(if is_html {
+        html_to_text(&text)
+    } else {
+        text.into_owned()
+    })
+}
+
+// Rough text extraction: drops scripts, styles and tags, keeps the words
+fn html_to_text(html: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so both strings share indices
+    let lower = html.to_ascii_lowercase();
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find('<').map(|idx| pos + idx) {
+        text.push_str(&html[pos..start]);
+        text.push(' ');
+        let closing = if lower[start..].starts_with("<script") {
+            "</script>"
+        } else if lower[start..].starts_with("<style") {
+            "</style>"
+        } else {
+            ">"
+        };
+        match lower[start..].find(closing) {
+            Some(idx) => pos = start + idx + closing.len(),
+            None => {
+                pos = html.len();
+                break;
+            }
+        }
+    }
+    text.push_str(&html[pos..]);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'");
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,255 @@
+// Pluely logging. `tracing` events go to stderr and to daily JSON log files in
+// the app data directory, a week of which are kept, so bug reports can show
+// what happened before a problem.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use tracing::{warn, Level};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+pub const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "pluely";
+const MAX_LOG_FILES: usize = 7;
+// Overrides the filter, e.g. PLUELY_LOG=debug or PLUELY_LOG=pluely_lib::stt=trace
+const FILTER_ENV: &str = "PLUELY_LOG";
+const DEFAULT_FILTER: &str = "info,pluely_lib=debug";
+const DEFAULT_RECENT_LINES: usize = 200;
+const MAX_RECENT_LINES: usize = 5_000;
+// Log lines included in a diagnostics bundle
+const DIAGNOSTICS_LINES: usize = 500;
+
+// Flushes buffered lines when dropped, so it lives as long as the process
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+fn filter() -> EnvFilter {
+    EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER))
+}
+
+// Shared by every workspace, so one file covers switching between them
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(LOG_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Installs the global subscriber; logs only to stderr when the log directory
+/// can't be used
+pub fn init(app: &AppHandle) {
+    let appender = log_dir(app).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| format!("Failed to open log file: {}", e))
+    });
+    let registry = tracing_subscriber::registry()
+        .with(filter())
+        .with(fmt::layer().with_writer(std::io::stderr));
+    match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let _ = FILE_GUARD.set(guard);
+            let _ = registry
+                .with(fmt::layer().json().with_writer(writer))
+                .try_init();
+        }
+        Err(e) => {
+            let _ = registry.try_init();
+            warn!("Logging to stderr only: {}", e);
+        }
+    }
+}
+
+/// Stderr only, for the MCP server whose stdout carries the protocol
+pub fn init_stderr() {
+    let _ = tracing_subscriber::registry()
+        .with(filter())
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .try_init();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+// One line of the JSON file format
+#[derive(Deserialize)]
+struct LogLine {
+    timestamp: String,
+    level: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogLine {
+    fn into_entry(mut self) -> LogEntry {
+        let mut message = match self.fields.remove("message") {
+            Some(serde_json::Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        // Structured fields follow the message as key=value
+        for (key, value) in self.fields {
+            message.push_str(&format!(" {}={}", key, value));
+        }
+        LogEntry {
+            timestamp: self.timestamp,
+            level: self.level,
+            target: self.target,
+            message,
+        }
+    }
+}
+
+// The newest `lines` entries at `level` or more severe, oldest first
+fn recent_logs(app: &AppHandle, level: Level, lines: usize) -> Result<Vec<LogEntry>, String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir(app)?)
+        .map_err(|e| format!("Failed to read log directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(".log"))
+        })
+        .collect();
+    // Names end in the date, so this is newest first
+    files.sort();
+    files.reverse();
+
+    let mut entries = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        // A line still being written doesn't parse and is skipped
+        for line in text.lines().rev() {
+            let Ok(parsed) = serde_json::from_str::<LogLine>(line) else {
+                continue;
+            };
+            if Level::from_str(&parsed.level).is_ok_and(|parsed| parsed <= level) {
+                entries.push(parsed.into_entry());
+                if entries.len() == lines {
+                    entries.reverse();
+                    return Ok(entries);
+                }
+            }
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Tauri command returning the latest log entries. `level` is the least
+/// severe level included ("error" to "trace", default all).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recent_logs(
+    app: AppHandle,
+    level: Option<String>,
+    lines: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = match level.as_deref() {
+        Some(level) => {
+            Level::from_str(level).map_err(|_| format!("Invalid log level: {}", level))?
+        }
+        None => Level::TRACE,
+    };
+    let lines = lines
+        .unwrap_or(DEFAULT_RECENT_LINES)
+        .clamp(1, MAX_RECENT_LINES);
+    tauri::async_runtime::spawn_blocking(move || recent_logs(&app, level, lines))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+fn monitor_layout(app: &AppHandle) -> Result<String, String> {
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| format!("Failed to get monitor layout: {}", e))?;
+    let primary = app.primary_monitor().ok().flatten();
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let is_primary = primary.as_ref().is_some_and(|primary| {
+                primary.name() == monitor.name() && primary.position() == monitor.position()
+            });
+            format!(
+                "  {}  {}x{} at {},{}  scale {}  {}{}",
+                index + 1,
+                monitor.size().width,
+                monitor.size().height,
+                monitor.position().x,
+                monitor.position().y,
+                monitor.scale_factor(),
+                monitor.name().map(String::as_str).unwrap_or("unknown"),
+                if is_primary { " (primary)" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+// Plain text for pasting into a bug report; holds no keys or chat content
+fn diagnostics(app: &AppHandle) -> Result<String, String> {
+    let monitors = monitor_layout(app).unwrap_or_else(|e| format!("  {}", e));
+    let environment = crate::crash::capture_environment();
+    let logs = recent_logs(app, Level::DEBUG, DIAGNOSTICS_LINES)?
+        .into_iter()
+        .map(|entry| {
+            format!(
+                "{} {:>5} {}: {}",
+                entry.timestamp, entry.level, entry.target, entry.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!(
+        "Pluely {}\nOS: {} {}\nSession: {} {}\nScreen capture: {}\nSystem audio: {}\nTauri: {}\nWebview: {}\nWorkspace: {}\n\nMonitors:\n{}\n\nRecent logs:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        environment.session_type.as_deref().unwrap_or("-"),
+        environment.desktop.as_deref().unwrap_or(""),
+        environment.screen_backend,
+        environment.audio_backend,
+        tauri::VERSION,
+        tauri::webview_version().unwrap_or_else(|e| format!("unknown ({})", e)),
+        crate::workspaces::active_workspace(),
+        monitors,
+        logs
+    ))
+}
+
+/// Tauri command building the diagnostics bundle (system info, monitor layout
+/// and recent logs), copying it to the clipboard and returning it
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_diagnostics(app: AppHandle) -> Result<String, String> {
+    let bundle = tauri::async_runtime::spawn_blocking(move || diagnostics(&app))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))??;
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(bundle.clone()))
+        .map_err(|e| format!("Failed to copy diagnostics: {}", e))?;
+    Ok(bundle)
+}
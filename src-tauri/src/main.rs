@@ -2,5 +2,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if pluely_lib::is_mcp_launch() {
+        pluely_lib::run_mcp_server();
+        return;
+    }
+    if let Some(command) = pluely_lib::cli_command() {
+        std::process::exit(pluely_lib::run_cli(command));
+    }
     pluely_lib::run()
 }
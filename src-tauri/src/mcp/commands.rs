@@ -0,0 +1,22 @@
+// Pluely MCP commands: what to paste into an agent's MCP server settings.
+use serde::Serialize;
+use specta::Type;
+
+use super::MCP_FLAG;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct McpServerCommand {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+// The command an MCP client runs to start Pluely's stdio server
+#[tauri::command]
+#[specta::specta]
+pub fn get_mcp_server_command() -> Result<McpServerCommand, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate executable: {}", e))?;
+    Ok(McpServerCommand {
+        command: exe.to_string_lossy().to_string(),
+        args: vec![MCP_FLAG.to_string()],
+    })
+}
@@ -0,0 +1,230 @@
+// Pluely as a Model Context Protocol server. Started with `--mcp`, it speaks
+// JSON-RPC over stdio so agents like Claude Desktop or IDE assistants can use
+// the capture stack: screenshots, OCR and the active window.
+use base64::{engine::general_purpose, Engine as _};
+use image::ImageFormat;
+use serde_json::{json, Value};
+use std::io::Cursor;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::warn;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+pub const MCP_FLAG: &str = "--mcp";
+// Used when the client doesn't say which version it speaks
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn tool_list() -> Value {
+    json!([
+        {
+            "name": "take_screenshot",
+            "description": "Capture the monitor under the mouse cursor as a PNG image.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "ocr_region",
+            "description": "Read the text in a region of the monitor under the mouse cursor. Coordinates are pixels from the monitor's top-left corner; omit them to read the whole monitor.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "x": { "type": "integer", "minimum": 0 },
+                    "y": { "type": "integer", "minimum": 0 },
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                }
+            }
+        },
+        {
+            "name": "get_active_window",
+            "description": "Title, application and bounds of the window the user is working in.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+// Reads requests from stdin until it closes. Stdout carries only protocol
+// messages, so diagnostics go to stderr.
+pub async fn serve_stdio() -> Result<(), String> {
+    // Screenshots follow the user's sensitive app rules
+    if let Err(e) = crate::settings::load_headless() {
+        warn!("Using the default sensitive app rules: {}", e);
+    }
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| format!("Failed to read from stdin: {}", e))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(message).await,
+            Err(e) => Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Invalid JSON: {}", e),
+            )),
+        };
+        if let Some(response) = response {
+            let mut out = response.to_string();
+            out.push('\n');
+            stdout
+                .write_all(out.as_bytes())
+                .await
+                .map_err(|e| format!("Failed to write to stdout: {}", e))?;
+            stdout
+                .flush()
+                .await
+                .map_err(|e| format!("Failed to write to stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+// Notifications (no id) get no response
+async fn handle_message(message: Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => {
+            let version = params
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or(PROTOCOL_VERSION);
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "pluely", "version": env!("CARGO_PKG_VERSION") }
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_list() }),
+        "tools/call" => {
+            let Some(name) = params.get("name").and_then(|n| n.as_str()) else {
+                return Some(error_response(id, INVALID_PARAMS, "Missing tool name"));
+            };
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            // Tool failures are results the agent can read, not protocol errors
+            match call_tool(name, &arguments).await {
+                Ok(content) => json!({ "content": content, "isError": false }),
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": e }],
+                    "isError": true
+                }),
+            }
+        }
+        _ => {
+            return Some(error_response(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("Unknown method: {}", method),
+            ))
+        }
+    };
+
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+async fn call_tool(name: &str, arguments: &Value) -> Result<Vec<Value>, String> {
+    match name {
+        "take_screenshot" => {
+            let image = crate::capture::capture_screen_base64().await?;
+            Ok(vec![
+                json!({ "type": "image", "data": image, "mimeType": "image/png" }),
+            ])
+        }
+        "ocr_region" => {
+            let image = crate::capture::capture_screen_base64().await?;
+            let region = ["x", "y", "width", "height"]
+                .map(|key| arguments.get(key).and_then(|v| v.as_u64()));
+            let image = match region {
+                [Some(x), Some(y), Some(width), Some(height)] => {
+                    crop_png(&image, x as u32, y as u32, width as u32, height as u32)?
+                }
+                [None, None, None, None] => image,
+                _ => return Err("Give all of x, y, width and height, or none".to_string()),
+            };
+            let text = crate::llm::ocr_image(image).await?;
+            Ok(vec![json!({ "type": "text", "text": text })])
+        }
+        "get_active_window" => {
+            let window = tauri::async_runtime::spawn_blocking(active_window)
+                .await
+                .map_err(|e| format!("Task panicked: {}", e))??;
+            Ok(vec![json!({ "type": "text", "text": window.to_string() })])
+        }
+        other => Err(format!("Unknown tool: {}", other)),
+    }
+}
+
+fn crop_png(image_base64: &str, x: u32, y: u32, width: u32, height: u32) -> Result<String, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let image =
+        image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    if x >= image.width() || y >= image.height() {
+        return Err(format!(
+            "Region starts outside the {}x{} monitor",
+            image.width(),
+            image.height()
+        ));
+    }
+    // Regions running off the edge are clipped to the monitor
+    let cropped = image.crop_imm(
+        x,
+        y,
+        width.min(image.width() - x),
+        height.min(image.height() - y),
+    );
+
+    let mut png = Vec::new();
+    cropped
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(general_purpose::STANDARD.encode(png))
+}
+
+// xcap lists windows front to back, so the first visible one other than
+// Pluely itself is the one in use.
+fn active_window() -> Result<Value, String> {
+    let windows = xcap::Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+    let window = windows
+        .iter()
+        .find(|w| {
+            !w.is_minimized()
+                && w.width() > 0
+                && !w.title().is_empty()
+                && !w.app_name().eq_ignore_ascii_case("pluely")
+        })
+        .ok_or("No active window found".to_string())?;
+    Ok(json!({
+        "title": window.title(),
+        "app": window.app_name(),
+        "x": window.x(),
+        "y": window.y(),
+        "width": window.width(),
+        "height": window.height(),
+    }))
+}
@@ -0,0 +1,342 @@
+// Pluely meeting detection. An opt-in watcher notices when a meeting app is in
+// the foreground or using the microphone and when the OS Do Not Disturb / Focus
+// mode changes, and emits events so the UI can offer transcription. Stealth
+// mode can also be switched on automatically when a meeting starts.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, warn};
+
+use crate::events::{MeetingEnded, MeetingStarted};
+
+// A meeting counts as over after this long without any sign of it, so
+// switching away from the meeting window doesn't end it
+const ENDED_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MeetingSettings {
+    // Off unless the user opts in
+    pub detect: bool,
+    // Process names of meeting apps, matched against microphone users
+    pub apps: Vec<String>,
+    // Window titles that mean a meeting is open, e.g. a Google Meet tab
+    pub window_titles: Vec<String>,
+    // Hides the dashboard and keeps every window out of screen shares
+    pub auto_stealth: bool,
+    // Asks the UI to offer transcription when a meeting starts
+    pub offer_transcription: bool,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for MeetingSettings {
+    fn default() -> Self {
+        Self {
+            detect: false,
+            apps: ["zoom", "teams", "webex", "facetime", "slack", "discord"]
+                .map(String::from)
+                .to_vec(),
+            window_titles: [
+                "zoom meeting",
+                "meet - ",
+                "meeting | microsoft teams",
+                "webex meeting",
+            ]
+            .map(String::from)
+            .to_vec(),
+            auto_stealth: false,
+            offer_transcription: true,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum MeetingSignal {
+    // A meeting window is focused
+    Foreground,
+    // A meeting app is recording from the microphone
+    Microphone,
+}
+
+// Payload of `meeting-started`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Meeting {
+    // The matched app name or window title
+    pub app: String,
+    pub signal: MeetingSignal,
+    // Unix milliseconds
+    pub started_at: i64,
+    pub offer_transcription: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingStatus {
+    pub meeting: Option<Meeting>,
+    // None where the platform doesn't say
+    pub do_not_disturb: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct MeetingState {
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    meeting: Mutex<Option<Meeting>>,
+    do_not_disturb: Mutex<Option<bool>>,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// Apps currently recording from the microphone, lowercased
+#[cfg(target_os = "linux")]
+fn microphone_users() -> Vec<String> {
+    let Ok(output) = std::process::Command::new("pactl")
+        .args(["list", "source-outputs"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(" = ")?;
+            matches!(key, "application.name" | "application.process.binary")
+                .then(|| value.trim_matches('"').to_lowercase())
+        })
+        .collect()
+}
+
+// Windows records microphone use per app; a zero stop time means still in use
+#[cfg(target_os = "windows")]
+fn microphone_users() -> Vec<String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    const CONSENT_STORE: &str = r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+    let Ok(store) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(CONSENT_STORE) else {
+        return Vec::new();
+    };
+    let in_use = |key: &RegKey| {
+        key.get_value::<u64, _>("LastUsedTimeStart").unwrap_or(0) != 0
+            && key.get_value::<u64, _>("LastUsedTimeStop").unwrap_or(1) == 0
+    };
+    let mut users = Vec::new();
+    for name in store.enum_keys().filter_map(Result::ok) {
+        let Ok(key) = store.open_subkey(&name) else {
+            continue;
+        };
+        if name == "NonPackaged" {
+            // Desktop apps are keyed by their path with '#' for '\'
+            for path in key.enum_keys().filter_map(Result::ok) {
+                if key.open_subkey(&path).is_ok_and(|app| in_use(&app)) {
+                    users.push(path.rsplit('#').next().unwrap_or(&path).to_lowercase());
+                }
+            }
+        } else if in_use(&key) {
+            users.push(name.to_lowercase());
+        }
+    }
+    users
+}
+
+// macOS doesn't tell other apps who holds the microphone
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn microphone_users() -> Vec<String> {
+    Vec::new()
+}
+
+// Focus modes are recorded as assertions; reading them needs Full Disk Access
+#[cfg(target_os = "macos")]
+fn do_not_disturb() -> Option<bool> {
+    let path = std::env::var("HOME").ok()? + "/Library/DoNotDisturb/DB/Assertions.json";
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    let records = json["data"][0]["storeAssertionRecords"].as_array();
+    Some(records.is_some_and(|records| !records.is_empty()))
+}
+
+// Turning off toasts is what Focus Assist and the notification switch do
+#[cfg(target_os = "windows")]
+fn do_not_disturb() -> Option<bool> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Notifications\Settings")
+        .ok()?;
+    let enabled = key
+        .get_value::<u32, _>("NOC_GLOBAL_SETTING_TOASTS_ENABLED")
+        .unwrap_or(1);
+    Some(enabled == 0)
+}
+
+// GNOME hides banners in Do Not Disturb; other desktops aren't known
+#[cfg(target_os = "linux")]
+fn do_not_disturb() -> Option<bool> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "false" => Some(true),
+        "true" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn do_not_disturb() -> Option<bool> {
+    None
+}
+
+fn find_match(patterns: &[String], text: &str) -> Option<String> {
+    patterns
+        .iter()
+        .find(|pattern| !pattern.is_empty() && text.contains(&pattern.to_lowercase()))
+        .cloned()
+}
+
+// The meeting app in use right now, if any
+fn detect(settings: &MeetingSettings) -> Option<(String, MeetingSignal)> {
+    if let Some(focused) = crate::focus::focused_window() {
+        let title = focused.title.to_lowercase();
+        if let Some(pattern) = find_match(&settings.window_titles, &title) {
+            return Some((pattern, MeetingSignal::Foreground));
+        }
+    }
+    microphone_users()
+        .iter()
+        .find_map(|user| find_match(&settings.apps, user))
+        .map(|app| (app, MeetingSignal::Microphone))
+}
+
+// Keeps Pluely out of the meeting: the dashboard shows up in the taskbar and
+// screen shares, so it is hidden, and every window is content protected
+fn enter_stealth(app: &AppHandle) {
+    if let Err(e) = crate::window::set_content_protection_all(app, true) {
+        warn!("{}", e);
+    }
+    if let Some(dashboard) = app.get_webview_window("dashboard") {
+        let _ = dashboard.hide();
+    }
+}
+
+fn watch(app: AppHandle, settings: MeetingSettings, stop: Arc<AtomicBool>) {
+    let interval = Duration::from_secs(settings.poll_interval_secs);
+    let mut last_seen = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        if crate::power::background_paused() {
+            std::thread::sleep(interval);
+            continue;
+        }
+        let state = app.state::<MeetingState>();
+
+        let dnd = do_not_disturb();
+        if let Ok(mut last) = state.do_not_disturb.lock() {
+            if *last != dnd {
+                *last = dnd;
+                let changed = crate::events::DoNotDisturbChanged::new(dnd);
+                if let Err(e) = app.emit("do-not-disturb-changed", changed) {
+                    warn!("Failed to emit do not disturb change: {}", e);
+                }
+            }
+        }
+
+        let detected = detect(&settings);
+        let Ok(mut meeting) = state.meeting.lock() else {
+            break;
+        };
+        match (detected, meeting.as_ref()) {
+            (Some(_), Some(_)) => last_seen = Instant::now(),
+            (Some((app_name, signal)), None) => {
+                last_seen = Instant::now();
+                let started = Meeting {
+                    app: app_name,
+                    signal,
+                    started_at: now_ms(),
+                    offer_transcription: settings.offer_transcription,
+                };
+                debug!("Meeting started in {} ({:?})", started.app, signal);
+                if settings.auto_stealth {
+                    enter_stealth(&app);
+                }
+                if let Err(e) = app.emit("meeting-started", MeetingStarted(started.clone())) {
+                    warn!("Failed to emit meeting start: {}", e);
+                }
+                *meeting = Some(started);
+            }
+            (None, Some(_)) if last_seen.elapsed() >= ENDED_AFTER => {
+                if let Some(ended) = meeting.take() {
+                    debug!("Meeting in {} ended", ended.app);
+                    if let Err(e) = app.emit("meeting-ended", MeetingEnded(ended)) {
+                        warn!("Failed to emit meeting end: {}", e);
+                    }
+                }
+            }
+            _ => {}
+        }
+        drop(meeting);
+        std::thread::sleep(interval);
+    }
+}
+
+// Starts or stops the watcher to match `settings`
+pub fn apply_meeting_settings(app: &AppHandle, settings: &MeetingSettings) -> Result<(), String> {
+    if !(1..=60).contains(&settings.poll_interval_secs) {
+        return Err("Invalid meeting poll interval: must be 1-60 seconds".to_string());
+    }
+    let state = app.state::<MeetingState>();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Restarted so new apps and intervals take effect
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if settings.detect {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let settings = settings.clone();
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, settings, flag));
+        *watcher = Some(stop);
+    } else {
+        *state
+            .meeting
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))? = None;
+    }
+    Ok(())
+}
+
+/// Tauri command returning the meeting in progress and the Do Not Disturb
+/// state
+#[tauri::command]
+#[specta::specta]
+pub fn get_meeting_status(app: AppHandle) -> Result<MeetingStatus, String> {
+    let state = app.state::<MeetingState>();
+    let meeting = state
+        .meeting
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone();
+    Ok(MeetingStatus {
+        meeting,
+        do_not_disturb: do_not_disturb(),
+    })
+}
@@ -0,0 +1,86 @@
+// Pluely network commands: proxy configuration and connectivity checks.
+use serde::Serialize;
+use specta::Type;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::{http_client, proxy_config, set_proxy_config, ProxyConfig, PROXY_KEY_ID};
+use crate::llm::{build_provider_kind, current_config, ChatMessage, ChatRole, LlmProviderKind};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTest {
+    pub provider: LlmProviderKind,
+    // Scheme and host only; never includes keys
+    pub url: String,
+    pub reachable: bool,
+    pub via_proxy: bool,
+    // Any HTTP status (even 401) means the API was reached
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_proxy_config() -> Result<ProxyConfig, String> {
+    proxy_config()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_proxy_config(mut config: ProxyConfig) -> Result<(), String> {
+    // The password goes to the keychain; the config keeps everything else
+    if let Some(password) = config.password.take() {
+        crate::secrets::store_api_key(PROXY_KEY_ID, &password)?;
+    }
+    set_proxy_config(config)
+}
+
+// Checks that `provider`'s API can be reached with the current proxy settings
+#[tauri::command]
+#[specta::specta]
+pub async fn test_connection(
+    app: AppHandle,
+    provider: LlmProviderKind,
+) -> Result<ConnectionTest, String> {
+    let config = current_config(&app)?;
+    // The request the provider would send tells us where its API lives
+    let (request, _) = build_provider_kind(&config, provider)?
+        .build_request(&[ChatMessage::new(ChatRole::User, "ping")], false)?;
+    let url =
+        reqwest::Url::parse(&request.url).map_err(|e| format!("Invalid provider URL: {}", e))?;
+    let origin = url.origin().ascii_serialization();
+    let via_proxy = proxy_config()?.enabled;
+
+    let started = Instant::now();
+    let result = http_client()?
+        .get(&origin)
+        .timeout(TEST_TIMEOUT)
+        .send()
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => ConnectionTest {
+            provider,
+            url: origin,
+            reachable: true,
+            via_proxy,
+            status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ConnectionTest {
+            provider,
+            url: origin,
+            reachable: false,
+            via_proxy,
+            status: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
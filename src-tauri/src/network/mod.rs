@@ -0,0 +1,385 @@
+// Pluely outbound networking. Every HTTP request goes through the client built
+// here so a configured HTTP or SOCKS proxy applies everywhere, and so
+// local-only mode can keep every request on this machine.
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain account for the proxy password
+const PROXY_KEY_ID: &str = "proxy";
+
+// Local servers (Ollama, LM Studio) are never reached through the proxy
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+// Requests to IP addresses off this machine are sent to this proxy in
+// local-only mode, whose name the resolver refuses
+const BLOCKED_PROXY: &str = "http://blocked-by-local-only-mode.invalid";
+
+// Longest proxy answer to a CONNECT accepted before giving up
+const MAX_TUNNEL_RESPONSE: usize = 8 * 1024;
+
+// How long a reachability probe waits for any answer
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Process-wide because providers build requests without an app handle
+static PROXY: Mutex<Option<ProxyConfig>> = Mutex::new(None);
+static CLIENT: Mutex<Option<reqwest::Client>> = Mutex::new(None);
+static LOCAL_ONLY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct ProxyConfig {
+    // When off, reqwest still honours HTTP_PROXY / HTTPS_PROXY from the environment
+    pub enabled: bool,
+    // http://, https://, socks5:// or socks5h:// (DNS resolved by the proxy).
+    // Streaming connections only go through http:// proxies.
+    pub url: Option<String>,
+    pub username: Option<String>,
+    // Written to the keychain, never kept in the config
+    pub password: Option<String>,
+    // Hosts, domains (".corp.example") or CIDR ranges that skip the proxy
+    #[serde(default)]
+    pub bypass: Vec<String>,
+}
+
+pub fn proxy_config() -> Result<ProxyConfig, String> {
+    let config = PROXY
+        .lock()
+        .map_err(|e| format!("Failed to read proxy config: {}", e))?
+        .clone()
+        .unwrap_or_default();
+    Ok(config)
+}
+
+// Validates and applies `config`; later requests use the new client
+pub fn set_proxy_config(config: ProxyConfig) -> Result<(), String> {
+    build_proxy(&config)?;
+    *PROXY
+        .lock()
+        .map_err(|e| format!("Failed to update proxy config: {}", e))? = Some(config);
+    *CLIENT
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = None;
+    Ok(())
+}
+
+fn build_proxy(config: &ProxyConfig) -> Result<Option<reqwest::Proxy>, String> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let url = config
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or("Proxy URL is not configured".to_string())?;
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| format!("Invalid proxy URL {}: {}", url, e))?;
+    if !PROXY_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "Unsupported proxy scheme {}: expected http, https, socks5 or socks5h",
+            parsed.scheme()
+        ));
+    }
+
+    let mut proxy =
+        reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy URL {}: {}", url, e))?;
+    if let Some((username, password)) = proxy_credentials(config)? {
+        proxy = proxy.basic_auth(&username, &password);
+    }
+
+    let bypass = LOCAL_HOSTS
+        .iter()
+        .map(|host| host.to_string())
+        .chain(
+            config
+                .bypass
+                .iter()
+                .map(|entry| entry.trim().to_string())
+                .filter(|entry| !entry.is_empty()),
+        )
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(Some(proxy.no_proxy(reqwest::NoProxy::from_string(&bypass))))
+}
+
+// The proxy as a URL carrying its credentials, for clients that aren't built
+// here. None when no proxy is configured.
+pub fn proxy_url() -> Result<Option<reqwest::Url>, String> {
+    let config = proxy_config()?;
+    if build_proxy(&config)?.is_none() {
+        return Ok(None);
+    }
+    let mut url = reqwest::Url::parse(config.url.as_deref().unwrap_or_default().trim())
+        .map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if let Some((username, password)) = proxy_credentials(&config)? {
+        url.set_username(&username)
+            .and_then(|_| url.set_password(Some(&password)))
+            .map_err(|_| "Invalid proxy URL: it can't carry credentials".to_string())?;
+    }
+    Ok(Some(url))
+}
+
+// The proxy username and its keychain password, when a username is set
+fn proxy_credentials(config: &ProxyConfig) -> Result<Option<(String, String)>, String> {
+    let Some(username) = config.username.as_deref().filter(|u| !u.is_empty()) else {
+        return Ok(None);
+    };
+    let password = crate::secrets::get_api_key(PROXY_KEY_ID)?.unwrap_or_default();
+    Ok(Some((username.to_string(), password)))
+}
+
+// Whether `host` skips the proxy: a local host, or one matching a bypass
+// entry or a subdomain of it. CIDR entries only apply to HTTP requests.
+fn bypasses_proxy(config: &ProxyConfig, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    LOCAL_HOSTS.contains(&host.as_str())
+        || config.bypass.iter().any(|entry| {
+            let entry = entry.trim().trim_start_matches('.').to_ascii_lowercase();
+            entry == "*" || host == entry || host.ends_with(&format!(".{}", entry))
+        })
+}
+
+pub type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// Opens a websocket through the configured proxy, over an HTTP CONNECT
+// tunnel. Only http:// proxies can carry one, so other schemes are refused
+// rather than connecting around the proxy. Unlike HTTP requests, proxies from
+// the environment aren't used.
+pub async fn connect_websocket(request: Request) -> Result<WebSocket, String> {
+    let uri = request.uri().clone();
+    let host = uri
+        .host()
+        .ok_or("WebSocket URL has no host".to_string())?
+        .trim_matches(['[', ']'])
+        .to_string();
+    if local_only() && !LOCAL_HOSTS.contains(&host.to_ascii_lowercase().as_str()) {
+        return Err(format!("Local-only mode blocks requests to {}", host));
+    }
+
+    let config = proxy_config()?;
+    if build_proxy(&config)?.is_none() || bypasses_proxy(&config, &host) {
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(socket);
+    }
+    let proxy = reqwest::Url::parse(config.url.as_deref().unwrap_or_default().trim())
+        .map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if proxy.scheme() != "http" {
+        return Err(format!(
+            "Streaming connections can't go through a {} proxy; use an http:// proxy or add {} to the bypass list",
+            proxy.scheme(),
+            host
+        ));
+    }
+
+    let port = match uri.scheme_str() {
+        Some("wss") => uri.port_u16().unwrap_or(443),
+        _ => uri.port_u16().unwrap_or(80),
+    };
+    let stream = connect_tunnel(&config, &proxy, &host, port).await?;
+    let (socket, _) = tokio_tungstenite::client_async_tls(request, stream)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(socket)
+}
+
+// A TCP stream to `host:port` through the proxy at `proxy`
+async fn connect_tunnel(
+    config: &ProxyConfig,
+    proxy: &reqwest::Url,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, String> {
+    let proxy_host = proxy
+        .host_str()
+        .ok_or("Proxy URL has no host".to_string())?
+        .trim_matches(['[', ']']);
+    let proxy_port = proxy.port_or_known_default().unwrap_or(80);
+    let mut stream = TcpStream::connect((proxy_host, proxy_port))
+        .await
+        .map_err(|e| format!("Failed to reach proxy {}: {}", proxy_host, e))?;
+
+    let target = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let mut head = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+    if let Some((username, password)) = proxy_credentials(config)? {
+        let credentials = general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        head.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+    head.push_str("\r\n");
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write to proxy: {}", e))?;
+
+    // One byte at a time, so nothing past the headers is taken from the tunnel
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_TUNNEL_RESPONSE {
+            return Err("Proxy sent an oversized response".to_string());
+        }
+        let byte = stream
+            .read_u8()
+            .await
+            .map_err(|e| format!("Failed to read from proxy: {}", e))?;
+        response.push(byte);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or_default();
+    if !status
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code.starts_with('2'))
+    {
+        return Err(format!("Proxy refused the connection: {}", status));
+    }
+    Ok(stream)
+}
+
+// Whether requests may only reach this machine, by the user's choice or a
+// managed policy
+pub fn local_only() -> bool {
+    LOCAL_ONLY.load(Ordering::SeqCst) || crate::settings::policy_forces_local_only()
+}
+
+// Turns local-only mode on or off; later requests use a client built for it
+pub fn set_local_only(enabled: bool) -> Result<(), String> {
+    if LOCAL_ONLY.swap(enabled, Ordering::SeqCst) != enabled {
+        *CLIENT
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))? = None;
+    }
+    Ok(())
+}
+
+// For features that can't work without leaving the machine
+pub fn ensure_online(feature: &str) -> Result<(), String> {
+    if local_only() {
+        return Err(format!("{} is off in local-only mode", feature));
+    }
+    Ok(())
+}
+
+// Resolves localhost and nothing else
+struct LocalOnlyResolver;
+
+impl reqwest::dns::Resolve for LocalOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            if !LOCAL_HOSTS.contains(&host.to_ascii_lowercase().as_str()) {
+                return Err(format!("Local-only mode blocks requests to {}", host).into());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(
+                [
+                    SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+                    SocketAddr::from((Ipv6Addr::LOCALHOST, 0)),
+                ]
+                .into_iter(),
+            );
+            Ok(addrs)
+        })
+    }
+}
+
+// Names only resolve to localhost, and addresses off the machine, which skip
+// name resolution, go to a proxy that can't be resolved. Proxies, including
+// ones from the environment, are dropped.
+fn local_only_builder(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    builder
+        .no_proxy()
+        .proxy(reqwest::Proxy::custom(|url| {
+            let remote = url
+                .host_str()
+                .and_then(|host| host.trim_matches(['[', ']']).parse::<IpAddr>().ok())
+                .is_some_and(|ip| !ip.is_loopback());
+            remote.then_some(BLOCKED_PROXY)
+        }))
+        .dns_resolver(Arc::new(LocalOnlyResolver))
+}
+
+// A builder with the proxy applied, for callers that need their own timeouts
+pub fn client_builder() -> Result<reqwest::ClientBuilder, String> {
+    let builder = reqwest::Client::builder();
+    if local_only() {
+        return Ok(local_only_builder(builder));
+    }
+    match build_proxy(&proxy_config()?)? {
+        Some(proxy) => Ok(builder.proxy(proxy)),
+        None => Ok(builder),
+    }
+}
+
+// The shared client; built once per proxy config so connections are pooled
+pub fn http_client() -> Result<reqwest::Client, String> {
+    let mut client = CLIENT
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(client) = client.as_ref() {
+        return Ok(client.clone());
+    }
+    let built = client_builder()?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    *client = Some(built.clone());
+    Ok(built)
+}
+
+// Sends `request`, first recording it in the audit log as coming from
+// `source`. `redaction` lists what was left out or reduced before sending.
+// Every request that carries user data goes through here.
+pub async fn send(
+    request: reqwest::RequestBuilder,
+    source: &str,
+    redaction: &[String],
+) -> Result<reqwest::Response, reqwest::Error> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    // Multipart bodies are streamed but carry their length in a header
+    let bytes = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|body| body.len() as u64)
+        .or_else(|| {
+            request
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|length| length.to_str().ok()?.parse().ok())
+        });
+    crate::audit::record_egress(source, request.url(), bytes, redaction);
+    client.execute(request).await
+}
+
+// True when `url`'s server answers at all; error statuses such as 401 count
+pub async fn is_reachable(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let Ok(client) = http_client() else {
+        return false;
+    };
+    client
+        .head(url.origin().ascii_serialization())
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
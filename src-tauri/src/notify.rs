@@ -0,0 +1,101 @@
+// OS notifications for things that finish while Pluely is out of sight:
+// captures, transcriptions, provider errors and updates. Each category can
+// be turned off in the `notifications` settings section.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    CaptureSaved,
+    TranscriptionFinished,
+    ProviderError,
+    UpdateAvailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    // Skip notifications while the main window is visible and focused
+    pub only_when_hidden: bool,
+    pub capture_saved: bool,
+    pub transcription_finished: bool,
+    pub provider_error: bool,
+    pub update_available: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            only_when_hidden: true,
+            capture_saved: true,
+            transcription_finished: true,
+            provider_error: true,
+            update_available: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn allows(&self, category: NotificationCategory) -> bool {
+        self.enabled
+            && match category {
+                NotificationCategory::CaptureSaved => self.capture_saved,
+                NotificationCategory::TranscriptionFinished => self.transcription_finished,
+                NotificationCategory::ProviderError => self.provider_error,
+                NotificationCategory::UpdateAvailable => self.update_available,
+            }
+    }
+}
+
+fn window_in_view(app: &AppHandle) -> bool {
+    app.get_webview_window("main").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false)
+    })
+}
+
+// Keeps notification bodies to a glance
+pub fn summary(text: &str) -> String {
+    const MAX_CHARS: usize = 160;
+    let text = text.trim();
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+// Shows a notification unless its category is off; failures are only logged
+// since a missed notification shouldn't fail the work that triggered it
+pub fn notify(app: &AppHandle, category: NotificationCategory, title: &str, body: &str) {
+    let settings = crate::settings::current_settings(app)
+        .map(|settings| settings.notifications)
+        .unwrap_or_default();
+    if !settings.allows(category) || (settings.only_when_hidden && window_in_view(app)) {
+        return;
+    }
+    // Banners would pop up over a game or a presentation
+    if crate::fullscreen::suppress_overlays(app) {
+        return;
+    }
+
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Tauri command for events the frontend finishes, e.g. a streamed answer
+#[tauri::command]
+#[specta::specta]
+pub fn send_notification(
+    app: AppHandle,
+    category: NotificationCategory,
+    title: String,
+    body: String,
+) {
+    notify(&app, category, &title, &body);
+}
@@ -0,0 +1,264 @@
+// Pluely OCR language packs. Tesseract models for other scripts are downloaded
+// on demand into the app data directory instead of being bundled, and a
+// capture can name the language it is in to have them used.
+use futures_util::StreamExt;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+
+// Shared by every workspace, like the logs
+const TESSDATA_DIR: &str = "tessdata";
+const MODEL_EXTENSION: &str = "traineddata";
+// The fast integer models: a fraction of the size of the best ones, close in
+// accuracy on screen text
+const MODEL_URL: &str = "https://github.com/tesseract-ocr/tessdata_fast/raw/main";
+const MAX_CODE_LEN: usize = 32;
+// Progress is reported at most once per this many bytes
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+#[derive(Default)]
+pub struct OcrState {
+    // Codes of the packs being downloaded
+    downloading: Mutex<HashSet<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum OcrLanguageSource {
+    // Downloaded by Pluely; can be removed
+    App,
+    // Installed with tesseract itself
+    System,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLanguage {
+    // Tesseract's code, such as "eng", "jpn" or "chi_sim"
+    pub code: String,
+    pub source: OcrLanguageSource,
+    // Only known for downloaded packs
+    pub bytes: Option<u64>,
+}
+
+// Emitted as `ocr-language-progress` while a pack downloads
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLanguageProgress {
+    pub code: String,
+    pub downloaded: u64,
+    // None when the server didn't say
+    pub total: Option<u64>,
+    pub done: bool,
+}
+
+fn tessdata_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(TESSDATA_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create OCR language directory: {}", e))?;
+    Ok(dir)
+}
+
+fn validate_code(code: &str) -> Result<(), String> {
+    if code.is_empty()
+        || code.len() > MAX_CODE_LEN
+        || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(format!("Invalid OCR language: {}", code));
+    }
+    Ok(())
+}
+
+fn model_path(app: &AppHandle, code: &str) -> Result<PathBuf, String> {
+    validate_code(code)?;
+    Ok(tessdata_dir(app)?.join(format!("{}.{}", code, MODEL_EXTENSION)))
+}
+
+// Languages the tesseract install knows on its own
+fn system_languages() -> Vec<String> {
+    let Ok(output) = std::process::Command::new("tesseract")
+        .arg("--list-langs")
+        .output()
+    else {
+        return Vec::new();
+    };
+    // The first line is a heading naming the directory
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .map(|line| line.trim().to_string())
+        .filter(|line| validate_code(line).is_ok())
+        .collect()
+}
+
+/// Tesseract options for reading text in `language`, which may join several
+/// codes with "+". Downloaded packs are used when they cover every code;
+/// otherwise tesseract looks in its own install. None leaves tesseract's
+/// default.
+pub fn language_options(app: &AppHandle, language: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(language) = language.map(str::trim).filter(|l| !l.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let mut downloaded = true;
+    for code in language.split('+') {
+        downloaded &= model_path(app, code)?.exists();
+    }
+    let mut options = Vec::new();
+    if downloaded {
+        options.push("--tessdata-dir".to_string());
+        options.push(tessdata_dir(app)?.to_string_lossy().to_string());
+    }
+    options.push("-l".to_string());
+    options.push(language.to_string());
+    Ok(options)
+}
+
+// Downloaded packs first, then the ones tesseract has of its own
+#[tauri::command]
+#[specta::specta]
+pub async fn list_ocr_languages(app: AppHandle) -> Result<Vec<OcrLanguage>, String> {
+    let dir = tessdata_dir(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut languages: Vec<OcrLanguage> = std::fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read OCR language directory: {}", e))?
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != MODEL_EXTENSION {
+                    return None;
+                }
+                Some(OcrLanguage {
+                    code: path.file_stem()?.to_string_lossy().to_string(),
+                    source: OcrLanguageSource::App,
+                    bytes: entry.metadata().ok().map(|metadata| metadata.len()),
+                })
+            })
+            .collect();
+        languages.sort_by(|a, b| a.code.cmp(&b.code));
+        for code in system_languages() {
+            if !languages.iter().any(|language| language.code == code) {
+                languages.push(OcrLanguage {
+                    code,
+                    source: OcrLanguageSource::System,
+                    bytes: None,
+                });
+            }
+        }
+        Ok(languages)
+    })
+    .await
+    .map_err(|e| format!("Failed to list OCR languages: {}", e))?
+}
+
+// Downloads a language pack by its tesseract code, reporting progress as
+// `ocr-language-progress`
+#[tauri::command]
+#[specta::specta]
+pub async fn download_ocr_language(app: AppHandle, code: String) -> Result<OcrLanguage, String> {
+    let path = model_path(&app, &code)?;
+    {
+        let state = app.state::<OcrState>();
+        let mut downloading = state
+            .downloading
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        if !downloading.insert(code.clone()) {
+            return Err(format!("OCR language {} is already downloading", code));
+        }
+    }
+    let result = download(&app, &code, &path).await;
+    if let Ok(mut downloading) = app.state::<OcrState>().downloading.lock() {
+        downloading.remove(&code);
+    }
+    let bytes = result?;
+    Ok(OcrLanguage {
+        code,
+        source: OcrLanguageSource::App,
+        bytes: Some(bytes),
+    })
+}
+
+async fn download(app: &AppHandle, code: &str, path: &Path) -> Result<u64, String> {
+    let url = format!("{}/{}.{}", MODEL_URL, code, MODEL_EXTENSION);
+    let response = crate::network::http_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download OCR language {}: {}", code, e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("Unknown OCR language: {}", code));
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download OCR language {}: {}",
+            code,
+            response.status()
+        ));
+    }
+    let total = response.content_length();
+    let progress = |downloaded, done| {
+        let _ = app.emit(
+            "ocr-language-progress",
+            OcrLanguageProgress {
+                code: code.to_string(),
+                downloaded,
+                total,
+                done,
+            },
+        );
+    };
+
+    // Written beside the pack and renamed into place, so tesseract never
+    // sees half a model
+    let partial = path.with_extension("part");
+    let mut file = std::fs::File::create(&partial)
+        .map_err(|e| format!("Failed to save OCR language {}: {}", code, e))?;
+    let mut downloaded = 0u64;
+    let mut reported = 0u64;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let written = chunk
+            .map_err(|e| format!("Failed to download OCR language {}: {}", code, e))
+            .and_then(|chunk| {
+                file.write_all(&chunk)
+                    .map(|_| chunk.len() as u64)
+                    .map_err(|e| format!("Failed to save OCR language {}: {}", code, e))
+            });
+        match written {
+            Ok(written) => downloaded += written,
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                return Err(e);
+            }
+        }
+        if downloaded - reported >= PROGRESS_STEP {
+            reported = downloaded;
+            progress(downloaded, false);
+        }
+    }
+    drop(file);
+    std::fs::rename(&partial, path)
+        .map_err(|e| format!("Failed to save OCR language {}: {}", code, e))?;
+    progress(downloaded, true);
+    Ok(downloaded)
+}
+
+// Removes a downloaded pack; the ones tesseract ships with stay
+#[tauri::command]
+#[specta::specta]
+pub fn remove_ocr_language(app: AppHandle, code: String) -> Result<(), String> {
+    let path = model_path(&app, &code)?;
+    if !path.exists() {
+        return Err(format!("OCR language {} was not downloaded", code));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove OCR language: {}", e))
+}
@@ -0,0 +1,250 @@
+// Pluely pinned captures. A capture from the library can be pinned to the
+// screen in its own small borderless window that stays on top, like a sticky
+// reference while working. Pins and where they were left survive restarts.
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, LogicalSize, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tracing::warn;
+
+const PINS_FILE: &str = "pins.json";
+const WINDOW_PREFIX: &str = "pin-";
+// Longest edge of a new pin, in logical pixels
+const MAX_PIN_SIZE: f64 = 480.0;
+const MIN_PIN_SIZE: f64 = 64.0;
+// Pins are saved once a drag or resize has settled this long
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Pin {
+    pub id: String,
+    // File name in the capture library
+    pub capture_id: String,
+    // Logical pixels; None until the pin has been placed
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Default)]
+pub struct PinsState {
+    // None until read from disk
+    pins: Mutex<Option<Vec<Pin>>>,
+    // Bumped on every change so only the last of a burst is written
+    generation: AtomicU64,
+}
+
+fn pins_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspaces::data_dir(app)?.join(PINS_FILE))
+}
+
+fn read_pins(app: &AppHandle) -> Result<Vec<Pin>, String> {
+    let path = pins_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read pins: {}", e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse pins: {}", e))
+}
+
+fn write_pins(app: &AppHandle, pins: &[Pin]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(pins)
+        .map_err(|e| format!("Failed to serialize pins: {}", e))?;
+    std::fs::write(pins_path(app)?, json).map_err(|e| format!("Failed to save pins: {}", e))
+}
+
+// Runs `f` on the pin list, saving it afterwards when `save` is set
+fn with_pins<T>(
+    app: &AppHandle,
+    save: bool,
+    f: impl FnOnce(&mut Vec<Pin>) -> T,
+) -> Result<T, String> {
+    let state = app.state::<PinsState>();
+    let mut pins = state
+        .pins
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if pins.is_none() {
+        *pins = Some(read_pins(app)?);
+    }
+    let list = pins.get_or_insert_with(Vec::new);
+    let result = f(list);
+    if save {
+        write_pins(app, list)?;
+    }
+    Ok(result)
+}
+
+fn update_pins<T>(app: &AppHandle, f: impl FnOnce(&mut Vec<Pin>) -> T) -> Result<T, String> {
+    with_pins(app, true, f)
+}
+
+fn all_pins(app: &AppHandle) -> Result<Vec<Pin>, String> {
+    with_pins(app, false, |pins| pins.clone())
+}
+
+fn window_label(pin_id: &str) -> String {
+    format!("{}{}", WINDOW_PREFIX, pin_id)
+}
+
+fn open_window(app: &AppHandle, pin: &Pin) -> Result<WebviewWindow, String> {
+    if let Some(window) = app.get_webview_window(&window_label(&pin.id)) {
+        let _ = window.set_focus();
+        return Ok(window);
+    }
+    let mut builder = WebviewWindowBuilder::new(
+        app,
+        window_label(&pin.id),
+        WebviewUrl::App(format!("/pin/{}", pin.id).into()),
+    )
+    .title("Pluely - Pin")
+    .inner_size(pin.width, pin.height)
+    .min_inner_size(MIN_PIN_SIZE, MIN_PIN_SIZE)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .shadow(true)
+    .content_protected(crate::window::content_protected())
+    .focused(false);
+    builder = match (pin.x, pin.y) {
+        (Some(x), Some(y)) => builder.position(x, y),
+        _ => builder.center(),
+    };
+    builder
+        .build()
+        .map_err(|e| format!("Failed to open pin window: {}", e))
+}
+
+// Records where a pin window was moved or resized to
+pub fn window_changed(window: &tauri::Window) {
+    let Some(pin_id) = window.label().strip_prefix(WINDOW_PREFIX) else {
+        return;
+    };
+    let (Ok(scale), Ok(position), Ok(size)) = (
+        window.scale_factor(),
+        window.outer_position(),
+        window.inner_size(),
+    ) else {
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    let size: LogicalSize<f64> = size.to_logical(scale);
+    let app = window.app_handle().clone();
+    let moved = with_pins(&app, false, |pins| {
+        if let Some(pin) = pins.iter_mut().find(|pin| pin.id == pin_id) {
+            pin.x = Some(position.x);
+            pin.y = Some(position.y);
+            pin.width = size.width;
+            pin.height = size.height;
+        }
+    });
+    if let Err(e) = moved {
+        warn!("{}", e);
+        return;
+    }
+
+    // Written once the drag is over rather than on every step of it
+    let state = app.state::<PinsState>();
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if app.state::<PinsState>().generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = update_pins(&app, |_| ()) {
+            warn!("{}", e);
+        }
+    });
+}
+
+/// Reopens the pins left on screen last time; pins whose capture has been
+/// deleted since are dropped
+pub fn restore_pins(app: &AppHandle) {
+    let restored = update_pins(app, |pins| {
+        pins.retain(|pin| crate::captures::capture_path(app, &pin.capture_id).is_ok());
+        pins.clone()
+    });
+    match restored {
+        Ok(pins) => {
+            for pin in pins {
+                if let Err(e) = open_window(app, &pin) {
+                    warn!("{}", e);
+                }
+            }
+        }
+        Err(e) => warn!("Failed to restore pins: {}", e),
+    }
+}
+
+/// Tauri command pinning a capture (by its file name in the library) to the
+/// screen in an always-on-top window
+#[tauri::command]
+#[specta::specta]
+pub fn pin_capture(app: AppHandle, capture_id: String) -> Result<Pin, String> {
+    let path = crate::captures::capture_path(&app, &capture_id)?;
+    let bytes = crate::storage::read_file(&path)?;
+    let (width, height) = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read capture: {}", e))?
+        .into_dimensions()
+        .map_err(|e| format!("Failed to read capture: {}", e))?;
+    // Shown at most MAX_PIN_SIZE on its longest edge, never enlarged
+    let scale = (MAX_PIN_SIZE / width.max(height) as f64).min(1.0);
+    let pin = Pin {
+        id: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+        capture_id,
+        x: None,
+        y: None,
+        width: (width as f64 * scale).max(MIN_PIN_SIZE),
+        height: (height as f64 * scale).max(MIN_PIN_SIZE),
+    };
+    update_pins(&app, |pins| pins.push(pin.clone()))?;
+    open_window(&app, &pin)?;
+    Ok(pin)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_pins(app: AppHandle) -> Result<Vec<Pin>, String> {
+    all_pins(&app)
+}
+
+/// Tauri command closing a pin's window and forgetting it
+#[tauri::command]
+#[specta::specta]
+pub fn close_pin(app: AppHandle, pin_id: String) -> Result<(), String> {
+    let removed = update_pins(&app, |pins| {
+        let before = pins.len();
+        pins.retain(|pin| pin.id != pin_id);
+        pins.len() != before
+    })?;
+    if !removed {
+        return Err(format!("Pin not found: {}", pin_id));
+    }
+    if let Some(window) = app.get_webview_window(&window_label(&pin_id)) {
+        window
+            .destroy()
+            .map_err(|e| format!("Failed to close pin window: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Tauri command returning a pin's image as base64, for its window to show
+#[tauri::command]
+#[specta::specta]
+pub fn get_pin_image(app: AppHandle, pin_id: String) -> Result<String, String> {
+    let pin = all_pins(&app)?
+        .into_iter()
+        .find(|pin| pin.id == pin_id)
+        .ok_or_else(|| format!("Pin not found: {}", pin_id))?;
+    let bytes = crate::storage::read_file(&crate::captures::capture_path(&app, &pin.capture_id)?)?;
+    Ok(general_purpose::STANDARD.encode(bytes))
+}
@@ -0,0 +1,431 @@
+// Pluely window placement. The main window's position and size are saved per
+// monitor layout, so docking and undocking a laptop each bring back their own
+// spot, and a window dropped near a screen edge snaps to it.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime,
+    WebviewWindow,
+};
+use tracing::{debug, warn};
+
+use crate::events::WindowModeChanged;
+
+// A move or resize counts as finished after this long without another
+const SETTLE_DELAY: Duration = Duration::from_millis(750);
+// How much of the window has to stay on screen when restoring
+const MIN_VISIBLE_PX: i32 = 64;
+// Gap between the window and the cursor or screen corner it is shown at
+const SHOW_MARGIN: i32 = 24;
+// Switching modes animates over this many frames
+const MODE_FRAMES: u32 = 10;
+const MODE_FRAME_DELAY: Duration = Duration::from_millis(16);
+
+// Mirrors the setting, since the hotkey handler has no settings access
+static SHOW_AT: Mutex<ShowAt> = Mutex::new(ShowAt::Saved);
+
+// Where the show-window hotkey brings the main window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ShowAt {
+    // Wherever it was left
+    #[default]
+    Saved,
+    // Just below the cursor
+    Cursor,
+    // A corner of the monitor the cursor is on
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// Physical pixels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPlacement {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Default)]
+pub struct PlacementState {
+    // Bumped on every move so only the last one of a drag is handled
+    generation: AtomicU64,
+    mode: Mutex<WindowMode>,
+}
+
+// Layouts of the main window, each with its own size and position
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode {
+    // Thin command bar
+    #[default]
+    Bar,
+    Compact,
+    // Full chat panel
+    Expanded,
+}
+
+impl WindowMode {
+    // Logical size until the user resizes the window in this mode
+    fn default_size(self) -> (f64, f64) {
+        match self {
+            WindowMode::Bar => (600.0, 54.0),
+            WindowMode::Compact => (600.0, 400.0),
+            WindowMode::Expanded => (900.0, 700.0),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            WindowMode::Bar => WindowMode::Compact,
+            WindowMode::Compact => WindowMode::Expanded,
+            WindowMode::Expanded => WindowMode::Bar,
+        }
+    }
+}
+
+// Identifies the monitor layout, e.g. "2560x1440@0,0;1920x1080@2560,0"
+fn layout_key(monitors: &[Monitor]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|monitor| {
+            format!(
+                "{}x{}@{},{}",
+                monitor.size().width,
+                monitor.size().height,
+                monitor.position().x,
+                monitor.position().y
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+fn current_placement(window: &WebviewWindow) -> Result<WindowPlacement, String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?;
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    Ok(WindowPlacement {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+// The monitor holding the window's center, or the nearest one
+fn monitor_for<'a>(monitors: &'a [Monitor], placement: &WindowPlacement) -> Option<&'a Monitor> {
+    let center_x = placement.x + placement.width as i32 / 2;
+    let center_y = placement.y + placement.height as i32 / 2;
+    monitors.iter().min_by_key(|monitor| {
+        let area = monitor.work_area();
+        let dx = (area.position.x - center_x)
+            .max(center_x - (area.position.x + area.size.width as i32))
+            .max(0) as i64;
+        let dy = (area.position.y - center_y)
+            .max(center_y - (area.position.y + area.size.height as i32))
+            .max(0) as i64;
+        dx * dx + dy * dy
+    })
+}
+
+// Moves a placement back on screen if a monitor it used is gone
+fn clamp(monitors: &[Monitor], placement: WindowPlacement) -> WindowPlacement {
+    let Some(monitor) = monitor_for(monitors, &placement) else {
+        return placement;
+    };
+    let area = monitor.work_area();
+    let (left, top) = (area.position.x, area.position.y);
+    let (right, bottom) = (left + area.size.width as i32, top + area.size.height as i32);
+    let width = placement.width.min(area.size.width);
+    let height = placement.height.min(area.size.height);
+    let visible = MIN_VISIBLE_PX.min(width as i32);
+    WindowPlacement {
+        x: placement
+            .x
+            .clamp(left - width as i32 + visible, right - visible),
+        // The title area must stay reachable, so never above the top edge
+        y: placement
+            .y
+            .clamp(top, bottom - MIN_VISIBLE_PX.min(height as i32)),
+        width,
+        height,
+    }
+}
+
+// Pulls an edge within `margin` of the work area onto it
+fn snap(monitors: &[Monitor], placement: WindowPlacement, margin: i32) -> WindowPlacement {
+    let Some(monitor) = monitor_for(monitors, &placement) else {
+        return placement;
+    };
+    let area = monitor.work_area();
+    let (left, top) = (area.position.x, area.position.y);
+    let right = left + area.size.width as i32 - placement.width as i32;
+    let bottom = top + area.size.height as i32 - placement.height as i32;
+    let snap_axis = |value: i32, low: i32, high: i32| {
+        if (value - low).abs() <= margin {
+            low
+        } else if (value - high).abs() <= margin {
+            high
+        } else {
+            value
+        }
+    };
+    WindowPlacement {
+        x: snap_axis(placement.x, left, right),
+        y: snap_axis(placement.y, top, bottom),
+        ..placement
+    }
+}
+
+// Snaps the finished move and saves where the window ended up
+fn settle(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    // Minimized and hidden windows report positions that aren't worth keeping
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return Ok(());
+    }
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let mut settings = crate::settings::current_settings(app)?;
+    let mut placement = current_placement(&window)?;
+
+    if settings.window.snap_to_edges {
+        let snapped = snap(&monitors, placement, settings.window.snap_margin as i32);
+        if snapped != placement {
+            // Setting the position moves the window again, which saves it
+            return window
+                .set_position(PhysicalPosition::new(snapped.x, snapped.y))
+                .map_err(|e| format!("Failed to snap window: {}", e));
+        }
+        placement = snapped;
+    }
+
+    let key = layout_key(&monitors);
+    if settings.window.placements.get(&key) == Some(&placement) {
+        return Ok(());
+    }
+    debug!("Saving window placement for {}", key);
+    settings.window.placements.insert(key, placement);
+    // Nothing to apply; only the file and the cached copy change
+    crate::settings::commit_settings(app, settings, &[])?;
+    Ok(())
+}
+
+/// Handles a move or resize of the main window once it comes to rest
+pub fn window_changed(app: &AppHandle) {
+    let state = app.state::<PlacementState>();
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SETTLE_DELAY).await;
+        if app
+            .state::<PlacementState>()
+            .generation
+            .load(Ordering::SeqCst)
+            != generation
+        {
+            return;
+        }
+        if let Err(e) = settle(&app) {
+            warn!("{}", e);
+        }
+    });
+}
+
+/// Puts the main window where it was last left on this monitor layout,
+/// keeping it on screen; without a saved placement it stays where it is
+pub fn restore(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let settings = crate::settings::current_settings(app)?;
+    let Some(saved) = settings.window.placements.get(&layout_key(&monitors)) else {
+        return Ok(());
+    };
+    let placement = clamp(&monitors, *saved);
+    window
+        .set_size(PhysicalSize::new(placement.width, placement.height))
+        .map_err(|e| format!("Failed to restore window size: {}", e))?;
+    window
+        .set_position(PhysicalPosition::new(placement.x, placement.y))
+        .map_err(|e| format!("Failed to restore window position: {}", e))
+}
+
+pub fn set_show_at(show_at: ShowAt) {
+    if let Ok(mut current) = SHOW_AT.lock() {
+        *current = show_at;
+    }
+}
+
+/// Moves the main window onto the monitor with the cursor, next to the cursor
+/// or into the configured corner; does nothing in `ShowAt::Saved` mode
+pub fn move_to_cursor<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    let show_at = *SHOW_AT
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if show_at == ShowAt::Saved {
+        return Ok(());
+    }
+    let (mouse_x, mouse_y) = crate::capture::get_mouse_position()?;
+    let monitors = xcap::Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let monitor = crate::capture::find_monitor_at_position(&monitors, mouse_x, mouse_y)
+        .map(|idx| &monitors[idx])
+        .ok_or_else(|| "No monitor found under the cursor".to_string())?;
+    let (left, top) = (monitor.x(), monitor.y());
+    let (right, bottom) = (left + monitor.width() as i32, top + monitor.height() as i32);
+
+    // The cursor and monitors are in points on macOS and pixels elsewhere
+    let scale = if cfg!(target_os = "macos") {
+        window
+            .scale_factor()
+            .map_err(|e| format!("Failed to get scale factor: {}", e))?
+    } else {
+        1.0
+    };
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    let width = (size.width as f64 / scale).round() as i32;
+    let height = (size.height as f64 / scale).round() as i32;
+
+    let (x, y) = match show_at {
+        ShowAt::Saved | ShowAt::Cursor => (mouse_x - width / 2, mouse_y + SHOW_MARGIN),
+        ShowAt::TopLeft => (left + SHOW_MARGIN, top + SHOW_MARGIN),
+        ShowAt::TopCenter => ((left + right - width) / 2, top + SHOW_MARGIN),
+        ShowAt::TopRight => (right - width - SHOW_MARGIN, top + SHOW_MARGIN),
+        ShowAt::BottomLeft => (left + SHOW_MARGIN, bottom - height - SHOW_MARGIN),
+        ShowAt::BottomRight => (right - width - SHOW_MARGIN, bottom - height - SHOW_MARGIN),
+    };
+    // Kept wholly on the cursor's monitor where it fits
+    let x = x.clamp(left, (right - width).max(left));
+    let y = y.clamp(top, (bottom - height).max(top));
+    let result = if cfg!(target_os = "macos") {
+        window.set_position(LogicalPosition::new(x, y))
+    } else {
+        window.set_position(PhysicalPosition::new(x, y))
+    };
+    result.map_err(|e| format!("Failed to move window to the cursor: {}", e))
+}
+
+// Eases the window from one placement to the other
+fn animate(window: WebviewWindow, from: WindowPlacement, to: WindowPlacement) {
+    let lerp = |a: i64, b: i64, t: f64| (a as f64 + (b - a) as f64 * t).round() as i64;
+    for frame in 1..=MODE_FRAMES {
+        let t = frame as f64 / MODE_FRAMES as f64;
+        // Ease out so the window settles gently
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        let position = PhysicalPosition::new(
+            lerp(from.x as i64, to.x as i64, t) as i32,
+            lerp(from.y as i64, to.y as i64, t) as i32,
+        );
+        let size = PhysicalSize::new(
+            lerp(from.width as i64, to.width as i64, t) as u32,
+            lerp(from.height as i64, to.height as i64, t) as u32,
+        );
+        if window
+            .set_size(size)
+            .and(window.set_position(position))
+            .is_err()
+        {
+            break;
+        }
+        std::thread::sleep(MODE_FRAME_DELAY);
+    }
+}
+
+/// Switches the main window to `mode`, saving the geometry of the mode it
+/// leaves and restoring the one `mode` was last left with
+pub fn set_mode(app: &AppHandle, mode: WindowMode) -> Result<(), String> {
+    let state = app.state::<PlacementState>();
+    let mut current = state
+        .mode
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if *current == mode {
+        return Ok(());
+    }
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let from = current_placement(&window)?;
+    let mut settings = crate::settings::current_settings(app)?;
+    settings.window.mode_geometry.insert(*current, from);
+
+    let target = match settings.window.mode_geometry.get(&mode) {
+        Some(saved) => *saved,
+        None => {
+            // A new mode opens at its default size, centered where the window was
+            let scale = window
+                .scale_factor()
+                .map_err(|e| format!("Failed to get scale factor: {}", e))?;
+            let (width, height) = mode.default_size();
+            let (width, height) = ((width * scale) as u32, (height * scale) as u32);
+            WindowPlacement {
+                x: from.x + (from.width as i32 - width as i32) / 2,
+                y: from.y,
+                width,
+                height,
+            }
+        }
+    };
+    let target = clamp(&monitors, target);
+    crate::settings::commit_settings(app, settings, &[])?;
+
+    debug!("Window mode {:?} -> {:?}", *current, mode);
+    *current = mode;
+    // The frontend switches its layout before the window finishes resizing
+    if let Err(e) = app.emit("window-mode-changed", WindowModeChanged::new(mode)) {
+        warn!("Failed to emit window mode change: {}", e);
+    }
+    std::thread::spawn(move || animate(window, from, target));
+    Ok(())
+}
+
+pub fn current_mode(app: &AppHandle) -> WindowMode {
+    app.state::<PlacementState>()
+        .mode
+        .lock()
+        .map(|mode| *mode)
+        .unwrap_or_default()
+}
+
+/// Tauri command to switch the main window between the command bar, compact
+/// and expanded layouts
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_mode(app: AppHandle, mode: WindowMode) -> Result<(), String> {
+    set_mode(&app, mode)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_window_mode(app: AppHandle) -> WindowMode {
+    current_mode(&app)
+}
+
+/// Moves to the next mode: bar, compact, expanded, then bar again
+pub fn cycle_mode(app: &AppHandle) -> Result<(), String> {
+    set_mode(app, current_mode(app).next())
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::{
+    discover, enabled_plugin, ensure_running, grant, plugins_dir, tool_name, HostService,
+    PluginGrant, PluginManifest, PluginsState, CALL_TIMEOUT,
+};
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    // The plugin's folder name
+    pub id: String,
+    pub path: String,
+    pub manifest: Option<PluginManifest>,
+    // Why the manifest couldn't be read
+    pub error: Option<String>,
+    pub grant: PluginGrant,
+    // Asked for in the manifest but not granted
+    pub missing_host_services: Vec<HostService>,
+    // Tool names as the model sees them
+    pub tools: Vec<String>,
+    pub running: bool,
+}
+
+/// Installed plugins with their manifests, grants and whether they're running
+#[tauri::command]
+#[specta::specta]
+pub fn list_plugins(app: AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let running = app
+        .state::<PluginsState>()
+        .running
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .iter()
+        .filter(|(_, process)| process.is_running())
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+
+    discover(&app)?
+        .into_iter()
+        .map(|(path, manifest)| {
+            let id = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let grant = grant(&app, &id)?;
+            let (manifest, error) = match manifest {
+                Ok(manifest) => (Some(manifest), None),
+                Err(e) => (None, Some(e)),
+            };
+            let missing_host_services = manifest
+                .iter()
+                .flat_map(|manifest| &manifest.host_services)
+                .filter(|service| !grant.host_services.contains(service))
+                .copied()
+                .collect();
+            let tools = manifest
+                .iter()
+                .flat_map(|manifest| &manifest.tools)
+                .map(|tool| tool_name(&id, &tool.name))
+                .collect();
+            Ok(PluginInfo {
+                running: running.contains(&id),
+                id,
+                path: path.to_string_lossy().to_string(),
+                manifest,
+                error,
+                grant,
+                missing_host_services,
+                tools,
+            })
+        })
+        .collect()
+}
+
+/// Folder plugins are installed into, one subfolder each
+#[tauri::command]
+#[specta::specta]
+pub fn get_plugins_dir(app: AppHandle) -> Result<String, String> {
+    Ok(plugins_dir(&app)?.to_string_lossy().to_string())
+}
+
+/// Calls `command` of an enabled plugin, starting it if needed, and returns
+/// what the plugin answered
+#[tauri::command]
+#[specta::specta]
+pub async fn call_plugin_command(
+    app: AppHandle,
+    plugin_id: String,
+    command: String,
+    arguments: Option<Value>,
+) -> Result<Value, String> {
+    let (_, manifest) = enabled_plugin(&app, &plugin_id)?;
+    if !manifest.commands.iter().any(|c| c.name == command) {
+        return Err(format!("Plugin {} has no command {}", plugin_id, command));
+    }
+    let process = ensure_running(&app, &plugin_id).await?;
+    process
+        .request(
+            "commands/call",
+            json!({ "name": command, "arguments": arguments.unwrap_or_else(|| json!({})) }),
+            CALL_TIMEOUT,
+        )
+        .await
+}
+
+/// Stops a running plugin; it starts again on next use. Returns whether it
+/// was running.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_plugin(app: AppHandle, plugin_id: String) -> Result<bool, String> {
+    super::stop(&app, &plugin_id)
+}
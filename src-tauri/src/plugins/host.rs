@@ -0,0 +1,344 @@
+// A plugin's process and the JSON-RPC 2.0 connection to it, one message per
+// line over stdio as with `--mcp`. Both sides send requests: Pluely calls the
+// plugin's commands and tools, and the plugin calls host services, which are
+// answered only within its grants. The process runs unsandboxed, with the
+// user's permissions (see mod.rs).
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+use super::{HostService, PluginManifest};
+use crate::llm::{ChatMessage, ChatRole};
+
+// JSON-RPC error codes
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// A host service the plugin wasn't granted
+const NOT_PERMITTED: i64 = -32001;
+const SERVICE_FAILED: i64 = -32002;
+
+// The only variables a plugin inherits; the rest of Pluely's environment may
+// hold provider keys
+const INHERITED_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "SystemRoot",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "LANG",
+];
+
+type Stdin = Arc<tokio::sync::Mutex<ChildStdin>>;
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+pub struct PluginProcess {
+    id: String,
+    stdin: Stdin,
+    // Requests waiting for the plugin's answer, by JSON-RPC id
+    pending: Pending,
+    next_id: AtomicU64,
+    running: Arc<AtomicBool>,
+    child: Mutex<Option<Child>>,
+}
+
+// Programs named with a path run from the plugin's folder; bare names are
+// looked up on PATH, such as `node` or `python3`
+fn program(manifest: &PluginManifest, dir: &Path) -> std::path::PathBuf {
+    let command = Path::new(manifest.command.trim());
+    if command.components().count() > 1 {
+        dir.join(command)
+    } else {
+        command.to_path_buf()
+    }
+}
+
+async fn write_message(stdin: &Stdin, message: &Value) -> Result<(), String> {
+    let mut line = message.to_string();
+    line.push('\n');
+    let mut stdin = stdin.lock().await;
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write to plugin: {}", e))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("Failed to write to plugin: {}", e))
+}
+
+impl PluginProcess {
+    pub fn spawn(
+        app: &AppHandle,
+        manifest: &PluginManifest,
+        dir: &Path,
+        granted: Vec<HostService>,
+    ) -> Result<Arc<Self>, String> {
+        let mut command = tokio::process::Command::new(program(manifest, dir));
+        command
+            .args(&manifest.args)
+            .current_dir(dir)
+            .env_clear()
+            .envs(
+                INHERITED_ENV
+                    .iter()
+                    .filter_map(|key| std::env::var_os(key).map(|value| (key, value))),
+            )
+            .env("PLUELY_PLUGIN_DIR", dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to start plugin {}: {}", manifest.id, e))?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(format!("Failed to start plugin {}: no stdio", manifest.id));
+        };
+
+        let process = Arc::new(Self {
+            id: manifest.id.clone(),
+            stdin: Arc::new(tokio::sync::Mutex::new(stdin)),
+            pending: Pending::default(),
+            next_id: AtomicU64::new(1),
+            running: Arc::new(AtomicBool::new(true)),
+            child: Mutex::new(Some(child)),
+        });
+        tauri::async_runtime::spawn(log_stderr(manifest.id.clone(), stderr));
+        tauri::async_runtime::spawn(read_messages(
+            app.clone(),
+            manifest.id.clone(),
+            granted,
+            stdout,
+            process.stdin.clone(),
+            process.pending.clone(),
+            process.running.clone(),
+        ));
+        Ok(process)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Ok(mut child) = self.child.lock() {
+            if let Some(mut child) = child.take() {
+                if let Err(e) = child.start_kill() {
+                    warn!("Failed to stop plugin {}: {}", self.id, e);
+                }
+            }
+        }
+    }
+
+    // Sends a request and waits up to `timeout` for its result
+    pub async fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        if !self.is_running() {
+            return Err(format!("Plugin {} is not running", self.id));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?
+            .insert(id, sender);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = write_message(&self.stdin, &message).await {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+            return Err(e);
+        }
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(format!("Plugin {} exited", self.id)),
+            Err(_) => {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.remove(&id);
+                }
+                Err(format!(
+                    "Plugin {} didn't answer {} within {}s",
+                    self.id,
+                    method,
+                    timeout.as_secs()
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+async fn log_stderr(id: String, stderr: ChildStderr) {
+    let mut lines = BufReader::new(stderr).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        info!("[plugin {}] {}", id, line);
+    }
+}
+
+async fn read_messages(
+    app: AppHandle,
+    id: String,
+    granted: Vec<HostService>,
+    stdout: ChildStdout,
+    stdin: Stdin,
+    pending: Pending,
+    running: Arc<AtomicBool>,
+) {
+    let granted = Arc::new(granted);
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Plugin {} sent invalid JSON: {}", id, e);
+                continue;
+            }
+        };
+
+        if let Some(method) = message.get("method").and_then(Value::as_str) {
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            // Notifications (no id) get no response; `log` is the only one
+            let Some(request_id) = message.get("id").cloned() else {
+                if method == "log" {
+                    let text = params.get("message").and_then(Value::as_str);
+                    info!("[plugin {}] {}", id, text.unwrap_or_default());
+                }
+                continue;
+            };
+            let (app, granted, stdin, method) = (
+                app.clone(),
+                granted.clone(),
+                stdin.clone(),
+                method.to_string(),
+            );
+            tauri::async_runtime::spawn(async move {
+                let response = match host_service(&app, &granted, &method, &params).await {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": request_id, "result": result }),
+                    Err((code, message)) => json!({
+                        "jsonrpc": "2.0",
+                        "id": request_id,
+                        "error": { "code": code, "message": message }
+                    }),
+                };
+                if let Err(e) = write_message(&stdin, &response).await {
+                    warn!("{}", e);
+                }
+            });
+            continue;
+        }
+
+        // Otherwise it answers one of our requests
+        let Some(request_id) = message.get("id").and_then(Value::as_u64) else {
+            continue;
+        };
+        let result = match message.get("error") {
+            Some(error) => Err(error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("Plugin error")
+                .to_string()),
+            None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+        };
+        if let Some(sender) = pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(&request_id))
+        {
+            let _ = sender.send(result);
+        }
+    }
+
+    // Dropping the senders fails every request still waiting
+    running.store(false, Ordering::SeqCst);
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+    info!("Plugin {} exited", id);
+}
+
+// Answers a plugin's request for something only Pluely can do
+async fn host_service(
+    app: &AppHandle,
+    granted: &[HostService],
+    method: &str,
+    params: &Value,
+) -> Result<Value, (i64, String)> {
+    let needed = match method {
+        "host/screenshot" | "host/ocrScreen" => HostService::Screen,
+        "host/readClipboard" => HostService::Clipboard,
+        "host/complete" => HostService::Llm,
+        _ => {
+            return Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method)));
+        }
+    };
+    if !granted.contains(&needed) {
+        return Err((
+            NOT_PERMITTED,
+            format!("{} needs the {} host service", method, needed.name()),
+        ));
+    }
+
+    let result = match method {
+        "host/screenshot" => crate::capture::capture_screen_base64()
+            .await
+            .map(|image| json!({ "image": image, "mimeType": "image/png" })),
+        "host/ocrScreen" => match crate::capture::capture_screen_base64().await {
+            Ok(image) => crate::llm::ocr_image(image)
+                .await
+                .map(|text| json!({ "text": text })),
+            Err(e) => Err(e),
+        },
+        "host/readClipboard" => tauri::async_runtime::spawn_blocking(|| {
+            arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.get_text())
+                .map_err(|e| format!("Failed to read clipboard: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))
+        .and_then(|text| text)
+        .map(|text| json!({ "text": text })),
+        _ => {
+            let Some(prompt) = params.get("prompt").and_then(Value::as_str) else {
+                return Err((INVALID_PARAMS, "host/complete needs a prompt".to_string()));
+            };
+            let mut messages = Vec::new();
+            if let Some(system) = params.get("system").and_then(Value::as_str) {
+                messages.push(ChatMessage::new(ChatRole::System, system));
+            }
+            messages.push(ChatMessage::new(ChatRole::User, prompt));
+            crate::llm::complete(app, messages)
+                .await
+                .map(|text| json!({ "text": text }))
+        }
+    };
+    result.map_err(|e| (SERVICE_FAILED, e))
+}
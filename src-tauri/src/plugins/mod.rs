@@ -0,0 +1,390 @@
+// Pluely plugins. A plugin is a folder under `plugins/` in the app's data
+// directory with a `plugin.json` manifest and a program Pluely runs as a
+// sidecar, speaking JSON-RPC over stdio (see host.rs). Its manifest declares
+// commands the frontend can call, tools the model can call and the host
+// services it wants.
+//
+// A plugin starts only once the user enables it. Host services are what
+// Pluely does on a plugin's behalf (the screen, the clipboard, the model), and
+// a plugin gets no more of those than the user granted. They are not a
+// sandbox: the process runs with the user's permissions, so it can read and
+// write their files and reach the network like any other program, and only
+// plugins the user trusts should be enabled. It is started without Pluely's
+// environment, which may hold provider keys.
+//
+// Manifest:
+// {"id", "name", "version", "description", "command", "args",
+//  "hostServices": ["screen" | "clipboard" | "llm"],
+//  "commands": [{"name", "description"}],
+//  "tools": [{"name", "description", "parameters" (JSON Schema)}]}
+//
+// Pluely sends `initialize` {"protocolVersion", "pluelyVersion", "granted"},
+// then `commands/call` and `tools/call` {"name", "arguments"}; tools answer
+// like MCP tools, {"content": [{"type": "text" | "image", ...}], "isError"}.
+// Plugins may call `host/screenshot`, `host/ocrScreen`, `host/readClipboard`
+// and `host/complete` {"prompt", "system"}, and send `log` {"message"}. A
+// plugin should exit when its stdin closes.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::llm::{ToolCall, ToolSpec};
+use host::PluginProcess;
+
+mod commands;
+mod host;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+const PROTOCOL_VERSION: u32 = 1;
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+const CALL_TIMEOUT: Duration = Duration::from_secs(60);
+// Tools are offered to the model as `<plugin>__<tool>`
+const TOOL_SEPARATOR: &str = "__";
+// The longest tool name providers accept
+const MAX_TOOL_NAME: usize = 64;
+
+// Something Pluely does when a plugin asks, if the user granted it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum HostService {
+    // Screenshots and OCR of the screen
+    Screen,
+    Clipboard,
+    // Prompts to the configured model, on the user's account
+    Llm,
+}
+
+impl HostService {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Screen => "screen",
+            Self::Clipboard => "clipboard",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginTool {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_schema")]
+    pub parameters: Value,
+}
+
+fn empty_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    // Also the plugin's folder name
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    // Relative paths are inside the plugin's folder
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub host_services: Vec<HostService>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub tools: Vec<PluginTool>,
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl PluginManifest {
+    fn validate(&self, folder: &str) -> Result<(), String> {
+        if self.id != folder {
+            return Err(format!(
+                "Plugin id {} doesn't match its folder {}",
+                self.id, folder
+            ));
+        }
+        if !valid_name(&self.id) || self.id.contains(TOOL_SEPARATOR) {
+            return Err(format!("Invalid plugin id: {}", self.id));
+        }
+        if self.command.trim().is_empty() {
+            return Err(format!("Plugin {} has no command", self.id));
+        }
+        for command in &self.commands {
+            if !valid_name(&command.name) {
+                return Err(format!("Invalid plugin command name: {}", command.name));
+            }
+        }
+        for tool in &self.tools {
+            if !valid_name(&tool.name) || tool_name(&self.id, &tool.name).len() > MAX_TOOL_NAME {
+                return Err(format!("Invalid plugin tool name: {}", tool.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginGrant {
+    pub enabled: bool,
+    pub host_services: Vec<HostService>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginSettings {
+    // By plugin id; plugins without an entry are off
+    pub plugins: HashMap<String, PluginGrant>,
+}
+
+#[derive(Default)]
+pub struct PluginsState {
+    settings: Mutex<PluginSettings>,
+    // Started on first use
+    running: Mutex<HashMap<String, Arc<PluginProcess>>>,
+}
+
+pub fn tool_name(plugin_id: &str, tool: &str) -> String {
+    format!("{}{}{}", plugin_id, TOOL_SEPARATOR, tool)
+}
+
+pub fn plugins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join(PLUGINS_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create plugins directory: {}", e))?;
+    Ok(dir)
+}
+
+fn read_manifest(dir: &Path) -> Result<PluginManifest, String> {
+    let folder = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let json = std::fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to read {} manifest: {}", folder, e))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid {} manifest: {}", folder, e))?;
+    manifest.validate(&folder)?;
+    Ok(manifest)
+}
+
+// A plugin folder, with its manifest or why it couldn't be read
+type Discovered = (PathBuf, Result<PluginManifest, String>);
+
+// Every plugin folder
+fn discover(app: &AppHandle) -> Result<Vec<Discovered>, String> {
+    let entries = std::fs::read_dir(plugins_dir(app)?)
+        .map_err(|e| format!("Failed to read plugins directory: {}", e))?;
+    let mut plugins: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .map(|path| {
+            let manifest = read_manifest(&path);
+            (path, manifest)
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(plugins)
+}
+
+fn grant(app: &AppHandle, id: &str) -> Result<PluginGrant, String> {
+    Ok(app
+        .state::<PluginsState>()
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .plugins
+        .get(id)
+        .cloned()
+        .unwrap_or_default())
+}
+
+// The manifest of an enabled plugin
+fn enabled_plugin(app: &AppHandle, id: &str) -> Result<(PathBuf, PluginManifest), String> {
+    if !valid_name(id) {
+        return Err(format!("Invalid plugin id: {}", id));
+    }
+    let dir = plugins_dir(app)?.join(id);
+    let manifest = read_manifest(&dir)?;
+    if !grant(app, id)?.enabled {
+        return Err(format!("Plugin {} is not enabled", id));
+    }
+    Ok((dir, manifest))
+}
+
+// The running process of an enabled plugin, started and initialized if needed
+async fn ensure_running(app: &AppHandle, id: &str) -> Result<Arc<PluginProcess>, String> {
+    let (dir, manifest) = enabled_plugin(app, id)?;
+    let state = app.state::<PluginsState>();
+    if let Some(process) = state
+        .running
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .get(id)
+        .filter(|process| process.is_running())
+    {
+        return Ok(process.clone());
+    }
+
+    // Only what the plugin asked for and the user granted
+    let granted: Vec<HostService> = grant(app, id)?
+        .host_services
+        .into_iter()
+        .filter(|service| manifest.host_services.contains(service))
+        .collect();
+    let process = PluginProcess::spawn(app, &manifest, &dir, granted.clone())?;
+    process
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "pluelyVersion": env!("CARGO_PKG_VERSION"),
+                "granted": granted,
+            }),
+            START_TIMEOUT,
+        )
+        .await
+        .inspect_err(|_| process.stop())?;
+
+    let mut running = state
+        .running
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    // Another call may have started it meanwhile
+    if let Some(existing) = running.get(id).filter(|process| process.is_running()) {
+        process.stop();
+        return Ok(existing.clone());
+    }
+    running.insert(id.to_string(), process.clone());
+    Ok(process)
+}
+
+pub fn stop(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let process = app
+        .state::<PluginsState>()
+        .running
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .remove(id);
+    if let Some(process) = &process {
+        process.stop();
+    }
+    Ok(process.is_some())
+}
+
+// Validates the section and stops plugins that were turned off or whose
+// grants changed; they start again with the new grants on next use
+pub fn apply_plugin_settings(app: &AppHandle, settings: &PluginSettings) -> Result<(), String> {
+    if let Some(id) = settings.plugins.keys().find(|id| !valid_name(id)) {
+        return Err(format!("Invalid plugin id: {}", id));
+    }
+    let state = app.state::<PluginsState>();
+    let previous = std::mem::replace(
+        &mut *state
+            .settings
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?,
+        settings.clone(),
+    );
+    let running: Vec<String> = state
+        .running
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .keys()
+        .cloned()
+        .collect();
+    for id in running {
+        if previous.plugins.get(&id) != settings.plugins.get(&id) {
+            stop(app, &id)?;
+        }
+    }
+    Ok(())
+}
+
+// Declarations for plugin tools named `<plugin>__<tool>`; None for names that
+// aren't plugin tools
+pub fn tool_spec(app: &AppHandle, name: &str) -> Option<Result<ToolSpec, String>> {
+    let (id, tool) = name.split_once(TOOL_SEPARATOR)?;
+    let spec = enabled_plugin(app, id).and_then(|(_, manifest)| {
+        manifest
+            .tools
+            .into_iter()
+            .find(|candidate| candidate.name == tool)
+            .map(|tool| ToolSpec {
+                name: name.to_string(),
+                description: tool.description,
+                parameters: tool.parameters,
+            })
+            .ok_or(format!("Unknown tool: {}", name))
+    });
+    Some(spec)
+}
+
+// Runs a `<plugin>__<tool>` call, returning its text and base64 images
+pub async fn run_tool(app: &AppHandle, call: &ToolCall) -> Result<(String, Vec<String>), String> {
+    let (id, tool) = call
+        .name
+        .split_once(TOOL_SEPARATOR)
+        .ok_or(format!("Unknown tool: {}", call.name))?;
+    let process = ensure_running(app, id).await?;
+    let result = process
+        .request(
+            "tools/call",
+            json!({ "name": tool, "arguments": call.arguments }),
+            CALL_TIMEOUT,
+        )
+        .await?;
+
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for block in result
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => texts.extend(block.get("text").and_then(Value::as_str)),
+            Some("image") => images.extend(block.get("data").and_then(Value::as_str)),
+            _ => {}
+        }
+    }
+    let text = texts.join("\n");
+    if result.get("isError").and_then(Value::as_bool) == Some(true) {
+        return Err(text);
+    }
+    Ok((text, images.into_iter().map(String::from).collect()))
+}
@@ -0,0 +1,276 @@
+// Pluely power awareness. A watcher reads whether the machine runs on battery
+// or AC and whether the OS low-power mode is on, emits `power-status-changed`,
+// and can switch to a lighter battery profile: smaller screenshots and paused
+// clipboard and focus watchers.
+use image::imageops::FilterType;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, warn};
+
+// Battery level changes slowly; this is often enough to notice unplugging
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+// Process-wide because capture code runs without an app handle
+static CAPTURE_MAX_PX: AtomicU32 = AtomicU32::new(0);
+static BACKGROUND_PAUSED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PowerSettings {
+    // Uses the battery profile below on battery or in low-power mode
+    pub degrade_on_battery: bool,
+    // Longest edge of screenshots in the battery profile; 0 keeps full size
+    pub battery_capture_max_px: u32,
+    // Stops the clipboard and focus watchers in the battery profile
+    pub pause_background_tasks: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            degrade_on_battery: false,
+            battery_capture_max_px: 1_920,
+            pause_background_tasks: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    pub source: PowerSource,
+    // 0-100 across all batteries; None without one
+    pub battery_percent: Option<f32>,
+    pub charging: bool,
+    pub low_power_mode: bool,
+    // Whether the battery profile is in effect
+    pub degraded: bool,
+}
+
+#[derive(Default)]
+pub struct PowerState {
+    settings: Mutex<PowerSettings>,
+    // Stop flag of the running watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    status: Mutex<Option<PowerStatus>>,
+}
+
+fn read_batteries() -> Result<(PowerSource, Option<f32>, bool), String> {
+    use starship_battery::State;
+
+    let manager =
+        starship_battery::Manager::new().map_err(|e| format!("Failed to read battery: {}", e))?;
+    let batteries: Vec<_> = manager
+        .batteries()
+        .map_err(|e| format!("Failed to read battery: {}", e))?
+        .filter_map(Result::ok)
+        .collect();
+    // Desktops have no battery and are always on AC
+    if batteries.is_empty() {
+        return Ok((PowerSource::Ac, None, false));
+    }
+    let percent = batteries
+        .iter()
+        .map(|battery| battery.state_of_charge().value)
+        .sum::<f32>()
+        / batteries.len() as f32
+        * 100.0;
+    let discharging = batteries
+        .iter()
+        .any(|battery| battery.state() == State::Discharging);
+    let charging = batteries
+        .iter()
+        .any(|battery| battery.state() == State::Charging);
+    let source = if discharging {
+        PowerSource::Battery
+    } else {
+        PowerSource::Ac
+    };
+    Ok((source, Some(percent.round()), charging))
+}
+
+#[cfg(target_os = "windows")]
+fn low_power_mode() -> bool {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SystemStatusFlag is 1 while battery saver is on
+    unsafe { GetSystemPowerStatus(&mut status) }.as_bool() && status.SystemStatusFlag == 1
+}
+
+#[cfg(target_os = "macos")]
+fn low_power_mode() -> bool {
+    std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+                let mut parts = line.split_whitespace();
+                parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+            })
+        })
+        .unwrap_or(false)
+}
+
+// The ACPI platform profile, which power-profiles-daemon switches
+#[cfg(target_os = "linux")]
+fn low_power_mode() -> bool {
+    std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+        .map(|profile| profile.trim() == "low-power")
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn low_power_mode() -> bool {
+    false
+}
+
+fn read_status(settings: &PowerSettings) -> PowerStatus {
+    let (source, battery_percent, charging) = read_batteries().unwrap_or_else(|e| {
+        debug!("{}", e);
+        (PowerSource::Unknown, None, false)
+    });
+    let low_power_mode = low_power_mode();
+    PowerStatus {
+        source,
+        battery_percent,
+        charging,
+        low_power_mode,
+        degraded: settings.degrade_on_battery && (source == PowerSource::Battery || low_power_mode),
+    }
+}
+
+// Puts the battery profile in effect, or lifts it
+fn set_degraded(settings: &PowerSettings, degraded: bool) {
+    let max_px = if degraded {
+        settings.battery_capture_max_px
+    } else {
+        0
+    };
+    CAPTURE_MAX_PX.store(max_px, Ordering::SeqCst);
+    BACKGROUND_PAUSED.store(
+        degraded && settings.pause_background_tasks,
+        Ordering::SeqCst,
+    );
+}
+
+// Records `settings`' view of the power status and reports changes. Battery
+// percentage alone doesn't count, so the event fires on real transitions.
+fn update(app: &AppHandle, settings: &PowerSettings) -> Result<PowerStatus, String> {
+    let status = read_status(settings);
+    set_degraded(settings, status.degraded);
+    let state = app.state::<PowerState>();
+    let mut last = state
+        .status
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let changed = last.as_ref().is_none_or(|last| {
+        last.source != status.source
+            || last.charging != status.charging
+            || last.low_power_mode != status.low_power_mode
+            || last.degraded != status.degraded
+    });
+    *last = Some(status.clone());
+    if changed {
+        if let Err(e) = app.emit(
+            "power-status-changed",
+            crate::events::PowerStatusChanged(status.clone()),
+        ) {
+            warn!("Failed to emit power status: {}", e);
+        }
+    }
+    Ok(status)
+}
+
+fn watch(app: AppHandle, stop: Arc<AtomicBool>) {
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(settings) = app
+            .state::<PowerState>()
+            .settings
+            .lock()
+            .map(|settings| settings.clone())
+        else {
+            continue;
+        };
+        if let Err(e) = update(&app, &settings) {
+            warn!("{}", e);
+        }
+    }
+}
+
+// Applies `settings` and makes sure the watcher runs; the status is reported
+// whether or not the battery profile is enabled
+pub fn apply_power_settings(app: &AppHandle, settings: &PowerSettings) -> Result<(), String> {
+    if settings.battery_capture_max_px != 0 && settings.battery_capture_max_px < 480 {
+        return Err("Invalid battery capture size: must be 0 or at least 480 px".to_string());
+    }
+    let state = app.state::<PowerState>();
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = settings.clone();
+    update(app, settings)?;
+
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if watcher.is_none() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, flag));
+        *watcher = Some(stop);
+    }
+    Ok(())
+}
+
+/// Whether background watchers should skip their work to save power
+pub fn background_paused() -> bool {
+    BACKGROUND_PAUSED.load(Ordering::SeqCst)
+}
+
+/// Scales a capture down to the battery profile's size; unchanged otherwise
+pub fn fit_capture(image: RgbaImage) -> RgbaImage {
+    let max_px = CAPTURE_MAX_PX.load(Ordering::SeqCst);
+    let (width, height) = image.dimensions();
+    if max_px == 0 || width.max(height) <= max_px {
+        return image;
+    }
+    let scale = max_px as f64 / width.max(height) as f64;
+    let (width, height) = (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    );
+    image::imageops::resize(&image, width, height, FilterType::Triangle)
+}
+
+/// Tauri command returning the current power source, battery level and
+/// low-power mode
+#[tauri::command]
+#[specta::specta]
+pub fn get_power_status(app: AppHandle) -> Result<PowerStatus, String> {
+    let settings = app
+        .state::<PowerState>()
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone();
+    update(&app, &settings)
+}
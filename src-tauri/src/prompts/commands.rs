@@ -0,0 +1,160 @@
+// Pluely prompt profile commands: manage profiles and pick one per conversation.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::{apply_profile, load_profiles, save_profiles, AppliedProfile, PromptProfile};
+
+#[derive(Default)]
+pub struct PromptsState {
+    // Serializes read-modify-write of the profiles file
+    file: Mutex<()>,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+pub fn find_profile(app: &AppHandle, id: &str) -> Result<PromptProfile, String> {
+    load_profiles(app)?
+        .into_iter()
+        .find(|profile| profile.id == id)
+        .ok_or(format!("Prompt profile {} not found", id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_prompt_profiles(app: AppHandle) -> Result<Vec<PromptProfile>, String> {
+    load_profiles(&app)
+}
+
+// Creates the profile when it has no id yet, otherwise replaces it
+#[tauri::command]
+#[specta::specta]
+pub fn save_prompt_profile(
+    app: AppHandle,
+    profile: PromptProfile,
+) -> Result<PromptProfile, String> {
+    let mut profile = profile;
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if let Some(temperature) = profile.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err("Invalid temperature: must be 0.0-2.0".to_string());
+        }
+    }
+    if let Some(variable) = profile.variables.iter().find(|v| v.name.trim().is_empty()) {
+        return Err(format!("Invalid variable name: {:?}", variable.name));
+    }
+
+    let state = app.state::<PromptsState>();
+    let _lock = state
+        .file
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut profiles = load_profiles(&app)?;
+
+    if profiles
+        .iter()
+        .any(|p| p.id != profile.id && p.name.eq_ignore_ascii_case(&profile.name))
+    {
+        return Err(format!("A profile named {} already exists", profile.name));
+    }
+
+    let now = now_ms();
+    profile.updated_at = now;
+    match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) if !profile.id.is_empty() => {
+            profile.created_at = existing.created_at;
+            *existing = profile.clone();
+        }
+        _ => {
+            profile.id = uuid::Uuid::new_v4().to_string();
+            profile.created_at = now;
+            profiles.push(profile.clone());
+        }
+    }
+
+    save_profiles(&app, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_prompt_profile(app: AppHandle, id: String) -> Result<bool, String> {
+    let state = app.state::<PromptsState>();
+    let _lock = state
+        .file
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut profiles = load_profiles(&app)?;
+    let before = profiles.len();
+    profiles.retain(|p| p.id != id);
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    save_profiles(&app, &profiles)?;
+    Ok(true)
+}
+
+// Restores profiles from a backup. Each replaces the profile with the same id
+// or name; the rest are added. Returns how many were imported.
+pub fn import_profiles(app: &AppHandle, imported: Vec<PromptProfile>) -> Result<usize, String> {
+    let state = app.state::<PromptsState>();
+    let _lock = state
+        .file
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    let mut profiles = load_profiles(app)?;
+
+    let count = imported.len();
+    for mut profile in imported {
+        if profile.id.is_empty() {
+            profile.id = uuid::Uuid::new_v4().to_string();
+        }
+        profiles.retain(|p| p.id != profile.id && !p.name.eq_ignore_ascii_case(&profile.name));
+        profiles.push(profile);
+    }
+
+    save_profiles(app, &profiles)?;
+    Ok(count)
+}
+
+// Resolves a profile's variables so the caller can preview or use the prompt
+#[tauri::command]
+#[specta::specta]
+pub fn apply_prompt_profile(
+    app: AppHandle,
+    id: String,
+    values: Option<HashMap<String, String>>,
+) -> Result<AppliedProfile, String> {
+    let profile = find_profile(&app, &id)?;
+    apply_profile(&profile, &values.unwrap_or_default())
+}
+
+// Picks the profile new messages in a conversation use; None clears it
+#[tauri::command]
+#[specta::specta]
+pub async fn set_conversation_profile(
+    app: AppHandle,
+    conversation_id: String,
+    profile_id: Option<String>,
+) -> Result<bool, String> {
+    if let Some(profile_id) = &profile_id {
+        find_profile(&app, profile_id)?;
+    }
+
+    let pool = crate::storage::pool(&app).await?;
+    let result = sqlx::query("UPDATE conversations SET profile_id = ? WHERE id = ?")
+        .bind(&profile_id)
+        .bind(&conversation_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to update conversation: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,115 @@
+// Pluely prompt profiles: named system prompts with variables and their own
+// model settings, e.g. "Code reviewer" or "Meeting summarizer".
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::llm::LlmProviderKind;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+const PROFILES_FILE: &str = "prompt_profiles.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    // Used when the caller gives no value; variables without one are required
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptProfile {
+    // Empty when creating a profile
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    // May reference variables as `{{name}}`
+    pub system_prompt: String,
+    #[serde(default)]
+    pub variables: Vec<ProfileVariable>,
+    #[serde(default)]
+    pub provider: Option<LlmProviderKind>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+// A profile ready to use: variables filled in
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedProfile {
+    pub id: String,
+    pub name: String,
+    pub system_prompt: String,
+    pub provider: Option<LlmProviderKind>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+}
+
+fn profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = crate::workspaces::data_dir(app)?;
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(app_data_dir.join(PROFILES_FILE))
+}
+
+pub fn load_profiles(app: &AppHandle) -> Result<Vec<PromptProfile>, String> {
+    let path = profiles_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read profiles file: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse profiles file: {}", e))
+}
+
+pub fn save_profiles(app: &AppHandle, profiles: &[PromptProfile]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::write(profiles_path(app)?, content)
+        .map_err(|e| format!("Failed to write profiles file: {}", e))
+}
+
+// Fills `{{name}}` placeholders from `values`, falling back to each
+// variable's default.
+pub fn apply_profile(
+    profile: &PromptProfile,
+    values: &HashMap<String, String>,
+) -> Result<AppliedProfile, String> {
+    let mut system_prompt = profile.system_prompt.clone();
+    for variable in &profile.variables {
+        let value = values
+            .get(&variable.name)
+            .or(variable.default.as_ref())
+            .ok_or(format!(
+                "Missing value for variable {} in profile {}",
+                variable.name, profile.name
+            ))?;
+        system_prompt = system_prompt.replace(&format!("{{{{{}}}}}", variable.name), value);
+    }
+
+    Ok(AppliedProfile {
+        id: profile.id.clone(),
+        name: profile.name.clone(),
+        system_prompt,
+        provider: profile.provider,
+        model: profile.model.clone(),
+        temperature: profile.temperature,
+    })
+}
@@ -0,0 +1,316 @@
+// Pluely realtime commands: configure, start and stop a voice session.
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use specta::Type;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tokio::sync::{mpsc, Notify};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+use super::player::AudioPlayer;
+use super::{build_provider, RealtimeConfig, RealtimeProvider, ServerEvent, SpeakerRole};
+use crate::control::{BridgeEvent, TranscriptSource};
+use crate::events::RealtimeError;
+use crate::speaker::mic::MicInput;
+use crate::speaker::resample::resample_linear;
+
+// Mic audio goes up every 100ms
+const CHUNK_MS: u32 = 100;
+
+#[derive(Default)]
+pub struct RealtimeState {
+    config: Mutex<RealtimeConfig>,
+    session: Mutex<Option<RealtimeSession>>,
+}
+
+struct RealtimeSession {
+    task: tauri::async_runtime::JoinHandle<()>,
+    interrupt: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeStatus {
+    Connecting,
+    Listening,
+    Speaking,
+    Interrupted,
+    Closed,
+}
+
+// Emitted as `realtime-status`
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RealtimeStatusEvent {
+    pub status: RealtimeStatus,
+    pub error: Option<String>,
+}
+
+// Named by hand, as `RealtimeStatus` is taken by the status itself
+impl Event for RealtimeStatusEvent {
+    const NAME: &'static str = "realtime-status";
+}
+
+// Emitted as `realtime-transcript` for both sides of the conversation
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RealtimeTranscript {
+    pub role: SpeakerRole,
+    pub text: String,
+    pub is_final: bool,
+}
+
+fn current_config(app: &AppHandle) -> Result<RealtimeConfig, String> {
+    let state = app.state::<RealtimeState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read realtime config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+fn emit_status(app: &AppHandle, status: RealtimeStatus, error: Option<String>) {
+    let _ = app.emit("realtime-status", RealtimeStatusEvent { status, error });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_realtime_config(app: AppHandle) -> Result<RealtimeConfig, String> {
+    current_config(&app)
+}
+
+// Takes effect from the next session
+#[tauri::command]
+#[specta::specta]
+pub fn update_realtime_config(app: AppHandle, config: RealtimeConfig) -> Result<(), String> {
+    let state = app.state::<RealtimeState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update realtime config: {}", e))? = config;
+    Ok(())
+}
+
+// Connects to the configured voice model and starts talking. Progress arrives
+// as `realtime-status` and `realtime-transcript` events.
+#[tauri::command]
+#[specta::specta]
+pub fn start_realtime_session(app: AppHandle) -> Result<(), String> {
+    crate::network::ensure_online("Realtime voice")?;
+    let state = app.state::<RealtimeState>();
+    let mut session = state
+        .session
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if session.is_some() {
+        return Err("A realtime session is already running".to_string());
+    }
+
+    let config = current_config(&app)?;
+    let provider = build_provider(&config)?;
+    let interrupt = Arc::new(Notify::new());
+
+    let app_clone = app.clone();
+    let interrupt_clone = interrupt.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        emit_status(&app_clone, RealtimeStatus::Connecting, None);
+        let result = run_session(&app_clone, provider, config.input_device, interrupt_clone).await;
+        if let Err(e) = &result {
+            warn!("Realtime session ended with error: {}", e);
+        }
+        emit_status(&app_clone, RealtimeStatus::Closed, result.err());
+
+        let state = app_clone.state::<RealtimeState>();
+        if let Ok(mut session) = state.session.lock() {
+            *session = None;
+        };
+    });
+
+    *session = Some(RealtimeSession { task, interrupt });
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_realtime_session(app: AppHandle) -> Result<bool, String> {
+    let state = app.state::<RealtimeState>();
+    let session = state
+        .session
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .take();
+    let Some(session) = session else {
+        return Ok(false);
+    };
+    // Aborting drops the socket, microphone and player
+    session.task.abort();
+    emit_status(&app, RealtimeStatus::Closed, None);
+    Ok(true)
+}
+
+// Cuts the model off as if the user had started talking
+#[tauri::command]
+#[specta::specta]
+pub fn interrupt_realtime_response(app: AppHandle) -> Result<bool, String> {
+    let state = app.state::<RealtimeState>();
+    let session = state
+        .session
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    match session.as_ref() {
+        Some(session) => {
+            session.interrupt.notify_one();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+async fn run_session(
+    app: &AppHandle,
+    provider: Box<dyn RealtimeProvider>,
+    input_device: Option<String>,
+    interrupt: Arc<Notify>,
+) -> Result<(), String> {
+    let request = provider.request()?;
+    // Audio is streamed, so its size isn't known up front
+    if let Ok(url) = reqwest::Url::parse(&request.uri().to_string()) {
+        crate::audit::record_egress("realtime voice", &url, None, &[]);
+    }
+    let socket = crate::network::connect_websocket(request)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", provider.name(), e))?;
+    let (mut sink, mut source) = socket.split();
+    for message in provider.setup_messages() {
+        sink.send(Message::Text(message))
+            .await
+            .map_err(|e| format!("Failed to start session: {}", e))?;
+    }
+
+    let player = AudioPlayer::start()?;
+    let mic = MicInput::new(input_device)
+        .stream()
+        .map_err(|e| format!("Failed to open microphone: {}", e))?;
+
+    // Mic -> 100ms PCM chunks at the rate the provider expects
+    let (audio_tx, mut audio_rx) = mpsc::channel::<Vec<i16>>(32);
+    let target_rate = provider.input_sample_rate();
+    let capture = tokio::spawn(async move {
+        let mut mic = mic;
+        let mic_rate = mic.sample_rate();
+        let chunk_len = (mic_rate * CHUNK_MS / 1000) as usize;
+        let mut chunk = Vec::with_capacity(chunk_len);
+        while let Some(sample) = mic.next().await {
+            chunk.push(sample);
+            if chunk.len() >= chunk_len {
+                let pcm = resample_linear(&chunk, mic_rate, target_rate)
+                    .iter()
+                    .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                    .collect();
+                chunk.clear();
+                if audio_tx.send(pcm).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    emit_status(app, RealtimeStatus::Listening, None);
+
+    let output_rate = provider.output_sample_rate();
+    // Assistant reply currently playing, so a barge-in can trim it
+    let mut current_item: Option<String> = None;
+    let mut speaking = false;
+
+    let result = async {
+        loop {
+            let mut barge_in = false;
+            tokio::select! {
+                chunk = audio_rx.recv() => {
+                    let chunk = chunk.ok_or("Microphone stopped".to_string())?;
+                    sink.send(Message::Text(provider.audio_message(&chunk)))
+                        .await
+                        .map_err(|e| format!("Failed to send audio: {}", e))?;
+                }
+                message = source.next() => {
+                    let json: serde_json::Value = match message {
+                        Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                            Ok(json) => json,
+                            Err(_) => continue,
+                        },
+                        // Gemini sends JSON in binary frames
+                        Some(Ok(Message::Binary(bytes))) => match serde_json::from_slice(&bytes) {
+                            Ok(json) => json,
+                            Err(_) => continue,
+                        },
+                        Some(Ok(Message::Close(frame))) => {
+                            return match frame.filter(|f| !f.reason.is_empty()) {
+                                Some(frame) => Err(frame.reason.to_string()),
+                                None => Ok(()),
+                            };
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(format!("Realtime connection error: {}", e)),
+                        None => return Ok(()),
+                    };
+
+                    for event in provider.parse(&json) {
+                        match event {
+                            ServerEvent::Audio { samples, item_id } => {
+                                if item_id.is_some() {
+                                    current_item = item_id;
+                                }
+                                player.push_pcm16(&samples, output_rate);
+                                if !speaking {
+                                    speaking = true;
+                                    emit_status(app, RealtimeStatus::Speaking, None);
+                                }
+                            }
+                            ServerEvent::Transcript { role, text, is_final } => {
+                                crate::control::publish(BridgeEvent::Transcription {
+                                    source: TranscriptSource::Realtime,
+                                    role: Some(role),
+                                    text: text.clone(),
+                                    is_final,
+                                });
+                                let _ = app.emit(
+                                    "realtime-transcript",
+                                    RealtimeTranscript { role, text, is_final },
+                                );
+                            }
+                            ServerEvent::SpeechStarted => barge_in = true,
+                            ServerEvent::ResponseDone => {
+                                if speaking {
+                                    speaking = false;
+                                    emit_status(app, RealtimeStatus::Listening, None);
+                                }
+                            }
+                            ServerEvent::Error(e) => {
+                                let _ = app.emit("realtime-error", RealtimeError(e));
+                            }
+                        }
+                    }
+                }
+                _ = interrupt.notified() => barge_in = true,
+            }
+
+            // Stop talking the moment the user does
+            if barge_in && player.is_playing() {
+                let played_ms = player.played_ms();
+                player.clear();
+                for message in provider.interrupt_messages(current_item.as_deref(), played_ms) {
+                    sink.send(Message::Text(message))
+                        .await
+                        .map_err(|e| format!("Failed to interrupt response: {}", e))?;
+                }
+                current_item = None;
+                speaking = false;
+                emit_status(app, RealtimeStatus::Interrupted, None);
+            }
+        }
+    }
+    .await;
+
+    capture.abort();
+    result
+}
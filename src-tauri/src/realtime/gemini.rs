@@ -0,0 +1,135 @@
+// Gemini Live API (BidiGenerateContent) over WebSocket
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+
+use super::{
+    base64_to_pcm16, pcm16_to_base64, RealtimeConfig, RealtimeProvider, ServerEvent, SpeakerRole,
+};
+
+const LIVE_URL: &str = "wss://generativelanguage.googleapis.com/ws/google.ai.generativelanguage.v1beta.GenerativeService.BidiGenerateContent";
+
+pub struct GeminiLive {
+    config: RealtimeConfig,
+    api_key: String,
+}
+
+impl GeminiLive {
+    pub fn new(config: &RealtimeConfig, api_key: String) -> Self {
+        Self {
+            config: config.clone(),
+            api_key,
+        }
+    }
+
+    fn model(&self) -> String {
+        let model = self
+            .config
+            .model
+            .as_deref()
+            .unwrap_or("gemini-2.0-flash-live-001");
+        if model.starts_with("models/") {
+            model.to_string()
+        } else {
+            format!("models/{}", model)
+        }
+    }
+}
+
+impl RealtimeProvider for GeminiLive {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn input_sample_rate(&self) -> u32 {
+        16_000
+    }
+
+    fn request(&self) -> Result<Request, String> {
+        // Live only takes the key as a query parameter
+        format!("{}?key={}", LIVE_URL, self.api_key)
+            .into_client_request()
+            .map_err(|e| format!("Invalid Gemini Live URL: {}", e))
+    }
+
+    fn setup_messages(&self) -> Vec<String> {
+        let mut setup = json!({
+            "model": self.model(),
+            "generationConfig": {
+                "responseModalities": ["AUDIO"],
+                "speechConfig": {
+                    "voiceConfig": {
+                        "prebuiltVoiceConfig": {
+                            "voiceName": self.config.voice.as_deref().unwrap_or("Puck")
+                        }
+                    }
+                }
+            },
+            "inputAudioTranscription": {},
+            "outputAudioTranscription": {},
+        });
+        if let Some(instructions) = &self.config.instructions {
+            setup["systemInstruction"] = json!({ "parts": [{ "text": instructions }] });
+        }
+        vec![json!({ "setup": setup }).to_string()]
+    }
+
+    fn audio_message(&self, samples: &[i16]) -> String {
+        json!({
+            "realtimeInput": {
+                "audio": {
+                    "data": pcm16_to_base64(samples),
+                    "mimeType": format!("audio/pcm;rate={}", self.input_sample_rate()),
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn parse(&self, message: &Value) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        if let Some(error) = message.pointer("/error/message").and_then(|m| m.as_str()) {
+            events.push(ServerEvent::Error(error.to_string()));
+        }
+        let Some(content) = message.get("serverContent") else {
+            return events;
+        };
+
+        // Gemini detects barge-in itself and says so
+        if content.get("interrupted").and_then(|v| v.as_bool()) == Some(true) {
+            events.push(ServerEvent::SpeechStarted);
+        }
+        if let Some(parts) = content
+            .pointer("/modelTurn/parts")
+            .and_then(|p| p.as_array())
+        {
+            for part in parts {
+                if let Some(data) = part.pointer("/inlineData/data").and_then(|d| d.as_str()) {
+                    events.push(ServerEvent::Audio {
+                        samples: base64_to_pcm16(data),
+                        item_id: None,
+                    });
+                }
+            }
+        }
+        for (key, role) in [
+            ("inputTranscription", SpeakerRole::User),
+            ("outputTranscription", SpeakerRole::Assistant),
+        ] {
+            if let Some(text) = content
+                .pointer(&format!("/{}/text", key))
+                .and_then(|t| t.as_str())
+            {
+                events.push(ServerEvent::Transcript {
+                    role,
+                    text: text.to_string(),
+                    is_final: false,
+                });
+            }
+        }
+        if content.get("turnComplete").and_then(|v| v.as_bool()) == Some(true) {
+            events.push(ServerEvent::ResponseDone);
+        }
+        events
+    }
+}
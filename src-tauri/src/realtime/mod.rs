@@ -0,0 +1,122 @@
+// Pluely realtime voice sessions: a WebSocket to a speech-to-speech model
+// (OpenAI Realtime or Gemini Live). Mic audio streams up, the model's voice
+// plays back, and talking over it interrupts the answer (barge-in).
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+
+mod commands;
+mod gemini;
+mod openai;
+pub mod player;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeProviderKind {
+    #[default]
+    OpenAi,
+    Gemini,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct RealtimeConfig {
+    pub provider: RealtimeProviderKind,
+    // Unset fields use each provider's default
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub voice: Option<String>,
+    #[serde(default)]
+    pub instructions: Option<String>,
+    // Microphone to listen on; None uses the default input
+    #[serde(default)]
+    pub input_device: Option<String>,
+}
+
+// What the session loop needs to know from a server message
+#[derive(Debug, Clone)]
+pub enum ServerEvent {
+    // Mono 16-bit PCM at the provider's output rate
+    Audio {
+        samples: Vec<i16>,
+        item_id: Option<String>,
+    },
+    Transcript {
+        role: SpeakerRole,
+        text: String,
+        is_final: bool,
+    },
+    // The user started talking over the model
+    SpeechStarted,
+    ResponseDone,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakerRole {
+    User,
+    Assistant,
+}
+
+pub trait RealtimeProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Rates the API expects for mic audio and sends back for speech
+    fn input_sample_rate(&self) -> u32;
+
+    fn output_sample_rate(&self) -> u32 {
+        24_000
+    }
+
+    fn request(&self) -> Result<Request, String>;
+
+    // Sent once after connecting
+    fn setup_messages(&self) -> Vec<String>;
+
+    fn audio_message(&self, samples: &[i16]) -> String;
+
+    fn parse(&self, message: &serde_json::Value) -> Vec<ServerEvent>;
+
+    // Sent on barge-in so the model stops and forgets what wasn't heard
+    fn interrupt_messages(&self, _item_id: Option<&str>, _played_ms: u64) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+pub fn build_provider(config: &RealtimeConfig) -> Result<Box<dyn RealtimeProvider>, String> {
+    match config.provider {
+        RealtimeProviderKind::OpenAi => {
+            let api_key = crate::secrets::get_api_key("openai")?
+                .ok_or("OpenAI API key is not configured".to_string())?;
+            Ok(Box::new(openai::OpenAiRealtime::new(config, api_key)))
+        }
+        RealtimeProviderKind::Gemini => {
+            let api_key = crate::secrets::get_api_key("gemini")?
+                .ok_or("Gemini API key is not configured".to_string())?;
+            Ok(Box::new(gemini::GeminiLive::new(config, api_key)))
+        }
+    }
+}
+
+fn pcm16_to_base64(samples: &[i16]) -> String {
+    use base64::{engine::general_purpose, Engine as _};
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    general_purpose::STANDARD.encode(bytes)
+}
+
+fn base64_to_pcm16(data: &str) -> Vec<i16> {
+    use base64::{engine::general_purpose, Engine as _};
+    general_purpose::STANDARD
+        .decode(data)
+        .map(|bytes| {
+            bytes
+                .chunks_exact(2)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect()
+        })
+        .unwrap_or_default()
+}
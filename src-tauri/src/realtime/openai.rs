@@ -0,0 +1,148 @@
+// OpenAI Realtime API over WebSocket
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+
+use super::{
+    base64_to_pcm16, pcm16_to_base64, RealtimeConfig, RealtimeProvider, ServerEvent, SpeakerRole,
+};
+
+const REALTIME_URL: &str = "wss://api.openai.com/v1/realtime";
+
+pub struct OpenAiRealtime {
+    config: RealtimeConfig,
+    api_key: String,
+}
+
+impl OpenAiRealtime {
+    pub fn new(config: &RealtimeConfig, api_key: String) -> Self {
+        Self {
+            config: config.clone(),
+            api_key,
+        }
+    }
+
+    fn model(&self) -> &str {
+        self.config
+            .model
+            .as_deref()
+            .unwrap_or("gpt-4o-realtime-preview")
+    }
+}
+
+impl RealtimeProvider for OpenAiRealtime {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn input_sample_rate(&self) -> u32 {
+        24_000
+    }
+
+    fn request(&self) -> Result<Request, String> {
+        let mut request = format!("{}?model={}", REALTIME_URL, self.model())
+            .into_client_request()
+            .map_err(|e| format!("Invalid realtime URL: {}", e))?;
+        let headers = request.headers_mut();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+                .map_err(|e| format!("Invalid OpenAI API key: {}", e))?,
+        );
+        headers.insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+        Ok(request)
+    }
+
+    fn setup_messages(&self) -> Vec<String> {
+        let mut session = json!({
+            "modalities": ["audio", "text"],
+            "voice": self.config.voice.as_deref().unwrap_or("alloy"),
+            "input_audio_format": "pcm16",
+            "output_audio_format": "pcm16",
+            "input_audio_transcription": { "model": "whisper-1" },
+            // The server decides when the user has finished a turn
+            "turn_detection": { "type": "server_vad" },
+        });
+        if let Some(instructions) = &self.config.instructions {
+            session["instructions"] = json!(instructions);
+        }
+        vec![json!({ "type": "session.update", "session": session }).to_string()]
+    }
+
+    fn audio_message(&self, samples: &[i16]) -> String {
+        json!({ "type": "input_audio_buffer.append", "audio": pcm16_to_base64(samples) })
+            .to_string()
+    }
+
+    fn parse(&self, message: &Value) -> Vec<ServerEvent> {
+        let text = |key: &str| {
+            message
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let event = match message.get("type").and_then(|t| t.as_str()) {
+            Some("response.audio.delta") => ServerEvent::Audio {
+                samples: base64_to_pcm16(&text("delta")),
+                item_id: message
+                    .get("item_id")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+            },
+            Some("response.audio_transcript.delta") => ServerEvent::Transcript {
+                role: SpeakerRole::Assistant,
+                text: text("delta"),
+                is_final: false,
+            },
+            Some("response.audio_transcript.done") => ServerEvent::Transcript {
+                role: SpeakerRole::Assistant,
+                text: text("transcript"),
+                is_final: true,
+            },
+            Some("conversation.item.input_audio_transcription.completed") => {
+                ServerEvent::Transcript {
+                    role: SpeakerRole::User,
+                    text: text("transcript"),
+                    is_final: true,
+                }
+            }
+            Some("input_audio_buffer.speech_started") => ServerEvent::SpeechStarted,
+            Some("response.done") => ServerEvent::ResponseDone,
+            // Barge-in cancels replies that already finished generating
+            Some("error")
+                if message.pointer("/error/code").and_then(|c| c.as_str())
+                    == Some("response_cancel_not_active") =>
+            {
+                return Vec::new()
+            }
+            Some("error") => ServerEvent::Error(
+                message
+                    .pointer("/error/message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown realtime error")
+                    .to_string(),
+            ),
+            _ => return Vec::new(),
+        };
+        vec![event]
+    }
+
+    fn interrupt_messages(&self, item_id: Option<&str>, played_ms: u64) -> Vec<String> {
+        let mut messages = vec![json!({ "type": "response.cancel" }).to_string()];
+        // Trims the reply to what was actually heard
+        if let Some(item_id) = item_id {
+            messages.push(
+                json!({
+                    "type": "conversation.item.truncate",
+                    "item_id": item_id,
+                    "content_index": 0,
+                    "audio_end_ms": played_ms,
+                })
+                .to_string(),
+            );
+        }
+        messages
+    }
+}
@@ -0,0 +1,182 @@
+// Plays model speech on the default output device. Barge-in clears the queue
+// so the assistant stops mid-sentence.
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::SampleFormat;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::error;
+
+use crate::speaker::resample::resample_linear;
+
+pub struct AudioPlayer {
+    queue: Arc<Mutex<VecDeque<f32>>>,
+    // Samples played since the last `clear`, at the device rate
+    played: Arc<AtomicU64>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+    sample_rate: u32,
+}
+
+impl AudioPlayer {
+    pub fn start() -> Result<Self, String> {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        let played = Arc::new(AtomicU64::new(0));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (init_tx, init_rx) = std::sync::mpsc::channel();
+
+        let (queue_clone, played_clone, shutdown_clone) =
+            (queue.clone(), played.clone(), shutdown.clone());
+        // cpal streams are not Send on every backend, so playback gets its own thread
+        let thread = thread::spawn(move || {
+            let stream = match open_output_stream(queue_clone, played_clone) {
+                Ok((stream, sample_rate)) => {
+                    let _ = init_tx.send(Ok(sample_rate));
+                    stream
+                }
+                Err(e) => {
+                    let _ = init_tx.send(Err(e));
+                    return;
+                }
+            };
+            while !shutdown_clone.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(50));
+            }
+            drop(stream);
+        });
+
+        let sample_rate = match init_rx.recv() {
+            Ok(Ok(sample_rate)) => sample_rate,
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                return Err(e);
+            }
+            Err(e) => {
+                let _ = thread.join();
+                return Err(format!("Failed to start audio output: {}", e));
+            }
+        };
+
+        Ok(Self {
+            queue,
+            played,
+            shutdown,
+            thread: Some(thread),
+            sample_rate,
+        })
+    }
+
+    // Queues mono 16-bit PCM recorded at `rate`
+    pub fn push_pcm16(&self, samples: &[i16], rate: u32) {
+        let samples: Vec<f32> = samples
+            .iter()
+            .map(|&s| s as f32 / i16::MAX as f32)
+            .collect();
+        let resampled = resample_linear(&samples, rate, self.sample_rate);
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.extend(resampled);
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.queue.lock().map(|q| !q.is_empty()).unwrap_or(false)
+    }
+
+    // Milliseconds of audio heard since the last `clear`
+    pub fn played_ms(&self) -> u64 {
+        self.played.load(Ordering::SeqCst) * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    // Drops everything not yet played
+    pub fn clear(&self) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.clear();
+        }
+        self.played.store(0, Ordering::SeqCst);
+    }
+}
+
+impl Drop for AudioPlayer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn open_output_stream(
+    queue: Arc<Mutex<VecDeque<f32>>>,
+    played: Arc<AtomicU64>,
+) -> Result<(cpal::Stream, u32), String> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or("No audio output device available".to_string())?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| format!("Failed to read output config: {}", e))?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels().max(1) as usize;
+    let sample_format = config.sample_format();
+    let stream_config: cpal::StreamConfig = config.into();
+
+    // Mono samples copied to every channel; silence when the queue runs dry
+    let next_frame = move |frames: usize| -> Vec<f32> {
+        let mut queue = match queue.lock() {
+            Ok(queue) => queue,
+            Err(_) => return vec![0.0; frames],
+        };
+        let available = frames.min(queue.len());
+        played.fetch_add(available as u64, Ordering::SeqCst);
+        let mut out: Vec<f32> = queue.drain(..available).collect();
+        out.resize(frames, 0.0);
+        out
+    };
+    let err_fn = |e: cpal::StreamError| error!("Audio output error: {}", e);
+
+    let stream = match sample_format {
+        SampleFormat::F32 => device.build_output_stream(
+            &stream_config,
+            move |data: &mut [f32], _| {
+                let frames = next_frame(data.len() / channels);
+                for (frame, sample) in data.chunks_mut(channels).zip(frames) {
+                    frame.fill(sample);
+                }
+            },
+            err_fn,
+            None,
+        ),
+        SampleFormat::I16 => device.build_output_stream(
+            &stream_config,
+            move |data: &mut [i16], _| {
+                let frames = next_frame(data.len() / channels);
+                for (frame, sample) in data.chunks_mut(channels).zip(frames) {
+                    frame.fill((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16);
+                }
+            },
+            err_fn,
+            None,
+        ),
+        SampleFormat::U16 => device.build_output_stream(
+            &stream_config,
+            move |data: &mut [u16], _| {
+                let frames = next_frame(data.len() / channels);
+                for (frame, sample) in data.chunks_mut(channels).zip(frames) {
+                    let value = (sample.clamp(-1.0, 1.0) + 1.0) * (u16::MAX as f32 / 2.0);
+                    frame.fill(value as u16);
+                }
+            },
+            err_fn,
+            None,
+        ),
+        other => return Err(format!("Unsupported output sample format: {:?}", other)),
+    }
+    .map_err(|e| format!("Failed to open audio output: {}", e))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start audio output: {}", e))?;
+    Ok((stream, sample_rate))
+}
@@ -0,0 +1,288 @@
+// Pluely smart selection. The frozen frame of a capture session is searched
+// for rectangles that look like UI elements (buttons, panels, text lines), so
+// the overlay can snap its selection to whatever is under the cursor.
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, ImageFormat, RgbaImage};
+use serde::Serialize;
+use specta::Type;
+use tauri::Manager;
+
+use crate::capture::CaptureState;
+
+// Edges are found on a copy this wide at most, which keeps detection quick on
+// large monitors and merges the letters of a word into one region
+const DETECT_WIDTH: u32 = 1280;
+// Luma step between neighbours that counts as an edge
+const EDGE_THRESHOLD: u8 = 28;
+// Regions smaller than this on either side, in detection pixels, are noise
+const MIN_SIDE: u32 = 6;
+// Regions covering more of the frame than this are the frame itself
+const MAX_COVERAGE: f64 = 0.9;
+// Regions overlapping a smaller one by this much are the same element
+const SAME_REGION_IOU: f64 = 0.9;
+const MAX_REGIONS: usize = 400;
+
+#[derive(Debug, Clone, Copy, Serialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RegionKind {
+    // Bounded by edges: a button, field, panel or icon
+    Element,
+    // A line or block of text found by OCR
+    Text,
+}
+
+// A candidate rectangle, in image pixels of the monitor like a selection
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub kind: RegionKind,
+}
+
+impl DetectedRegion {
+    fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    fn iou(&self, other: &DetectedRegion) -> f64 {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        if right <= left || bottom <= top {
+            return 0.0;
+        }
+        let overlap = (right - left) as u64 * (bottom - top) as u64;
+        overlap as f64 / (self.area() + other.area() - overlap) as f64
+    }
+}
+
+// Pixels that differ enough from their right or lower neighbour, grown by one
+// pixel so the outline of an element stays connected
+fn edge_mask(gray: &GrayImage) -> Vec<bool> {
+    let (width, height) = (gray.width() as usize, gray.height() as usize);
+    let luma = gray.as_raw();
+    let mut edges = vec![false; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let here = luma[y * width + x];
+            let right = x + 1 < width && here.abs_diff(luma[y * width + x + 1]) > EDGE_THRESHOLD;
+            let below = y + 1 < height && here.abs_diff(luma[(y + 1) * width + x]) > EDGE_THRESHOLD;
+            edges[y * width + x] = right || below;
+        }
+    }
+    let mut grown = edges.clone();
+    for y in 0..height {
+        for x in 0..width {
+            if !edges[y * width + x] {
+                continue;
+            }
+            for (dx, dy) in [(-1i64, 0i64), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+                    grown[ny as usize * width + nx as usize] = true;
+                }
+            }
+        }
+    }
+    grown
+}
+
+// Bounding boxes of the connected edge areas, in detection pixels
+fn edge_boxes(gray: &GrayImage) -> Vec<(u32, u32, u32, u32)> {
+    let (width, height) = (gray.width() as usize, gray.height() as usize);
+    let mut mask = edge_mask(gray);
+    let mut boxes = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..mask.len() {
+        if !mask[start] {
+            continue;
+        }
+        mask[start] = false;
+        stack.push(start);
+        let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % width, index / width);
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+            let neighbours = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < width).then(|| index + 1),
+                (y > 0).then(|| index - width),
+                (y + 1 < height).then(|| index + width),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if mask[next] {
+                    mask[next] = false;
+                    stack.push(next);
+                }
+            }
+        }
+        boxes.push((
+            left as u32,
+            top as u32,
+            (right - left + 1) as u32,
+            (bottom - top + 1) as u32,
+        ));
+    }
+    boxes
+}
+
+fn detect_elements(image: &RgbaImage) -> Vec<DetectedRegion> {
+    let gray = DynamicImage::ImageRgba8(image.clone());
+    let gray = if image.width() > DETECT_WIDTH {
+        gray.resize(DETECT_WIDTH, u32::MAX, FilterType::Triangle)
+    } else {
+        gray
+    }
+    .to_luma8();
+    let scale = image.width() as f64 / gray.width() as f64;
+    let frame = gray.width() as f64 * gray.height() as f64;
+
+    edge_boxes(&gray)
+        .into_iter()
+        .filter(|(_, _, w, h)| {
+            *w >= MIN_SIDE && *h >= MIN_SIDE && (*w as f64 * *h as f64) < frame * MAX_COVERAGE
+        })
+        .map(|(x, y, w, h)| DetectedRegion {
+            x: (x as f64 * scale) as u32,
+            y: (y as f64 * scale) as u32,
+            width: ((w as f64 * scale).round() as u32).min(image.width()),
+            height: ((h as f64 * scale).round() as u32).min(image.height()),
+            kind: RegionKind::Element,
+        })
+        .collect()
+}
+
+// One entry of `tesseract`'s TSV output: a block, paragraph, line or word
+pub(crate) struct OcrBox {
+    // 2 is a block, 3 a paragraph, 4 a line and 5 a word
+    pub level: u32,
+    // Block, paragraph and line numbers, shared by the words of a line
+    pub line: (u32, u32, u32),
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    // Only set on words
+    pub text: String,
+}
+
+// Runs the `tesseract` CLI over `image` for the layout of its text, with any
+// extra `options` such as a page segmentation mode or language
+pub(crate) fn ocr_boxes(image: &RgbaImage, options: &[String]) -> Result<Vec<OcrBox>, String> {
+    let path = std::env::temp_dir().join(format!("pluely-ocr-{}.png", uuid::Uuid::new_v4()));
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write image: {}", e))?;
+    let output = std::process::Command::new("tesseract")
+        .arg(&path)
+        .arg("stdout")
+        .args(options)
+        .arg("tsv")
+        .output();
+    let _ = std::fs::remove_file(&path);
+
+    let output =
+        output.map_err(|e| format!("Failed to run tesseract (is it installed?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "OCR failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // level, page, block, paragraph, line, word, left, top, width, height,
+    // confidence, text
+    let boxes = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split('\t').collect();
+            let fields: Vec<u32> = columns
+                .iter()
+                .take(10)
+                .map(|field| field.parse().ok())
+                .collect::<Option<_>>()?;
+            if fields.len() < 10 || fields[8] == 0 || fields[9] == 0 {
+                return None;
+            }
+            Some(OcrBox {
+                level: fields[0],
+                line: (fields[2], fields[3], fields[4]),
+                x: fields[6],
+                y: fields[7],
+                width: fields[8],
+                height: fields[9],
+                text: columns.get(11).map_or("", |text| text.trim()).to_string(),
+            })
+        })
+        .collect();
+    Ok(boxes)
+}
+
+// Text lines and blocks
+fn detect_text(image: &RgbaImage, options: &[String]) -> Result<Vec<DetectedRegion>, String> {
+    Ok(ocr_boxes(image, options)?
+        .into_iter()
+        .filter(|ocr_box| matches!(ocr_box.level, 2 | 4))
+        .map(|ocr_box| DetectedRegion {
+            x: ocr_box.x,
+            y: ocr_box.y,
+            width: ocr_box.width,
+            height: ocr_box.height,
+            kind: RegionKind::Text,
+        })
+        .collect())
+}
+
+/// Candidate rectangles on the frozen frame of `monitor_index`, smallest
+/// first so the first one holding the cursor is the tightest fit. `ocr` adds
+/// text lines and blocks, which needs `tesseract` installed; `language` is the
+/// OCR language when it isn't English.
+#[tauri::command]
+#[specta::specta]
+pub async fn detect_regions(
+    app: tauri::AppHandle,
+    monitor_index: usize,
+    ocr: Option<bool>,
+    language: Option<String>,
+) -> Result<Vec<DetectedRegion>, String> {
+    let options = crate::ocr::language_options(&app, language.as_deref())?;
+    let image = app
+        .state::<CaptureState>()
+        .captured_monitors
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .get(&monitor_index)
+        .map(|info| info.image.clone())
+        .ok_or_else(|| format!("No captured image found for monitor {}", monitor_index))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut candidates = detect_elements(&image);
+        if ocr.unwrap_or(false) {
+            candidates.extend(detect_text(&image, &options)?);
+        }
+        candidates.sort_by_key(|region| region.area());
+
+        let mut regions: Vec<DetectedRegion> = Vec::new();
+        for candidate in candidates {
+            if regions.len() >= MAX_REGIONS {
+                break;
+            }
+            if regions
+                .iter()
+                .all(|kept| kept.iou(&candidate) < SAME_REGION_IOU)
+            {
+                regions.push(candidate);
+            }
+        }
+        Ok(regions)
+    })
+    .await
+    .map_err(|e| format!("Failed to detect regions: {}", e))?
+}
@@ -0,0 +1,124 @@
+// Provider API keys live in the OS credential store (macOS Keychain, Windows
+// Credential Manager, Secret Service on Linux) instead of plaintext settings.
+// Each workspace profile has its own set.
+use keyring::Entry;
+use std::collections::HashMap;
+
+use crate::llm::LlmProviderKind;
+use crate::workspaces::DEFAULT_WORKSPACE;
+
+const KEYCHAIN_SERVICE: &str = "pluely";
+
+// Every API key entry the app itself writes
+pub fn known_key_ids() -> Vec<&'static str> {
+    let mut ids: Vec<&'static str> = LlmProviderKind::ALL
+        .into_iter()
+        .map(|kind| kind.key_id())
+        .collect();
+    ids.extend([
+        "deepgram",
+        "elevenlabs",
+        "proxy",
+        "notion",
+        "share_s3",
+        "share_imgur",
+        "share_custom",
+        crate::webhooks::SECRETS_KEY_ID,
+    ]);
+    ids
+}
+
+fn entry_in(workspace: &str, provider: &str) -> Result<Entry, String> {
+    let provider = provider.trim();
+    if provider.is_empty() {
+        return Err("Provider is required".to_string());
+    }
+    // The default workspace keeps the names keys had before workspaces
+    let account = if workspace == DEFAULT_WORKSPACE {
+        format!("api_key:{}", provider)
+    } else {
+        format!("api_key:{}:{}", workspace, provider)
+    };
+    Entry::new(KEYCHAIN_SERVICE, &account)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+// Entries of the active workspace
+fn entry(provider: &str) -> Result<Entry, String> {
+    entry_in(&crate::workspaces::active_workspace(), provider)
+}
+
+// Stored key for `provider`, or None if there isn't one
+pub fn get_api_key(provider: &str) -> Result<Option<String>, String> {
+    match entry(provider)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read API key for {}: {}", provider, e)),
+    }
+}
+
+// Saves the key; an empty key removes the entry instead
+pub fn store_api_key(provider: &str, key: &str) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        remove_api_key(provider)?;
+        return Ok(());
+    }
+    entry(provider)?
+        .set_password(key)
+        .map_err(|e| format!("Failed to save API key for {}: {}", provider, e))
+}
+
+fn remove_api_key(provider: &str) -> Result<bool, String> {
+    delete_entry(entry(provider)?, provider)
+}
+
+fn delete_entry(entry: Entry, provider: &str) -> Result<bool, String> {
+    match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(format!("Failed to delete API key for {}: {}", provider, e)),
+    }
+}
+
+// Removes `providers`' keys from a workspace that is being deleted
+pub fn delete_workspace_keys(workspace: &str, providers: &[&str]) -> Result<(), String> {
+    for provider in providers {
+        delete_entry(entry_in(workspace, provider)?, provider)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_api_key(provider: String, key: String) -> Result<(), String> {
+    store_api_key(&provider, &key)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn has_api_key(provider: String) -> Result<bool, String> {
+    Ok(get_api_key(&provider)?.is_some_and(|key| !key.trim().is_empty()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_api_key(provider: String) -> Result<bool, String> {
+    remove_api_key(&provider)
+}
+
+// Moves keys saved by older versions into the keychain. Returns the providers
+// that were migrated so the caller can scrub its plaintext copies.
+#[tauri::command]
+#[specta::specta]
+pub fn migrate_api_keys(keys: HashMap<String, String>) -> Result<Vec<String>, String> {
+    let mut migrated = Vec::new();
+    for (provider, key) in keys {
+        if key.trim().is_empty() {
+            continue;
+        }
+        store_api_key(&provider, &key)?;
+        migrated.push(provider);
+    }
+    Ok(migrated)
+}
@@ -0,0 +1,217 @@
+// Pluely sensitive apps. Rules name applications or window titles, such as
+// password managers and banking sites, that must not end up in a capture. When
+// a matching window is focused or shows on a captured monitor, its rule either
+// blocks the capture or blacks the window out. This runs on every screen grab
+// before it is encoded, so no preview, library file or request ever holds it.
+//
+// A window is blacked out whether or not other windows cover it. Where windows
+// can't be listed (Wayland) only the focused window is checked, and a match
+// blocks the capture since it can't be found to black out.
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tracing::{info, warn};
+use xcap::{Monitor, Window};
+
+// Process-wide because screens are grabbed without an app handle
+static SETTINGS: Mutex<Option<SensitiveAppSettings>> = Mutex::new(None);
+
+const REDACTED: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveAction {
+    // Fails the capture
+    Block,
+    // Blacks out the window
+    Redact,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SensitiveRule {
+    // Matched case-insensitively within the application name; any when empty
+    pub app: String,
+    // Matched case-insensitively within the window title; any when empty
+    pub title: String,
+    pub action: SensitiveAction,
+}
+
+impl Default for SensitiveRule {
+    fn default() -> Self {
+        Self {
+            app: String::new(),
+            title: String::new(),
+            action: SensitiveAction::Redact,
+        }
+    }
+}
+
+impl SensitiveRule {
+    fn new(app: &str, title: &str, action: SensitiveAction) -> Self {
+        Self {
+            app: app.to_string(),
+            title: title.to_string(),
+            action,
+        }
+    }
+
+    fn matches(&self, app: &str, title: &str) -> bool {
+        let contains = |value: &str, pattern: &str| {
+            pattern.trim().is_empty()
+                || value
+                    .to_lowercase()
+                    .contains(&pattern.trim().to_lowercase())
+        };
+        contains(app, &self.app) && contains(title, &self.title)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SensitiveAppSettings {
+    pub enabled: bool,
+    pub rules: Vec<SensitiveRule>,
+}
+
+impl Default for SensitiveAppSettings {
+    fn default() -> Self {
+        use SensitiveAction::{Block, Redact};
+        Self {
+            enabled: true,
+            rules: vec![
+                SensitiveRule::new("1Password", "", Block),
+                SensitiveRule::new("Bitwarden", "", Block),
+                SensitiveRule::new("KeePass", "", Block),
+                SensitiveRule::new("LastPass", "", Block),
+                SensitiveRule::new("Dashlane", "", Block),
+                SensitiveRule::new("Keychain Access", "", Block),
+                SensitiveRule::new("", "Online Banking", Redact),
+                SensitiveRule::new("", "Bank Login", Redact),
+            ],
+        }
+    }
+}
+
+pub fn apply_sensitive_app_settings(settings: &SensitiveAppSettings) -> Result<(), String> {
+    if settings
+        .rules
+        .iter()
+        .any(|rule| rule.app.trim().is_empty() && rule.title.trim().is_empty())
+    {
+        return Err("Invalid sensitive app rule: set an app or a window title".to_string());
+    }
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(settings.clone());
+    Ok(())
+}
+
+fn settings() -> Result<SensitiveAppSettings, String> {
+    Ok(SETTINGS
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone()
+        .unwrap_or_default())
+}
+
+fn first_match<'a>(
+    rules: &'a [SensitiveRule],
+    app: &str,
+    title: &str,
+) -> Option<&'a SensitiveRule> {
+    rules.iter().find(|rule| rule.matches(app, title))
+}
+
+fn blocked(name: &str) -> String {
+    format!("Capture blocked: {} is on the sensitive apps list", name)
+}
+
+// Blacks out `window`'s part of `image`, a grab of `monitor`. Window positions
+// are in the monitor's units, which may be logical rather than image pixels.
+fn redact(image: &mut RgbaImage, monitor: &Monitor, window: &Window) {
+    let scale_x = image.width() as f64 / monitor.width().max(1) as f64;
+    let scale_y = image.height() as f64 / monitor.height().max(1) as f64;
+    let to_image = |position: i32, origin: i32, scale: f64, limit: u32| {
+        (((position - origin) as f64 * scale).round().max(0.0) as u32).min(limit)
+    };
+    let left = to_image(window.x(), monitor.x(), scale_x, image.width());
+    let top = to_image(window.y(), monitor.y(), scale_y, image.height());
+    let right = to_image(
+        window.x() + window.width() as i32,
+        monitor.x(),
+        scale_x,
+        image.width(),
+    );
+    let bottom = to_image(
+        window.y() + window.height() as i32,
+        monitor.y(),
+        scale_y,
+        image.height(),
+    );
+    for y in top..bottom {
+        for x in left..right {
+            image.put_pixel(x, y, REDACTED);
+        }
+    }
+}
+
+// Whether any part of `window` is on `monitor`
+fn overlaps(monitor: &Monitor, window: &Window) -> bool {
+    window.x() < monitor.x() + monitor.width() as i32
+        && window.x() + window.width() as i32 > monitor.x()
+        && window.y() < monitor.y() + monitor.height() as i32
+        && window.y() + window.height() as i32 > monitor.y()
+}
+
+/// Checks a fresh grab of `monitor` against the rules, blacking out windows
+/// to redact, and fails when a window to block is focused or on the monitor
+pub fn enforce(monitor: &Monitor, image: &mut RgbaImage) -> Result<(), String> {
+    let settings = settings()?;
+    if !settings.enabled || settings.rules.is_empty() {
+        return Ok(());
+    }
+
+    // The focused window blocks even from another monitor
+    let focused = crate::focus::focused_window().and_then(|focused| {
+        first_match(&settings.rules, &focused.app_name, &focused.title)
+            .map(|rule| (focused.app_name.clone(), rule.action))
+    });
+    if let Some((name, SensitiveAction::Block)) = &focused {
+        return Err(blocked(name));
+    }
+
+    let windows = match Window::all() {
+        Ok(windows) => windows,
+        Err(e) => {
+            warn!("Failed to list windows for sensitive apps: {}", e);
+            // A window that can't be found can't be blacked out
+            return match focused {
+                Some((name, _)) => Err(blocked(&name)),
+                None => Ok(()),
+            };
+        }
+    };
+    let mut redacted = 0;
+    for window in windows.iter().filter(|window| {
+        !window.is_minimized()
+            && !window.app_name().eq_ignore_ascii_case("pluely")
+            && overlaps(monitor, window)
+    }) {
+        match first_match(&settings.rules, window.app_name(), window.title()) {
+            Some(rule) if rule.action == SensitiveAction::Block => {
+                return Err(blocked(window.app_name()));
+            }
+            Some(_) => {
+                redact(image, monitor, window);
+                redacted += 1;
+            }
+            None => {}
+        }
+    }
+    if redacted > 0 {
+        info!("Redacted {} sensitive window(s) from a capture", redacted);
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Encrypted backup archives for moving Pluely to another machine. The file is
+// a magic header, a format version and a salt, then the sealed JSON payload.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::crypto;
+use crate::prompts::PromptProfile;
+
+const MAGIC: &[u8; 8] = b"PLUELYBK";
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub app_version: String,
+    pub created_at: i64,
+    // Settings document, hotkeys included; upgraded on import like the file
+    pub settings: Value,
+    #[serde(default)]
+    pub profiles: Vec<PromptProfile>,
+    // Only present when the user asked to include them
+    #[serde(default)]
+    pub api_keys: HashMap<String, String>,
+}
+
+pub fn write_archive(path: &Path, passphrase: &str, backup: &Backup) -> Result<(), String> {
+    let payload =
+        serde_json::to_vec(backup).map_err(|e| format!("Failed to serialize backup: {}", e))?;
+    let salt = crypto::random_salt();
+    let key = crypto::derive_key(passphrase, &salt)?;
+
+    let mut archive = MAGIC.to_vec();
+    archive.push(FORMAT_VERSION);
+    archive.extend(salt);
+    archive.extend(crypto::encrypt(&key, &payload)?);
+    std::fs::write(path, archive).map_err(|e| format!("Failed to write backup: {}", e))
+}
+
+pub fn read_archive(path: &Path, passphrase: &str) -> Result<Backup, String> {
+    let archive = std::fs::read(path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let header = MAGIC.len() + 1 + crypto::SALT_LEN;
+    if archive.len() < header || !archive.starts_with(MAGIC) {
+        return Err("Not a Pluely backup file".to_string());
+    }
+    let version = archive[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {}", version));
+    }
+
+    let salt = &archive[MAGIC.len() + 1..header];
+    let key = crypto::derive_key(passphrase, salt)?;
+    let payload = crypto::decrypt(&key, &archive[header..])
+        .map_err(|_| "Failed to open backup: wrong passphrase or damaged file".to_string())?;
+    serde_json::from_slice(&payload).map_err(|e| format!("Failed to parse backup: {}", e))
+}
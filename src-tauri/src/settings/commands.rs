@@ -0,0 +1,234 @@
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tauri_specta::Event;
+
+use super::backup::{self, Backup};
+use super::policy::{self, PolicyStatus};
+use super::{commit_settings, current_settings, migrations, parse_settings, Settings};
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedBackup {
+    pub settings: Settings,
+    pub profiles: usize,
+    pub api_keys: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SettingsChanged {
+    // Dot path of the changed setting; a section name after a reset
+    pub key: String,
+    pub value: Value,
+}
+
+fn to_value(settings: &Settings) -> Result<Value, String> {
+    serde_json::to_value(settings).map_err(|e| format!("Failed to serialize settings: {}", e))
+}
+
+// Looks up "section.field.sub" style paths; an empty path is the whole document
+fn get_path<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+    if key.is_empty() {
+        return Some(root);
+    }
+    key.split('.').try_fold(root, |value, part| value.get(part))
+}
+
+pub(super) fn set_path(root: &mut Value, key: &str, value: Value) -> Result<(), String> {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (get_path_mut(root, parent), last),
+        None => (Some(root), key),
+    };
+    match parent {
+        Some(Value::Object(map)) => {
+            map.insert(last.to_string(), value);
+            Ok(())
+        }
+        // Optional sections that are still unset
+        Some(parent @ Value::Null) => {
+            parent[last] = value;
+            Ok(())
+        }
+        _ => Err(format!("Unknown setting: {}", key)),
+    }
+}
+
+fn get_path_mut<'a>(root: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.')
+        .try_fold(root, |value, part| value.get_mut(part))
+}
+
+fn section(key: &str) -> &str {
+    key.split('.').next().unwrap_or(key)
+}
+
+fn emit_changed(app: &AppHandle, key: &str, settings: &Settings) -> Result<(), String> {
+    let value = get_path(&to_value(settings)?, key)
+        .cloned()
+        .unwrap_or(Value::Null);
+    app.emit(
+        "settings-changed",
+        SettingsChanged {
+            key: key.to_string(),
+            value,
+        },
+    )
+    .map_err(|e| format!("Failed to emit settings change: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_settings(app: AppHandle) -> Result<Settings, String> {
+    current_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_setting(app: AppHandle, key: String) -> Result<Value, String> {
+    let settings = to_value(&current_settings(&app)?)?;
+    get_path(&settings, &key)
+        .cloned()
+        .ok_or_else(|| format!("Unknown setting: {}", key))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_setting(app: AppHandle, key: String, value: Value) -> Result<Settings, String> {
+    if section(&key) == "version" {
+        return Err("The settings version cannot be changed".to_string());
+    }
+    if policy::is_locked(&key) {
+        return Err(format!("{} is managed by your organization", key));
+    }
+    let mut document = to_value(&current_settings(&app)?)?;
+    if document.get(section(&key)).is_none() {
+        return Err(format!("Unknown setting: {}", key));
+    }
+    set_path(&mut document, &key, value)?;
+    let settings: Settings = serde_json::from_value(document)
+        .map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+    // Fields the typed sections don't know are dropped while parsing
+    if get_path(&to_value(&settings)?, &key).is_none() {
+        return Err(format!("Unknown setting: {}", key));
+    }
+
+    let settings = commit_settings(&app, settings, &[section(&key)])?;
+    emit_changed(&app, &key, &settings)?;
+    Ok(settings)
+}
+
+/// The managed policy: whether one is installed, what it locks and which
+/// providers it allows
+#[tauri::command]
+#[specta::specta]
+pub fn get_managed_policy() -> PolicyStatus {
+    policy::policy_status()
+}
+
+// Restores one section to its defaults, or everything when none is given
+#[tauri::command]
+#[specta::specta]
+pub fn reset_settings(app: AppHandle, section: Option<String>) -> Result<Settings, String> {
+    let defaults = to_value(&Settings::default())?;
+    let sections: Vec<String> = match section {
+        Some(section) if section != "version" && defaults.get(&section).is_some() => {
+            vec![section]
+        }
+        Some(section) => return Err(format!("Unknown settings section: {}", section)),
+        None => defaults
+            .as_object()
+            .map(|map| map.keys().filter(|k| *k != "version").cloned().collect())
+            .unwrap_or_default(),
+    };
+
+    let mut document = to_value(&current_settings(&app)?)?;
+    for section in &sections {
+        document[section.as_str()] = defaults[section.as_str()].clone();
+    }
+    let settings = parse_settings(document)?;
+    let changed: Vec<&str> = sections.iter().map(String::as_str).collect();
+    let settings = commit_settings(&app, settings, &changed)?;
+    for section in &sections {
+        emit_changed(&app, section, &settings)?;
+    }
+    Ok(settings)
+}
+
+// One-time import of the values the frontend used to keep in localStorage.
+// Skipped once a settings file exists so a stale webview can't overwrite it.
+#[tauri::command]
+#[specta::specta]
+pub fn import_local_storage(
+    app: AppHandle,
+    entries: HashMap<String, String>,
+) -> Result<Settings, String> {
+    if super::settings_file_exists(&app) {
+        return current_settings(&app);
+    }
+    let document = serde_json::to_value(entries)
+        .map_err(|e| format!("Failed to read localStorage entries: {}", e))?;
+    let settings = parse_settings(migrations::migrate(document)?)?;
+    let settings = commit_settings(&app, settings, super::BACKEND_SECTIONS)?;
+    emit_changed(&app, "", &settings)?;
+    Ok(settings)
+}
+
+// Writes settings, hotkeys and prompt profiles to an encrypted archive. API
+// keys are left out unless `include_api_keys` is set.
+#[tauri::command]
+#[specta::specta]
+pub fn export_settings(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    include_api_keys: Option<bool>,
+) -> Result<(), String> {
+    let mut api_keys = HashMap::new();
+    if include_api_keys.unwrap_or(false) {
+        for id in crate::secrets::known_key_ids() {
+            if let Some(key) = crate::secrets::get_api_key(id)? {
+                api_keys.insert(id.to_string(), key);
+            }
+        }
+    }
+
+    let backup = Backup {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default(),
+        settings: to_value(&current_settings(&app)?)?,
+        profiles: crate::prompts::load_profiles(&app)?,
+        api_keys,
+    };
+    backup::write_archive(Path::new(&path), &passphrase, &backup)
+}
+
+// Restores an archive from `export_settings`, replacing the current settings
+#[tauri::command]
+#[specta::specta]
+pub fn import_settings(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> Result<ImportedBackup, String> {
+    let backup = backup::read_archive(Path::new(&path), &passphrase)?;
+    let settings = parse_settings(migrations::migrate(backup.settings)?)?;
+
+    let settings = commit_settings(&app, settings, super::BACKEND_SECTIONS)?;
+    for (id, key) in &backup.api_keys {
+        crate::secrets::store_api_key(id, key)?;
+    }
+    let profiles = crate::prompts::import_profiles(&app, backup.profiles)?;
+    emit_changed(&app, "", &settings)?;
+
+    Ok(ImportedBackup {
+        settings,
+        profiles,
+        api_keys: backup.api_keys.len(),
+    })
+}
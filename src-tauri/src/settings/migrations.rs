@@ -0,0 +1,85 @@
+// Upgrades settings documents written by older versions. Each step takes a
+// document at version N and returns it at version N + 1.
+use serde_json::{json, Map, Value};
+
+type Step = fn(Value) -> Result<Value, String>;
+
+// Version 0 is the flat key/value map the frontend kept in localStorage
+const STEPS: &[Step] = &[from_local_storage];
+
+pub const CURRENT_VERSION: u32 = STEPS.len() as u32;
+
+pub fn migrate(mut value: Value) -> Result<Value, String> {
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    if version > STEPS.len() {
+        return Err(format!(
+            "Settings were saved by a newer version of Pluely (version {})",
+            version
+        ));
+    }
+    for step in &STEPS[version..] {
+        value = step(value)?;
+    }
+    Ok(value)
+}
+
+// localStorage values are strings, most of them JSON encoded
+fn parse_stored(raw: &Value) -> Value {
+    match raw {
+        Value::String(text) => serde_json::from_str(text).unwrap_or_else(|_| raw.clone()),
+        other => other.clone(),
+    }
+}
+
+// Copies `from[key]` to `to[target]` when present
+fn copy_field(from: &Value, key: &str, to: &mut Value, target: &str) {
+    if let Some(value) = from.get(key).filter(|v| !v.is_null()) {
+        to[target] = value.clone();
+    }
+}
+
+fn from_local_storage(value: Value) -> Result<Value, String> {
+    let Value::Object(entries) = value else {
+        return Err("Invalid settings: expected an object".to_string());
+    };
+
+    let mut settings = json!({ "version": 1 });
+    let mut extra = Map::new();
+    for (key, raw) in entries {
+        let value = parse_stored(&raw);
+        match key.as_str() {
+            "version" => {}
+            "theme" => settings["appearance"]["theme"] = value,
+            "transparency" => settings["appearance"]["transparency"] = value,
+            // Free text; kept verbatim even when it happens to parse as JSON
+            "system_prompt" => settings["systemPrompt"]["prompt"] = raw,
+            "selected_system_prompt_id" => settings["systemPrompt"]["selectedId"] = value,
+            "selected_audio_input_device" => settings["audio"]["inputDevice"] = raw,
+            "selected_audio_output_device" => settings["audio"]["outputDevice"] = raw,
+            "screenshot_config" => settings["screenshot"] = value,
+            "response_settings" => settings["response"] = value,
+            "system_audio_context" => {
+                let section = &mut settings["systemAudio"];
+                copy_field(&value, "useSystemPrompt", section, "useSystemPrompt");
+                copy_field(&value, "contextContent", section, "contextContent");
+            }
+            "system_audio_quick_actions" => settings["systemAudio"]["quickActions"] = value,
+            // Nested { appIcon: { isVisible } } style objects
+            "customizable" => {
+                let window = &mut settings["window"];
+                copy_field(&value["appIcon"], "isVisible", window, "appIconVisible");
+                copy_field(&value["alwaysOnTop"], "isEnabled", window, "alwaysOnTop");
+                copy_field(&value["autostart"], "isEnabled", window, "autostart");
+                copy_field(&value["cursor"], "type", window, "cursor");
+            }
+            "shortcuts" if value.get("bindings").is_some_and(Value::is_object) => {
+                settings["shortcuts"] = value
+            }
+            _ => {
+                extra.insert(key, value);
+            }
+        }
+    }
+    settings["extra"] = Value::Object(extra);
+    Ok(settings)
+}
@@ -0,0 +1,640 @@
+// Pluely settings store. Every preference lives in one versioned JSON file in
+// the app config directory, so nothing is lost when the webview's storage is
+// cleared. Backend sections are pushed into their modules on load and change.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+use crate::analytics::TelemetrySettings;
+use crate::capture::OverlaySettings;
+use crate::captures::{RetentionPolicy, ThumbnailSettings, WatermarkSettings};
+use crate::clipboard::ClipboardSettings;
+use crate::control::ControlApiSettings;
+use crate::crash::CrashReportSettings;
+use crate::feedback::CaptureFeedbackSettings;
+use crate::focus::FocusSettings;
+use crate::fullscreen::FullscreenSettings;
+use crate::hooks::HookSettings;
+use crate::idle::IdleSettings;
+use crate::integrations::IntegrationSettings;
+use crate::llm::{LlmConfig, LlmProviderKind};
+use crate::meeting::MeetingSettings;
+use crate::network::ProxyConfig;
+use crate::notify::NotificationSettings;
+use crate::placement::{ShowAt, WindowMode, WindowPlacement};
+use crate::plugins::PluginSettings;
+use crate::power::PowerSettings;
+use crate::realtime::RealtimeConfig;
+use crate::sensitive::SensitiveAppSettings;
+use crate::share::ShareSettings;
+use crate::shortcuts::ShortcutsConfig;
+use crate::stt::SttConfig;
+use crate::tts::TtsConfig;
+use crate::webhooks::WebhookSettings;
+use crate::window::WindowEffect;
+
+mod backup;
+mod commands;
+mod migrations;
+mod policy;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+pub use migrations::CURRENT_VERSION;
+pub use policy::{ensure_provider_allowed, policy_forces_local_only, telemetry_allowed};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+// Sections that configure backend modules rather than the frontend
+const BACKEND_SECTIONS: &[&str] = &[
+    "appearance",
+    "window",
+    "llm",
+    "stt",
+    "tts",
+    "realtime",
+    "proxy",
+    "shortcuts",
+    "privacy",
+    "sensitiveApps",
+    "captures",
+    "clipboard",
+    "focus",
+    "idle",
+    "power",
+    "meetings",
+    "fullscreen",
+    "webhooks",
+    "hooks",
+    "plugins",
+    "integrations",
+    "share",
+    "crashReports",
+    "telemetry",
+    "controlApi",
+];
+
+#[derive(Default)]
+pub struct SettingsState {
+    // Loaded from disk on first use
+    settings: Mutex<Option<Settings>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppearanceSettings {
+    pub theme: Theme,
+    // Percent, 0-100
+    pub transparency: u8,
+    // Of the whole main window, 0.2-1.0; `transparency` only fades the background
+    pub window_opacity: f64,
+    pub window_effect: WindowEffect,
+}
+
+impl Default for AppearanceSettings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            transparency: 10,
+            window_opacity: 1.0,
+            window_effect: WindowEffect::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorType {
+    #[default]
+    Invisible,
+    Default,
+    Auto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowSettings {
+    pub app_icon_visible: bool,
+    pub always_on_top: bool,
+    pub autostart: bool,
+    pub cursor: CursorType,
+    // Launch hidden, reachable from the tray icon and hotkeys
+    pub start_minimized: bool,
+    // Same, but only when launched at login
+    pub autostart_hidden: bool,
+    // Keep the main window out of the taskbar
+    pub skip_taskbar: bool,
+    // Keep Pluely out of Alt+Tab, or the dock and Cmd+Tab on macOS
+    pub hide_from_switcher: bool,
+    // Pull the main window onto a screen edge dropped within `snap_margin` px
+    pub snap_to_edges: bool,
+    pub snap_margin: u32,
+    // Last main window placement for each monitor layout
+    pub placements: HashMap<String, WindowPlacement>,
+    // Where the show-window hotkey brings the main window
+    pub show_at: ShowAt,
+    // Last geometry of each window mode
+    pub mode_geometry: HashMap<WindowMode, WindowPlacement>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            app_icon_visible: true,
+            always_on_top: false,
+            autostart: true,
+            cursor: CursorType::default(),
+            start_minimized: false,
+            autostart_hidden: false,
+            skip_taskbar: true,
+            hide_from_switcher: false,
+            snap_to_edges: true,
+            snap_margin: 16,
+            placements: HashMap::new(),
+            show_at: ShowAt::default(),
+            mode_geometry: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotMode {
+    #[default]
+    Manual,
+    Auto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotSettings {
+    pub mode: ScreenshotMode,
+    // Sent along with the capture in auto mode
+    pub auto_prompt: String,
+    pub enabled: bool,
+    // Selection mode only: a ratio like "16:9" or a size like "1280x720"
+    pub selection_constraint: Option<String>,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            mode: ScreenshotMode::default(),
+            auto_prompt: "Analyze this screenshot and provide insights".to_string(),
+            enabled: true,
+            selection_constraint: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponseSettings {
+    pub response_length: String,
+    pub language: String,
+    pub auto_scroll: bool,
+}
+
+impl Default for ResponseSettings {
+    fn default() -> Self {
+        Self {
+            response_length: "auto".to_string(),
+            language: "english".to_string(),
+            auto_scroll: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioSettings {
+    // Device names; None uses the system default
+    pub input_device: Option<String>,
+    pub output_device: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SystemPromptSettings {
+    pub prompt: Option<String>,
+    // Row id of the saved prompt in use
+    pub selected_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SystemAudioSettings {
+    pub use_system_prompt: bool,
+    pub context_content: String,
+    pub quick_actions: Vec<String>,
+}
+
+impl Default for SystemAudioSettings {
+    fn default() -> Self {
+        Self {
+            use_system_prompt: true,
+            context_content: String::new(),
+            quick_actions: [
+                "What should I say?",
+                "Follow-up questions",
+                "Fact-check",
+                "Recap",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    // Seal history, cached answers and capture files with a keychain key.
+    // History search is unavailable while this is on.
+    pub encrypt_history: bool,
+    // Keep Pluely's windows out of screen shares and recordings
+    pub hide_from_screen_share: bool,
+    // No network access at all; models, transcription, speech and OCR run
+    // on this machine
+    pub local_only: bool,
+    // Wipe temporary copies of captures whenever the screen locks
+    pub purge_captures_on_lock: bool,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            encrypt_history: false,
+            hide_from_screen_share: true,
+            local_only: false,
+            purge_captures_on_lock: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub appearance: AppearanceSettings,
+    pub window: WindowSettings,
+    pub screenshot: ScreenshotSettings,
+    pub response: ResponseSettings,
+    pub audio: AudioSettings,
+    pub system_prompt: SystemPromptSettings,
+    pub system_audio: SystemAudioSettings,
+    pub privacy: PrivacySettings,
+    // Apps and windows kept out of captures
+    pub sensitive_apps: SensitiveAppSettings,
+    // Retention of the capture library
+    pub captures: RetentionPolicy,
+    pub notifications: NotificationSettings,
+    // Shutter sound when a capture is taken
+    pub capture_feedback: CaptureFeedbackSettings,
+    // Look of the area selection overlay
+    pub overlay: OverlaySettings,
+    // Previews of captures in history listings and capture events
+    pub thumbnails: ThumbnailSettings,
+    // Stamp on saved and exported screenshots
+    pub watermark: WatermarkSettings,
+    pub clipboard: ClipboardSettings,
+    // Which app the user was in, recorded with questions
+    pub focus: FocusSettings,
+    // Pausing background work while the user is away
+    pub idle: IdleSettings,
+    // Lighter captures and background work on battery
+    pub power: PowerSettings,
+    // Meeting app and Do Not Disturb detection
+    pub meetings: MeetingSettings,
+    // Staying out of the way of fullscreen apps
+    pub fullscreen: FullscreenSettings,
+    // Local REST server for scripts and launchers
+    pub control_api: ControlApiSettings,
+    // Endpoints notified of captures and answers
+    pub webhooks: WebhookSettings,
+    // Commands run on captures and answers
+    pub hooks: HookSettings,
+    // Which plugins are enabled and which host services they may call; the
+    // plugin processes themselves run with the user's permissions
+    pub plugins: PluginSettings,
+    // Obsidian and Notion, where answers can be sent as notes
+    pub integrations: IntegrationSettings,
+    // Where captures are uploaded to be shared as a link
+    pub share: ShareSettings,
+    // Consent to upload crash reports
+    pub crash_reports: CrashReportSettings,
+    // Consent to send usage counts and timings
+    pub telemetry: TelemetrySettings,
+    // None until bindings are saved; the frontend knows the defaults
+    pub shortcuts: Option<ShortcutsConfig>,
+    pub llm: LlmConfig,
+    pub stt: SttConfig,
+    pub tts: TtsConfig,
+    pub realtime: RealtimeConfig,
+    pub proxy: ProxyConfig,
+    // Other frontend keys (custom providers, flags), kept as they were
+    pub extra: serde_json::Map<String, Value>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            appearance: AppearanceSettings::default(),
+            window: WindowSettings::default(),
+            screenshot: ScreenshotSettings::default(),
+            response: ResponseSettings::default(),
+            audio: AudioSettings::default(),
+            system_prompt: SystemPromptSettings::default(),
+            system_audio: SystemAudioSettings::default(),
+            privacy: PrivacySettings::default(),
+            sensitive_apps: SensitiveAppSettings::default(),
+            captures: RetentionPolicy::default(),
+            notifications: NotificationSettings::default(),
+            capture_feedback: CaptureFeedbackSettings::default(),
+            overlay: OverlaySettings::default(),
+            thumbnails: ThumbnailSettings::default(),
+            watermark: WatermarkSettings::default(),
+            clipboard: ClipboardSettings::default(),
+            focus: FocusSettings::default(),
+            idle: IdleSettings::default(),
+            power: PowerSettings::default(),
+            meetings: MeetingSettings::default(),
+            fullscreen: FullscreenSettings::default(),
+            control_api: ControlApiSettings::default(),
+            webhooks: WebhookSettings::default(),
+            hooks: HookSettings::default(),
+            plugins: PluginSettings::default(),
+            integrations: IntegrationSettings::default(),
+            share: ShareSettings::default(),
+            crash_reports: CrashReportSettings::default(),
+            telemetry: TelemetrySettings::default(),
+            shortcuts: None,
+            llm: LlmConfig::default(),
+            stt: SttConfig::default(),
+            tts: TtsConfig::default(),
+            realtime: RealtimeConfig::default(),
+            proxy: ProxyConfig::default(),
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+impl Settings {
+    // Checks the frontend sections; backend sections are checked by their
+    // modules when applied
+    pub fn validate(&self) -> Result<(), String> {
+        if self.appearance.transparency > 100 {
+            return Err("Invalid transparency: must be 0-100".to_string());
+        }
+        if self.window.snap_margin > 200 {
+            return Err("Invalid snap margin: must be 0-200 px".to_string());
+        }
+        self.overlay.validate()?;
+        self.thumbnails.validate()?;
+        self.watermark.validate()?;
+        if self.screenshot.mode == ScreenshotMode::Auto
+            && self.screenshot.auto_prompt.trim().is_empty()
+        {
+            return Err("Auto screenshot mode needs a prompt".to_string());
+        }
+        if self
+            .system_audio
+            .quick_actions
+            .iter()
+            .any(|a| a.trim().is_empty())
+        {
+            return Err("Quick actions cannot be empty".to_string());
+        }
+        if let Some(shortcuts) = &self.shortcuts {
+            for (action, binding) in &shortcuts.bindings {
+                // move_window holds only modifiers; arrows are added when registering
+                if !binding.enabled || binding.key.is_empty() || action == "move_window" {
+                    continue;
+                }
+                binding
+                    .key
+                    .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                    .map_err(|e| {
+                        format!("Invalid shortcut '{}' for {}: {}", binding.key, action, e)
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    // Keys were handed to the keychain while applying; the file never holds them
+    fn strip_secrets(&mut self) {
+        for kind in LlmProviderKind::ALL {
+            self.llm.settings_mut(kind).api_key = None;
+        }
+        self.stt.openai.api_key = None;
+        self.stt.deepgram.api_key = None;
+        self.tts.openai.api_key = None;
+        self.tts.elevenlabs.api_key = None;
+        self.proxy.password = None;
+        for endpoint in &mut self.webhooks.endpoints {
+            endpoint.secret = None;
+        }
+        self.integrations.notion.token = None;
+        self.share.s3.secret_access_key = None;
+        self.share.imgur.client_id = None;
+        self.share.custom.authorization = None;
+    }
+}
+
+// Each workspace profile has its own file
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspaces::config_dir(app)?.join(SETTINGS_FILE))
+}
+
+pub fn settings_file_exists(app: &AppHandle) -> bool {
+    settings_path(app)
+        .map(|path| path.exists())
+        .unwrap_or(false)
+}
+
+fn read_settings(app: &AppHandle) -> Result<Settings, String> {
+    read_settings_at(&settings_path(app)?)
+}
+
+// Reads the file, upgrading older versions; defaults when there is none yet
+fn read_settings_at(path: &Path) -> Result<Settings, String> {
+    if !path.exists() {
+        return policy::enforce(Settings::default());
+    }
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read settings: {}", e))?;
+    let value: Value =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse settings: {}", e))?;
+    policy::enforce(parse_settings(migrations::migrate(value)?)?)
+}
+
+pub fn parse_settings(value: Value) -> Result<Settings, String> {
+    serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))
+}
+
+// Written to a temporary file first so a crash never leaves half a file
+fn write_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write settings: {}", e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+pub fn current_settings(app: &AppHandle) -> Result<Settings, String> {
+    let state = app.state::<SettingsState>();
+    let mut cached = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(settings) = cached.as_ref() {
+        return Ok(settings.clone());
+    }
+    let settings = read_settings(app)?;
+    *cached = Some(settings.clone());
+    Ok(settings)
+}
+
+// Hands backend sections to their modules, which validate them and move any
+// keys into the keychain
+fn apply_sections(app: &AppHandle, settings: &Settings, sections: &[&str]) -> Result<(), String> {
+    for section in sections {
+        match *section {
+            "appearance" => {
+                crate::window::set_main_window_opacity(app, settings.appearance.window_opacity)?;
+                crate::window::set_main_window_effect(app, settings.appearance.window_effect)?;
+            }
+            "window" => crate::window::apply_window_settings(app, &settings.window)?,
+            "llm" => crate::llm::update_llm_config(app.clone(), settings.llm.clone())?,
+            "stt" => crate::stt::update_stt_config(app.clone(), settings.stt.clone())?,
+            "tts" => crate::tts::update_tts_config(app.clone(), settings.tts.clone())?,
+            "realtime" => {
+                crate::realtime::update_realtime_config(app.clone(), settings.realtime.clone())?
+            }
+            "proxy" => crate::network::update_proxy_config(settings.proxy.clone())?,
+            "captures" => {
+                crate::captures::update_capture_retention(app.clone(), settings.captures.clone())?
+            }
+            "clipboard" => crate::clipboard::apply_clipboard_settings(app, &settings.clipboard)?,
+            "focus" => crate::focus::apply_focus_settings(app, &settings.focus)?,
+            "idle" => crate::idle::apply_idle_settings(app, &settings.idle)?,
+            "power" => crate::power::apply_power_settings(app, &settings.power)?,
+            "meetings" => crate::meeting::apply_meeting_settings(app, &settings.meetings)?,
+            "fullscreen" => {
+                crate::fullscreen::apply_fullscreen_settings(app, &settings.fullscreen)?
+            }
+            "webhooks" => crate::webhooks::apply_webhook_settings(app, &settings.webhooks)?,
+            "hooks" => crate::hooks::apply_hook_settings(app, &settings.hooks)?,
+            "plugins" => crate::plugins::apply_plugin_settings(app, &settings.plugins)?,
+            "integrations" => {
+                crate::integrations::apply_integration_settings(&settings.integrations)?
+            }
+            "share" => crate::share::apply_share_settings(&settings.share)?,
+            "crashReports" => {
+                crate::crash::apply_crash_report_settings(app, &settings.crash_reports)
+            }
+            "telemetry" => crate::analytics::apply_telemetry_settings(&settings.telemetry),
+            "controlApi" => crate::control::apply_control_api_settings(app, &settings.control_api)?,
+            "sensitiveApps" => {
+                crate::sensitive::apply_sensitive_app_settings(&settings.sensitive_apps)?
+            }
+            "privacy" => {
+                crate::network::set_local_only(settings.privacy.local_only)?;
+                crate::wipe::set_purge_on_lock(app, settings.privacy.purge_captures_on_lock)?;
+                crate::storage::set_history_encryption(app, settings.privacy.encrypt_history)?;
+                crate::window::set_content_protection_all(
+                    app,
+                    settings.privacy.hide_from_screen_share,
+                )?;
+            }
+            "shortcuts" => {
+                if let Some(shortcuts) = &settings.shortcuts {
+                    crate::shortcuts::update_shortcuts(app.clone(), shortcuts.clone())?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Validates, applies and saves `settings`; `changed` names the sections that differ
+pub fn commit_settings(
+    app: &AppHandle,
+    mut settings: Settings,
+    changed: &[&str],
+) -> Result<Settings, String> {
+    settings.version = CURRENT_VERSION;
+    // Locked values win over whatever was asked for
+    let mut settings = policy::enforce(settings)?;
+    settings.validate()?;
+    apply_sections(app, &settings, changed)?;
+    settings.strip_secrets();
+    write_settings(app, &settings)?;
+
+    let state = app.state::<SettingsState>();
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(settings.clone());
+    Ok(settings)
+}
+
+// Rereads settings after the workspace profile changed and configures every
+// backend module from them, defaults included
+pub fn reload_settings(app: &AppHandle) -> Result<Settings, String> {
+    let settings = read_settings(app)?;
+    apply_sections(app, &settings, BACKEND_SECTIONS)?;
+
+    let state = app.state::<SettingsState>();
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(settings.clone());
+    Ok(settings)
+}
+
+// Loads the saved settings at startup and configures the backend from them
+pub fn load_on_startup(app: &AppHandle) {
+    // A managed policy applies even before anything is saved
+    if !settings_file_exists(app) && !policy::is_managed() {
+        return;
+    }
+    match current_settings(app) {
+        Ok(settings) => {
+            if let Err(e) = apply_sections(app, &settings, BACKEND_SECTIONS) {
+                warn!("Failed to apply saved settings: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to load settings: {}", e),
+    }
+}
+
+// The CLI and MCP server capture the screen without starting the app, so they
+// load the active profile's sensitive-app rules from its settings file first
+pub fn load_headless() -> Result<(), String> {
+    let dir = crate::workspaces::init_headless().ok_or("Failed to find the config directory")?;
+    let settings = read_settings_at(&dir.join(SETTINGS_FILE))?;
+    crate::sensitive::apply_sensitive_app_settings(&settings.sensitive_apps)
+}
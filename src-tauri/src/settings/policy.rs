@@ -0,0 +1,231 @@
+// Managed policy. An administrator can provision a policy file at a fixed path
+// per OS that locks settings for everyone on the machine. Locked values
+// override whatever is saved and can't be changed from the app. A policy that
+// can't be read is reported in its status and otherwise ignored.
+//
+// {"allowedProviders": ["ollama", "lm_studio"], "localOnly": true,
+//  "telemetry": false, "retention": {"maxAgeDays": 7},
+//  "settings": {"privacy.encryptHistory": true}}
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use super::commands::set_path;
+use super::{parse_settings, Settings};
+use crate::llm::LlmProviderKind;
+
+const POLICY_FILE: &str = "policy.json";
+
+static POLICY: Lazy<Option<Result<Policy, String>>> = Lazy::new(load);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionLimits {
+    pub max_age_days: Option<u32>,
+    pub max_total_mb: Option<u64>,
+    pub max_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Policy {
+    // Model providers that may be used; all when None
+    pub allowed_providers: Option<Vec<LlmProviderKind>>,
+    // Locks local-only mode on, keeping everything on the machine (see
+    // network/mod.rs), and clears webhooks and crash reports
+    pub local_only: bool,
+    // False turns off crash reports, usage counts and usage reporting
+    pub telemetry: bool,
+    // Capture retention limits, locked where set
+    pub retention: RetentionLimits,
+    // Any other settings to lock, by dot path
+    pub settings: serde_json::Map<String, Value>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            allowed_providers: None,
+            local_only: false,
+            telemetry: true,
+            retention: RetentionLimits::default(),
+            settings: serde_json::Map::new(),
+        }
+    }
+}
+
+impl Policy {
+    // The setting values the policy fixes, by dot path
+    fn locks(&self) -> Vec<(String, Value)> {
+        let mut locks: Vec<(String, Value)> = self
+            .settings
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let mut lock = |key: &str, value: Value| locks.push((key.to_string(), value));
+        if !self.telemetry || self.local_only {
+            lock("crashReports.upload", json!(false));
+            lock("telemetry.shareUsage", json!(false));
+        }
+        if self.local_only {
+            lock("privacy.localOnly", json!(true));
+            lock("webhooks.endpoints", json!([]));
+        }
+        if let Some(days) = self.retention.max_age_days {
+            lock("captures.maxAgeDays", json!(days));
+        }
+        if let Some(mb) = self.retention.max_total_mb {
+            lock("captures.maxTotalMb", json!(mb));
+        }
+        if let Some(count) = self.retention.max_count {
+            lock("captures.maxCount", json!(count));
+        }
+        locks
+    }
+
+    // None when every provider is allowed
+    fn allowed(&self) -> Option<Vec<LlmProviderKind>> {
+        if !self.local_only {
+            return self.allowed_providers.clone();
+        }
+        let allowed = self
+            .allowed_providers
+            .clone()
+            .unwrap_or(vec![LlmProviderKind::Ollama, LlmProviderKind::LmStudio]);
+        Some(allowed.into_iter().filter(|kind| kind.is_local()).collect())
+    }
+}
+
+// Outside any user's profile, where only administrators can write
+pub fn policy_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    let dir =
+        PathBuf::from(std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into()))
+            .join("Pluely");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/Pluely");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let dir = PathBuf::from("/etc/pluely");
+    dir.join(POLICY_FILE)
+}
+
+fn load() -> Option<Result<Policy, String>> {
+    let path = policy_path();
+    if !path.is_file() {
+        return None;
+    }
+    let policy = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read managed policy: {}", e))
+        .and_then(|text| {
+            serde_json::from_str::<Policy>(&text)
+                .map_err(|e| format!("Invalid managed policy: {}", e))
+        });
+    match &policy {
+        Ok(_) => info!("Managed policy loaded from {}", path.display()),
+        Err(e) => warn!("{}", e),
+    }
+    Some(policy)
+}
+
+// The policy in force; read once, so changes take effect on restart
+fn policy() -> Option<&'static Policy> {
+    POLICY.as_ref()?.as_ref().ok()
+}
+
+pub fn is_managed() -> bool {
+    policy().is_some()
+}
+
+// Applies the policy's locks and provider list over `settings`. A lock that
+// doesn't fit the settings is skipped rather than failing every load.
+pub fn enforce(settings: Settings) -> Result<Settings, String> {
+    let Some(policy) = policy() else {
+        return Ok(settings);
+    };
+    let mut document = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    for (key, value) in policy.locks() {
+        let mut locked = document.clone();
+        match set_path(&mut locked, &key, value).and_then(|_| parse_settings(locked.clone())) {
+            Ok(_) => document = locked,
+            Err(e) => warn!("Skipping managed setting {}: {}", key, e),
+        }
+    }
+    let mut settings = parse_settings(document)?;
+
+    if let Some(allowed) = policy.allowed() {
+        if !allowed.contains(&settings.llm.provider) {
+            if let Some(first) = allowed.first() {
+                settings.llm.provider = *first;
+            }
+        }
+        settings.llm.failover.retain(|kind| allowed.contains(kind));
+    }
+    Ok(settings)
+}
+
+// Whether `key` is, contains or sits inside a locked setting
+pub fn is_locked(key: &str) -> bool {
+    let Some(policy) = policy() else {
+        return false;
+    };
+    policy.locks().iter().any(|(locked, _)| {
+        key.is_empty()
+            || key == locked
+            || locked.starts_with(&format!("{}.", key))
+            || key.starts_with(&format!("{}.", locked))
+    })
+}
+
+pub fn ensure_provider_allowed(kind: LlmProviderKind) -> Result<(), String> {
+    match policy().and_then(Policy::allowed) {
+        Some(allowed) if !allowed.contains(&kind) => Err(format!(
+            "Provider {} is not allowed by your organization's policy",
+            kind.key_id()
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Holds even before settings are loaded
+pub fn policy_forces_local_only() -> bool {
+    policy().is_some_and(|policy| policy.local_only)
+}
+
+pub fn telemetry_allowed() -> bool {
+    !crate::network::local_only() && policy().is_none_or(|policy| policy.telemetry)
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyStatus {
+    // Whether a policy file is installed, valid or not
+    pub managed: bool,
+    pub path: String,
+    // Why an installed policy isn't enforced
+    pub error: Option<String>,
+    pub locked_keys: Vec<String>,
+    pub allowed_providers: Option<Vec<LlmProviderKind>>,
+    pub local_only: bool,
+    pub telemetry: bool,
+}
+
+pub fn policy_status() -> PolicyStatus {
+    let policy = policy();
+    PolicyStatus {
+        managed: POLICY.is_some(),
+        path: policy_path().to_string_lossy().to_string(),
+        error: POLICY
+            .as_ref()
+            .and_then(|policy| policy.as_ref().err().cloned()),
+        locked_keys: policy
+            .map(|policy| policy.locks().into_iter().map(|(key, _)| key).collect())
+            .unwrap_or_default(),
+        allowed_providers: policy.and_then(Policy::allowed),
+        local_only: policy_forces_local_only(),
+        telemetry: telemetry_allowed(),
+    }
+}
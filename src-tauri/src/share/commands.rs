@@ -0,0 +1,118 @@
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+use tracing::warn;
+
+use super::{
+    content_type, s3, upload_custom, upload_imgur, ShareTarget, CUSTOM_KEY_ID, IMGUR_KEY_ID,
+    S3_KEY_ID,
+};
+use crate::secrets::get_api_key;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedCapture {
+    pub target: ShareTarget,
+    pub url: String,
+    // Unix milliseconds; None for links that don't expire
+    pub expires_at: Option<i64>,
+    // False when copying is off or the clipboard couldn't be written
+    pub copied: bool,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn copy_link(url: &str) -> bool {
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(url)) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Failed to copy share link: {}", e);
+            false
+        }
+    }
+}
+
+/// Uploads saved capture `capture_id` to `target` and returns a link to it,
+/// which is also copied to the clipboard unless that is turned off
+#[tauri::command]
+#[specta::specta]
+pub async fn share_capture(
+    app: AppHandle,
+    capture_id: String,
+    target: ShareTarget,
+) -> Result<SharedCapture, String> {
+    crate::network::ensure_online("Sharing captures")?;
+    let settings = crate::settings::current_settings(&app)?.share;
+    let path = crate::captures::capture_path(&app, &capture_id)?;
+    let bytes = crate::storage::read_file(&path)?;
+    content_type(&bytes)?;
+    crate::analytics::count("share");
+
+    let now = now_ms();
+    let (url, expires_at) = match target {
+        ShareTarget::S3 => {
+            let s3_settings = &settings.s3;
+            if [
+                &s3_settings.endpoint,
+                &s3_settings.bucket,
+                &s3_settings.access_key_id,
+            ]
+            .iter()
+            .any(|value| value.trim().is_empty())
+            {
+                return Err("Set up an S3 bucket in settings first".to_string());
+            }
+            let secret =
+                get_api_key(S3_KEY_ID)?.ok_or("Add the S3 secret access key in settings first")?;
+            // A random folder keeps links from being guessed
+            let key = format!(
+                "{}{}/{}",
+                s3_settings.prefix.trim(),
+                uuid::Uuid::new_v4(),
+                capture_id
+            );
+            s3::put_object(s3_settings, &secret, &key, bytes, now).await?;
+            match s3_settings
+                .public_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+            {
+                Some(base) => (s3::public_url(base, &key), None),
+                None => (
+                    s3::presigned_url(s3_settings, &secret, &key, s3_settings.link_ttl_secs, now)?,
+                    Some(now + i64::from(s3_settings.link_ttl_secs) * 1000),
+                ),
+            }
+        }
+        ShareTarget::Imgur => {
+            let client_id =
+                get_api_key(IMGUR_KEY_ID)?.ok_or("Add an Imgur client id in settings first")?;
+            (upload_imgur(&client_id, &capture_id, bytes).await?, None)
+        }
+        ShareTarget::Custom => {
+            let authorization = get_api_key(CUSTOM_KEY_ID)?;
+            let url = upload_custom(
+                &settings.custom,
+                authorization.as_deref(),
+                &capture_id,
+                bytes,
+            )
+            .await?;
+            (url, None)
+        }
+    };
+
+    let copied = settings.copy_link && copy_link(&url);
+    Ok(SharedCapture {
+        target,
+        url,
+        expires_at,
+        copied,
+    })
+}
@@ -0,0 +1,252 @@
+// Pluely capture sharing. A saved capture is uploaded to a destination the
+// user configured (an S3-compatible bucket, Imgur, or any endpoint taking a
+// PUT) and the link to it is copied to the clipboard. Each destination's
+// credentials live in the keychain.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+
+mod commands;
+mod s3;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain entries, one per destination
+pub const S3_KEY_ID: &str = "share_s3";
+pub const IMGUR_KEY_ID: &str = "share_imgur";
+pub const CUSTOM_KEY_ID: &str = "share_custom";
+
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+// The longest a presigned S3 link can last
+const MAX_LINK_TTL_SECS: u32 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareTarget {
+    S3,
+    Imgur,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct S3ShareSettings {
+    // Such as https://s3.us-east-1.amazonaws.com, or an R2 or MinIO endpoint
+    pub endpoint: String,
+    // "auto" for R2
+    pub region: String,
+    pub bucket: String,
+    // Prepended to object keys
+    pub prefix: String,
+    // Bucket in the path rather than the host name, as MinIO expects
+    pub path_style: bool,
+    pub access_key_id: String,
+    // Written to the keychain, never kept in the config; empty removes it
+    pub secret_access_key: Option<String>,
+    // Where the bucket is served publicly. Without one, links are presigned
+    // and stop working after `link_ttl_secs`.
+    pub public_url: Option<String>,
+    pub link_ttl_secs: u32,
+}
+
+impl Default for S3ShareSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            prefix: "pluely/".to_string(),
+            path_style: false,
+            access_key_id: String::new(),
+            secret_access_key: None,
+            public_url: None,
+            link_ttl_secs: MAX_LINK_TTL_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImgurShareSettings {
+    // An Imgur app's client id. Written to the keychain; empty removes it.
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomShareSettings {
+    // Where the image is PUT; `{name}` becomes its file name
+    pub url: String,
+    // Sent as the Authorization header. Written to the keychain; empty
+    // removes it.
+    pub authorization: Option<String>,
+    // The link to hand out, with `{name}`. Without one, the response's
+    // Location header or a URL in its body is used, then the upload URL.
+    pub public_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShareSettings {
+    pub s3: S3ShareSettings,
+    pub imgur: ImgurShareSettings,
+    pub custom: CustomShareSettings,
+    pub copy_link: bool,
+}
+
+impl Default for ShareSettings {
+    fn default() -> Self {
+        Self {
+            s3: S3ShareSettings::default(),
+            imgur: ImgurShareSettings::default(),
+            custom: CustomShareSettings::default(),
+            copy_link: true,
+        }
+    }
+}
+
+fn validate_url(url: &str, what: &str) -> Result<(), String> {
+    let parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid {} {}: {}", what, url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Invalid {} {}: expected http or https", what, url));
+    }
+    Ok(())
+}
+
+// Settings left empty are fine; a destination is only checked once it is
+// filled in
+impl ShareSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.s3.endpoint.trim().is_empty() {
+            validate_url(&self.s3.endpoint, "S3 endpoint")?;
+        }
+        if let Some(url) = self
+            .s3
+            .public_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+        {
+            validate_url(url, "S3 public URL")?;
+        }
+        if self.s3.link_ttl_secs == 0 || self.s3.link_ttl_secs > MAX_LINK_TTL_SECS {
+            return Err(format!(
+                "Invalid S3 link lifetime: must be 1-{} seconds",
+                MAX_LINK_TTL_SECS
+            ));
+        }
+        if !self.custom.url.trim().is_empty() {
+            validate_url(&self.custom.url, "upload URL")?;
+        }
+        if let Some(url) = self
+            .custom
+            .public_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+        {
+            validate_url(url, "share URL")?;
+        }
+        Ok(())
+    }
+}
+
+// Validates the section and moves credentials into the keychain
+pub fn apply_share_settings(settings: &ShareSettings) -> Result<(), String> {
+    settings.validate()?;
+    for (key_id, secret) in [
+        (S3_KEY_ID, &settings.s3.secret_access_key),
+        (IMGUR_KEY_ID, &settings.imgur.client_id),
+        (CUSTOM_KEY_ID, &settings.custom.authorization),
+    ] {
+        if let Some(secret) = secret {
+            crate::secrets::store_api_key(key_id, secret)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn content_type(bytes: &[u8]) -> Result<&'static str, String> {
+    match crate::captures::extension_for(bytes) {
+        "png" => Ok("image/png"),
+        "jpg" => Ok("image/jpeg"),
+        _ => Err("Only screenshots can be shared".to_string()),
+    }
+}
+
+async fn upload_imgur(client_id: &str, name: &str, bytes: Vec<u8>) -> Result<String, String> {
+    let part = reqwest::multipart::Part::bytes(bytes).file_name(name.to_string());
+    let request = crate::network::http_client()?
+        .post("https://api.imgur.com/3/image")
+        .header(
+            reqwest::header::AUTHORIZATION,
+            format!("Client-ID {}", client_id),
+        )
+        .timeout(UPLOAD_TIMEOUT)
+        .multipart(reqwest::multipart::Form::new().part("image", part));
+    let response = crate::network::send(request, "share", &[])
+        .await
+        .map_err(|e| format!("Failed to upload to Imgur: {}", e))?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from Imgur: {}", e))?;
+    match body["data"]["link"].as_str() {
+        Some(link) if status.is_success() => Ok(link.to_string()),
+        _ => Err(format!(
+            "Imgur returned {}: {}",
+            status,
+            body["data"]["error"].as_str().unwrap_or("no link")
+        )),
+    }
+}
+
+async fn upload_custom(
+    settings: &CustomShareSettings,
+    authorization: Option<&str>,
+    name: &str,
+    bytes: Vec<u8>,
+) -> Result<String, String> {
+    if settings.url.trim().is_empty() {
+        return Err("Set an upload URL in settings first".to_string());
+    }
+    let url = settings.url.trim().replace("{name}", name);
+    let mut request = crate::network::http_client()?
+        .put(&url)
+        .header(reqwest::header::CONTENT_TYPE, content_type(&bytes)?)
+        .timeout(UPLOAD_TIMEOUT)
+        .body(bytes);
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+    let response = crate::network::send(request, "share", &[])
+        .await
+        .map_err(|e| format!("Failed to upload capture: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Upload endpoint returned {}", response.status()));
+    }
+
+    if let Some(link) = settings
+        .public_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+    {
+        return Ok(link.replace("{name}", name));
+    }
+    // Locations may be relative to the upload URL
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|location| reqwest::Url::parse(&url).ok()?.join(location).ok());
+    if let Some(location) = location {
+        return Ok(location.to_string());
+    }
+    let body = response.text().await.unwrap_or_default();
+    match reqwest::Url::parse(body.trim()) {
+        Ok(link) if matches!(link.scheme(), "http" | "https") => Ok(link.to_string()),
+        _ => Ok(url),
+    }
+}
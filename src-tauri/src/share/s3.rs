@@ -0,0 +1,185 @@
+// Uploads to S3-compatible storage (AWS, R2, MinIO, B2 and the like), signed
+// with AWS Signature Version 4
+use sha2::{Digest, Sha256};
+
+use super::{content_type, S3ShareSettings, UPLOAD_TIMEOUT};
+use crate::crypto::{mac, to_hex};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+// Percent-encoding as SigV4 canonicalizes it; `/` is kept in paths
+fn uri_encode(text: &str, keep_slash: bool) -> String {
+    let mut out = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+struct Object {
+    scheme: String,
+    // With a port when it isn't the scheme's default
+    host: String,
+    // Encoded
+    path: String,
+}
+
+fn object(settings: &S3ShareSettings, key: &str) -> Result<Object, String> {
+    let endpoint = reqwest::Url::parse(settings.endpoint.trim())
+        .map_err(|e| format!("Invalid S3 endpoint: {}", e))?;
+    let host = endpoint.host_str().ok_or("Invalid S3 endpoint: no host")?;
+    let host = match endpoint.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    let bucket = settings.bucket.trim();
+    let key = uri_encode(key, true);
+    let (host, path) = if settings.path_style {
+        (host, format!("/{}/{}", uri_encode(bucket, false), key))
+    } else {
+        (format!("{}.{}", bucket, host), format!("/{}", key))
+    };
+    Ok(Object {
+        scheme: endpoint.scheme().to_string(),
+        host,
+        path,
+    })
+}
+
+// 20240501T093000Z and 20240501 for a time in unix milliseconds
+fn amz_dates(unix_ms: i64) -> (String, String) {
+    let iso = crate::captures::iso_date(unix_ms).replace(['-', ':'], "");
+    let date = iso[..8].to_string();
+    (iso, date)
+}
+
+fn signature(secret: &str, date: &str, region: &str, string_to_sign: &str) -> String {
+    let key = mac(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = mac(&key, region.as_bytes());
+    let key = mac(&key, b"s3");
+    let key = mac(&key, b"aws4_request");
+    to_hex(&mac(&key, string_to_sign.as_bytes()))
+}
+
+fn string_to_sign(amz_date: &str, scope: &str, canonical_request: &str) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        amz_date,
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    )
+}
+
+// PUTs `bytes` as object `key`
+pub async fn put_object(
+    settings: &S3ShareSettings,
+    secret: &str,
+    key: &str,
+    bytes: Vec<u8>,
+    now_ms: i64,
+) -> Result<(), String> {
+    let object = object(settings, key)?;
+    let region = settings.region.trim();
+    let (amz_date, date) = amz_dates(now_ms);
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let payload_hash = sha256_hex(&bytes);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        object.path, object.host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let authorization = format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM,
+        settings.access_key_id.trim(),
+        scope,
+        signed_headers,
+        signature(
+            secret,
+            &date,
+            region,
+            &string_to_sign(&amz_date, &scope, &canonical_request)
+        )
+    );
+
+    let request = crate::network::http_client()?
+        .put(format!(
+            "{}://{}{}",
+            object.scheme, object.host, object.path
+        ))
+        .header(reqwest::header::AUTHORIZATION, authorization)
+        .header(reqwest::header::CONTENT_TYPE, content_type(&bytes)?)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", amz_date)
+        .timeout(UPLOAD_TIMEOUT)
+        .body(bytes);
+    let response = crate::network::send(request, "share", &[])
+        .await
+        .map_err(|e| format!("Failed to upload to S3: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        // Errors are XML with the reason in <Message>
+        let reason = body
+            .split_once("<Message>")
+            .and_then(|(_, rest)| rest.split_once("</Message>"))
+            .map(|(reason, _)| reason);
+        return Err(match reason {
+            Some(reason) => format!("S3 returned {}: {}", status, reason),
+            None => format!("S3 returned {}", status),
+        });
+    }
+    Ok(())
+}
+
+// A GET link to object `key` that works for `ttl_secs` without credentials
+pub fn presigned_url(
+    settings: &S3ShareSettings,
+    secret: &str,
+    key: &str,
+    ttl_secs: u32,
+    now_ms: i64,
+) -> Result<String, String> {
+    let object = object(settings, key)?;
+    let region = settings.region.trim();
+    let (amz_date, date) = amz_dates(now_ms);
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    // Already in sorted order, as the canonical request needs
+    let query = format!(
+        "X-Amz-Algorithm={}&X-Amz-Credential={}&X-Amz-Date={}&X-Amz-Expires={}&X-Amz-SignedHeaders=host",
+        ALGORITHM,
+        uri_encode(&format!("{}/{}", settings.access_key_id.trim(), scope), false),
+        amz_date,
+        ttl_secs
+    );
+    let canonical_request = format!(
+        "GET\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD",
+        object.path, query, object.host
+    );
+    let signature = signature(
+        secret,
+        &date,
+        region,
+        &string_to_sign(&amz_date, &scope, &canonical_request),
+    );
+    Ok(format!(
+        "{}://{}{}?{}&X-Amz-Signature={}",
+        object.scheme, object.host, object.path, query, signature
+    ))
+}
+
+// Link to object `key` where the bucket is served publicly
+pub fn public_url(base: &str, key: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), uri_encode(key, true))
+}
@@ -1,15 +1,22 @@
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use specta::Type;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 use tokio::time::{sleep, Duration};
+use tracing::{debug, warn};
 
 #[cfg(target_os = "macos")]
 use tauri_nspanel::ManagerExt;
 
+#[cfg(target_os = "windows")]
+use crate::events::ToggleWindowVisibility;
+use crate::events::{
+    CustomShortcutTriggered, FocusTextInput, HotkeysPaused, ShortcutRegistrationError,
+    StartAudioRecording, ToggleSystemAudio, TriggerScreenshot,
+};
 use crate::window::create_dashboard_window;
 // State for window visibility
 pub struct WindowVisibility {
@@ -20,12 +27,15 @@ pub struct WindowVisibility {
 // State for registered shortcuts
 pub struct RegisteredShortcuts {
     pub shortcuts: Mutex<HashMap<String, String>>, // action_id -> shortcut_key
+    // Bindings stay in `shortcuts` but aren't registered with the OS while paused
+    pub paused: AtomicBool,
 }
 
 impl Default for RegisteredShortcuts {
     fn default() -> Self {
         RegisteredShortcuts {
             shortcuts: Mutex::new(HashMap::new()),
+            paused: AtomicBool::new(false),
         }
     }
 }
@@ -52,6 +62,12 @@ impl LicenseState {
     }
 }
 
+// Whether the main window lets mouse events through to the app underneath
+#[derive(Default)]
+pub struct ClickThroughState {
+    enabled: AtomicBool,
+}
+
 pub(crate) type MoveWindowTask = Arc<AtomicBool>;
 
 pub(crate) struct MoveWindowState {
@@ -66,16 +82,19 @@ impl Default for MoveWindowState {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ShortcutBinding {
     pub action: String,
     pub key: String,
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ShortcutsConfig {
     pub bindings: HashMap<String, ShortcutBinding>,
+    // Actions the user added; only the frontend reads them
+    #[serde(default, rename = "customActions")]
+    pub custom_actions: Vec<serde_json::Value>,
 }
 
 /// Initialize global shortcuts for the application
@@ -87,17 +106,17 @@ pub fn setup_global_shortcuts<R: Runtime>(
     let _registered = match state.shortcuts.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
-            eprintln!("Mutex poisoned in setup, recovering...");
+            warn!("Mutex poisoned in setup, recovering...");
             poisoned.into_inner()
         }
     };
-    eprintln!("Global shortcuts state initialized, waiting for frontend config");
+    debug!("Global shortcuts state initialized, waiting for frontend config");
 
     Ok(())
 }
 
 /// Handle shortcut action based on action_id
-pub fn handle_shortcut_action<R: Runtime>(app: &AppHandle<R>, action_id: &str) {
+pub fn handle_shortcut_action(app: &AppHandle, action_id: &str) {
     match action_id {
         "toggle_dashboard" => handle_toggle_dashboard(app),
         "toggle_window" => handle_toggle_window(app),
@@ -109,14 +128,22 @@ pub fn handle_shortcut_action<R: Runtime>(app: &AppHandle<R>, action_id: &str) {
         "audio_recording" => handle_audio_shortcut(app),
         "screenshot" => handle_screenshot_shortcut(app),
         "system_audio" => handle_system_audio_shortcut(app),
+        "toggle_click_through" => handle_toggle_click_through(app),
+        "cycle_window_mode" => {
+            if let Err(e) = crate::placement::cycle_mode(app) {
+                warn!("Failed to switch window mode: {}", e);
+            }
+        }
         custom_action => {
             // Emit custom action event for frontend to handle
             if let Some(window) = app.get_webview_window("main") {
                 if let Err(e) = window.emit(
                     "custom-shortcut-triggered",
-                    json!({ "action": custom_action }),
+                    CustomShortcutTriggered {
+                        action: custom_action.to_string(),
+                    },
                 ) {
-                    eprintln!("Failed to emit custom shortcut event: {}", e);
+                    warn!("Failed to emit custom shortcut event: {}", e);
                 }
             }
         }
@@ -187,19 +214,25 @@ fn handle_toggle_window<R: Runtime>(app: &AppHandle<R>) {
         let mut is_hidden = state.is_hidden.lock().unwrap();
         *is_hidden = !*is_hidden;
 
-        if let Err(e) = window.emit("toggle-window-visibility", *is_hidden) {
-            eprintln!("Failed to emit toggle-window-visibility event: {}", e);
+        if let Err(e) = window.emit(
+            "toggle-window-visibility",
+            ToggleWindowVisibility(*is_hidden),
+        ) {
+            warn!("Failed to emit toggle-window-visibility event: {}", e);
         }
 
         if !*is_hidden {
+            if let Err(e) = crate::placement::move_to_cursor(&window) {
+                warn!("{}", e);
+            }
             if let Err(e) = window.show() {
-                eprintln!("Failed to show window: {}", e);
+                warn!("Failed to show window: {}", e);
             }
             if let Err(e) = window.set_focus() {
-                eprintln!("Failed to focus window: {}", e);
+                warn!("Failed to focus window: {}", e);
             }
-            if let Err(e) = window.emit("focus-text-input", json!({})) {
-                eprintln!("Failed to emit focus-text-input event: {}", e);
+            if let Err(e) = window.emit("focus-text-input", FocusTextInput {}) {
+                warn!("Failed to emit focus-text-input event: {}", e);
             }
         }
         return;
@@ -215,17 +248,20 @@ fn handle_toggle_window<R: Runtime>(app: &AppHandle<R>) {
             }
             // Window is visible, hide it and handle app icon based on user settings
             if let Err(e) = window.hide() {
-                eprintln!("Failed to hide window: {}", e);
+                warn!("Failed to hide window: {}", e);
             }
         }
         Ok(false) => {
+            if let Err(e) = crate::placement::move_to_cursor(&window) {
+                warn!("{}", e);
+            }
             // Window is hidden, show it and handle app icon based on user settings
             if let Err(e) = window.show() {
-                eprintln!("Failed to show window: {}", e);
+                warn!("Failed to show window: {}", e);
             }
 
             if let Err(e) = window.set_focus() {
-                eprintln!("Failed to focus window: {}", e);
+                warn!("Failed to focus window: {}", e);
             }
 
             #[cfg(target_os = "macos")]
@@ -234,10 +270,10 @@ fn handle_toggle_window<R: Runtime>(app: &AppHandle<R>) {
                 panel.show();
             }
             // Emit event to focus text input
-            window.emit("focus-text-input", json!({})).unwrap();
+            window.emit("focus-text-input", FocusTextInput {}).unwrap();
         }
         Err(e) => {
-            eprintln!("Failed to check window visibility: {}", e);
+            warn!("Failed to check window visibility: {}", e);
         }
     }
 }
@@ -251,13 +287,13 @@ fn handle_audio_shortcut<R: Runtime>(app: &AppHandle<R>) {
                 return;
             }
             if let Err(e) = window.set_focus() {
-                eprintln!("Failed to focus window: {}", e);
+                warn!("Failed to focus window: {}", e);
             }
         }
 
         // Emit event to start audio recording
-        if let Err(e) = window.emit("start-audio-recording", json!({})) {
-            eprintln!("Failed to emit audio recording event: {}", e);
+        if let Err(e) = window.emit("start-audio-recording", StartAudioRecording {}) {
+            warn!("Failed to emit audio recording event: {}", e);
         }
     }
 }
@@ -266,8 +302,8 @@ fn handle_audio_shortcut<R: Runtime>(app: &AppHandle<R>) {
 fn handle_screenshot_shortcut<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window("main") {
         // Emit event to trigger screenshot - frontend will determine auto/manual mode
-        if let Err(e) = window.emit("trigger-screenshot", json!({})) {
-            eprintln!("Failed to emit screenshot event: {}", e);
+        if let Err(e) = window.emit("trigger-screenshot", TriggerScreenshot {}) {
+            warn!("Failed to emit screenshot event: {}", e);
         }
     }
 }
@@ -278,23 +314,24 @@ fn handle_system_audio_shortcut<R: Runtime>(app: &AppHandle<R>) {
         // Ensure window is visible
         if let Ok(false) = window.is_visible() {
             if let Err(e) = window.show() {
-                eprintln!("Failed to show window: {}", e);
+                warn!("Failed to show window: {}", e);
                 return;
             }
             if let Err(e) = window.set_focus() {
-                eprintln!("Failed to focus window: {}", e);
+                warn!("Failed to focus window: {}", e);
             }
         }
 
         // Emit event to toggle system audio capture - frontend will determine current state
-        if let Err(e) = window.emit("toggle-system-audio", json!({})) {
-            eprintln!("Failed to emit system audio event: {}", e);
+        if let Err(e) = window.emit("toggle-system-audio", ToggleSystemAudio {}) {
+            warn!("Failed to emit system audio event: {}", e);
         }
     }
 }
 
 /// Tauri command to get all registered shortcuts
 #[tauri::command]
+#[specta::specta]
 pub fn get_registered_shortcuts<R: Runtime>(
     app: AppHandle<R>,
 ) -> Result<HashMap<String, String>, String> {
@@ -302,7 +339,7 @@ pub fn get_registered_shortcuts<R: Runtime>(
     let registered = match state.shortcuts.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
-            eprintln!("Mutex poisoned in get_registered_shortcuts, recovering...");
+            warn!("Mutex poisoned in get_registered_shortcuts, recovering...");
             poisoned.into_inner()
         }
     };
@@ -311,11 +348,12 @@ pub fn get_registered_shortcuts<R: Runtime>(
 
 /// Tauri command to update shortcuts dynamically
 #[tauri::command]
+#[specta::specta]
 pub fn update_shortcuts<R: Runtime>(
     app: AppHandle<R>,
     config: ShortcutsConfig,
 ) -> Result<(), String> {
-    eprintln!("Updating shortcuts with {} bindings", config.bindings.len());
+    debug!("Updating shortcuts with {} bindings", config.bindings.len());
 
     let mut shortcuts_to_register = Vec::new();
 
@@ -336,7 +374,7 @@ pub fn update_shortcuts<R: Runtime>(
                             shortcuts_to_register.push((direction_action_id, full_key, shortcut));
                         }
                         Err(e) => {
-                            eprintln!("Invalid shortcut '{}' for move_window: {}", full_key, e);
+                            warn!("Invalid shortcut '{}' for move_window: {}", full_key, e);
                             return Err(format!(
                                 "Invalid shortcut '{}' for move_window: {}",
                                 full_key, e
@@ -353,7 +391,7 @@ pub fn update_shortcuts<R: Runtime>(
                     shortcuts_to_register.push((action_id.clone(), binding.key.clone(), shortcut));
                 }
                 Err(e) => {
-                    eprintln!(
+                    warn!(
                         "Invalid shortcut '{}' for action '{}': {}",
                         binding.key, action_id, e
                     );
@@ -377,14 +415,23 @@ pub fn update_shortcuts<R: Runtime>(
 
     let mut registration_failures: Vec<(String, String, String)> = Vec::new();
 
+    let paused = app
+        .state::<RegisteredShortcuts>()
+        .paused
+        .load(Ordering::SeqCst);
     for (action_id, shortcut_str, shortcut) in shortcuts_to_register {
+        // Remembered for when hotkeys are resumed
+        if paused {
+            successfully_registered.insert(action_id, shortcut_str);
+            continue;
+        }
         match app.global_shortcut().register(shortcut) {
             Ok(_) => {
-                eprintln!("Registered shortcut: {} -> {}", action_id, shortcut_str);
+                debug!("Registered shortcut: {} -> {}", action_id, shortcut_str);
                 successfully_registered.insert(action_id, shortcut_str);
             }
             Err(e) => {
-                eprintln!("Failed to register {} shortcut: {}", action_id, e);
+                warn!("Failed to register {} shortcut: {}", action_id, e);
                 registration_failures.push((action_id, shortcut_str, e.to_string()));
             }
         }
@@ -396,7 +443,7 @@ pub fn update_shortcuts<R: Runtime>(
         let mut registered = match state.shortcuts.lock() {
             Ok(guard) => guard,
             Err(poisoned) => {
-                eprintln!("Mutex poisoned in update_shortcuts, recovering...");
+                warn!("Mutex poisoned in update_shortcuts, recovering...");
                 poisoned.into_inner()
             }
         };
@@ -407,8 +454,11 @@ pub fn update_shortcuts<R: Runtime>(
 
     if !registration_failures.is_empty() {
         if let Some(window) = app.get_webview_window("main") {
-            if let Err(e) = window.emit("shortcut-registration-error", &registration_failures) {
-                eprintln!("Failed to emit shortcut registration error event: {}", e);
+            if let Err(e) = window.emit(
+                "shortcut-registration-error",
+                ShortcutRegistrationError(registration_failures.clone()),
+            ) {
+                warn!("Failed to emit shortcut registration error event: {}", e);
             }
         }
 
@@ -432,7 +482,7 @@ fn unregister_all_shortcuts<R: Runtime>(app: &AppHandle<R>) -> Result<(), String
     let registered = match state.shortcuts.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
-            eprintln!("Mutex poisoned in unregister_all_shortcuts, recovering...");
+            warn!("Mutex poisoned in unregister_all_shortcuts, recovering...");
             poisoned.into_inner()
         }
     };
@@ -441,26 +491,76 @@ fn unregister_all_shortcuts<R: Runtime>(app: &AppHandle<R>) -> Result<(), String
         if let Ok(shortcut) = shortcut_str.parse::<Shortcut>() {
             match app.global_shortcut().unregister(shortcut) {
                 Ok(_) => {
-                    eprintln!("Unregistered shortcut: {} -> {}", action_id, shortcut_str);
+                    debug!("Unregistered shortcut: {} -> {}", action_id, shortcut_str);
                 }
                 Err(e) => {
-                    eprintln!("Failed to unregister shortcut {}: {}", shortcut_str, e);
+                    warn!("Failed to unregister shortcut {}: {}", shortcut_str, e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pause or resume every global shortcut without forgetting the bindings
+pub fn set_shortcuts_paused<R: Runtime>(app: &AppHandle<R>, paused: bool) -> Result<(), String> {
+    let state = app.state::<RegisteredShortcuts>();
+    if state.paused.swap(paused, Ordering::SeqCst) == paused {
+        return Ok(());
+    }
+
+    if paused {
+        stop_all_move_windows(app);
+        unregister_all_shortcuts(app)?;
+    } else {
+        let registered = match state.shortcuts.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                warn!("Mutex poisoned in set_shortcuts_paused, recovering...");
+                poisoned.into_inner().clone()
+            }
+        };
+        for (action_id, shortcut_str) in registered {
+            if let Ok(shortcut) = shortcut_str.parse::<Shortcut>() {
+                if let Err(e) = app.global_shortcut().register(shortcut) {
+                    warn!("Failed to register {} shortcut: {}", action_id, e);
                 }
             }
         }
     }
 
+    if let Err(e) = app.emit("hotkeys-paused", HotkeysPaused(paused)) {
+        warn!("Failed to emit hotkeys-paused event: {}", e);
+    }
     Ok(())
 }
 
+/// Tauri command to pause or resume global shortcuts
+#[tauri::command]
+#[specta::specta]
+pub fn set_hotkeys_paused<R: Runtime>(app: AppHandle<R>, paused: bool) -> Result<(), String> {
+    set_shortcuts_paused(&app, paused)
+}
+
+/// Tauri command to check if global shortcuts are paused
+#[tauri::command]
+#[specta::specta]
+pub fn get_hotkeys_paused<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<RegisteredShortcuts>()
+        .paused
+        .load(Ordering::SeqCst)
+}
+
 /// Tauri command to check if shortcuts are registered
 #[tauri::command]
+#[specta::specta]
 pub fn check_shortcuts_registered<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
     let state = app.state::<RegisteredShortcuts>();
     let registered = match state.shortcuts.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
-            eprintln!("Mutex poisoned in check_shortcuts_registered, recovering...");
+            warn!("Mutex poisoned in check_shortcuts_registered, recovering...");
             poisoned.into_inner()
         }
     };
@@ -469,17 +569,19 @@ pub fn check_shortcuts_registered<R: Runtime>(app: AppHandle<R>) -> Result<bool,
 
 /// Tauri command to validate shortcut key
 #[tauri::command]
+#[specta::specta]
 pub fn validate_shortcut_key(key: String) -> Result<bool, String> {
     match key.parse::<Shortcut>() {
         Ok(_) => Ok(true),
         Err(e) => {
-            eprintln!("Invalid shortcut '{}': {}", key, e);
+            warn!("Invalid shortcut '{}': {}", key, e);
             Ok(false)
         }
     }
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_license_status<R: Runtime>(app: AppHandle<R>, has_license: bool) -> Result<(), String> {
     {
         let state = app.state::<LicenseState>();
@@ -493,6 +595,7 @@ pub fn set_license_status<R: Runtime>(app: AppHandle<R>, has_license: bool) -> R
 
 /// Tauri command to set app icon visibility in dock/taskbar
 #[tauri::command]
+#[specta::specta]
 pub fn set_app_icon_visibility<R: Runtime>(app: AppHandle<R>, visible: bool) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
@@ -504,7 +607,7 @@ pub fn set_app_icon_visibility<R: Runtime>(app: AppHandle<R>, visible: bool) ->
         };
 
         app.set_activation_policy(policy).map_err(|e| {
-            eprintln!("Failed to set activation policy: {}", e);
+            warn!("Failed to set activation policy: {}", e);
             format!("Failed to set activation policy: {}", e)
         })?;
     }
@@ -517,7 +620,7 @@ pub fn set_app_icon_visibility<R: Runtime>(app: AppHandle<R>, visible: bool) ->
                 .set_skip_taskbar(!visible)
                 .map_err(|e| format!("Failed to set taskbar visibility: {}", e))?;
         } else {
-            eprintln!("Main window not found on Windows");
+            warn!("Main window not found on Windows");
         }
     }
 
@@ -529,7 +632,7 @@ pub fn set_app_icon_visibility<R: Runtime>(app: AppHandle<R>, visible: bool) ->
                 .set_skip_taskbar(!visible)
                 .map_err(|e| format!("Failed to set panel visibility: {}", e))?;
         } else {
-            eprintln!("Main window not found on Linux");
+            warn!("Main window not found on Linux");
         }
     }
 
@@ -538,6 +641,7 @@ pub fn set_app_icon_visibility<R: Runtime>(app: AppHandle<R>, visible: bool) ->
 
 /// Tauri command to set always on top state
 #[tauri::command]
+#[specta::specta]
 pub fn set_always_on_top<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("main") {
         window
@@ -550,6 +654,45 @@ pub fn set_always_on_top<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result
     Ok(())
 }
 
+/// Tauri command to let clicks pass through the main window. The window can't
+/// be clicked while enabled, so the `toggle_click_through` hotkey turns it off.
+#[tauri::command]
+#[specta::specta]
+pub fn set_click_through<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window
+        .set_ignore_cursor_events(enabled)
+        .map_err(|e| format!("Failed to set click-through: {}", e))?;
+    app.state::<ClickThroughState>()
+        .enabled
+        .store(enabled, Ordering::SeqCst);
+    if let Err(e) = window.emit(
+        "click-through-changed",
+        crate::events::ClickThroughChanged::new(enabled),
+    ) {
+        warn!("Failed to emit click-through change: {}", e);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_click_through<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<ClickThroughState>()
+        .enabled
+        .load(Ordering::SeqCst)
+}
+
+/// Handle click-through shortcut
+fn handle_toggle_click_through<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = !get_click_through(app.clone());
+    if let Err(e) = set_click_through(app.clone(), enabled) {
+        warn!("{}", e);
+    }
+}
+
 /// Handle toggle dashboard shortcut
 fn handle_toggle_dashboard<R: Runtime>(app: &AppHandle<R>) {
     use tauri::Manager;
@@ -558,27 +701,27 @@ fn handle_toggle_dashboard<R: Runtime>(app: &AppHandle<R>) {
             Ok(true) => {
                 // Window is visible, hide it
                 if let Err(e) = dashboard_window.hide() {
-                    eprintln!("Failed to hide dashboard window: {}", e);
+                    warn!("Failed to hide dashboard window: {}", e);
                 }
             }
             Ok(false) => {
                 // Window is hidden, show and focus it
                 if let Err(e) = dashboard_window.show() {
-                    eprintln!("Failed to show dashboard window: {}", e);
+                    warn!("Failed to show dashboard window: {}", e);
                 }
                 if let Err(e) = dashboard_window.set_focus() {
-                    eprintln!("Failed to focus dashboard window: {}", e);
+                    warn!("Failed to focus dashboard window: {}", e);
                 }
             }
             Err(e) => {
-                eprintln!("Failed to check dashboard visibility: {}", e);
+                warn!("Failed to check dashboard visibility: {}", e);
             }
         }
     } else {
         // Window doesn't exist, create it
         match create_dashboard_window(app) {
-            Ok(_) => eprintln!("Dashboard window created successfully"),
-            Err(e) => eprintln!("Failed to create dashboard window: {}", e),
+            Ok(_) => debug!("Dashboard window created successfully"),
+            Err(e) => warn!("Failed to create dashboard window: {}", e),
         }
     }
 }
@@ -592,7 +735,7 @@ fn handle_focus_input<R: Runtime>(app: &AppHandle<R>) {
         }
 
         let _ = window.set_focus();
-        let _ = window.emit("focus-text-input", json!({}));
+        let _ = window.emit("focus-text-input", FocusTextInput {});
     }
 }
 
@@ -607,7 +750,7 @@ fn handle_move_window<R: Runtime>(app: &AppHandle<R>, direction: &str) {
                     "left" => (current_pos.x - step, current_pos.y),
                     "right" => (current_pos.x + step, current_pos.y),
                     _ => {
-                        eprintln!("Invalid direction: {}", direction);
+                        warn!("Invalid direction: {}", direction);
                         return;
                     }
                 };
@@ -618,20 +761,21 @@ fn handle_move_window<R: Runtime>(app: &AppHandle<R>, direction: &str) {
                         y: new_y,
                     }))
                 {
-                    eprintln!("Failed to set window position: {}", e);
+                    warn!("Failed to set window position: {}", e);
                 }
             }
             Err(e) => {
-                eprintln!("Failed to get window position: {}", e);
+                warn!("Failed to get window position: {}", e);
             }
         }
     } else {
-        eprintln!("Main window not found");
+        warn!("Main window not found");
     }
 }
 
 /// Tauri command to exit the application
 #[tauri::command]
+#[specta::specta]
 pub fn exit_app(app_handle: tauri::AppHandle) {
     app_handle.exit(0);
 }
@@ -1,10 +1,22 @@
 // Pluely AI Speech Detection, and capture system audio (speaker output) as a stream of f32 samples.
+use crate::events::{
+    AudioEncodingError, CaptureStarted, CaptureStopped, ContinuousRecordingStart,
+    ContinuousRecordingStopped, ManualStopContinuous, RecordingProgress, SpeechDetected,
+    SpeechDiscarded, SpeechEnd, SpeechStart,
+};
+use crate::speaker::denoise::DenoiseStream;
+use crate::speaker::hotswap::HotSwapStream;
+use crate::speaker::meter::LevelMeter;
+use crate::speaker::mic::MicInput;
+use crate::speaker::mixer::{DualCaptureMode, MixedStream};
+use crate::speaker::vad::{SpeechClassifier, VadAggressiveness};
 use crate::speaker::SpeakerInput;
 use anyhow::Result;
 use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
 use futures_util::StreamExt;
 use hound::{WavSpec, WavWriter};
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::VecDeque;
 use std::io::Cursor;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -15,7 +27,7 @@ use tauri_plugin_shell::ShellExt;
 use tracing::{error, warn};
 
 // VAD Configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct VadConfig {
     pub enabled: bool,
     pub hop_size: usize,
@@ -26,6 +38,38 @@ pub struct VadConfig {
     pub pre_speech_chunks: usize,
     pub noise_gate_threshold: f32,
     pub max_recording_duration_secs: u64,
+    // Run the WebRTC classifier on top of the energy gate
+    #[serde(default = "default_use_webrtc")]
+    pub use_webrtc: bool,
+    #[serde(default)]
+    pub webrtc_mode: VadAggressiveness,
+    // Fraction of voiced 10ms frames required for a chunk to count as speech
+    #[serde(default = "default_voiced_ratio")]
+    pub voiced_ratio: f32,
+    // Run RNNoise over the microphone before segmentation
+    #[serde(default)]
+    pub noise_suppression: bool,
+}
+
+fn default_use_webrtc() -> bool {
+    true
+}
+
+fn default_voiced_ratio() -> f32 {
+    0.5
+}
+
+// Mixed mic + system audio is produced at a speech-friendly common rate
+const MIXED_SAMPLE_RATE: u32 = 16_000;
+
+// Payload for the `speech-end` event
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SpeechSegment {
+    // "system", "microphone" or "mixed"
+    pub source: &'static str,
+    pub audio: String,
+    pub sample_rate: u32,
+    pub duration_ms: u64,
 }
 
 impl Default for VadConfig {
@@ -40,17 +84,25 @@ impl Default for VadConfig {
             pre_speech_chunks: 12,  // ~0.27s - enough to catch word start
             noise_gate_threshold: 0.003, // Stronger noise filtering
             max_recording_duration_secs: 180, // 3 minutes default
+            use_webrtc: true,
+            webrtc_mode: VadAggressiveness::Aggressive,
+            voiced_ratio: 0.5,
+            noise_suppression: false,
         }
     }
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn start_system_audio_capture(
     app: AppHandle,
     vad_config: Option<VadConfig>,
     device_id: Option<String>,
+    capture_mode: Option<DualCaptureMode>,
+    mic_device_id: Option<String>,
 ) -> Result<(), String> {
     let state = app.state::<crate::AudioState>();
+    let capture_mode = capture_mode.unwrap_or_default();
 
     // Check if already capturing (atomic check)
     {
@@ -74,12 +126,21 @@ pub async fn start_system_audio_capture(
         *vad_cfg = config;
     }
 
-    let input = SpeakerInput::new_with_device(device_id).map_err(|e| {
+    let input = SpeakerInput::new_with_device(device_id.clone()).map_err(|e| {
         error!("Failed to create speaker input: {}", e);
         format!("Failed to access system audio: {}", e)
     })?;
 
-    let stream = input.stream();
+    // Switches requested for a previous capture don't carry over
+    state.system_device.clear();
+    state.mic_device.clear();
+
+    let stream = HotSwapStream::new(
+        app.clone(),
+        state.system_device.clone(),
+        input.stream(),
+        device_id,
+    );
     let sr = stream.sample_rate();
 
     // Validate sample rate
@@ -91,6 +152,21 @@ pub async fn start_system_audio_capture(
         ));
     }
 
+    let mic_stream = if capture_mode == DualCaptureMode::SystemOnly {
+        None
+    } else {
+        let mic = MicInput::new(mic_device_id.clone()).stream().map_err(|e| {
+            error!("Failed to create microphone input: {}", e);
+            format!("Failed to access microphone: {}", e)
+        })?;
+        Some(HotSwapStream::new(
+            app.clone(),
+            state.mic_device.clone(),
+            mic,
+            mic_device_id,
+        ))
+    };
+
     let app_clone = app.clone();
     let vad_config = state
         .vad_config
@@ -105,14 +181,38 @@ pub async fn start_system_audio_capture(
         .map_err(|e| format!("Failed to set capturing state: {}", e))? = true;
 
     // Emit capture started event
-    let _ = app_clone.emit("capture-started", sr);
+    let started_rate = if capture_mode == DualCaptureMode::Mixed {
+        MIXED_SAMPLE_RATE
+    } else {
+        sr
+    };
+    let _ = app_clone.emit("capture-started", CaptureStarted(started_rate));
 
     let state_clone = app.state::<crate::AudioState>();
     let task = tokio::spawn(async move {
-        if vad_config.enabled {
-            run_vad_capture(app_clone.clone(), stream, sr, vad_config).await;
-        } else {
-            run_continuous_capture(app_clone.clone(), stream, sr, vad_config).await;
+        match (capture_mode, mic_stream) {
+            (DualCaptureMode::Mixed, Some(mic)) => {
+                let mic_rate = mic.sample_rate();
+                let mic = DenoiseStream::new(mic, mic_rate, vad_config.noise_suppression);
+                let mixed = MixedStream::new(mic, mic_rate, stream, sr, MIXED_SAMPLE_RATE);
+                run_capture(app_clone.clone(), mixed, MIXED_SAMPLE_RATE, "mixed", vad_config)
+                    .await;
+            }
+            (DualCaptureMode::Separate, Some(mic)) => {
+                let mic_rate = mic.sample_rate();
+                let mic = DenoiseStream::new(mic, mic_rate, vad_config.noise_suppression);
+                tokio::join!(
+                    run_capture(
+                        app_clone.clone(),
+                        mic,
+                        mic_rate,
+                        "microphone",
+                        vad_config.clone()
+                    ),
+                    run_capture(app_clone.clone(), stream, sr, "system", vad_config),
+                );
+            }
+            _ => run_capture(app_clone.clone(), stream, sr, "system", vad_config).await,
         }
 
         let state = app_clone.state::<crate::AudioState>();
@@ -131,11 +231,26 @@ pub async fn start_system_audio_capture(
     Ok(())
 }
 
+async fn run_capture(
+    app: AppHandle,
+    stream: impl StreamExt<Item = f32> + Unpin,
+    sr: u32,
+    source: &'static str,
+    config: VadConfig,
+) {
+    if config.enabled {
+        run_vad_capture(app, stream, sr, source, config).await;
+    } else {
+        run_continuous_capture(app, stream, sr, source, config).await;
+    }
+}
+
 // VAD-enabled capture - OPTIMIZED for real-time speech detection
 async fn run_vad_capture(
     app: AppHandle,
     stream: impl StreamExt<Item = f32> + Unpin,
     sr: u32,
+    source: &'static str,
     config: VadConfig,
 ) {
     let mut stream = stream;
@@ -147,8 +262,13 @@ async fn run_vad_capture(
     let mut silence_chunks = 0;
     let mut speech_chunks = 0;
     let max_samples = sr as usize * 30; // 30s safety cap per utterance
+    let mut classifier = config
+        .use_webrtc
+        .then(|| SpeechClassifier::new(sr, config.webrtc_mode, config.voiced_ratio));
+    let mut meter = LevelMeter::new(source, sr);
 
     while let Some(sample) = stream.next().await {
+        meter.push(&app, sample);
         buffer.push_back(sample);
 
         // Process in fixed chunks for VAD analysis
@@ -164,7 +284,14 @@ async fn run_vad_capture(
             let mono = apply_noise_gate(&mono, config.noise_gate_threshold);
 
             let (rms, peak) = calculate_audio_metrics(&mono);
-            let is_speech = rms > config.sensitivity_rms || peak > config.peak_threshold;
+            let loud_enough = rms > config.sensitivity_rms || peak > config.peak_threshold;
+
+            // Energy gate first (cheap), then confirm with the classifier so
+            // loud non-speech (typing, music) doesn't open a segment
+            let is_speech = match classifier.as_mut() {
+                Some(classifier) if loud_enough => classifier.is_speech(&mono),
+                _ => loud_enough,
+            };
 
             if is_speech {
                 if !in_speech {
@@ -175,7 +302,7 @@ async fn run_vad_capture(
                     // Include pre-speech buffer for natural sound
                     speech_buffer.extend(pre_speech.drain(..));
 
-                    let _ = app.emit("speech-start", ());
+                    let _ = app.emit("speech-start", SpeechStart);
                 }
 
                 speech_chunks += 1;
@@ -184,11 +311,7 @@ async fn run_vad_capture(
 
                 // Safety cap: force emit if exceeds 30s
                 if speech_buffer.len() > max_samples {
-                    let normalized_buffer = normalize_audio_level(&speech_buffer, 0.1);
-                    if let Ok(b64) = samples_to_wav_b64(sr, &normalized_buffer) {
-                        // let duration = speech_buffer.len() as f32 / sr as f32;
-                        let _ = app.emit("speech-detected", b64);
-                    }
+                    emit_speech_segment(&app, source, sr, &speech_buffer);
                     speech_buffer.clear();
                     in_speech = false;
                     speech_chunks = 0;
@@ -216,18 +339,13 @@ async fn run_vad_capture(
                             }
 
                             // Emit complete speech segment
-                            let normalized_buffer = normalize_audio_level(&speech_buffer, 0.1);
-                            if let Ok(b64) = samples_to_wav_b64(sr, &normalized_buffer) {
-                                // let duration = speech_buffer.len() as f32 / sr as f32;
-                                let _ = app.emit("speech-detected", b64);
-                            } else {
-                                error!("Failed to encode speech to WAV");
-                                let _ = app.emit("audio-encoding-error", "Failed to encode speech");
-                            }
+                            emit_speech_segment(&app, source, sr, &speech_buffer);
                         } else {
                             let _ = app.emit(
                                 "speech-discarded",
-                                "Audio too short (likely background noise)",
+                                SpeechDiscarded(
+                                    "Audio too short (likely background noise)".to_string(),
+                                ),
                             );
                         }
 
@@ -256,11 +374,37 @@ async fn run_vad_capture(
     }
 }
 
+// Emit a finished utterance as both `speech-end` (segment metadata) and
+// `speech-detected` (bare base64, kept for existing listeners)
+fn emit_speech_segment(app: &AppHandle, source: &'static str, sr: u32, samples: &[f32]) {
+    let normalized_buffer = normalize_audio_level(samples, 0.1);
+    match samples_to_wav_b64(sr, &normalized_buffer) {
+        Ok(b64) => {
+            let segment = SpeechSegment {
+                source,
+                audio: b64.clone(),
+                sample_rate: sr,
+                duration_ms: samples.len() as u64 * 1000 / sr as u64,
+            };
+            let _ = app.emit("speech-end", SpeechEnd(segment));
+            let _ = app.emit("speech-detected", SpeechDetected(b64));
+        }
+        Err(e) => {
+            error!("Failed to encode speech to WAV: {}", e);
+            let _ = app.emit(
+                "audio-encoding-error",
+                AudioEncodingError("Failed to encode speech".to_string()),
+            );
+        }
+    }
+}
+
 // Continuous capture (VAD disabled)
 async fn run_continuous_capture(
     app: AppHandle,
     stream: impl StreamExt<Item = f32> + Unpin,
     sr: u32,
+    source: &'static str,
     config: VadConfig,
 ) {
     let mut stream = stream;
@@ -270,6 +414,7 @@ async fn run_continuous_capture(
     let mut audio_buffer = Vec::with_capacity(max_samples);
     let start_time = Instant::now();
     let max_duration = Duration::from_secs(config.max_recording_duration_secs);
+    let mut meter = LevelMeter::new(source, sr);
 
     // Atomic flag for manual stop
     let stop_flag = Arc::new(AtomicBool::new(false));
@@ -283,7 +428,7 @@ async fn run_continuous_capture(
     // Emit recording started
     let _ = app.emit(
         "continuous-recording-start",
-        config.max_recording_duration_secs,
+        ContinuousRecordingStart(config.max_recording_duration_secs),
     );
 
     // Accumulate audio - check stop flag on EVERY sample for immediate response
@@ -301,13 +446,17 @@ async fn run_continuous_capture(
                             break;
                         }
 
+                        meter.push(&app, sample);
                         audio_buffer.push(sample);
 
                         let elapsed = start_time.elapsed();
 
                         // Emit progress every second
                         if audio_buffer.len() % (sr as usize) == 0 {
-                            let _ = app.emit("recording-progress", elapsed.as_secs());
+                            let _ = app.emit(
+                                "recording-progress",
+                                RecordingProgress(elapsed.as_secs()),
+                            );
                         }
 
                         // Check size limit (safety)
@@ -344,19 +493,22 @@ async fn run_continuous_capture(
 
         match samples_to_wav_b64(sr, &cleaned_audio) {
             Ok(b64) => {
-                let _ = app.emit("speech-detected", b64);
+                let _ = app.emit("speech-detected", SpeechDetected(b64));
             }
             Err(e) => {
                 error!("Failed to encode continuous audio: {}", e);
-                let _ = app.emit("audio-encoding-error", e);
+                let _ = app.emit("audio-encoding-error", AudioEncodingError(e));
             }
         }
     } else {
         warn!("No audio captured in continuous mode");
-        let _ = app.emit("audio-encoding-error", "No audio recorded");
+        let _ = app.emit(
+            "audio-encoding-error",
+            AudioEncodingError("No audio recorded".to_string()),
+        );
     }
 
-    let _ = app.emit("continuous-recording-stopped", ());
+    let _ = app.emit("continuous-recording-stopped", ContinuousRecordingStopped);
 }
 
 // Apply noise gate
@@ -458,7 +610,35 @@ fn samples_to_wav_b64(sample_rate: u32, mono_f32: &[f32]) -> Result<String, Stri
     Ok(B64.encode(cursor.into_inner()))
 }
 
+// Moves the running capture to another device; None selects the system default.
+// `source` is "system" (default) or "microphone".
+#[tauri::command]
+#[specta::specta]
+pub fn set_audio_input(
+    app: AppHandle,
+    device_id: Option<String>,
+    source: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<crate::AudioState>();
+    let capturing = *state
+        .is_capturing
+        .lock()
+        .map_err(|e| format!("Failed to read capturing state: {}", e))?;
+    if !capturing {
+        return Err("No capture running".to_string());
+    }
+
+    match source.as_deref().unwrap_or("system") {
+        "system" => state.system_device.request(device_id),
+        "microphone" => state.mic_device.request(device_id),
+        other => return Err(format!("Unknown audio source: {}", other)),
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
+#[specta::specta]
 pub async fn stop_system_audio_capture(app: AppHandle) -> Result<(), String> {
     let state = app.state::<crate::AudioState>();
 
@@ -487,14 +667,15 @@ pub async fn stop_system_audio_capture(app: AppHandle) -> Result<(), String> {
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
     // Emit stopped event
-    let _ = app.emit("capture-stopped", ());
+    let _ = app.emit("capture-stopped", CaptureStopped);
     Ok(())
 }
 
 /// Manual stop for continuous recording
 #[tauri::command]
+#[specta::specta]
 pub async fn manual_stop_continuous(app: AppHandle) -> Result<(), String> {
-    let _ = app.emit("manual-stop-continuous", ());
+    let _ = app.emit("manual-stop-continuous", ManualStopContinuous);
 
     tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
 
@@ -502,6 +683,7 @@ pub async fn manual_stop_continuous(app: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn check_system_audio_access(_app: AppHandle) -> Result<bool, String> {
     match SpeakerInput::new() {
         Ok(_) => Ok(true),
@@ -513,6 +695,7 @@ pub fn check_system_audio_access(_app: AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn request_system_audio_access(app: AppHandle) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
@@ -557,6 +740,7 @@ pub async fn request_system_audio_access(app: AppHandle) -> Result<(), String> {
 
 // VAD Configuration Management
 #[tauri::command]
+#[specta::specta]
 pub async fn get_vad_config(app: AppHandle) -> Result<VadConfig, String> {
     let state = app.state::<crate::AudioState>();
     let config = state
@@ -568,11 +752,15 @@ pub async fn get_vad_config(app: AppHandle) -> Result<VadConfig, String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn update_vad_config(app: AppHandle, config: VadConfig) -> Result<(), String> {
     // Validate config
     if config.sensitivity_rms < 0.0 || config.sensitivity_rms > 1.0 {
         return Err("Invalid sensitivity_rms: must be 0.0-1.0".to_string());
     }
+    if !(0.0..=1.0).contains(&config.voiced_ratio) {
+        return Err("Invalid voiced_ratio: must be 0.0-1.0".to_string());
+    }
     if config.max_recording_duration_secs > 3600 {
         return Err("Invalid max_recording_duration_secs: must be <= 3600 (1 hour)".to_string());
     }
@@ -587,6 +775,7 @@ pub async fn update_vad_config(app: AppHandle, config: VadConfig) -> Result<(),
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn get_capture_status(app: AppHandle) -> Result<bool, String> {
     let state = app.state::<crate::AudioState>();
     let is_capturing = *state
@@ -597,6 +786,7 @@ pub async fn get_capture_status(app: AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn get_audio_sample_rate(_app: AppHandle) -> Result<u32, String> {
     let input = SpeakerInput::new().map_err(|e| {
         error!("Failed to create speaker input: {}", e);
@@ -610,7 +800,7 @@ pub fn get_audio_sample_rate(_app: AppHandle) -> Result<u32, String> {
 }
 
 // Audio device info struct
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct AudioDeviceInfo {
     pub device_id: String,
     pub label: String,
@@ -619,6 +809,7 @@ pub struct AudioDeviceInfo {
 
 // List audio devices using pactl (Linux) or cpal
 #[tauri::command]
+#[specta::specta]
 pub async fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>, String> {
     #[cfg(target_os = "linux")]
     {
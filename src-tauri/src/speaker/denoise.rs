@@ -0,0 +1,96 @@
+// Pluely noise suppression stage (RNNoise via nnnoiseless) for the mic stream.
+use futures_util::Stream;
+use nnnoiseless::DenoiseState;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::speaker::resample::resample_linear;
+
+// RNNoise is trained on 48 kHz audio in 10ms frames
+const DENOISE_SAMPLE_RATE: u32 = 48_000;
+// RNNoise expects samples in i16 range rather than -1.0..1.0
+const PCM_SCALE: f32 = i16::MAX as f32;
+
+pub struct DenoiseStream<S> {
+    inner: S,
+    sample_rate: u32,
+    // When disabled samples pass through untouched
+    state: Option<Box<DenoiseState<'static>>>,
+    pending: Vec<f32>,
+    frame: Vec<f32>,
+    out: VecDeque<f32>,
+    finished: bool,
+}
+
+impl<S: Stream<Item = f32> + Unpin> DenoiseStream<S> {
+    pub fn new(inner: S, sample_rate: u32, enabled: bool) -> Self {
+        Self {
+            inner,
+            sample_rate,
+            state: enabled.then(DenoiseState::new),
+            pending: Vec::new(),
+            frame: Vec::with_capacity(DenoiseState::FRAME_SIZE * 2),
+            out: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    // 10ms of input at the source rate
+    fn block_len(&self) -> usize {
+        (self.sample_rate / 100).max(1) as usize
+    }
+
+    fn process_block(&mut self) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        let block = std::mem::take(&mut self.pending);
+        self.frame.extend(
+            resample_linear(&block, self.sample_rate, DENOISE_SAMPLE_RATE)
+                .into_iter()
+                .map(|s| s * PCM_SCALE),
+        );
+
+        let mut denoised = Vec::with_capacity(self.frame.len());
+        let mut output = [0.0f32; DenoiseState::FRAME_SIZE];
+        while self.frame.len() >= DenoiseState::FRAME_SIZE {
+            let input: Vec<f32> = self.frame.drain(..DenoiseState::FRAME_SIZE).collect();
+            state.process_frame(&mut output, &input);
+            denoised.extend(output.iter().map(|s| (s / PCM_SCALE).clamp(-1.0, 1.0)));
+        }
+
+        self.out
+            .extend(resample_linear(&denoised, DENOISE_SAMPLE_RATE, self.sample_rate));
+    }
+}
+
+impl<S: Stream<Item = f32> + Unpin> Stream for DenoiseStream<S> {
+    type Item = f32;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<f32>> {
+        let this = &mut *self;
+        if this.state.is_none() {
+            return Pin::new(&mut this.inner).poll_next(cx);
+        }
+
+        while this.out.is_empty() && !this.finished {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    this.pending.push(sample);
+                    if this.pending.len() >= this.block_len() {
+                        this.process_block();
+                    }
+                }
+                Poll::Ready(None) => this.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match this.out.pop_front() {
+            Some(sample) => Poll::Ready(Some(sample)),
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// Pluely device hot-swap: reopens a capture source on a new device, either on
+// request or when the current one disappears, without ending the stream.
+use futures_util::Stream;
+use serde::Serialize;
+use specta::Type;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tauri_specta::Event;
+use tracing::{error, warn};
+
+use crate::speaker::mic::{MicInput, MicStream};
+use crate::speaker::mixer::ResampledStream;
+use crate::speaker::{SpeakerInput, SpeakerStream};
+
+// Give up after this many failovers without receiving any audio
+const MAX_FAILOVERS: u32 = 3;
+
+// Payload for the `audio-device-changed` event
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct AudioDeviceChanged {
+    // "system" or "microphone"
+    pub source: &'static str,
+    // None means the system default device
+    pub device_id: Option<String>,
+    // "requested" or "device_lost"
+    pub reason: &'static str,
+}
+
+// Shared between the capture task and `set_audio_input` so a switch can be
+// requested while the stream is waiting for samples.
+#[derive(Default)]
+pub struct DeviceSelector {
+    // Checked on every poll so the lock is only taken when a switch is pending
+    pending: AtomicBool,
+    state: Mutex<SelectorState>,
+}
+
+#[derive(Default)]
+struct SelectorState {
+    requested: Option<Option<String>>,
+    waker: Option<Waker>,
+}
+
+impl DeviceSelector {
+    pub fn request(&self, device_id: Option<String>) {
+        let mut state = self.state.lock().unwrap();
+        state.requested = Some(device_id);
+        self.pending.store(true, Ordering::Release);
+        if let Some(waker) = state.waker.take() {
+            drop(state);
+            waker.wake();
+        }
+    }
+
+    fn take_request(&self) -> Option<Option<String>> {
+        if !self.pending.swap(false, Ordering::AcqRel) {
+            return None;
+        }
+        self.state.lock().unwrap().requested.take()
+    }
+
+    fn register(&self, waker: &Waker) {
+        self.state.lock().unwrap().waker = Some(waker.clone());
+    }
+
+    // Drops a request left over from a previous capture
+    pub fn clear(&self) {
+        self.pending.store(false, Ordering::Release);
+        self.state.lock().unwrap().requested = None;
+    }
+}
+
+// A capture source that can be reopened on another device.
+pub trait SwappableSource: Stream<Item = f32> + Unpin + Send + Sized + 'static {
+    const NAME: &'static str;
+
+    fn open(device_id: Option<String>) -> anyhow::Result<Self>;
+    fn rate(&self) -> u32;
+}
+
+// Opens `device_id`, falling back to the default device. Blocks while the
+// audio backend starts, so it runs off the async runtime.
+fn open_device<S: SwappableSource>(device_id: Option<String>) -> Option<(S, Option<String>)> {
+    match S::open(device_id.clone()) {
+        Ok(stream) => Some((stream, device_id)),
+        Err(e) if device_id.is_some() => {
+            warn!("Failed to open {} device, using default: {}", S::NAME, e);
+            match S::open(None) {
+                Ok(stream) => Some((stream, None)),
+                Err(e) => {
+                    error!("Failed to open default {} device: {}", S::NAME, e);
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            error!("Failed to open default {} device: {}", S::NAME, e);
+            None
+        }
+    }
+}
+
+// A device being opened, and why
+struct Opening<S> {
+    task: JoinHandle<Option<(S, Option<String>)>>,
+    reason: &'static str,
+}
+
+impl SwappableSource for SpeakerStream {
+    const NAME: &'static str = "system";
+
+    fn open(device_id: Option<String>) -> anyhow::Result<Self> {
+        Ok(SpeakerInput::new_with_device(device_id)?.stream())
+    }
+
+    fn rate(&self) -> u32 {
+        self.sample_rate()
+    }
+}
+
+impl SwappableSource for MicStream {
+    const NAME: &'static str = "microphone";
+
+    fn open(device_id: Option<String>) -> anyhow::Result<Self> {
+        MicInput::new(device_id).stream()
+    }
+
+    fn rate(&self) -> u32 {
+        self.sample_rate()
+    }
+}
+
+// Keeps emitting samples at the rate of the first device, resampling if a
+// replacement device runs at a different rate.
+pub struct HotSwapStream<S: SwappableSource> {
+    app: AppHandle,
+    selector: Arc<DeviceSelector>,
+    inner: Option<ResampledStream<S>>,
+    opening: Option<Opening<S>>,
+    device_id: Option<String>,
+    sample_rate: u32,
+    failovers: u32,
+}
+
+impl<S: SwappableSource> HotSwapStream<S> {
+    pub fn new(
+        app: AppHandle,
+        selector: Arc<DeviceSelector>,
+        stream: S,
+        device_id: Option<String>,
+    ) -> Self {
+        let sample_rate = stream.rate();
+        Self {
+            app,
+            selector,
+            inner: Some(ResampledStream::new(stream, sample_rate, sample_rate)),
+            opening: None,
+            device_id,
+            sample_rate,
+            failovers: 0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn switch_to(&mut self, device_id: Option<String>, reason: &'static str) {
+        // Release the old device before opening the new one
+        self.inner = None;
+        self.opening = Some(Opening {
+            task: tauri::async_runtime::spawn_blocking(move || open_device::<S>(device_id)),
+            reason,
+        });
+    }
+
+    fn switched(&mut self, stream: S, device_id: Option<String>, reason: &'static str) {
+        let rate = stream.rate();
+        self.inner = Some(ResampledStream::new(stream, rate, self.sample_rate));
+        self.device_id = device_id.clone();
+
+        let _ = self.app.emit(
+            "audio-device-changed",
+            AudioDeviceChanged {
+                source: S::NAME,
+                device_id,
+                reason,
+            },
+        );
+    }
+}
+
+impl<S: SwappableSource> Stream for HotSwapStream<S> {
+    type Item = f32;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<f32>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(opening) = this.opening.as_mut() {
+                let result = match Pin::new(&mut opening.task).poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                let reason = opening.reason;
+                this.opening = None;
+                match result {
+                    Ok(Some((stream, device_id))) => this.switched(stream, device_id, reason),
+                    Ok(None) => return Poll::Ready(None),
+                    Err(e) => {
+                        error!("Failed to open {} device: {}", S::NAME, e);
+                        return Poll::Ready(None);
+                    }
+                }
+            }
+
+            if let Some(device_id) = this.selector.take_request() {
+                this.failovers = 0;
+                this.switch_to(device_id, "requested");
+                continue;
+            }
+
+            let Some(inner) = this.inner.as_mut() else {
+                return Poll::Ready(None);
+            };
+
+            match Pin::new(inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    this.failovers = 0;
+                    return Poll::Ready(Some(sample));
+                }
+                Poll::Ready(None) => {
+                    this.failovers += 1;
+                    if this.failovers > MAX_FAILOVERS {
+                        error!("{} audio keeps failing, stopping capture", S::NAME);
+                        return Poll::Ready(None);
+                    }
+                    warn!(
+                        "{} audio device {:?} lost, falling back to default",
+                        S::NAME,
+                        this.device_id
+                    );
+                    this.switch_to(None, "device_lost");
+                }
+                Poll::Pending => {
+                    this.selector.register(cx.waker());
+                    // A switch requested before the waker was stored would be missed
+                    if this.selector.pending.load(Ordering::Acquire) {
+                        cx.waker().wake_by_ref();
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
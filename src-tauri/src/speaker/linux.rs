@@ -5,6 +5,7 @@ use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::task::{Poll, Waker};
 use std::thread;
+use tracing::{error, warn};
 
 use libpulse_binding as pulse;
 use libpulse_simple_binding as psimple;
@@ -14,6 +15,8 @@ use pulse::sample::{Format, Spec};
 use pulse::stream::Direction;
 
 const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+// Consecutive read failures (~1s) before the source is considered gone
+const MAX_READ_ERRORS: u32 = 10;
 
 pub struct SpeakerInput {
     source_name: Option<String>,
@@ -47,18 +50,18 @@ impl SpeakerInput {
                 source_name.as_deref(),
                 init_tx,
             ) {
-                eprintln!("Audio capture loop failed: {}", e);
+                error!("Audio capture loop failed: {}", e);
             }
         }));
 
         let (sample_rate, init_success) = match init_rx.recv() {
             Ok(Ok(sr)) => (sr, true),
             Ok(Err(e)) => {
-                eprintln!("Audio initialization failed: {}", e);
+                error!("Audio initialization failed: {}", e);
                 (DEFAULT_SAMPLE_RATE, false)
             }
             Err(e) => {
-                eprintln!("Failed to receive audio init signal: {}", e);
+                warn!("Failed to receive audio init signal: {}", e);
                 (DEFAULT_SAMPLE_RATE, false)
             }
         };
@@ -147,6 +150,7 @@ impl SpeakerStream {
 
                 // Buffer for reading audio data
                 let mut buffer = vec![0u8; 4096]; // 1024 f32 samples * 4 bytes each
+                let mut consecutive_errors = 0;
 
                 loop {
                     if waker_state.lock().unwrap().shutdown {
@@ -155,6 +159,8 @@ impl SpeakerStream {
 
                     match simple.read(&mut buffer) {
                         Ok(_) => {
+                            consecutive_errors = 0;
+
                             // Convert byte buffer to f32 samples
                             let samples: Vec<f32> = buffer
                                 .chunks_exact(4)
@@ -184,10 +190,7 @@ impl SpeakerStream {
                                 };
 
                                 if dropped > 0 {
-                                    eprintln!(
-                                        "Linux buffer overflow - dropped {} samples",
-                                        dropped
-                                    );
+                                    warn!("Linux buffer overflow - dropped {} samples", dropped);
                                 }
 
                                 // Wake up consumer
@@ -204,11 +207,24 @@ impl SpeakerStream {
                             }
                         }
                         Err(e) => {
-                            eprintln!("PulseAudio read error: {}", e);
+                            error!("PulseAudio read error: {}", e);
+                            consecutive_errors += 1;
+                            if consecutive_errors >= MAX_READ_ERRORS {
+                                warn!("PulseAudio source unavailable, stopping capture");
+                                break;
+                            }
                             thread::sleep(std::time::Duration::from_millis(100));
                         }
                     }
                 }
+
+                // End the stream so consumers can fail over instead of waiting forever
+                let mut state = waker_state.lock().unwrap();
+                state.shutdown = true;
+                if let Some(waker) = state.waker.take() {
+                    drop(state);
+                    waker.wake();
+                }
             }
             Err(e) => {
                 let _ = init_tx.send(Err(e));
@@ -8,6 +8,7 @@ use ringbuf::{
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Poll, Waker};
+use tracing::{error, warn};
 
 use ca::aggregate_device_keys as agg_keys;
 use cidre::{arc, av, cat, cf, core_audio as ca, ns, os};
@@ -188,11 +189,11 @@ fn process_audio_data(ctx: &mut Ctx, data: &[f32]) {
 
         // Only terminate after many consecutive drops (prevents temporary spikes from killing stream)
         if consecutive == 25 {
-            eprintln!("Warning: Audio buffer experiencing drops - system may be overloaded");
+            warn!("Audio buffer experiencing drops - system may be overloaded");
         }
 
         if consecutive > 50 {
-            eprintln!("Critical: Audio buffer overflow - capture stopping");
+            error!("Audio buffer overflow - capture stopping");
             ctx.should_terminate.store(true, Ordering::Release);
             return;
         }
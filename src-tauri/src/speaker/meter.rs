@@ -0,0 +1,66 @@
+// Pluely audio level metering, emitted as `audio-level` events for VU meters.
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+use tauri_specta::Event;
+
+// ~10 updates per second is smooth enough for a meter without flooding IPC
+const METER_INTERVAL_MS: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct AudioLevel {
+    pub channel: &'static str,
+    pub rms: f32,
+    pub peak: f32,
+    // RMS in dBFS, floored at -100 for silence
+    pub rms_db: f32,
+}
+
+pub struct LevelMeter {
+    channel: &'static str,
+    interval_samples: usize,
+    count: usize,
+    sumsq: f32,
+    peak: f32,
+}
+
+impl LevelMeter {
+    pub fn new(channel: &'static str, sample_rate: u32) -> Self {
+        Self {
+            channel,
+            interval_samples: (sample_rate * METER_INTERVAL_MS / 1000).max(1) as usize,
+            count: 0,
+            sumsq: 0.0,
+            peak: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, app: &AppHandle, sample: f32) {
+        self.sumsq += sample * sample;
+        self.peak = self.peak.max(sample.abs());
+        self.count += 1;
+
+        if self.count >= self.interval_samples {
+            let rms = (self.sumsq / self.count as f32).sqrt();
+            let rms_db = if rms > 0.0 {
+                (20.0 * rms.log10()).max(-100.0)
+            } else {
+                -100.0
+            };
+
+            let _ = app.emit(
+                "audio-level",
+                AudioLevel {
+                    channel: self.channel,
+                    rms,
+                    peak: self.peak,
+                    rms_db,
+                },
+            );
+
+            self.count = 0;
+            self.sumsq = 0.0;
+            self.peak = 0.0;
+        }
+    }
+}
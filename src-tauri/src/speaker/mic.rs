@@ -0,0 +1,225 @@
+// Pluely microphone input and stream (cpal, all platforms)
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::SampleFormat;
+use futures_util::Stream;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::thread;
+use std::time::Duration;
+use tracing::{error, warn};
+
+// Matches the speaker backends' ring buffer size
+const MAX_BUFFER_SIZE: usize = 131072;
+
+pub struct MicInput {
+    device_id: Option<String>,
+}
+
+struct WakerState {
+    waker: Option<Waker>,
+    shutdown: bool,
+}
+
+pub struct MicStream {
+    sample_queue: Arc<Mutex<VecDeque<f32>>>,
+    waker_state: Arc<Mutex<WakerState>>,
+    capture_thread: Option<thread::JoinHandle<()>>,
+    sample_rate: u32,
+}
+
+impl MicInput {
+    // `device_id` is the cpal device name; None or "default" uses the default input.
+    pub fn new(device_id: Option<String>) -> Self {
+        Self { device_id }
+    }
+
+    pub fn stream(self) -> Result<MicStream> {
+        let sample_queue = Arc::new(Mutex::new(VecDeque::new()));
+        let waker_state = Arc::new(Mutex::new(WakerState {
+            waker: None,
+            shutdown: false,
+        }));
+        let (init_tx, init_rx) = std::sync::mpsc::channel();
+
+        let queue_clone = sample_queue.clone();
+        let waker_clone = waker_state.clone();
+        let device_id = self.device_id;
+
+        // cpal streams are not Send on every backend, so the stream lives on
+        // its own thread for the lifetime of the capture
+        let capture_thread = thread::spawn(move || {
+            let stream = match open_input_stream(device_id.as_deref(), queue_clone, waker_clone.clone())
+            {
+                Ok((stream, sample_rate)) => {
+                    let _ = init_tx.send(Ok(sample_rate));
+                    stream
+                }
+                Err(e) => {
+                    let _ = init_tx.send(Err(e));
+                    return;
+                }
+            };
+
+            while !waker_clone.lock().unwrap().shutdown {
+                thread::sleep(Duration::from_millis(50));
+            }
+
+            drop(stream);
+        });
+
+        let sample_rate = match init_rx.recv() {
+            Ok(Ok(sr)) => sr,
+            Ok(Err(e)) => {
+                let _ = capture_thread.join();
+                return Err(e);
+            }
+            Err(e) => {
+                let _ = capture_thread.join();
+                return Err(anyhow!("Failed to receive microphone init signal: {}", e));
+            }
+        };
+
+        Ok(MicStream {
+            sample_queue,
+            waker_state,
+            capture_thread: Some(capture_thread),
+            sample_rate,
+        })
+    }
+}
+
+fn open_input_stream(
+    device_id: Option<&str>,
+    sample_queue: Arc<Mutex<VecDeque<f32>>>,
+    waker_state: Arc<Mutex<WakerState>>,
+) -> Result<(cpal::Stream, u32)> {
+    let host = cpal::default_host();
+    let named = match device_id {
+        Some(id) if id != "default" => {
+            let found = host
+                .input_devices()?
+                .find(|d| d.name().map(|name| name == id).unwrap_or(false));
+            if found.is_none() {
+                // PulseAudio source names don't always match cpal device names
+                warn!("Microphone '{}' not found, using default input", id);
+            }
+            found
+        }
+        _ => None,
+    };
+    let device = named
+        .or_else(|| host.default_input_device())
+        .ok_or_else(|| anyhow!("No microphone available"))?;
+
+    let config = device.default_input_config()?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels().max(1) as usize;
+    let sample_format = config.sample_format();
+    let stream_config: cpal::StreamConfig = config.into();
+
+    let error_state = waker_state.clone();
+
+    // Downmix interleaved frames to mono and hand them to the consumer
+    let push = move |mono: Vec<f32>| {
+        {
+            let mut queue = sample_queue.lock().unwrap();
+            queue.extend(mono);
+            if queue.len() > MAX_BUFFER_SIZE {
+                let to_drop = queue.len() - MAX_BUFFER_SIZE;
+                queue.drain(0..to_drop);
+            }
+        }
+        if let Some(waker) = waker_state.lock().unwrap().waker.take() {
+            waker.wake();
+        }
+    };
+    // A disconnected device ends the stream so the consumer can fail over
+    let err_fn = move |e: cpal::StreamError| {
+        error!("Microphone stream error: {}", e);
+        if matches!(e, cpal::StreamError::DeviceNotAvailable) {
+            let mut state = error_state.lock().unwrap();
+            state.shutdown = true;
+            if let Some(waker) = state.waker.take() {
+                drop(state);
+                waker.wake();
+            }
+        }
+    };
+
+    let stream = match sample_format {
+        SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _| {
+                push(downmix(data, channels, |s| s));
+            },
+            err_fn,
+            None,
+        )?,
+        SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _| {
+                push(downmix(data, channels, |s| s as f32 / i16::MAX as f32));
+            },
+            err_fn,
+            None,
+        )?,
+        SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _| {
+                push(downmix(data, channels, |s| {
+                    (s as f32 - u16::MAX as f32 / 2.0) / (u16::MAX as f32 / 2.0)
+                }));
+            },
+            err_fn,
+            None,
+        )?,
+        other => return Err(anyhow!("Unsupported microphone sample format: {:?}", other)),
+    };
+
+    stream.play()?;
+    Ok((stream, sample_rate))
+}
+
+fn downmix<T: Copy>(data: &[T], channels: usize, to_f32: impl Fn(T) -> f32) -> Vec<f32> {
+    data.chunks(channels)
+        .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+impl MicStream {
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+impl Stream for MicStream {
+    type Item = f32;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if let Some(sample) = self.sample_queue.lock().unwrap().pop_front() {
+            return Poll::Ready(Some(sample));
+        }
+
+        let mut state = self.waker_state.lock().unwrap();
+        if state.shutdown {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for MicStream {
+    fn drop(&mut self) {
+        self.waker_state.lock().unwrap().shutdown = true;
+        if let Some(thread) = self.capture_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Pluely dual-stream mixer: combines microphone and system audio into one
+// mono stream at a common sample rate.
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::speaker::resample::resample_linear;
+
+// Sources are resampled in 20ms blocks
+const BLOCK_MS: u32 = 20;
+// If one source stalls for this long, the other keeps flowing padded with silence
+const MAX_SKEW_MS: u32 = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DualCaptureMode {
+    // Only system audio (previous behaviour)
+    #[default]
+    SystemOnly,
+    // Mic and system summed into one stream
+    Mixed,
+    // Mic and system segmented independently, events labeled per source
+    Separate,
+}
+
+// Resamples a single source to `target_rate` in fixed blocks.
+pub struct ResampledStream<S> {
+    inner: S,
+    source_rate: u32,
+    target_rate: u32,
+    pending: Vec<f32>,
+    out: VecDeque<f32>,
+    finished: bool,
+}
+
+impl<S: Stream<Item = f32> + Unpin> ResampledStream<S> {
+    pub fn new(inner: S, source_rate: u32, target_rate: u32) -> Self {
+        Self {
+            inner,
+            source_rate,
+            target_rate,
+            pending: Vec::new(),
+            out: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    fn block_len(&self) -> usize {
+        (self.source_rate * BLOCK_MS / 1000).max(1) as usize
+    }
+
+    // Pulls every sample that is ready without blocking, resampling complete blocks.
+    fn fill(&mut self, cx: &mut Context<'_>) {
+        while !self.finished {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => {
+                    self.pending.push(sample);
+                    if self.pending.len() >= self.block_len() {
+                        let block = std::mem::take(&mut self.pending);
+                        self.out
+                            .extend(resample_linear(&block, self.source_rate, self.target_rate));
+                    }
+                }
+                Poll::Ready(None) => self.finished = true,
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+impl<S: Stream<Item = f32> + Unpin> Stream for ResampledStream<S> {
+    type Item = f32;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<f32>> {
+        if self.out.is_empty() {
+            self.fill(cx);
+        }
+
+        match self.out.pop_front() {
+            Some(sample) => Poll::Ready(Some(sample)),
+            None if self.finished => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+pub struct MixedStream<A, B> {
+    mic: ResampledStream<A>,
+    system: ResampledStream<B>,
+    mic_gain: f32,
+    system_gain: f32,
+    max_skew: usize,
+}
+
+impl<A, B> MixedStream<A, B>
+where
+    A: Stream<Item = f32> + Unpin,
+    B: Stream<Item = f32> + Unpin,
+{
+    pub fn new(mic: A, mic_rate: u32, system: B, system_rate: u32, target_rate: u32) -> Self {
+        Self {
+            mic: ResampledStream::new(mic, mic_rate, target_rate),
+            system: ResampledStream::new(system, system_rate, target_rate),
+            mic_gain: 1.0,
+            system_gain: 1.0,
+            max_skew: (target_rate * MAX_SKEW_MS / 1000) as usize,
+        }
+    }
+}
+
+impl<A, B> Stream for MixedStream<A, B>
+where
+    A: Stream<Item = f32> + Unpin,
+    B: Stream<Item = f32> + Unpin,
+{
+    type Item = f32;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<f32>> {
+        let this = &mut *self;
+        this.mic.fill(cx);
+        this.system.fill(cx);
+
+        let mic_ready = !this.mic.out.is_empty();
+        let system_ready = !this.system.out.is_empty();
+        let mic_done = this.mic.finished && !mic_ready;
+        let system_done = this.system.finished && !system_ready;
+
+        if mic_done && system_done {
+            return Poll::Ready(None);
+        }
+
+        // Mix when both have audio; otherwise only let one through alone when
+        // the other has ended or fallen too far behind
+        let mic_lagging = mic_done || this.system.out.len() > this.max_skew;
+        let system_lagging = system_done || this.mic.out.len() > this.max_skew;
+
+        let (mic, system) = if mic_ready && system_ready {
+            (this.mic.out.pop_front(), this.system.out.pop_front())
+        } else if system_ready && mic_lagging {
+            (None, this.system.out.pop_front())
+        } else if mic_ready && system_lagging {
+            (this.mic.out.pop_front(), None)
+        } else {
+            return Poll::Pending;
+        };
+
+        let mixed = mic.unwrap_or(0.0) * this.mic_gain + system.unwrap_or(0.0) * this.system_gain;
+        Poll::Ready(Some(mixed.clamp(-1.0, 1.0)))
+    }
+}
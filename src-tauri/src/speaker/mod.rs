@@ -18,9 +18,19 @@ mod linux;
 use linux::{SpeakerInput as PlatformSpeakerInput, SpeakerStream as PlatformSpeakerStream};
 
 mod commands;
+mod denoise;
+pub mod hotswap;
+pub mod meter;
+pub mod mic;
+pub mod mixer;
+pub mod opus;
+mod recorder;
+pub mod resample;
+mod vad;
 
 // Re-export commands for tauri handler
 pub use commands::*;
+pub use recorder::*;
 
 // Pluely speaker input and stream
 pub struct SpeakerInput {
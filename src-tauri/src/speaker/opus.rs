@@ -0,0 +1,170 @@
+// Pluely Opus encoding: 20ms mono frames, optionally wrapped in an Ogg stream.
+use anyhow::{anyhow, Result};
+use audiopus::coder::Encoder;
+use audiopus::{Application, Bitrate, Channels, SampleRate};
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use std::io::Write;
+
+use crate::speaker::resample::resample_linear;
+
+// Opus only runs at fixed rates; everything is resampled to 48 kHz
+pub const OPUS_SAMPLE_RATE: u32 = 48_000;
+// 20ms at 48 kHz
+pub const OPUS_FRAME_SAMPLES: usize = 960;
+// Encoder lookahead for the audio application at 48 kHz
+const OPUS_PRE_SKIP: u16 = 312;
+// Worst-case packet size recommended by libopus
+const MAX_PACKET_BYTES: usize = 4000;
+
+pub struct OpusFrameEncoder {
+    encoder: Encoder,
+    input_rate: u32,
+    pending: Vec<f32>,
+}
+
+impl OpusFrameEncoder {
+    pub fn new(input_rate: u32, bitrate: i32) -> Result<Self> {
+        let mut encoder = Encoder::new(SampleRate::Hz48000, Channels::Mono, Application::Audio)
+            .map_err(|e| anyhow!("Failed to create Opus encoder: {}", e))?;
+        encoder
+            .set_bitrate(Bitrate::BitsPerSecond(bitrate))
+            .map_err(|e| anyhow!("Failed to set Opus bitrate: {}", e))?;
+
+        Ok(Self {
+            encoder,
+            input_rate,
+            pending: Vec::with_capacity(OPUS_FRAME_SAMPLES * 2),
+        })
+    }
+
+    // Buffers samples and returns every complete 20ms packet.
+    pub fn push(&mut self, samples: &[f32]) -> Result<Vec<Vec<u8>>> {
+        self.pending
+            .extend(resample_linear(samples, self.input_rate, OPUS_SAMPLE_RATE));
+
+        let mut packets = Vec::new();
+        while self.pending.len() >= OPUS_FRAME_SAMPLES {
+            let frame: Vec<f32> = self.pending.drain(..OPUS_FRAME_SAMPLES).collect();
+            packets.push(self.encode_frame(&frame)?);
+        }
+        Ok(packets)
+    }
+
+    // Pads the remainder with silence and returns the final packet.
+    pub fn flush(&mut self) -> Result<Vec<u8>> {
+        let mut frame = std::mem::take(&mut self.pending);
+        frame.resize(OPUS_FRAME_SAMPLES, 0.0);
+        self.encode_frame(&frame)
+    }
+
+    fn encode_frame(&mut self, frame: &[f32]) -> Result<Vec<u8>> {
+        let mut packet = vec![0u8; MAX_PACKET_BYTES];
+        let len = self
+            .encoder
+            .encode_float(frame, &mut packet)
+            .map_err(|e| anyhow!("Opus encoding failed: {}", e))?;
+        packet.truncate(len);
+        Ok(packet)
+    }
+}
+
+// Ogg Opus (RFC 7845) mono stream writer
+pub struct OggOpusWriter<W: Write> {
+    writer: PacketWriter<W>,
+    encoder: OpusFrameEncoder,
+    serial: u32,
+    granule: u64,
+    // Close a page after every packet so a live reader never waits on a full page
+    page_per_packet: bool,
+}
+
+impl<W: Write> OggOpusWriter<W> {
+    pub fn new(inner: W, input_rate: u32, bitrate: i32) -> Result<Self> {
+        let mut writer = PacketWriter::new(inner);
+        let serial = uuid::Uuid::new_v4().as_u128() as u32;
+
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        head.push(1); // version
+        head.push(1); // channel count
+        head.extend_from_slice(&OPUS_PRE_SKIP.to_le_bytes());
+        head.extend_from_slice(&input_rate.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        head.push(0); // mapping family
+        writer.write_packet(
+            head.into_boxed_slice(),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        let vendor = b"pluely";
+        let mut tags = Vec::new();
+        tags.extend_from_slice(b"OpusTags");
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor);
+        tags.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+        writer.write_packet(
+            tags.into_boxed_slice(),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        Ok(Self {
+            writer,
+            encoder: OpusFrameEncoder::new(input_rate, bitrate)?,
+            serial,
+            granule: OPUS_PRE_SKIP as u64,
+            page_per_packet: false,
+        })
+    }
+
+    // Writer for live uploads: every 20ms packet is emitted as its own page.
+    pub fn streaming(inner: W, input_rate: u32, bitrate: i32) -> Result<Self> {
+        let mut writer = Self::new(inner, input_rate, bitrate)?;
+        writer.page_per_packet = true;
+        Ok(writer)
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.writer.inner()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.inner_mut()
+    }
+
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        let end_info = if self.page_per_packet {
+            PacketWriteEndInfo::EndPage
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        for packet in self.encoder.push(samples)? {
+            self.granule += OPUS_FRAME_SAMPLES as u64;
+            self.writer.write_packet(
+                packet.into_boxed_slice(),
+                self.serial,
+                end_info,
+                self.granule,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Writes the final packet and returns the underlying writer.
+    pub fn finalize(mut self) -> Result<W> {
+        let packet = self.encoder.flush()?;
+        self.granule += OPUS_FRAME_SAMPLES as u64;
+        self.writer.write_packet(
+            packet.into_boxed_slice(),
+            self.serial,
+            PacketWriteEndInfo::EndStream,
+            self.granule,
+        )?;
+        let mut inner = self.writer.into_inner();
+        inner.flush()?;
+        Ok(inner)
+    }
+}
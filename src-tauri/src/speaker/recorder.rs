@@ -0,0 +1,343 @@
+// Pluely audio recorder: archives captured audio to WAV or Ogg Opus files,
+// independent of the VAD/transcription pipeline.
+use futures_util::{Stream, StreamExt};
+use hound::{WavSpec, WavWriter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use crate::speaker::denoise::DenoiseStream;
+use crate::speaker::meter::LevelMeter;
+use crate::speaker::mic::MicInput;
+use crate::speaker::mixer::{DualCaptureMode, MixedStream};
+use crate::speaker::opus::OggOpusWriter;
+use crate::speaker::SpeakerInput;
+
+// Mixed recordings use a common rate; 48 kHz keeps Opus from resampling twice
+const MIXED_RECORDING_RATE: u32 = 48_000;
+const DEFAULT_OPUS_BITRATE: i32 = 32_000;
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    Wav,
+    Opus,
+}
+
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct RecordingStatus {
+    pub path: String,
+    pub duration_ms: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RecordingSummary {
+    pub path: String,
+    pub format: RecordingFormat,
+    pub duration_ms: u64,
+    pub bytes: u64,
+}
+
+#[derive(Default)]
+pub struct RecorderState {
+    task: Mutex<Option<JoinHandle<Result<RecordingSummary, String>>>>,
+    stop_tx: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+type SampleStream = Pin<Box<dyn Stream<Item = f32> + Send>>;
+
+// Counts bytes handed to the file so status events don't need to stat it
+struct CountingWriter<W> {
+    inner: W,
+    bytes: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+enum RecordingWriter {
+    Wav(WavWriter<BufWriter<File>>),
+    Opus(OggOpusWriter<CountingWriter<BufWriter<File>>>),
+}
+
+impl RecordingWriter {
+    fn create(path: &Path, format: RecordingFormat, sr: u32, bitrate: i32) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create recording file: {}", e))?;
+        let file = BufWriter::new(file);
+
+        match format {
+            RecordingFormat::Wav => {
+                let spec = WavSpec {
+                    channels: 1,
+                    sample_rate: sr,
+                    bits_per_sample: 16,
+                    sample_format: hound::SampleFormat::Int,
+                };
+                let writer = WavWriter::new(file, spec)
+                    .map_err(|e| format!("Failed to start WAV recording: {}", e))?;
+                Ok(Self::Wav(writer))
+            }
+            RecordingFormat::Opus => {
+                let counting = CountingWriter {
+                    inner: file,
+                    bytes: 0,
+                };
+                let writer = OggOpusWriter::new(counting, sr, bitrate)
+                    .map_err(|e| format!("Failed to start Opus recording: {}", e))?;
+                Ok(Self::Opus(writer))
+            }
+        }
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), String> {
+        match self {
+            Self::Wav(writer) => {
+                for &s in samples {
+                    let v = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                    writer
+                        .write_sample(v)
+                        .map_err(|e| format!("Failed to write WAV samples: {}", e))?;
+                }
+                Ok(())
+            }
+            Self::Opus(writer) => writer
+                .write_samples(samples)
+                .map_err(|e| format!("Failed to write Opus samples: {}", e)),
+        }
+    }
+
+    // Approximate size on disk, used for progress events only
+    fn bytes(&self) -> u64 {
+        match self {
+            // 44-byte header plus 16-bit samples
+            Self::Wav(writer) => 44 + writer.len() as u64 * 2,
+            Self::Opus(writer) => writer.get_ref().bytes,
+        }
+    }
+
+    fn finalize(self) -> Result<(), String> {
+        match self {
+            Self::Wav(writer) => writer
+                .finalize()
+                .map_err(|e| format!("Failed to finalize WAV recording: {}", e)),
+            Self::Opus(writer) => writer
+                .finalize()
+                .map(|_| ())
+                .map_err(|e| format!("Failed to finalize Opus recording: {}", e)),
+        }
+    }
+}
+
+// Recordings follow the capture pipeline's noise suppression setting
+fn noise_suppression(app: &AppHandle) -> Result<bool, String> {
+    let state = app.state::<crate::AudioState>();
+    let enabled = state
+        .vad_config
+        .lock()
+        .map_err(|e| format!("Failed to read VAD config: {}", e))?
+        .noise_suppression;
+    Ok(enabled)
+}
+
+// Starts recording system audio (optionally with the microphone mixed in) to `path`.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_audio_recording(
+    app: AppHandle,
+    path: String,
+    format: RecordingFormat,
+    device_id: Option<String>,
+    capture_mode: Option<DualCaptureMode>,
+    mic_device_id: Option<String>,
+    bitrate: Option<i32>,
+) -> Result<(), String> {
+    let state = app.state::<RecorderState>();
+    {
+        let guard = state
+            .task
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        if guard.is_some() {
+            return Err("Recording already running".to_string());
+        }
+    }
+
+    let bitrate = bitrate.unwrap_or(DEFAULT_OPUS_BITRATE);
+    if format == RecordingFormat::Opus && !(6_000..=510_000).contains(&bitrate) {
+        return Err("Invalid bitrate: expected 6000-510000 bps".to_string());
+    }
+
+    let input = SpeakerInput::new_with_device(device_id)
+        .map_err(|e| format!("Failed to access system audio: {}", e))?;
+    let system = input.stream();
+    let system_rate = system.sample_rate();
+
+    let (stream, sr, channel): (SampleStream, u32, &'static str) =
+        if capture_mode.unwrap_or_default() == DualCaptureMode::SystemOnly {
+            (Box::pin(system), system_rate, "system")
+        } else {
+            // Separate tracks aren't supported in a single file, so both modes mix
+            let mic = MicInput::new(mic_device_id)
+                .stream()
+                .map_err(|e| format!("Failed to access microphone: {}", e))?;
+            let mic_rate = mic.sample_rate();
+            let mic = DenoiseStream::new(mic, mic_rate, noise_suppression(&app)?);
+            let mixed = MixedStream::new(mic, mic_rate, system, system_rate, MIXED_RECORDING_RATE);
+            (Box::pin(mixed), MIXED_RECORDING_RATE, "mixed")
+        };
+
+    let writer = RecordingWriter::create(Path::new(&path), format, sr, bitrate)?;
+
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let app_clone = app.clone();
+    let task = tokio::spawn(record(
+        app_clone, stream, sr, channel, writer, path, format, stop_rx,
+    ));
+
+    *state
+        .stop_tx
+        .lock()
+        .map_err(|e| format!("Failed to store stop signal: {}", e))? = Some(stop_tx);
+    *state
+        .task
+        .lock()
+        .map_err(|e| format!("Failed to store task: {}", e))? = Some(task);
+
+    let _ = app.emit("recording-started", crate::events::RecordingStarted(format));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn record(
+    app: AppHandle,
+    mut stream: SampleStream,
+    sr: u32,
+    channel: &'static str,
+    mut writer: RecordingWriter,
+    path: String,
+    format: RecordingFormat,
+    mut stop_rx: oneshot::Receiver<()>,
+) -> Result<RecordingSummary, String> {
+    // Write in 20ms blocks to keep per-sample overhead low
+    let block_len = (sr / 50).max(1) as usize;
+    let mut block = Vec::with_capacity(block_len);
+    let mut samples_written: u64 = 0;
+    let mut meter = LevelMeter::new(channel, sr);
+    let mut last_status = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = &mut stop_rx => break,
+            sample = stream.next() => {
+                let Some(sample) = sample else {
+                    warn!("Recording source ended");
+                    break;
+                };
+                meter.push(&app, sample);
+                block.push(sample);
+                if block.len() < block_len {
+                    continue;
+                }
+
+                writer.write(&block)?;
+                samples_written += block.len() as u64;
+                block.clear();
+
+                if last_status.elapsed() >= STATUS_INTERVAL {
+                    last_status = Instant::now();
+                    let _ = app.emit(
+                        "recording-status",
+                        RecordingStatus {
+                            path: path.clone(),
+                            duration_ms: samples_written * 1000 / sr as u64,
+                            bytes: writer.bytes(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    if !block.is_empty() {
+        writer.write(&block)?;
+        samples_written += block.len() as u64;
+    }
+    writer.finalize()?;
+
+    let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(RecordingSummary {
+        path,
+        format,
+        duration_ms: samples_written * 1000 / sr as u64,
+        bytes,
+    })
+}
+
+// Stops the active recording, finalizes the file and returns its summary.
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_audio_recording(app: AppHandle) -> Result<RecordingSummary, String> {
+    let state = app.state::<RecorderState>();
+    if let Some(stop_tx) = state
+        .stop_tx
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .take()
+    {
+        let _ = stop_tx.send(());
+    }
+
+    let task = state
+        .task
+        .lock()
+        .map_err(|e| format!("Failed to acquire task lock: {}", e))?
+        .take()
+        .ok_or("No recording in progress".to_string())?;
+
+    let summary = task
+        .await
+        .map_err(|e| format!("Recording task panicked: {}", e))?
+        .map_err(|e| {
+            error!("Recording failed: {}", e);
+            e
+        })?;
+
+    let _ = app.emit(
+        "recording-finished",
+        crate::events::RecordingFinished(summary.clone()),
+    );
+    Ok(summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_recording_status(app: AppHandle) -> Result<bool, String> {
+    let state = app.state::<RecorderState>();
+    let guard = state
+        .task
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    Ok(guard.as_ref().is_some_and(|task| !task.is_finished()))
+}
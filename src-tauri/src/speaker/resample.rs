@@ -0,0 +1,29 @@
+// Pluely sample-rate conversion helpers shared by the audio pipeline.
+
+// Linear-interpolation resampler. Cheap and allocation-light; good enough for
+// speech classification and transcription input.
+pub fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if samples.is_empty() || from_rate == 0 || to_rate == 0 {
+        return Vec::new();
+    }
+
+    if from_rate == to_rate {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (samples.len() as f64 / ratio) as usize;
+    let last = samples.len() - 1;
+    let mut out = Vec::with_capacity(out_len);
+
+    for i in 0..out_len {
+        let pos = i as f64 * ratio;
+        let idx = pos as usize;
+        let frac = (pos - idx as f64) as f32;
+        let a = samples[idx.min(last)];
+        let b = samples[(idx + 1).min(last)];
+        out.push(a + (b - a) * frac);
+    }
+
+    out
+}
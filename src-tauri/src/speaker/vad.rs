@@ -0,0 +1,88 @@
+// Pluely voice activity classifier backed by the WebRTC VAD.
+use crate::speaker::resample::resample_linear;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use webrtc_vad::{SampleRate, Vad, VadMode};
+
+// The WebRTC VAD only accepts 10/20/30ms frames of 16-bit PCM at fixed rates,
+// so every chunk is resampled to 16 kHz and classified in 10ms frames.
+const CLASSIFIER_RATE: u32 = 16_000;
+const FRAME_SAMPLES: usize = (CLASSIFIER_RATE / 100) as usize;
+
+// Aggressiveness of the WebRTC classifier (higher rejects more non-speech)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadAggressiveness {
+    Quality,
+    LowBitrate,
+    #[default]
+    Aggressive,
+    VeryAggressive,
+}
+
+impl From<VadAggressiveness> for VadMode {
+    fn from(value: VadAggressiveness) -> Self {
+        match value {
+            VadAggressiveness::Quality => VadMode::Quality,
+            VadAggressiveness::LowBitrate => VadMode::LowBitrate,
+            VadAggressiveness::Aggressive => VadMode::Aggressive,
+            VadAggressiveness::VeryAggressive => VadMode::VeryAggressive,
+        }
+    }
+}
+
+pub struct SpeechClassifier {
+    vad: Vad,
+    input_rate: u32,
+    // Minimum fraction of voiced 10ms frames for a chunk to count as speech
+    voiced_ratio: f32,
+    // Samples short of a whole frame, classified with the next chunk
+    leftover: Vec<i16>,
+    // Result for the last chunk that held at least one frame
+    last: bool,
+}
+
+// SAFETY: the underlying fvad instance has no thread affinity; it is owned
+// exclusively by the capture task and never shared between threads.
+unsafe impl Send for SpeechClassifier {}
+
+impl SpeechClassifier {
+    pub fn new(input_rate: u32, mode: VadAggressiveness, voiced_ratio: f32) -> Self {
+        Self {
+            vad: Vad::new_with_rate_and_mode(SampleRate::Rate16kHz, mode.into()),
+            input_rate,
+            voiced_ratio: voiced_ratio.clamp(0.0, 1.0),
+            leftover: Vec::new(),
+            last: false,
+        }
+    }
+
+    // Returns true when enough of the chunk is classified as voiced speech.
+    pub fn is_speech(&mut self, chunk: &[f32]) -> bool {
+        let mut pcm = std::mem::take(&mut self.leftover);
+        pcm.extend(
+            resample_linear(chunk, self.input_rate, CLASSIFIER_RATE)
+                .into_iter()
+                .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+        );
+        let mut frames = 0usize;
+        let mut voiced = 0usize;
+
+        let mut whole = pcm.chunks_exact(FRAME_SAMPLES);
+        for frame in &mut whole {
+            frames += 1;
+            if let Ok(true) = self.vad.is_voice_segment(frame) {
+                voiced += 1;
+            }
+        }
+        self.leftover = whole.remainder().to_vec();
+
+        // Not a whole frame yet - keep the previous verdict
+        if frames == 0 {
+            return self.last;
+        }
+
+        self.last = voiced as f32 / frames as f32 >= self.voiced_ratio;
+        self.last
+    }
+}
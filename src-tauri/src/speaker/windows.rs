@@ -9,6 +9,9 @@ use std::time::Duration;
 use tracing::error;
 use wasapi::{get_default_device, Direction, SampleType, StreamMode, WaveFormat};
 
+// Consecutive read failures before the device is considered gone
+const MAX_READ_ERRORS: u32 = 5;
+
 pub struct SpeakerInput {
     device_index: Option<usize>,
 }
@@ -127,6 +130,7 @@ impl SpeakerStream {
         match init_result {
             Ok((h_event, render_client, sample_rate)) => {
                 let _ = init_tx.send(Ok(sample_rate));
+                let mut consecutive_errors = 0;
 
                 loop {
                     {
@@ -136,16 +140,21 @@ impl SpeakerStream {
                         }
                     }
 
-                    if h_event.wait_for_event(3000).is_err() {
-                        error!("Pluely timeout error, stopping capture");
-                        break;
-                    }
+                    // Loopback capture gets no events while nothing is playing,
+                    // so a timeout alone doesn't mean the device is gone
+                    let _ = h_event.wait_for_event(3000);
 
                     let mut temp_queue = VecDeque::new();
                     if let Err(e) = render_client.read_from_device_to_deque(&mut temp_queue) {
                         error!("Pluely Failed to read audio data: {}", e);
+                        consecutive_errors += 1;
+                        if consecutive_errors >= MAX_READ_ERRORS {
+                            error!("Pluely audio device unavailable, stopping capture");
+                            break;
+                        }
                         continue;
                     }
+                    consecutive_errors = 0;
 
                     if temp_queue.is_empty() {
                         continue;
@@ -200,6 +209,14 @@ impl SpeakerStream {
                         }
                     }
                 }
+
+                // End the stream so consumers can fail over instead of waiting forever
+                let mut state = waker_state.lock().unwrap();
+                state.shutdown = true;
+                if let Some(waker) = state.waker.take() {
+                    drop(state);
+                    waker.wake();
+                }
             }
             Err(e) => {
                 let _ = init_tx.send(Err(e));
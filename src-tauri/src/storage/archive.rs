@@ -0,0 +1,250 @@
+// History backups as a ZIP: a manifest, each conversation as JSON and the
+// captures its messages show. Captures are stored decrypted, since the key
+// stays in the keychain of the machine that made the archive.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::{encryption, Conversation};
+
+const MANIFEST: &str = "manifest.json";
+const CONVERSATIONS_DIR: &str = "conversations";
+const CAPTURES_DIR: &str = "captures";
+// Raised when the layout changes in a way older versions can't read
+const ARCHIVE_VERSION: u32 = 1;
+// Larger entries are refused rather than read into memory
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u32,
+    exported_at: i64,
+    // Ids of the conversations in `conversations/`
+    conversations: Vec<String>,
+}
+
+pub struct ArchivedHistory {
+    // Capture paths are file names in `captures`
+    pub conversations: Vec<Conversation>,
+    pub captures: HashMap<String, Vec<u8>>,
+}
+
+// A capture file name that can't leave the folder it is written to: a single
+// plain component, with no drive prefix like `C:evil.png` on Windows
+fn valid_capture_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\', ':'])
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn write_entry(
+    zip: &mut ZipWriter<File>,
+    name: String,
+    options: SimpleFileOptions,
+    bytes: &[u8],
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    zip.write_all(bytes)
+        .map_err(|e| format!("Failed to write archive: {}", e))
+}
+
+fn write_entries(
+    zip: &mut ZipWriter<File>,
+    mut conversations: Vec<Conversation>,
+    exported_at: i64,
+) -> Result<usize, String> {
+    let json = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Captures are already compressed images
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    // Archive name of each capture path; names are made unique when two
+    // folders hold files of the same name
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    for conversation in &mut conversations {
+        for message in &mut conversation.messages {
+            let mut kept = Vec::new();
+            for mut capture in std::mem::take(&mut message.captures) {
+                if let Some(name) = names.get(&capture.path) {
+                    capture.path = name.clone();
+                    kept.push(capture);
+                    continue;
+                }
+                let bytes = match encryption::read_file(Path::new(&capture.path)) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        tracing::warn!("Leaving capture {} out of archive: {}", capture.path, e);
+                        continue;
+                    }
+                };
+                let file_name = Path::new(&capture.path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .filter(|name| valid_capture_name(name))
+                    .unwrap_or_else(|| "capture".to_string());
+                let mut name = file_name.clone();
+                let mut n = 1;
+                while taken.contains(&name) {
+                    n += 1;
+                    name = format!("{}-{}", n, file_name);
+                }
+                write_entry(zip, format!("{}/{}", CAPTURES_DIR, name), stored, &bytes)?;
+                taken.insert(name.clone());
+                names.insert(capture.path.clone(), name.clone());
+                capture.path = name;
+                kept.push(capture);
+            }
+            message.captures = kept;
+        }
+        let json_bytes = serde_json::to_vec_pretty(conversation)
+            .map_err(|e| format!("Failed to serialize conversation: {}", e))?;
+        write_entry(
+            zip,
+            format!("{}/{}.json", CONVERSATIONS_DIR, conversation.id),
+            json,
+            &json_bytes,
+        )?;
+    }
+
+    let manifest = Manifest {
+        version: ARCHIVE_VERSION,
+        exported_at,
+        conversations: conversations.iter().map(|c| c.id.clone()).collect(),
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    write_entry(zip, MANIFEST.to_string(), json, &manifest_bytes)?;
+    Ok(names.len())
+}
+
+// Writes `conversations` to a ZIP at `path`; returns how many captures went in
+pub fn write_archive(
+    conversations: Vec<Conversation>,
+    path: &Path,
+    exported_at: i64,
+) -> Result<usize, String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let result = write_entries(&mut zip, conversations, exported_at).and_then(|count| {
+        zip.finish()
+            .map(|_| count)
+            .map_err(|e| format!("Failed to write archive: {}", e))
+    });
+    // No half-written backup is left behind
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+fn read_entry(zip: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+    let entry = zip
+        .by_name(name)
+        .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(format!("Archive entry is too large: {}", name));
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(MAX_ENTRY_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+    Ok(bytes)
+}
+
+// Reads an archive made by `write_archive`. Captures the archive lacks are
+// dropped from their messages.
+pub fn read_archive(path: &Path) -> Result<ArchivedHistory, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let manifest: Manifest = serde_json::from_slice(&read_entry(&mut zip, MANIFEST)?)
+        .map_err(|e| format!("Invalid archive manifest: {}", e))?;
+    if manifest.version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Archive was made by a newer version of Pluely (format {})",
+            manifest.version
+        ));
+    }
+
+    let mut conversations = Vec::new();
+    let mut captures = HashMap::new();
+    for id in &manifest.conversations {
+        if !valid_id(id) {
+            return Err(format!("Invalid conversation id in archive: {}", id));
+        }
+        let name = format!("{}/{}.json", CONVERSATIONS_DIR, id);
+        let mut conversation: Conversation = serde_json::from_slice(&read_entry(&mut zip, &name)?)
+            .map_err(|e| format!("Invalid conversation in archive: {}", e))?;
+        conversation.id = id.clone();
+        for message in &mut conversation.messages {
+            let mut kept = Vec::new();
+            for capture in std::mem::take(&mut message.captures) {
+                if !valid_capture_name(&capture.path) {
+                    tracing::warn!("Skipping capture with invalid name: {}", capture.path);
+                    continue;
+                }
+                if !captures.contains_key(&capture.path) {
+                    let entry = format!("{}/{}", CAPTURES_DIR, capture.path);
+                    match read_entry(&mut zip, &entry) {
+                        Ok(bytes) => {
+                            captures.insert(capture.path.clone(), bytes);
+                        }
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            continue;
+                        }
+                    }
+                }
+                kept.push(capture);
+            }
+            message.captures = kept;
+        }
+        conversations.push(conversation);
+    }
+    Ok(ArchivedHistory {
+        conversations,
+        captures,
+    })
+}
+
+// Writes a capture from an archive into the library at `dir`. The file is
+// named after its content, keeping only the extension of the archived name, so
+// that name never becomes a path and restoring a capture twice reuses the file.
+// Returns where it went.
+pub fn restore_capture(dir: &Path, name: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| {
+            (1..=8).contains(&extension.len())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .unwrap_or("bin")
+        .to_ascii_lowercase();
+    let hash: String = Sha256::digest(bytes)[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let target = dir.join(format!("restored-{}.{}", hash, extension));
+    if encryption::read_file(&target).is_ok_and(|existing| existing == bytes) {
+        return Ok(target);
+    }
+    std::fs::write(&target, bytes).map_err(|e| format!("Failed to restore capture: {}", e))?;
+    Ok(target)
+}
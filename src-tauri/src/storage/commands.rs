@@ -0,0 +1,613 @@
+// Pluely storage commands: conversations and their messages.
+use base64::{engine::general_purpose, Engine as _};
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::archive::{read_archive, restore_capture, write_archive};
+use super::encryption::{self, open_optional, open_text};
+use super::export::{render_html, render_markdown, render_pdf, ExportFormat};
+use super::search::{fts_query, SEARCH_SQL};
+use super::{
+    pool, queued_requests, remove_queued_request, sql_plugin_url, CaptureRef, Conversation,
+    ConversationSummary, HistoryArchiveReport, HistoryFilter, QueuedRequest, SearchFilters,
+    SearchHit, SearchSource, StoredMessage, UsageBucket, UsagePeriod, UsageSummary,
+};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+// The database the frontend's SQL plugin should load for the active profile;
+// opened here first so a new profile's tables exist
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_database(app: AppHandle) -> Result<String, String> {
+    pool(&app).await?;
+    Ok(sql_plugin_url())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_conversations(
+    app: AppHandle,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<ConversationSummary>, String> {
+    let pool = pool(&app).await?;
+    sqlx::query_as::<_, ConversationSummary>(
+        "SELECT c.id, c.title, c.model, c.profile_id, c.created_at, c.updated_at,
+                (SELECT COUNT(*) FROM messages m WHERE m.conversation_id = c.id) AS message_count
+         FROM conversations c
+         ORDER BY c.updated_at DESC
+         LIMIT ? OFFSET ?",
+    )
+    .bind(limit.unwrap_or(-1))
+    .bind(offset.unwrap_or(0))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to list conversations: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn load_conversation(app: AppHandle, id: String) -> Result<Option<Conversation>, String> {
+    let pool = pool(&app).await?;
+
+    let Some(row) = sqlx::query(
+        "SELECT id, title, model, profile_id, created_at, updated_at FROM conversations WHERE id = ?",
+    )
+    .bind(&id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| format!("Failed to load conversation: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    let message_rows = sqlx::query(
+        "SELECT id, role, content, timestamp, model, attached_files, app_context
+         FROM messages WHERE conversation_id = ? ORDER BY timestamp ASC",
+    )
+    .bind(&id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to load messages: {}", e))?;
+
+    let capture_rows = sqlx::query(
+        "SELECT mc.message_id, mc.path, mc.kind, mc.ocr_text FROM message_captures mc
+         JOIN messages m ON m.id = mc.message_id
+         WHERE m.conversation_id = ? ORDER BY mc.id ASC",
+    )
+    .bind(&id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to load captures: {}", e))?;
+
+    let messages = message_rows
+        .iter()
+        .map(|row| {
+            let message_id: String = row.get("id");
+            let captures = capture_rows
+                .iter()
+                .filter(|c| c.get::<String, _>("message_id") == message_id)
+                .map(|c| {
+                    Ok(CaptureRef {
+                        path: c.get("path"),
+                        kind: c.get("kind"),
+                        ocr_text: open_optional(c.get("ocr_text"))?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(StoredMessage {
+                id: message_id,
+                role: row.get("role"),
+                content: open_text(row.get("content"))?,
+                timestamp: row.get("timestamp"),
+                model: row.get("model"),
+                captures,
+                attached_files: open_optional(row.get("attached_files"))?,
+                // Unreadable context is dropped rather than failing the load
+                app_context: open_optional(row.get("app_context"))?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Some(Conversation {
+        id: row.get("id"),
+        title: row.get("title"),
+        model: row.get("model"),
+        profile_id: row.get("profile_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        messages,
+    }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_conversation(
+    app: AppHandle,
+    title: String,
+    model: Option<String>,
+    profile_id: Option<String>,
+) -> Result<ConversationSummary, String> {
+    let pool = pool(&app).await?;
+    let now = now_ms();
+    let conversation = ConversationSummary {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: title.trim().to_string(),
+        model,
+        profile_id,
+        created_at: now,
+        updated_at: now,
+        message_count: 0,
+    };
+
+    sqlx::query(
+        "INSERT INTO conversations (id, title, model, profile_id, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&conversation.id)
+    .bind(&conversation.title)
+    .bind(&conversation.model)
+    .bind(&conversation.profile_id)
+    .bind(conversation.created_at)
+    .bind(conversation.updated_at)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to create conversation: {}", e))?;
+
+    Ok(conversation)
+}
+
+// Appends a message (or replaces one with the same id, e.g. a finished
+// stream) along with its capture references.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_message(
+    app: AppHandle,
+    conversation_id: String,
+    mut message: StoredMessage,
+) -> Result<(), String> {
+    if !["user", "assistant", "system"].contains(&message.role.as_str()) {
+        return Err(format!("Invalid message role: {}", message.role));
+    }
+    if message.role == "user" && message.app_context.is_none() {
+        message.app_context = crate::focus::last_focused(&app);
+    }
+
+    let pool = pool(&app).await?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    write_message(&mut tx, &conversation_id, &message).await?;
+
+    // The latest model answering becomes the conversation's model
+    if message.model.is_some() {
+        sqlx::query("UPDATE conversations SET model = ? WHERE id = ?")
+            .bind(&message.model)
+            .bind(&conversation_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update conversation: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to save message: {}", e))
+}
+
+// Inserts or updates a message and replaces its captures
+async fn write_message(
+    conn: &mut SqliteConnection,
+    conversation_id: &str,
+    message: &StoredMessage,
+) -> Result<(), String> {
+    let app_context = message
+        .app_context
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("Failed to serialize app context: {}", e))?;
+
+    sqlx::query(
+        "INSERT INTO messages
+            (id, conversation_id, role, content, timestamp, attached_files, model, app_context)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+            content = excluded.content,
+            timestamp = excluded.timestamp,
+            attached_files = excluded.attached_files,
+            model = excluded.model,
+            app_context = COALESCE(messages.app_context, excluded.app_context)",
+    )
+    .bind(&message.id)
+    .bind(conversation_id)
+    .bind(&message.role)
+    .bind(encryption::seal_text(&message.content)?)
+    .bind(message.timestamp)
+    .bind(encryption::seal_optional(&message.attached_files)?)
+    .bind(&message.model)
+    .bind(encryption::seal_optional(&app_context)?)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to save message: {}", e))?;
+
+    sqlx::query("DELETE FROM message_captures WHERE message_id = ?")
+        .bind(&message.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to save captures: {}", e))?;
+    for capture in &message.captures {
+        encryption::seal_file(Path::new(&capture.path))?;
+        sqlx::query(
+            "INSERT INTO message_captures (message_id, path, kind, ocr_text, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&message.id)
+        .bind(&capture.path)
+        .bind(&capture.kind)
+        .bind(encryption::seal_optional(&capture.ocr_text)?)
+        .bind(now_ms())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to save captures: {}", e))?;
+    }
+    Ok(())
+}
+
+// A saved capture as base64, decrypted when history encryption sealed it.
+// Only paths referenced by a message can be read.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_capture(app: AppHandle, path: String) -> Result<String, String> {
+    let pool = pool(&app).await?;
+    let known: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM message_captures WHERE path = ?)")
+            .bind(&path)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| format!("Failed to read captures: {}", e))?;
+    if !known {
+        return Err(format!("Unknown capture: {}", path));
+    }
+    let bytes = encryption::read_file(Path::new(&path))?;
+    Ok(general_purpose::STANDARD.encode(bytes))
+}
+
+// Writes conversation `id` to `path` as Markdown, standalone HTML or PDF for
+// sharing, returning the path written
+#[tauri::command]
+#[specta::specta]
+pub async fn export_conversation(
+    app: AppHandle,
+    id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<String, String> {
+    let conversation = load_conversation(app, id.clone())
+        .await?
+        .ok_or_else(|| format!("Conversation not found: {}", id))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let target = Path::new(&path);
+        let write = |contents: String| {
+            std::fs::write(target, contents)
+                .map_err(|e| format!("Failed to export conversation: {}", e))
+        };
+        match format {
+            ExportFormat::Markdown => write(render_markdown(&conversation, target)?)?,
+            ExportFormat::Html => write(render_html(&conversation))?,
+            ExportFormat::Pdf => render_pdf(&render_html(&conversation), target)?,
+        }
+        Ok(path)
+    })
+    .await
+    .map_err(|e| format!("Failed to export conversation: {}", e))?
+}
+
+// Backs up the conversations matching `filter` (all of them when None) to a
+// ZIP at `path`, with their captures
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history(
+    app: AppHandle,
+    filter: Option<HistoryFilter>,
+    path: String,
+) -> Result<HistoryArchiveReport, String> {
+    let filter = filter.unwrap_or_default();
+    let pool = pool(&app).await?;
+    let mut ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM conversations
+         WHERE (?1 IS NULL OR updated_at >= ?1) AND (?2 IS NULL OR updated_at <= ?2)
+         ORDER BY created_at ASC",
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to list conversations: {}", e))?;
+    if let Some(wanted) = &filter.ids {
+        ids.retain(|id| wanted.contains(id));
+    }
+    if ids.is_empty() {
+        return Err("No conversations to export".to_string());
+    }
+
+    let mut conversations = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(conversation) = load_conversation(app.clone(), id).await? {
+            conversations.push(conversation);
+        }
+    }
+    let mut report = HistoryArchiveReport {
+        conversations: conversations.len(),
+        messages: conversations.iter().map(|c| c.messages.len()).sum(),
+        ..Default::default()
+    };
+    let exported_at = now_ms();
+    report.captures = tauri::async_runtime::spawn_blocking(move || {
+        write_archive(conversations, Path::new(&path), exported_at)
+    })
+    .await
+    .map_err(|e| format!("Failed to export history: {}", e))??;
+    Ok(report)
+}
+
+// Restores conversations from an `export_history` archive. Conversations
+// already in the history are skipped, so importing twice adds nothing.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_history(app: AppHandle, path: String) -> Result<HistoryArchiveReport, String> {
+    let archive = tauri::async_runtime::spawn_blocking(move || read_archive(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Failed to import history: {}", e))??;
+    let dir = crate::captures::captures_dir(&app)?;
+    let pool = pool(&app).await?;
+
+    let mut report = HistoryArchiveReport::default();
+    // Archive name of each capture to where it was restored
+    let mut restored: HashMap<String, String> = HashMap::new();
+    for mut conversation in archive.conversations {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM conversations WHERE id = ?)")
+                .bind(&conversation.id)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| format!("Failed to read conversations: {}", e))?;
+        if exists {
+            report.skipped += 1;
+            continue;
+        }
+
+        for capture in conversation
+            .messages
+            .iter_mut()
+            .flat_map(|message| message.captures.iter_mut())
+        {
+            let path = match restored.get(&capture.path) {
+                Some(path) => path.clone(),
+                None => {
+                    let bytes = archive
+                        .captures
+                        .get(&capture.path)
+                        .ok_or_else(|| format!("Capture missing from archive: {}", capture.path))?;
+                    let path = restore_capture(&dir, &capture.path, bytes)?
+                        .to_string_lossy()
+                        .to_string();
+                    restored.insert(capture.path.clone(), path.clone());
+                    path
+                }
+            };
+            capture.path = path;
+        }
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        sqlx::query(
+            "INSERT INTO conversations (id, title, model, profile_id, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&conversation.id)
+        .bind(&conversation.title)
+        .bind(&conversation.model)
+        .bind(&conversation.profile_id)
+        .bind(conversation.created_at)
+        .bind(conversation.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to import conversation: {}", e))?;
+        for message in &conversation.messages {
+            write_message(&mut tx, &conversation.id, message).await?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to import conversation: {}", e))?;
+
+        report.conversations += 1;
+        report.messages += conversation.messages.len();
+    }
+    report.captures = restored.len();
+    Ok(report)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_conversation(
+    app: AppHandle,
+    id: String,
+    title: String,
+) -> Result<bool, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+
+    let pool = pool(&app).await?;
+    let result = sqlx::query("UPDATE conversations SET title = ?, updated_at = ? WHERE id = ?")
+        .bind(title)
+        .bind(now_ms())
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to rename conversation: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_conversation(app: AppHandle, id: String) -> Result<bool, String> {
+    let pool = pool(&app).await?;
+    // Messages and captures cascade
+    let result = sqlx::query("DELETE FROM conversations WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete conversation: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Ranked full-text matches across message content and capture OCR text.
+// The index only holds sealed text while history encryption is on.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history(
+    app: AppHandle,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<Vec<SearchHit>, String> {
+    if encryption::is_enabled() {
+        return Err(
+            "Search is unavailable while history encryption is on. Turn it off in privacy settings to search."
+                .to_string(),
+        );
+    }
+    let Some(fts) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let filters = filters.unwrap_or_default();
+    let source = match filters.source {
+        SearchSource::All => "all",
+        SearchSource::Messages => "messages",
+        SearchSource::Captures => "captures",
+    };
+
+    let pool = pool(&app).await?;
+    sqlx::query_as::<_, SearchHit>(SEARCH_SQL)
+        .bind(fts)
+        .bind(filters.conversation_id)
+        .bind(filters.role)
+        .bind(filters.from)
+        .bind(filters.to)
+        .bind(filters.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 500))
+        .bind(source)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to search history: {}", e))
+}
+
+// Groups usage since `since` by a fixed SQL expression; `key` is never user input
+async fn usage_buckets(
+    pool: &sqlx::SqlitePool,
+    key: &str,
+    since: i64,
+) -> Result<Vec<UsageBucket>, String> {
+    let sql = format!(
+        "SELECT {} AS key,
+                COUNT(*) AS requests,
+                COALESCE(SUM(input_tokens), 0) AS input_tokens,
+                COALESCE(SUM(output_tokens), 0) AS output_tokens,
+                COALESCE(SUM(cost_usd), 0.0) AS cost_usd,
+                COUNT(*) - COUNT(cost_usd) AS unpriced_requests
+         FROM llm_usage
+         WHERE created_at >= ?
+         GROUP BY 1
+         ORDER BY 1 DESC",
+        key
+    );
+    sqlx::query_as::<_, UsageBucket>(&sql)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read usage: {}", e))
+}
+
+// Tokens and estimated cost over `period`, in total and per day, provider and model
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_summary(
+    app: AppHandle,
+    period: Option<UsagePeriod>,
+) -> Result<UsageSummary, String> {
+    let period = period.unwrap_or_default();
+    let since = period.duration_ms().map(|ms| now_ms() - ms);
+    let from = since.unwrap_or(0);
+    let pool = pool(&app).await?;
+
+    let total = usage_buckets(&pool, "'total'", from)
+        .await?
+        .pop()
+        .unwrap_or(UsageBucket {
+            key: "total".to_string(),
+            requests: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cost_usd: 0.0,
+            unpriced_requests: 0,
+        });
+    let by_day = usage_buckets(
+        &pool,
+        "date(created_at / 1000, 'unixepoch', 'localtime')",
+        from,
+    )
+    .await?;
+    let mut by_provider = usage_buckets(&pool, "provider", from).await?;
+    let mut by_model = usage_buckets(&pool, "model", from).await?;
+    // Most expensive first
+    by_provider.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    by_model.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+
+    Ok(UsageSummary {
+        period,
+        since,
+        total,
+        by_day,
+        by_provider,
+        by_model,
+    })
+}
+
+// Empties the LLM response cache, returning how many answers were removed
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_response_cache(app: AppHandle) -> Result<u64, String> {
+    let pool = pool(&app).await?;
+    let result = sqlx::query("DELETE FROM response_cache")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to clear response cache: {}", e))?;
+    Ok(result.rows_affected())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_queued_requests(app: AppHandle) -> Result<Vec<QueuedRequest>, String> {
+    queued_requests(&app).await
+}
+
+// Drops a queued request so it is never sent
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_queued_request(app: AppHandle, id: String) -> Result<bool, String> {
+    remove_queued_request(&app, &id).await
+}
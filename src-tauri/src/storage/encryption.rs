@@ -0,0 +1,300 @@
+// Optional encryption of history at rest. Message text, capture OCR text,
+// cached answers and queued requests are sealed column by column, and capture
+// and attachment files in place, with a random key kept in the OS keychain. The database
+// itself stays plain SQLite so the frontend's SQL plugin can still write to it;
+// history is read back through `load_conversation`, which opens sealed values.
+// The search index can't match sealed text, so search is off meanwhile.
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use specta::Type;
+use sqlx::Row;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+use super::pool;
+use crate::crypto::{self, KEY_LEN};
+
+// Keychain entry holding the base64 key
+pub const HISTORY_KEY_ID: &str = "history_encryption";
+// Marks sealed column values; anything else is plaintext
+pub const TEXT_PREFIX: &str = "enc:v1:";
+// Marks sealed capture files
+const FILE_MAGIC: &[u8] = b"PLUELYENC1";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static KEY: Mutex<Option<[u8; KEY_LEN]>> = Mutex::new(None);
+// One conversion at a time; toggling twice quickly queues the second
+static CONVERTING: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionProgress {
+    pub encrypt: bool,
+    pub rows: u64,
+    pub files: u64,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+// Created on first use. Never deleted, so turning encryption off can still
+// read what was sealed before.
+fn key() -> Result<[u8; KEY_LEN], String> {
+    let mut cached = KEY
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(key) = *cached {
+        return Ok(key);
+    }
+
+    let key = match crate::secrets::get_api_key(HISTORY_KEY_ID)? {
+        Some(encoded) => general_purpose::STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .ok_or("History encryption key in the keychain is invalid".to_string())?,
+        None => {
+            let key = crypto::random_key();
+            crate::secrets::store_api_key(HISTORY_KEY_ID, &general_purpose::STANDARD.encode(key))?;
+            key
+        }
+    };
+    *cached = Some(key);
+    Ok(key)
+}
+
+fn seal_text_with(key: &[u8; KEY_LEN], text: &str) -> Result<String, String> {
+    let sealed = crypto::encrypt(key, text.as_bytes())?;
+    Ok(format!(
+        "{}{}",
+        TEXT_PREFIX,
+        general_purpose::STANDARD.encode(sealed)
+    ))
+}
+
+// Seals `text` when encryption is on; already sealed text is left alone
+pub fn seal_text(text: &str) -> Result<String, String> {
+    if !is_enabled() || text.starts_with(TEXT_PREFIX) {
+        return Ok(text.to_string());
+    }
+    seal_text_with(&key()?, text)
+}
+
+pub fn seal_optional(text: &Option<String>) -> Result<Option<String>, String> {
+    text.as_deref().map(seal_text).transpose()
+}
+
+// Plaintext of a value read back, sealed or not
+pub fn open_text(text: String) -> Result<String, String> {
+    let Some(encoded) = text.strip_prefix(TEXT_PREFIX) else {
+        return Ok(text);
+    };
+    let sealed = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode encrypted history: {}", e))?;
+    let plain = crypto::decrypt(&key()?, &sealed)?;
+    String::from_utf8(plain).map_err(|e| format!("Failed to decode encrypted history: {}", e))
+}
+
+pub fn open_optional(text: Option<String>) -> Result<Option<String>, String> {
+    text.map(open_text).transpose()
+}
+
+// Contents of a capture file, decrypted if it was sealed
+pub fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read capture: {}", e))?;
+    match bytes.strip_prefix(FILE_MAGIC) {
+        Some(sealed) => crypto::decrypt(&key()?, sealed),
+        None => Ok(bytes),
+    }
+}
+
+// Rewrites a capture file sealed or plain; returns whether it changed
+fn convert_file(path: &Path, encrypt: bool) -> Result<bool, String> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read capture: {}", e))?;
+    let sealed = bytes.starts_with(FILE_MAGIC);
+    let converted = match (encrypt, sealed) {
+        (true, false) => {
+            let mut out = FILE_MAGIC.to_vec();
+            out.extend(crypto::encrypt(&key()?, &bytes)?);
+            out
+        }
+        (false, true) => crypto::decrypt(&key()?, &bytes[FILE_MAGIC.len()..])?,
+        _ => return Ok(false),
+    };
+
+    let tmp = path.with_extension("pluely-tmp");
+    std::fs::write(&tmp, converted).map_err(|e| format!("Failed to write capture: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write capture: {}", e))?;
+    Ok(true)
+}
+
+// Seals a capture file as it is saved, when encryption is on
+pub fn seal_file(path: &Path) -> Result<(), String> {
+    if is_enabled() {
+        convert_file(path, true)?;
+    }
+    Ok(())
+}
+
+// (table, key column, value column) of every sealed column
+const SEALED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("messages", "id", "content"),
+    ("messages", "id", "attached_files"),
+    ("messages", "id", "app_context"),
+    ("message_captures", "id", "ocr_text"),
+    ("response_cache", "key", "response"),
+    ("request_queue", "id", "request"),
+];
+
+// Seals or opens every stored row and capture file to match `encrypt`.
+// Rows the frontend wrote in plaintext are picked up on the next run.
+async fn convert_history(app: &AppHandle, encrypt: bool) -> Result<EncryptionProgress, String> {
+    let _converting = CONVERTING.lock().await;
+    let pool = pool(app).await?;
+    let key = key()?;
+    let mut progress = EncryptionProgress {
+        encrypt,
+        ..Default::default()
+    };
+
+    for (table, id_column, column) in SEALED_COLUMNS {
+        // Table and column names are constants above, never user input
+        let filter = if encrypt { "NOT LIKE" } else { "LIKE" };
+        let select = format!(
+            "SELECT CAST({id} AS TEXT) AS id, {col} AS value FROM {table}
+             WHERE {col} IS NOT NULL AND {col} {filter} '{prefix}%'",
+            id = id_column,
+            col = column,
+            table = table,
+            filter = filter,
+            prefix = TEXT_PREFIX,
+        );
+        let rows = sqlx::query(&select)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+
+        let update = format!(
+            "UPDATE {} SET {} = ? WHERE CAST({} AS TEXT) = ?",
+            table, column, id_column
+        );
+        for row in rows {
+            let value: String = row.get("value");
+            let converted = if encrypt {
+                seal_text_with(&key, &value)?
+            } else {
+                open_text(value)?
+            };
+            sqlx::query(&update)
+                .bind(converted)
+                .bind(row.get::<String, _>("id"))
+                .execute(&pool)
+                .await
+                .map_err(|e| format!("Failed to update {}: {}", table, e))?;
+            progress.rows += 1;
+        }
+        let _ = app.emit(
+            "history-encryption",
+            crate::events::HistoryEncryption(progress.clone()),
+        );
+    }
+
+    let paths: Vec<String> = sqlx::query_scalar("SELECT DISTINCT path FROM message_captures")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to read captures: {}", e))?;
+    for path in paths {
+        if convert_file(Path::new(&path), encrypt)? {
+            progress.files += 1;
+        }
+    }
+
+    // Attachment pages and thumbnails, one directory per attachment
+    let dirs: Vec<String> = sqlx::query_scalar("SELECT path FROM attachments")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to read attachments: {}", e))?;
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if convert_file(&entry.path(), encrypt)? {
+                progress.files += 1;
+            }
+        }
+    }
+
+    if encrypt && progress.rows > 0 {
+        // Drop plaintext left in the search index and in freed pages
+        for sql in [
+            "INSERT INTO messages_fts(messages_fts) VALUES ('optimize')",
+            "INSERT INTO captures_fts(captures_fts) VALUES ('optimize')",
+            "VACUUM",
+        ] {
+            sqlx::query(sql)
+                .execute(&pool)
+                .await
+                .map_err(|e| format!("Failed to compact database: {}", e))?;
+        }
+    }
+    Ok(progress)
+}
+
+// Forgets the key and turns encryption off without converting anything, once
+// any running conversion has finished. Used when the workspace changes.
+pub async fn reset_history_encryption() {
+    let _converting = CONVERTING.lock().await;
+    ENABLED.store(false, Ordering::SeqCst);
+    if let Ok(mut key) = KEY.lock() {
+        *key = None;
+    }
+}
+
+// Turns encryption on or off and converts existing history in the background,
+// reporting through `history-encryption` events
+pub fn set_history_encryption(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        // Fail now rather than in the background if the keychain is unusable
+        key()?;
+    }
+    let was_enabled = ENABLED.swap(enabled, Ordering::SeqCst);
+    // Enabled also runs at startup to seal rows written in plaintext since
+    if !enabled && !was_enabled {
+        return Ok(());
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let progress = match convert_history(&app, enabled).await {
+            Ok(progress) => EncryptionProgress {
+                done: true,
+                ..progress
+            },
+            Err(e) => {
+                warn!("Failed to convert history encryption: {}", e);
+                EncryptionProgress {
+                    encrypt: enabled,
+                    done: true,
+                    error: Some(e),
+                    ..Default::default()
+                }
+            }
+        };
+        let _ = app.emit(
+            "history-encryption",
+            crate::events::HistoryEncryption(progress),
+        );
+    });
+    Ok(())
+}
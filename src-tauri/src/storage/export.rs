@@ -0,0 +1,212 @@
+// Renders a saved conversation for sharing outside the app: Markdown with its
+// captures in a folder beside it, a single HTML file with them inlined, or a
+// PDF printed from that HTML by a headless Chromium-based browser.
+use base64::{engine::general_purpose, Engine as _};
+use pulldown_cmark::{html, Event, Options, Parser};
+use serde::Deserialize;
+use specta::Type;
+use std::path::Path;
+
+use super::{encryption, Conversation, StoredMessage};
+use crate::captures::http_date;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI',Roboto,sans-serif;\
+max-width:860px;margin:2em auto;padding:0 1em;color:#1f2328;line-height:1.5}\
+header{border-bottom:1px solid #d0d7de;margin-bottom:1.5em}\
+.meta{color:#656d76;font-size:.9em}\
+.message{margin:1.5em 0;padding:1em 1.25em;border-radius:8px;border:1px solid #d0d7de}\
+.user{background:#f6f8fa}\
+.role{font-weight:600;margin-bottom:.5em}\
+pre{background:#0d1117;color:#e6edf3;padding:1em;border-radius:6px;overflow-x:auto}\
+code{font-family:ui-monospace,Menlo,Consolas,monospace;font-size:.9em}\
+img{max-width:100%;border:1px solid #d0d7de;border-radius:6px;margin-top:.5em}\
+table{border-collapse:collapse}td,th{border:1px solid #d0d7de;padding:.3em .6em}";
+
+fn role_label(role: &str) -> &str {
+    match role {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "system" => "System",
+        other => other,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn mime_type(bytes: &[u8]) -> Option<&'static str> {
+    match crate::captures::extension_for(bytes) {
+        "png" => Some("image/png"),
+        "jpg" => Some("image/jpeg"),
+        _ => None,
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn header_line(conversation: &Conversation) -> String {
+    let mut line = format!(
+        "Exported from Pluely · {}",
+        http_date(conversation.created_at)
+    );
+    if let Some(model) = &conversation.model {
+        line.push_str(&format!(" · {}", model));
+    }
+    line
+}
+
+// Markdown, with the captures copied into `<name>-captures` next to `path`
+pub fn render_markdown(conversation: &Conversation, path: &Path) -> Result<String, String> {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "conversation".to_string());
+    let captures_dir_name = format!("{}-captures", stem);
+    let captures_dir = path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(&captures_dir_name);
+
+    let mut out = format!(
+        "# {}\n\n_{}_\n\n",
+        conversation.title,
+        header_line(conversation)
+    );
+    for message in &conversation.messages {
+        out.push_str(&format!(
+            "## {} · {}\n\n{}\n\n",
+            role_label(&message.role),
+            http_date(message.timestamp),
+            message.content.trim()
+        ));
+        for capture in &message.captures {
+            let name = file_name(&capture.path);
+            match encryption::read_file(Path::new(&capture.path)) {
+                Ok(bytes) if mime_type(&bytes).is_some() => {
+                    std::fs::create_dir_all(&captures_dir)
+                        .map_err(|e| format!("Failed to create captures folder: {}", e))?;
+                    std::fs::write(captures_dir.join(&name), bytes)
+                        .map_err(|e| format!("Failed to export capture: {}", e))?;
+                    out.push_str(&format!("![{}]({}/{})\n\n", name, captures_dir_name, name));
+                }
+                _ => out.push_str(&format!("_[{}: {}]_\n\n", capture.kind, name)),
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Message text as HTML. Raw HTML in messages is shown as text, so an export
+// can't carry markup or scripts the user never saw.
+fn markdown_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    )
+    .map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        event => event,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+fn render_message_html(message: &StoredMessage) -> String {
+    let mut out = format!(
+        "<section class=\"message {}\"><div class=\"role\">{} <span class=\"meta\">{}</span></div>{}",
+        escape_html(&message.role),
+        escape_html(role_label(&message.role)),
+        escape_html(&http_date(message.timestamp)),
+        markdown_to_html(&message.content)
+    );
+    for capture in &message.captures {
+        let name = escape_html(&file_name(&capture.path));
+        match encryption::read_file(Path::new(&capture.path)) {
+            Ok(bytes) => match mime_type(&bytes) {
+                Some(mime) => out.push_str(&format!(
+                    "<img alt=\"{}\" src=\"data:{};base64,{}\">",
+                    name,
+                    mime,
+                    general_purpose::STANDARD.encode(bytes)
+                )),
+                None => out.push_str(&format!(
+                    "<p class=\"meta\">[{}: {}]</p>",
+                    escape_html(&capture.kind),
+                    name
+                )),
+            },
+            Err(_) => out.push_str(&format!(
+                "<p class=\"meta\">[Missing capture: {}]</p>",
+                name
+            )),
+        }
+    }
+    out.push_str("</section>");
+    out
+}
+
+// One self-contained page, captures inlined as data URLs
+pub fn render_html(conversation: &Conversation) -> String {
+    let title = escape_html(&conversation.title);
+    let messages: String = conversation
+        .messages
+        .iter()
+        .map(render_message_html)
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+         <style>{}</style></head><body><header><h1>{}</h1><p class=\"meta\">{}</p>\
+         </header>{}</body></html>\n",
+        title,
+        STYLE,
+        title,
+        escape_html(&header_line(conversation)),
+        messages
+    )
+}
+
+// Prints `html` to a PDF at `path`
+pub fn render_pdf(html: &str, path: &Path) -> Result<(), String> {
+    let browser = crate::browser::find_browser()
+        .ok_or("PDF export needs Chrome, Edge or Chromium installed; export as HTML instead")?;
+    let page = std::env::temp_dir().join(format!("pluely-export-{}.html", uuid::Uuid::new_v4()));
+    std::fs::write(&page, html).map_err(|e| format!("Failed to write export page: {}", e))?;
+    let output = std::process::Command::new(browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-pdf-header-footer")
+        .arg(format!("--print-to-pdf={}", path.display()))
+        .arg(
+            reqwest::Url::from_file_path(&page)
+                .map_or_else(|_| page.display().to_string(), |url| url.to_string()),
+        )
+        .output();
+    let _ = std::fs::remove_file(&page);
+
+    let output = output.map_err(|e| format!("Failed to run PDF renderer: {}", e))?;
+    if !output.status.success() || !path.exists() {
+        return Err(format!(
+            "PDF export failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,450 @@
+// Pluely conversation storage. Shares pluely.db with the frontend's SQL plugin
+// and its migrations, so either side can open the database first.
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::migrate::{Migration, MigrationType, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use std::borrow::Cow;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+mod archive;
+mod commands;
+mod encryption;
+mod export;
+mod search;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+pub use encryption::{
+    open_text, read_file, reset_history_encryption, seal_file, set_history_encryption,
+    EncryptionProgress, HISTORY_KEY_ID,
+};
+
+// Same file the SQL plugin opens as `sqlite:pluely.db`
+const DB_FILE: &str = "pluely.db";
+
+// What the frontend's SQL plugin opens for the active workspace. Its paths are
+// relative to the app config directory, like the workspace directories.
+pub fn sql_plugin_url() -> String {
+    let workspace = crate::workspaces::active_workspace();
+    if workspace == crate::workspaces::DEFAULT_WORKSPACE {
+        format!("sqlite:{}", DB_FILE)
+    } else {
+        format!(
+            "sqlite:{}/{}/{}",
+            crate::workspaces::WORKSPACES_DIR,
+            workspace,
+            DB_FILE
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct StorageState {
+    pool: Mutex<Option<SqlitePool>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSummary {
+    pub id: String,
+    pub title: String,
+    pub model: Option<String>,
+    pub profile_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub message_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRef {
+    pub path: String,
+    #[serde(default = "default_capture_kind")]
+    pub kind: String,
+    // Text read from the capture, so history search can find it
+    #[serde(default)]
+    pub ocr_text: Option<String>,
+}
+
+fn default_capture_kind() -> String {
+    "screenshot".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredMessage {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    pub timestamp: i64,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub captures: Vec<CaptureRef>,
+    // JSON the frontend keeps for inline attachments
+    #[serde(default)]
+    pub attached_files: Option<String>,
+    // Where the user was when asking; filled in for user messages while focus
+    // tracking is on
+    #[serde(default)]
+    pub app_context: Option<crate::focus::FocusedApp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    pub model: Option<String>,
+    pub profile_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub messages: Vec<StoredMessage>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    #[default]
+    All,
+    Messages,
+    Captures,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchFilters {
+    pub conversation_id: Option<String>,
+    pub role: Option<String>,
+    // Unix milliseconds, inclusive
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub source: SearchSource,
+    pub limit: Option<i64>,
+}
+
+// Which conversations `export_history` includes; all of them when empty
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    pub ids: Option<Vec<String>>,
+    // Unix milliseconds, inclusive, matched against when a conversation was
+    // last active
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryArchiveReport {
+    pub conversations: usize,
+    pub messages: usize,
+    pub captures: usize,
+    // Conversations left alone on import because one with the same id exists
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    // "message" or "capture"
+    pub source: String,
+    pub message_id: String,
+    pub conversation_id: String,
+    pub conversation_title: String,
+    pub role: String,
+    pub timestamp: i64,
+    // Matched terms are wrapped in `**`
+    pub snippet: String,
+    pub rank: f64,
+    pub capture_path: Option<String>,
+}
+
+// What one LLM request used, as recorded by the llm module
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub request_id: String,
+    pub provider: String,
+    pub model: String,
+    // None when the request was built by the frontend and couldn't be counted
+    pub input_tokens: Option<u32>,
+    pub output_tokens: u32,
+    pub cost_usd: Option<f64>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UsagePeriod {
+    // Rolling windows ending now
+    Day,
+    #[default]
+    Week,
+    Month,
+    All,
+}
+
+impl UsagePeriod {
+    pub fn duration_ms(self) -> Option<i64> {
+        const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+        match self {
+            UsagePeriod::Day => Some(DAY_MS),
+            UsagePeriod::Week => Some(7 * DAY_MS),
+            UsagePeriod::Month => Some(30 * DAY_MS),
+            UsagePeriod::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    // Local date (YYYY-MM-DD), provider or model, depending on the grouping
+    pub key: String,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+    // Requests whose model has no known price, so `cost_usd` is a lower bound
+    pub unpriced_requests: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub period: UsagePeriod,
+    // Unix milliseconds; None for all time
+    pub since: Option<i64>,
+    pub total: UsageBucket,
+    pub by_day: Vec<UsageBucket>,
+    pub by_provider: Vec<UsageBucket>,
+    pub by_model: Vec<UsageBucket>,
+}
+
+// A chat request waiting for the network to come back
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRequest {
+    pub id: String,
+    // JSON owned by the llm module; large when images are attached
+    #[serde(skip)]
+    pub request: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub last_attempt_at: Option<i64>,
+}
+
+// The SQL plugin's migrations, in the form sqlx runs them. Both sides record
+// versions in the same `_sqlx_migrations` table, so each runs only once.
+fn migrator() -> Migrator {
+    let migrations = crate::db::migrations()
+        .into_iter()
+        .map(|migration| {
+            let kind = match migration.kind {
+                tauri_plugin_sql::MigrationKind::Up => MigrationType::ReversibleUp,
+                tauri_plugin_sql::MigrationKind::Down => MigrationType::ReversibleDown,
+            };
+            Migration::new(
+                migration.version,
+                Cow::Borrowed(migration.description),
+                kind,
+                Cow::Borrowed(migration.sql),
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..Migrator::DEFAULT
+    }
+}
+
+// Opens the database on first use and brings the schema up to date
+pub async fn pool(app: &AppHandle) -> Result<SqlitePool, String> {
+    let state = app.state::<StorageState>();
+    if let Some(pool) = state
+        .pool
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone()
+    {
+        return Ok(pool);
+    }
+
+    // Each workspace profile has its own database
+    let dir = crate::workspaces::config_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+
+    let options = SqliteConnectOptions::new()
+        .filename(dir.join(DB_FILE))
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    migrator()
+        .run(&pool)
+        .await
+        .map_err(|e| format!("Failed to migrate database: {}", e))?;
+
+    *state
+        .pool
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(pool.clone());
+    Ok(pool)
+}
+
+// Closes the database so the next use opens the active workspace's
+pub async fn close_pool(app: &AppHandle) -> Result<(), String> {
+    let pool = app
+        .state::<StorageState>()
+        .pool
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .take();
+    if let Some(pool) = pool {
+        pool.close().await;
+    }
+    Ok(())
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+pub async fn record_usage(app: &AppHandle, usage: UsageRecord) -> Result<(), String> {
+    let pool = pool(app).await?;
+    sqlx::query(
+        "INSERT INTO llm_usage
+            (request_id, provider, model, input_tokens, output_tokens, cost_usd, cancelled, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&usage.request_id)
+    .bind(&usage.provider)
+    .bind(&usage.model)
+    .bind(usage.input_tokens)
+    .bind(usage.output_tokens)
+    .bind(usage.cost_usd)
+    .bind(usage.cancelled)
+    .bind(now_ms())
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to record usage: {}", e))?;
+    Ok(())
+}
+
+// A cached answer no older than `max_age_ms`, counting the hit
+pub async fn cached_response(
+    app: &AppHandle,
+    key: &str,
+    max_age_ms: i64,
+) -> Result<Option<String>, String> {
+    let pool = pool(app).await?;
+    let now = now_ms();
+    let row = sqlx::query(
+        "UPDATE response_cache SET hits = hits + 1, last_hit_at = ?
+         WHERE key = ? AND created_at >= ?
+         RETURNING response",
+    )
+    .bind(now)
+    .bind(key)
+    .bind(now.saturating_sub(max_age_ms))
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| format!("Failed to read response cache: {}", e))?;
+    row.map(|row| open_text(row.get("response"))).transpose()
+}
+
+pub async fn store_response(
+    app: &AppHandle,
+    key: &str,
+    provider: &str,
+    model: &str,
+    response: &str,
+) -> Result<(), String> {
+    let pool = pool(app).await?;
+    sqlx::query(
+        "INSERT OR REPLACE INTO response_cache (key, provider, model, response, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(key)
+    .bind(provider)
+    .bind(model)
+    .bind(encryption::seal_text(response)?)
+    .bind(now_ms())
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to write response cache: {}", e))?;
+    Ok(())
+}
+
+// Queues a request under its request id, replacing an earlier copy
+pub async fn enqueue_request(app: &AppHandle, id: &str, request: &str) -> Result<(), String> {
+    let pool = pool(app).await?;
+    sqlx::query("INSERT OR REPLACE INTO request_queue (id, request, created_at) VALUES (?, ?, ?)")
+        .bind(id)
+        .bind(encryption::seal_text(request)?)
+        .bind(now_ms())
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to queue request: {}", e))?;
+    Ok(())
+}
+
+// Oldest first, so answers come back in the order they were asked
+pub async fn queued_requests(app: &AppHandle) -> Result<Vec<QueuedRequest>, String> {
+    let pool = pool(app).await?;
+    let requests = sqlx::query_as::<_, QueuedRequest>(
+        "SELECT id, request, attempts, last_error, created_at, last_attempt_at
+         FROM request_queue ORDER BY created_at ASC",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read request queue: {}", e))?;
+    requests
+        .into_iter()
+        .map(|request| {
+            Ok(QueuedRequest {
+                request: open_text(request.request)?,
+                ..request
+            })
+        })
+        .collect()
+}
+
+pub async fn record_queue_attempt(app: &AppHandle, id: &str, error: &str) -> Result<(), String> {
+    let pool = pool(app).await?;
+    sqlx::query(
+        "UPDATE request_queue SET attempts = attempts + 1, last_error = ?, last_attempt_at = ?
+         WHERE id = ?",
+    )
+    .bind(error)
+    .bind(now_ms())
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to update request queue: {}", e))?;
+    Ok(())
+}
+
+pub async fn remove_queued_request(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let pool = pool(app).await?;
+    let result = sqlx::query("DELETE FROM request_queue WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to update request queue: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
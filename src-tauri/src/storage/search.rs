@@ -0,0 +1,48 @@
+// Turns free text into an FTS5 query: every word must match, and the last
+// one matches as a prefix so results update while typing.
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
+
+// Ranks both sources together; bm25 is lower for better matches. Encrypted
+// rows are indexed as ciphertext, so they are left out.
+pub const SEARCH_SQL: &str = "
+    SELECT * FROM (
+        SELECT 'message' AS source, m.id AS message_id, m.conversation_id,
+               c.title AS conversation_title, m.role, m.timestamp,
+               snippet(messages_fts, 0, '**', '**', '…', 16) AS snippet,
+               bm25(messages_fts) AS rank, NULL AS capture_path
+        FROM messages_fts
+        JOIN messages m ON m.rowid = messages_fts.rowid
+        JOIN conversations c ON c.id = m.conversation_id
+        WHERE ?7 IN ('all', 'messages') AND messages_fts MATCH ?1
+          AND m.content NOT LIKE 'enc:v1:%'
+          AND (?2 IS NULL OR m.conversation_id = ?2)
+          AND (?3 IS NULL OR m.role = ?3)
+          AND (?4 IS NULL OR m.timestamp >= ?4)
+          AND (?5 IS NULL OR m.timestamp <= ?5)
+        UNION ALL
+        SELECT 'capture' AS source, m.id AS message_id, m.conversation_id,
+               c.title AS conversation_title, m.role, m.timestamp,
+               snippet(captures_fts, 0, '**', '**', '…', 16) AS snippet,
+               bm25(captures_fts) AS rank, mc.path AS capture_path
+        FROM captures_fts
+        JOIN message_captures mc ON mc.id = captures_fts.rowid
+        JOIN messages m ON m.id = mc.message_id
+        JOIN conversations c ON c.id = m.conversation_id
+        WHERE ?7 IN ('all', 'captures') AND captures_fts MATCH ?1
+          AND mc.ocr_text NOT LIKE 'enc:v1:%'
+          AND (?2 IS NULL OR m.conversation_id = ?2)
+          AND (?3 IS NULL OR m.role = ?3)
+          AND (?4 IS NULL OR m.timestamp >= ?4)
+          AND (?5 IS NULL OR m.timestamp <= ?5)
+    )
+    ORDER BY rank ASC
+    LIMIT ?6";
@@ -0,0 +1,318 @@
+// Pluely STT commands: provider configuration, one-shot and streaming transcription.
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use super::{
+    build_provider, PcmChunk, StreamEncoding, SttConfig, SttProvider, SttProviderKind,
+    DEEPGRAM_KEY_ID, OPENAI_KEY_ID,
+};
+use crate::api::decode_audio_base64;
+use crate::control::{BridgeEvent, TranscriptSource};
+use crate::events::{SttError, SttFinal, SttPartial};
+use crate::notify::{notify, summary, NotificationCategory};
+use crate::speaker::meter::LevelMeter;
+use crate::speaker::SpeakerInput;
+use crate::whisper::TranscriptUpdate;
+
+// Streaming chunks are forwarded to the provider every 100ms
+const STREAM_CHUNK_MS: u32 = 100;
+
+#[derive(Default)]
+pub struct SttState {
+    config: Mutex<SttConfig>,
+    stream_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+fn current_config(app: &AppHandle) -> Result<SttConfig, String> {
+    let state = app.state::<SttState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read STT config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_stt_config(app: AppHandle) -> Result<SttConfig, String> {
+    current_config(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_stt_config(app: AppHandle, mut config: SttConfig) -> Result<(), String> {
+    if config.provider == SttProviderKind::LocalWhisper && !crate::whisper::is_local_whisper_available()
+    {
+        return Err("Local Whisper support is not enabled in this build".to_string());
+    }
+
+    if let StreamEncoding::Opus { bitrate } = config.stream_encoding {
+        if !(6_000..=510_000).contains(&bitrate) {
+            return Err("Invalid Opus bitrate: expected 6000-510000 bps".to_string());
+        }
+    }
+
+    // Keys go to the keychain; the config keeps everything else
+    if let Some(api_key) = config.openai.api_key.take() {
+        crate::secrets::store_api_key(OPENAI_KEY_ID, &api_key)?;
+    }
+    if let Some(api_key) = config.deepgram.api_key.take() {
+        crate::secrets::store_api_key(DEEPGRAM_KEY_ID, &api_key)?;
+    }
+
+    let state = app.state::<SttState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update STT config: {}", e))? = config;
+
+    Ok(())
+}
+
+// Built on a blocking thread, as the local Whisper provider loads its model
+async fn provider_for(app: &AppHandle, config: &SttConfig) -> Result<Box<dyn SttProvider>, String> {
+    let (app, config) = (app.clone(), config.clone());
+    tauri::async_runtime::spawn_blocking(move || build_provider(&app, &config))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+// Transcribes a base64 WAV clip with the configured provider.
+#[tauri::command]
+#[specta::specta]
+pub async fn stt_transcribe(app: AppHandle, audio_base64: String) -> Result<String, String> {
+    let config = current_config(&app)?;
+    let provider = provider_for(&app, &config).await?;
+    let audio_bytes = decode_audio_base64(&audio_base64)?;
+
+    let started = std::time::Instant::now();
+    match provider.transcribe(audio_bytes).await {
+        Ok(text) => {
+            crate::analytics::timing("transcription", started.elapsed());
+            crate::control::publish(BridgeEvent::Transcription {
+                source: TranscriptSource::Recording,
+                role: None,
+                text: text.clone(),
+                is_final: true,
+            });
+            notify(
+                &app,
+                NotificationCategory::TranscriptionFinished,
+                "Transcription finished",
+                &summary(&text),
+            );
+            Ok(text)
+        }
+        Err(e) => {
+            error!("{} transcription failed: {}", provider.name(), e);
+            notify(
+                &app,
+                NotificationCategory::ProviderError,
+                "Transcription failed",
+                &summary(&e),
+            );
+            Err(e)
+        }
+    }
+}
+
+// Body of a request to a speech-to-text API set up with a curl command
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CustomSttBody {
+    // Multipart form: the audio as `file`, then `fields`
+    Form { fields: Vec<(String, String)> },
+    // The audio itself
+    Binary,
+    // JSON that already carries the audio
+    Json { text: String },
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CustomSttResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+// Sends a request to a custom speech-to-text API through the shared client,
+// so the proxy and local-only mode apply and it is recorded in the audit log.
+// `audio_base64` holds the clip and `mime_type` its type.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_custom_stt_request(
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: CustomSttBody,
+    audio_base64: String,
+    mime_type: String,
+) -> Result<CustomSttResponse, String> {
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|e| format!("Invalid request method: {}", e))?;
+    let has_body = method != reqwest::Method::GET;
+    let mut request = crate::network::http_client()?.request(method, &url);
+    let has_content_type = headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"));
+    let is_form = matches!(body, CustomSttBody::Form { .. });
+    for (name, value) in &headers {
+        // Multipart bodies carry their own boundary in the content type
+        if is_form && name.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
+        request = request.header(name, value);
+    }
+
+    if has_body {
+        request = match body {
+            CustomSttBody::Form { fields } => {
+                let mut part = reqwest::multipart::Part::bytes(decode_audio_base64(&audio_base64)?)
+                    .file_name("audio.wav");
+                if !mime_type.is_empty() {
+                    part = part
+                        .mime_str(&mime_type)
+                        .map_err(|e| format!("Invalid audio type: {}", e))?;
+                }
+                let mut form = reqwest::multipart::Form::new().part("file", part);
+                for (name, value) in fields {
+                    form = form.text(name, value);
+                }
+                request.multipart(form)
+            }
+            CustomSttBody::Binary => {
+                if !has_content_type && !mime_type.is_empty() {
+                    request = request.header("Content-Type", &mime_type);
+                }
+                request.body(decode_audio_base64(&audio_base64)?)
+            }
+            CustomSttBody::Json { text } => request.body(text),
+        };
+    }
+
+    let response = crate::network::send(request, "stt", &[])
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+    let status = response.status().as_u16();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read transcription response: {}", e))?;
+    Ok(CustomSttResponse { status, body })
+}
+
+// Streams system audio to the configured provider, emitting `stt-partial` and
+// `stt-final` events as transcripts arrive.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_stt_stream(app: AppHandle, device_id: Option<String>) -> Result<(), String> {
+    let state = app.state::<SttState>();
+    {
+        let guard = state
+            .stream_task
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        if guard.is_some() {
+            return Err("STT stream already running".to_string());
+        }
+    }
+
+    let config = current_config(&app)?;
+    let provider = provider_for(&app, &config).await?;
+    if !provider.supports_streaming() {
+        return Err(format!(
+            "{} does not support streaming transcription",
+            provider.name()
+        ));
+    }
+
+    let input = SpeakerInput::new_with_device(device_id)
+        .map_err(|e| format!("Failed to access system audio: {}", e))?;
+    let mut stream = input.stream();
+    let sr = stream.sample_rate();
+    let chunk_samples = (sr * STREAM_CHUNK_MS / 1000) as usize;
+
+    let (audio_tx, audio_rx) = mpsc::channel::<PcmChunk>(64);
+    let (update_tx, mut update_rx) = mpsc::channel::<TranscriptUpdate>(64);
+
+    let app_clone = app.clone();
+    let task = tokio::spawn(async move {
+        // Capture -> PCM chunks
+        let meter_app = app_clone.clone();
+        let capture = tokio::spawn(async move {
+            let mut chunk = Vec::with_capacity(chunk_samples);
+            let mut meter = LevelMeter::new("system", sr);
+            while let Some(sample) = stream.next().await {
+                meter.push(&meter_app, sample);
+                chunk.push((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16);
+                if chunk.len() >= chunk_samples {
+                    let full = std::mem::replace(&mut chunk, Vec::with_capacity(chunk_samples));
+                    if audio_tx.send(full).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        // Provider -> events
+        let emitter_app = app_clone.clone();
+        let emitter = tokio::spawn(async move {
+            while let Some(update) = update_rx.recv().await {
+                crate::control::publish(BridgeEvent::Transcription {
+                    source: TranscriptSource::Stt,
+                    role: None,
+                    text: update.text.clone(),
+                    is_final: update.is_final,
+                });
+                let _ = if update.is_final {
+                    emitter_app.emit("stt-final", SttFinal(update))
+                } else {
+                    emitter_app.emit("stt-partial", SttPartial(update))
+                };
+            }
+        });
+
+        if let Err(e) = provider.transcribe_stream(audio_rx, sr, update_tx).await {
+            warn!("STT stream ended with error: {}", e);
+            let _ = app_clone.emit("stt-error", SttError(e));
+        }
+
+        capture.abort();
+        let _ = emitter.await;
+
+        let state = app_clone.state::<SttState>();
+        if let Ok(mut guard) = state.stream_task.lock() {
+            *guard = None;
+        };
+    });
+
+    *state
+        .stream_task
+        .lock()
+        .map_err(|e| format!("Failed to store task: {}", e))? = Some(task);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_stt_stream(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<SttState>();
+    let mut guard = state
+        .stream_task
+        .lock()
+        .map_err(|e| format!("Failed to acquire task lock: {}", e))?;
+
+    if let Some(task) = guard.take() {
+        task.abort();
+    }
+
+    Ok(())
+}
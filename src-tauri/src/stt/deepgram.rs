@@ -0,0 +1,223 @@
+// Deepgram prerecorded and live (websocket) transcription
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+use super::{DeepgramSttConfig, PcmChunk, StreamEncoding, SttProvider};
+use crate::speaker::opus::OggOpusWriter;
+use crate::whisper::TranscriptUpdate;
+
+pub(super) const DEEPGRAM_HOST: &str = "api.deepgram.com";
+
+pub struct DeepgramStt {
+    config: DeepgramSttConfig,
+    encoding: StreamEncoding,
+    api_key: String,
+}
+
+impl DeepgramStt {
+    pub fn new(config: DeepgramSttConfig, encoding: StreamEncoding) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or("Deepgram API key is not configured".to_string())?;
+        Ok(Self {
+            config,
+            encoding,
+            api_key,
+        })
+    }
+
+    // The listen endpoint with the configured model and language encoded
+    // into the query, followed by `params`
+    fn listen_url(&self, scheme: &str, params: &[(&str, String)]) -> Result<reqwest::Url, String> {
+        let mut url = reqwest::Url::parse(&format!("{}://{}/v1/listen", scheme, DEEPGRAM_HOST))
+            .map_err(|e| format!("Invalid Deepgram URL: {}", e))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("model", &self.config.model)
+                .append_pair("smart_format", "true");
+            if let Some(language) = &self.config.language {
+                query.append_pair("language", language);
+            }
+            query.extend_pairs(params);
+        }
+        Ok(url)
+    }
+}
+
+// Pulls the first alternative's transcript out of a Deepgram result
+fn extract_transcript(json: &serde_json::Value) -> Option<String> {
+    let alternatives = json
+        .pointer("/results/channels/0/alternatives/0/transcript")
+        .or_else(|| json.pointer("/channel/alternatives/0/transcript"))?;
+    alternatives.as_str().map(|text| text.trim().to_string())
+}
+
+#[async_trait]
+impl SttProvider for DeepgramStt {
+    fn name(&self) -> &'static str {
+        "deepgram"
+    }
+
+    async fn transcribe(&self, wav_bytes: Vec<u8>) -> Result<String, String> {
+        let url = self.listen_url("https", &[])?;
+
+        let request = crate::network::http_client()?
+            .post(url)
+            .header("Authorization", format!("Token {}", self.api_key))
+            .header("Content-Type", "audio/wav")
+            .body(wav_bytes);
+        let response = crate::network::send(request, "transcription", &[])
+            .await
+            .map_err(|e| format!("Transcription request failed to send: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown server error".to_string());
+            return Err(format!("Deepgram transcription error ({}): {}", status, error_text));
+        }
+
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse transcription response: {}", e))?;
+
+        extract_transcript(&json).ok_or("Transcription response did not contain text".to_string())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn transcribe_stream(
+        &self,
+        mut audio: mpsc::Receiver<PcmChunk>,
+        sample_rate: u32,
+        updates: mpsc::Sender<TranscriptUpdate>,
+    ) -> Result<(), String> {
+        // Ogg Opus is self-describing; raw PCM needs its format spelled out
+        let (mut params, mut opus) = match self.encoding {
+            StreamEncoding::Linear16 => (
+                vec![
+                    ("encoding", "linear16".to_string()),
+                    ("channels", "1".to_string()),
+                    ("sample_rate", sample_rate.to_string()),
+                ],
+                None,
+            ),
+            StreamEncoding::Opus { bitrate } => {
+                let writer = OggOpusWriter::streaming(Vec::new(), sample_rate, bitrate)
+                    .map_err(|e| format!("Failed to start Opus encoder: {}", e))?;
+                (Vec::new(), Some(writer))
+            }
+        };
+        params.push(("interim_results", "true".to_string()));
+        let url = self.listen_url("wss", &params)?;
+
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| format!("Invalid Deepgram URL: {}", e))?;
+        request.headers_mut().insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Token {}", self.api_key))
+                .map_err(|e| format!("Invalid Deepgram API key: {}", e))?,
+        );
+        // Audio is streamed, so its size isn't known up front
+        crate::audit::record_egress("transcription stream", &url, None, &[]);
+
+        let socket = crate::network::connect_websocket(request)
+            .await
+            .map_err(|e| format!("Failed to connect to Deepgram: {}", e))?;
+        let (mut sink, mut source) = socket.split();
+
+        // Upload audio until the capture side hangs up, then ask Deepgram to
+        // flush remaining results
+        let uploader = tokio::spawn(async move {
+            if let Some(writer) = opus.as_mut() {
+                // OpusHead/OpusTags pages go out before any audio
+                let header = std::mem::take(writer.get_mut());
+                if sink.send(Message::Binary(header)).await.is_err() {
+                    return;
+                }
+            }
+
+            while let Some(chunk) = audio.recv().await {
+                let bytes = match opus.as_mut() {
+                    Some(writer) => {
+                        let samples: Vec<f32> =
+                            chunk.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+                        if let Err(e) = writer.write_samples(&samples) {
+                            warn!("Opus encoding failed: {}", e);
+                            return;
+                        }
+                        std::mem::take(writer.get_mut())
+                    }
+                    None => chunk.iter().flat_map(|s| s.to_le_bytes()).collect(),
+                };
+                // Opus may not have a full 20ms frame yet
+                if bytes.is_empty() {
+                    continue;
+                }
+                if sink.send(Message::Binary(bytes)).await.is_err() {
+                    return;
+                }
+            }
+
+            if let Some(tail) = opus.take().and_then(|writer| writer.finalize().ok()) {
+                let _ = sink.send(Message::Binary(tail)).await;
+            }
+            let _ = sink
+                .send(Message::Text(r#"{"type":"CloseStream"}"#.to_string()))
+                .await;
+        });
+
+        while let Some(message) = source.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    uploader.abort();
+                    return Err(format!("Deepgram stream error: {}", e));
+                }
+            };
+
+            let Message::Text(text) = message else {
+                continue;
+            };
+
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+
+            if let Some(transcript) = extract_transcript(&json).filter(|t| !t.is_empty()) {
+                let is_final = json
+                    .get("is_final")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                if updates
+                    .send(TranscriptUpdate {
+                        text: transcript,
+                        is_final,
+                    })
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+
+        uploader.abort();
+        Ok(())
+    }
+}
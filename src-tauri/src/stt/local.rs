@@ -0,0 +1,120 @@
+// Offline transcription through the local Whisper engine
+use async_trait::async_trait;
+
+use super::SttProvider;
+
+#[cfg(feature = "whisper")]
+use super::PcmChunk;
+#[cfg(feature = "whisper")]
+use crate::whisper::{decode_wav_bytes, loaded_engine, TranscriptUpdate, WhisperEngine};
+#[cfg(feature = "whisper")]
+use std::sync::Arc;
+#[cfg(feature = "whisper")]
+use tokio::sync::mpsc;
+
+pub struct LocalWhisperStt {
+    #[cfg(feature = "whisper")]
+    engine: Arc<WhisperEngine>,
+}
+
+impl LocalWhisperStt {
+    #[cfg(feature = "whisper")]
+    pub fn new(app: &tauri::AppHandle) -> Result<Self, String> {
+        Ok(Self {
+            engine: loaded_engine(app)?,
+        })
+    }
+
+    #[cfg(not(feature = "whisper"))]
+    pub fn new(_app: &tauri::AppHandle) -> Result<Self, String> {
+        Err("Local Whisper support is not enabled in this build".to_string())
+    }
+}
+
+#[async_trait]
+impl SttProvider for LocalWhisperStt {
+    fn name(&self) -> &'static str {
+        "local_whisper"
+    }
+
+    #[cfg(feature = "whisper")]
+    async fn transcribe(&self, wav_bytes: Vec<u8>) -> Result<String, String> {
+        let (samples, sample_rate) = decode_wav_bytes(wav_bytes)?;
+        let engine = self.engine.clone();
+
+        tauri::async_runtime::spawn_blocking(move || engine.transcribe_buffer(&samples, sample_rate))
+            .await
+            .map_err(|e| format!("Task panicked: {}", e))?
+    }
+
+    #[cfg(not(feature = "whisper"))]
+    async fn transcribe(&self, _wav_bytes: Vec<u8>) -> Result<String, String> {
+        Err("Local Whisper support is not enabled in this build".to_string())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        cfg!(feature = "whisper")
+    }
+
+    // Re-runs inference over the growing window every `step_ms`, finalizing
+    // once the window is full (same behaviour as `start_whisper_stream`).
+    #[cfg(feature = "whisper")]
+    async fn transcribe_stream(
+        &self,
+        mut audio: mpsc::Receiver<PcmChunk>,
+        sample_rate: u32,
+        updates: mpsc::Sender<TranscriptUpdate>,
+    ) -> Result<(), String> {
+        let config = self.engine.config().clone();
+        let step_samples = (sample_rate as u64 * config.step_ms / 1000) as usize;
+        let window_samples = (sample_rate as u64 * config.window_ms / 1000) as usize;
+
+        let mut window: Vec<f32> = Vec::with_capacity(window_samples);
+        let mut since_step = 0usize;
+        let mut stream_open = true;
+
+        while stream_open {
+            match audio.recv().await {
+                Some(chunk) => {
+                    since_step += chunk.len();
+                    window.extend(chunk.iter().map(|&s| s as f32 / i16::MAX as f32));
+                    if since_step < step_samples {
+                        continue;
+                    }
+                }
+                // Flush whatever is left as a final segment
+                None => stream_open = false,
+            }
+            since_step = 0;
+
+            if window.is_empty() {
+                continue;
+            }
+
+            let is_final = !stream_open || window.len() >= window_samples;
+            let samples = if is_final {
+                std::mem::take(&mut window)
+            } else {
+                window.clone()
+            };
+
+            let engine = self.engine.clone();
+            let text = tauri::async_runtime::spawn_blocking(move || {
+                engine.transcribe_buffer(&samples, sample_rate)
+            })
+            .await
+            .map_err(|e| format!("Task panicked: {}", e))??;
+
+            if !text.is_empty()
+                && updates
+                    .send(TranscriptUpdate { text, is_final })
+                    .await
+                    .is_err()
+            {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
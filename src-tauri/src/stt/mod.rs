@@ -0,0 +1,173 @@
+// Pluely speech-to-text providers. The audio pipeline talks to the
+// `SttProvider` trait so no vendor is hardcoded.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::sync::mpsc;
+
+use crate::whisper::TranscriptUpdate;
+
+mod commands;
+mod deepgram;
+mod local;
+mod openai;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain accounts for provider keys; OpenAI shares its key with the LLM provider
+const OPENAI_KEY_ID: &str = "openai";
+const DEEPGRAM_KEY_ID: &str = "deepgram";
+// Hosts each keychain key may be sent to. An endpoint elsewhere needs its own
+// key in the config, so changing the base URL can't carry the saved one off.
+const KEY_HOSTS: &[(&str, &str)] = &[
+    (OPENAI_KEY_ID, "api.openai.com"),
+    (DEEPGRAM_KEY_ID, deepgram::DEEPGRAM_HOST),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SttProviderKind {
+    #[default]
+    OpenAi,
+    Deepgram,
+    LocalWhisper,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OpenAiSttConfig {
+    pub api_key: Option<String>,
+    pub base_url: String,
+    pub model: String,
+    pub language: Option<String>,
+}
+
+impl Default for OpenAiSttConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "whisper-1".to_string(),
+            language: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeepgramSttConfig {
+    pub api_key: Option<String>,
+    pub model: String,
+    pub language: Option<String>,
+}
+
+impl Default for DeepgramSttConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            model: "nova-2".to_string(),
+            language: None,
+        }
+    }
+}
+
+// How streaming providers receive audio over the wire
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(tag = "codec", rename_all = "snake_case")]
+pub enum StreamEncoding {
+    // Raw 16-bit PCM
+    Linear16,
+    // 20ms Opus frames in an Ogg stream
+    Opus { bitrate: i32 },
+}
+
+impl Default for StreamEncoding {
+    fn default() -> Self {
+        Self::Opus { bitrate: 24_000 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+pub struct SttConfig {
+    pub provider: SttProviderKind,
+    #[serde(default)]
+    pub openai: OpenAiSttConfig,
+    #[serde(default)]
+    pub deepgram: DeepgramSttConfig,
+    #[serde(default)]
+    pub stream_encoding: StreamEncoding,
+}
+
+// Audio handed to streaming providers: mono 16-bit PCM chunks
+pub type PcmChunk = Vec<i16>;
+
+#[async_trait]
+pub trait SttProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Transcribes a complete WAV clip.
+    async fn transcribe(&self, wav_bytes: Vec<u8>) -> Result<String, String>;
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    // Consumes PCM chunks until the sender is dropped, pushing partial and
+    // final transcripts to `updates`.
+    async fn transcribe_stream(
+        &self,
+        _audio: mpsc::Receiver<PcmChunk>,
+        _sample_rate: u32,
+        _updates: mpsc::Sender<TranscriptUpdate>,
+    ) -> Result<(), String> {
+        Err(format!("{} does not support streaming transcription", self.name()))
+    }
+}
+
+// The key saved under `key_id`, if `url` is on that key's host over https
+fn keychain_key(key_id: &str, url: &str) -> Result<Option<String>, String> {
+    let url = reqwest::Url::parse(url.trim()).ok();
+    let allowed = url.as_ref().is_some_and(|url| {
+        url.scheme() == "https"
+            && KEY_HOSTS
+                .iter()
+                .any(|(id, host)| *id == key_id && url.host_str() == Some(*host))
+    });
+    if !allowed {
+        return Ok(None);
+    }
+    crate::secrets::get_api_key(key_id)
+}
+
+// Builds the provider selected in `config`.
+pub fn build_provider(
+    app: &tauri::AppHandle,
+    config: &SttConfig,
+) -> Result<Box<dyn SttProvider>, String> {
+    // Local-only mode transcribes on this machine whatever is selected
+    let provider = if crate::network::local_only() {
+        SttProviderKind::LocalWhisper
+    } else {
+        config.provider
+    };
+    match provider {
+        SttProviderKind::OpenAi => {
+            let mut openai = config.openai.clone();
+            if openai.api_key.is_none() {
+                openai.api_key = keychain_key(OPENAI_KEY_ID, &openai.base_url)?;
+            }
+            Ok(Box::new(openai::OpenAiStt::new(openai)?))
+        }
+        SttProviderKind::Deepgram => {
+            let mut deepgram = config.deepgram.clone();
+            if deepgram.api_key.is_none() {
+                let url = format!("https://{}", deepgram::DEEPGRAM_HOST);
+                deepgram.api_key = keychain_key(DEEPGRAM_KEY_ID, &url)?;
+            }
+            Ok(Box::new(deepgram::DeepgramStt::new(
+                deepgram,
+                config.stream_encoding,
+            )?))
+        }
+        SttProviderKind::LocalWhisper => Ok(Box::new(local::LocalWhisperStt::new(app)?)),
+    }
+}
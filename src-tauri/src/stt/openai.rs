@@ -0,0 +1,76 @@
+// OpenAI Whisper API (and compatible `/audio/transcriptions` endpoints)
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+
+use super::{OpenAiSttConfig, SttProvider};
+
+pub struct OpenAiStt {
+    config: OpenAiSttConfig,
+    api_key: String,
+}
+
+impl OpenAiStt {
+    pub fn new(config: OpenAiSttConfig) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or("OpenAI API key is not configured".to_string())?;
+        Ok(Self { config, api_key })
+    }
+}
+
+#[async_trait]
+impl SttProvider for OpenAiStt {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn transcribe(&self, wav_bytes: Vec<u8>) -> Result<String, String> {
+        let audio_part = Part::bytes(wav_bytes)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| format!("Failed to prepare audio payload: {}", e))?;
+
+        let mut form = Form::new()
+            .part("file", audio_part)
+            .text("model", self.config.model.clone())
+            .text("response_format", "json");
+
+        if let Some(language) = &self.config.language {
+            form = form.text("language", language.clone());
+        }
+
+        let url = format!(
+            "{}/audio/transcriptions",
+            self.config.base_url.trim_end_matches('/')
+        );
+
+        let request = crate::network::http_client()?
+            .post(&url)
+            .bearer_auth(&self.api_key)
+            .multipart(form);
+        let response = crate::network::send(request, "transcription", &[])
+            .await
+            .map_err(|e| format!("Transcription request failed to send: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown server error".to_string());
+            return Err(format!("OpenAI transcription error ({}): {}", status, error_text));
+        }
+
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse transcription response: {}", e))?;
+
+        json.get("text")
+            .and_then(|text| text.as_str())
+            .map(|text| text.trim().to_string())
+            .ok_or("Transcription response did not contain text".to_string())
+    }
+}
@@ -0,0 +1,179 @@
+// Pluely table extraction. OCR words in a region of the screen are grouped
+// into rows by their vertical position and into columns by the gaps that run
+// down the whole region, so a screenshot of a spreadsheet or dashboard comes
+// back as data instead of pixels.
+use serde::Serialize;
+use specta::Type;
+
+use crate::capture::SelectionCoords;
+use crate::regions::{ocr_boxes, OcrBox};
+
+// Words closer than this many text heights apart are one cell
+const CELL_GAP: f64 = 0.9;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedTable {
+    // Every row has one entry per column, empty where nothing was read
+    pub rows: Vec<Vec<String>>,
+    pub columns: usize,
+    pub csv: String,
+}
+
+// A run of words on one row, spanning `left` to `right`
+struct Cell {
+    left: u32,
+    right: u32,
+    text: String,
+}
+
+fn median(mut values: Vec<u32>) -> u32 {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied().unwrap_or(0)
+}
+
+// Words whose vertical centers fall inside the same band, left to right
+fn group_rows(mut words: Vec<OcrBox>) -> Vec<Vec<OcrBox>> {
+    words.sort_by_key(|word| word.y + word.height / 2);
+    let mut rows: Vec<Vec<OcrBox>> = Vec::new();
+    let mut band = (0, 0);
+    for word in words {
+        let center = word.y + word.height / 2;
+        match rows.last_mut() {
+            Some(row) if center >= band.0 && center <= band.1 => {
+                band = (band.0.min(word.y), band.1.max(word.y + word.height));
+                row.push(word);
+            }
+            _ => {
+                band = (word.y, word.y + word.height);
+                rows.push(vec![word]);
+            }
+        }
+    }
+    for row in &mut rows {
+        row.sort_by_key(|word| word.x);
+    }
+    rows
+}
+
+// Joins neighbouring words of a row into cells, so "Total amount" stays one
+// value
+fn group_cells(row: Vec<OcrBox>, max_gap: u32) -> Vec<Cell> {
+    let mut cells: Vec<Cell> = Vec::new();
+    for word in row {
+        match cells.last_mut() {
+            Some(cell) if word.x.saturating_sub(cell.right) <= max_gap => {
+                cell.right = cell.right.max(word.x + word.width);
+                cell.text.push(' ');
+                cell.text.push_str(&word.text);
+            }
+            _ => cells.push(Cell {
+                left: word.x,
+                right: word.x + word.width,
+                text: word.text,
+            }),
+        }
+    }
+    cells
+}
+
+// Column spans: the cells' horizontal extents merged where they overlap
+fn column_spans(rows: &[Vec<Cell>]) -> Vec<(u32, u32)> {
+    let mut extents: Vec<(u32, u32)> = rows
+        .iter()
+        .flatten()
+        .map(|cell| (cell.left, cell.right))
+        .collect();
+    extents.sort_unstable();
+    let mut spans: Vec<(u32, u32)> = Vec::new();
+    for (left, right) in extents {
+        match spans.last_mut() {
+            Some(span) if left <= span.1 => span.1 = span.1.max(right),
+            _ => spans.push((left, right)),
+        }
+    }
+    spans
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| csv_field(value))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn build_table(boxes: Vec<OcrBox>) -> ExtractedTable {
+    let words: Vec<OcrBox> = boxes
+        .into_iter()
+        .filter(|ocr_box| ocr_box.level == 5 && !ocr_box.text.is_empty())
+        .collect();
+    let text_height = median(words.iter().map(|word| word.height).collect());
+    let max_gap = (text_height as f64 * CELL_GAP) as u32;
+
+    let rows: Vec<Vec<Cell>> = group_rows(words)
+        .into_iter()
+        .map(|row| group_cells(row, max_gap))
+        .collect();
+    let spans = column_spans(&rows);
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|cells| {
+            let mut values = vec![String::new(); spans.len()];
+            for cell in cells {
+                let column = spans
+                    .iter()
+                    .position(|(left, right)| cell.left >= *left && cell.left <= *right)
+                    .unwrap_or(0);
+                if !values[column].is_empty() {
+                    values[column].push(' ');
+                }
+                values[column].push_str(&cell.text);
+            }
+            values
+        })
+        .collect();
+
+    ExtractedTable {
+        csv: to_csv(&rows),
+        columns: spans.len(),
+        rows,
+    }
+}
+
+/// Reads the table in `coords` on `monitor` (the whole monitor when None) as
+/// rows of cells and as CSV, OCR'd in `language` when given. Needs
+/// `tesseract` installed.
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_table(
+    app: tauri::AppHandle,
+    coords: Option<SelectionCoords>,
+    monitor: usize,
+    language: Option<String>,
+) -> Result<ExtractedTable, String> {
+    let options = crate::ocr::language_options(&app, language.as_deref())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let image = crate::watch::capture_region(monitor, coords)?;
+        crate::audit::record_capture("table", None);
+        let table = build_table(ocr_boxes(&image, &options)?);
+        if table.rows.is_empty() {
+            return Err("No text found in the selected area".to_string());
+        }
+        Ok(table)
+    })
+    .await
+    .map_err(|e| format!("Failed to extract table: {}", e))?
+}
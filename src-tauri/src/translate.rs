@@ -0,0 +1,161 @@
+// Pluely capture translation. Text in a region of the screen is OCR'd line by
+// line and translated by the configured model (a local one keeps it on the
+// device), and each line comes back with where it was so the overlay can draw
+// the translation over the original.
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::capture::SelectionCoords;
+use crate::llm::{ChatMessage, ChatRole};
+use crate::regions::{ocr_boxes, OcrBox};
+
+const MAX_LANGUAGE_LEN: usize = 64;
+
+// One line of text, in image pixels of the monitor
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedLine {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub source: String,
+    // The source again when the model skipped the line
+    pub translated: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedCapture {
+    pub target_lang: String,
+    pub source_text: String,
+    pub translated_text: String,
+    pub lines: Vec<TranslatedLine>,
+}
+
+// The OCR'd lines, each with its words joined, offset onto the monitor
+fn ocr_lines(boxes: Vec<OcrBox>, offset: (u32, u32)) -> Vec<TranslatedLine> {
+    let mut lines: Vec<TranslatedLine> = Vec::new();
+    let mut words: HashMap<(u32, u32, u32), Vec<String>> = HashMap::new();
+    let mut keys = Vec::new();
+    for ocr_box in boxes {
+        match ocr_box.level {
+            4 => {
+                keys.push(ocr_box.line);
+                lines.push(TranslatedLine {
+                    x: ocr_box.x + offset.0,
+                    y: ocr_box.y + offset.1,
+                    width: ocr_box.width,
+                    height: ocr_box.height,
+                    source: String::new(),
+                    translated: String::new(),
+                });
+            }
+            5 if !ocr_box.text.is_empty() => {
+                words.entry(ocr_box.line).or_default().push(ocr_box.text)
+            }
+            _ => {}
+        }
+    }
+    lines
+        .into_iter()
+        .zip(keys)
+        .filter_map(|(mut line, key)| {
+            line.source = words.remove(&key)?.join(" ");
+            Some(line)
+        })
+        .collect()
+}
+
+fn prompt(lines: &[TranslatedLine], target_lang: &str) -> Vec<ChatMessage> {
+    let numbered = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| format!("{}: {}", index + 1, line.source))
+        .collect::<Vec<_>>()
+        .join("\n");
+    vec![
+        ChatMessage::new(
+            ChatRole::System,
+            format!(
+                "Translate each numbered line of text from a screenshot into {}. \
+                 Reply with only the translated lines, each starting with its \
+                 number and a colon, in the same order. Keep names, numbers and \
+                 code as they are, and keep a line unchanged if it is already in {}.",
+                target_lang, target_lang
+            ),
+        ),
+        ChatMessage::new(ChatRole::User, numbered),
+    ]
+}
+
+// Translations by line number from a reply to `prompt`
+fn parse_reply(reply: &str) -> HashMap<usize, String> {
+    reply
+        .lines()
+        .filter_map(|line| {
+            let (number, text) = line.trim().split_once(':')?;
+            let number = number.trim().parse::<usize>().ok()?;
+            Some((number, text.trim().to_string()))
+        })
+        .collect()
+}
+
+/// OCRs `coords` on `monitor` (the whole monitor when None) and translates it
+/// into `target_lang` with the configured model, line by line with each
+/// line's position. `source_lang` is the OCR language of the text when it
+/// isn't English. Needs `tesseract` installed.
+#[tauri::command]
+#[specta::specta]
+pub async fn translate_capture(
+    app: AppHandle,
+    coords: Option<SelectionCoords>,
+    monitor: usize,
+    target_lang: String,
+    source_lang: Option<String>,
+) -> Result<TranslatedCapture, String> {
+    let target_lang = target_lang.trim().to_string();
+    if target_lang.is_empty() || target_lang.len() > MAX_LANGUAGE_LEN {
+        return Err(format!("Invalid target language: {}", target_lang));
+    }
+    let options = crate::ocr::language_options(&app, source_lang.as_deref())?;
+    let offset = coords.map_or((0, 0), |coords| (coords.x, coords.y));
+    let mut lines = tauri::async_runtime::spawn_blocking(move || {
+        let image = crate::watch::capture_region(monitor, coords)?;
+        crate::audit::record_capture("translate", None);
+        Ok::<_, String>(ocr_lines(ocr_boxes(&image, &options)?, offset))
+    })
+    .await
+    .map_err(|e| format!("Failed to read text: {}", e))??;
+    if lines.is_empty() {
+        return Err("No text found in the selected area".to_string());
+    }
+
+    let reply = crate::llm::complete(&app, prompt(&lines, &target_lang)).await?;
+    let mut translations = parse_reply(&reply);
+    for (index, line) in lines.iter_mut().enumerate() {
+        line.translated = translations
+            .remove(&(index + 1))
+            .filter(|text| !text.is_empty())
+            .unwrap_or_else(|| line.source.clone());
+    }
+
+    let source_text = lines
+        .iter()
+        .map(|line| line.source.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let translated_text = lines
+        .iter()
+        .map(|line| line.translated.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(TranslatedCapture {
+        target_lang,
+        source_text,
+        translated_text,
+        lines,
+    })
+}
@@ -0,0 +1,123 @@
+// Pluely system tray icon with quick actions that run without the main window
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{App, AppHandle, Emitter, Listener, Manager};
+use tracing::warn;
+
+use crate::shortcuts;
+
+const TRAY_ID: &str = "main";
+
+/// Opens the area selection overlay; the selection arrives as
+/// `captured-selection`, like the hotkey
+pub fn capture_area(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::capture::start_screen_capture(app, None).await {
+            warn!("Failed to start area capture: {}", e);
+        }
+    });
+}
+
+/// Captures the whole screen, emitted with the same event and payload as an
+/// area capture
+pub fn capture_screen(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match crate::capture::capture_screen_base64().await {
+            Ok(image) => {
+                let thumbnail = crate::captures::thumbnail_of_base64(&app, &image);
+                let captured = crate::events::CapturedSelection::from_png_base64(None, image)
+                    .with_thumbnail(thumbnail);
+                if let Err(e) = app.emit("captured-selection", captured) {
+                    warn!("Failed to emit screen capture: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to capture screen: {}", e),
+        }
+    });
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "capture_area" => capture_area(app),
+        "capture_screen" => capture_screen(app),
+        "toggle_window" => shortcuts::handle_shortcut_action(app, "toggle_window"),
+        "pause_hotkeys" => {
+            let paused = app
+                .state::<shortcuts::RegisteredShortcuts>()
+                .paused
+                .load(std::sync::atomic::Ordering::SeqCst);
+            if let Err(e) = shortcuts::set_shortcuts_paused(app, !paused) {
+                warn!("Failed to pause hotkeys: {}", e);
+            }
+        }
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+/// Creates the tray icon and its menu
+pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
+    let capture_area = MenuItem::with_id(app, "capture_area", "Capture area", true, None::<&str>)?;
+    let capture_screen =
+        MenuItem::with_id(app, "capture_screen", "Capture screen", true, None::<&str>)?;
+    let toggle_window =
+        MenuItem::with_id(app, "toggle_window", "Toggle window", true, None::<&str>)?;
+    let pause_hotkeys = CheckMenuItem::with_id(
+        app,
+        "pause_hotkeys",
+        "Pause hotkeys",
+        true,
+        false,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &capture_area,
+            &capture_screen,
+            &toggle_window,
+            &PredefinedMenuItem::separator(app)?,
+            &pause_hotkeys,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Pluely")
+        .menu(&menu)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    // Hotkeys can also be paused from the frontend
+    app.listen("hotkeys-paused", move |event| {
+        if let Ok(paused) = serde_json::from_str::<bool>(event.payload()) {
+            let _ = pause_hotkeys.set_checked(paused);
+        }
+    });
+    Ok(())
+}
+
+/// Hides the main window at launch when the user chose to start in the tray
+pub fn apply_start_minimized(app: &AppHandle) {
+    let start_minimized = crate::settings::current_settings(app)
+        .map(|settings| {
+            settings.window.start_minimized
+                || (settings.window.autostart_hidden && crate::autostart::launched_at_login())
+        })
+        .unwrap_or(false);
+    if !start_minimized {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.hide() {
+            warn!("Failed to hide window: {}", e);
+        }
+    }
+}
@@ -0,0 +1,254 @@
+// Pluely TTS commands: provider configuration and reading text aloud.
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::{
+    build_fallback, build_provider, speakable_text, split_chunks, Speech, TtsConfig,
+    ELEVENLABS_KEY_ID, OPENAI_KEY_ID,
+};
+use crate::realtime::player::AudioPlayer;
+
+const PROGRESS_INTERVAL_MS: u64 = 250;
+
+#[derive(Default)]
+pub struct TtsState {
+    config: Mutex<TtsConfig>,
+    playback: Mutex<Option<Playback>>,
+}
+
+struct Playback {
+    id: String,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+// Emitted as `tts-progress` while an answer is read aloud
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsProgress {
+    pub id: String,
+    // Chunk being heard, out of `chunks`
+    pub chunk: usize,
+    pub chunks: usize,
+    pub played_ms: u64,
+    // Audio synthesized so far; grows until every chunk is ready
+    pub total_ms: u64,
+}
+
+// Emitted as `tts-finished` once playback ends for any reason
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsFinished {
+    pub id: String,
+    pub stopped: bool,
+    pub error: Option<String>,
+}
+
+fn current_config(app: &AppHandle) -> Result<TtsConfig, String> {
+    let state = app.state::<TtsState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read TTS config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_tts_config(app: AppHandle) -> Result<TtsConfig, String> {
+    current_config(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_tts_config(app: AppHandle, mut config: TtsConfig) -> Result<(), String> {
+    if !(0.25..=4.0).contains(&config.openai.speed) {
+        return Err("Invalid speed: must be 0.25-4.0".to_string());
+    }
+
+    // Keys go to the keychain; the config keeps everything else
+    if let Some(api_key) = config.openai.api_key.take() {
+        crate::secrets::store_api_key(OPENAI_KEY_ID, &api_key)?;
+    }
+    if let Some(api_key) = config.elevenlabs.api_key.take() {
+        crate::secrets::store_api_key(ELEVENLABS_KEY_ID, &api_key)?;
+    }
+
+    let state = app.state::<TtsState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update TTS config: {}", e))? = config;
+
+    Ok(())
+}
+
+// Reads `text` aloud, replacing anything already playing. Returns the playback
+// id carried by `tts-progress` and `tts-finished`.
+#[tauri::command]
+#[specta::specta]
+pub fn speak(app: AppHandle, text: String, voice: Option<String>) -> Result<String, String> {
+    let chunks = split_chunks(&speakable_text(&text));
+    if chunks.is_empty() {
+        return Err("Nothing to speak".to_string());
+    }
+    let config = current_config(&app)?;
+    let provider = build_provider(&config)?;
+    let fallback = build_fallback(&config);
+
+    stop_speaking(app.clone())?;
+    let state = app.state::<TtsState>();
+    let mut playback = state
+        .playback
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+    crate::analytics::count("speech");
+    let id = uuid::Uuid::new_v4().to_string();
+    let app_clone = app.clone();
+    let id_clone = id.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let (speech_tx, speech_rx) = mpsc::channel::<Speech>(2);
+        let chunk_count = chunks.len();
+
+        // Synthesizes ahead while earlier chunks play
+        let synthesis = tauri::async_runtime::spawn(async move {
+            let mut provider = provider;
+            let mut fallback = fallback;
+            let mut voice = voice;
+            for chunk in &chunks {
+                let speech = match provider.synthesize(chunk, voice.as_deref()).await {
+                    Ok(speech) => speech,
+                    Err(e) => match fallback.take() {
+                        Some(local) => {
+                            warn!(
+                                "{} speech failed, using {}: {}",
+                                provider.name(),
+                                local.name(),
+                                e
+                            );
+                            // Provider voices mean nothing to the local one
+                            provider = local;
+                            voice = None;
+                            provider.synthesize(chunk, None).await?
+                        }
+                        None => return Err(e),
+                    },
+                };
+                if speech_tx.send(speech).await.is_err() {
+                    break;
+                }
+            }
+            Ok::<(), String>(())
+        });
+
+        let result = play(&app_clone, &id_clone, chunk_count, speech_rx).await;
+        let result = match (result, synthesis.await) {
+            (Err(e), _) => Err(e),
+            (Ok(()), Ok(Err(e))) => Err(e),
+            (Ok(()), Err(e)) => Err(format!("Task panicked: {}", e)),
+            (Ok(()), Ok(Ok(()))) => Ok(()),
+        };
+        let _ = app_clone.emit(
+            "tts-finished",
+            TtsFinished {
+                id: id_clone.clone(),
+                stopped: false,
+                error: result.err(),
+            },
+        );
+
+        let state = app_clone.state::<TtsState>();
+        if let Ok(mut playback) = state.playback.lock() {
+            if playback.as_ref().is_some_and(|p| p.id == id_clone) {
+                *playback = None;
+            }
+        };
+    });
+
+    *playback = Some(Playback {
+        id: id.clone(),
+        task,
+    });
+    Ok(id)
+}
+
+// Stops playback immediately; false when nothing was playing
+#[tauri::command]
+#[specta::specta]
+pub fn stop_speaking(app: AppHandle) -> Result<bool, String> {
+    let state = app.state::<TtsState>();
+    let playback = state
+        .playback
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .take();
+    let Some(playback) = playback else {
+        return Ok(false);
+    };
+    // Aborting drops the player, which closes the output stream
+    playback.task.abort();
+    let _ = app.emit(
+        "tts-finished",
+        TtsFinished {
+            id: playback.id,
+            stopped: true,
+            error: None,
+        },
+    );
+    Ok(true)
+}
+
+// Queues speech as it arrives and reports progress until all of it was heard
+async fn play(
+    app: &AppHandle,
+    id: &str,
+    chunk_count: usize,
+    mut speech_rx: mpsc::Receiver<Speech>,
+) -> Result<(), String> {
+    let player = AudioPlayer::start()?;
+    // Where each chunk ends, in ms from the start
+    let mut chunk_ends: Vec<u64> = Vec::with_capacity(chunk_count);
+    let mut total_ms = 0;
+    let mut synthesis_done = false;
+    let mut ticker = tokio::time::interval(Duration::from_millis(PROGRESS_INTERVAL_MS));
+
+    loop {
+        tokio::select! {
+            speech = speech_rx.recv(), if !synthesis_done => match speech {
+                Some(speech) => {
+                    total_ms += speech.duration_ms();
+                    chunk_ends.push(total_ms);
+                    player.push_pcm16(&speech.samples, speech.sample_rate);
+                }
+                None => synthesis_done = true,
+            },
+            _ = ticker.tick() => {
+                let played_ms = player.played_ms().min(total_ms);
+                let chunk = chunk_ends
+                    .iter()
+                    .position(|&end| end > played_ms)
+                    .unwrap_or(chunk_ends.len().saturating_sub(1));
+                let _ = app.emit(
+                    "tts-progress",
+                    TtsProgress {
+                        id: id.to_string(),
+                        chunk,
+                        chunks: chunk_count,
+                        played_ms,
+                        total_ms,
+                    },
+                );
+                if synthesis_done && !player.is_playing() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
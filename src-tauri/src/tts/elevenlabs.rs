@@ -0,0 +1,73 @@
+// ElevenLabs text-to-speech API
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{pcm16_from_le_bytes, ElevenLabsTtsConfig, Speech, TtsProvider};
+
+const API_URL: &str = "https://api.elevenlabs.io/v1/text-to-speech";
+const PCM_SAMPLE_RATE: u32 = 24_000;
+
+pub struct ElevenLabsTts {
+    config: ElevenLabsTtsConfig,
+    api_key: String,
+}
+
+impl ElevenLabsTts {
+    pub fn new(config: ElevenLabsTtsConfig) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or("ElevenLabs API key is not configured".to_string())?;
+        Ok(Self { config, api_key })
+    }
+}
+
+#[async_trait]
+impl TtsProvider for ElevenLabsTts {
+    fn name(&self) -> &'static str {
+        "elevenlabs"
+    }
+
+    async fn synthesize(&self, text: &str, voice: Option<&str>) -> Result<Speech, String> {
+        let url = format!(
+            "{}/{}?output_format=pcm_{}",
+            API_URL,
+            voice.unwrap_or(&self.config.voice_id),
+            PCM_SAMPLE_RATE
+        );
+        let body = json!({
+            "text": text,
+            "model_id": self.config.model,
+        });
+
+        let request = crate::network::http_client()?
+            .post(&url)
+            .header("xi-api-key", &self.api_key)
+            .json(&body);
+        let response = crate::network::send(request, "speech", &[])
+            .await
+            .map_err(|e| format!("Speech request failed to send: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown server error".to_string());
+            return Err(format!(
+                "ElevenLabs speech error ({}): {}",
+                status, error_text
+            ));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read speech audio: {}", e))?;
+        Ok(Speech {
+            samples: pcm16_from_le_bytes(&bytes),
+            sample_rate: PCM_SAMPLE_RATE,
+        })
+    }
+}
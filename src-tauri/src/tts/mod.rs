@@ -0,0 +1,270 @@
+// Pluely text-to-speech. Answers are read aloud by a provider API, with a
+// local Piper voice as the offline fallback.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+mod commands;
+mod elevenlabs;
+mod openai;
+mod piper;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain accounts for provider keys; OpenAI shares its key with the LLM provider
+const OPENAI_KEY_ID: &str = "openai";
+const ELEVENLABS_KEY_ID: &str = "elevenlabs";
+// The only host the saved OpenAI key is sent to; another base URL needs its
+// own key in the config
+const OPENAI_HOST: &str = "api.openai.com";
+
+// Longest piece of text synthesized in one request, so playback starts early
+const MAX_CHUNK_CHARS: usize = 400;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsProviderKind {
+    #[default]
+    OpenAi,
+    ElevenLabs,
+    Piper,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OpenAiTtsConfig {
+    pub api_key: Option<String>,
+    pub base_url: String,
+    pub model: String,
+    pub voice: String,
+    // 0.25-4.0
+    pub speed: f32,
+}
+
+impl Default for OpenAiTtsConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "gpt-4o-mini-tts".to_string(),
+            voice: "alloy".to_string(),
+            speed: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ElevenLabsTtsConfig {
+    pub api_key: Option<String>,
+    pub model: String,
+    pub voice_id: String,
+}
+
+impl Default for ElevenLabsTtsConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            model: "eleven_multilingual_v2".to_string(),
+            voice_id: "21m00Tcm4TlvDq8ikWAM".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PiperTtsConfig {
+    // Piper executable, looked up on PATH unless absolute
+    pub binary: String,
+    // Voice model (.onnx) with its .onnx.json next to it
+    pub model_path: Option<String>,
+}
+
+impl Default for PiperTtsConfig {
+    fn default() -> Self {
+        Self {
+            binary: "piper".to_string(),
+            model_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TtsConfig {
+    pub provider: TtsProviderKind,
+    #[serde(default)]
+    pub openai: OpenAiTtsConfig,
+    #[serde(default)]
+    pub elevenlabs: ElevenLabsTtsConfig,
+    #[serde(default)]
+    pub piper: PiperTtsConfig,
+    // Retry with Piper when the provider API fails (offline, no key, ...)
+    #[serde(default = "default_fallback")]
+    pub fallback_to_piper: bool,
+}
+
+fn default_fallback() -> bool {
+    true
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            provider: TtsProviderKind::default(),
+            openai: OpenAiTtsConfig::default(),
+            elevenlabs: ElevenLabsTtsConfig::default(),
+            piper: PiperTtsConfig::default(),
+            fallback_to_piper: default_fallback(),
+        }
+    }
+}
+
+// Mono 16-bit PCM
+pub struct Speech {
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+}
+
+impl Speech {
+    pub fn duration_ms(&self) -> u64 {
+        self.samples.len() as u64 * 1000 / self.sample_rate.max(1) as u64
+    }
+}
+
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // `voice` overrides the configured voice for this request
+    async fn synthesize(&self, text: &str, voice: Option<&str>) -> Result<Speech, String>;
+}
+
+// Builds the provider selected in `config`.
+pub fn build_provider(config: &TtsConfig) -> Result<Box<dyn TtsProvider>, String> {
+    // Local-only mode speaks with Piper whatever is selected
+    let provider = if crate::network::local_only() {
+        TtsProviderKind::Piper
+    } else {
+        config.provider
+    };
+    match provider {
+        TtsProviderKind::OpenAi => {
+            let mut openai = config.openai.clone();
+            let on_openai = reqwest::Url::parse(openai.base_url.trim())
+                .is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some(OPENAI_HOST));
+            if openai.api_key.is_none() && on_openai {
+                openai.api_key = crate::secrets::get_api_key(OPENAI_KEY_ID)?;
+            }
+            Ok(Box::new(openai::OpenAiTts::new(openai)?))
+        }
+        TtsProviderKind::ElevenLabs => {
+            let mut elevenlabs = config.elevenlabs.clone();
+            if elevenlabs.api_key.is_none() {
+                elevenlabs.api_key = crate::secrets::get_api_key(ELEVENLABS_KEY_ID)?;
+            }
+            Ok(Box::new(elevenlabs::ElevenLabsTts::new(elevenlabs)?))
+        }
+        TtsProviderKind::Piper => Ok(Box::new(piper::PiperTts::new(config.piper.clone())?)),
+    }
+}
+
+// The local voice to retry with, if one is configured
+pub fn build_fallback(config: &TtsConfig) -> Option<Box<dyn TtsProvider>> {
+    if !config.fallback_to_piper
+        || config.provider == TtsProviderKind::Piper
+        || crate::network::local_only()
+    {
+        return None;
+    }
+    piper::PiperTts::new(config.piper.clone())
+        .ok()
+        .map(|piper| Box::new(piper) as Box<dyn TtsProvider>)
+}
+
+// Drops markdown that reads badly aloud: code blocks, emphasis, headings, link targets
+pub fn speakable_text(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let line = trimmed.trim_start_matches(['#', '>', '-', '*', ' ']);
+        let mut rest = line;
+        while let Some(start) = rest.find("](") {
+            out.push_str(&rest[..start]);
+            rest = match rest[start..].find(')') {
+                Some(end) => &rest[start + end + 1..],
+                None => "",
+            };
+        }
+        out.push_str(rest);
+        out.push('\n');
+    }
+    out.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '[' | '#'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// Splits text at sentence ends into pieces of at most MAX_CHUNK_CHARS
+pub fn split_chunks(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for sentence in text.split_inclusive(['.', '!', '?', '\n']) {
+        if !current.is_empty() && current.len() + sentence.len() > MAX_CHUNK_CHARS {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(sentence);
+        // A single long sentence is cut at whitespace
+        while current.len() > MAX_CHUNK_CHARS {
+            let mut cut = MAX_CHUNK_CHARS;
+            while !current.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let space = current[..cut]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace());
+            if let Some((space, c)) = space {
+                // A piece starting with the whitespace it was cut at keeps it,
+                // so the cut still moves forward
+                cut = if space == 0 { c.len_utf8() } else { space };
+            }
+            let tail = current.split_off(cut);
+            chunks.push(std::mem::replace(&mut current, tail));
+        }
+    }
+    chunks.push(current);
+    chunks
+        .into_iter()
+        .map(|chunk| chunk.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+fn pcm16_from_le_bytes(bytes: &[u8]) -> Vec<i16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_chunks_cuts_after_multibyte_whitespace() {
+        for space in ['\u{a0}', '\u{3000}'] {
+            let text = format!("ab{}{}", space, "x".repeat(2 * MAX_CHUNK_CHARS));
+            let chunks = split_chunks(&text);
+            assert!(chunks.iter().all(|chunk| chunk.len() <= MAX_CHUNK_CHARS));
+            assert_eq!(chunks.concat().replace(space, ""), text.replace(space, ""));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// OpenAI speech API (and compatible `/audio/speech` endpoints)
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{pcm16_from_le_bytes, OpenAiTtsConfig, Speech, TtsProvider};
+
+// `pcm` responses are 24kHz mono 16-bit little-endian
+const PCM_SAMPLE_RATE: u32 = 24_000;
+
+pub struct OpenAiTts {
+    config: OpenAiTtsConfig,
+    api_key: String,
+}
+
+impl OpenAiTts {
+    pub fn new(config: OpenAiTtsConfig) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or("OpenAI API key is not configured".to_string())?;
+        Ok(Self { config, api_key })
+    }
+}
+
+#[async_trait]
+impl TtsProvider for OpenAiTts {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn synthesize(&self, text: &str, voice: Option<&str>) -> Result<Speech, String> {
+        let url = format!(
+            "{}/audio/speech",
+            self.config.base_url.trim_end_matches('/')
+        );
+        let body = json!({
+            "model": self.config.model,
+            "input": text,
+            "voice": voice.unwrap_or(&self.config.voice),
+            "speed": self.config.speed,
+            "response_format": "pcm",
+        });
+
+        let request = crate::network::http_client()?
+            .post(&url)
+            .bearer_auth(&self.api_key)
+            .json(&body);
+        let response = crate::network::send(request, "speech", &[])
+            .await
+            .map_err(|e| format!("Speech request failed to send: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown server error".to_string());
+            return Err(format!("OpenAI speech error ({}): {}", status, error_text));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read speech audio: {}", e))?;
+        Ok(Speech {
+            samples: pcm16_from_le_bytes(&bytes),
+            sample_rate: PCM_SAMPLE_RATE,
+        })
+    }
+}
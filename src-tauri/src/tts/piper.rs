@@ -0,0 +1,91 @@
+// Local Piper voices: text on stdin, raw 16-bit PCM on stdout
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::{pcm16_from_le_bytes, PiperTtsConfig, Speech, TtsProvider};
+
+// Used when the voice's .onnx.json doesn't say
+const DEFAULT_SAMPLE_RATE: u32 = 22_050;
+
+pub struct PiperTts {
+    binary: String,
+    model_path: PathBuf,
+}
+
+impl PiperTts {
+    pub fn new(config: PiperTtsConfig) -> Result<Self, String> {
+        let model_path = config
+            .model_path
+            .filter(|path| !path.trim().is_empty())
+            .map(PathBuf::from)
+            .ok_or("Piper voice model is not configured".to_string())?;
+        Ok(Self {
+            binary: config.binary,
+            model_path,
+        })
+    }
+}
+
+// Reads `audio.sample_rate` from the model's config file
+fn model_sample_rate(model_path: &std::path::Path) -> u32 {
+    let mut config_path = model_path.as_os_str().to_owned();
+    config_path.push(".json");
+    std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .and_then(|json| json.pointer("/audio/sample_rate").and_then(|r| r.as_u64()))
+        .map(|rate| rate as u32)
+        .unwrap_or(DEFAULT_SAMPLE_RATE)
+}
+
+#[async_trait]
+impl TtsProvider for PiperTts {
+    fn name(&self) -> &'static str {
+        "piper"
+    }
+
+    // `voice` is another model path
+    async fn synthesize(&self, text: &str, voice: Option<&str>) -> Result<Speech, String> {
+        let binary = self.binary.clone();
+        let model_path = voice
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.model_path.clone());
+        let text = text.to_string();
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut child = Command::new(&binary)
+                .arg("--model")
+                .arg(&model_path)
+                .arg("--output-raw")
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("Failed to run piper (is it installed?): {}", e))?;
+
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin
+                    .write_all(text.as_bytes())
+                    .map_err(|e| format!("Failed to send text to piper: {}", e))?;
+            }
+            let output = child
+                .wait_with_output()
+                .map_err(|e| format!("Failed to run piper: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Piper failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+
+            Ok(Speech {
+                samples: pcm16_from_le_bytes(&output.stdout),
+                sample_rate: model_sample_rate(&model_path),
+            })
+        })
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+    }
+}
@@ -0,0 +1,110 @@
+// Pluely updates. Checks and downloads run here rather than in the webview so
+// local-only mode and the proxy apply to them like to every other request.
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::notify::{notify, NotificationCategory};
+
+/// The update found by the last check, kept for install_update
+#[derive(Default)]
+pub struct UpdaterState {
+    pending: Mutex<Option<Update>>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AvailableUpdate {
+    pub version: String,
+    // Release notes, in Markdown
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum UpdateProgress {
+    #[serde(rename_all = "camelCase")]
+    Started { content_length: Option<u64> },
+    #[serde(rename_all = "camelCase")]
+    Progress { chunk_length: usize },
+    Finished,
+}
+
+fn updater(app: &AppHandle) -> Result<Updater, String> {
+    crate::network::ensure_online("Checking for updates")?;
+    let mut builder = app.updater_builder();
+    if let Some(proxy) = crate::network::proxy_url()? {
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to initialize updater: {}", e))
+}
+
+/// Tauri command to check for an update, notifying when one is available
+#[tauri::command]
+#[specta::specta]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<AvailableUpdate>, String> {
+    let update = updater(&app)?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+    let state = app.state::<UpdaterState>();
+    let mut pending = state
+        .pending
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    *pending = update.clone();
+    let Some(update) = update else {
+        return Ok(None);
+    };
+
+    notify(
+        &app,
+        NotificationCategory::UpdateAvailable,
+        "Update available",
+        &format!("Pluely {} is ready to install", update.version),
+    );
+    Ok(Some(AvailableUpdate {
+        version: update.version,
+        body: update.body,
+    }))
+}
+
+/// Tauri command to download and install the update the last check found.
+/// The app has to be relaunched afterwards, except on Windows.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_update(
+    app: AppHandle,
+    on_event: Channel<UpdateProgress>,
+) -> Result<(), String> {
+    // Local-only mode may have been turned on since the check
+    crate::network::ensure_online("Installing updates")?;
+    let update = app
+        .state::<UpdaterState>()
+        .pending
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .clone()
+        .ok_or("No update to install".to_string())?;
+
+    let mut started = false;
+    update
+        .download_and_install(
+            |chunk_length, content_length| {
+                if !started {
+                    started = true;
+                    let _ = on_event.send(UpdateProgress::Started { content_length });
+                }
+                let _ = on_event.send(UpdateProgress::Progress { chunk_length });
+            },
+            || {
+                let _ = on_event.send(UpdateProgress::Finished);
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to install update: {}", e))
+}
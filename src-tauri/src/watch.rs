@@ -0,0 +1,309 @@
+// Pluely watch mode. A region of the screen is captured on a timer and only
+// frames that look different from the last one sent are emitted, so a build
+// log or dashboard can be monitored and the model asked only when it changes.
+// The change trigger is the lighter sibling: it compares low-res grabs of a
+// monitor or region and only says that something changed, and by how much.
+use base64::Engine;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{ColorType, GenericImageView, ImageEncoder, RgbaImage};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+use xcap::Monitor;
+
+use crate::capture::SelectionCoords;
+use crate::events::{ScreenChanged, WatchFrame};
+
+const MIN_INTERVAL_MS: u64 = 500;
+const MAX_INTERVAL_MS: u64 = 3_600_000;
+// Hashes this many bits apart or fewer count as the same frame, which rides
+// out a blinking cursor or a ticking clock
+const CHANGE_THRESHOLD: u32 = 3;
+// Width of the grayscale grabs the change trigger compares
+const TRIGGER_WIDTH: u32 = 96;
+// Luma difference under which a pixel counts as unchanged, for noise and
+// compression artefacts
+const PIXEL_TOLERANCE: u8 = 24;
+
+#[derive(Default)]
+pub struct WatchState {
+    // Stop flag of the running watch
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+    // Stop flag of the running change trigger
+    trigger: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+// Stops whatever `slot` holds and, with `next`, runs it in its place
+fn replace_worker(
+    slot: &Mutex<Option<Arc<AtomicBool>>>,
+    next: Option<Box<dyn FnOnce(Arc<AtomicBool>) + Send>>,
+) -> Result<(), String> {
+    let mut worker = slot
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if let Some(stop) = worker.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if let Some(run) = next {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        std::thread::spawn(move || run(flag));
+        *worker = Some(stop);
+    }
+    Ok(())
+}
+
+// Difference hash: one bit per neighbouring pair of pixels in a 9x8 grayscale
+// thumbnail, set where the left one is brighter
+fn perceptual_hash(image: &RgbaImage) -> u64 {
+    let gray = image::imageops::grayscale(image);
+    let small = image::imageops::resize(&gray, 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+// The whole monitor when `coords` is None
+pub(crate) fn capture_region(
+    monitor: usize,
+    coords: Option<SelectionCoords>,
+) -> Result<RgbaImage, String> {
+    let screen = Monitor::all()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?
+        .into_iter()
+        .nth(monitor)
+        .ok_or_else(|| format!("Monitor not found: {}", monitor))?;
+    let mut image = screen
+        .capture_image()
+        .map_err(|e| format!("Failed to capture image: {}", e))?;
+    crate::sensitive::enforce(&screen, &mut image)?;
+    let Some(coords) = coords else {
+        return Ok(image);
+    };
+    if coords.x.saturating_add(coords.width) > image.width()
+        || coords.y.saturating_add(coords.height) > image.height()
+    {
+        return Err(format!(
+            "Region is outside monitor {} ({}x{})",
+            monitor,
+            image.width(),
+            image.height()
+        ));
+    }
+    Ok(image
+        .view(coords.x, coords.y, coords.width, coords.height)
+        .to_image())
+}
+
+fn encode_png(image: &RgbaImage) -> Result<String, String> {
+    let mut png_buffer = Vec::new();
+    PngEncoder::new(&mut png_buffer)
+        .write_image(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            ColorType::Rgba8.into(),
+        )
+        .map_err(|e| format!("Failed to encode to PNG: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png_buffer))
+}
+
+fn watch(
+    app: AppHandle,
+    coords: SelectionCoords,
+    monitor: usize,
+    interval: Duration,
+    stop: Arc<AtomicBool>,
+) {
+    let mut last_hash: Option<u64> = None;
+    while !stop.load(Ordering::SeqCst) {
+        if !crate::power::background_paused() {
+            match capture_region(monitor, Some(coords)) {
+                Ok(region) => {
+                    let hash = perceptual_hash(&region);
+                    let unchanged = matches!(
+                        last_hash,
+                        Some(last) if (last ^ hash).count_ones() <= CHANGE_THRESHOLD
+                    );
+                    if !unchanged {
+                        let frame = crate::power::fit_capture(region);
+                        match encode_png(&frame) {
+                            Ok(data) => {
+                                crate::audit::record_capture(
+                                    "watch",
+                                    Some(crate::audit::base64_size(&data)),
+                                );
+                                last_hash = Some(hash);
+                                let payload = WatchFrame::png(
+                                    monitor,
+                                    frame.width(),
+                                    frame.height(),
+                                    format!("{:016x}", hash),
+                                    data,
+                                );
+                                if let Err(e) = app.emit("watch-frame", payload) {
+                                    warn!("Failed to emit watch-frame event: {}", e);
+                                }
+                            }
+                            Err(e) => warn!("{}", e),
+                        }
+                    }
+                }
+                Err(e) => warn!("Watch capture failed: {}", e),
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Tauri command starting watch mode on a region of a monitor, in image pixels.
+/// Replaces any watch already running.
+#[tauri::command]
+#[specta::specta]
+pub fn start_watch(
+    app: AppHandle,
+    coords: SelectionCoords,
+    monitor: usize,
+    interval_ms: u64,
+) -> Result<(), String> {
+    if !(MIN_INTERVAL_MS..=MAX_INTERVAL_MS).contains(&interval_ms) {
+        return Err(format!(
+            "Invalid watch interval: must be {}-{} ms",
+            MIN_INTERVAL_MS, MAX_INTERVAL_MS
+        ));
+    }
+    if coords.width == 0 || coords.height == 0 {
+        return Err("Invalid watch region dimensions".to_string());
+    }
+    // Fails now rather than on every tick if the region doesn't fit
+    capture_region(monitor, Some(coords))?;
+
+    let handle = app.clone();
+    let interval = Duration::from_millis(interval_ms);
+    replace_worker(
+        &app.state::<WatchState>().watcher,
+        Some(Box::new(move |stop| {
+            watch(handle, coords, monitor, interval, stop)
+        })),
+    )
+}
+
+/// Tauri command stopping watch mode; does nothing when no watch is running
+#[tauri::command]
+#[specta::specta]
+pub fn stop_watch(app: AppHandle) -> Result<(), String> {
+    replace_worker(&app.state::<WatchState>().watcher, None)
+}
+
+// Grayscale thumbnail `TRIGGER_WIDTH` wide, keeping the aspect ratio
+fn thumbnail(image: &RgbaImage) -> image::GrayImage {
+    let width = TRIGGER_WIDTH.min(image.width()).max(1);
+    let height =
+        ((image.height() as u64 * width as u64 / image.width().max(1) as u64) as u32).max(1);
+    let gray = image::imageops::grayscale(image);
+    image::imageops::resize(&gray, width, height, FilterType::Triangle)
+}
+
+// Share of pixels, 0.0 to 1.0, that differ by more than `PIXEL_TOLERANCE`
+fn changed_share(before: &image::GrayImage, after: &image::GrayImage) -> f64 {
+    if before.dimensions() != after.dimensions() {
+        return 1.0;
+    }
+    let changed = before
+        .pixels()
+        .zip(after.pixels())
+        .filter(|(a, b)| a[0].abs_diff(b[0]) > PIXEL_TOLERANCE)
+        .count();
+    changed as f64 / before.pixels().len().max(1) as f64
+}
+
+fn trigger(
+    app: AppHandle,
+    monitor: usize,
+    coords: Option<SelectionCoords>,
+    threshold: f64,
+    interval: Duration,
+    stop: Arc<AtomicBool>,
+) {
+    // Compared against the grab at the last event rather than the previous
+    // tick, so slow changes still add up to one
+    let mut baseline: Option<image::GrayImage> = None;
+    while !stop.load(Ordering::SeqCst) {
+        if !crate::power::background_paused() {
+            match capture_region(monitor, coords) {
+                Ok(image) => {
+                    let current = thumbnail(&image);
+                    match &baseline {
+                        None => baseline = Some(current),
+                        Some(before) => {
+                            let change = changed_share(before, &current);
+                            if change >= threshold {
+                                let payload = ScreenChanged::new(monitor, coords, change);
+                                if let Err(e) = app.emit("screen-changed", payload) {
+                                    warn!("Failed to emit screen-changed event: {}", e);
+                                }
+                                baseline = Some(current);
+                            }
+                        }
+                    }
+                }
+                Err(e) => warn!("Change trigger capture failed: {}", e),
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Tauri command starting the change trigger on a monitor, or a region of it
+/// in image pixels. `threshold` is the share of the screen, 0.0 to 1.0, that
+/// has to change before `screen-changed` is emitted. Replaces any trigger
+/// already running.
+#[tauri::command]
+#[specta::specta]
+pub fn start_change_trigger(
+    app: AppHandle,
+    monitor: usize,
+    coords: Option<SelectionCoords>,
+    threshold: f64,
+    interval_ms: u64,
+) -> Result<(), String> {
+    if !(MIN_INTERVAL_MS..=MAX_INTERVAL_MS).contains(&interval_ms) {
+        return Err(format!(
+            "Invalid trigger interval: must be {}-{} ms",
+            MIN_INTERVAL_MS, MAX_INTERVAL_MS
+        ));
+    }
+    if threshold.is_nan() || threshold <= 0.0 || threshold > 1.0 {
+        return Err("Invalid change threshold: must be above 0.0 and at most 1.0".to_string());
+    }
+    if coords.is_some_and(|c| c.width == 0 || c.height == 0) {
+        return Err("Invalid trigger region dimensions".to_string());
+    }
+    capture_region(monitor, coords)?;
+
+    let handle = app.clone();
+    let interval = Duration::from_millis(interval_ms);
+    replace_worker(
+        &app.state::<WatchState>().trigger,
+        Some(Box::new(move |stop| {
+            trigger(handle, monitor, coords, threshold, interval, stop)
+        })),
+    )
+}
+
+/// Tauri command stopping the change trigger; does nothing when none is running
+#[tauri::command]
+#[specta::specta]
+pub fn stop_change_trigger(app: AppHandle) -> Result<(), String> {
+    replace_worker(&app.state::<WatchState>().trigger, None)
+}
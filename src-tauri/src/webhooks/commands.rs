@@ -0,0 +1,60 @@
+use serde::Serialize;
+use serde_json::json;
+use specta::Type;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use super::{payload, send, WebhooksState};
+use crate::llm::RetryConfig;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTest {
+    pub status: u16,
+    pub latency_ms: u64,
+}
+
+// Sends a `ping` event to one saved endpoint, once and without retries
+#[tauri::command]
+#[specta::specta]
+pub async fn test_webhook(app: AppHandle, id: String) -> Result<WebhookTest, String> {
+    let (endpoint, secret) = {
+        let state = app.state::<WebhooksState>();
+        let settings = state
+            .settings
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let endpoint = settings
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Webhook not found: {}", id))?;
+        let secret = state
+            .secrets
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?
+            .get(&id)
+            .cloned();
+        (endpoint, secret)
+    };
+
+    let body = payload("ping", now_ms(), json!({ "webhookId": endpoint.id }));
+    let no_retries = RetryConfig {
+        max_retries: 0,
+        ..RetryConfig::default()
+    };
+    let started = Instant::now();
+    let status = send(&endpoint.url, "ping", secret.as_deref(), &body, &no_retries).await?;
+    Ok(WebhookTest {
+        status,
+        latency_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
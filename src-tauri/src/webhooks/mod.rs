@@ -0,0 +1,392 @@
+// Pluely webhooks. Finished captures and answers are POSTed as JSON to
+// user-defined endpoints, for automations in n8n, Zapier or a script. Requests
+// are signed with the endpoint's secret and retried on transient failures.
+//
+// Payload: {"event": "capture.completed" | "answer.completed", "id",
+//           "timestamp" (unix ms), "data"}
+// Headers: X-Pluely-Event, X-Pluely-Timestamp (unix seconds) and, with a
+// secret, X-Pluely-Signature: sha256=<hex HMAC of "<timestamp>.<body>">
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::control::BridgeEvent;
+use crate::llm::RetryConfig;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+// Keychain entry holding every endpoint's secret as a JSON map by id
+pub const SECRETS_KEY_ID: &str = "webhook_secrets";
+const MAX_RETRIES: u32 = 10;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// How long a signed capture link keeps working
+const LINK_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEvent {
+    Capture,
+    Answer,
+}
+
+impl WebhookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Capture => "capture.completed",
+            Self::Answer => "answer.completed",
+        }
+    }
+}
+
+// What a capture payload carries besides the file's metadata
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureAttachment {
+    #[default]
+    None,
+    // Screenshots as base64 PNG or JPEG
+    Image,
+    // A signed link served by the control API, when it is running
+    Url,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    pub enabled: bool,
+    pub events: Vec<WebhookEvent>,
+    pub attach: CaptureAttachment,
+    // Written to the keychain, never kept in the config; empty removes it
+    pub secret: Option<String>,
+}
+
+impl Default for WebhookEndpoint {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            url: String::new(),
+            enabled: true,
+            events: vec![WebhookEvent::Capture, WebhookEvent::Answer],
+            attach: CaptureAttachment::None,
+            secret: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebhookSettings {
+    pub endpoints: Vec<WebhookEndpoint>,
+    pub retry: RetryConfig,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            retry: RetryConfig {
+                max_retries: 3,
+                initial_delay_ms: 2_000,
+                max_delay_ms: 60_000,
+            },
+        }
+    }
+}
+
+impl WebhookSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.retry.max_retries > MAX_RETRIES {
+            return Err(format!(
+                "Invalid webhook retries: must be 0-{}",
+                MAX_RETRIES
+            ));
+        }
+        let mut ids = HashSet::new();
+        for endpoint in &self.endpoints {
+            if endpoint.id.trim().is_empty() {
+                return Err("Webhook id cannot be empty".to_string());
+            }
+            if !ids.insert(endpoint.id.as_str()) {
+                return Err(format!("Duplicate webhook id: {}", endpoint.id));
+            }
+            let url = reqwest::Url::parse(endpoint.url.trim())
+                .map_err(|e| format!("Invalid webhook URL {}: {}", endpoint.url, e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!(
+                    "Invalid webhook URL {}: expected http or https",
+                    endpoint.url
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.endpoints
+            .iter()
+            .any(|endpoint| endpoint.enabled && !endpoint.events.is_empty())
+    }
+}
+
+#[derive(Default)]
+pub struct WebhooksState {
+    // Secrets stripped; they are kept in `secrets`
+    settings: Mutex<WebhookSettings>,
+    secrets: Mutex<HashMap<String, String>>,
+    // Forwards bridge events while any endpoint is enabled
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+// Emitted as `webhook-failed` once an endpoint gave up
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookFailed {
+    pub id: String,
+    pub event: String,
+    pub error: String,
+}
+
+fn load_secrets() -> Result<HashMap<String, String>, String> {
+    match crate::secrets::get_api_key(SECRETS_KEY_ID)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to read webhook secrets: {}", e)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn store_secrets(secrets: &HashMap<String, String>) -> Result<(), String> {
+    if secrets.is_empty() {
+        // An empty value removes the entry
+        return crate::secrets::store_api_key(SECRETS_KEY_ID, "");
+    }
+    let json = serde_json::to_string(secrets)
+        .map_err(|e| format!("Failed to serialize webhook secrets: {}", e))?;
+    crate::secrets::store_api_key(SECRETS_KEY_ID, &json)
+}
+
+// Validates `settings`, moves new secrets into the keychain and starts or
+// stops forwarding
+pub fn apply_webhook_settings(app: &AppHandle, settings: &WebhookSettings) -> Result<(), String> {
+    settings.validate()?;
+
+    // Endpoints without a secret keep the stored one; removed endpoints lose theirs
+    let stored = load_secrets()?;
+    let mut secrets = HashMap::new();
+    for endpoint in &settings.endpoints {
+        let secret = match endpoint.secret.as_deref().map(str::trim) {
+            Some(secret) => Some(secret.to_string()).filter(|secret| !secret.is_empty()),
+            None => stored.get(&endpoint.id).cloned(),
+        };
+        if let Some(secret) = secret {
+            secrets.insert(endpoint.id.clone(), secret);
+        }
+    }
+    if secrets != stored {
+        store_secrets(&secrets)?;
+    }
+
+    let mut settings = settings.clone();
+    for endpoint in &mut settings.endpoints {
+        endpoint.secret = None;
+    }
+    let active = settings.is_active();
+    let state = app.state::<WebhooksState>();
+    *state
+        .secrets
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = secrets;
+    *state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = settings;
+
+    let mut worker = state
+        .worker
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    match (active, worker.is_some()) {
+        (true, false) => *worker = Some(tauri::async_runtime::spawn(forward_events(app.clone()))),
+        (false, true) => {
+            if let Some(worker) = worker.take() {
+                worker.abort();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn forward_events(app: AppHandle) {
+    let mut events = crate::control::subscribe();
+    loop {
+        match events.recv().await {
+            Ok(message) => {
+                let (event, data) = match message.event {
+                    BridgeEvent::CaptureCompleted(file) => (WebhookEvent::Capture, json!(file)),
+                    BridgeEvent::LlmDone { request_id, text } => (
+                        WebhookEvent::Answer,
+                        json!({ "requestId": request_id, "text": text }),
+                    ),
+                    _ => continue,
+                };
+                dispatch(&app, event, message.ts, data);
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!("Webhooks missed {} events", missed);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// Adds the image or link the endpoint asked for to a capture's data
+fn attach_capture(app: &AppHandle, attach: CaptureAttachment, data: &mut Value) {
+    let Some(path) = data.get("path").and_then(Value::as_str).map(Path::new) else {
+        return;
+    };
+    match attach {
+        CaptureAttachment::None => {}
+        CaptureAttachment::Image => {
+            if data.get("kind").and_then(Value::as_str) != Some("screenshot") {
+                return;
+            }
+            match crate::storage::read_file(path) {
+                Ok(bytes) => data["image"] = json!(general_purpose::STANDARD.encode(bytes)),
+                Err(e) => warn!("Failed to attach capture to webhook: {}", e),
+            }
+        }
+        CaptureAttachment::Url => {
+            match crate::control::signed_capture_url(app, path, LINK_TTL_SECS) {
+                Ok(url) => data["url"] = json!(url),
+                Err(e) => warn!("Failed to sign capture link: {}", e),
+            }
+        }
+    }
+}
+
+fn payload(event: &str, timestamp: i64, data: Value) -> String {
+    json!({
+        "event": event,
+        "id": uuid::Uuid::new_v4().to_string(),
+        "timestamp": timestamp,
+        "data": data,
+    })
+    .to_string()
+}
+
+// Sends one event to every endpoint subscribed to it, each on its own task so
+// a slow endpoint doesn't hold up the others
+fn dispatch(app: &AppHandle, event: WebhookEvent, timestamp: i64, data: Value) {
+    let state = app.state::<WebhooksState>();
+    let (settings, secrets) = match (state.settings.lock(), state.secrets.lock()) {
+        (Ok(settings), Ok(secrets)) => (settings.clone(), secrets.clone()),
+        _ => return,
+    };
+    for endpoint in settings
+        .endpoints
+        .iter()
+        .filter(|endpoint| endpoint.enabled && endpoint.events.contains(&event))
+    {
+        let mut data = data.clone();
+        if event == WebhookEvent::Capture {
+            attach_capture(app, endpoint.attach, &mut data);
+        }
+        let body = payload(event.name(), timestamp, data);
+        let app = app.clone();
+        let endpoint = endpoint.clone();
+        let secret = secrets.get(&endpoint.id).cloned();
+        let retry = settings.retry;
+        tauri::async_runtime::spawn(async move {
+            let sent = send(
+                &endpoint.url,
+                event.name(),
+                secret.as_deref(),
+                &body,
+                &retry,
+            )
+            .await;
+            if let Err(error) = sent {
+                warn!("Webhook {} failed: {}", endpoint.id, error);
+                let failed = WebhookFailed {
+                    id: endpoint.id,
+                    event: event.name().to_string(),
+                    error,
+                };
+                if let Err(e) = app.emit("webhook-failed", failed) {
+                    warn!("Failed to emit webhook failure: {}", e);
+                }
+            }
+        });
+    }
+}
+
+// Posts `body`, retrying network errors, rate limits and server errors.
+// Returns the final status code.
+pub async fn send(
+    url: &str,
+    event: &str,
+    secret: Option<&str>,
+    body: &str,
+    retry: &RetryConfig,
+) -> Result<u16, String> {
+    let client = crate::network::http_client()?;
+    let mut attempt = 0;
+    loop {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            .to_string();
+        let mut request = client
+            .post(url.trim())
+            .timeout(REQUEST_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Pluely-Event", event)
+            .header("X-Pluely-Timestamp", &timestamp);
+        if let Some(secret) = secret {
+            let signed = format!("{}.{}", timestamp, body);
+            request = request.header(
+                "X-Pluely-Signature",
+                format!(
+                    "sha256={}",
+                    crate::crypto::sign(secret.as_bytes(), signed.as_bytes())
+                ),
+            );
+        }
+
+        let request = request.body(body.to_string());
+        let (error, retry_after) = match crate::network::send(request, "webhook", &[]).await {
+            Ok(response) if response.status().is_success() => {
+                return Ok(response.status().as_u16())
+            }
+            Ok(response) if !crate::llm::is_retryable_status(response.status()) => {
+                return Err(format!("Webhook returned {}", response.status()))
+            }
+            Ok(response) => (
+                format!("Webhook returned {}", response.status()),
+                crate::llm::retry_after(response.headers()),
+            ),
+            Err(e) => (format!("Failed to send webhook: {}", e), None),
+        };
+        attempt += 1;
+        if attempt > retry.max_retries {
+            return Err(error);
+        }
+        tokio::time::sleep(retry.delay(attempt, retry_after)).await;
+    }
+}
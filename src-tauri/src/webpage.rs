@@ -0,0 +1,425 @@
+// Pluely link context. A pasted link is fetched and its readable text pulled
+// out the way reader modes do, keeping the article and dropping navigation,
+// ads and comments. A screenshot of the page can be rendered alongside.
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::StreamExt;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+const SCREENSHOT_WIDTH: u32 = 1280;
+const SCREENSHOT_HEIGHT: u32 = 1600;
+// Pages are cut off past this many bytes
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+// Enough for a long article; more would crowd out the conversation
+const MAX_TEXT_CHARS: usize = 40_000;
+// Marked-up articles shorter than this are probably teasers
+const MIN_ARTICLE_CHARS: usize = 500;
+// Paragraphs shorter than this don't count towards a container's score
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+// Never part of the readable text
+const SKIPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "form",
+    "button", "input", "select", "textarea", "nav", "footer", "aside", "header",
+];
+const BLOCKS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "body",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+// Class and id words of page furniture
+const BOILERPLATE: &[&str] = &[
+    "comment",
+    "sidebar",
+    "footer",
+    "related",
+    "share",
+    "social",
+    "promo",
+    "advert",
+    "sponsor",
+    "newsletter",
+    "cookie",
+    "banner",
+    "popup",
+    "breadcrumb",
+    "menu",
+];
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlContext {
+    // After redirects
+    pub url: String,
+    pub title: Option<String>,
+    pub site_name: Option<String>,
+    pub description: Option<String>,
+    pub byline: Option<String>,
+    // Headings, paragraphs, lists and code as light Markdown
+    pub text: String,
+    pub truncated: bool,
+    // Base64 PNG, when one was asked for and could be rendered
+    pub screenshot: Option<String>,
+    pub screenshot_error: Option<String>,
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_of(element: ElementRef) -> String {
+    collapse(&element.text().collect::<String>())
+}
+
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    let names = format!(
+        "{} {}",
+        value.attr("class").unwrap_or_default(),
+        value.id().unwrap_or_default()
+    )
+    .to_lowercase();
+    BOILERPLATE.iter().any(|word| names.contains(word))
+        || value.attr("aria-hidden") == Some("true")
+        || value.attr("hidden").is_some()
+}
+
+fn meta(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("content"))
+        .map(collapse)
+        .find(|content| !content.is_empty())
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .map(text_of)
+        .find(|text| !text.is_empty())
+}
+
+// Share of an element's text that sits in links; menus are mostly links
+fn link_density(element: ElementRef, text_len: usize) -> f64 {
+    let Ok(links) = Selector::parse("a") else {
+        return 0.0;
+    };
+    let linked: usize = element.select(&links).map(|link| text_of(link).len()).sum();
+    linked as f64 / text_len.max(1) as f64
+}
+
+// The element holding the page's main text
+fn main_content(document: &Html) -> Option<ElementRef<'_>> {
+    // Pages that mark up their content are taken at their word
+    for selector in ["article", "main", "[role=main]"] {
+        let Ok(selector) = Selector::parse(selector) else {
+            continue;
+        };
+        if let Some(element) = document
+            .select(&selector)
+            .max_by_key(|element| text_of(*element).len())
+            .filter(|element| text_of(*element).len() >= MIN_ARTICLE_CHARS)
+        {
+            return Some(element);
+        }
+    }
+
+    // Otherwise the container with the most paragraph text wins; its parent
+    // shares the credit, for articles split across sibling blocks
+    let paragraphs = Selector::parse("p, pre, td").ok()?;
+    let mut scores = HashMap::new();
+    for paragraph in document.select(&paragraphs) {
+        let text = text_of(paragraph);
+        if text.len() < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (text.len() as f64 / 100.0).min(3.0);
+        let mut ancestors = paragraph.ancestors().filter_map(ElementRef::wrap);
+        for share in [1.0, 0.5] {
+            if let Some(ancestor) = ancestors.next() {
+                scores.entry(ancestor.id()).or_insert((ancestor, 0.0)).1 += score * share;
+            }
+        }
+    }
+    scores
+        .into_values()
+        .map(|(element, score)| {
+            let mut score = score * (1.0 - link_density(element, text_of(element).len()));
+            if is_boilerplate(element) {
+                score *= 0.25;
+            }
+            (element, score)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(element, _)| element)
+        .or_else(|| {
+            let body = Selector::parse("body").ok()?;
+            document.select(&body).next()
+        })
+}
+
+fn push_block(out: &mut String, block: &str) {
+    let block = block.trim();
+    if block.is_empty() {
+        return;
+    }
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str(block);
+}
+
+// Text that runs on between blocks, such as a paragraph's links and spans
+fn flush(out: &mut String, pending: &mut String) {
+    push_block(out, &collapse(pending));
+    pending.clear();
+}
+
+fn render(element: ElementRef, out: &mut String, pending: &mut String) {
+    for child in element.children() {
+        let child_element = match child.value() {
+            Node::Text(text) => {
+                pending.push_str(text);
+                continue;
+            }
+            Node::Element(_) => match ElementRef::wrap(child) {
+                Some(child_element) => child_element,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let name = child_element.value().name();
+        if SKIPPED.contains(&name) || is_boilerplate(child_element) {
+            continue;
+        }
+        if name == "br" {
+            pending.push(' ');
+            continue;
+        }
+        if !BLOCKS.contains(&name) {
+            render(child_element, out, pending);
+            continue;
+        }
+
+        flush(out, pending);
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                let text = text_of(child_element);
+                if !text.is_empty() {
+                    push_block(out, &format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            "pre" => {
+                let code: String = child_element.text().collect();
+                if !code.trim().is_empty() {
+                    push_block(out, &format!("```\n{}\n```", code.trim_end()));
+                }
+            }
+            "li" => {
+                let mut item = String::new();
+                render(child_element, &mut item, pending);
+                flush(&mut item, pending);
+                if !item.is_empty() {
+                    // Items sit on consecutive lines
+                    if !out.is_empty() && !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("- ");
+                    out.push_str(&item.replace("\n\n", "\n  "));
+                }
+            }
+            _ => {
+                render(child_element, out, pending);
+                flush(out, pending);
+            }
+        }
+    }
+}
+
+fn readable_text(document: &Html) -> String {
+    let Some(content) = main_content(document) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    let mut pending = String::new();
+    render(content, &mut out, &mut pending);
+    flush(&mut out, &mut pending);
+    out
+}
+
+// Cuts `text` at a paragraph boundary near `max_chars`
+fn truncate(text: String, max_chars: usize) -> (String, bool) {
+    let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+        return (text, false);
+    };
+    let cut = text[..cut].rfind("\n\n").unwrap_or(cut);
+    (text[..cut].trim_end().to_string(), true)
+}
+
+async fn fetch(url: &reqwest::Url) -> Result<(String, String, String), String> {
+    let request = crate::network::http_client()?
+        .get(url.clone())
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!(
+                "Mozilla/5.0 (compatible; Pluely/",
+                env!("CARGO_PKG_VERSION"),
+                ")"
+            ),
+        )
+        .header(
+            reqwest::header::ACCEPT,
+            "text/html,application/xhtml+xml,text/plain;q=0.9",
+        )
+        .timeout(FETCH_TIMEOUT);
+    let response = crate::network::send(request, "link", &[])
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_lowercase();
+    if !["text/html", "application/xhtml", "text/plain"]
+        .iter()
+        .any(|kind| content_type.starts_with(kind))
+    {
+        return Err(format!(
+            "Only web pages can be read; {} is {}",
+            url, content_type
+        ));
+    }
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_PAGE_BYTES {
+            body.truncate(MAX_PAGE_BYTES);
+            break;
+        }
+    }
+    Ok((
+        final_url,
+        content_type,
+        String::from_utf8_lossy(&body).to_string(),
+    ))
+}
+
+/// Fetches `url` and returns its readable text and details, plus a
+/// screenshot when `screenshot` is set, as context for a pasted link
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_url_context(
+    url: String,
+    screenshot: Option<bool>,
+) -> Result<UrlContext, String> {
+    crate::network::ensure_online("Reading links")?;
+    let parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Invalid URL {}: expected http or https", url));
+    }
+
+    // The screenshot renders while the page is read
+    let shot = screenshot.unwrap_or(false).then(|| {
+        // The browser loads the page itself, outside the shared client
+        crate::audit::record_egress("link screenshot", &parsed, None, &[]);
+        let url = parsed.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::browser::screenshot(
+                &url,
+                SCREENSHOT_WIDTH,
+                SCREENSHOT_HEIGHT,
+                SCREENSHOT_TIMEOUT,
+            )
+        })
+    });
+
+    let (final_url, content_type, body) = fetch(&parsed).await?;
+    let mut context = if content_type.starts_with("text/plain") {
+        let (text, truncated) = truncate(body.trim().to_string(), MAX_TEXT_CHARS);
+        UrlContext {
+            url: final_url,
+            title: None,
+            site_name: None,
+            description: None,
+            byline: None,
+            text,
+            truncated,
+            screenshot: None,
+            screenshot_error: None,
+        }
+    } else {
+        let document = Html::parse_document(&body);
+        let (text, truncated) = truncate(readable_text(&document), MAX_TEXT_CHARS);
+        UrlContext {
+            url: final_url,
+            title: meta(&document, "meta[property='og:title']")
+                .or_else(|| first_text(&document, "title")),
+            site_name: meta(&document, "meta[property='og:site_name']"),
+            description: meta(&document, "meta[name='description']")
+                .or_else(|| meta(&document, "meta[property='og:description']")),
+            byline: meta(&document, "meta[name='author']"),
+            text,
+            truncated,
+            screenshot: None,
+            screenshot_error: None,
+        }
+    };
+
+    if let Some(shot) = shot {
+        match shot
+            .await
+            .map_err(|e| format!("Failed to render page: {}", e))
+            .and_then(|result| result)
+        {
+            Ok(png) => context.screenshot = Some(general_purpose::STANDARD.encode(png)),
+            Err(e) => {
+                warn!("Failed to screenshot {}: {}", url, e);
+                context.screenshot_error = Some(e);
+            }
+        }
+    }
+    Ok(context)
+}
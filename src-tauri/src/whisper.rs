@@ -0,0 +1,466 @@
+// Pluely local transcription engine (whisper.cpp via whisper-rs).
+// Compiled only with the `whisper` feature; without it every command
+// returns an error so the frontend can fall back to cloud STT.
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+#[cfg(feature = "whisper")]
+use crate::events::{WhisperFinal, WhisperPartial};
+#[cfg(feature = "whisper")]
+use crate::speaker::meter::LevelMeter;
+#[cfg(feature = "whisper")]
+use crate::speaker::resample::resample_linear;
+#[cfg(feature = "whisper")]
+use crate::speaker::SpeakerInput;
+#[cfg(feature = "whisper")]
+use futures_util::StreamExt;
+#[cfg(feature = "whisper")]
+use std::sync::Arc;
+#[cfg(feature = "whisper")]
+use tauri::Emitter;
+#[cfg(feature = "whisper")]
+use tokio::sync::mpsc;
+#[cfg(feature = "whisper")]
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+// Whisper models are trained on 16 kHz mono audio
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+// Captured audio is handed to the streaming loop in chunks this long
+#[cfg(feature = "whisper")]
+const STREAM_CHUNK_MS: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WhisperConfig {
+    pub model_path: Option<String>,
+    pub language: Option<String>,
+    pub threads: i32,
+    pub translate: bool,
+    // Streaming mode: how often a partial transcript is produced
+    pub step_ms: u64,
+    // Streaming mode: audio window length before a segment is finalized
+    pub window_ms: u64,
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        Self {
+            model_path: None,
+            language: None, // auto-detect
+            threads: 4,
+            translate: false,
+            step_ms: 2000,
+            window_ms: 15000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TranscriptUpdate {
+    pub text: String,
+    pub is_final: bool,
+}
+
+#[derive(Default)]
+pub struct WhisperState {
+    config: Mutex<WhisperConfig>,
+    #[cfg(feature = "whisper")]
+    engine: Mutex<Option<Arc<WhisperEngine>>>,
+    // Set while a stream runs; sending on it stops capture, and the stream
+    // ends once the audio already captured is transcribed
+    stream_stop: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+#[cfg(feature = "whisper")]
+pub struct WhisperEngine {
+    ctx: WhisperContext,
+    config: WhisperConfig,
+}
+
+#[cfg(feature = "whisper")]
+impl WhisperEngine {
+    pub fn config(&self) -> &WhisperConfig {
+        &self.config
+    }
+
+    pub fn load(config: WhisperConfig) -> Result<Self, String> {
+        let model_path = config
+            .model_path
+            .clone()
+            .ok_or("No Whisper model configured".to_string())?;
+
+        let ctx = WhisperContext::new_with_params(&model_path, WhisperContextParameters::default())
+            .map_err(|e| format!("Failed to load Whisper model {}: {}", model_path, e))?;
+
+        Ok(Self { ctx, config })
+    }
+
+    // Transcribes a complete mono buffer recorded at `sample_rate`.
+    pub fn transcribe_buffer(&self, samples: &[f32], sample_rate: u32) -> Result<String, String> {
+        if samples.is_empty() {
+            return Err("Empty audio buffer".to_string());
+        }
+
+        let audio = resample_linear(samples, sample_rate, WHISPER_SAMPLE_RATE);
+
+        let mut state = self
+            .ctx
+            .create_state()
+            .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_n_threads(self.config.threads);
+        params.set_translate(self.config.translate);
+        params.set_language(self.config.language.as_deref());
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+
+        state
+            .full(params, &audio)
+            .map_err(|e| format!("Whisper inference failed: {}", e))?;
+
+        let segments = state
+            .full_n_segments()
+            .map_err(|e| format!("Failed to read Whisper segments: {}", e))?;
+
+        let mut text = String::new();
+        for i in 0..segments {
+            let segment = state
+                .full_get_segment_text(i)
+                .map_err(|e| format!("Failed to read Whisper segment {}: {}", i, e))?;
+            text.push_str(&segment);
+        }
+
+        Ok(text.trim().to_string())
+    }
+}
+
+#[cfg(feature = "whisper")]
+pub(crate) fn loaded_engine(app: &AppHandle) -> Result<Arc<WhisperEngine>, String> {
+    let state = app.state::<WhisperState>();
+    let mut engine = state
+        .engine
+        .lock()
+        .map_err(|e| format!("Failed to acquire Whisper engine lock: {}", e))?;
+
+    if let Some(engine) = engine.as_ref() {
+        return Ok(engine.clone());
+    }
+
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read Whisper config: {}", e))?
+        .clone();
+    let loaded = Arc::new(WhisperEngine::load(config)?);
+    *engine = Some(loaded.clone());
+
+    Ok(loaded)
+}
+
+// Loading reads the whole model file, so async callers do it on a blocking
+// thread
+#[cfg(feature = "whisper")]
+pub(crate) async fn load_engine(app: &AppHandle) -> Result<Arc<WhisperEngine>, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || loaded_engine(&app))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+// Decode a base64 WAV (optionally a data URL) into mono f32 samples.
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+fn decode_wav_base64(audio_base64: &str) -> Result<(Vec<f32>, u32), String> {
+    let trimmed = audio_base64.trim();
+    let data = match trimmed.find(',') {
+        Some(idx) => &trimmed[idx + 1..],
+        None => trimmed,
+    };
+    let bytes = B64
+        .decode(data)
+        .map_err(|e| format!("Failed to decode audio data: {}", e))?;
+
+    decode_wav_bytes(bytes)
+}
+
+// Decode WAV bytes into mono f32 samples and their sample rate.
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+pub(crate) fn decode_wav_bytes(bytes: Vec<u8>) -> Result<(Vec<f32>, u32), String> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to read WAV data: {}", e))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read WAV samples: {}", e))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to read WAV samples: {}", e))?
+        }
+    };
+
+    // Downmix to mono
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok((mono, spec.sample_rate))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_whisper_config(app: AppHandle) -> Result<WhisperConfig, String> {
+    let state = app.state::<WhisperState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read Whisper config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_whisper_config(app: AppHandle, config: WhisperConfig) -> Result<(), String> {
+    if config.step_ms < 500 || config.window_ms < config.step_ms {
+        return Err("Invalid streaming window: step_ms must be >= 500 and <= window_ms".to_string());
+    }
+    if config.window_ms > 30_000 {
+        return Err("Invalid window_ms: Whisper processes at most 30s per pass".to_string());
+    }
+
+    let state = app.state::<WhisperState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update Whisper config: {}", e))? = config;
+
+    // Drop the loaded model so the next transcription picks up the new config
+    #[cfg(feature = "whisper")]
+    {
+        *state
+            .engine
+            .lock()
+            .map_err(|e| format!("Failed to reset Whisper engine: {}", e))? = None;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn is_local_whisper_available() -> bool {
+    cfg!(feature = "whisper")
+}
+
+// Transcribes a base64 WAV clip (same shape as `speech-detected` payloads).
+#[cfg(feature = "whisper")]
+#[tauri::command]
+#[specta::specta]
+pub async fn transcribe_local(app: AppHandle, audio_base64: String) -> Result<String, String> {
+    let engine = load_engine(&app).await?;
+    let (samples, sample_rate) = decode_wav_base64(&audio_base64)?;
+
+    tauri::async_runtime::spawn_blocking(move || engine.transcribe_buffer(&samples, sample_rate))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))?
+}
+
+#[cfg(not(feature = "whisper"))]
+#[tauri::command]
+#[specta::specta]
+pub async fn transcribe_local(_app: AppHandle, _audio_base64: String) -> Result<String, String> {
+    Err("Local Whisper support is not enabled in this build".to_string())
+}
+
+// Streaming mode: captures system audio and emits `whisper-partial` events for
+// the growing window, then `whisper-final` once the window is full. Capture
+// runs in its own task, so audio keeps arriving while a pass is transcribed.
+#[cfg(feature = "whisper")]
+#[tauri::command]
+#[specta::specta]
+pub async fn start_whisper_stream(app: AppHandle, device_id: Option<String>) -> Result<(), String> {
+    let state = app.state::<WhisperState>();
+    if state
+        .stream_stop
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?
+        .is_some()
+    {
+        return Err("Whisper stream already running".to_string());
+    }
+
+    let engine = load_engine(&app).await?;
+    let input = SpeakerInput::new_with_device(device_id)
+        .map_err(|e| format!("Failed to access system audio: {}", e))?;
+    let mut stream = input.stream();
+    let sr = stream.sample_rate();
+
+    let step_samples = (sr as u64 * engine.config.step_ms / 1000) as usize;
+    let window_samples = (sr as u64 * engine.config.window_ms / 1000) as usize;
+    let chunk_samples = (sr * STREAM_CHUNK_MS / 1000) as usize;
+
+    // Checked again and stored under one lock, so two starts can't both run
+    let mut stop_guard = state
+        .stream_stop
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if stop_guard.is_some() {
+        return Err("Whisper stream already running".to_string());
+    }
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    // Unbounded: a slow pass must not make capture drop audio
+    let (audio_tx, mut audio_rx) = mpsc::unbounded_channel::<Vec<f32>>();
+
+    // Capture -> chunks, until the device goes away or the stream is stopped
+    let meter_app = app.clone();
+    let capture = tokio::spawn(async move {
+        let mut chunk = Vec::with_capacity(chunk_samples);
+        let mut meter = LevelMeter::new("system", sr);
+        loop {
+            let sample = tokio::select! {
+                sample = stream.next() => sample,
+                _ = &mut stop_rx => None,
+            };
+            let Some(sample) = sample else {
+                break;
+            };
+            meter.push(&meter_app, sample);
+            chunk.push(sample);
+            if chunk.len() >= chunk_samples {
+                let full = std::mem::replace(&mut chunk, Vec::with_capacity(chunk_samples));
+                if audio_tx.send(full).is_err() {
+                    return;
+                }
+            }
+        }
+        let _ = audio_tx.send(chunk);
+    });
+
+    let app_clone = app.clone();
+    tokio::spawn(async move {
+        let mut window: Vec<f32> = Vec::with_capacity(window_samples);
+        let mut since_step = 0usize;
+
+        while let Some(chunk) = audio_rx.recv().await {
+            // Audio captured during the last pass joins the window too
+            since_step += chunk.len();
+            window.extend(chunk);
+            while let Ok(chunk) = audio_rx.try_recv() {
+                since_step += chunk.len();
+                window.extend(chunk);
+            }
+
+            if since_step < step_samples {
+                continue;
+            }
+            since_step = 0;
+
+            let is_final = window.len() >= window_samples;
+            let audio = if is_final {
+                std::mem::take(&mut window)
+            } else {
+                window.clone()
+            };
+            if !transcribe_pass(&app_clone, &engine, audio, sr, is_final).await {
+                break;
+            }
+        }
+
+        // Capture stopped; what's left of the window is the last segment
+        capture.abort();
+        if !window.is_empty() {
+            transcribe_pass(&app_clone, &engine, window, sr, true).await;
+        }
+
+        // A stop already took the sender; otherwise the device went away.
+        // Only this stream's sender is closed, as its receiver is gone.
+        let state = app_clone.state::<WhisperState>();
+        if let Ok(mut guard) = state.stream_stop.lock() {
+            if guard.as_ref().is_some_and(|stop| stop.is_closed()) {
+                *guard = None;
+            }
+        };
+    });
+
+    *stop_guard = Some(stop_tx);
+
+    Ok(())
+}
+
+// Transcribes one streaming window and emits the result. Returns false if
+// inference panicked, which ends the stream.
+#[cfg(feature = "whisper")]
+async fn transcribe_pass(
+    app: &AppHandle,
+    engine: &Arc<WhisperEngine>,
+    audio: Vec<f32>,
+    sr: u32,
+    is_final: bool,
+) -> bool {
+    let engine = engine.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || engine.transcribe_buffer(&audio, sr)).await;
+
+    match result {
+        Ok(Ok(text)) if !text.is_empty() => {
+            let update = TranscriptUpdate { text, is_final };
+            let _ = if is_final {
+                app.emit("whisper-final", WhisperFinal(update))
+            } else {
+                app.emit("whisper-partial", WhisperPartial(update))
+            };
+        }
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => {
+            tracing::warn!("Whisper streaming inference failed: {}", e);
+        }
+        Err(e) => {
+            tracing::error!("Whisper streaming task panicked: {}", e);
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(not(feature = "whisper"))]
+#[tauri::command]
+#[specta::specta]
+pub async fn start_whisper_stream(
+    _app: AppHandle,
+    _device_id: Option<String>,
+) -> Result<(), String> {
+    Err("Local Whisper support is not enabled in this build".to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_whisper_stream(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<WhisperState>();
+    let mut guard = state
+        .stream_stop
+        .lock()
+        .map_err(|e| format!("Failed to acquire task lock: {}", e))?;
+
+    // The stream finishes transcribing what was captured before it ends
+    if let Some(stop) = guard.take() {
+        let _ = stop.send(());
+    }
+
+    Ok(())
+}
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(target_os = "macos")]
 use tauri::LogicalPosition;
 use tauri::{App, AppHandle, Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
@@ -5,6 +8,311 @@ use tauri::{App, AppHandle, Manager, Runtime, WebviewWindow, WebviewWindowBuilde
 // The offset from the top of the screen to the window
 const TOP_OFFSET: i32 = 54;
 
+// Whether windows are hidden from screen shares and recordings; new windows
+// (the dashboard, capture overlays) are created to match
+static CONTENT_PROTECTED: AtomicBool = AtomicBool::new(true);
+
+pub fn content_protected() -> bool {
+    CONTENT_PROTECTED.load(Ordering::SeqCst)
+}
+
+// Windows 10 2004 and later can leave the window out of captures entirely;
+// older versions only support showing it black
+#[cfg(target_os = "windows")]
+fn protect_window<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_MONITOR, WDA_NONE,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let hwnd = HWND(hwnd.0 as isize);
+    let applied = if enabled {
+        unsafe {
+            SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE).as_bool()
+                || SetWindowDisplayAffinity(hwnd, WDA_MONITOR).as_bool()
+        }
+    } else {
+        unsafe { SetWindowDisplayAffinity(hwnd, WDA_NONE).as_bool() }
+    };
+    if !applied {
+        return Err(format!(
+            "Failed to set display affinity: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+// On macOS this sets the NSWindow sharing type to none
+#[cfg(not(target_os = "windows"))]
+fn protect_window<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) -> Result<(), String> {
+    window
+        .set_content_protected(enabled)
+        .map_err(|e| format!("Failed to set content protection: {}", e))
+}
+
+/// Hides every open window from screen sharing and recording, or shows them
+/// again, and remembers the choice for windows opened later
+pub fn set_content_protection_all(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    CONTENT_PROTECTED.store(enabled, Ordering::SeqCst);
+    for window in app.webview_windows().values() {
+        protect_window(window, enabled)?;
+    }
+    Ok(())
+}
+
+/// Tauri command to hide a window from screen shares (stealth mode). Without
+/// a label it applies to every window, including overlays opened later.
+#[tauri::command]
+#[specta::specta]
+pub fn set_content_protection(
+    app: AppHandle,
+    window: Option<String>,
+    enabled: bool,
+) -> Result<(), String> {
+    match window {
+        Some(label) => {
+            let window = app
+                .get_webview_window(&label)
+                .ok_or_else(|| format!("Window not found: {}", label))?;
+            protect_window(&window, enabled)
+        }
+        None => set_content_protection_all(&app, enabled),
+    }
+}
+
+// Frosted background behind the transparent main window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    // Windows 10 and later
+    Blur,
+    Acrylic,
+    // Windows 11
+    Mica,
+    // macOS
+    Vibrancy,
+}
+
+#[cfg(target_os = "windows")]
+fn set_alpha<R: Runtime>(window: &WebviewWindow<R>, opacity: f64) -> Result<(), String> {
+    use windows::Win32::Foundation::{COLORREF, HWND};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let hwnd = HWND(hwnd.0 as isize);
+    let alpha = (opacity * 255.0).round() as u8;
+    let applied = unsafe {
+        // Only layered windows can be translucent
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA).as_bool()
+    };
+    if !applied {
+        return Err(format!(
+            "Failed to set window opacity: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn set_alpha<R: Runtime>(window: &WebviewWindow<R>, opacity: f64) -> Result<(), String> {
+    use objc2::runtime::AnyObject;
+
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| format!("Failed to get window handle: {}", e))? as usize;
+    // AppKit windows may only be changed on the main thread
+    window
+        .run_on_main_thread(move || unsafe {
+            let ns_window = ns_window as *mut AnyObject;
+            let _: () = objc2::msg_send![ns_window, setAlphaValue: opacity];
+        })
+        .map_err(|e| format!("Failed to set window opacity: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn set_alpha<R: Runtime>(window: &WebviewWindow<R>, opacity: f64) -> Result<(), String> {
+    use gtk::prelude::WidgetExt;
+
+    window
+        .gtk_window()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?
+        .set_opacity(opacity);
+    Ok(())
+}
+
+/// Makes the main window see-through; `opacity` is 0.2 (faint) to 1.0
+pub fn set_main_window_opacity(app: &AppHandle, opacity: f64) -> Result<(), String> {
+    // Fully transparent would leave an invisible window catching clicks
+    if !(0.2..=1.0).contains(&opacity) {
+        return Err("Invalid opacity: must be 0.2-1.0".to_string());
+    }
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    set_alpha(&window, opacity)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_opacity(app: AppHandle, value: f64) -> Result<(), String> {
+    set_main_window_opacity(&app, value)
+}
+
+fn clear_effects<R: Runtime>(window: &WebviewWindow<R>) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = window_vibrancy::clear_blur(window);
+        let _ = window_vibrancy::clear_acrylic(window);
+        let _ = window_vibrancy::clear_mica(window);
+    }
+    #[cfg(target_os = "macos")]
+    let _ = window_vibrancy::clear_vibrancy(window);
+    #[cfg(target_os = "linux")]
+    let _ = window;
+}
+
+/// Applies `effect` to the main window, replacing the previous one
+pub fn set_main_window_effect(app: &AppHandle, effect: WindowEffect) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    clear_effects(&window);
+    // Dark tint so text stays readable over bright content
+    #[cfg(target_os = "windows")]
+    let tint = Some((18, 18, 18, 125));
+    let applied: Result<(), window_vibrancy::Error> = match effect {
+        WindowEffect::None => Ok(()),
+        #[cfg(target_os = "windows")]
+        WindowEffect::Blur => window_vibrancy::apply_blur(&window, tint),
+        #[cfg(target_os = "windows")]
+        WindowEffect::Acrylic => window_vibrancy::apply_acrylic(&window, tint),
+        #[cfg(target_os = "windows")]
+        WindowEffect::Mica => window_vibrancy::apply_mica(&window, None),
+        #[cfg(target_os = "macos")]
+        WindowEffect::Vibrancy => window_vibrancy::apply_vibrancy(
+            &window,
+            window_vibrancy::NSVisualEffectMaterial::HudWindow,
+            None,
+            None,
+        ),
+        #[allow(unreachable_patterns)]
+        other => {
+            return Err(format!(
+                "The {:?} effect isn't supported on this platform",
+                other
+            ))
+        }
+    };
+    applied.map_err(|e| format!("Failed to apply window effect: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_effect(app: AppHandle, effect: WindowEffect) -> Result<(), String> {
+    set_main_window_effect(&app, effect)
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    app.get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())
+}
+
+// A tool window is left out of Alt+Tab and the taskbar
+#[cfg(target_os = "windows")]
+fn hide_from_switcher(app: &AppHandle, hidden: bool) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetWindowLongPtrW, GWL_EXSTYLE, WS_EX_APPWINDOW, WS_EX_TOOLWINDOW,
+    };
+
+    let hwnd = main_window(app)?
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let hwnd = HWND(hwnd.0 as isize);
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let style = if hidden {
+            (style | WS_EX_TOOLWINDOW.0 as isize) & !(WS_EX_APPWINDOW.0 as isize)
+        } else {
+            style & !(WS_EX_TOOLWINDOW.0 as isize)
+        };
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style);
+    }
+    Ok(())
+}
+
+// Accessory apps have no dock icon and don't appear in Cmd+Tab
+#[cfg(target_os = "macos")]
+fn hide_from_switcher(app: &AppHandle, hidden: bool) -> Result<(), String> {
+    let visible = !hidden
+        && crate::settings::current_settings(app)
+            .map(|settings| settings.window.app_icon_visible)
+            .unwrap_or(true);
+    crate::shortcuts::set_app_icon_visibility(app.clone(), visible)
+}
+
+// Window switchers on Linux list the same windows as the panel
+#[cfg(target_os = "linux")]
+fn hide_from_switcher(app: &AppHandle, hidden: bool) -> Result<(), String> {
+    if hidden {
+        main_window(app)?
+            .set_skip_taskbar(true)
+            .map_err(|e| format!("Failed to hide from window switcher: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Tauri command to keep the main window out of the taskbar
+#[tauri::command]
+#[specta::specta]
+pub fn set_skip_taskbar(app: AppHandle, enabled: bool) -> Result<(), String> {
+    main_window(&app)?
+        .set_skip_taskbar(enabled)
+        .map_err(|e| format!("Failed to set taskbar visibility: {}", e))
+}
+
+/// Tauri command to keep Pluely out of Alt+Tab (Windows, Linux) or the dock
+/// and Cmd+Tab (macOS)
+#[tauri::command]
+#[specta::specta]
+pub fn set_hide_from_switcher(app: AppHandle, enabled: bool) -> Result<(), String> {
+    hide_from_switcher(&app, enabled)
+}
+
+// Restores the saved HUD preferences; the commands above only last until restart
+pub fn apply_window_settings(
+    app: &AppHandle,
+    settings: &crate::settings::WindowSettings,
+) -> Result<(), String> {
+    crate::placement::set_show_at(settings.show_at);
+    let window = main_window(app)?;
+    window
+        .set_always_on_top(settings.always_on_top)
+        .map_err(|e| format!("Failed to set always on top: {}", e))?;
+    window
+        .set_skip_taskbar(settings.skip_taskbar)
+        .map_err(|e| format!("Failed to set taskbar visibility: {}", e))?;
+    // Only hiding is applied, so turning it off doesn't undo the taskbar choice
+    if settings.hide_from_switcher {
+        hide_from_switcher(app, true)?;
+    }
+    Ok(())
+}
+
 /// Sets up the main window with custom positioning
 pub fn setup_main_window(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
     // Try different possible window labels
@@ -71,6 +379,7 @@ pub fn center_window_completely(window: &WebviewWindow) -> Result<(), Box<dyn st
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn set_window_height(window: tauri::WebviewWindow, height: u32) -> Result<(), String> {
     use tauri::{LogicalSize, Size};
 
@@ -94,6 +403,7 @@ pub fn set_window_height(window: tauri::WebviewWindow, height: u32) -> Result<()
 // }
 
 #[tauri::command]
+#[specta::specta]
 pub fn open_dashboard(app: tauri::AppHandle) -> Result<(), String> {
     // Check if dashboard window already exists
     if let Some(dashboard_window) = app.get_webview_window("dashboard") {
@@ -114,6 +424,7 @@ pub fn open_dashboard(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn toggle_dashboard(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(dashboard_window) = app.get_webview_window("dashboard") {
         match dashboard_window.is_visible() {
@@ -146,6 +457,7 @@ pub fn toggle_dashboard(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub fn move_window(app: tauri::AppHandle, direction: String, step: i32) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("main") {
         let current_pos = window
@@ -173,6 +485,48 @@ pub fn move_window(app: tauri::AppHandle, direction: String, step: i32) -> Resul
     Ok(())
 }
 
+// Label of the window bound to a history conversation
+fn conversation_window_label(conversation_id: &str) -> String {
+    format!("conversation-{}", conversation_id)
+}
+
+/// Tauri command opening a history conversation in a chat window of its own,
+/// or focusing the one already showing it
+#[tauri::command]
+#[specta::specta]
+pub fn open_conversation_window(app: AppHandle, conversation_id: String) -> Result<(), String> {
+    // The id ends up in a window label and a URL
+    if conversation_id.is_empty()
+        || !conversation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid conversation id: {}", conversation_id));
+    }
+    let label = conversation_window_label(&conversation_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        window
+            .show()
+            .map_err(|e| format!("Failed to show conversation window: {}", e))?;
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus conversation window: {}", e));
+    }
+    WebviewWindowBuilder::new(
+        &app,
+        label,
+        tauri::WebviewUrl::App(format!("/conversation/{}", conversation_id).into()),
+    )
+    .title("Pluely - Conversation")
+    .inner_size(520.0, 640.0)
+    .min_inner_size(360.0, 400.0)
+    .decorations(true)
+    .content_protected(content_protected())
+    .build()
+    .map_err(|e| format!("Failed to open conversation window: {}", e))?;
+    Ok(())
+}
+
 pub fn create_dashboard_window<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<WebviewWindow<R>, tauri::Error> {
@@ -188,7 +542,7 @@ pub fn create_dashboard_window<R: Runtime>(
         .min_inner_size(800.0, 600.0)
         .hidden_title(true)
         .title_bar_style(tauri::TitleBarStyle::Overlay)
-        .content_protected(true)
+        .content_protected(content_protected())
         .visible(true)
         .traffic_light_position(LogicalPosition::new(14.0, 18.0));
 
@@ -199,7 +553,7 @@ pub fn create_dashboard_window<R: Runtime>(
         .decorations(true)
         .inner_size(800.0, 600.0)
         .min_inner_size(800.0, 600.0)
-        .content_protected(true)
+        .content_protected(content_protected())
         .visible(true);
 
     base_builder.build()
@@ -0,0 +1,298 @@
+// Pluely capture wipe. `purge_all_captures` zeroes the screens held for a
+// selection, overwrites and deletes the scratch files OCR, export and page
+// rendering leave in the temp directory, and clears the clipboard if it still
+// holds an image Pluely produced. It runs when the app exits and, if the user
+// opts in, whenever the OS session locks. The capture library is left alone;
+// its retention policy covers it. SSDs may keep old copies of overwritten
+// blocks, so deleted files are only as gone as the disk allows.
+use serde::Serialize;
+use specta::Type;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+// How often the session lock is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Scratch files and directories Pluely creates in the temp directory
+const TEMP_PREFIXES: [&str; 3] = ["pluely-ocr-", "pluely-export-", "pluely-browser-"];
+// Recent capture images that may have been copied to the clipboard
+const MAX_TRACKED: usize = 20;
+
+// Process-wide because captures are encoded without an app handle
+static TRACKED: Mutex<VecDeque<Vec<u8>>> = Mutex::new(VecDeque::new());
+
+#[derive(Default)]
+pub struct WipeState {
+    // Stop flag of the running lock watcher
+    watcher: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeTrigger {
+    Manual,
+    // The OS session locked
+    Lock,
+    Exit,
+}
+
+// Returned by `purge_all_captures` and emitted as `captures-purged`
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub trigger: PurgeTrigger,
+    // Screens held for a selection
+    pub screens: usize,
+    pub temp_files: usize,
+    pub clipboard_cleared: bool,
+}
+
+// Remembers a capture image so a purge can recognize it on the clipboard
+pub fn track_image(image: &image::RgbaImage) {
+    let print = crate::clipboard::image_fingerprint(
+        image.width() as usize,
+        image.height() as usize,
+        image.as_raw(),
+    );
+    if let Ok(mut tracked) = TRACKED.lock() {
+        if tracked.len() == MAX_TRACKED {
+            tracked.pop_front();
+        }
+        tracked.push_back(print);
+    }
+}
+
+// Clears the clipboard if it holds one of the tracked capture images
+fn clear_clipboard() -> Result<bool, String> {
+    let tracked = std::mem::take(
+        &mut *TRACKED
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?,
+    );
+    if tracked.is_empty() {
+        return Ok(false);
+    }
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    let Ok(image) = clipboard.get_image() else {
+        return Ok(false);
+    };
+    let print = crate::clipboard::image_fingerprint(image.width, image.height, &image.bytes);
+    if !tracked.contains(&print) {
+        return Ok(false);
+    }
+    clipboard
+        .clear()
+        .map_err(|e| format!("Failed to clear clipboard: {}", e))?;
+    Ok(true)
+}
+
+// Overwrites a file with zeros before deleting it
+fn shred_file(path: &Path) -> std::io::Result<()> {
+    let mut left = std::fs::metadata(path)?.len();
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = [0u8; 64 * 1024];
+    while left > 0 {
+        let chunk = left.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..chunk])?;
+        left -= chunk as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)
+}
+
+// Shreds `path`, and everything in it if it is a directory; returns how many
+// files were removed. Links are removed without following them.
+fn shred(path: &Path) -> usize {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        let removed = std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| shred(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0);
+        if let Err(e) = std::fs::remove_dir(path) {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+        return removed;
+    }
+    let result = if metadata.is_file() {
+        shred_file(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => 1,
+        Err(e) => {
+            warn!("Failed to remove {}: {}", path.display(), e);
+            0
+        }
+    }
+}
+
+fn purge_temp_files() -> usize {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            TEMP_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .map(|entry| shred(&entry.path()))
+        .sum()
+}
+
+/// Wipes every temporary copy of a capture and tells the UI to drop its own
+pub fn purge(app: &AppHandle, trigger: PurgeTrigger) -> PurgeReport {
+    let clipboard_cleared = clear_clipboard().unwrap_or_else(|e| {
+        warn!("{}", e);
+        false
+    });
+    let report = PurgeReport {
+        trigger,
+        screens: crate::capture::purge_captures(app),
+        temp_files: purge_temp_files(),
+        clipboard_cleared,
+    };
+    info!(
+        "Purged captures ({:?}): {} screen(s), {} temp file(s), clipboard {}",
+        trigger,
+        report.screens,
+        report.temp_files,
+        if clipboard_cleared { "cleared" } else { "kept" }
+    );
+    if let Err(e) = app.emit(
+        "captures-purged",
+        crate::events::CapturesPurged(report.clone()),
+    ) {
+        warn!("Failed to emit captures-purged event: {}", e);
+    }
+    report
+}
+
+#[cfg(target_os = "linux")]
+fn session_locked() -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output = std::process::Command::new("loginctl")
+        .args(["show-session", &session, "--property=LockedHint", "--value"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+// The console session only carries this key while the screen is locked
+#[cfg(target_os = "macos")]
+fn session_locked() -> Option<bool> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-n", "Root", "-d1"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+// The input desktop can't be switched to while the lock screen owns it
+#[cfg(target_os = "windows")]
+fn session_locked() -> Option<bool> {
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::System::StationsAndDesktops::{
+        CloseDesktop, OpenInputDesktop, SwitchDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+    };
+    unsafe {
+        match OpenInputDesktop(
+            DESKTOP_CONTROL_FLAGS(0),
+            BOOL::from(false),
+            DESKTOP_SWITCHDESKTOP,
+        ) {
+            Ok(desktop) => {
+                let locked = !SwitchDesktop(desktop).as_bool();
+                let _ = CloseDesktop(desktop);
+                Some(locked)
+            }
+            Err(_) => Some(true),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn session_locked() -> Option<bool> {
+    None
+}
+
+fn watch(app: AppHandle, stop: Arc<AtomicBool>) {
+    let mut was_locked = false;
+    let mut reported_unknown = false;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Some(locked) = session_locked() else {
+            if !reported_unknown {
+                warn!("Session lock state is unavailable; captures won't be purged on lock");
+                reported_unknown = true;
+            }
+            continue;
+        };
+        if locked && !was_locked {
+            purge(&app, PurgeTrigger::Lock);
+        }
+        was_locked = locked;
+    }
+}
+
+// Starts or stops the lock watcher
+pub fn set_purge_on_lock(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app.state::<WipeState>();
+    let mut watcher = state
+        .watcher
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+    if watcher.is_some() == enabled {
+        return Ok(());
+    }
+    if let Some(stop) = watcher.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if enabled {
+        let stop = Arc::new(AtomicBool::new(false));
+        let app = app.clone();
+        let flag = stop.clone();
+        std::thread::spawn(move || watch(app, flag));
+        *watcher = Some(stop);
+    }
+    Ok(())
+}
+
+/// Tauri command wiping the screens, temp files and clipboard copies of
+/// captures right away
+#[tauri::command]
+#[specta::specta]
+pub async fn purge_all_captures(app: AppHandle) -> Result<PurgeReport, String> {
+    tauri::async_runtime::spawn_blocking(move || purge(&app, PurgeTrigger::Manual))
+        .await
+        .map_err(|e| format!("Task panicked: {}", e))
+}
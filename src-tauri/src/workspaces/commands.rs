@@ -0,0 +1,148 @@
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+use tauri_specta::Event;
+
+use super::{
+    active_workspace, list_workspaces, save_active, set_active, validate_name, workspace_dirs,
+    workspace_exists, DEFAULT_WORKSPACE,
+};
+use crate::settings::Settings;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceProfile {
+    pub name: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitched {
+    pub name: String,
+    pub settings: Settings,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_profile() -> String {
+    active_workspace()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_workspace_profiles(app: AppHandle) -> Result<Vec<WorkspaceProfile>, String> {
+    let active = active_workspace();
+    Ok(list_workspaces(&app)?
+        .into_iter()
+        .map(|name| WorkspaceProfile {
+            active: name == active,
+            name,
+        })
+        .collect())
+}
+
+// Creates an empty profile, optionally starting from the current settings.
+// API keys are never copied.
+#[tauri::command]
+#[specta::specta]
+pub fn create_workspace_profile(
+    app: AppHandle,
+    name: String,
+    copy_settings: Option<bool>,
+) -> Result<WorkspaceProfile, String> {
+    let name = validate_name(&name)?;
+    if workspace_exists(&app, &name)? {
+        return Err(format!("A profile named {} already exists", name));
+    }
+
+    let (config_dir, data_dir) = workspace_dirs(&app, &name)?;
+    for dir in [&config_dir, &data_dir] {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create profile directory: {}", e))?;
+    }
+    if copy_settings.unwrap_or(false) {
+        let settings = serde_json::to_string_pretty(&crate::settings::current_settings(&app)?)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(config_dir.join(crate::settings::SETTINGS_FILE), settings)
+            .map_err(|e| format!("Failed to write settings: {}", e))?;
+    }
+
+    Ok(WorkspaceProfile {
+        name,
+        active: false,
+    })
+}
+
+// Deletes a profile with its settings, history, captures and keys. The
+// default and the active profile can't be deleted.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_workspace_profile(app: AppHandle, name: String) -> Result<bool, String> {
+    let name = validate_name(&name)?;
+    if name == DEFAULT_WORKSPACE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == active_workspace() {
+        return Err("Switch to another profile before deleting this one".to_string());
+    }
+    if !workspace_exists(&app, &name)? {
+        return Ok(false);
+    }
+
+    let mut key_ids = crate::secrets::known_key_ids();
+    key_ids.push(crate::storage::HISTORY_KEY_ID);
+    key_ids.push(crate::control::TOKEN_KEY_ID);
+    crate::secrets::delete_workspace_keys(&name, &key_ids)?;
+
+    let (config_dir, data_dir) = workspace_dirs(&app, &name)?;
+    for dir in [&config_dir, &data_dir] {
+        if dir.is_dir() {
+            std::fs::remove_dir_all(dir)
+                .map_err(|e| format!("Failed to delete profile directory: {}", e))?;
+        }
+    }
+    Ok(true)
+}
+
+// Makes `name` the active profile: closes the history database, then loads
+// the profile's settings and hands them to every backend module. The frontend
+// reopens its own connection from `get_history_database` on `profile-switched`.
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<Settings, String> {
+    let name = validate_name(&name)?;
+    if !workspace_exists(&app, &name)? {
+        return Err(format!("Profile {} does not exist", name));
+    }
+    if name == active_workspace() {
+        return crate::settings::current_settings(&app);
+    }
+
+    // Nothing may keep writing the previous profile's history
+    crate::storage::reset_history_encryption().await;
+    crate::storage::close_pool(&app).await?;
+
+    let previous = active_workspace();
+    set_active(&name)?;
+    let settings = match crate::settings::reload_settings(&app) {
+        Ok(settings) => settings,
+        Err(e) => {
+            // Back to where we were so the app stays usable
+            set_active(&previous)?;
+            crate::settings::reload_settings(&app)?;
+            return Err(format!("Failed to switch to profile {}: {}", name, e));
+        }
+    };
+    save_active(&app, &name)?;
+
+    app.emit(
+        "profile-switched",
+        ProfileSwitched {
+            name,
+            settings: settings.clone(),
+        },
+    )
+    .map_err(|e| format!("Failed to emit profile switch: {}", e))?;
+    Ok(settings)
+}
@@ -0,0 +1,170 @@
+// Pluely workspace profiles ("work", "personal"). Each has its own settings,
+// history database, capture library, prompt profiles and API keys. The
+// default workspace uses the original locations so nothing moves on upgrade.
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+mod commands;
+
+// Re-export commands for tauri handler
+pub use commands::*;
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+pub const WORKSPACES_DIR: &str = "workspaces";
+// Records the active workspace; shared by all of them
+const ACTIVE_FILE: &str = "workspace.json";
+// The app's `identifier` in tauri.conf.json, which names its config directory
+const APP_IDENTIFIER: &str = "com.srikanthnani.pluely";
+
+// Process-wide because the keychain helpers have no app handle
+static ACTIVE: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ActiveWorkspace {
+    active: String,
+}
+
+pub fn active_workspace() -> String {
+    ACTIVE
+        .lock()
+        .ok()
+        .and_then(|active| active.clone())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+fn set_active(name: &str) -> Result<(), String> {
+    *ACTIVE
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))? = Some(name.to_string());
+    Ok(())
+}
+
+// Lowercase letters, digits, `-` and `_`, so names are safe as directories
+pub fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.len() > 32 {
+        return Err("Profile name must be 1-32 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name {}: use letters, digits, - and _",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+fn base_config_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))
+}
+
+fn base_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+fn scoped(base: PathBuf, workspace: &str) -> PathBuf {
+    if workspace == DEFAULT_WORKSPACE {
+        base
+    } else {
+        base.join(WORKSPACES_DIR).join(workspace)
+    }
+}
+
+// Settings and the history database of the active workspace
+pub fn config_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(scoped(base_config_dir(app)?, &active_workspace()))
+}
+
+// Captures and prompt profiles of the active workspace
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(scoped(base_data_dir(app)?, &active_workspace()))
+}
+
+fn workspace_dirs(app: &AppHandle, name: &str) -> Result<(PathBuf, PathBuf), String> {
+    Ok((
+        scoped(base_config_dir(app)?, name),
+        scoped(base_data_dir(app)?, name),
+    ))
+}
+
+pub fn workspace_exists(app: &AppHandle, name: &str) -> Result<bool, String> {
+    Ok(name == DEFAULT_WORKSPACE || workspace_dirs(app, name)?.0.is_dir())
+}
+
+// Default first, then the others by name
+pub fn list_workspaces(app: &AppHandle) -> Result<Vec<String>, String> {
+    let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+    let dir = base_config_dir(app)?.join(WORKSPACES_DIR);
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        let mut others: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str().map(String::from))
+            .filter(|name| validate_name(name).as_deref() == Ok(name.as_str()))
+            .collect();
+        others.sort();
+        names.extend(others);
+    }
+    Ok(names)
+}
+
+fn save_active(app: &AppHandle, name: &str) -> Result<(), String> {
+    let dir = base_config_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&ActiveWorkspace {
+        active: name.to_string(),
+    })
+    .map_err(|e| format!("Failed to serialize active profile: {}", e))?;
+    std::fs::write(dir.join(ACTIVE_FILE), json)
+        .map_err(|e| format!("Failed to save active profile: {}", e))
+}
+
+fn saved_active(base_config_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(base_config_dir.join(ACTIVE_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<ActiveWorkspace>(&json).ok())
+        .map(|saved| saved.active)
+}
+
+// Restores the workspace that was active when the app last ran. Must run
+// before anything opens the database or reads settings.
+pub fn init(app: &AppHandle) {
+    let saved = base_config_dir(app).ok().and_then(|dir| saved_active(&dir));
+    let Some(name) = saved else {
+        return;
+    };
+    match workspace_exists(app, &name) {
+        Ok(true) => {
+            if let Err(e) = set_active(&name) {
+                warn!("Failed to restore profile {}: {}", name, e);
+            }
+        }
+        _ => warn!("Profile {} no longer exists, using the default", name),
+    }
+}
+
+// The CLI and MCP server run without an app handle: this finds the config
+// directory Tauri would use, restores the active workspace from it and
+// returns that workspace's directory
+pub fn init_headless() -> Option<PathBuf> {
+    let base = dirs::config_dir()?.join(APP_IDENTIFIER);
+    let name = saved_active(&base)
+        .filter(|name| validate_name(name).as_deref() == Ok(name.as_str()))
+        .filter(|name| name == DEFAULT_WORKSPACE || scoped(base.clone(), name).is_dir())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string());
+    if let Err(e) = set_active(&name) {
+        warn!("Failed to restore profile {}: {}", name, e);
+    }
+    Some(scoped(base, &name))
+}
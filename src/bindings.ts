@@ -0,0 +1,2543 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async getAppVersion() : Promise<string> {
+    return await TAURI_INVOKE("get_app_version");
+},
+async setWindowHeight(height: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_height", { height }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openDashboard() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_dashboard") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async toggleDashboard() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_dashboard") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async moveWindow(direction: string, step: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_window", { direction, step }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to hide a window from screen shares (stealth mode). Without
+ * a label it applies to every window, including overlays opened later.
+ */
+async setContentProtection(window: string | null, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_content_protection", { window, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setWindowOpacity(value: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_opacity", { value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setWindowEffect(effect: WindowEffect) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_effect", { effect }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to keep the main window out of the taskbar
+ */
+async setSkipTaskbar(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_skip_taskbar", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to keep Pluely out of Alt+Tab (Windows, Linux) or the dock
+ * and Cmd+Tab (macOS)
+ */
+async setHideFromSwitcher(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hide_from_switcher", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command opening a history conversation in a chat window of its own,
+ * or focusing the one already showing it
+ */
+async openConversationWindow(conversationId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_conversation_window", { conversationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to switch the main window between the command bar, compact
+ * and expanded layouts
+ */
+async setWindowMode(mode: WindowMode) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_mode", { mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getWindowMode() : Promise<WindowMode> {
+    return await TAURI_INVOKE("get_window_mode");
+},
+/**
+ * Tauri command pinning a capture (by its file name in the library) to the
+ * screen in an always-on-top window
+ */
+async pinCapture(captureId: string) : Promise<Result<Pin, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pin_capture", { captureId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listPins() : Promise<Result<Pin[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_pins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command closing a pin's window and forgetting it
+ */
+async closePin(pinId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_pin", { pinId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning a pin's image as base64, for its window to show
+ */
+async getPinImage(pinId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pin_image", { pinId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async captureToBase64() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture_to_base64") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startScreenCapture(constraint: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_screen_capture", { constraint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async captureSelectedArea(coords: SelectionCoords, monitorIndex: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture_selected_area", { coords, monitorIndex }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async nudgeSelection(dx: number, dy: number) : Promise<Result<Selection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nudge_selection", { dx, dy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resizeSelection(dw: number, dh: number) : Promise<Result<Selection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resize_selection", { dw, dh }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async confirmSelection() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("confirm_selection") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Measures a selection on a monitor, for using the overlay as a screen ruler
+ */
+async measureSelection(coords: SelectionCoords, monitor: number) : Promise<Result<SelectionMeasurement, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("measure_selection", { coords, monitor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Candidate rectangles on the frozen frame of `monitor_index`, smallest
+ * first so the first one holding the cursor is the tightest fit. `ocr` adds
+ * text lines and blocks, which needs `tesseract` installed; `language` is the
+ * OCR language when it isn't English.
+ */
+async detectRegions(monitorIndex: number, ocr: boolean | null, language: string | null) : Promise<Result<DetectedRegion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("detect_regions", { monitorIndex, ocr, language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the table in `coords` on `monitor` (the whole monitor when None) as
+ * rows of cells and as CSV, OCR'd in `language` when given. Needs
+ * `tesseract` installed.
+ */
+async extractTable(coords: SelectionCoords | null, monitor: number, language: string | null) : Promise<Result<ExtractedTable, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("extract_table", { coords, monitor, language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The code blocks in `coords` on `monitor` (the whole monitor when None) as
+ * text, top to bottom. `language` is the OCR language of comments and
+ * strings when they aren't English. Needs `tesseract` installed.
+ */
+async extractCode(coords: SelectionCoords | null, monitor: number, language: string | null) : Promise<Result<CodeBlock[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("extract_code", { coords, monitor, language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * OCRs `coords` on `monitor` (the whole monitor when None) and translates it
+ * into `target_lang` with the configured model, line by line with each
+ * line's position. `source_lang` is the OCR language of the text when it
+ * isn't English. Needs `tesseract` installed.
+ */
+async translateCapture(coords: SelectionCoords | null, monitor: number, targetLang: string, sourceLang: string | null) : Promise<Result<TranslatedCapture, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("translate_capture", { coords, monitor, targetLang, sourceLang }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listCameras() : Promise<Result<CameraDevice[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_cameras") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A PNG frame from camera `device_id` (the first camera when None) as base64,
+ * the same format `capture_to_base64` returns
+ */
+async captureCameraFrame(deviceId: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture_camera_frame", { deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOverlayConfig() : Promise<Result<OverlaySettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_overlay_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOverlayImage(monitorIndex: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_overlay_image", { monitorIndex }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async closeOverlayWindow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_overlay_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cek apakah tools untuk mendapatkan posisi mouse tersedia
+ */
+async checkMultiMonitorSupport() : Promise<MultiMonitorSupport> {
+    return await TAURI_INVOKE("check_multi_monitor_support");
+},
+/**
+ * Tauri command starting watch mode on a region of a monitor, in image pixels.
+ * Replaces any watch already running.
+ */
+async startWatch(coords: SelectionCoords, monitor: number, intervalMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_watch", { coords, monitor, intervalMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command stopping watch mode; does nothing when no watch is running
+ */
+async stopWatch() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_watch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command starting the change trigger on a monitor, or a region of it
+ * in image pixels. `threshold` is the share of the screen, 0.0 to 1.0, that
+ * has to change before `screen-changed` is emitted. Replaces any trigger
+ * already running.
+ */
+async startChangeTrigger(monitor: number, coords: SelectionCoords | null, threshold: number, intervalMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_change_trigger", { monitor, coords, threshold, intervalMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command stopping the change trigger; does nothing when none is running
+ */
+async stopChangeTrigger() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_change_trigger") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the clipboard image as a base64 PNG, the same
+ * shape as `capture_to_base64`
+ */
+async readClipboardImage() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_clipboard_image") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the clipboard text, for "ask about what you
+ * just copied" after a `clipboard-changed` event
+ */
+async readClipboardText() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_clipboard_text") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check whether the clipboard watcher is running
+ */
+async isClipboardWatcherRunning() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_clipboard_watcher_running") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveCapture(dataBase64: string, kind: string | null, monitor: string | null, note: string | null) : Promise<Result<CaptureFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_capture", { dataBase64, kind, monitor, note }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listCaptures() : Promise<Result<CaptureFile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_captures") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteCapture(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_capture", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCaptureRetention() : Promise<Result<RetentionPolicy, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_capture_retention") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateCaptureRetention(policy: RetentionPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_capture_retention", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async runCaptureCleanup() : Promise<Result<CleanupReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_capture_cleanup") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getStorageUsage() : Promise<Result<StorageUsage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_storage_usage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async diffCaptures(idA: string, idB: string) : Promise<Result<CaptureDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_captures", { idA, idB }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readCaptureMetadata(path: string) : Promise<Result<CaptureMetadata, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_capture_metadata", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async watermarkImage(dataBase64: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("watermark_image", { dataBase64 }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check if shortcuts are registered
+ */
+async checkShortcutsRegistered() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_shortcuts_registered") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to get all registered shortcuts
+ */
+async getRegisteredShortcuts() : Promise<Result<Partial<{ [key in string]: string }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_registered_shortcuts") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to update shortcuts dynamically
+ */
+async updateShortcuts(config: ShortcutsConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_shortcuts", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to validate shortcut key
+ */
+async validateShortcutKey(key: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_shortcut_key", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to pause or resume global shortcuts
+ */
+async setHotkeysPaused(paused: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hotkeys_paused", { paused }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to check if global shortcuts are paused
+ */
+async getHotkeysPaused() : Promise<boolean> {
+    return await TAURI_INVOKE("get_hotkeys_paused");
+},
+async setLicenseStatus(hasLicense: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_license_status", { hasLicense }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set app icon visibility in dock/taskbar
+ */
+async setAppIconVisibility(visible: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_app_icon_visibility", { visible }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to set always on top state
+ */
+async setAlwaysOnTop(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_always_on_top", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to let clicks pass through the main window. The window can't
+ * be clicked while enabled, so the `toggle_click_through` hotkey turns it off.
+ */
+async setClickThrough(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_click_through", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getClickThrough() : Promise<boolean> {
+    return await TAURI_INVOKE("get_click_through");
+},
+/**
+ * Tauri command to exit the application
+ */
+async exitApp() : Promise<void> {
+    await TAURI_INVOKE("exit_app");
+},
+async getAutostart() : Promise<Result<AutostartStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_autostart") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setAutostart(enabled: boolean, startHidden: boolean | null) : Promise<Result<AutostartStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_autostart", { enabled, startHidden }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async activateLicenseApi(licenseKey: string) : Promise<Result<ActivationResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("activate_license_api", { licenseKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deactivateLicenseApi() : Promise<Result<ActivationResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("deactivate_license_api") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async validateLicenseApi() : Promise<Result<ValidateResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_license_api") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async maskLicenseKeyCmd(licenseKey: string) : Promise<string> {
+    return await TAURI_INVOKE("mask_license_key_cmd", { licenseKey });
+},
+async getCheckoutUrl() : Promise<Result<CheckoutResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_checkout_url") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secureStorageSave(items: StorageItem[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secure_storage_save", { items }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secureStorageGet() : Promise<Result<StorageResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secure_storage_get") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async secureStorageRemove(keys: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("secure_storage_remove", { keys }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async transcribeAudio(audioBase64: string) : Promise<Result<AudioResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("transcribe_audio", { audioBase64 }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async chatStreamResponse(userMessage: string, systemPrompt: string | null, imageBase64: JsonValue | null, history: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("chat_stream_response", { userMessage, systemPrompt, imageBase64, history }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async fetchModels() : Promise<Result<Model[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createSystemPrompt(userPrompt: string) : Promise<Result<SystemPromptResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_system_prompt", { userPrompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkLicenseStatus() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_license_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getActivity() : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_activity") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendChatRequest(requestId: string, provider: ProviderRequest, payload: JsonValue, onEvent: TAURI_CHANNEL<ChatStreamEvent>) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_chat_request", { requestId, provider, payload, onEvent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelRequest(requestId: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_request", { requestId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLlmConfig() : Promise<Result<LlmConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_llm_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateLlmConfig(config: LlmConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_llm_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listLlmProviders() : Promise<Result<ProviderInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_llm_providers") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async llmChat(requestId: string, messages: ChatMessage[], stream: boolean | null, profileId: string | null, variables: Partial<{ [key in string]: string }> | null, tools: string[] | null, onEvent: TAURI_CHANNEL<ChatStreamEvent>) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("llm_chat", { requestId, messages, stream, profileId, variables, tools, onEvent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async countTokens(model: string, messages: ChatMessage[]) : Promise<Result<TokenCount, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("count_tokens", { model, messages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listLocalModels() : Promise<Result<LocalModel[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_local_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkLocalLlmHealth() : Promise<Result<LocalServerStatus[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_local_llm_health") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async retryQueuedRequests() : Promise<void> {
+    await TAURI_INVOKE("retry_queued_requests");
+},
+async previewPiiScrub(messages: ChatMessage[]) : Promise<Result<ScrubPreview, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_pii_scrub", { messages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async approvePiiScrub(approvalId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("approve_pii_scrub", { approvalId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryDatabase() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_database") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listConversations(limit: number | null, offset: number | null) : Promise<Result<ConversationSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_conversations", { limit, offset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async loadConversation(id: string) : Promise<Result<Conversation | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_conversation", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createConversation(title: string, model: string | null, profileId: string | null) : Promise<Result<ConversationSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_conversation", { title, model, profileId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveMessage(conversationId: string, message: StoredMessage) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_message", { conversationId, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportConversation(id: string, format: ExportFormat, path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_conversation", { id, format, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportHistory(filter: HistoryFilter | null, path: string) : Promise<Result<HistoryArchiveReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history", { filter, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importHistory(path: string) : Promise<Result<HistoryArchiveReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_history", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async renameConversation(id: string, title: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_conversation", { id, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteConversation(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_conversation", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readCapture(path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_capture", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchHistory(query: string, filters: SearchFilters | null) : Promise<Result<SearchHit[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_history", { query, filters }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUsageSummary(period: UsagePeriod | null) : Promise<Result<UsageSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_summary", { period }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearResponseCache() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_response_cache") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listQueuedRequests() : Promise<Result<QueuedRequest[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_queued_requests") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelQueuedRequest(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_queued_request", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getEmbeddingsConfig() : Promise<Result<EmbeddingsConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_embeddings_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateEmbeddingsConfig(config: EmbeddingsConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_embeddings_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async indexHistory(limit: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("index_history", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async semanticSearch(query: string, k: number | null) : Promise<Result<SemanticHit[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("semantic_search", { query, k }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMcpServerCommand() : Promise<Result<McpServerCommand, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_server_command") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getProxyConfig() : Promise<Result<ProxyConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_proxy_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateProxyConfig(config: ProxyConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_proxy_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testConnection(provider: LlmProviderKind) : Promise<Result<ConnectionTest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_connection", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command for events the frontend finishes, e.g. a streamed answer
+ */
+async sendNotification(category: NotificationCategory, title: string, body: string) : Promise<void> {
+    await TAURI_INVOKE("send_notification", { category, title, body });
+},
+/**
+ * Tauri command to check for an update, notifying when one is available
+ */
+async checkForUpdate() : Promise<Result<AvailableUpdate | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command to download and install the update the last check found.
+ * The app has to be relaunched afterwards, except on Windows.
+ */
+async installUpdate(onEvent: TAURI_CHANNEL<UpdateProgress>) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_update", { onEvent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listPromptProfiles() : Promise<Result<PromptProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_prompt_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async savePromptProfile(profile: PromptProfile) : Promise<Result<PromptProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_prompt_profile", { profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deletePromptProfile(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async applyPromptProfile(id: string, values: Partial<{ [key in string]: string }> | null) : Promise<Result<AppliedProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_prompt_profile", { id, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setConversationProfile(conversationId: string, profileId: string | null) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_conversation_profile", { conversationId, profileId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getRealtimeConfig() : Promise<Result<RealtimeConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_realtime_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateRealtimeConfig(config: RealtimeConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_realtime_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startRealtimeSession() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_realtime_session") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopRealtimeSession() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_realtime_session") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async interruptRealtimeResponse() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("interrupt_realtime_response") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setApiKey(provider: string, key: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_api_key", { provider, key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasApiKey(provider: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_api_key", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteApiKey(provider: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_api_key", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async migrateApiKeys(keys: Partial<{ [key in string]: string }>) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("migrate_api_keys", { keys }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSettings() : Promise<Result<Settings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSetting(key: string) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_setting", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSetting(key: string, value: JsonValue) : Promise<Result<Settings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_setting", { key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resetSettings(section: string | null) : Promise<Result<Settings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_settings", { section }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importLocalStorage(entries: Partial<{ [key in string]: string }>) : Promise<Result<Settings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_local_storage", { entries }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportSettings(path: string, passphrase: string, includeApiKeys: boolean | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_settings", { path, passphrase, includeApiKeys }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importSettings(path: string, passphrase: string) : Promise<Result<ImportedBackup, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_settings", { path, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The managed policy: whether one is installed, what it locks and which
+ * providers it allows
+ */
+async getManagedPolicy() : Promise<PolicyStatus> {
+    return await TAURI_INVOKE("get_managed_policy");
+},
+async getActiveProfile() : Promise<string> {
+    return await TAURI_INVOKE("get_active_profile");
+},
+async listWorkspaceProfiles() : Promise<Result<WorkspaceProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_workspace_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createWorkspaceProfile(name: string, copySettings: boolean | null) : Promise<Result<WorkspaceProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_workspace_profile", { name, copySettings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteWorkspaceProfile(name: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_workspace_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async switchProfile(name: string) : Promise<Result<Settings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startSystemAudioCapture(vadConfig: VadConfig | null, deviceId: string | null, captureMode: DualCaptureMode | null, micDeviceId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_system_audio_capture", { vadConfig, deviceId, captureMode, micDeviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopSystemAudioCapture() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_system_audio_capture") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setAudioInput(deviceId: string | null, source: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_audio_input", { deviceId, source }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Manual stop for continuous recording
+ */
+async manualStopContinuous() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("manual_stop_continuous") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkSystemAudioAccess() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_system_audio_access") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async requestSystemAudioAccess() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_system_audio_access") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getVadConfig() : Promise<Result<VadConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_vad_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateVadConfig(config: VadConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_vad_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCaptureStatus() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_capture_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAudioSampleRate() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audio_sample_rate") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listAudioDevices() : Promise<Result<AudioDeviceInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_audio_devices") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startAudioRecording(path: string, format: RecordingFormat, deviceId: string | null, captureMode: DualCaptureMode | null, micDeviceId: string | null, bitrate: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_audio_recording", { path, format, deviceId, captureMode, micDeviceId, bitrate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopAudioRecording() : Promise<Result<RecordingSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_audio_recording") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getRecordingStatus() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recording_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getWhisperConfig() : Promise<Result<WhisperConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_whisper_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateWhisperConfig(config: WhisperConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_whisper_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async isLocalWhisperAvailable() : Promise<boolean> {
+    return await TAURI_INVOKE("is_local_whisper_available");
+},
+async transcribeLocal(audioBase64: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("transcribe_local", { audioBase64 }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startWhisperStream(deviceId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_whisper_stream", { deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopWhisperStream() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_whisper_stream") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listOcrLanguages() : Promise<Result<OcrLanguage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_ocr_languages") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async downloadOcrLanguage(code: string) : Promise<Result<OcrLanguage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_ocr_language", { code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeOcrLanguage(code: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_ocr_language", { code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSttConfig() : Promise<Result<SttConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_stt_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateSttConfig(config: SttConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_stt_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sttTranscribe(audioBase64: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stt_transcribe", { audioBase64 }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendCustomSttRequest(url: string, method: string, headers: Partial<{ [key in string]: string }>, body: CustomSttBody, audioBase64: string, mimeType: string) : Promise<Result<CustomSttResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_custom_stt_request", { url, method, headers, body, audioBase64, mimeType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startSttStream(deviceId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_stt_stream", { deviceId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopSttStream() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_stt_stream") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTtsConfig() : Promise<Result<TtsConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tts_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateTtsConfig(config: TtsConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_tts_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async speak(text: string, voice: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("speak", { text, voice }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopSpeaking() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_speaking") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async ingestFiles(paths: string[]) : Promise<Result<IngestReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ingest_files", { paths }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listAttachments(messageId: string | null) : Promise<Result<Attachment[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_attachments", { messageId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readAttachment(id: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_attachment", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async linkAttachments(messageId: string, ids: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("link_attachments", { messageId, ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteAttachment(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_attachment", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Page count, title and whether the PDF at `path` has text, for picking
+ * which pages to attach
+ */
+async inspectDocument(path: string) : Promise<Result<DocumentInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("inspect_document", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Renders `pages` (1-based; the first pages when None) of the PDF at `path`,
+ * registers them as an attachment and returns it with the pages' text
+ */
+async ingestDocument(path: string, pages: number[] | null) : Promise<Result<IngestedDocument, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ingest_document", { path, pages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetches `url` and returns its readable text and details, plus a
+ * screenshot when `screenshot` is set, as context for a pasted link
+ */
+async fetchUrlContext(url: string, screenshot: boolean | null) : Promise<Result<UrlContext, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_url_context", { url, screenshot }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the question from the last `pluely://ask` link, once
+ */
+async takeDeepLinkAsk() : Promise<Result<DeepLinkAsk | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("take_deep_link_ask") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getControlApiStatus() : Promise<Result<ControlApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_control_api_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getControlApiToken() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_control_api_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async regenerateControlApiToken() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("regenerate_control_api_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testWebhook(id: string) : Promise<Result<WebhookTest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_webhook", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testHook(id: string, event: HookEvent | null) : Promise<Result<HookRun, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_hook", { id, event }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHookRuns() : Promise<Result<HookRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_hook_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearHookRuns() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_hook_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Installed plugins with their manifests, grants and whether they're running
+ */
+async listPlugins() : Promise<Result<PluginInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_plugins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Folder plugins are installed into, one subfolder each
+ */
+async getPluginsDir() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_plugins_dir") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Calls `command` of an enabled plugin, starting it if needed, and returns
+ * what the plugin answered
+ */
+async callPluginCommand(pluginId: string, command: string, arguments: JsonValue | null) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("call_plugin_command", { pluginId, command, arguments }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops a running plugin; it starts again on next use. Returns whether it
+ * was running.
+ */
+async stopPlugin(pluginId: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_plugin", { pluginId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends a saved answer, the question before it and their captures to the
+ * user's notes, returning where the note went
+ */
+async sendAnswerToNotes(destination: NoteDestination, conversationId: string, messageId: string) : Promise<Result<SentNote, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_answer_to_notes", { destination, conversationId, messageId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Uploads saved capture `capture_id` to `target` and returns a link to it,
+ * which is also copied to the clipboard unless that is turned off
+ */
+async shareCapture(captureId: string, target: ShareTarget) : Promise<Result<SharedCapture, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_capture", { captureId, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the latest log entries. `level` is the least
+ * severe level included ("error" to "trace", default all).
+ */
+async getRecentLogs(level: string | null, lines: number | null) : Promise<Result<LogEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_logs", { level, lines }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command building the diagnostics bundle (system info, monitor layout
+ * and recent logs), copying it to the clipboard and returning it
+ */
+async copyDiagnostics() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_diagnostics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The most recent `limit` entries (500 by default), newest first, optionally
+ * only those of `kind`
+ */
+async getAuditLog(limit: number | null, kind: AuditKind | null) : Promise<Result<AuditEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audit_log", { limit, kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command wiping the screens, temp files and clipboard copies of
+ * captures right away
+ */
+async purgeAllCaptures() : Promise<Result<PurgeReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purge_all_captures") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning exactly what the next report would send, whether
+ * or not sharing is on
+ */
+async previewTelemetryPayload() : Promise<Result<TelemetryPayload, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_telemetry_payload") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command discarding the counts collected so far
+ */
+async clearTelemetry() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_telemetry") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command telling the webview whether usage may leave the machine:
+ * the user agreed, local-only mode is off and no policy forbids it
+ */
+async usageSharingAllowed() : Promise<boolean> {
+    return await TAURI_INVOKE("usage_sharing_allowed");
+},
+/**
+ * Tauri command listing saved crash reports, newest first
+ */
+async listCrashReports() : Promise<Result<CrashReport[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteCrashReport(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_crash_report", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command uploading pending reports; fails unless uploads are on
+ */
+async uploadCrashReports() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("upload_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the app the user was last in, if tracked
+ */
+async getFocusedApp() : Promise<Result<FocusedApp | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_focused_app") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning whether the user is idle and for how long
+ */
+async getIdleStatus() : Promise<Result<IdleStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_idle_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the current power source, battery level and
+ * low-power mode
+ */
+async getPowerStatus() : Promise<Result<PowerStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_power_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the meeting in progress and the Do Not Disturb
+ * state
+ */
+async getMeetingStatus() : Promise<Result<MeetingStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_meeting_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tauri command returning the fullscreen app in front, if any
+ */
+async getFullscreenApp() : Promise<Result<FullscreenApp | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_fullscreen_app") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+attachmentsAdded: AttachmentsAdded,
+attachmentsIngesting: AttachmentsIngesting,
+audioDeviceChanged: AudioDeviceChanged,
+audioEncodingError: AudioEncodingError,
+audioLevel: AudioLevel,
+captureClosed: CaptureClosed,
+captureStarted: CaptureStarted,
+captureStopped: CaptureStopped,
+capturedSelection: CapturedSelection,
+capturesCleaned: CapturesCleaned,
+capturesPurged: CapturesPurged,
+chatStreamChunk: ChatStreamChunk,
+chatStreamComplete: ChatStreamComplete,
+clickThroughChanged: ClickThroughChanged,
+clipboardChanged: ClipboardChanged,
+continuousRecordingStart: ContinuousRecordingStart,
+continuousRecordingStopped: ContinuousRecordingStopped,
+controlApiAsk: ControlApiAsk,
+customShortcutTriggered: CustomShortcutTriggered,
+deepLinkAsk: DeepLinkAsk,
+doNotDisturbChanged: DoNotDisturbChanged,
+focusTextInput: FocusTextInput,
+fullscreenEntered: FullscreenEntered,
+fullscreenExited: FullscreenExited,
+historyEncryption: HistoryEncryption,
+hookFinished: HookFinished,
+hotkeysPaused: HotkeysPaused,
+llmAttempt: LlmAttempt,
+llmCacheHit: LlmCacheHit,
+llmCancelled: LlmCancelled,
+llmContextTrimmed: LlmContextTrimmed,
+llmImagesProcessed: LlmImagesProcessed,
+llmScrubReview: LlmScrubReview,
+llmTextScrubbed: LlmTextScrubbed,
+llmToolCall: LlmToolCall,
+manualStopContinuous: ManualStopContinuous,
+meetingEnded: MeetingEnded,
+meetingStarted: MeetingStarted,
+ocrLanguageProgress: OcrLanguageProgress,
+powerStatusChanged: PowerStatusChanged,
+profileSwitched: ProfileSwitched,
+queueStatus: QueueStatus,
+queuedRequestFinished: QueuedRequestFinished,
+realtimeError: RealtimeError,
+realtimeStatus: RealtimeStatusEvent,
+realtimeTranscript: RealtimeTranscript,
+recordingFinished: RecordingFinished,
+recordingProgress: RecordingProgress,
+recordingStarted: RecordingStarted,
+recordingStatus: RecordingStatus,
+screenChanged: ScreenChanged,
+selectionChanged: SelectionChanged,
+settingsChanged: SettingsChanged,
+shortcutRegistrationError: ShortcutRegistrationError,
+speechDetected: SpeechDetected,
+speechDiscarded: SpeechDiscarded,
+speechEnd: SpeechEnd,
+speechStart: SpeechStart,
+startAudioRecording: StartAudioRecording,
+sttError: SttError,
+sttFinal: SttFinal,
+sttPartial: SttPartial,
+toggleSystemAudio: ToggleSystemAudio,
+toggleWindowVisibility: ToggleWindowVisibility,
+triggerScreenshot: TriggerScreenshot,
+ttsFinished: TtsFinished,
+ttsProgress: TtsProgress,
+userActive: UserActive,
+userIdle: UserIdle,
+watchFrame: WatchFrame,
+webhookFailed: WebhookFailed,
+whisperFinal: WhisperFinal,
+whisperPartial: WhisperPartial,
+windowModeChanged: WindowModeChanged
+}>({
+attachmentsAdded: "attachments-added",
+attachmentsIngesting: "attachments-ingesting",
+audioDeviceChanged: "audio-device-changed",
+audioEncodingError: "audio-encoding-error",
+audioLevel: "audio-level",
+captureClosed: "capture-closed",
+captureStarted: "capture-started",
+captureStopped: "capture-stopped",
+capturedSelection: "captured-selection",
+capturesCleaned: "captures-cleaned",
+capturesPurged: "captures-purged",
+chatStreamChunk: "chat_stream_chunk",
+chatStreamComplete: "chat_stream_complete",
+clickThroughChanged: "click-through-changed",
+clipboardChanged: "clipboard-changed",
+continuousRecordingStart: "continuous-recording-start",
+continuousRecordingStopped: "continuous-recording-stopped",
+controlApiAsk: "control-api-ask",
+customShortcutTriggered: "custom-shortcut-triggered",
+deepLinkAsk: "deep-link-ask",
+doNotDisturbChanged: "do-not-disturb-changed",
+focusTextInput: "focus-text-input",
+fullscreenEntered: "fullscreen-entered",
+fullscreenExited: "fullscreen-exited",
+historyEncryption: "history-encryption",
+hookFinished: "hook-finished",
+hotkeysPaused: "hotkeys-paused",
+llmAttempt: "llm-attempt",
+llmCacheHit: "llm-cache-hit",
+llmCancelled: "llm-cancelled",
+llmContextTrimmed: "llm-context-trimmed",
+llmImagesProcessed: "llm-images-processed",
+llmScrubReview: "llm-scrub-review",
+llmTextScrubbed: "llm-text-scrubbed",
+llmToolCall: "llm-tool-call",
+manualStopContinuous: "manual-stop-continuous",
+meetingEnded: "meeting-ended",
+meetingStarted: "meeting-started",
+ocrLanguageProgress: "ocr-language-progress",
+powerStatusChanged: "power-status-changed",
+profileSwitched: "profile-switched",
+queueStatus: "queue-status",
+queuedRequestFinished: "queued-request-finished",
+realtimeError: "realtime-error",
+realtimeStatus: "realtime-status",
+realtimeTranscript: "realtime-transcript",
+recordingFinished: "recording-finished",
+recordingProgress: "recording-progress",
+recordingStarted: "recording-started",
+recordingStatus: "recording-status",
+screenChanged: "screen-changed",
+selectionChanged: "selection-changed",
+settingsChanged: "settings-changed",
+shortcutRegistrationError: "shortcut-registration-error",
+speechDetected: "speech-detected",
+speechDiscarded: "speech-discarded",
+speechEnd: "speech-end",
+speechStart: "speech-start",
+startAudioRecording: "start-audio-recording",
+sttError: "stt-error",
+sttFinal: "stt-final",
+sttPartial: "stt-partial",
+toggleSystemAudio: "toggle-system-audio",
+toggleWindowVisibility: "toggle-window-visibility",
+triggerScreenshot: "trigger-screenshot",
+ttsFinished: "tts-finished",
+ttsProgress: "tts-progress",
+userActive: "user-active",
+userIdle: "user-idle",
+watchFrame: "watch-frame",
+webhookFailed: "webhook-failed",
+whisperFinal: "whisper-final",
+whisperPartial: "whisper-partial",
+windowModeChanged: "window-mode-changed"
+})
+
+/** user-defined constants **/
+
+export const EVENT_SCHEMA_VERSION = 2 as const;
+
+/** user-defined types **/
+
+export type ActivationResponse = { activated: boolean; error: string | null; license_key: string | null; instance: InstanceInfo | null }
+export type AppearanceSettings = { theme: Theme; transparency: number; windowOpacity: number; windowEffect: WindowEffect }
+export type AppliedProfile = { id: string; name: string; systemPrompt: string; provider: LlmProviderKind | null; model: string | null; temperature: number | null }
+export type Attachment = { id: string; messageId: string | null; name: string; kind: AttachmentKind; mimeType: string; bytes: number; pageCount: number; thumbnail: string; createdAt: number }
+export type AttachmentKind = "image" | "pdf"
+/**
+ * `attachments-added`
+ */
+export type AttachmentsAdded = IngestReport
+/**
+ * `attachments-ingesting`: how many files are being read
+ */
+export type AttachmentsIngesting = number
+export type AttemptStatus = "throttled" | "started" | "retrying" | "failed" | "succeeded"
+export type AudioDeviceChanged = { source: string; device_id: string | null; reason: string }
+export type AudioDeviceInfo = { device_id: string; label: string; kind: string }
+/**
+ * `audio-encoding-error`
+ */
+export type AudioEncodingError = string
+export type AudioLevel = { channel: string; rms: number; peak: number; rms_db: number }
+export type AudioResponse = { success: boolean; transcription: string | null; error: string | null }
+export type AudioSettings = { inputDevice: string | null; outputDevice: string | null }
+export type AuditEntry = { timestamp: number; kind: AuditKind; source: string; destination: string | null; bytes: number | null; redaction?: string[] }
+export type AuditKind = "capture" | "egress"
+export type AutostartStatus = { enabled: boolean; startHidden: boolean }
+export type AvailableUpdate = { version: string; body: string | null }
+export type CacheConfig = { enabled: boolean; ttl_secs: number }
+export type CameraDevice = { id: string; name: string; description: string }
+export type CaptureAttachment = "none" | "image" | "url"
+export type CaptureCloseReason = "cancelled" | "restarted"
+/**
+ * `capture-closed`: the overlays closed without a capture
+ */
+export type CaptureClosed = { version: number; reason: CaptureCloseReason }
+export type CaptureDiff = { image: string; width: number; height: number; changedPercent: number }
+export type CaptureEnvironment = { screenBackend: string; audioBackend: string; sessionType: string | null; desktop: string | null }
+export type CaptureFeedbackSettings = { soundOnSelection: boolean; soundOnScreen: boolean; soundOnCamera: boolean; volume: number }
+export type CaptureFile = { path: string; kind: string; bytes: number; createdAt: number; thumbnail: string | null }
+export type CaptureMetadata = { createdAt: number | null; monitor: string | null; app: string | null; note: string | null; software: string | null }
+export type CaptureRef = { path: string; kind?: string; ocrText?: string | null }
+/**
+ * `capture-started`: the sample rate of the audio that follows
+ */
+export type CaptureStarted = number
+/**
+ * `capture-stopped`
+ */
+export type CaptureStopped = null
+/**
+ * `captured-selection`: an area or whole-screen capture
+ */
+export type CapturedSelection = { version: number; id: string; monitor: number | null; width: number; height: number; format: ImageFormat; timestamp: number; data: string; thumbnail: string | null }
+/**
+ * `captures-cleaned`: the retention policy removed captures
+ */
+export type CapturesCleaned = CleanupReport
+/**
+ * `captures-purged`
+ */
+export type CapturesPurged = PurgeReport
+export type ChatMessage = { role: ChatRole; content: string; images?: string[]; tool_calls?: ToolCall[]; tool_call_id?: string | null; tool_name?: string | null }
+export type ChatRole = "system" | "user" | "assistant" | "tool"
+/**
+ * `chat_stream_chunk`: text streamed by `chat_stream_response`
+ */
+export type ChatStreamChunk = string
+/**
+ * `chat_stream_complete`: the whole response once the stream ends
+ */
+export type ChatStreamComplete = string
+export type ChatStreamEvent = { event: "delta"; data: { text: string } } | { event: "done"; data: { text: string } } | { event: "cancelled"; data: { text: string } } | { event: "queued" }
+export type CheckoutResponse = { success: boolean | null; checkout_url: string | null; error: string | null }
+export type CleanupReport = { removed: number; freedBytes: number }
+/**
+ * `click-through-changed`
+ */
+export type ClickThroughChanged = { version: number; enabled: boolean }
+export type ClipboardChanged = { kind: "text"; chars: number; bytes: number; lines: number } | { kind: "image"; width: number; height: number; bytes: number }
+export type ClipboardSettings = { watch: boolean; pollIntervalMs: number }
+export type CodeBlock = { language: string | null; text: string; x: number; y: number; width: number; height: number }
+export type ConnectionTest = { provider: LlmProviderKind; url: string; reachable: boolean; viaProxy: boolean; status: number | null; latencyMs: number; error: string | null }
+/**
+ * `continuous-recording-start`: the longest the recording may run, in seconds
+ */
+export type ContinuousRecordingStart = number
+/**
+ * `continuous-recording-stopped`
+ */
+export type ContinuousRecordingStopped = null
+export type ControlApiAsk = { text: string; submit?: boolean }
+export type ControlApiSettings = { enabled: boolean; port: number }
+export type ControlApiStatus = { running: boolean; port: number | null; url: string | null }
+export type Conversation = { id: string; title: string; model: string | null; profileId: string | null; createdAt: number; updatedAt: number; messages: StoredMessage[] }
+export type ConversationSummary = { id: string; title: string; model: string | null; profileId: string | null; createdAt: number; updatedAt: number; messageCount: number }
+export type CrashReport = { id: string; createdAt: number; appVersion: string; os: string; arch: string; thread: string; message: string; location: string | null; backtrace: string; environment: CaptureEnvironment; uploaded: boolean }
+export type CrashReportSettings = { upload: boolean }
+export type CursorType = "invisible" | "default" | "auto"
+export type CustomShareSettings = { url: string; authorization: string | null; publicUrl: string | null }
+/**
+ * `custom-shortcut-triggered`: a shortcut bound to a custom action
+ */
+export type CustomShortcutTriggered = { action: string }
+export type CustomSttBody = { kind: "form"; fields: ([string, string])[] } | { kind: "binary" } | { kind: "json"; text: string }
+export type CustomSttResponse = { status: number; body: string }
+/**
+ * `deep-link-ask`: also what take_deep_link_ask returns
+ */
+export type DeepLinkAsk = { text: string }
+export type DeepgramSttConfig = { api_key: string | null; model: string; language: string | null }
+export type DetectedRegion = { x: number; y: number; width: number; height: number; kind: RegionKind }
+/**
+ * `do-not-disturb-changed`
+ */
+export type DoNotDisturbChanged = { version: number; enabled: boolean | null }
+export type DocumentInfo = { name: string; title: string | null; pageCount: number; hasText: boolean }
+export type DocumentPage = { page: number; text: string }
+export type DualCaptureMode = "system_only" | "mixed" | "separate"
+export type ElevenLabsTtsConfig = { api_key: string | null; model: string; voice_id: string }
+export type EmbeddingProviderKind = "open_ai" | "ollama"
+export type EmbeddingsConfig = { provider: EmbeddingProviderKind; model?: string | null; base_url?: string | null }
+export type EncryptionProgress = { encrypt: boolean; rows: number; files: number; done: boolean; error: string | null }
+export type ExportFormat = "markdown" | "html" | "pdf"
+export type ExtractedTable = { rows: string[][]; columns: number; csv: string }
+export type FocusSettings = { track: boolean; addToPrompt: boolean; pollIntervalMs: number }
+/**
+ * `focus-text-input`
+ */
+export type FocusTextInput = Record<string, never>
+export type FocusedApp = { appName: string; title: string; seenAt: number }
+export type FullscreenApp = { appName: string; exclusive: boolean }
+/**
+ * `fullscreen-entered`
+ */
+export type FullscreenEntered = FullscreenApp
+/**
+ * `fullscreen-exited`
+ */
+export type FullscreenExited = FullscreenApp
+export type FullscreenSettings = { detect: boolean; allowOverlays: boolean; pollIntervalMs: number }
+export type HistoryArchiveReport = { conversations: number; messages: number; captures: number; skipped: number }
+/**
+ * `history-encryption`: progress of encrypting or decrypting the history
+ */
+export type HistoryEncryption = EncryptionProgress
+export type HistoryFilter = { ids: string[] | null; from: number | null; to: number | null }
+export type Hook = { id: string; enabled: boolean; events: HookEvent[]; command: string; args: string[]; workingDir: string | null; timeoutSecs: number }
+export type HookEvent = "capture-saved" | "answer-completed"
+/**
+ * `hook-finished`
+ */
+export type HookFinished = HookRun
+export type HookRun = { id: string; event: string; startedAt: number; durationMs: number; exitCode: number | null; timedOut: boolean; stdout: string; stderr: string; error: string | null }
+export type HookSettings = { hooks: Hook[] }
+export type HostService = "screen" | "clipboard" | "llm"
+/**
+ * `hotkeys-paused`
+ */
+export type HotkeysPaused = boolean
+export type IdleSettings = { enabled: boolean; idleAfterSecs: number; pauseRealtime: boolean }
+export type IdleStatus = { idle: boolean; idleSecs: number }
+export type ImageFormat = "png"
+export type ImageInfo = { width: number; height: number; bytes: number; format: string }
+export type ImageReuse = { message_index: number; image_index: number; original_message_index: number; original_image_index: number }
+export type ImageTransform = { message_index: number; image_index: number; original: ImageInfo; processed: ImageInfo }
+export type ImgurShareSettings = { clientId: string | null }
+export type ImportedBackup = { settings: Settings; profiles: number; apiKeys: number }
+export type IngestReport = { attachments: Attachment[]; rejected: RejectedFile[] }
+export type IngestedDocument = { attachment: Attachment; pages: DocumentPage[] }
+export type InstanceInfo = { id: string; name: string; created_at: string }
+export type IntegrationSettings = { obsidian: ObsidianSettings; notion: NotionSettings }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+export type LlmAttempt = { request_id: string; provider: string; attempt: number; status: AttemptStatus; error: string | null; retry_in_ms: number | null }
+export type LlmCacheHit = { request_id: string; provider: string; model: string }
+export type LlmCancelled = { request_id: string; text: string }
+export type LlmConfig = { provider: LlmProviderKind; openai?: ProviderSettings; anthropic?: ProviderSettings; gemini?: ProviderSettings; openrouter?: ProviderSettings; openai_compatible?: ProviderSettings; azure_openai?: ProviderSettings; ollama?: ProviderSettings; lm_studio?: ProviderSettings; retry?: RetryConfig; failover?: LlmProviderKind[]; cache?: CacheConfig; scrub?: ScrubConfig; queue_when_offline?: boolean }
+export type LlmContextTrimmed = ({ dropped_messages: number; dropped_images: number; tokens_before: number; tokens_after: number }) & { request_id: string }
+export type LlmImagesProcessed = { request_id: string; images: ImageTransform[]; reused: ImageReuse[] }
+export type LlmProviderKind = "open_ai" | "anthropic" | "gemini" | "open_router" | "open_ai_compatible" | "azure_open_ai" | "ollama" | "lm_studio"
+export type LlmScrubReview = ({ changes: ScrubChange[]; approvalId: string | null }) & { request_id: string }
+export type LlmTextScrubbed = { request_id: string; masked: string[] }
+export type LlmToolCall = { request_id: string; call: ToolCall; status: ToolCallStatus; error: string | null }
+export type LocalModel = { server: LlmProviderKind; name: string; size_bytes: number | null; supports_vision: boolean }
+export type LocalServerStatus = { server: LlmProviderKind; base_url: string; online: boolean; version: string | null; error: string | null }
+export type LogEntry = { timestamp: string; level: string; target: string; message: string }
+/**
+ * `manual-stop-continuous`
+ */
+export type ManualStopContinuous = null
+export type McpServerCommand = { command: string; args: string[] }
+export type Meeting = { app: string; signal: MeetingSignal; startedAt: number; offerTranscription: boolean }
+/**
+ * `meeting-ended`
+ */
+export type MeetingEnded = Meeting
+export type MeetingSettings = { detect: boolean; apps: string[]; windowTitles: string[]; autoStealth: boolean; offerTranscription: boolean; pollIntervalSecs: number }
+export type MeetingSignal = "foreground" | "microphone"
+/**
+ * `meeting-started`
+ */
+export type MeetingStarted = Meeting
+export type MeetingStatus = { meeting: Meeting | null; doNotDisturb: boolean | null }
+export type Model = { provider: string; name: string; id: string; model: string; description: string; modality: string; isAvailable: boolean }
+/**
+ * Informasi dukungan multi-monitor
+ */
+export type MultiMonitorSupport = { supported: boolean; session_type: string; available_tools: string[]; missing_tools: string[]; install_command: string }
+export type NoteDestination = "obsidian" | "notion"
+export type NotificationCategory = "capture_saved" | "transcription_finished" | "provider_error" | "update_available"
+export type NotificationSettings = { enabled: boolean; onlyWhenHidden: boolean; captureSaved: boolean; transcriptionFinished: boolean; providerError: boolean; updateAvailable: boolean }
+export type NotionSettings = { token: string | null; parentPage: string }
+export type ObsidianSettings = { vaultPath: string | null; folder: string; attachmentsFolder: string; tags: string[] }
+export type OcrLanguage = { code: string; source: OcrLanguageSource; bytes: number | null }
+export type OcrLanguageProgress = { code: string; downloaded: number; total: number | null; done: boolean }
+export type OcrLanguageSource = "app" | "system"
+export type OpenAiSttConfig = { api_key: string | null; base_url: string; model: string; language: string | null }
+export type OpenAiTtsConfig = { api_key: string | null; base_url: string; model: string; voice: string; speed: number }
+export type OverlaySettings = { dimColor: string; dimOpacity: number; borderColor: string | null; showDimensions: boolean; showMagnifier: boolean }
+export type Pin = { id: string; captureId: string; x: number | null; y: number | null; width: number; height: number }
+export type PiperTtsConfig = { binary: string; model_path: string | null }
+export type PluginCommand = { name: string; description?: string }
+export type PluginGrant = { enabled: boolean; hostServices: HostService[] }
+export type PluginInfo = { id: string; path: string; manifest: PluginManifest | null; error: string | null; grant: PluginGrant; missingHostServices: HostService[]; tools: string[]; running: boolean }
+export type PluginManifest = { id: string; name: string; version?: string; description?: string; command: string; args?: string[]; hostServices?: HostService[]; commands?: PluginCommand[]; tools?: PluginTool[] }
+export type PluginSettings = { plugins: Partial<{ [key in string]: PluginGrant }> }
+export type PluginTool = { name: string; description: string; parameters?: JsonValue }
+export type PolicyStatus = { managed: boolean; path: string; error: string | null; lockedKeys: string[]; allowedProviders: LlmProviderKind[] | null; localOnly: boolean; telemetry: boolean }
+export type PowerSettings = { degradeOnBattery: boolean; batteryCaptureMaxPx: number; pauseBackgroundTasks: boolean }
+export type PowerSource = "ac" | "battery" | "unknown"
+export type PowerStatus = { source: PowerSource; batteryPercent: number | null; charging: boolean; lowPowerMode: boolean; degraded: boolean }
+/**
+ * `power-status-changed`
+ */
+export type PowerStatusChanged = PowerStatus
+export type PrivacySettings = { encryptHistory: boolean; hideFromScreenShare: boolean; localOnly: boolean; purgeCapturesOnLock: boolean }
+export type ProfileSwitched = { name: string; settings: Settings }
+export type ProfileVariable = { name: string; description?: string | null; default?: string | null }
+export type PromptProfile = { id?: string; name: string; systemPrompt: string; variables?: ProfileVariable[]; provider?: LlmProviderKind | null; model?: string | null; temperature?: number | null; createdAt?: number; updatedAt?: number }
+export type ProviderInfo = { kind: LlmProviderKind; name: string; model: string; supports_vision: boolean; supports_streaming: boolean; limits: TokenLimits }
+export type ProviderRequest = { url: string; method?: string; headers?: Partial<{ [key in string]: string }>; stream?: boolean; responseContentPath?: string | null; apiKeyRef?: string | null; toolFormat?: ToolFormat | null }
+export type ProviderSettings = { api_key: string | null; base_url: string | null; model: string | null; max_output_tokens: number | null; temperature: number | null; context_window: number | null; supports_vision: boolean | null; api_version: string | null; deployment: string | null; requests_per_minute: number | null; tokens_per_minute: number | null }
+export type ProxyConfig = { enabled: boolean; url: string | null; username: string | null; password: string | null; bypass?: string[] }
+export type PurgeReport = { trigger: PurgeTrigger; screens: number; tempFiles: number; clipboardCleared: boolean }
+export type PurgeTrigger = "manual" | "lock" | "exit"
+export type QueueStatus = { pending: number; online: boolean }
+export type QueuedRequest = { id: string; attempts: number; lastError: string | null; createdAt: number; lastAttemptAt: number | null }
+export type QueuedRequestFinished = { request_id: string; text: string | null; error: string | null }
+export type RealtimeConfig = { provider: RealtimeProviderKind; model?: string | null; voice?: string | null; instructions?: string | null; input_device?: string | null }
+/**
+ * `realtime-error`
+ */
+export type RealtimeError = string
+export type RealtimeProviderKind = "open_ai" | "gemini"
+export type RealtimeStatus = "connecting" | "listening" | "speaking" | "interrupted" | "closed"
+export type RealtimeStatusEvent = { status: RealtimeStatus; error: string | null }
+export type RealtimeTranscript = { role: SpeakerRole; text: string; is_final: boolean }
+/**
+ * `recording-finished`
+ */
+export type RecordingFinished = RecordingSummary
+export type RecordingFormat = "wav" | "opus"
+/**
+ * `recording-progress`: seconds recorded so far
+ */
+export type RecordingProgress = number
+/**
+ * `recording-started`
+ */
+export type RecordingStarted = RecordingFormat
+export type RecordingStatus = { path: string; duration_ms: number; bytes: number }
+export type RecordingSummary = { path: string; format: RecordingFormat; duration_ms: number; bytes: number }
+export type RegionKind = "element" | "text"
+export type RejectedFile = { path: string; reason: string }
+export type ResponseSettings = { responseLength: string; language: string; autoScroll: boolean }
+export type RetentionPolicy = { maxAgeDays: number | null; maxTotalMb: number | null; maxCount: number | null }
+export type RetryConfig = { max_retries: number; initial_delay_ms: number; max_delay_ms: number }
+export type S3ShareSettings = { endpoint: string; region: string; bucket: string; prefix: string; pathStyle: boolean; accessKeyId: string; secretAccessKey: string | null; publicUrl: string | null; linkTtlSecs: number }
+/**
+ * `screen-changed`: the change trigger saw enough of the screen change
+ */
+export type ScreenChanged = { version: number; monitor: number; region: SelectionCoords | null; change: number; timestamp: number }
+export type ScreenshotMode = "manual" | "auto"
+export type ScreenshotSettings = { mode: ScreenshotMode; autoPrompt: string; enabled: boolean; selectionConstraint: string | null }
+export type ScrubChange = { messageIndex: number; original: string; scrubbed: string; matches: ScrubMatch[] }
+export type ScrubConfig = { enabled: boolean; emails: boolean; tokens: boolean; ipAddresses: boolean; names: string[]; customRules: ScrubRule[]; requireApproval: boolean }
+export type ScrubKind = "email" | "token" | "ip_address" | "name" | "custom"
+export type ScrubMatch = { kind: ScrubKind; start: number; end: number; text: string; replacement: string }
+export type ScrubPreview = { changes: ScrubChange[]; approvalId: string | null }
+export type ScrubRule = { label: string; pattern: string }
+export type SearchFilters = { conversationId: string | null; role: string | null; from: number | null; to: number | null; source: SearchSource; limit: number | null }
+export type SearchHit = { source: string; messageId: string; conversationId: string; conversationTitle: string; role: string; timestamp: number; snippet: string; rank: number; capturePath: string | null }
+export type SearchSource = "all" | "messages" | "captures"
+export type Selection = { monitorIndex: number; x: number; y: number; width: number; height: number }
+/**
+ * `selection-changed`: the keyboard selection moved, in image pixels
+ */
+export type SelectionChanged = { version: number; monitorIndex: number; x: number; y: number; width: number; height: number }
+export type SelectionCoords = { x: number; y: number; width: number; height: number }
+export type SelectionMeasurement = { widthPx: number; heightPx: number; dpi: number; widthMm: number; heightMm: number; widthIn: number; heightIn: number; centerX: number; centerY: number }
+export type SemanticHit = { source: string; messageId: string; conversationId: string; conversationTitle: string; role: string; timestamp: number; text: string; score: number }
+export type SensitiveAction = "block" | "redact"
+export type SensitiveAppSettings = { enabled: boolean; rules: SensitiveRule[] }
+export type SensitiveRule = { app: string; title: string; action: SensitiveAction }
+export type SentNote = { destination: NoteDestination; location: string }
+export type Settings = { version: number; appearance: AppearanceSettings; window: WindowSettings; screenshot: ScreenshotSettings; response: ResponseSettings; audio: AudioSettings; systemPrompt: SystemPromptSettings; systemAudio: SystemAudioSettings; privacy: PrivacySettings; sensitiveApps: SensitiveAppSettings; captures: RetentionPolicy; notifications: NotificationSettings; captureFeedback: CaptureFeedbackSettings; overlay: OverlaySettings; thumbnails: ThumbnailSettings; watermark: WatermarkSettings; clipboard: ClipboardSettings; focus: FocusSettings; idle: IdleSettings; power: PowerSettings; meetings: MeetingSettings; fullscreen: FullscreenSettings; controlApi: ControlApiSettings; webhooks: WebhookSettings; hooks: HookSettings; plugins: PluginSettings; integrations: IntegrationSettings; share: ShareSettings; crashReports: CrashReportSettings; telemetry: TelemetrySettings; shortcuts: ShortcutsConfig | null; llm: LlmConfig; stt: SttConfig; tts: TtsConfig; realtime: RealtimeConfig; proxy: ProxyConfig; extra: Partial<{ [key in string]: JsonValue }> }
+export type SettingsChanged = { key: string; value: JsonValue }
+export type ShareSettings = { s3: S3ShareSettings; imgur: ImgurShareSettings; custom: CustomShareSettings; copyLink: boolean }
+export type ShareTarget = "s3" | "imgur" | "custom"
+export type SharedCapture = { target: ShareTarget; url: string; expiresAt: number | null; copied: boolean }
+export type ShortcutBinding = { action: string; key: string; enabled: boolean }
+/**
+ * `shortcut-registration-error`: action, shortcut and error of each
+ * shortcut that couldn't be registered
+ */
+export type ShortcutRegistrationError = ([string, string, string])[]
+export type ShortcutsConfig = { bindings: Partial<{ [key in string]: ShortcutBinding }>; customActions?: JsonValue[] }
+export type ShowAt = "saved" | "cursor" | "topLeft" | "topCenter" | "topRight" | "bottomLeft" | "bottomRight"
+export type SpeakerRole = "user" | "assistant"
+/**
+ * `speech-detected`: base64 WAV of a finished utterance
+ */
+export type SpeechDetected = string
+/**
+ * `speech-discarded`: why a segment was dropped
+ */
+export type SpeechDiscarded = string
+/**
+ * `speech-end`: metadata and audio of a finished utterance
+ */
+export type SpeechEnd = SpeechSegment
+export type SpeechSegment = { source: string; audio: string; sample_rate: number; duration_ms: number }
+/**
+ * `speech-start`
+ */
+export type SpeechStart = null
+/**
+ * `start-audio-recording`
+ */
+export type StartAudioRecording = Record<string, never>
+export type StorageItem = { key: string; value: string }
+export type StorageResult = { license_key: string | null; instance_id: string | null; selected_pluely_model: string | null }
+export type StorageUsage = { capturesBytes: number; capturesCount: number; oldestCaptureAt: number | null; databaseBytes: number; otherBytes: number; totalBytes: number; retention: RetentionPolicy }
+export type StoredMessage = { id: string; role: string; content: string; timestamp: number; model?: string | null; captures?: CaptureRef[]; attachedFiles?: string | null; appContext?: FocusedApp | null }
+export type StreamEncoding = { codec: "linear_16" } | { codec: "opus"; bitrate: number }
+export type SttConfig = { provider: SttProviderKind; openai?: OpenAiSttConfig; deepgram?: DeepgramSttConfig; stream_encoding?: StreamEncoding }
+/**
+ * `stt-error`
+ */
+export type SttError = string
+/**
+ * `stt-final`
+ */
+export type SttFinal = TranscriptUpdate
+/**
+ * `stt-partial`
+ */
+export type SttPartial = TranscriptUpdate
+export type SttProviderKind = "open_ai" | "deepgram" | "local_whisper"
+export type SystemAudioSettings = { useSystemPrompt: boolean; contextContent: string; quickActions: string[] }
+export type SystemPromptResponse = { prompt_name: string; system_prompt: string }
+export type SystemPromptSettings = { prompt: string | null; selectedId: number | null }
+export type TelemetryPayload = { appVersion: string; os: string; periodStart: number; periodEnd: number; counters: Partial<{ [key in string]: number }>; timings: Partial<{ [key in string]: Timing }> }
+export type TelemetrySettings = { shareUsage: boolean }
+export type Theme = "light" | "dark" | "system"
+export type ThumbnailSettings = { size: number; quality: number }
+export type Timing = { count: number; totalMs: number; maxMs: number }
+/**
+ * `toggle-system-audio`
+ */
+export type ToggleSystemAudio = Record<string, never>
+/**
+ * `toggle-window-visibility`: whether the main window is now hidden
+ */
+export type ToggleWindowVisibility = boolean
+export type TokenCount = { total: number; per_message: number[]; image_tokens: number }
+export type TokenLimits = { context_window: number; max_output_tokens: number }
+export type ToolCall = { id: string; name: string; arguments?: JsonValue }
+export type ToolCallStatus = "started" | "succeeded" | "failed"
+export type ToolFormat = "open_ai" | "anthropic" | "gemini"
+export type TranscriptUpdate = { text: string; is_final: boolean }
+export type TranslatedCapture = { targetLang: string; sourceText: string; translatedText: string; lines: TranslatedLine[] }
+export type TranslatedLine = { x: number; y: number; width: number; height: number; source: string; translated: string }
+/**
+ * `trigger-screenshot`
+ */
+export type TriggerScreenshot = Record<string, never>
+export type TtsConfig = { provider: TtsProviderKind; openai?: OpenAiTtsConfig; elevenlabs?: ElevenLabsTtsConfig; piper?: PiperTtsConfig; fallback_to_piper?: boolean }
+export type TtsFinished = { id: string; stopped: boolean; error: string | null }
+export type TtsProgress = { id: string; chunk: number; chunks: number; playedMs: number; totalMs: number }
+export type TtsProviderKind = "open_ai" | "eleven_labs" | "piper"
+export type UpdateProgress = { event: "started"; data: { contentLength: number | null } } | { event: "progress"; data: { chunkLength: number } } | { event: "finished" }
+export type UrlContext = { url: string; title: string | null; siteName: string | null; description: string | null; byline: string | null; text: string; truncated: boolean; screenshot: string | null; screenshotError: string | null }
+export type UsageBucket = { key: string; requests: number; inputTokens: number; outputTokens: number; costUsd: number; unpricedRequests: number }
+export type UsagePeriod = "day" | "week" | "month" | "all"
+export type UsageSummary = { period: UsagePeriod; since: number | null; total: UsageBucket; byDay: UsageBucket[]; byProvider: UsageBucket[]; byModel: UsageBucket[] }
+/**
+ * `user-active`
+ */
+export type UserActive = IdleStatus
+/**
+ * `user-idle`
+ */
+export type UserIdle = IdleStatus
+export type VadAggressiveness = "quality" | "low_bitrate" | "aggressive" | "very_aggressive"
+export type VadConfig = { enabled: boolean; hop_size: number; sensitivity_rms: number; peak_threshold: number; silence_chunks: number; min_speech_chunks: number; pre_speech_chunks: number; noise_gate_threshold: number; max_recording_duration_secs: number; use_webrtc?: boolean; webrtc_mode?: VadAggressiveness; voiced_ratio?: number; noise_suppression?: boolean }
+export type ValidateResponse = { is_active: boolean; last_validated_at: string | null }
+/**
+ * `watch-frame`: a watched region changed since the last frame sent
+ */
+export type WatchFrame = { version: number; id: string; monitor: number; width: number; height: number; format: ImageFormat; hash: string; timestamp: number; data: string }
+export type WatermarkPosition = "topLeft" | "topRight" | "bottomLeft" | "bottomRight" | "center"
+export type WatermarkSettings = { enabled: boolean; text: string | null; imagePath: string | null; position: WatermarkPosition; opacity: number; textScale: number; imageScale: number; fontPath: string | null }
+export type WebhookEndpoint = { id: string; url: string; enabled: boolean; events: WebhookEvent[]; attach: CaptureAttachment; secret: string | null }
+export type WebhookEvent = "capture" | "answer"
+export type WebhookFailed = { id: string; event: string; error: string }
+export type WebhookSettings = { endpoints: WebhookEndpoint[]; retry: RetryConfig }
+export type WebhookTest = { status: number; latencyMs: number }
+export type WhisperConfig = { model_path: string | null; language: string | null; threads: number; translate: boolean; step_ms: number; window_ms: number }
+/**
+ * `whisper-final`
+ */
+export type WhisperFinal = TranscriptUpdate
+/**
+ * `whisper-partial`
+ */
+export type WhisperPartial = TranscriptUpdate
+export type WindowEffect = "none" | "blur" | "acrylic" | "mica" | "vibrancy"
+export type WindowMode = "bar" | "compact" | "expanded"
+/**
+ * `window-mode-changed`
+ */
+export type WindowModeChanged = { version: number; mode: WindowMode }
+export type WindowPlacement = { x: number; y: number; width: number; height: number }
+export type WindowSettings = { appIconVisible: boolean; alwaysOnTop: boolean; autostart: boolean; cursor: CursorType; startMinimized: boolean; autostartHidden: boolean; skipTaskbar: boolean; hideFromSwitcher: boolean; snapToEdges: boolean; snapMargin: number; placements: Partial<{ [key in string]: WindowPlacement }>; showAt: ShowAt; modeGeometry: Partial<{ [key in WindowMode]: WindowPlacement }> }
+export type WorkspaceProfile = { name: string; active: boolean }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
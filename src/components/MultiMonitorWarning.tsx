@@ -1,15 +1,7 @@
 import { useState, useEffect } from "react";
-import { invoke } from "@tauri-apps/api/core";
 import { AlertTriangle, Monitor, Copy, Check, X } from "lucide-react";
 import { Card, Button } from "@/components";
-
-interface MultiMonitorSupport {
-  supported: boolean;
-  session_type: string;
-  available_tools: string[];
-  missing_tools: string[];
-  install_command: string;
-}
+import { commands, type MultiMonitorSupport } from "@/bindings";
 
 interface MultiMonitorWarningProps {
   showAlways?: boolean;
@@ -31,9 +23,7 @@ export const MultiMonitorWarning = ({
 
   const checkSupport = async () => {
     try {
-      const result = await invoke<MultiMonitorSupport>(
-        "check_multi_monitor_support"
-      );
+      const result = await commands.checkMultiMonitorSupport();
       setSupport(result);
 
       // Check if user previously dismissed this warning
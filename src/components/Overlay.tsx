@@ -1,13 +1,37 @@
 import React, { useState, useEffect, useRef } from "react";
-import { invoke } from "@tauri-apps/api/core";
 import { MousePointer2 } from "lucide-react";
+import {
+  commands,
+  events,
+  type OverlaySettings,
+  type SelectionCoords,
+} from "@/bindings";
 
-interface SelectionCoords {
-  x: number;
-  y: number;
-  width: number;
-  height: number;
-}
+const DEFAULT_OVERLAY_CONFIG: OverlaySettings = {
+  dimColor: "#0f172a",
+  dimOpacity: 0.35,
+  borderColor: null,
+  showDimensions: false,
+  showMagnifier: false,
+};
+
+const MAGNIFIER_SIZE = 120;
+const MAGNIFIER_ZOOM = 4;
+
+// "#rgb" or "#rrggbb" with an alpha, as a CSS rgba()
+const hexToRgba = (hex: string, alpha: number) => {
+  let digits = hex.replace("#", "");
+  if (digits.length === 3) {
+    digits = digits
+      .split("")
+      .map((d) => d + d)
+      .join("");
+  }
+  const value = parseInt(digits, 16);
+  return `rgba(${(value >> 16) & 255}, ${(value >> 8) & 255}, ${
+    value & 255
+  }, ${alpha})`;
+};
 
 interface OverlayProps {
   monitorIndex: number;
@@ -25,17 +49,16 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
   });
   const [cursorPosition, setCursorPosition] = useState({ x: 0, y: 0 });
   const [cursorVisible, setCursorVisible] = useState(false);
+  const [config, setConfig] = useState<OverlaySettings>(DEFAULT_OVERLAY_CONFIG);
+  // The frozen screen, only fetched for the magnifier
+  const [screenImage, setScreenImage] = useState<string | null>(null);
 
   const selectionRef = useRef<HTMLDivElement>(null);
 
   // Handle cancellation (ESC key, cancel button)
   const handleCancel = async () => {
     setIsSelecting(false);
-    try {
-      await invoke("close_overlay_window", { reason: "User cancelled" });
-    } catch {
-      // Error ignored
-    }
+    await commands.closeOverlayWindow();
   };
 
   // Handle selection completion
@@ -45,22 +68,17 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
     width: number,
     height: number
   ) => {
-    try {
-      const scaleFactor = window.devicePixelRatio || 1;
-      const coords: SelectionCoords = {
-        x: Math.round(x * scaleFactor),
-        y: Math.round(y * scaleFactor),
-        width: Math.round(width * scaleFactor),
-        height: Math.round(height * scaleFactor),
-      };
-
-      await invoke("capture_selected_area", {
-        coords,
-        monitorIndex,
-      });
-    } catch {
-      // Error ignored
-      console.error("Error capturing selected area");
+    const scaleFactor = window.devicePixelRatio || 1;
+    const coords: SelectionCoords = {
+      x: Math.round(x * scaleFactor),
+      y: Math.round(y * scaleFactor),
+      width: Math.round(width * scaleFactor),
+      height: Math.round(height * scaleFactor),
+    };
+
+    const result = await commands.captureSelectedArea(coords, monitorIndex);
+    if (result.status === "error") {
+      console.error("Error capturing selected area:", result.error);
     }
   };
 
@@ -141,6 +159,84 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
     }
   };
 
+  // Keyboard selection: arrows move, Alt+arrows resize, Shift for 10px steps,
+  // Enter captures. The rectangle itself lives in Rust.
+  const handleSelectionKey = (e: KeyboardEvent) => {
+    if (e.key === "Enter") {
+      e.preventDefault();
+      // Fails while nothing is selected yet
+      commands.confirmSelection();
+      return;
+    }
+    const directions: Record<string, [number, number]> = {
+      ArrowLeft: [-1, 0],
+      ArrowRight: [1, 0],
+      ArrowUp: [0, -1],
+      ArrowDown: [0, 1],
+    };
+    const direction = directions[e.key];
+    if (!direction) return;
+    e.preventDefault();
+    const step = e.shiftKey ? 10 : 1;
+    const [dx, dy] = direction.map((d) => d * step);
+    const update = e.altKey
+      ? commands.resizeSelection(dx, dy)
+      : commands.nudgeSelection(dx, dy);
+    update.then((result) => {
+      if (result.status === "error") console.error(result.error);
+    });
+  };
+
+  useEffect(() => {
+    commands.getOverlayConfig().then((result) => {
+      // Defaults are used otherwise
+      if (result.status === "ok") setConfig(result.data);
+    });
+  }, []);
+
+  useEffect(() => {
+    if (!config.showMagnifier) return;
+    commands.getOverlayImage(monitorIndex).then((result) => {
+      // No magnifier otherwise
+      if (result.status === "ok") {
+        setScreenImage(`data:image/jpeg;base64,${result.data}`);
+      }
+    });
+  }, [config.showMagnifier, monitorIndex]);
+
+  const scaleFactor = window.devicePixelRatio || 1;
+  const borderStyle = config.borderColor
+    ? { borderColor: config.borderColor }
+    : {};
+
+  // Draws the keyboard selection when it is on this monitor
+  useEffect(() => {
+    const unlisten = events.selectionChanged.listen((event) => {
+      const selection = event.payload;
+      if (selection.monitorIndex !== monitorIndex) {
+        setSelectionStyle((prev) => ({ ...prev, display: "none" }));
+        return;
+      }
+      const scaleFactor = window.devicePixelRatio || 1;
+      setSelectionStyle({
+        left: selection.x / scaleFactor,
+        top: selection.y / scaleFactor,
+        width: selection.width / scaleFactor,
+        height: selection.height / scaleFactor,
+        display: "block",
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [monitorIndex]);
+
+  useEffect(() => {
+    window.addEventListener("keydown", handleSelectionKey);
+    return () => window.removeEventListener("keydown", handleSelectionKey);
+  }, []);
+
   // Event listeners setup
   useEffect(() => {
     // ESC key listeners (multiple levels for reliability)
@@ -161,7 +257,7 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
         className="fixed inset-0 w-screen h-screen overflow-hidden"
         style={{
           cursor: "none",
-          backgroundColor: "rgba(15, 23, 42, 0.35)",
+          backgroundColor: hexToRgba(config.dimColor, config.dimOpacity),
           backdropFilter: "blur(2px)",
         }}
         onMouseDown={handleMouseDown}
@@ -172,7 +268,10 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
         <div className="fixed top-5 left-1/2 transform -translate-x-1/2 bg-black/70 text-white px-6 py-3 rounded-lg font-sans text-sm pointer-events-none z-[5000] shadow-2xl backdrop-blur-sm">
           <div className="flex items-center gap-2">
             <span className="font-semibold">Screen Capture:</span>
-            <span>Click and drag to select area · Press ESC to cancel</span>
+            <span>
+              Click and drag to select area · Arrows to select by keyboard,
+              Enter to capture · Press ESC to cancel
+            </span>
           </div>
         </div>
 
@@ -195,6 +294,7 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
           ref={selectionRef}
           className="absolute border-2 border-primary-foreground bg-primary/10 rounded-3xl rounded-br-none pointer-events-none"
           style={{
+            ...borderStyle,
             left: selectionStyle.left,
             top: selectionStyle.top,
             width: selectionStyle.width,
@@ -216,6 +316,45 @@ const Overlay: React.FC<OverlayProps> = ({ monitorIndex }) => {
           }}
         />
 
+        {/* Selection size in screen pixels */}
+        {config.showDimensions &&
+          selectionStyle.display === "block" &&
+          selectionStyle.width > 0 && (
+            <div
+              className="absolute bg-black/70 text-white px-2 py-0.5 rounded font-mono text-xs pointer-events-none z-[5000]"
+              style={{
+                left: selectionStyle.left,
+                top: Math.max(selectionStyle.top - 24, 0),
+              }}
+            >
+              {Math.round(selectionStyle.width * scaleFactor)} ×{" "}
+              {Math.round(selectionStyle.height * scaleFactor)}
+            </div>
+          )}
+
+        {/* Magnifier */}
+        {config.showMagnifier && screenImage && cursorVisible && (
+          <div
+            className="fixed rounded-full border-2 border-white shadow-2xl pointer-events-none z-[9000]"
+            style={{
+              ...borderStyle,
+              width: MAGNIFIER_SIZE,
+              height: MAGNIFIER_SIZE,
+              left: cursorPosition.x + 24,
+              top: cursorPosition.y + 24,
+              backgroundImage: `url(${screenImage})`,
+              backgroundRepeat: "no-repeat",
+              backgroundSize: `${window.innerWidth * MAGNIFIER_ZOOM}px ${
+                window.innerHeight * MAGNIFIER_ZOOM
+              }px`,
+              backgroundPosition: `${
+                MAGNIFIER_SIZE / 2 - cursorPosition.x * MAGNIFIER_ZOOM
+              }px ${MAGNIFIER_SIZE / 2 - cursorPosition.y * MAGNIFIER_ZOOM}px`,
+              imageRendering: "pixelated",
+            }}
+          />
+        )}
+
         {/* Custom Cursor */}
         <div
           className="fixed pointer-events-none z-[9999] transition-opacity duration-100"
@@ -15,9 +15,10 @@ import {
   ScrollArea,
   Markdown,
 } from "@/components";
-import { check, Update } from "@tauri-apps/plugin-updater";
+import { Channel } from "@tauri-apps/api/core";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { useWindowResize } from "@/hooks";
+import { AvailableUpdate, commands, UpdateProgress } from "@/bindings";
 
 type UpdateState =
   | "checking"
@@ -37,7 +38,7 @@ interface DownloadProgress {
 
 export const Updater = () => {
   const [updateState, setUpdateState] = useState<UpdateState>("uptodate");
-  const [update, setUpdate] = useState<Update | null>(null);
+  const [update, setUpdate] = useState<AvailableUpdate | null>(null);
   const [progress, setProgress] = useState<DownloadProgress>({
     downloaded: 0,
     contentLength: 0,
@@ -52,7 +53,10 @@ export const Updater = () => {
     try {
       setUpdateState("checking");
 
-      const foundUpdate = await check();
+      // Checked by the backend, which refuses it in local-only mode
+      const result = await commands.checkForUpdate();
+      if (result.status === "error") throw new Error(result.error);
+      const foundUpdate = result.data;
       if (foundUpdate) {
         setUpdate(foundUpdate);
         setUpdateState("available");
@@ -73,16 +77,17 @@ export const Updater = () => {
       setUpdateState("downloading");
       setProgress({ downloaded: 0, contentLength: 0, percentage: 0 });
 
-      await update.downloadAndInstall((event) => {
+      const channel = new Channel<UpdateProgress>();
+      channel.onmessage = (event) => {
         switch (event.event) {
-          case "Started":
+          case "started":
             setProgress((prev) => ({
               ...prev,
               contentLength: event.data.contentLength || 0,
             }));
             break;
 
-          case "Progress":
+          case "progress":
             setProgress((prev) => {
               const downloaded = prev.downloaded + event.data.chunkLength;
               const percentage =
@@ -98,11 +103,13 @@ export const Updater = () => {
             });
             break;
 
-          case "Finished":
+          case "finished":
             setUpdateState("installing");
             break;
         }
-      });
+      };
+      const result = await commands.installUpdate(channel);
+      if (result.status === "error") throw new Error(result.error);
 
       setUpdateState("ready");
 
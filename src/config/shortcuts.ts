@@ -71,6 +71,26 @@ export const DEFAULT_SHORTCUT_ACTIONS: ShortcutAction[] = [
       linux: "ctrl+shift+s",
     },
   },
+  {
+    id: "toggle_click_through",
+    name: "Click-Through",
+    description: "Let clicks pass through the overlay to the app underneath",
+    defaultKey: {
+      macos: "cmd+shift+k",
+      windows: "ctrl+shift+k",
+      linux: "ctrl+shift+k",
+    },
+  },
+  {
+    id: "cycle_window_mode",
+    name: "Window Layout",
+    description: "Switch between the command bar, compact and expanded layouts",
+    defaultKey: {
+      macos: "cmd+shift+l",
+      windows: "ctrl+shift+l",
+      linux: "ctrl+shift+l",
+    },
+  },
   {
     id: "scroll_chat_up",
     name: "Scroll Chat Up",
@@ -4,7 +4,12 @@ import {
   SPEECH_TO_TEXT_PROVIDERS,
   STORAGE_KEYS,
 } from "@/config";
-import { getPlatform, safeLocalStorage, trackAppStart } from "@/lib";
+import {
+  getPlatform,
+  migrateStoredApiKey,
+  safeLocalStorage,
+  secureProviderVariables,
+} from "@/lib";
 import { getShortcutsConfig } from "@/lib/storage";
 import {
   getCustomizableState,
@@ -186,6 +191,7 @@ export const AppProvider = ({ children }: { children: ReactNode }) => {
               parsed.autoPrompt ||
               "Analyze this screenshot and provide insights",
             enabled: parsed.enabled !== undefined ? parsed.enabled : false,
+            selectionConstraint: parsed.selectionConstraint || undefined,
           });
         }
       } catch {
@@ -216,7 +222,17 @@ export const AppProvider = ({ children }: { children: ReactNode }) => {
       STORAGE_KEYS.SELECTED_AI_PROVIDER
     );
     if (savedSelectedAi) {
-      setSelectedAIProvider(JSON.parse(savedSelectedAi));
+      const selectedAi = JSON.parse(savedSelectedAi);
+      setSelectedAIProvider(selectedAi);
+      migrateStoredApiKey(selectedAi)
+        .then((migrated) => {
+          if (migrated !== selectedAi) {
+            setSelectedAIProvider(migrated);
+          }
+        })
+        .catch((error) =>
+          console.error("Failed to move API key to the keychain:", error)
+        );
     }
 
     // Load selected STT provider
@@ -291,17 +307,6 @@ export const AppProvider = ({ children }: { children: ReactNode }) => {
     const initializeApp = async () => {
       // Load license and data
       await getActiveLicenseStatus();
-
-      // Track app start
-      try {
-        const appVersion = await invoke<string>("get_app_version");
-        const storage = await invoke<{
-          instance_id: string;
-        }>("secure_storage_get");
-        await trackAppStart(appVersion, storage.instance_id || "");
-      } catch (error) {
-        console.debug("Failed to track app start:", error);
-      }
     };
     // Load data
     loadData();
@@ -407,10 +412,18 @@ export const AppProvider = ({ children }: { children: ReactNode }) => {
   // Sync selected AI to localStorage
   useEffect(() => {
     if (selectedAIProvider.provider) {
-      safeLocalStorage.setItem(
-        STORAGE_KEYS.SELECTED_AI_PROVIDER,
-        JSON.stringify(selectedAIProvider)
-      );
+      // The key itself goes to the keychain; localStorage only keeps a marker
+      secureProviderVariables(selectedAIProvider)
+        .catch((error) => {
+          console.error("Failed to save API key to the keychain:", error);
+          return selectedAIProvider;
+        })
+        .then((selected) => {
+          safeLocalStorage.setItem(
+            STORAGE_KEYS.SELECTED_AI_PROVIDER,
+            JSON.stringify(selected)
+          );
+        });
     }
   }, [selectedAIProvider]);
 
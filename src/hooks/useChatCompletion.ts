@@ -1,6 +1,7 @@
 import { useState, useCallback, useRef, useEffect } from "react";
 import { useApp } from "@/contexts";
 import { MAX_FILES } from "@/config";
+import { commands, events } from "@/bindings";
 import {
   fetchAIResponse,
   saveConversation,
@@ -11,8 +12,6 @@ import {
   generateMessageId,
   generateRequestId,
 } from "@/lib";
-import { invoke } from "@tauri-apps/api/core";
-import { listen } from "@tauri-apps/api/event";
 
 // Types for completion
 interface AttachedFile {
@@ -574,21 +573,26 @@ export const useChatCompletion = (
       }
 
       if (config.enabled) {
-        const base64 = await invoke("capture_to_base64");
+        const result = await commands.captureToBase64();
+        if (result.status === "error") throw new Error(result.error);
+        const base64 = result.data;
 
         if (config.mode === "auto") {
           // Auto mode: Submit directly to AI with the configured prompt
-          await handleScreenshotSubmit(base64 as string, config.autoPrompt);
+          await handleScreenshotSubmit(base64, config.autoPrompt);
         } else if (config.mode === "manual") {
           // Manual mode: Add to attached files without prompt
-          await handleScreenshotSubmit(base64 as string);
+          await handleScreenshotSubmit(base64);
         }
         // Reset flag after processing
         screenshotInitiatedByThisContext.current = false;
       } else {
         // Selection Mode: Open overlay to select an area
         isProcessingScreenshotRef.current = false;
-        await invoke("start_screen_capture");
+        const result = await commands.startScreenCapture(
+          screenshotConfigRef.current.selectionConstraint || null
+        );
+        if (result.status === "error") throw new Error(result.error);
       }
     } catch (error) {
       setState((prev) => ({
@@ -608,7 +612,7 @@ export const useChatCompletion = (
     let unlisten: any;
 
     const setupListener = async () => {
-      unlisten = await listen("captured-selection", async (event: any) => {
+      unlisten = await events.capturedSelection.listen(async (event) => {
         // Only process if this context initiated the screenshot
         if (!screenshotInitiatedByThisContext.current) {
           return;
@@ -619,16 +623,16 @@ export const useChatCompletion = (
         }
 
         isProcessingScreenshotRef.current = true;
-        const base64 = event.payload;
+        const base64 = event.payload.data;
         const config = screenshotConfigRef.current;
 
         try {
           if (config.mode === "auto") {
             // Auto mode: Submit directly to AI with the configured prompt
-            await handleScreenshotSubmit(base64 as string, config.autoPrompt);
+            await handleScreenshotSubmit(base64, config.autoPrompt);
           } else if (config.mode === "manual") {
             // Manual mode: Add to attached files without prompt
-            await handleScreenshotSubmit(base64 as string);
+            await handleScreenshotSubmit(base64);
           }
         } catch (error) {
           console.error("Error processing selection:", error);
@@ -652,7 +656,7 @@ export const useChatCompletion = (
   }, [handleScreenshotSubmit]);
 
   useEffect(() => {
-    const unlisten = listen("capture-closed", () => {
+    const unlisten = events.captureClosed.listen(() => {
       setIsScreenshotLoading(false);
       isProcessingScreenshotRef.current = false;
       screenshotInitiatedByThisContext.current = false;
@@ -2,6 +2,7 @@ import { useState, useCallback, useRef, useEffect } from "react";
 import { useWindowResize } from "./useWindow";
 import { useGlobalShortcuts } from "@/hooks";
 import { MAX_FILES } from "@/config";
+import { commands, events } from "@/bindings";
 import { useApp } from "@/contexts";
 import {
   fetchAIResponse,
@@ -15,8 +16,6 @@ import {
   generateRequestId,
   getResponseSettings,
 } from "@/lib";
-import { invoke } from "@tauri-apps/api/core";
-import { listen } from "@tauri-apps/api/event";
 
 // Types for completion
 interface AttachedFile {
@@ -294,6 +293,12 @@ export const useCompletion = () => {
     ]
   );
 
+  // The latest submit, for listeners that outlive a render
+  const submitRef = useRef(submit);
+  useEffect(() => {
+    submitRef.current = submit;
+  }, [submit]);
+
   const cancel = useCallback(() => {
     if (abortControllerRef.current) {
       abortControllerRef.current.abort();
@@ -921,20 +926,25 @@ export const useCompletion = () => {
       }
 
       if (config.enabled) {
-        const base64 = await invoke("capture_to_base64");
+        const result = await commands.captureToBase64();
+        if (result.status === "error") throw new Error(result.error);
+        const base64 = result.data;
 
         if (config.mode === "auto") {
           // Auto mode: Submit directly to AI with the configured prompt
-          await handleScreenshotSubmit(base64 as string, config.autoPrompt);
+          await handleScreenshotSubmit(base64, config.autoPrompt);
         } else if (config.mode === "manual") {
           // Manual mode: Add to attached files without prompt
-          await handleScreenshotSubmit(base64 as string);
+          await handleScreenshotSubmit(base64);
         }
         screenshotInitiatedByThisContext.current = false;
       } else {
         // Selection Mode: Open overlay to select an area
         isProcessingScreenshotRef.current = false;
-        await invoke("start_screen_capture");
+        const result = await commands.startScreenCapture(
+          screenshotConfigRef.current.selectionConstraint || null
+        );
+        if (result.status === "error") throw new Error(result.error);
       }
     } catch (error) {
       setState((prev) => ({
@@ -954,7 +964,7 @@ export const useCompletion = () => {
     let unlisten: any;
 
     const setupListener = async () => {
-      unlisten = await listen("captured-selection", async (event: any) => {
+      unlisten = await events.capturedSelection.listen(async (event) => {
         if (!screenshotInitiatedByThisContext.current) {
           return;
         }
@@ -964,16 +974,16 @@ export const useCompletion = () => {
         }
 
         isProcessingScreenshotRef.current = true;
-        const base64 = event.payload;
+        const base64 = event.payload.data;
         const config = screenshotConfigRef.current;
 
         try {
           if (config.mode === "auto") {
             // Auto mode: Submit directly to AI with the configured prompt
-            await handleScreenshotSubmit(base64 as string, config.autoPrompt);
+            await handleScreenshotSubmit(base64, config.autoPrompt);
           } else if (config.mode === "manual") {
             // Manual mode: Add to attached files without prompt
-            await handleScreenshotSubmit(base64 as string);
+            await handleScreenshotSubmit(base64);
           }
         } catch (error) {
           console.error("Error processing selection:", error);
@@ -997,7 +1007,7 @@ export const useCompletion = () => {
   }, [handleScreenshotSubmit]);
 
   useEffect(() => {
-    const unlisten = listen("capture-closed", () => {
+    const unlisten = events.captureClosed.listen(() => {
       setIsScreenshotLoading(false);
       isProcessingScreenshotRef.current = false;
       screenshotInitiatedByThisContext.current = false;
@@ -1008,6 +1018,41 @@ export const useCompletion = () => {
     };
   }, []);
 
+  // Questions sent to the local control API's /v1/ask
+  useEffect(() => {
+    const unlisten = events.controlApiAsk.listen((event) => {
+      if (event.payload.submit) {
+        submitRef.current(event.payload.text);
+      } else {
+        setInput(event.payload.text);
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setInput]);
+
+  // Questions from pluely://ask links are only filled in, never sent. One
+  // that arrived before this listener existed is picked up on mount.
+  useEffect(() => {
+    const takeQuestion = async () => {
+      const result = await commands.takeDeepLinkAsk();
+      if (result.status === "ok" && result.data?.text) {
+        setInput(result.data.text);
+      }
+    };
+
+    takeQuestion();
+    const unlisten = events.deepLinkAsk.listen(() => {
+      takeQuestion();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setInput]);
+
   const toggleRecording = useCallback(() => {
     setEnableVAD(!enableVAD);
     setMicOpen(!micOpen);
@@ -49,6 +49,18 @@ export const useSettings = () => {
     );
   };
 
+  const handleScreenshotConstraintChange = (value: string) => {
+    const newConfig = {
+      ...screenshotConfiguration,
+      selectionConstraint: value.trim() || undefined,
+    };
+    setScreenshotConfiguration(newConfig);
+    safeLocalStorage.setItem(
+      STORAGE_KEYS.SCREENSHOT_CONFIG,
+      JSON.stringify(newConfig)
+    );
+  };
+
   const handleScreenshotEnabledChange = (enabled: boolean) => {
     // License check removed, allow selection mode for all users
     const newConfig = { ...screenshotConfiguration, enabled };
@@ -97,6 +109,7 @@ export const useSettings = () => {
     setScreenshotConfiguration,
     handleScreenshotModeChange,
     handleScreenshotPromptChange,
+    handleScreenshotConstraintChange,
     handleScreenshotEnabledChange,
     allAiProviders,
     allSttProviders,
@@ -31,6 +31,10 @@ export interface VadConfig {
   pre_speech_chunks: number;
   noise_gate_threshold: number;
   max_recording_duration_secs: number;
+  use_webrtc: boolean;
+  webrtc_mode: "quality" | "low_bitrate" | "aggressive" | "very_aggressive";
+  voiced_ratio: number;
+  noise_suppression: boolean;
 }
 
 // OPTIMIZED VAD defaults - matches backend exactly for perfect performance
@@ -44,6 +48,10 @@ const DEFAULT_VAD_CONFIG: VadConfig = {
   pre_speech_chunks: 12, // ~0.27s - enough to catch word start
   noise_gate_threshold: 0.003, // Stronger noise filtering
   max_recording_duration_secs: 180, // 3 minutes default
+  use_webrtc: true, // Confirm energy-gated chunks with the WebRTC classifier
+  webrtc_mode: "aggressive",
+  voiced_ratio: 0.5, // Half of the 10ms frames must be voiced
+  noise_suppression: false, // RNNoise on the microphone stream
 };
 
 // Chat message interface (reusing from useCompletion)
@@ -1,11 +1,10 @@
+import { invoke } from "@tauri-apps/api/core";
 import { PostHog } from "tauri-plugin-posthog-api";
 
 /**
  * Event names for tracking
  */
 export const ANALYTICS_EVENTS = {
-  // App Lifecycle
-  APP_STARTED: "app_started",
   // License Events
   GET_LICENSE: "get_license",
 } as const;
@@ -18,20 +17,14 @@ export const captureEvent = async (
   properties?: Record<string, any>
 ) => {
   try {
+    // Only with the user's consent, outside local-only mode and when no
+    // policy turns telemetry off
+    if (!(await invoke<boolean>("usage_sharing_allowed"))) {
+      return;
+    }
     await PostHog.capture(eventName, properties || {});
   } catch (error) {
     // Silently fail - we don't want analytics to break the app
     console.debug("Analytics event failed:", eventName, error);
   }
 };
-
-/**
- * Track app initialization
- */
-export const trackAppStart = async (appVersion: string, instanceId: string) => {
-  await captureEvent(ANALYTICS_EVENTS.APP_STARTED, {
-    app_version: appVersion,
-    platform: navigator.platform,
-    instance_id: instanceId,
-  });
-};
@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { getDatabase } from "./config";
 import { ChatConversation } from "@/types";
 import { safeLocalStorage } from "@/lib";
@@ -5,16 +6,6 @@ import { safeLocalStorage } from "@/lib";
 // Legacy localStorage key for migration purposes
 const LEGACY_CHAT_HISTORY_KEY = "chat_history";
 
-/**
- * Database conversation type (flattened for SQL)
- */
-interface DbConversation {
-  id: string;
-  title: string;
-  created_at: number;
-  updated_at: number;
-}
-
 /**
  * Database message type (flattened for SQL)
  */
@@ -145,53 +136,55 @@ export async function createConversation(
 }
 
 /**
- * Get all conversations with messages in a single optimized query
+ * Conversation as loaded by the Rust backend, which opens sealed columns
+ * when history encryption is on
  */
-export async function getAllConversations(): Promise<ChatConversation[]> {
-  const db = await getDatabase();
-
-  try {
-    // Get all conversations
-    const conversations = await db.select<DbConversation[]>(
-      "SELECT * FROM conversations ORDER BY updated_at DESC"
-    );
+interface StoredConversation {
+  id: string;
+  title: string;
+  createdAt: number;
+  updatedAt: number;
+  messages: {
+    id: string;
+    role: "user" | "assistant" | "system";
+    content: string;
+    timestamp: number;
+    attachedFiles: string | null; // JSON string
+  }[];
+}
 
-    if (conversations.length === 0) {
-      return [];
-    }
+function fromStored(conv: StoredConversation): ChatConversation {
+  return {
+    id: conv.id,
+    title: conv.title,
+    createdAt: conv.createdAt,
+    updatedAt: conv.updatedAt,
+    messages: conv.messages.map((msg) => ({
+      id: msg.id,
+      role: msg.role,
+      content: msg.content,
+      timestamp: msg.timestamp,
+      attachedFiles: safeJsonParse(msg.attachedFiles, undefined),
+    })),
+  };
+}
 
-    // Get all messages for these conversations in one query
-    const conversationIds = conversations.map((c) => c.id);
-    const placeholders = conversationIds.map(() => "?").join(",");
-    const allMessages = await db.select<DbMessage[]>(
-      `SELECT * FROM messages WHERE conversation_id IN (${placeholders}) ORDER BY conversation_id, timestamp ASC`,
-      conversationIds
+/**
+ * Get all conversations with messages, newest first
+ */
+export async function getAllConversations(): Promise<ChatConversation[]> {
+  try {
+    const summaries = await invoke<{ id: string }[]>("list_conversations", {});
+    const conversations = await Promise.all(
+      summaries.map((summary) =>
+        invoke<StoredConversation | null>("load_conversation", {
+          id: summary.id,
+        })
+      )
     );
-
-    // Group messages by conversation_id
-    const messagesByConversation = new Map<string, DbMessage[]>();
-    for (const msg of allMessages) {
-      if (!messagesByConversation.has(msg.conversation_id)) {
-        messagesByConversation.set(msg.conversation_id, []);
-      }
-      messagesByConversation.get(msg.conversation_id)!.push(msg);
-    }
-
-    // Build result
-    return conversations.map((conv) => ({
-      id: conv.id,
-      title: conv.title,
-      createdAt: conv.created_at,
-      updatedAt: conv.updated_at,
-      messages:
-        messagesByConversation.get(conv.id)?.map((msg) => ({
-          id: msg.id,
-          role: msg.role,
-          content: msg.content,
-          timestamp: msg.timestamp,
-          attachedFiles: safeJsonParse(msg.attached_files, undefined),
-        })) || [],
-    }));
+    return conversations
+      .filter((conv): conv is StoredConversation => conv !== null)
+      .map(fromStored);
   } catch (error) {
     console.error("Failed to get all conversations:", error);
     throw error;
@@ -209,40 +202,11 @@ export async function getConversationById(
     return null;
   }
 
-  const db = await getDatabase();
-
   try {
-    // Get conversation
-    const conversations = await db.select<DbConversation[]>(
-      "SELECT * FROM conversations WHERE id = ?",
-      [id]
-    );
-
-    if (conversations.length === 0) {
-      return null;
-    }
-
-    const conv = conversations[0];
-
-    // Get messages
-    const messages = await db.select<DbMessage[]>(
-      "SELECT * FROM messages WHERE conversation_id = ? ORDER BY timestamp ASC",
-      [id]
-    );
-
-    return {
-      id: conv.id,
-      title: conv.title,
-      createdAt: conv.created_at,
-      updatedAt: conv.updated_at,
-      messages: messages.map((msg) => ({
-        id: msg.id,
-        role: msg.role,
-        content: msg.content,
-        timestamp: msg.timestamp,
-        attachedFiles: safeJsonParse(msg.attached_files, undefined),
-      })),
-    };
+    const conv = await invoke<StoredConversation | null>("load_conversation", {
+      id,
+    });
+    return conv ? fromStored(conv) : null;
   } catch (error) {
     console.error(`Failed to get conversation ${id}:`, error);
     return null;
@@ -425,8 +389,8 @@ export async function migrateLocalStorageToSQLite(): Promise<{
       return { success: true, migratedCount: 0 };
     }
 
-    // Get existing localStorage data
-    const existingData = safeLocalStorage.getItem(LEGACY_CHAT_HISTORY_KEY);
+    // Get existing localStorage data; it never went to the settings store
+    const existingData = localStorage.getItem(LEGACY_CHAT_HISTORY_KEY);
     if (!existingData) {
       // No data to migrate
       safeLocalStorage.setItem(migrationKey, "true");
@@ -546,7 +510,7 @@ export async function migrateLocalStorageToSQLite(): Promise<{
     safeLocalStorage.setItem(migrationKey, "true");
 
     // Clear localStorage chat history after migration attempt
-    safeLocalStorage.removeItem(LEGACY_CHAT_HISTORY_KEY);
+    localStorage.removeItem(LEGACY_CHAT_HISTORY_KEY);
 
     const message =
       errorCount > 0
@@ -1,19 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import Database from "@tauri-apps/plugin-sql";
 
-/**
- * Database configuration
- */
-export const DB_NAME = "sqlite:pluely.db";
-
 let dbInstance: Database | null = null;
 
 /**
- * Get database instance
+ * Get database instance for the active profile
  */
 export async function getDatabase(): Promise<Database> {
   if (!dbInstance) {
     try {
-      dbInstance = await Database.load(DB_NAME);
+      // Each profile has its own history database
+      const name = await invoke<string>("get_history_database");
+      dbInstance = await Database.load(name);
     } catch (error) {
       throw new Error(
         `Failed to initialize database: ${
@@ -24,3 +23,10 @@ export async function getDatabase(): Promise<Database> {
   }
   return dbInstance;
 }
+
+// The next query opens the new profile's database
+listen("profile-switched", async () => {
+  const previous = dbInstance;
+  dbInstance = null;
+  await previous?.close(previous.path).catch(() => {});
+});
@@ -0,0 +1,84 @@
+import { invoke } from "@tauri-apps/api/core";
+import { AI_PROVIDERS } from "@/config";
+
+// Saved in place of an API key that lives in the OS keychain. The Rust proxy
+// swaps `{{API_KEY}}` for the real key when a request names the provider.
+export const KEYCHAIN_API_KEY = "••••••••••••";
+
+type SelectedProvider = {
+  provider: string;
+  variables: Record<string, string>;
+};
+
+export const isKeychainApiKey = (value?: string) => value === KEYCHAIN_API_KEY;
+
+// The proxy only sends keychain keys to a built-in provider's own host, so
+// custom providers keep their key with the rest of their settings.
+export const canUseKeychain = (provider?: string) =>
+  AI_PROVIDERS.some((builtIn) => builtIn.id === provider);
+
+export async function hasApiKey(provider: string): Promise<boolean> {
+  return invoke<boolean>("has_api_key", { provider });
+}
+
+// Moves the provider's plaintext `api_key` variable into the keychain and
+// returns a copy that is safe to persist.
+export async function secureProviderVariables<T extends SelectedProvider>(
+  selected: T
+): Promise<T> {
+  const apiKey = selected.variables?.api_key;
+  if (
+    apiKey === undefined ||
+    isKeychainApiKey(apiKey) ||
+    !canUseKeychain(selected.provider)
+  ) {
+    return selected;
+  }
+
+  if (apiKey.trim() === "") {
+    await invoke("delete_api_key", { provider: selected.provider });
+    return selected;
+  }
+
+  await invoke("set_api_key", { provider: selected.provider, key: apiKey });
+  return {
+    ...selected,
+    variables: { ...selected.variables, api_key: KEYCHAIN_API_KEY },
+  };
+}
+
+// Checks a provider loaded from localStorage: keys saved in plaintext by older
+// versions move to the keychain, and a marker whose key has gone is cleared.
+export async function migrateStoredApiKey<T extends SelectedProvider>(
+  selected: T
+): Promise<T> {
+  const apiKey = selected.variables?.api_key;
+  if (!apiKey || !selected.provider) {
+    return selected;
+  }
+
+  if (isKeychainApiKey(apiKey)) {
+    if (
+      canUseKeychain(selected.provider) &&
+      (await hasApiKey(selected.provider))
+    ) {
+      return selected;
+    }
+    return { ...selected, variables: { ...selected.variables, api_key: "" } };
+  }
+
+  if (!canUseKeychain(selected.provider)) {
+    return selected;
+  }
+
+  const migrated = await invoke<string[]>("migrate_api_keys", {
+    keys: { [selected.provider]: apiKey },
+  });
+  if (!migrated.includes(selected.provider)) {
+    return selected;
+  }
+  return {
+    ...selected,
+    variables: { ...selected.variables, api_key: KEYCHAIN_API_KEY },
+  };
+}
@@ -2,3 +2,5 @@ export * from "./ai-response.function";
 export * from "./stt.function";
 export * from "./common.function";
 export * from "./pluely.api";
+export * from "./llm-proxy.function";
+export * from "./api-keys.function";
@@ -0,0 +1,106 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+
+// Matches `ChatStreamEvent` in src-tauri/src/llm/mod.rs
+type ChatStreamEvent =
+  | { event: "delta"; data: { text: string } }
+  | { event: "done"; data: { text: string } }
+  | { event: "cancelled"; data: { text: string } };
+
+// Sends a provider request through the Rust proxy (no CORS, keys stay out of
+// browser fetch) and yields text deltas as they stream in.
+export async function* streamChatViaProxy(params: {
+  url: string;
+  method?: string;
+  headers: Record<string, string>;
+  body: any;
+  stream: boolean;
+  responseContentPath?: string;
+  // Provider whose keychain key fills `{{API_KEY}}` in the url and headers
+  apiKeyRef?: string;
+  signal?: AbortSignal;
+}): AsyncIterable<string> {
+  const {
+    url,
+    method,
+    headers,
+    body,
+    stream,
+    responseContentPath,
+    apiKeyRef,
+    signal,
+  } = params;
+
+  if (signal?.aborted) {
+    return;
+  }
+
+  const requestId = crypto.randomUUID();
+  const pending: string[] = [];
+  let finished = false;
+  let failure: string | null = null;
+  let notify: (() => void) | null = null;
+  const wake = () => {
+    notify?.();
+    notify = null;
+  };
+
+  const channel = new Channel<ChatStreamEvent>();
+  channel.onmessage = (message) => {
+    if (message.event === "delta") {
+      pending.push(message.data.text);
+    } else if (message.event === "done" && !stream) {
+      // Non-streaming responses arrive in one piece
+      pending.push(message.data.text);
+    }
+    wake();
+  };
+
+  const onAbort = () => {
+    invoke("cancel_request", { requestId }).catch(() => {});
+  };
+  signal?.addEventListener("abort", onAbort);
+
+  invoke<string>("send_chat_request", {
+    requestId,
+    provider: {
+      url,
+      method: method || "POST",
+      headers,
+      stream,
+      responseContentPath,
+      apiKeyRef,
+    },
+    payload: body,
+    onEvent: channel,
+  })
+    .catch((error) => {
+      failure = error instanceof Error ? error.message : String(error);
+    })
+    .finally(() => {
+      finished = true;
+      wake();
+    });
+
+  try {
+    while (true) {
+      while (pending.length > 0) {
+        if (signal?.aborted) return;
+        yield pending.shift() as string;
+      }
+      if (finished) break;
+      await new Promise<void>((resolve) => {
+        notify = resolve;
+      });
+    }
+
+    if (failure && !signal?.aborted) {
+      yield failure;
+    }
+  } finally {
+    signal?.removeEventListener("abort", onAbort);
+    // The consumer stopped early; don't leave the request running
+    if (!finished) {
+      onAbort();
+    }
+  }
+}
@@ -3,8 +3,8 @@ import {
   getByPath,
   blobToBase64,
 } from "./common.function";
-import { fetch as tauriFetch } from "@tauri-apps/plugin-http";
 import { invoke } from "@tauri-apps/api/core";
+import { commands, CustomSttBody } from "@/bindings";
 
 import { TYPE_PROVIDER } from "@/types";
 import curl2Json from "@bany/curl-to-json";
@@ -119,17 +119,14 @@ export async function fetchSTT(params: STTParams): Promise<string> {
       url += (url.includes("?") ? "&" : "?") + queryString;
     }
 
-    let finalHeaders = { ...headers };
-    let body: FormData | string | Blob;
+    const finalHeaders: Record<string, string> = { ...headers };
+    let body: CustomSttBody;
 
     const isForm =
       provider.curl.includes("-F ") || provider.curl.includes("--form");
     if (isForm) {
-      const form = new FormData();
-      const freshBlob = new Blob([await audio.arrayBuffer()], {
-        type: audio.type,
-      });
-      form.append("file", freshBlob, "audio.wav");
+      // The audio is sent as the "file" part ahead of these
+      const fields: [string, string][] = [];
       const headerKeys = Object.keys(headers).map((k) =>
         k.toUpperCase().replace(/[-_]/g, "")
       );
@@ -142,7 +139,7 @@ export async function fetchSTT(params: STTParams): Promise<string> {
             key.toUpperCase() === "AUDIO"
           )
             continue;
-          form.append(key.toLowerCase(), val as string | Blob);
+          fields.push([key.toLowerCase(), String(val)]);
           continue;
         }
 
@@ -159,7 +156,7 @@ export async function fetchSTT(params: STTParams): Promise<string> {
           )
             continue;
 
-          form.append(formKey, formValue);
+          fields.push([formKey, formValue]);
         } else {
           if (key.toLowerCase() === "file") continue; // Already handled by form.append('file', audio)
           if (
@@ -168,53 +165,50 @@ export async function fetchSTT(params: STTParams): Promise<string> {
             key.toUpperCase() === "AUDIO"
           )
             continue;
-          form.append(key.toLowerCase(), val as string | Blob);
+          fields.push([key.toLowerCase(), String(val)]);
         }
       }
-      delete finalHeaders["Content-Type"];
-      body = form;
+      body = { kind: "form", fields };
     } else if (isBinaryUpload) {
       // Deepgram-style: raw binary body
-      body = new Blob([await audio.arrayBuffer()], {
-        type: audio.type,
-      });
+      body = { kind: "binary" };
     } else {
       // Google-style: JSON payload with base64
       allVariables.AUDIO = await blobToBase64(audio);
       const dataObj = curlJson.data ? { ...curlJson.data } : {};
-      body = JSON.stringify(deepVariableReplacer(dataObj, allVariables));
+      body = {
+        kind: "json",
+        text: JSON.stringify(deepVariableReplacer(dataObj, allVariables)),
+      };
     }
 
-    const fetchFunction = url?.includes("http") ? fetch : tauriFetch;
-
-    // Send request
-    let response: Response;
-    try {
-      response = await fetchFunction(url, {
-        method: curlJson.method || "POST",
-        headers: finalHeaders,
-        body: curlJson.method === "GET" ? undefined : body,
-      });
-    } catch (e) {
-      throw new Error(`Network error: ${e instanceof Error ? e.message : e}`);
+    // Sent by the backend, so the proxy and local-only mode apply and the
+    // upload is recorded in the audit log
+    const result = await commands.sendCustomSttRequest(
+      url,
+      curlJson.method || "POST",
+      finalHeaders,
+      body,
+      body.kind === "json" ? "" : await blobToBase64(audio),
+      audio.type
+    );
+    if (result.status === "error") {
+      throw new Error(result.error);
     }
+    const response = result.data;
 
-    if (!response.ok) {
-      let errText = "";
-      try {
-        errText = await response.text();
-      } catch {}
+    if (response.status < 200 || response.status >= 300) {
       let errMsg: string;
       try {
-        const errObj = JSON.parse(errText);
-        errMsg = errObj.message || errText;
+        const errObj = JSON.parse(response.body);
+        errMsg = errObj.message || response.body;
       } catch {
-        errMsg = errText || response.statusText;
+        errMsg = response.body || `status ${response.status}`;
       }
       throw new Error(`HTTP ${response.status}: ${errMsg}`);
     }
 
-    const responseText = await response.text();
+    const responseText = response.body;
     let data: any;
     try {
       data = JSON.parse(responseText);
@@ -1,12 +1,10 @@
 import { STORAGE_KEYS } from "@/config";
+import { getSetting, setSetting } from "./settings.storage";
 import { TYPE_PROVIDER } from "@/types";
 
 export function getCustomAiProviders(): TYPE_PROVIDER[] {
   try {
-    if (typeof window === "undefined") return [];
-    const saved = localStorage.getItem(STORAGE_KEYS.CUSTOM_AI_PROVIDERS);
-    if (!saved) return [];
-    const parsed = JSON.parse(saved);
+    const parsed = getSetting(`extra.${STORAGE_KEYS.CUSTOM_AI_PROVIDERS}`);
     if (!Array.isArray(parsed)) return [];
     return parsed.filter(
       (p: any) => p.id && p.isCustom && typeof p.curl === "string"
@@ -19,11 +17,7 @@ export function getCustomAiProviders(): TYPE_PROVIDER[] {
 
 export function setCustomAiProviders(providers: TYPE_PROVIDER[]): void {
   try {
-    if (typeof window === "undefined") return;
-    localStorage.setItem(
-      STORAGE_KEYS.CUSTOM_AI_PROVIDERS,
-      JSON.stringify(providers)
-    );
+    setSetting(`extra.${STORAGE_KEYS.CUSTOM_AI_PROVIDERS}`, providers);
   } catch (error) {
     console.error("Error setting custom AI providers:", error);
   }
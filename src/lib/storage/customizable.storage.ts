@@ -1,4 +1,4 @@
-import { STORAGE_KEYS } from "@/config";
+import { getSetting, setSetting } from "./settings.storage";
 
 export type CursorType = "invisible" | "default" | "auto";
 
@@ -24,40 +24,46 @@ export const DEFAULT_CUSTOMIZABLE_STATE: CustomizableState = {
   cursor: { type: "invisible" },
 };
 
+interface WindowSettings {
+  appIconVisible?: boolean;
+  alwaysOnTop?: boolean;
+  autostart?: boolean;
+  cursor?: CursorType;
+}
+
 /**
- * Get customizable state from localStorage
+ * Get customizable state from the window settings
  */
 export const getCustomizableState = (): CustomizableState => {
-  try {
-    const stored = localStorage.getItem(STORAGE_KEYS.CUSTOMIZABLE);
-    if (!stored) {
-      return DEFAULT_CUSTOMIZABLE_STATE;
-    }
-
-    const parsedState = JSON.parse(stored);
-
-    return {
-      appIcon: parsedState.appIcon || DEFAULT_CUSTOMIZABLE_STATE.appIcon,
-      alwaysOnTop:
-        parsedState.alwaysOnTop || DEFAULT_CUSTOMIZABLE_STATE.alwaysOnTop,
-      autostart: parsedState.autostart || DEFAULT_CUSTOMIZABLE_STATE.autostart,
-      cursor: parsedState.cursor || DEFAULT_CUSTOMIZABLE_STATE.cursor,
-    };
-  } catch (error) {
-    console.error("Failed to get customizable state:", error);
+  const window = getSetting<WindowSettings>("window");
+  if (!window) {
     return DEFAULT_CUSTOMIZABLE_STATE;
   }
+
+  const defaults = DEFAULT_CUSTOMIZABLE_STATE;
+  return {
+    appIcon: {
+      isVisible: window.appIconVisible ?? defaults.appIcon.isVisible,
+    },
+    alwaysOnTop: {
+      isEnabled: window.alwaysOnTop ?? defaults.alwaysOnTop.isEnabled,
+    },
+    autostart: { isEnabled: window.autostart ?? defaults.autostart.isEnabled },
+    cursor: { type: window.cursor ?? defaults.cursor.type },
+  };
 };
 
 /**
- * Save customizable state to localStorage
+ * Save customizable state to the window settings
  */
 export const setCustomizableState = (state: CustomizableState): void => {
-  try {
-    localStorage.setItem(STORAGE_KEYS.CUSTOMIZABLE, JSON.stringify(state));
-  } catch (error) {
-    console.error("Failed to save customizable state:", error);
-  }
+  setSetting("window", {
+    ...getSetting<WindowSettings>("window"),
+    appIconVisible: state.appIcon.isVisible,
+    alwaysOnTop: state.alwaysOnTop.isEnabled,
+    autostart: state.autostart.isEnabled,
+    cursor: state.cursor.type,
+  });
 };
 
 /**
@@ -1,22 +1,9 @@
+import { getStoredItem, setStoredItem } from "./settings.storage";
+
+// Same interface as localStorage, kept by the Rust settings store so values
+// survive reinstalls and are shared with the backend
 export const safeLocalStorage = {
-  getItem: (key: string): string | null => {
-    if (typeof window === "undefined") return null;
-    try {
-      return localStorage.getItem(key);
-    } catch {
-      return null;
-    }
-  },
-  setItem: (key: string, value: string): void => {
-    if (typeof window === "undefined") return;
-    try {
-      localStorage.setItem(key, value);
-    } catch {}
-  },
-  removeItem: (key: string): void => {
-    if (typeof window === "undefined") return;
-    try {
-      localStorage.removeItem(key);
-    } catch {}
-  },
+  getItem: (key: string): string | null => getStoredItem(key),
+  setItem: (key: string, value: string): void => setStoredItem(key, value),
+  removeItem: (key: string): void => setStoredItem(key, null),
 };
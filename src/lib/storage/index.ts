@@ -1,6 +1,7 @@
 export * from "./ai-providers";
 export * from "./stt-providers";
 export * from "./helper";
+export * from "./settings.storage";
 export * from "./customizable.storage";
 export * from "./shortcuts.storage";
 export * from "./response-settings.storage";
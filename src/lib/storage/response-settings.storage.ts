@@ -1,4 +1,4 @@
-import { STORAGE_KEYS } from "@/config";
+import { getSetting, setSetting } from "./settings.storage";
 import {
   DEFAULT_RESPONSE_LENGTH,
   DEFAULT_LANGUAGE,
@@ -18,17 +18,15 @@ export const DEFAULT_RESPONSE_SETTINGS: ResponseSettings = {
 };
 
 /**
- * Get response settings from localStorage
+ * Get response settings
  */
 export const getResponseSettings = (): ResponseSettings => {
   try {
-    const stored = localStorage.getItem(STORAGE_KEYS.RESPONSE_SETTINGS);
-    if (!stored) {
+    const parsedSettings = getSetting<Partial<ResponseSettings>>("response");
+    if (!parsedSettings) {
       return DEFAULT_RESPONSE_SETTINGS;
     }
 
-    const parsedSettings = JSON.parse(stored);
-
     return {
       responseLength:
         parsedSettings.responseLength ||
@@ -46,17 +44,10 @@ export const getResponseSettings = (): ResponseSettings => {
 };
 
 /**
- * Save response settings to localStorage
+ * Save response settings
  */
 export const setResponseSettings = (settings: ResponseSettings): void => {
-  try {
-    localStorage.setItem(
-      STORAGE_KEYS.RESPONSE_SETTINGS,
-      JSON.stringify(settings)
-    );
-  } catch (error) {
-    console.error("Failed to save response settings:", error);
-  }
+  setSetting("response", settings);
 };
 
 /**
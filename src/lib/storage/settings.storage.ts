@@ -0,0 +1,161 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { STORAGE_KEYS } from "@/config";
+
+type SettingsDocument = Record<string, any>;
+
+// Chat history older versions kept in localStorage; it moves to SQLite, not
+// to the settings file
+const LEGACY_KEYS = ["chat_history"];
+
+// Where each localStorage key of older versions lives in the settings
+// document, as src-tauri/src/settings/migrations.rs moves them. Keys not
+// listed here (custom providers, flags) are kept under `extra`.
+const KEY_PATHS: Record<string, string> = {
+  [STORAGE_KEYS.THEME]: "appearance.theme",
+  [STORAGE_KEYS.TRANSPARENCY]: "appearance.transparency",
+  [STORAGE_KEYS.SYSTEM_PROMPT]: "systemPrompt.prompt",
+  [STORAGE_KEYS.SELECTED_SYSTEM_PROMPT_ID]: "systemPrompt.selectedId",
+  [STORAGE_KEYS.SELECTED_AUDIO_INPUT_DEVICE]: "audio.inputDevice",
+  [STORAGE_KEYS.SELECTED_AUDIO_OUTPUT_DEVICE]: "audio.outputDevice",
+  [STORAGE_KEYS.SCREENSHOT_CONFIG]: "screenshot",
+  [STORAGE_KEYS.RESPONSE_SETTINGS]: "response",
+  [STORAGE_KEYS.SYSTEM_AUDIO_QUICK_ACTIONS]: "systemAudio.quickActions",
+  [STORAGE_KEYS.SHORTCUTS]: "shortcuts",
+};
+
+// Stored as text even when they happen to parse as JSON
+const TEXT_KEYS: string[] = [
+  STORAGE_KEYS.SYSTEM_PROMPT,
+  STORAGE_KEYS.SELECTED_AUDIO_INPUT_DEVICE,
+  STORAGE_KEYS.SELECTED_AUDIO_OUTPUT_DEVICE,
+];
+
+// The settings the Rust backend keeps in settings.json, loaded before the app
+// renders so the storage helpers can stay synchronous. Writes update this copy
+// right away and are saved through `set_setting`.
+let settings: SettingsDocument = {};
+
+const setPath = (key: string, value: unknown) => {
+  const parts = key.split(".");
+  const last = parts.pop()!;
+  let parent = settings;
+  for (const part of parts) {
+    if (typeof parent[part] !== "object" || parent[part] === null) {
+      parent[part] = {};
+    }
+    parent = parent[part];
+  }
+  parent[last] = value;
+};
+
+/**
+ * Get a setting by its dot path, e.g. "window.alwaysOnTop"
+ */
+export const getSetting = <T = unknown>(key: string): T | undefined =>
+  key
+    .split(".")
+    .reduce<any>((value, part) => value?.[part], settings) as T | undefined;
+
+/**
+ * Save a setting by its dot path
+ */
+export const setSetting = (key: string, value: unknown): void => {
+  setPath(key, value);
+  invoke("set_setting", { key, value }).catch((error) => {
+    console.error(`Failed to save setting ${key}:`, error);
+  });
+};
+
+/**
+ * Load the settings. The first run after an upgrade hands everything in
+ * localStorage to the backend; once the settings file exists that is skipped.
+ */
+export async function initSettings(): Promise<void> {
+  const entries: Record<string, string> = {};
+  for (let i = 0; i < localStorage.length; i++) {
+    const key = localStorage.key(i);
+    if (key !== null && !LEGACY_KEYS.includes(key)) {
+      entries[key] = localStorage.getItem(key) ?? "";
+    }
+  }
+  settings = await invoke<SettingsDocument>("import_local_storage", {
+    entries,
+  });
+}
+
+// Changes made by the backend, other windows or a profile switch
+listen<{ key: string; value: unknown }>("settings-changed", ({ payload }) => {
+  if (payload.key) {
+    setPath(payload.key, payload.value);
+  } else {
+    settings = payload.value as SettingsDocument;
+  }
+});
+listen<{ settings: SettingsDocument }>("profile-switched", ({ payload }) => {
+  settings = payload.settings;
+});
+
+/**
+ * Read a value stored under an old localStorage key, as text
+ */
+export const getStoredItem = (key: string): string | null => {
+  let value: unknown;
+  switch (key) {
+    // Split over the window section
+    case STORAGE_KEYS.CUSTOMIZABLE: {
+      const window = getSetting<SettingsDocument>("window");
+      value = window && {
+        appIcon: { isVisible: window.appIconVisible },
+        alwaysOnTop: { isEnabled: window.alwaysOnTop },
+        autostart: { isEnabled: window.autostart },
+        cursor: { type: window.cursor },
+      };
+      break;
+    }
+    case STORAGE_KEYS.SYSTEM_AUDIO_CONTEXT: {
+      const systemAudio = getSetting<SettingsDocument>("systemAudio");
+      value = systemAudio && {
+        useSystemPrompt: systemAudio.useSystemPrompt,
+        contextContent: systemAudio.contextContent,
+      };
+      break;
+    }
+    default:
+      value = getSetting(KEY_PATHS[key] ?? `extra.${key}`);
+  }
+  if (value === undefined || value === null) return null;
+  return typeof value === "string" ? value : JSON.stringify(value);
+};
+
+/**
+ * Save a value under an old localStorage key
+ */
+export const setStoredItem = (key: string, text: string | null): void => {
+  let value: any = text;
+  if (text !== null && !TEXT_KEYS.includes(key)) {
+    try {
+      value = JSON.parse(text);
+    } catch {}
+  }
+  switch (key) {
+    case STORAGE_KEYS.CUSTOMIZABLE:
+      setSetting("window", {
+        ...getSetting<SettingsDocument>("window"),
+        appIconVisible: value?.appIcon?.isVisible,
+        alwaysOnTop: value?.alwaysOnTop?.isEnabled,
+        autostart: value?.autostart?.isEnabled,
+        cursor: value?.cursor?.type,
+      });
+      break;
+    case STORAGE_KEYS.SYSTEM_AUDIO_CONTEXT:
+      setSetting("systemAudio", {
+        ...getSetting<SettingsDocument>("systemAudio"),
+        useSystemPrompt: value?.useSystemPrompt,
+        contextContent: value?.contextContent,
+      });
+      break;
+    default:
+      setSetting(KEY_PATHS[key] ?? `extra.${key}`, value);
+  }
+};
@@ -1,4 +1,4 @@
-import { STORAGE_KEYS, DEFAULT_SHORTCUT_ACTIONS } from "@/config";
+import { DEFAULT_SHORTCUT_ACTIONS } from "@/config";
 import {
   ShortcutsConfig,
   ShortcutBinding,
@@ -6,6 +6,7 @@ import {
   ShortcutAction,
 } from "@/types";
 import { getPlatform } from "@/lib";
+import { getSetting, setSetting } from "./settings.storage";
 
 /**
  * Get platform-specific default key for a shortcut action
@@ -44,13 +45,12 @@ export const getDefaultShortcutsConfig = (): ShortcutsConfig => {
 };
 
 /**
- * Get shortcuts configuration from localStorage
+ * Get shortcuts configuration
  */
 export const getShortcutsConfig = (): ShortcutsConfig => {
   try {
-    const stored = localStorage.getItem(STORAGE_KEYS.SHORTCUTS);
-    if (stored) {
-      const parsed = JSON.parse(stored);
+    const parsed = getSetting<ShortcutsConfig>("shortcuts");
+    if (parsed) {
       // Merge with defaults to ensure all default actions are present
       const defaults = getDefaultShortcutsConfig();
       return {
@@ -66,14 +66,10 @@ export const getShortcutsConfig = (): ShortcutsConfig => {
 };
 
 /**
- * Save shortcuts configuration to localStorage
+ * Save shortcuts configuration
  */
 export const setShortcutsConfig = (config: ShortcutsConfig): void => {
-  try {
-    localStorage.setItem(STORAGE_KEYS.SHORTCUTS, JSON.stringify(config));
-  } catch (error) {
-    console.error("Failed to save shortcuts config:", error);
-  }
+  setSetting("shortcuts", config);
 };
 
 /**
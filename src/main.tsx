@@ -1,31 +1,21 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
-import Overlay from "./components/Overlay";
 import { AppProvider, ThemeProvider } from "./contexts";
 import "./global.css";
-import { getCurrentWindow } from "@tauri-apps/api/window";
 import AppRoutes from "./routes";
+import { initSettings } from "./lib/storage";
 
-const currentWindow = getCurrentWindow();
-const windowLabel = currentWindow.label;
-
-// Render different components based on window label
-if (windowLabel.startsWith("capture-overlay-")) {
-  const monitorIndex = parseInt(windowLabel.split("-")[2], 10) || 0;
-  // Render overlay without providers
-  ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-    <React.StrictMode>
-      <Overlay monitorIndex={monitorIndex} />
-    </React.StrictMode>
-  );
-} else {
-  ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-    <React.StrictMode>
-      <ThemeProvider>
-        <AppProvider>
-          <AppRoutes />
-        </AppProvider>
-      </ThemeProvider>
-    </React.StrictMode>
-  );
-}
+// Storage helpers read the settings synchronously, so they load first
+initSettings()
+  .catch((error) => console.error("Failed to load settings:", error))
+  .finally(() => {
+    ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+      <React.StrictMode>
+        <ThemeProvider>
+          <AppProvider>
+            <AppRoutes />
+          </AppProvider>
+        </ThemeProvider>
+      </React.StrictMode>
+    );
+  });
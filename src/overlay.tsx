@@ -0,0 +1,16 @@
+import React from "react";
+import ReactDOM from "react-dom/client";
+import Overlay from "./components/Overlay";
+import "./global.css";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+
+// Entry point of the capture overlay windows, kept apart from the app bundle
+// so overlays open quickly and never show the main UI
+const windowLabel = getCurrentWindow().label;
+const monitorIndex = parseInt(windowLabel.split("-")[2], 10) || 0;
+
+ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+  <React.StrictMode>
+    <Overlay monitorIndex={monitorIndex} />
+  </React.StrictMode>
+);
@@ -18,10 +18,13 @@ import {
   SendIcon,
   Check,
   Loader2,
+  AppWindowIcon,
 } from "lucide-react";
 import { useState, useEffect } from "react";
 import moment from "moment";
-import { useParams, useNavigate } from "react-router-dom";
+import { useParams, useNavigate, useLocation } from "react-router-dom";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { PageLayout } from "@/layouts";
 import { useHistory, useChatCompletion } from "@/hooks";
 import {
@@ -35,6 +38,8 @@ import {
 const View = () => {
   const { conversationId } = useParams();
   const navigate = useNavigate();
+  // Shown in a conversation window of its own rather than the dashboard
+  const inOwnWindow = useLocation().pathname.startsWith("/conversation/");
   const [messages, setMessages] = useState<ChatConversation | null>(null);
 
   const {
@@ -75,17 +80,36 @@ const View = () => {
 
   const handleDelete = async () => {
     await confirmDelete();
-    navigate(-1);
+    if (inOwnWindow) {
+      await getCurrentWebviewWindow().close();
+    } else {
+      navigate(-1);
+    }
   };
 
   return (
     <PageLayout
       isMainTitle={false}
-      allowBackButton={true}
+      allowBackButton={!inOwnWindow}
       title={messages?.title || ""}
       description={`${messages?.messages.length} messages in this conversation`}
       rightSlot={
         <div className="flex flex-row items-center gap-2">
+          {!inOwnWindow && (
+            <Button
+              variant="outline"
+              title="Open this conversation in a window of its own"
+              className="text-[10px] lg:text-sm h-6 lg:h-8"
+              onClick={() =>
+                conversationId &&
+                invoke("open_conversation_window", { conversationId }).catch(
+                  console.error
+                )
+              }
+            >
+              New Window <AppWindowIcon className="size-3 lg:size-4" />
+            </Button>
+          )}
           <Button
             variant="outline"
             title="Open this conversation in overlay"
@@ -0,0 +1,58 @@
+import { useEffect, useState } from "react";
+import { useParams } from "react-router-dom";
+import { commands } from "@/bindings";
+
+// A capture pinned to the screen; drag to move, double-click or Esc to close
+const Pin = () => {
+  const { pinId } = useParams();
+  const [image, setImage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const close = async () => {
+    if (!pinId) return;
+    const result = await commands.closePin(pinId);
+    if (result.status === "error") {
+      console.error("Failed to close pin:", result.error);
+    }
+  };
+
+  useEffect(() => {
+    if (!pinId) return;
+    commands.getPinImage(pinId).then((result) => {
+      if (result.status === "ok") {
+        setImage(`data:image/png;base64,${result.data}`);
+      } else {
+        setError(result.error);
+      }
+    });
+  }, [pinId]);
+
+  useEffect(() => {
+    const onKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") close();
+    };
+    window.addEventListener("keydown", onKeyDown);
+    return () => window.removeEventListener("keydown", onKeyDown);
+  }, [pinId]);
+
+  return (
+    <div
+      className="h-screen w-screen overflow-hidden bg-background"
+      data-tauri-drag-region={true}
+      onDoubleClick={close}
+    >
+      {image ? (
+        <img
+          src={image}
+          alt="Pinned capture"
+          className="pointer-events-none h-full w-full select-none object-contain"
+          draggable={false}
+        />
+      ) : (
+        <p className="p-2 text-xs text-muted-foreground">{error}</p>
+      )}
+    </div>
+  );
+};
+
+export default Pin;
@@ -15,6 +15,7 @@ export const ScreenshotConfigs = ({
   handleScreenshotModeChange,
   handleScreenshotPromptChange,
   handleScreenshotEnabledChange,
+  handleScreenshotConstraintChange,
   // hasActiveLicense,
 }: UseSettingsReturn) => {
   return (
@@ -68,6 +69,23 @@ export const ScreenshotConfigs = ({
           </Select>
         </div>
 
+        {/* Selection Constraint - Only show in selection mode */}
+        {!screenshotConfiguration.enabled && (
+          <div className="space-y-2">
+            <Label className="text-sm font-medium">Selection Shape</Label>
+            <Input
+              placeholder="Any shape, or e.g. 16:9 or 1280x720"
+              value={screenshotConfiguration.selectionConstraint || ""}
+              onChange={(e) => handleScreenshotConstraintChange(e.target.value)}
+              className="w-full h-11 border-1 border-input/50 focus:border-primary/50 transition-colors"
+            />
+            <p className="text-xs text-muted-foreground">
+              Keep every selection to an aspect ratio or an exact pixel size,
+              for uniformly sized screenshots
+            </p>
+          </div>
+        )}
+
         {/* Mode Selection: Auto and Manual */}
         <div className="space-y-2">
           <div className="flex flex-col">
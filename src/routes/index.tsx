@@ -11,6 +11,7 @@ import {
   Screenshot,
   Chats,
   Responses,
+  Pin,
 } from "@/pages";
 import { DashboardLayout } from "@/layouts";
 
@@ -19,6 +20,8 @@ export default function AppRoutes() {
     <Router>
       <Routes>
         <Route path="/" element={<App />} />
+        <Route path="/pin/:pinId" element={<Pin />} />
+        <Route path="/conversation/:conversationId" element={<ViewChat />} />
         <Route element={<DashboardLayout />}>
           <Route path="/dashboard" element={<Dashboard />} />
           <Route path="/chats" element={<Chats />} />
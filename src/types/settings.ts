@@ -44,6 +44,8 @@ export interface ScreenshotConfig {
   mode: ScreenshotMode;
   autoPrompt: string;
   enabled: boolean;
+  // Selection mode only: a ratio like "16:9" or a size like "1280x720"
+  selectionConstraint?: string;
 }
 
 // Speech-to-Text provider types